@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS tag_object;
+DROP TABLE IF EXISTS tags;
+DROP TABLE IF EXISTS services;
+DROP TABLE IF EXISTS communities;
+DROP TABLE IF EXISTS users;
@@ -0,0 +1,55 @@
+-- Базовые таблицы, на которые ссылаются остальные модули
+
+-- Создание таблицы "users" для хранения пользователей
+CREATE TABLE IF NOT EXISTS users (
+    id SERIAL PRIMARY KEY,              -- Уникальный идентификатор
+    first_name VARCHAR(255) NOT NULL,   -- Имя пользователя
+    last_name VARCHAR(255) NOT NULL,    -- Фамилия пользователя
+    middle_name VARCHAR(255) NOT NULL,  -- Отчество пользователя
+    email VARCHAR(255) NOT NULL,        -- Адрес электронной почты
+    phone_number VARCHAR(255) NOT NULL, -- Номер телефона пользователя
+    description TEXT NOT NULL,          -- Описание пользователя
+    photo_link VARCHAR(255) NOT NULL,   -- Ссылка на фотографию пользователя
+    UNIQUE (email)
+);
+
+-- Создание таблицы "communities" для хранения сообществ
+CREATE TABLE IF NOT EXISTS communities (
+    id SERIAL PRIMARY KEY,                  -- Идентификатор сообщества
+    name VARCHAR(255) NOT NULL,             -- Название сообщества
+    description TEXT,                       -- Описание сообщества (может отсутствовать)
+    user_id INT NOT NULL,                   -- Идентификатор создателя
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(), -- Дата и время создания
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
+
+-- Создание таблицы "services" для хранения услуг, на которые подаются заявки
+CREATE TABLE IF NOT EXISTS services (
+    id SERIAL PRIMARY KEY,              -- Идентификатор услуги
+    name VARCHAR(255) NOT NULL,         -- Название услуги
+    description TEXT                    -- Описание услуги (может отсутствовать)
+);
+
+-- Создание таблицы "tags" для хранения тегов
+CREATE TABLE IF NOT EXISTS tags (
+    id SERIAL PRIMARY KEY,                       -- Идентификатор тега
+    name VARCHAR(255) NOT NULL,                  -- Название тега
+    description TEXT,                            -- Описание тега (может отсутствовать)
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(), -- Дата и время создания
+    created_by INT,                              -- Кто создал тег (может отсутствовать)
+    FOREIGN KEY (created_by) REFERENCES users (id) ON DELETE SET NULL
+);
+
+-- Создание таблицы "tag_object" для связи тегов с объектами любых типов
+CREATE TABLE IF NOT EXISTS tag_object (
+    id SERIAL PRIMARY KEY,                       -- Идентификатор связи
+    tag_id INT NOT NULL,                         -- Идентификатор тега
+    object_id INT NOT NULL,                      -- Идентификатор объекта
+    object_type VARCHAR(255) NOT NULL,           -- Тип объекта
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(), -- Дата и время создания
+    created_by INT,                              -- Кто привязал тег (может отсутствовать)
+    FOREIGN KEY (tag_id) REFERENCES tags (id) ON DELETE CASCADE,
+    FOREIGN KEY (created_by) REFERENCES users (id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_tag_object_object ON tag_object (object_type, object_id);
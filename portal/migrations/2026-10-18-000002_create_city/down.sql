@@ -0,0 +1,17 @@
+DROP TABLE IF EXISTS route_comments;
+DROP TABLE IF EXISTS route_images;
+DROP TABLE IF EXISTS route_tags;
+DROP TABLE IF EXISTS route_reviews;
+DROP TABLE IF EXISTS route_points;
+DROP TABLE IF EXISTS routes;
+DROP TABLE IF EXISTS user_maps;
+DROP TABLE IF EXISTS layers;
+DROP TABLE IF EXISTS maps;
+
+DROP TABLE IF EXISTS wifi_charging;
+DROP TABLE IF EXISTS user_points;
+DROP TABLE IF EXISTS goods;
+DROP TABLE IF EXISTS organizations;
+DROP TABLE IF EXISTS streets;
+DROP TABLE IF EXISTS districts;
+DROP TABLE IF EXISTS cities;
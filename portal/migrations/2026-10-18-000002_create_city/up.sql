@@ -0,0 +1,205 @@
+-- Таблицы модуля City.rs: город -> район -> улица -> объекты на улице
+
+CREATE EXTENSION IF NOT EXISTS postgis;
+
+-- Создание таблицы "cities" для хранения информации о городах
+CREATE TABLE IF NOT EXISTS cities (
+    id SERIAL PRIMARY KEY,             -- Идентификатор города
+    name VARCHAR(255) NOT NULL,        -- Название города
+    population INT,                    -- Численность населения (может отсутствовать)
+    area_sq_km NUMERIC,                -- Площадь в квадратных километрах (может отсутствовать)
+    mayor VARCHAR(255),                -- Имя мэра (может отсутствовать)
+    founding_date DATE                 -- Дата основания (может отсутствовать)
+);
+
+-- Создание таблицы "districts" для хранения информации о районах
+CREATE TABLE IF NOT EXISTS districts (
+    id SERIAL PRIMARY KEY,             -- Идентификатор района
+    city_id INT NOT NULL,              -- Идентификатор города
+    name VARCHAR(255) NOT NULL,        -- Название района
+    population INT NOT NULL,           -- Численность населения в районе
+    area_sq_km NUMERIC,                -- Площадь района в квадратных километрах (может отсутствовать)
+    FOREIGN KEY (city_id) REFERENCES cities (id) ON DELETE CASCADE
+);
+
+-- Создание таблицы "streets" для хранения информации о улицах
+CREATE TABLE IF NOT EXISTS streets (
+    id SERIAL PRIMARY KEY,             -- Идентификатор улицы
+    district_id INT NOT NULL,          -- Идентификатор района
+    name VARCHAR(255) NOT NULL,        -- Название улицы
+    length_km NUMERIC,                 -- Длина улицы в километрах (может отсутствовать)
+    surface_type VARCHAR(255),         -- Тип покрытия улицы (может отсутствовать)
+    speed_limit SMALLINT,              -- Ограничение скорости на улице (может отсутствовать)
+    FOREIGN KEY (district_id) REFERENCES districts (id) ON DELETE CASCADE
+);
+
+-- Создание таблицы "organizations" для хранения информации организациях
+CREATE TABLE IF NOT EXISTS organizations (
+    id SERIAL PRIMARY KEY,             -- Идентификатор организации
+    street_id INT NOT NULL,            -- Идентификатор улицы
+    name VARCHAR(255) NOT NULL,        -- Название организации
+    description TEXT,                  -- Описание организации (может отсутствовать)
+    contact_info VARCHAR(255),         -- Контактная информация (может отсутствовать)
+    operating_hours VARCHAR(255),      -- Часы работы (может отсутствовать)
+    website VARCHAR(255),              -- Веб-сайт (может отсутствовать)
+    email VARCHAR(255),                -- Электронная почта (может отсутствовать)
+    phone VARCHAR(255),                -- Телефон (может отсутствовать)
+    category VARCHAR(255),             -- Категория организации (может отсутствовать)
+    rating NUMERIC,                    -- Рейтинг организации (может отсутствовать)
+    FOREIGN KEY (street_id) REFERENCES streets (id) ON DELETE CASCADE
+);
+
+-- Создание таблицы "goods" для хранения информации о товарах и услугах
+CREATE TABLE IF NOT EXISTS goods (
+    id SERIAL PRIMARY KEY,             -- Идентификатор товара или услуги
+    street_id INT NOT NULL,            -- Идентификатор улицы
+    name VARCHAR(255) NOT NULL,        -- Название товара или услуги
+    description TEXT,                  -- Описание товара (может отсутствовать)
+    FOREIGN KEY (street_id) REFERENCES streets (id) ON DELETE CASCADE
+);
+
+-- Создание таблицы "user_points" для хранения информации о точках, отмеченных пользователями
+CREATE TABLE IF NOT EXISTS user_points (
+    id SERIAL PRIMARY KEY,              -- Идентификатор точки отмеченной пользователем
+    street_id INT NOT NULL,             -- Идентификатор улицы
+    name VARCHAR(255) NOT NULL,         -- Название точки
+    description TEXT,                   -- Описание точки (может отсутствовать)
+    category VARCHAR(255),              -- Категория точки (может отсутствовать)
+    rating NUMERIC,                     -- Рейтинг точки (может отсутствовать)
+    photo BYTEA,                        -- Фотография точки в виде BLOB (может отсутствовать)
+    latitude DOUBLE PRECISION NOT NULL, -- Широта точки
+    longitude DOUBLE PRECISION NOT NULL,-- Долгота точки
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(), -- Дата и время создания точки
+    user_id INT NOT NULL,               -- Идентификатор пользователя, создавшего точку
+    external_link VARCHAR(255),         -- Ссылка на внешний ресурс (может отсутствовать)
+    average_rating REAL,                -- Средний рейтинг точки (может отсутствовать)
+    reviews TEXT[],                     -- Отзывы о точке (массив, может быть пустым)
+    FOREIGN KEY (street_id) REFERENCES streets (id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
+
+-- Создание таблицы "wifi_charging" для хранения информации о бесплатных Wi-Fi точках и зарядках
+CREATE TABLE IF NOT EXISTS wifi_charging (
+    id SERIAL PRIMARY KEY,              -- Идентификатор точки
+    street_id INT NOT NULL,             -- Идентификатор улицы
+    name VARCHAR(255) NOT NULL,         -- Название точки
+    description TEXT,                   -- Описание точки (может отсутствовать)
+    services VARCHAR(255),              -- Доступные услуги (может отсутствовать)
+    location GEOMETRY(POINT, 4326),     -- Координаты точки на карте (может отсутствовать)
+    rating NUMERIC,                     -- Рейтинг точки (может отсутствовать)
+    FOREIGN KEY (street_id) REFERENCES streets (id) ON DELETE CASCADE
+);
+
+-- Добавление индексов для улучшения производительности запросов
+CREATE INDEX IF NOT EXISTS idx_districts_city_id ON districts (city_id);
+CREATE INDEX IF NOT EXISTS idx_streets_district_id ON streets (district_id);
+CREATE INDEX IF NOT EXISTS idx_organizations_street_id ON organizations (street_id);
+CREATE INDEX IF NOT EXISTS idx_goods_street_id ON goods (street_id);
+CREATE INDEX IF NOT EXISTS idx_user_points_street_id ON user_points (street_id);
+CREATE INDEX IF NOT EXISTS idx_user_points_user_id ON user_points (user_id);
+CREATE INDEX IF NOT EXISTS idx_wifi_charging_street_id ON wifi_charging (street_id);
+
+-- Пользовательские карты со слоями и маршрутами (модуль MapsRoutes.rs)
+
+CREATE TABLE maps (
+    id          SERIAL PRIMARY KEY,
+    title       VARCHAR(255) NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    user_id     INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at  TIMESTAMP NOT NULL DEFAULT NOW(),
+    location    GEOMETRY(POINT, 4326) NOT NULL,
+    public      BOOLEAN NOT NULL DEFAULT TRUE,
+    likes       INT NOT NULL DEFAULT 0,
+    views       INT NOT NULL DEFAULT 0
+);
+
+CREATE INDEX idx_maps_user ON maps (user_id);
+CREATE INDEX idx_maps_location ON maps USING GIST (location);
+
+CREATE TABLE layers (
+    id          SERIAL PRIMARY KEY,
+    map_id      INT NOT NULL REFERENCES maps(id) ON DELETE CASCADE,
+    title       VARCHAR(255) NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    layer_type  VARCHAR(50) NOT NULL,
+    user_id     INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at  TIMESTAMP NOT NULL DEFAULT NOW(),
+    visibility  BOOLEAN NOT NULL DEFAULT TRUE,
+    opacity     DOUBLE PRECISION NOT NULL DEFAULT 1 CHECK (opacity BETWEEN 0 AND 1)
+);
+
+CREATE INDEX idx_layers_map ON layers (map_id);
+
+-- Доступ пользователей к чужим картам
+CREATE TABLE user_maps (
+    id       SERIAL PRIMARY KEY,
+    user_id  INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    map_id   INT NOT NULL REFERENCES maps(id) ON DELETE CASCADE,
+    can_edit BOOLEAN NOT NULL DEFAULT FALSE,
+    can_view BOOLEAN NOT NULL DEFAULT TRUE,
+    UNIQUE (user_id, map_id)
+);
+
+CREATE TABLE routes (
+    id          SERIAL PRIMARY KEY,
+    map_id      INT NOT NULL REFERENCES maps(id) ON DELETE CASCADE,
+    title       VARCHAR(255) NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    user_id     INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    route_data  JSONB NOT NULL DEFAULT '{}',
+    created_at  TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_routes_map ON routes (map_id);
+
+CREATE TABLE route_points (
+    id          SERIAL PRIMARY KEY,
+    route_id    INT NOT NULL REFERENCES routes(id) ON DELETE CASCADE,
+    title       VARCHAR(255) NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    latitude    DOUBLE PRECISION NOT NULL CHECK (latitude BETWEEN -90 AND 90),
+    longitude   DOUBLE PRECISION NOT NULL CHECK (longitude BETWEEN -180 AND 180),
+    elevation   DOUBLE PRECISION NOT NULL DEFAULT 0
+);
+
+CREATE INDEX idx_route_points_route ON route_points (route_id);
+
+CREATE TABLE route_reviews (
+    id         SERIAL PRIMARY KEY,
+    route_id   INT NOT NULL REFERENCES routes(id) ON DELETE CASCADE,
+    user_id    INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    rating     DOUBLE PRECISION NOT NULL CHECK (rating BETWEEN 1 AND 5),
+    comment    TEXT NOT NULL DEFAULT '',
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_route_reviews_route ON route_reviews (route_id);
+
+CREATE TABLE route_tags (
+    id       SERIAL PRIMARY KEY,
+    route_id INT NOT NULL REFERENCES routes(id) ON DELETE CASCADE,
+    tag_name VARCHAR(100) NOT NULL,
+    category VARCHAR(100),
+    UNIQUE (route_id, tag_name)
+);
+
+CREATE TABLE route_images (
+    id         SERIAL PRIMARY KEY,
+    route_id   INT NOT NULL REFERENCES routes(id) ON DELETE CASCADE,
+    image_url  VARCHAR(500) NOT NULL,
+    caption    TEXT,
+    user_id    INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_route_images_route ON route_images (route_id);
+
+CREATE TABLE route_comments (
+    id           SERIAL PRIMARY KEY,
+    route_id     INT NOT NULL REFERENCES routes(id) ON DELETE CASCADE,
+    user_id      INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    comment_text TEXT NOT NULL,
+    created_at   TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_route_comments_route ON route_comments (route_id);
@@ -0,0 +1,6 @@
+DROP TABLE IF EXISTS event_subscribers;
+DROP TABLE IF EXISTS event_attendees;
+DROP TABLE IF EXISTS event_calendar;
+DROP TABLE IF EXISTS user_events;
+DROP TABLE IF EXISTS events;
+DROP TABLE IF EXISTS event_themes;
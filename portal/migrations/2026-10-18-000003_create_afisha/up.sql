@@ -0,0 +1,91 @@
+-- Таблицы модуля afisha.rs: события, их даты, посетители и подписчики
+
+-- Создание таблицы "event_themes" для хранения тематик событий
+CREATE TABLE IF NOT EXISTS event_themes (
+    id SERIAL PRIMARY KEY,        -- Идентификатор тематики
+    name VARCHAR(255) NOT NULL,   -- Название тематики
+    parent_id INT,                -- Идентификатор родительской тематики
+    FOREIGN KEY (parent_id) REFERENCES event_themes (id)
+);
+
+-- Создание таблицы "events" для хранения информации о событиях
+CREATE TABLE IF NOT EXISTS events (
+    id SERIAL PRIMARY KEY,                 -- Идентификатор события
+    title VARCHAR(255) NOT NULL,           -- Заголовок события
+    description TEXT NOT NULL,             -- Описание события
+    category VARCHAR(255),                 -- Категория события (необязательное)
+    date_start TIMESTAMP NOT NULL,         -- Дата и время начала события
+    date_end TIMESTAMP NOT NULL,           -- Дата и время окончания события
+    location VARCHAR(255),                 -- Местоположение события (необязательное)
+    ticket_price DOUBLE PRECISION,         -- Стоимость билета (необязательное)
+    currency VARCHAR(255),                 -- Валюта (необязательное)
+    contact_name VARCHAR(255),             -- Имя контактного лица (необязательное)
+    contact_phone VARCHAR(255),            -- Телефон контактного лица (необязательное)
+    contact_email VARCHAR(255),            -- Email контактного лица (необязательное)
+    image VARCHAR(255),                    -- Ссылка на изображение события (необязательное)
+    is_published BOOLEAN NOT NULL,         -- Флаг публикации события
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(), -- Дата и время создания события
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(), -- Дата и время последнего обновления события
+    CHECK (date_end >= date_start)
+);
+SELECT diesel_manage_updated_at('events');
+
+-- Создание таблицы "user_events" для хранения информации о событиях пользователя
+CREATE TABLE IF NOT EXISTS user_events (
+    id SERIAL PRIMARY KEY,                 -- Идентификатор события пользователя
+    user_id INT NOT NULL,                  -- Идентификатор пользователя, создавшего событие
+    title VARCHAR(255) NOT NULL,           -- Заголовок события
+    description TEXT NOT NULL,             -- Описание события
+    category VARCHAR(255),                 -- Категория события (необязательное)
+    date_start TIMESTAMP NOT NULL,         -- Дата и время начала события
+    date_end TIMESTAMP NOT NULL,           -- Дата и время окончания события
+    location VARCHAR(255),                 -- Местоположение события (необязательное)
+    ticket_price DOUBLE PRECISION,         -- Стоимость билета (необязательное)
+    currency VARCHAR(255),                 -- Валюта (необязательное)
+    contact_name VARCHAR(255),             -- Имя контактного лица (необязательное)
+    contact_phone VARCHAR(255),            -- Телефон контактного лица (необязательное)
+    contact_email VARCHAR(255),            -- Email контактного лица (необязательное)
+    image VARCHAR(255),                    -- Ссылка на изображение события (необязательное)
+    is_published BOOLEAN NOT NULL,         -- Флаг публикации события
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(), -- Дата и время создания события
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(), -- Дата и время последнего обновления события
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
+SELECT diesel_manage_updated_at('user_events');
+
+-- Создание таблицы "event_calendar" для хранения дат событий
+CREATE TABLE IF NOT EXISTS event_calendar (
+    id SERIAL PRIMARY KEY,                -- Идентификатор даты события
+    event_id INT NOT NULL,                -- Идентификатор события
+    date DATE NOT NULL,                   -- Дата события
+    time_start TIME,                      -- Время начала события (необязательное)
+    time_end TIME,                        -- Время окончания события (необязательное)
+    FOREIGN KEY (event_id) REFERENCES events (id) ON DELETE CASCADE
+);
+
+-- Создание таблицы "event_attendees" для хранения информации о посетителях событий
+CREATE TABLE IF NOT EXISTS event_attendees (
+    id SERIAL PRIMARY KEY,                -- Идентификатор посетителя
+    event_id INT NOT NULL,                -- Идентификатор события, которое посещает
+    user_id INT NOT NULL,                 -- Идентификатор пользователя, посетившего событие
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(), -- Дата и время посещения события
+    FOREIGN KEY (event_id) REFERENCES events (id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    UNIQUE (event_id, user_id)
+);
+
+-- Создание таблицы "event_subscribers" для хранения информации о подписчиках событий
+CREATE TABLE IF NOT EXISTS event_subscribers (
+    id SERIAL PRIMARY KEY,                -- Идентификатор подписки
+    event_id INT NOT NULL,                -- Идентификатор события, на которое подписан
+    user_id INT NOT NULL,                 -- Идентификатор пользователя, подписавшегося
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(), -- Дата и время создания подписки
+    FOREIGN KEY (event_id) REFERENCES events (id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    UNIQUE (event_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_events_date_start ON events (date_start);
+CREATE INDEX IF NOT EXISTS idx_events_category ON events (category);
+CREATE INDEX IF NOT EXISTS idx_event_calendar_event_id ON event_calendar (event_id);
+CREATE INDEX IF NOT EXISTS idx_event_calendar_date ON event_calendar (date);
@@ -0,0 +1,27 @@
+-- Создание таблицы "advertisements" для хранения объявлений
+CREATE TABLE IF NOT EXISTS advertisements (
+    id SERIAL PRIMARY KEY,              -- Идентификатор объявления
+    user_id INT NOT NULL,               -- Идентификатор пользователя, разместившего объявление
+    community_id INT,                   -- Идентификатор сообщества, если применимо
+    title VARCHAR(255) NOT NULL,        -- Заголовок объявления
+    description TEXT,                   -- Описание объявления (необязательное)
+    category VARCHAR(255),              -- Категория объявления (необязательное)
+    price NUMERIC,                      -- Цена, если применимо
+    currency VARCHAR(255),              -- Валюта, если есть
+    location VARCHAR(255),              -- Местоположение (необязательное)
+    contact_name VARCHAR(255),          -- Имя контактного лица (необязательное)
+    contact_phone VARCHAR(255),         -- Телефон контактного лица (необязательное)
+    contact_email VARCHAR(255),         -- Email контактного лица (необязательное)
+    images TEXT[] NOT NULL DEFAULT '{}',-- Ссылки на изображения объявления, массив
+    video VARCHAR(255),                 -- Ссылка на видео объявления, если есть
+    is_active BOOLEAN NOT NULL,         -- Флаг активности объявления
+    created_at TIMESTAMP NOT NULL,      -- Дата и время создания
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (community_id) REFERENCES communities (id) ON DELETE SET NULL
+);
+
+-- Создание индекса на поле "user_id" для быстрого поиска объявлений пользователя
+CREATE INDEX IF NOT EXISTS idx_advertisements_user_id ON advertisements (user_id);
+
+-- Создание индекса на поле "is_active" для быстрого поиска активных объявлений
+CREATE INDEX IF NOT EXISTS idx_advertisements_is_active ON advertisements (is_active);
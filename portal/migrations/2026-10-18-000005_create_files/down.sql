@@ -0,0 +1,6 @@
+DROP TABLE IF EXISTS file_report;
+DROP TABLE IF EXISTS file_downloads;
+DROP TABLE IF EXISTS file_likes;
+DROP TABLE IF EXISTS file_comments;
+DROP TABLE IF EXISTS file_tags;
+DROP TABLE IF EXISTS files;
@@ -0,0 +1,81 @@
+-- Таблицы модуля files.rs. В старом дампе эти таблицы были описаны дважды,
+-- здесь оставлен вариант с внешними ключами и проверками.
+
+-- Создание таблицы "files" для хранения информации о файлах
+CREATE TABLE IF NOT EXISTS files (
+    id SERIAL PRIMARY KEY,               -- Идентификатор файла
+    user_id INT NOT NULL,                -- Идентификатор пользователя, загрузившего файл
+    community_id INT,                    -- Идентификатор сообщества (может отсутствовать)
+    title VARCHAR(255) NOT NULL,         -- Заголовок файла
+    description TEXT,                    -- Описание файла (может отсутствовать)
+    file_type VARCHAR(255) NOT NULL,     -- Тип файла (например, "image", "document" и т. д.)
+    file_url TEXT,                       -- URL файла (может отсутствовать)
+    is_private BOOLEAN NOT NULL,         -- Флаг приватности файла (true - приватный, false - публичный)
+    is_approved BOOLEAN,                 -- Флаг одобрения файла (может отсутствовать)
+    is_deleted BOOLEAN NOT NULL,         -- Флаг удаления файла (true - удален, false - не удален)
+    uploaded_at TIMESTAMP NOT NULL DEFAULT NOW(), -- Дата и время загрузки файла
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (community_id) REFERENCES communities (id),
+    CHECK (file_type <> '')
+);
+
+-- Создание таблицы "file_tags" для хранения связей между файлами и метками
+CREATE TABLE IF NOT EXISTS file_tags (
+    id SERIAL PRIMARY KEY,          -- Идентификатор связи
+    file_id INT NOT NULL,           -- Идентификатор файла
+    tag_id INT NOT NULL,            -- Идентификатор метки
+    FOREIGN KEY (file_id) REFERENCES files (id) ON DELETE CASCADE,
+    FOREIGN KEY (tag_id) REFERENCES tags (id) ON DELETE CASCADE,
+    UNIQUE (file_id, tag_id)
+);
+
+-- Создание таблицы "file_comments" для хранения комментариев к файлам
+CREATE TABLE IF NOT EXISTS file_comments (
+    id SERIAL PRIMARY KEY,          -- Идентификатор комментария
+    file_id INT NOT NULL,           -- Идентификатор файла, к которому оставлен комментарий
+    user_id INT NOT NULL,           -- Идентификатор пользователя, оставившего комментарий
+    comment_text TEXT,              -- Текст комментария (может отсутствовать)
+    is_deleted BOOLEAN NOT NULL,    -- Флаг удаления комментария (true - удален, false - не удален)
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(), -- Дата и время создания комментария
+    FOREIGN KEY (file_id) REFERENCES files (id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
+
+-- Создание таблицы "file_likes" для хранения отметок "Нравится" к файлам
+CREATE TABLE IF NOT EXISTS file_likes (
+    id SERIAL PRIMARY KEY,          -- Идентификатор отметки "Нравится"
+    file_id INT NOT NULL,           -- Идентификатор файла, к которому оставлена отметка "Нравится"
+    user_id INT NOT NULL,           -- Идентификатор пользователя, поставившего отметку "Нравится"
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(), -- Дата и время создания отметки "Нравится"
+    FOREIGN KEY (file_id) REFERENCES files (id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    UNIQUE (file_id, user_id)
+);
+
+-- Создание таблицы "file_downloads" для хранения информации о скачиваниях файлов
+CREATE TABLE IF NOT EXISTS file_downloads (
+    id SERIAL PRIMARY KEY,          -- Идентификатор скачивания файла
+    file_id INT NOT NULL,           -- Идентификатор файла, который был скачан
+    user_id INT NOT NULL,           -- Идентификатор пользователя, скачавшего файл
+    downloaded_at TIMESTAMP NOT NULL DEFAULT NOW(), -- Дата и время скачивания файла
+    FOREIGN KEY (file_id) REFERENCES files (id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
+
+-- Создание таблицы "file_report" для хранения отчетов о файлах
+CREATE TABLE IF NOT EXISTS file_report (
+    id SERIAL PRIMARY KEY,          -- Идентификатор отчета
+    file_id INT NOT NULL,           -- Идентификатор файла, на который составлен отчет
+    user_id INT NOT NULL,           -- Идентификатор пользователя, создавшего отчет
+    reason VARCHAR(255) NOT NULL,   -- Причина отчета
+    description TEXT,               -- Описание отчета (может отсутствовать)
+    status VARCHAR(255),            -- Статус отчета (может отсутствовать)
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(), -- Дата и время создания отчета
+    FOREIGN KEY (file_id) REFERENCES files (id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    CHECK (reason <> '')
+);
+
+CREATE INDEX IF NOT EXISTS idx_files_user_id ON files (user_id);
+CREATE INDEX IF NOT EXISTS idx_file_comments_file_id ON file_comments (file_id);
+CREATE INDEX IF NOT EXISTS idx_file_downloads_file_id ON file_downloads (file_id);
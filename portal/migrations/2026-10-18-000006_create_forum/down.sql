@@ -0,0 +1,6 @@
+DROP TABLE IF EXISTS forum_reports;
+DROP TABLE IF EXISTS forum_subscriptions;
+DROP TABLE IF EXISTS forum_likes;
+DROP TABLE IF EXISTS forum_posts;
+DROP TABLE IF EXISTS forum_topics;
+DROP TABLE IF EXISTS forums;
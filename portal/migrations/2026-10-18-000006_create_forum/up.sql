@@ -0,0 +1,81 @@
+-- Таблицы модуля Forum.rs
+
+-- Создание таблицы "forums" для хранения информации о форумах
+CREATE TABLE IF NOT EXISTS forums (
+    id SERIAL PRIMARY KEY,               -- Идентификатор форума
+    name VARCHAR(255) NOT NULL,          -- Название форума
+    description TEXT,                    -- Описание форума (может быть пустым)
+    category VARCHAR(255),               -- Категория форума (может быть пустой)
+    is_private BOOLEAN NOT NULL,         -- Флаг приватности форума (true - приватный, false - публичный)
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(), -- Дата и время создания форума
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(), -- Дата и время последнего обновления форума
+    CHECK (name <> '')
+);
+SELECT diesel_manage_updated_at('forums');
+
+-- Создание таблицы "forum_topics" для хранения информации о темах форума
+CREATE TABLE IF NOT EXISTS forum_topics (
+    id SERIAL PRIMARY KEY,          -- Идентификатор темы
+    forum_id INT NOT NULL,          -- Идентификатор форума, к которому принадлежит тема
+    title VARCHAR(255) NOT NULL,    -- Заголовок темы
+    description TEXT,               -- Описание темы (может быть пустым)
+    user_id INT NOT NULL,           -- Идентификатор пользователя, создавшего тему
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(), -- Дата и время создания темы
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(), -- Дата и время последнего обновления темы
+    FOREIGN KEY (forum_id) REFERENCES forums (id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    CHECK (title <> '')
+);
+SELECT diesel_manage_updated_at('forum_topics');
+
+-- Создание таблицы "forum_posts" для хранения сообщений на форуме
+CREATE TABLE IF NOT EXISTS forum_posts (
+    id SERIAL PRIMARY KEY,          -- Идентификатор сообщения
+    topic_id INT NOT NULL,          -- Идентификатор темы, к которой принадлежит сообщение
+    user_id INT NOT NULL,           -- Идентификатор пользователя, написавшего сообщение
+    content TEXT NOT NULL,          -- Содержание сообщения
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(), -- Дата и время создания сообщения
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(), -- Дата и время последнего обновления сообщения
+    FOREIGN KEY (topic_id) REFERENCES forum_topics (id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
+SELECT diesel_manage_updated_at('forum_posts');
+
+-- Создание таблицы "forum_likes" для хранения лайков к сообщениям на форуме
+CREATE TABLE IF NOT EXISTS forum_likes (
+    id SERIAL PRIMARY KEY,          -- Идентификатор лайка
+    post_id INT NOT NULL,           -- Идентификатор сообщения, к которому поставлен лайк
+    user_id INT NOT NULL,           -- Идентификатор пользователя, поставившего лайк
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(), -- Дата и время поставки лайка
+    FOREIGN KEY (post_id) REFERENCES forum_posts (id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    UNIQUE (post_id, user_id)
+);
+
+-- Создание таблицы "forum_subscriptions" для описания подписок на темы форума
+CREATE TABLE IF NOT EXISTS forum_subscriptions (
+    id SERIAL PRIMARY KEY,          -- Идентификатор подписки
+    user_id INT NOT NULL,           -- Идентификатор пользователя, подписавшегося
+    topic_id INT NOT NULL,          -- Идентификатор темы, на которую подписан
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(), -- Дата и время подписки
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (topic_id) REFERENCES forum_topics (id) ON DELETE CASCADE,
+    UNIQUE (user_id, topic_id)
+);
+
+-- Создание таблицы "forum_reports" для хранения отчетов о сообщениях на форуме
+CREATE TABLE IF NOT EXISTS forum_reports (
+    id SERIAL PRIMARY KEY,          -- Идентификатор отчета
+    post_id INT NOT NULL,           -- Идентификатор сообщения, на которое составлен отчет
+    user_id INT NOT NULL,           -- Идентификатор пользователя, создавшего отчет
+    reason VARCHAR(255) NOT NULL,   -- Причина отчета
+    description TEXT NOT NULL DEFAULT '', -- Описание отчета (может быть пустым)
+    status VARCHAR(255) NOT NULL DEFAULT 'в ожидании', -- Статус отчета
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(), -- Дата и время создания отчета
+    FOREIGN KEY (post_id) REFERENCES forum_posts (id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    CHECK (reason <> '')
+);
+
+CREATE INDEX IF NOT EXISTS idx_forum_topics_forum_id ON forum_topics (forum_id);
+CREATE INDEX IF NOT EXISTS idx_forum_posts_topic_id ON forum_posts (topic_id);
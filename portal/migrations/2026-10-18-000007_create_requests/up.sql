@@ -0,0 +1,25 @@
+-- Создание таблицы "requests" для хранения информации о запросах
+CREATE TABLE IF NOT EXISTS requests (
+    id SERIAL PRIMARY KEY,                -- Идентификатор запроса
+    user_id INT NOT NULL,                 -- Идентификатор пользователя, создавшего запрос
+    service_id INT NOT NULL,              -- Идентификатор услуги, связанной с запросом
+    request_date TIMESTAMP NOT NULL,      -- Дата и время создания запроса
+    description TEXT,                     -- Описание запроса (может быть пустым)
+    status VARCHAR(255) NOT NULL,         -- Статус запроса
+    assigned_to INT,                      -- Идентификатор пользователя, назначенного на запрос
+    priority SMALLINT NOT NULL,           -- Приоритет запроса
+    due_date DATE,                        -- Срок выполнения запроса (может быть пустым)
+    location GEOMETRY(POINT, 4326),       -- Местоположение запроса (может быть пустым)
+    contact_name VARCHAR(255),            -- Имя контактного лица (может быть пустым)
+    contact_phone VARCHAR(255),           -- Телефон контактного лица (может быть пустым)
+    contact_email VARCHAR(255),           -- Email контактного лица (может быть пустым)
+    comments TEXT,                        -- Комментарии к запросу (может быть пустым)
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (service_id) REFERENCES services (id) ON DELETE CASCADE,
+    FOREIGN KEY (assigned_to) REFERENCES users (id) ON DELETE SET NULL,
+    CHECK (status <> ''),
+    CHECK (priority >= 0)
+);
+
+CREATE INDEX IF NOT EXISTS idx_requests_user_id ON requests (user_id);
+CREATE INDEX IF NOT EXISTS idx_requests_status ON requests (status);
@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS price_and_tickets;
+DROP TABLE IF EXISTS schedules;
+ALTER TABLE transport_vehicles DROP CONSTRAINT IF EXISTS fk_transport_vehicles_carrier_id;
+DROP TABLE IF EXISTS carrier_information;
+DROP TABLE IF EXISTS transport_vehicles;
@@ -0,0 +1,49 @@
+-- Таблицы модуля Schedules.rs. Транспорт и перевозчик ссылаются друг на друга,
+-- поэтому второй внешний ключ добавляется отдельно и проверяется в конце транзакции.
+
+-- Создание таблицы "transport_vehicles" для хранения информации о транспортных средствах
+CREATE TABLE IF NOT EXISTS transport_vehicles (
+    id SERIAL PRIMARY KEY,              -- Идентификатор транспортного средства
+    vehicle_name VARCHAR(255) NOT NULL, -- Название транспортного средства
+    vehicle_description TEXT NOT NULL,  -- Описание транспортного средства
+    carrier_id INT NOT NULL,            -- Идентификатор перевозчика (поставщика)
+    model VARCHAR(255) NOT NULL,        -- Модель транспортного средства
+    route_number VARCHAR(255)           -- Номер маршрута (может быть пустым)
+);
+
+-- Создание таблицы "carrier_information" для хранения информации о перевозчиках
+CREATE TABLE IF NOT EXISTS carrier_information (
+    id SERIAL PRIMARY KEY,              -- Идентификатор перевозчика
+    vehicle_id INT NOT NULL,            -- Идентификатор транспортного средства, связанного с перевозчиком
+    carrier_name VARCHAR(255) NOT NULL, -- Название перевозчика
+    carrier_contact_information TEXT NOT NULL, -- Контактная информация перевозчика
+    FOREIGN KEY (vehicle_id) REFERENCES transport_vehicles (id) ON DELETE CASCADE
+);
+
+ALTER TABLE transport_vehicles
+    ADD CONSTRAINT fk_transport_vehicles_carrier_id
+    FOREIGN KEY (carrier_id) REFERENCES carrier_information (id)
+    ON DELETE CASCADE DEFERRABLE INITIALLY DEFERRED;
+
+-- Создание таблицы "schedules" для хранения информации о расписаниях
+CREATE TABLE IF NOT EXISTS schedules (
+    id SERIAL PRIMARY KEY,              -- Идентификатор расписания
+    vehicle_id INT NOT NULL,            -- Идентификатор транспортного средства, к которому относится расписание
+    departure_date DATE NOT NULL,       -- Дата отправления
+    arrival_date DATE NOT NULL,         -- Дата прибытия
+    departure_location VARCHAR(255) NOT NULL, -- Место отправления
+    arrival_location VARCHAR(255) NOT NULL,   -- Место прибытия
+    intermediate_stops TEXT,            -- Промежуточные остановки (могут быть пустыми)
+    departure_time TIME NOT NULL,       -- Время отправления
+    arrival_time TIME NOT NULL,         -- Время прибытия
+    FOREIGN KEY (vehicle_id) REFERENCES transport_vehicles (id) ON DELETE CASCADE
+);
+
+-- Создание таблицы "price_and_tickets" для хранения информации о ценах и билетах
+CREATE TABLE IF NOT EXISTS price_and_tickets (
+    id SERIAL PRIMARY KEY,              -- Идентификатор информации о ценах и билетах
+    schedule_id INT NOT NULL,           -- Идентификатор расписания, связанного с ценами и билетами
+    ticket_price DOUBLE PRECISION NOT NULL, -- Цена билета
+    ticket_availability BOOLEAN NOT NULL,   -- Доступность билета (true/false)
+    FOREIGN KEY (schedule_id) REFERENCES schedules (id) ON DELETE CASCADE
+);
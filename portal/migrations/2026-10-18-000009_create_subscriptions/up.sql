@@ -0,0 +1,20 @@
+-- Создание таблицы "subscriptions" для хранения подписок
+CREATE TABLE IF NOT EXISTS subscriptions (
+    id SERIAL PRIMARY KEY,                  -- Уникальный идентификатор подписки
+    user_id INT NOT NULL,                   -- Идентификатор пользователя
+    object_id INT NOT NULL,                 -- Идентификатор объекта
+    object_type VARCHAR(255) NOT NULL,      -- Тип объекта
+    status VARCHAR(255) NOT NULL,           -- Статус подписки
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(), -- Дата и время создания подписки
+    name VARCHAR(255) NOT NULL,             -- Название
+    types SMALLINT NOT NULL,                -- Типы
+    link VARCHAR(255) NOT NULL,             -- Ссылка
+    image VARCHAR(255) NOT NULL,            -- Изображение
+    category_id INT NOT NULL,               -- Идентификатор категории
+    lists SMALLINT NOT NULL,                -- Списки
+    members INT NOT NULL,                   -- Количество участников
+    description TEXT,                       -- Описание (необязательное)
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_subscriptions_user_id ON subscriptions (user_id);
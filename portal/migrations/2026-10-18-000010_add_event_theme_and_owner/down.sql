@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS idx_events_user_id;
+DROP INDEX IF EXISTS idx_events_theme_id;
+
+ALTER TABLE events
+    DROP COLUMN user_id,
+    DROP COLUMN theme_id;
@@ -0,0 +1,10 @@
+-- Тематика и автор события нужны для фильтрации в API и проверки прав
+
+ALTER TABLE events
+    ADD COLUMN theme_id INT,    -- Идентификатор тематики (необязательное)
+    ADD COLUMN user_id INT,     -- Идентификатор пользователя, создавшего событие
+    ADD FOREIGN KEY (theme_id) REFERENCES event_themes (id) ON DELETE SET NULL,
+    ADD FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_events_theme_id ON events (theme_id);
+CREATE INDEX IF NOT EXISTS idx_events_user_id ON events (user_id);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS event_recurrence_exceptions;
+DROP TABLE IF EXISTS event_recurrences;
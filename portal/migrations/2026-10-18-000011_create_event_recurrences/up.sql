@@ -0,0 +1,36 @@
+-- Правила повторения событий и исключенные из них даты.
+-- Даты по правилам не хранятся, а вычисляются при запросе.
+
+-- Создание таблицы "event_recurrences" для хранения правил повторения
+CREATE TABLE IF NOT EXISTS event_recurrences (
+    id SERIAL PRIMARY KEY,                    -- Идентификатор правила
+    event_id INT NOT NULL,                    -- Идентификатор события
+    freq VARCHAR(16) NOT NULL,                -- Частота: daily, weekly или monthly
+    repeat_every SMALLINT NOT NULL DEFAULT 1, -- Каждый N-й день / неделю / месяц
+    weekdays SMALLINT[] NOT NULL DEFAULT '{}', -- Дни недели для weekly (1 - понедельник, 7 - воскресенье)
+    month_week SMALLINT,                      -- Номер недели месяца для monthly (1..5, -1 - последняя)
+    month_weekday SMALLINT,                   -- День недели для monthly (1..7)
+    date_start DATE NOT NULL,                 -- Дата, с которой действует правило
+    time_start TIME,                          -- Время начала (необязательное)
+    time_end TIME,                            -- Время окончания (необязательное)
+    repeat_until DATE,                        -- Последняя дата повторения (необязательное)
+    repeat_count INT,                         -- Сколько раз повторить (необязательное)
+    FOREIGN KEY (event_id) REFERENCES events (id) ON DELETE CASCADE,
+    CHECK (freq IN ('daily', 'weekly', 'monthly')),
+    CHECK (repeat_every >= 1),
+    CHECK (repeat_until IS NULL OR repeat_count IS NULL),
+    CHECK (repeat_until IS NULL OR repeat_until >= date_start),
+    CHECK (repeat_count IS NULL OR repeat_count > 0),
+    CHECK ((month_week IS NULL) = (month_weekday IS NULL))
+);
+
+-- Создание таблицы "event_recurrence_exceptions" для дат, в которые событие не проводится
+CREATE TABLE IF NOT EXISTS event_recurrence_exceptions (
+    id SERIAL PRIMARY KEY,                -- Идентификатор исключения
+    recurrence_id INT NOT NULL,           -- Идентификатор правила
+    date DATE NOT NULL,                   -- Отмененная дата
+    FOREIGN KEY (recurrence_id) REFERENCES event_recurrences (id) ON DELETE CASCADE,
+    UNIQUE (recurrence_id, date)
+);
+
+CREATE INDEX IF NOT EXISTS idx_event_recurrences_event_id ON event_recurrences (event_id);
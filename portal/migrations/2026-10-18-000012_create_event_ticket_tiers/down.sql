@@ -0,0 +1,9 @@
+DROP INDEX IF EXISTS idx_event_attendees_waitlist;
+
+ALTER TABLE event_attendees
+    DROP COLUMN checked_in_at,
+    DROP COLUMN check_in_token,
+    DROP COLUMN status,
+    DROP COLUMN tier_id;
+
+DROP TABLE IF EXISTS event_ticket_tiers;
@@ -0,0 +1,35 @@
+-- Категории билетов с ограничением мест и лист ожидания для посетителей
+
+-- Создание таблицы "event_ticket_tiers" для хранения категорий билетов
+CREATE TABLE IF NOT EXISTS event_ticket_tiers (
+    id SERIAL PRIMARY KEY,                         -- Идентификатор категории
+    event_id INT NOT NULL,                         -- Идентификатор события
+    name VARCHAR(255) NOT NULL,                    -- Название категории (партер, VIP и т.п.)
+    price DOUBLE PRECISION NOT NULL DEFAULT 0,     -- Стоимость билета
+    currency VARCHAR(255),                         -- Валюта (необязательное)
+    capacity INT NOT NULL,                         -- Количество мест
+    sold INT NOT NULL DEFAULT 0,                   -- Количество занятых мест
+    sale_start TIMESTAMP,                          -- Начало продаж (необязательное)
+    sale_end TIMESTAMP,                            -- Окончание продаж (необязательное)
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),   -- Дата и время создания
+    FOREIGN KEY (event_id) REFERENCES events (id) ON DELETE CASCADE,
+    CHECK (price >= 0),
+    CHECK (sold >= 0 AND sold <= capacity),
+    CHECK (sale_end IS NULL OR sale_start IS NULL OR sale_end > sale_start)
+);
+
+CREATE INDEX IF NOT EXISTS idx_event_ticket_tiers_event_id ON event_ticket_tiers (event_id);
+
+-- Посетитель либо получил билет (confirmed), либо стоит в листе ожидания (waitlist).
+-- Токен для входа выдается только вместе с билетом.
+ALTER TABLE event_attendees
+    ADD COLUMN tier_id INT,                                       -- Категория билета (необязательное)
+    ADD COLUMN status VARCHAR(16) NOT NULL DEFAULT 'confirmed',   -- Статус записи
+    ADD COLUMN check_in_token VARCHAR(64),                        -- Токен для QR-кода на входе
+    ADD COLUMN checked_in_at TIMESTAMP,                           -- Когда посетитель прошел на событие
+    ADD FOREIGN KEY (tier_id) REFERENCES event_ticket_tiers (id) ON DELETE RESTRICT,
+    ADD CHECK (status IN ('confirmed', 'waitlist')),
+    ADD CHECK ((status = 'confirmed') = (check_in_token IS NOT NULL)),
+    ADD UNIQUE (check_in_token);
+
+CREATE INDEX IF NOT EXISTS idx_event_attendees_waitlist ON event_attendees (tier_id, id) WHERE status = 'waitlist';
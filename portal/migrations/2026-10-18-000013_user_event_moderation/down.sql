@@ -0,0 +1,14 @@
+DROP TABLE IF EXISTS user_event_history;
+
+DROP INDEX IF EXISTS idx_user_events_user_id;
+DROP INDEX IF EXISTS idx_user_events_status;
+
+ALTER TABLE user_events ADD COLUMN is_published BOOLEAN NOT NULL DEFAULT FALSE;
+UPDATE user_events SET is_published = TRUE WHERE status = 'approved';
+
+ALTER TABLE user_events
+    DROP COLUMN event_id,
+    DROP COLUMN theme_id,
+    DROP COLUMN status;
+
+DROP TABLE IF EXISTS event_moderators;
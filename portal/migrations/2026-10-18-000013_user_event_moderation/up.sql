@@ -0,0 +1,41 @@
+-- Заявки пользователей на события проходят модерацию:
+-- draft -> pending -> approved/rejected. Одобренная заявка становится событием в афише.
+
+-- Модераторы афиши
+CREATE TABLE IF NOT EXISTS event_moderators (
+    user_id INT PRIMARY KEY,                        -- Идентификатор пользователя-модератора
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),    -- Дата и время назначения
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
+
+ALTER TABLE user_events
+    ADD COLUMN status VARCHAR(16) NOT NULL DEFAULT 'draft',  -- Состояние заявки
+    ADD COLUMN theme_id INT,                                 -- Тематика будущего события
+    ADD COLUMN event_id INT,                                 -- Событие, созданное при одобрении
+    ADD CHECK (status IN ('draft', 'pending', 'approved', 'rejected')),
+    ADD FOREIGN KEY (theme_id) REFERENCES event_themes (id) ON DELETE SET NULL,
+    ADD FOREIGN KEY (event_id) REFERENCES events (id) ON DELETE SET NULL;
+
+-- Опубликованные раньше заявки событиями так и не стали, поэтому отправляются на проверку
+UPDATE user_events SET status = 'pending' WHERE is_published;
+
+ALTER TABLE user_events DROP COLUMN is_published;
+
+CREATE INDEX IF NOT EXISTS idx_user_events_status ON user_events (status);
+CREATE INDEX IF NOT EXISTS idx_user_events_user_id ON user_events (user_id);
+
+-- История переходов заявки между состояниями и комментарии модераторов.
+-- У комментария без смены состояния from_status = to_status.
+CREATE TABLE IF NOT EXISTS user_event_history (
+    id SERIAL PRIMARY KEY,                          -- Идентификатор записи
+    user_event_id INT NOT NULL,                     -- Идентификатор заявки
+    actor_id INT,                                   -- Кто выполнил действие
+    from_status VARCHAR(16) NOT NULL,               -- Состояние до перехода
+    to_status VARCHAR(16) NOT NULL,                 -- Состояние после перехода
+    comment TEXT,                                   -- Комментарий
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),    -- Дата и время перехода
+    FOREIGN KEY (user_event_id) REFERENCES user_events (id) ON DELETE CASCADE,
+    FOREIGN KEY (actor_id) REFERENCES users (id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_event_history_user_event_id ON user_event_history (user_event_id);
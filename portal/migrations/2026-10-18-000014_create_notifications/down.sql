@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS email_queue;
+DROP TABLE IF EXISTS event_reminders;
+DROP TABLE IF EXISTS notifications;
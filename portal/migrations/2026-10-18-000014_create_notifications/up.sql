@@ -0,0 +1,51 @@
+-- Уведомления на сайте и учет отправленных напоминаний о событиях
+
+CREATE TABLE IF NOT EXISTS notifications (
+    id SERIAL PRIMARY KEY,                          -- Идентификатор уведомления
+    user_id INT NOT NULL,                           -- Кому адресовано
+    kind VARCHAR(32) NOT NULL,                      -- Тип уведомления (event_reminder и т.п.)
+    event_id INT,                                   -- Событие, к которому относится (необязательное)
+    title VARCHAR(255) NOT NULL,                    -- Заголовок
+    body TEXT NOT NULL,                             -- Текст
+    is_read BOOLEAN NOT NULL DEFAULT FALSE,         -- Прочитано ли
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),    -- Дата и время создания
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (event_id) REFERENCES events (id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_notifications_user_id ON notifications (user_id, id DESC);
+CREATE INDEX IF NOT EXISTS idx_notifications_unread ON notifications (user_id) WHERE NOT is_read;
+
+-- Одно напоминание на пользователя, проведение события и окно.
+-- Строка вставляется до отправки, поэтому два экземпляра планировщика
+-- не отправят одно напоминание дважды.
+CREATE TABLE IF NOT EXISTS event_reminders (
+    id SERIAL PRIMARY KEY,                          -- Идентификатор записи
+    event_id INT NOT NULL,                          -- Идентификатор события
+    user_id INT NOT NULL,                           -- Идентификатор пользователя
+    starts_at TIMESTAMP NOT NULL,                   -- Начало проведения, о котором напомнили
+    window_minutes INT NOT NULL,                    -- Окно напоминания в минутах
+    sent_at TIMESTAMP NOT NULL DEFAULT NOW(),       -- Когда отправлено
+    FOREIGN KEY (event_id) REFERENCES events (id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    UNIQUE (event_id, user_id, starts_at, window_minutes)
+);
+
+-- Очередь писем. Обработчики только добавляют письмо в очередь (в той же
+-- транзакции, что и изменения), а отправляет их по SMTP фоновая задача,
+-- не занимая соединение с базой на время отправки.
+CREATE TABLE email_queue (
+    id              SERIAL PRIMARY KEY,
+    user_id         INT REFERENCES users(id) ON DELETE CASCADE, -- Пусто для писем не пользователю (почта организации)
+    email           VARCHAR(255) NOT NULL,
+    kind            VARCHAR(50) NOT NULL,
+    subject         TEXT NOT NULL,
+    body            TEXT NOT NULL,
+    created_at      TIMESTAMP NOT NULL DEFAULT NOW(),
+    attempts        INT NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP NOT NULL DEFAULT NOW(), -- Раньше этого времени письмо не берется (повтор или отправка в работе)
+    sent_at         TIMESTAMP,
+    last_error      TEXT
+);
+
+CREATE INDEX idx_email_queue_due ON email_queue (next_attempt_at) WHERE sent_at IS NULL;
@@ -0,0 +1,3 @@
+ALTER TABLE events
+    DROP COLUMN cancel_reason,
+    DROP COLUMN cancelled_at;
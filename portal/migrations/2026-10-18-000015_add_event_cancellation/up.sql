@@ -0,0 +1,6 @@
+-- Событие отменяется, а не удаляется: посетители должны узнать об отмене,
+-- а ссылки и календари - продолжать открываться
+
+ALTER TABLE events
+    ADD COLUMN cancelled_at TIMESTAMP,  -- Когда событие отменено (NULL - не отменено)
+    ADD COLUMN cancel_reason TEXT;      -- Причина отмены
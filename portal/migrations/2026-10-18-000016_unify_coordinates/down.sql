@@ -0,0 +1,34 @@
+DROP INDEX IF EXISTS idx_advertisements_coordinates_geom;
+DROP INDEX IF EXISTS idx_events_coordinates_geom;
+DROP INDEX IF EXISTS idx_wifi_charging_location_geom;
+DROP INDEX IF EXISTS idx_organizations_location_geom;
+DROP INDEX IF EXISTS idx_user_points_location_geom;
+
+DROP INDEX IF EXISTS idx_advertisements_coordinates;
+DROP INDEX IF EXISTS idx_events_coordinates;
+DROP INDEX IF EXISTS idx_requests_location;
+DROP INDEX IF EXISTS idx_wifi_charging_location;
+DROP INDEX IF EXISTS idx_organizations_location;
+DROP INDEX IF EXISTS idx_user_points_location;
+
+ALTER TABLE advertisements DROP COLUMN coordinates;
+ALTER TABLE events DROP COLUMN coordinates;
+ALTER TABLE organizations DROP COLUMN location;
+
+ALTER TABLE user_points
+    ADD COLUMN latitude DOUBLE PRECISION,
+    ADD COLUMN longitude DOUBLE PRECISION;
+UPDATE user_points SET latitude = ST_Y(location), longitude = ST_X(location);
+ALTER TABLE user_points
+    ALTER COLUMN latitude SET NOT NULL,
+    ALTER COLUMN longitude SET NOT NULL,
+    DROP COLUMN location;
+
+ALTER TABLE route_points
+    ADD COLUMN latitude DOUBLE PRECISION CHECK (latitude BETWEEN -90 AND 90),
+    ADD COLUMN longitude DOUBLE PRECISION CHECK (longitude BETWEEN -180 AND 180);
+UPDATE route_points SET latitude = ST_Y(location), longitude = ST_X(location);
+ALTER TABLE route_points
+    ALTER COLUMN latitude SET NOT NULL,
+    ALTER COLUMN longitude SET NOT NULL,
+    DROP COLUMN location;
@@ -0,0 +1,38 @@
+-- Все координаты хранятся в одном виде: GEOMETRY(POINT, 4326), x - долгота, y - широта.
+-- Поиск по радиусу идет через geography, поэтому индексы строятся по приведению к ней.
+
+ALTER TABLE user_points ADD COLUMN location GEOMETRY(POINT, 4326);
+UPDATE user_points SET location = ST_SetSRID(ST_MakePoint(longitude, latitude), 4326);
+ALTER TABLE user_points
+    ALTER COLUMN location SET NOT NULL,
+    DROP COLUMN latitude,
+    DROP COLUMN longitude;
+
+ALTER TABLE route_points ADD COLUMN location GEOMETRY(POINT, 4326);
+UPDATE route_points SET location = ST_SetSRID(ST_MakePoint(longitude, latitude), 4326);
+ALTER TABLE route_points
+    ALTER COLUMN location SET NOT NULL,
+    DROP COLUMN latitude,
+    DROP COLUMN longitude;
+
+ALTER TABLE organizations ADD COLUMN location GEOMETRY(POINT, 4326);   -- Координаты организации
+
+-- У событий и объявлений location - текстовый адрес, координаты хранятся отдельно
+ALTER TABLE events ADD COLUMN coordinates GEOMETRY(POINT, 4326);
+ALTER TABLE advertisements ADD COLUMN coordinates GEOMETRY(POINT, 4326);
+
+CREATE INDEX IF NOT EXISTS idx_user_points_location ON user_points USING GIST ((location::geography));
+CREATE INDEX IF NOT EXISTS idx_organizations_location ON organizations USING GIST ((location::geography));
+CREATE INDEX IF NOT EXISTS idx_wifi_charging_location ON wifi_charging USING GIST ((location::geography));
+CREATE INDEX IF NOT EXISTS idx_requests_location ON requests USING GIST ((location::geography));
+CREATE INDEX IF NOT EXISTS idx_events_coordinates ON events USING GIST ((coordinates::geography));
+CREATE INDEX IF NOT EXISTS idx_advertisements_coordinates ON advertisements USING GIST ((coordinates::geography));
+
+-- Поиск в прямоугольнике идет по геометрии (оператор &&), индексы по ::geography
+-- ему не подходят. Для него нужны обычные индексы по самим колонкам.
+
+CREATE INDEX IF NOT EXISTS idx_user_points_location_geom ON user_points USING GIST (location);
+CREATE INDEX IF NOT EXISTS idx_organizations_location_geom ON organizations USING GIST (location);
+CREATE INDEX IF NOT EXISTS idx_wifi_charging_location_geom ON wifi_charging USING GIST (location);
+CREATE INDEX IF NOT EXISTS idx_events_coordinates_geom ON events USING GIST (coordinates);
+CREATE INDEX IF NOT EXISTS idx_advertisements_coordinates_geom ON advertisements USING GIST (coordinates);
@@ -0,0 +1,3 @@
+ALTER TABLE cities DROP COLUMN utc_offset;
+ALTER TABLE organizations DROP COLUMN opening_hours;
+ALTER TABLE organizations RENAME COLUMN opening_hours_note TO operating_hours;
@@ -0,0 +1,11 @@
+-- Часы работы организаций хранятся в структурированном виде: по дням недели
+-- и исключения по датам. Прежний свободный текст надежно не разобрать,
+-- поэтому он остается примечанием к расписанию.
+
+ALTER TABLE organizations RENAME COLUMN operating_hours TO opening_hours_note;   -- Примечание к часам работы
+ALTER TABLE organizations ADD COLUMN opening_hours JSONB;                         -- Расписание: {"week": [7 дней], "exceptions": [..]}
+
+-- Часы работы записаны в местном времени города, а сервер может работать
+-- в другом поясе. Смещение от UTC хранится у города, по умолчанию московское.
+ALTER TABLE cities ADD COLUMN utc_offset INT NOT NULL DEFAULT 180
+    CHECK (utc_offset BETWEEN -720 AND 840);   -- Смещение местного времени от UTC в минутах
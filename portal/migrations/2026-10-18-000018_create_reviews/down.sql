@@ -0,0 +1,16 @@
+DROP TRIGGER IF EXISTS user_points_delete_reviews ON user_points;
+DROP TRIGGER IF EXISTS organizations_delete_reviews ON organizations;
+DROP FUNCTION IF EXISTS reviews_delete_for_object();
+DROP TRIGGER IF EXISTS reviews_deleted ON reviews;
+DROP FUNCTION IF EXISTS reviews_refresh_rating();
+DROP TRIGGER IF EXISTS reviews_object_exists ON reviews;
+DROP FUNCTION IF EXISTS reviews_check_object();
+
+ALTER TABLE user_points
+    DROP COLUMN reviews_count,
+    ADD COLUMN average_rating REAL,
+    ADD COLUMN reviews TEXT[];
+ALTER TABLE organizations DROP COLUMN reviews_count;
+
+DROP TABLE IF EXISTS review_photos;
+DROP TABLE IF EXISTS reviews;
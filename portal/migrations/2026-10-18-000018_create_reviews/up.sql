@@ -0,0 +1,95 @@
+-- Отзывы об организациях и пользовательских точках. Объект задается видом
+-- и идентификатором, как в tag_object. Средняя оценка и число отзывов
+-- хранятся у самого объекта и пересчитываются вместе с отзывом.
+
+CREATE TABLE reviews (
+    id            SERIAL PRIMARY KEY,
+    object_type   VARCHAR(20) NOT NULL CHECK (object_type IN ('organization', 'user_point')),   -- Вид объекта
+    object_id     INT NOT NULL,                                                                 -- Идентификатор объекта
+    user_id       INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,                          -- Автор отзыва
+    rating        SMALLINT NOT NULL CHECK (rating BETWEEN 1 AND 5),                             -- Оценка в звездах
+    body          TEXT,                                                                         -- Текст отзыва
+    created_at    TIMESTAMP NOT NULL DEFAULT NOW(),                                             -- Дата и время создания
+    updated_at    TIMESTAMP,                                                                    -- Дата и время последнего изменения
+    reply         TEXT,                                                                         -- Ответ владельца
+    reply_user_id INT REFERENCES users(id) ON DELETE SET NULL,                                  -- Кто ответил
+    replied_at    TIMESTAMP,                                                                    -- Когда ответил
+    UNIQUE (object_type, object_id, user_id)                                                    -- Один отзыв от пользователя на объект
+);
+
+CREATE INDEX idx_reviews_object ON reviews (object_type, object_id, created_at DESC);
+
+-- Фотографии к отзыву - файлы, загруженные автором через модуль файлов
+CREATE TABLE review_photos (
+    review_id INT NOT NULL REFERENCES reviews(id) ON DELETE CASCADE,
+    file_id   INT NOT NULL REFERENCES files(id) ON DELETE CASCADE,
+    position  SMALLINT NOT NULL DEFAULT 0,   -- Порядок показа
+    PRIMARY KEY (review_id, file_id)
+);
+
+-- Прежние оценки ничем не подтверждены, теперь они считаются только по отзывам.
+-- Отзывы строками без автора и оценки перенести некуда.
+UPDATE organizations SET rating = NULL;
+ALTER TABLE organizations ADD COLUMN reviews_count INT NOT NULL DEFAULT 0;   -- Число отзывов
+
+UPDATE user_points SET rating = NULL;
+ALTER TABLE user_points
+    DROP COLUMN average_rating,
+    DROP COLUMN reviews,
+    ADD COLUMN reviews_count INT NOT NULL DEFAULT 0;                          -- Число отзывов
+
+-- У reviews нет внешнего ключа на объект, поэтому связь проверяется триггерами:
+-- отзыв можно оставить только существующему объекту, вместе с объектом
+-- удаляются его отзывы. Средняя оценка пересчитывается при любом удалении
+-- отзыва, в том числе каскадном при удалении пользователя.
+
+CREATE FUNCTION reviews_check_object() RETURNS TRIGGER AS $$
+BEGIN
+    IF (NEW.object_type = 'organization' AND NOT EXISTS (SELECT 1 FROM organizations WHERE id = NEW.object_id))
+        OR (NEW.object_type = 'user_point' AND NOT EXISTS (SELECT 1 FROM user_points WHERE id = NEW.object_id)) THEN
+        RAISE EXCEPTION 'review object %/% does not exist', NEW.object_type, NEW.object_id
+            USING ERRCODE = 'foreign_key_violation';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER reviews_object_exists
+    BEFORE INSERT OR UPDATE OF object_type, object_id ON reviews
+    FOR EACH ROW EXECUTE PROCEDURE reviews_check_object();
+
+CREATE FUNCTION reviews_refresh_rating() RETURNS TRIGGER AS $$
+BEGIN
+    IF OLD.object_type = 'organization' THEN
+        UPDATE organizations SET
+            rating = (SELECT TRUNC(AVG(rating), 2) FROM reviews WHERE object_type = OLD.object_type AND object_id = OLD.object_id),
+            reviews_count = (SELECT COUNT(*) FROM reviews WHERE object_type = OLD.object_type AND object_id = OLD.object_id)
+        WHERE id = OLD.object_id;
+    ELSIF OLD.object_type = 'user_point' THEN
+        UPDATE user_points SET
+            rating = (SELECT TRUNC(AVG(rating), 2) FROM reviews WHERE object_type = OLD.object_type AND object_id = OLD.object_id),
+            reviews_count = (SELECT COUNT(*) FROM reviews WHERE object_type = OLD.object_type AND object_id = OLD.object_id)
+        WHERE id = OLD.object_id;
+    END IF;
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER reviews_deleted
+    AFTER DELETE ON reviews
+    FOR EACH ROW EXECUTE PROCEDURE reviews_refresh_rating();
+
+CREATE FUNCTION reviews_delete_for_object() RETURNS TRIGGER AS $$
+BEGIN
+    DELETE FROM reviews WHERE object_type = TG_ARGV[0] AND object_id = OLD.id;
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER organizations_delete_reviews
+    AFTER DELETE ON organizations
+    FOR EACH ROW EXECUTE PROCEDURE reviews_delete_for_object('organization');
+
+CREATE TRIGGER user_points_delete_reviews
+    AFTER DELETE ON user_points
+    FOR EACH ROW EXECUTE PROCEDURE reviews_delete_for_object('user_point');
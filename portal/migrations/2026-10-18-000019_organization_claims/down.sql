@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS organization_claims;
+ALTER TABLE advertisements DROP COLUMN organization_id;
+ALTER TABLE organizations DROP COLUMN owner_id;
+DROP TABLE IF EXISTS administrators;
@@ -0,0 +1,34 @@
+-- Администраторы сайта: подтверждают права на организации
+CREATE TABLE administrators (
+    user_id    INT PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE organizations ADD COLUMN owner_id INT REFERENCES users(id) ON DELETE SET NULL;            -- Подтвержденный владелец
+ALTER TABLE advertisements ADD COLUMN organization_id INT REFERENCES organizations(id) ON DELETE SET NULL; -- Организация, от имени которой объявление
+
+-- Заявки на права владельца организации. Подтверждение - документ из модуля
+-- файлов или код, отправленный на почту организации.
+CREATE TABLE organization_claims (
+    id                SERIAL PRIMARY KEY,
+    organization_id   INT NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    user_id           INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,     -- Кто заявляет права
+    method            VARCHAR(10) NOT NULL CHECK (method IN ('document', 'email')),
+    file_id           INT REFERENCES files(id) ON DELETE SET NULL,             -- Документ, подтверждающий права
+    email             VARCHAR(255),                                            -- Адрес, на который отправлен код
+    email_code        VARCHAR(10),                                             -- Код из письма
+    code_sent_at      TIMESTAMP,                                               -- Когда отправлен код
+    code_attempts     INT NOT NULL DEFAULT 0,                                  -- Число попыток ввода кода
+    email_verified_at TIMESTAMP,                                               -- Когда код подтвержден
+    status            VARCHAR(20) NOT NULL DEFAULT 'pending'
+                      CHECK (status IN ('pending', 'approved', 'rejected', 'cancelled')),
+    comment           TEXT,                                                    -- Комментарий администратора
+    reviewed_by       INT REFERENCES users(id) ON DELETE SET NULL,             -- Администратор, рассмотревший заявку
+    reviewed_at       TIMESTAMP,
+    created_at        TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+-- Одна открытая заявка пользователя на организацию
+CREATE UNIQUE INDEX idx_organization_claims_pending
+    ON organization_claims (organization_id, user_id) WHERE status = 'pending';
+CREATE INDEX idx_organization_claims_status ON organization_claims (status, created_at);
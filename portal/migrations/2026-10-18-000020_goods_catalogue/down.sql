@@ -0,0 +1,19 @@
+DROP TRIGGER IF EXISTS organizations_street_changed ON organizations;
+DROP FUNCTION IF EXISTS goods_follow_organization_street();
+
+DROP TABLE IF EXISTS goods_photos;
+
+DROP INDEX IF EXISTS idx_goods_street_price;
+DROP INDEX IF EXISTS idx_goods_organization;
+
+ALTER TABLE goods
+    DROP COLUMN updated_at,
+    DROP COLUMN created_at,
+    DROP COLUMN is_service,
+    DROP COLUMN availability,
+    DROP COLUMN currency,
+    DROP COLUMN price,
+    DROP COLUMN category_id,
+    DROP COLUMN organization_id;
+
+DROP TABLE IF EXISTS goods_categories;
@@ -0,0 +1,55 @@
+-- Каталог товаров и услуг: товар принадлежит организации-продавцу.
+-- street_id остается и всегда равен улице организации, чтобы выборки
+-- по улице и району работали без лишних соединений.
+
+CREATE TABLE goods_categories (
+    id        SERIAL PRIMARY KEY,
+    name      VARCHAR(100) NOT NULL,                                  -- Название категории
+    parent_id INT REFERENCES goods_categories(id) ON DELETE SET NULL  -- Родительская категория
+);
+
+-- UNIQUE (parent_id, name) не срабатывает для корневых категорий: NULL не равен NULL.
+-- Уникальность проверяется по выражению, где у корня вместо parent_id стоит 0.
+CREATE UNIQUE INDEX idx_goods_categories_parent_name ON goods_categories (COALESCE(parent_id, 0), name);
+
+ALTER TABLE goods
+    ADD COLUMN organization_id INT REFERENCES organizations(id) ON DELETE CASCADE,   -- Продавец
+    ADD COLUMN category_id INT REFERENCES goods_categories(id) ON DELETE SET NULL,   -- Категория
+    ADD COLUMN price NUMERIC(12, 2) CHECK (price >= 0),                              -- Цена, пусто - по запросу
+    ADD COLUMN currency VARCHAR(3) NOT NULL DEFAULT 'RUB',                           -- Валюта цены
+    ADD COLUMN availability VARCHAR(20) NOT NULL DEFAULT 'in_stock'
+        CHECK (availability IN ('in_stock', 'on_order', 'out_of_stock')),            -- Наличие
+    ADD COLUMN is_service BOOLEAN NOT NULL DEFAULT FALSE,                            -- Услуга, а не товар
+    ADD COLUMN created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    ADD COLUMN updated_at TIMESTAMP;
+
+-- Старые записи привязываются к организации, только если она на улице одна
+UPDATE goods SET organization_id = o.id
+FROM (
+    SELECT street_id, MIN(id) AS id FROM organizations GROUP BY street_id HAVING COUNT(*) = 1
+) o
+WHERE o.street_id = goods.street_id;
+
+CREATE INDEX idx_goods_organization ON goods (organization_id);
+CREATE INDEX idx_goods_street_price ON goods (street_id, price);
+
+-- Фотографии товара - файлы владельца организации из модуля файлов
+CREATE TABLE goods_photos (
+    goods_id INT NOT NULL REFERENCES goods(id) ON DELETE CASCADE,
+    file_id  INT NOT NULL REFERENCES files(id) ON DELETE CASCADE,
+    position SMALLINT NOT NULL DEFAULT 0,   -- Порядок показа
+    PRIMARY KEY (goods_id, file_id)
+);
+
+-- При переезде организации ее товары переезжают вместе с ней
+CREATE FUNCTION goods_follow_organization_street() RETURNS TRIGGER AS $$
+BEGIN
+    UPDATE goods SET street_id = NEW.street_id WHERE organization_id = NEW.id;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER organizations_street_changed
+    AFTER UPDATE OF street_id ON organizations
+    FOR EACH ROW WHEN (OLD.street_id IS DISTINCT FROM NEW.street_id)
+    EXECUTE PROCEDURE goods_follow_organization_street();
@@ -0,0 +1,13 @@
+DROP TABLE IF EXISTS wifi_charging_reports;
+DROP TABLE IF EXISTS charging_connectors;
+
+ALTER TABLE wifi_charging
+    DROP COLUMN access_hours,
+    DROP COLUMN currency,
+    DROP COLUMN price_unit,
+    DROP COLUMN price,
+    DROP COLUMN is_free,
+    DROP COLUMN has_charging,
+    DROP COLUMN wifi_password_required,
+    DROP COLUMN wifi_ssid,
+    DROP COLUMN has_wifi;
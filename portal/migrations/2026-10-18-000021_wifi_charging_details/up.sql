@@ -0,0 +1,38 @@
+-- Подробности о точках Wi-Fi и зарядках вместо одного текстового поля services
+-- (оно остается примечанием) и отметки пользователей о текущем состоянии точки.
+
+ALTER TABLE wifi_charging
+    ADD COLUMN has_wifi BOOLEAN NOT NULL DEFAULT FALSE,                 -- Есть Wi-Fi
+    ADD COLUMN wifi_ssid VARCHAR(64),                                    -- Имя сети
+    ADD COLUMN wifi_password_required BOOLEAN NOT NULL DEFAULT FALSE,   -- Нужен пароль
+    ADD COLUMN has_charging BOOLEAN NOT NULL DEFAULT FALSE,             -- Есть зарядка для электромобилей
+    ADD COLUMN is_free BOOLEAN NOT NULL DEFAULT TRUE,                   -- Бесплатно
+    ADD COLUMN price NUMERIC(10, 2) CHECK (price >= 0),                  -- Стоимость, если платно
+    ADD COLUMN price_unit VARCHAR(10)
+        CHECK (price_unit IN ('kwh', 'minute', 'hour', 'session')),     -- За что берется плата
+    ADD COLUMN currency VARCHAR(3) NOT NULL DEFAULT 'RUB',              -- Валюта стоимости
+    ADD COLUMN access_hours JSONB;                                       -- Часы доступа, как opening_hours у организаций
+
+-- Разъемы зарядной станции
+CREATE TABLE charging_connectors (
+    id             SERIAL PRIMARY KEY,
+    point_id       INT NOT NULL REFERENCES wifi_charging(id) ON DELETE CASCADE,
+    connector_type VARCHAR(20) NOT NULL
+        CHECK (connector_type IN ('type1', 'type2', 'ccs1', 'ccs2', 'chademo', 'gbt', 'tesla', 'schuko')),
+    power_kw       NUMERIC(6, 1) NOT NULL CHECK (power_kw > 0),          -- Мощность, кВт
+    quantity       SMALLINT NOT NULL DEFAULT 1 CHECK (quantity > 0)      -- Сколько таких разъемов
+);
+
+CREATE INDEX idx_charging_connectors_point ON charging_connectors (point_id);
+
+-- Отметки пользователей: работает, сломано, занято
+CREATE TABLE wifi_charging_reports (
+    id         SERIAL PRIMARY KEY,
+    point_id   INT NOT NULL REFERENCES wifi_charging(id) ON DELETE CASCADE,
+    user_id    INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    status     VARCHAR(20) NOT NULL CHECK (status IN ('working', 'broken', 'occupied')),
+    comment    TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_wifi_charging_reports_point ON wifi_charging_reports (point_id, created_at DESC);
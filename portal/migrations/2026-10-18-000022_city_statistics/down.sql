@@ -0,0 +1,8 @@
+ALTER TABLE advertisements DROP COLUMN city_id;
+ALTER TABLE events DROP COLUMN city_id;
+
+DROP INDEX IF EXISTS idx_advertisements_created_at;
+DROP INDEX IF EXISTS idx_requests_request_date;
+
+ALTER TABLE wifi_charging DROP COLUMN created_at;
+ALTER TABLE organizations DROP COLUMN created_at;
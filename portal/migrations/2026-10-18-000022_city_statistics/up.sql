@@ -0,0 +1,19 @@
+-- Для помесячной статистики у организаций и точек Wi-Fi/зарядок нужна дата
+-- добавления. У уже существующих строк она неизвестна и остается пустой:
+-- такие строки входят в итоги, но не в помесячные ряды.
+
+ALTER TABLE organizations ADD COLUMN created_at TIMESTAMP;
+ALTER TABLE organizations ALTER COLUMN created_at SET DEFAULT NOW();
+
+ALTER TABLE wifi_charging ADD COLUMN created_at TIMESTAMP;
+ALTER TABLE wifi_charging ALTER COLUMN created_at SET DEFAULT NOW();
+
+-- События и объявления без координат нельзя отнести к городу по карте.
+-- Для них город указывается явно.
+ALTER TABLE events ADD COLUMN city_id INT REFERENCES cities(id) ON DELETE SET NULL;           -- Город, если нет координат
+ALTER TABLE advertisements ADD COLUMN city_id INT REFERENCES cities(id) ON DELETE SET NULL;   -- Город, если нет координат
+
+CREATE INDEX idx_requests_request_date ON requests (request_date);
+CREATE INDEX idx_advertisements_created_at ON advertisements (created_at);
+CREATE INDEX idx_events_city ON events (city_id);
+CREATE INDEX idx_advertisements_city ON advertisements (city_id);
@@ -0,0 +1,8 @@
+ALTER TABLE requests
+    DROP COLUMN address,
+    DROP COLUMN street_id;
+
+DROP INDEX IF EXISTS idx_streets_name_trgm;
+
+DROP TABLE IF EXISTS house_ranges;
+DROP TABLE IF EXISTS street_addresses;
@@ -0,0 +1,38 @@
+-- Геокодер по собственному реестру улиц: точки отдельных домов
+-- и диапазоны номеров вдоль отрезков улицы для интерполяции.
+
+CREATE TABLE street_addresses (
+    id        SERIAL PRIMARY KEY,
+    street_id INT NOT NULL REFERENCES streets(id) ON DELETE CASCADE,
+    house     VARCHAR(20) NOT NULL,              -- Номер дома в нормальном виде: "12", "12а", "12к1", "12/3"
+    number    INT NOT NULL CHECK (number > 0),   -- Числовая часть номера
+    location  GEOMETRY(POINT, 4326) NOT NULL,
+    UNIQUE (street_id, house)
+);
+
+CREATE INDEX idx_street_addresses_location ON street_addresses USING GIST (location);
+
+CREATE TABLE house_ranges (
+    id          SERIAL PRIMARY KEY,
+    street_id   INT NOT NULL REFERENCES streets(id) ON DELETE CASCADE,
+    from_number INT NOT NULL CHECK (from_number > 0),
+    to_number   INT NOT NULL,
+    parity      VARCHAR(4) NOT NULL DEFAULT 'all' CHECK (parity IN ('all', 'odd', 'even')),   -- Какая сторона улицы
+    geometry    GEOMETRY(LINESTRING, 4326) NOT NULL,   -- Отрезок, вдоль которого идут дома с from_number по to_number
+    CHECK (to_number >= from_number),
+    UNIQUE (street_id, from_number, to_number, parity)
+);
+
+CREATE INDEX idx_house_ranges_geometry ON house_ranges USING GIST (geometry);
+
+-- Нечеткий поиск улиц геокодером: кандидаты отбираются по триграммам
+-- в базе, а не перебором всех улиц. Название приводится к тому же виду,
+-- что и запрос (нижний регистр, ё -> е).
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX idx_streets_name_trgm ON streets USING GIN (translate(lower(name), 'ё', 'е') gin_trgm_ops);
+
+-- Адрес заявки, как его ввел пользователь, и найденная по нему улица
+ALTER TABLE requests
+    ADD COLUMN street_id INT REFERENCES streets(id) ON DELETE SET NULL,
+    ADD COLUMN address VARCHAR(255);
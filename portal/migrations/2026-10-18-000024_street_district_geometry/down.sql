@@ -0,0 +1,22 @@
+DROP TRIGGER IF EXISTS requests_assign_district ON requests;
+DROP TRIGGER IF EXISTS user_points_assign_district ON user_points;
+DROP FUNCTION IF EXISTS assign_district();
+
+DROP INDEX IF EXISTS idx_requests_district;
+DROP INDEX IF EXISTS idx_user_points_district;
+
+ALTER TABLE requests DROP COLUMN district_id;
+ALTER TABLE user_points DROP COLUMN district_id;
+
+DROP FUNCTION IF EXISTS district_at(GEOMETRY, INT);
+
+DROP TRIGGER IF EXISTS districts_boundary_changed ON districts;
+DROP FUNCTION IF EXISTS districts_area_from_boundary();
+DROP TRIGGER IF EXISTS streets_geometry_changed ON streets;
+DROP FUNCTION IF EXISTS streets_length_from_geometry();
+
+DROP INDEX IF EXISTS idx_districts_boundary;
+DROP INDEX IF EXISTS idx_streets_geometry;
+
+ALTER TABLE districts DROP COLUMN boundary;
+ALTER TABLE streets DROP COLUMN geometry;
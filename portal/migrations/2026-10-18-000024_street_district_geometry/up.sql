@@ -0,0 +1,77 @@
+-- Геометрия улиц и границы районов. Длина улицы и площадь района
+-- считаются по геометрии, отметки и заявки попадают в район по точке.
+
+ALTER TABLE streets ADD COLUMN geometry GEOMETRY(MULTILINESTRING, 4326);
+ALTER TABLE districts ADD COLUMN boundary GEOMETRY(MULTIPOLYGON, 4326);
+
+CREATE INDEX idx_streets_geometry ON streets USING GIST (geometry);
+CREATE INDEX idx_districts_boundary ON districts USING GIST (boundary);
+
+-- Длина в км и площадь в кв. км по geography, то есть на сфере.
+-- Без геометрии остаются значения, введенные вручную.
+CREATE FUNCTION streets_length_from_geometry() RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.geometry IS NOT NULL THEN
+        NEW.length_km := ROUND((ST_Length(NEW.geometry::geography) / 1000)::numeric, 2);
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER streets_geometry_changed
+    BEFORE INSERT OR UPDATE ON streets
+    FOR EACH ROW EXECUTE PROCEDURE streets_length_from_geometry();
+
+CREATE FUNCTION districts_area_from_boundary() RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.boundary IS NOT NULL THEN
+        NEW.area_sq_km := ROUND((ST_Area(NEW.boundary::geography) / 1000000)::numeric, 2);
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER districts_boundary_changed
+    BEFORE INSERT OR UPDATE ON districts
+    FOR EACH ROW EXECUTE PROCEDURE districts_area_from_boundary();
+
+-- Район по точке: граница, в которую попадает точка (из вложенных - меньшая),
+-- иначе район улицы
+CREATE FUNCTION district_at(point GEOMETRY, street INT) RETURNS INT AS $$
+    SELECT COALESCE(
+        (SELECT id FROM districts
+          WHERE boundary IS NOT NULL AND ST_Covers(boundary, point)
+          ORDER BY ST_Area(boundary), id LIMIT 1),
+        (SELECT district_id FROM streets WHERE id = street)
+    );
+$$ LANGUAGE sql STABLE;
+
+ALTER TABLE user_points ADD COLUMN district_id INT REFERENCES districts(id) ON DELETE SET NULL;
+ALTER TABLE requests ADD COLUMN district_id INT REFERENCES districts(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_user_points_district ON user_points (district_id);
+CREATE INDEX idx_requests_district ON requests (district_id);
+
+CREATE FUNCTION assign_district() RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.location IS NOT NULL THEN
+        NEW.district_id := district_at(NEW.location, NEW.street_id);
+    ELSIF NEW.street_id IS NOT NULL THEN
+        NEW.district_id := (SELECT district_id FROM streets WHERE id = NEW.street_id);
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER user_points_assign_district
+    BEFORE INSERT OR UPDATE OF location, street_id ON user_points
+    FOR EACH ROW EXECUTE PROCEDURE assign_district();
+
+CREATE TRIGGER requests_assign_district
+    BEFORE INSERT OR UPDATE OF location, street_id ON requests
+    FOR EACH ROW EXECUTE PROCEDURE assign_district();
+
+UPDATE user_points SET district_id = district_at(location, street_id);
+UPDATE requests SET district_id = district_at(location, street_id) WHERE location IS NOT NULL;
+UPDATE requests r SET district_id = s.district_id FROM streets s
+ WHERE r.location IS NULL AND s.id = r.street_id;
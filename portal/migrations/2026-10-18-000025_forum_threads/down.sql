@@ -0,0 +1,12 @@
+DROP TABLE IF EXISTS forum_post_revisions;
+
+DROP INDEX IF EXISTS idx_forum_posts_parent_id;
+
+ALTER TABLE forum_posts
+    DROP COLUMN deleted_by,
+    DROP COLUMN deleted_at,
+    DROP COLUMN edited_at,
+    DROP COLUMN edit_count,
+    DROP COLUMN quote_text,
+    DROP COLUMN quoted_post_id,
+    DROP COLUMN parent_id;
@@ -0,0 +1,24 @@
+-- Ответы на конкретное сообщение, цитаты, история правок
+-- и мягкое удаление сообщений форума
+
+ALTER TABLE forum_posts
+    ADD COLUMN parent_id INT REFERENCES forum_posts(id) ON DELETE SET NULL,      -- Сообщение, на которое это ответ
+    ADD COLUMN quoted_post_id INT REFERENCES forum_posts(id) ON DELETE SET NULL, -- Процитированное сообщение
+    ADD COLUMN quote_text TEXT,                                                  -- Текст цитаты на момент ответа
+    ADD COLUMN edit_count INT NOT NULL DEFAULT 0,                                -- Сколько раз сообщение правили
+    ADD COLUMN edited_at TIMESTAMP,                                              -- Когда правили в последний раз
+    ADD COLUMN deleted_at TIMESTAMP,                                             -- Когда удалено (текст скрывается, ответы остаются)
+    ADD COLUMN deleted_by INT REFERENCES users(id) ON DELETE SET NULL;           -- Кто удалил: автор или модератор
+
+CREATE INDEX idx_forum_posts_parent_id ON forum_posts (parent_id);
+
+-- Прежние версии текста: при каждой правке сюда попадает текст до нее
+CREATE TABLE forum_post_revisions (
+    id         SERIAL PRIMARY KEY,
+    post_id    INT NOT NULL REFERENCES forum_posts(id) ON DELETE CASCADE,
+    editor_id  INT REFERENCES users(id) ON DELETE SET NULL,   -- Кто сделал правку
+    content    TEXT NOT NULL,                                 -- Текст до правки
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()               -- Время правки
+);
+
+CREATE INDEX idx_forum_post_revisions_post_id ON forum_post_revisions (post_id, id);
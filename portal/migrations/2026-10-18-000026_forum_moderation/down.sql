@@ -0,0 +1,22 @@
+DROP TABLE IF EXISTS forum_moderation_log;
+DROP TABLE IF EXISTS forum_bans;
+DROP TABLE IF EXISTS forum_warnings;
+
+ALTER TABLE forum_topics
+    DROP COLUMN locked_by,
+    DROP COLUMN locked_at,
+    DROP COLUMN is_locked;
+
+DROP TABLE IF EXISTS forum_moderators;
+
+DROP INDEX IF EXISTS idx_forum_reports_status;
+DROP INDEX IF EXISTS idx_forum_reports_pending;
+
+ALTER TABLE forum_reports
+    DROP CONSTRAINT IF EXISTS forum_reports_status_check,
+    DROP COLUMN resolution,
+    DROP COLUMN resolved_at,
+    DROP COLUMN resolved_by,
+    ALTER COLUMN status SET DEFAULT 'в ожидании';
+
+UPDATE forum_reports SET status = 'в ожидании' WHERE status = 'pending';
@@ -0,0 +1,76 @@
+-- Модерация форума: жалобы с очередью на каждый форум, модераторы
+-- форумов, закрытие тем, предупреждения, временные баны и журнал действий
+
+-- Статусы жалоб латиницей, как в остальных таблицах
+UPDATE forum_reports SET status = 'pending' WHERE status = 'в ожидании';
+ALTER TABLE forum_reports
+    ALTER COLUMN status SET DEFAULT 'pending',
+    ADD COLUMN resolved_by INT REFERENCES users(id) ON DELETE SET NULL,  -- Модератор, разобравший жалобу
+    ADD COLUMN resolved_at TIMESTAMP,                                    -- Когда разобрана
+    ADD COLUMN resolution VARCHAR(20),                                   -- Что сделано: dismiss, hide_post, lock_topic, warn_user, ban_user
+    ADD CHECK (status IN ('pending', 'resolved', 'dismissed'));
+
+-- Один пользователь - одна неразобранная жалоба на сообщение
+CREATE UNIQUE INDEX idx_forum_reports_pending ON forum_reports (post_id, user_id) WHERE status = 'pending';
+CREATE INDEX idx_forum_reports_status ON forum_reports (status, created_at);
+
+-- Роли на форуме. owner управляет всеми ролями, lead назначает модераторов
+-- своего форума, moderator модерирует.
+CREATE TABLE forum_moderators (
+    forum_id   INT NOT NULL REFERENCES forums(id) ON DELETE CASCADE,
+    user_id    INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role       VARCHAR(20) NOT NULL DEFAULT 'moderator' CHECK (role IN ('moderator', 'lead', 'owner')),
+    granted_by INT REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (forum_id, user_id)
+);
+
+ALTER TABLE forum_topics
+    ADD COLUMN is_locked BOOLEAN NOT NULL DEFAULT FALSE,   -- В закрытой теме нельзя писать и править сообщения
+    ADD COLUMN locked_at TIMESTAMP,
+    ADD COLUMN locked_by INT REFERENCES users(id) ON DELETE SET NULL;
+
+CREATE TABLE forum_warnings (
+    id           SERIAL PRIMARY KEY,
+    forum_id     INT NOT NULL REFERENCES forums(id) ON DELETE CASCADE,
+    user_id      INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    moderator_id INT REFERENCES users(id) ON DELETE SET NULL,
+    report_id    INT REFERENCES forum_reports(id) ON DELETE SET NULL,
+    reason       TEXT NOT NULL,
+    created_at   TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_forum_warnings_user ON forum_warnings (forum_id, user_id);
+
+-- Временный бан на форуме. Бан действует, пока не истек и не снят.
+CREATE TABLE forum_bans (
+    id           SERIAL PRIMARY KEY,
+    forum_id     INT NOT NULL REFERENCES forums(id) ON DELETE CASCADE,
+    user_id      INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    moderator_id INT REFERENCES users(id) ON DELETE SET NULL,
+    report_id    INT REFERENCES forum_reports(id) ON DELETE SET NULL,
+    reason       TEXT NOT NULL,
+    created_at   TIMESTAMP NOT NULL DEFAULT NOW(),
+    expires_at   TIMESTAMP NOT NULL,
+    lifted_at    TIMESTAMP,
+    lifted_by    INT REFERENCES users(id) ON DELETE SET NULL,
+    CHECK (expires_at > created_at)
+);
+
+CREATE INDEX idx_forum_bans_user ON forum_bans (forum_id, user_id, expires_at);
+
+-- Журнал действий модераторов: кто, что и над чем сделал
+CREATE TABLE forum_moderation_log (
+    id             SERIAL PRIMARY KEY,
+    forum_id       INT NOT NULL REFERENCES forums(id) ON DELETE CASCADE,
+    moderator_id   INT REFERENCES users(id) ON DELETE SET NULL,
+    action         VARCHAR(20) NOT NULL,
+    report_id      INT REFERENCES forum_reports(id) ON DELETE SET NULL,
+    topic_id       INT REFERENCES forum_topics(id) ON DELETE SET NULL,
+    post_id        INT REFERENCES forum_posts(id) ON DELETE SET NULL,
+    target_user_id INT REFERENCES users(id) ON DELETE SET NULL,
+    details        TEXT,
+    created_at     TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_forum_moderation_log_forum ON forum_moderation_log (forum_id, created_at DESC);
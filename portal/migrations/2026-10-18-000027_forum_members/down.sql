@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS forum_join_requests;
+DROP TABLE IF EXISTS forum_invites;
+DROP TABLE IF EXISTS forum_members;
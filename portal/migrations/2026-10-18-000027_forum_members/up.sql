@@ -0,0 +1,45 @@
+-- Закрытые форумы: участники, приглашения по ссылке и заявки
+-- на вступление. Темы и сообщения закрытого форума видят только его
+-- участники, модераторы форума и администраторы.
+
+-- Только членство: роли на форуме хранятся в forum_moderators
+CREATE TABLE forum_members (
+    forum_id   INT NOT NULL REFERENCES forums(id) ON DELETE CASCADE,
+    user_id    INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    added_by   INT REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (forum_id, user_id)
+);
+
+CREATE INDEX idx_forum_members_user ON forum_members (user_id);
+
+-- Приглашение по ссылке. Без expires_at и max_uses действует, пока не отозвано.
+CREATE TABLE forum_invites (
+    id         SERIAL PRIMARY KEY,
+    forum_id   INT NOT NULL REFERENCES forums(id) ON DELETE CASCADE,
+    token      VARCHAR(64) NOT NULL UNIQUE,
+    created_by INT REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMP,
+    max_uses   INT CHECK (max_uses > 0),
+    uses       INT NOT NULL DEFAULT 0,
+    revoked_at TIMESTAMP,
+    CHECK (max_uses IS NULL OR uses <= max_uses)
+);
+
+CREATE INDEX idx_forum_invites_forum ON forum_invites (forum_id);
+
+CREATE TABLE forum_join_requests (
+    id         SERIAL PRIMARY KEY,
+    forum_id   INT NOT NULL REFERENCES forums(id) ON DELETE CASCADE,
+    user_id    INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    message    TEXT,
+    status     VARCHAR(20) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'approved', 'rejected')),
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    decided_by INT REFERENCES users(id) ON DELETE SET NULL,
+    decided_at TIMESTAMP
+);
+
+-- Одна неразобранная заявка пользователя на форум
+CREATE UNIQUE INDEX idx_forum_join_requests_pending ON forum_join_requests (forum_id, user_id) WHERE status = 'pending';
+CREATE INDEX idx_forum_join_requests_status ON forum_join_requests (forum_id, status, created_at);
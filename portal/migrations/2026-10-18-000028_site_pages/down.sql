@@ -0,0 +1,12 @@
+DROP TABLE IF EXISTS cookie_stats;
+DROP TABLE IF EXISTS cookie_users;
+DROP TABLE IF EXISTS feedbacks;
+DROP TABLE IF EXISTS serve_items;
+DROP TABLE IF EXISTS serve;
+DROP TABLE IF EXISTS serve_categories;
+DROP TABLE IF EXISTS tech_categories;
+DROP TABLE IF EXISTS item_files;
+DROP TABLE IF EXISTS category_items;
+DROP TABLE IF EXISTS items;
+DROP TABLE IF EXISTS categories;
+DROP TABLE IF EXISTS stat_pages;
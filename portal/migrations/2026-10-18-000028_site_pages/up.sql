@@ -0,0 +1,123 @@
+-- Таблицы шаблонных страниц сайта (views/pages.rs и views/for_kostia.rs):
+-- счетчики страниц, категории и объекты (блоги, услуги, товары, статьи, работы),
+-- каталог услуг, отзывы и история просмотров посетителей
+
+-- Счетчики страниц: одна строка на тип страницы
+CREATE TABLE stat_pages (
+    id      SERIAL PRIMARY KEY,
+    types   SMALLINT NOT NULL UNIQUE,       -- Тип страницы: 1 главная, 10 информация
+    view    INT NOT NULL DEFAULT 0,         -- Просмотров всего
+    height  FLOAT8 NOT NULL DEFAULT 0,      -- Средняя глубина прокрутки
+    seconds INT NOT NULL DEFAULT 0,         -- Среднее время на странице
+    now_u   INT NOT NULL DEFAULT 0          -- Посетителей на странице сейчас
+);
+
+-- Категории объектов сайта
+CREATE TABLE categories (
+    id          SERIAL PRIMARY KEY,
+    name        VARCHAR(100) NOT NULL,
+    description TEXT,
+    image       VARCHAR(500),
+    types       SMALLINT NOT NULL,          -- 1 блог, 2 услуга, 3 товар, 4 статья, 5 работа, 6 помощь
+    position    SMALLINT NOT NULL DEFAULT 0
+);
+
+CREATE INDEX idx_categories_types ON categories (types, position);
+
+-- Объекты сайта: блоги, услуги, товары, статьи и работы
+CREATE TABLE items (
+    id          SERIAL PRIMARY KEY,
+    user_id     INT NOT NULL REFERENCES users(id) ON DELETE CASCADE, -- Автор
+    title       VARCHAR(100) NOT NULL,
+    description TEXT,
+    content     TEXT,                                                -- Текст страницы объекта
+    image       VARCHAR(500),
+    types       SMALLINT NOT NULL,                                   -- Тип, как у categories
+    is_active   BOOLEAN NOT NULL DEFAULT TRUE,                       -- Неопубликованные видят только администраторы
+    created_at  TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_items_types ON items (types, created_at DESC);
+
+CREATE TABLE category_items (
+    category_id INT NOT NULL REFERENCES categories(id) ON DELETE CASCADE,
+    item_id     INT NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+    PRIMARY KEY (category_id, item_id)
+);
+
+-- Галерея объекта: файлы из files в порядке показа
+CREATE TABLE item_files (
+    item_id  INT NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+    file_id  INT NOT NULL REFERENCES files(id) ON DELETE CASCADE,
+    position SMALLINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (item_id, file_id)
+);
+
+CREATE INDEX idx_item_files_file_id ON item_files (file_id);
+
+-- Каталог услуг: технические категории -> категории услуг -> услуги
+CREATE TABLE tech_categories (
+    id          SERIAL PRIMARY KEY,
+    name        VARCHAR(100) NOT NULL,
+    description TEXT,
+    level       SMALLINT NOT NULL DEFAULT 0, -- Уровень сложности
+    position    SMALLINT NOT NULL DEFAULT 0
+);
+
+CREATE TABLE serve_categories (
+    id          SERIAL PRIMARY KEY,
+    name        VARCHAR(100) NOT NULL,
+    description TEXT,
+    tech_cat_id INT NOT NULL REFERENCES tech_categories(id) ON DELETE CASCADE,
+    position    SMALLINT NOT NULL DEFAULT 0
+);
+
+CREATE TABLE serve (
+    id          SERIAL PRIMARY KEY,
+    name        VARCHAR(100) NOT NULL,
+    description TEXT,
+    price       INT NOT NULL DEFAULT 0,
+    man_hours   INT NOT NULL DEFAULT 0,      -- Трудоемкость, часов
+    category_id INT NOT NULL REFERENCES serve_categories(id) ON DELETE CASCADE,
+    tech_cat_id INT NOT NULL REFERENCES tech_categories(id) ON DELETE CASCADE,
+    position    SMALLINT NOT NULL DEFAULT 0
+);
+
+-- Какие услуги входят в объект
+CREATE TABLE serve_items (
+    serve_id INT NOT NULL REFERENCES serve(id) ON DELETE CASCADE,
+    item_id  INT NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+    PRIMARY KEY (serve_id, item_id)
+);
+
+-- Отзывы и вопросы с формы обратной связи
+CREATE TABLE feedbacks (
+    id         SERIAL PRIMARY KEY,
+    username   VARCHAR(100) NOT NULL,
+    email      VARCHAR(100) NOT NULL,
+    message    TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+-- Посетители сайта по cookie, в том числе не вошедшие
+CREATE TABLE cookie_users (
+    id         SERIAL PRIMARY KEY,
+    user_id    INT REFERENCES users(id) ON DELETE SET NULL, -- Пользователь, если вошел
+    ip         VARCHAR(100) NOT NULL,
+    device     SMALLINT NOT NULL,                           -- 1 компьютер, 2 телефон
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+-- История просмотров страниц посетителем
+CREATE TABLE cookie_stats (
+    id         SERIAL PRIMARY KEY,
+    user_id    INT NOT NULL REFERENCES cookie_users(id) ON DELETE CASCADE,
+    page       SMALLINT NOT NULL,                           -- Тип страницы, как у stat_pages
+    link       VARCHAR(200) NOT NULL,
+    title      VARCHAR(200) NOT NULL,
+    height     FLOAT8 NOT NULL DEFAULT 0,
+    seconds    INT NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_cookie_stats_user_id ON cookie_stats (user_id, created_at DESC);
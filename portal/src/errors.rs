@@ -0,0 +1,102 @@
+use actix_web::{
+    HttpResponse,
+    ResponseError,
+    error::BlockingError,
+    http::StatusCode,
+};
+use diesel::{
+    r2d2::PoolError,
+    result::{DatabaseErrorKind, Error as DieselError},
+};
+use serde::Serialize;
+use std::fmt;
+
+
+// Общий тип ошибки приложения. Модели и обработчики возвращают его,
+// а actix сам превращает его в ответ с нужным статусом.
+#[derive(Debug)]
+pub enum Error {
+    NotFound,                // Запись не найдена
+    Forbidden,               // Нет прав на действие
+    Validation(String),      // Неверные данные от пользователя
+    Conflict(String),        // Нарушение уникальности или связей в базе
+    Database(DieselError),   // Любая другая ошибка базы
+    Unavailable,             // Нет свободных соединений с базой
+    Render(sailfish::RenderError), // Ошибка шаблона
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotFound      => write!(f, "Not found"),
+            Error::Forbidden     => write!(f, "Forbidden"),
+            Error::Validation(m) => write!(f, "Validation error: {}", m),
+            Error::Conflict(m)   => write!(f, "Conflict: {}", m),
+            Error::Database(e)   => write!(f, "Database error: {}", e),
+            Error::Unavailable   => write!(f, "Service unavailable"),
+            Error::Render(e)     => write!(f, "Render error: {}", e),
+        }
+    }
+}
+
+impl ResponseError for Error {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Error::NotFound      => StatusCode::NOT_FOUND,
+            Error::Forbidden     => StatusCode::FORBIDDEN,
+            Error::Validation(_) => StatusCode::BAD_REQUEST,
+            Error::Conflict(_)   => StatusCode::CONFLICT,
+            Error::Unavailable   => StatusCode::SERVICE_UNAVAILABLE,
+            Error::Database(_) | Error::Render(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        // подробности ошибок базы и шаблонов пишем в лог, клиенту они не нужны
+        let message = match self {
+            Error::Database(_) | Error::Render(_) => {
+                error!("{}", self);
+                "Internal server error".to_string()
+            },
+            _ => self.to_string(),
+        };
+        HttpResponse::build(self.status_code()).json(ErrorResponse { error: message })
+    }
+}
+
+impl From<DieselError> for Error {
+    fn from(err: DieselError) -> Error {
+        match err {
+            DieselError::NotFound => Error::NotFound,
+            DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, info) |
+            DieselError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, info) => {
+                Error::Conflict(info.message().to_string())
+            },
+            e => Error::Database(e),
+        }
+    }
+}
+
+impl From<PoolError> for Error {
+    fn from(err: PoolError) -> Error {
+        warn!("Нет свободных соединений с базой: {}", err);
+        Error::Unavailable
+    }
+}
+
+impl From<BlockingError> for Error {
+    fn from(_: BlockingError) -> Error {
+        Error::Unavailable
+    }
+}
+
+impl From<sailfish::RenderError> for Error {
+    fn from(err: sailfish::RenderError) -> Error {
+        Error::Render(err)
+    }
+}
@@ -0,0 +1,69 @@
+use actix_web::web;
+use chrono::Duration;
+use std::sync::Arc;
+
+use crate::errors::Error;
+use crate::models::QueuedEmail;
+use crate::notify::{EmailChannel, Notifier};
+use crate::utils::{db, DbPool};
+
+
+// Сколько секунд между проверками очереди писем
+const MAIL_INTERVAL: u64 = 10;
+// Сколько писем берется за одну проверку
+const MAIL_BATCH: i64 = 50;
+// На сколько минут взятое письмо скрывается от других экземпляров
+const MAIL_LEASE_MINUTES: i64 = 5;
+
+// Отправка писем из очереди email_queue. Соединение с базой берется только
+// на выборку и отметку, SMTP работает в пуле потоков без соединения.
+pub fn start_mailer(pool: web::Data<DbPool>, notifier: Arc<Notifier>) {
+    let channel = match notifier.email() {
+        Some(channel) => channel.clone(),
+        None => {
+            info!("SMTP не настроен, очередь писем не отправляется");
+            return;
+        },
+    };
+
+    actix_rt::spawn(async move {
+        let mut timer = actix_rt::time::interval(std::time::Duration::from_secs(MAIL_INTERVAL));
+        loop {
+            timer.tick().await;
+            match send_queued(&pool, &channel).await {
+                Ok(0) => (),
+                Ok(sent) => info!("Отправлено писем: {}", sent),
+                Err(e) => warn!("Ошибка при отправке очереди писем: {}", e),
+            }
+        }
+    });
+}
+
+// Одна проверка очереди. Возвращает число отправленных писем.
+async fn send_queued(pool: &web::Data<DbPool>, channel: &EmailChannel) -> Result<usize, Error> {
+    let now = chrono::Local::now().naive_local();
+    let emails = db(pool, move |_connection| {
+        QueuedEmail::lease_due(_connection, now, Duration::minutes(MAIL_LEASE_MINUTES), MAIL_BATCH)
+    }).await?;
+
+    let mut sent = 0;
+    for email in emails {
+        let smtp = channel.clone();
+        let (email, result) = web::block(move || {
+            let result = smtp.deliver(&email);
+            (email, result)
+        }).await?;
+        let now = chrono::Local::now().naive_local();
+        match result {
+            Ok(()) => {
+                sent += 1;
+                db(pool, move |_connection| QueuedEmail::mark_sent(_connection, email.id, now)).await?;
+            },
+            Err(e) => {
+                warn!("Не удалось отправить письмо {} ({}): {}", email.id, email.email, e);
+                db(pool, move |_connection| QueuedEmail::mark_failed(_connection, &email, &e, now)).await?;
+            },
+        }
+    }
+    Ok(sent)
+}
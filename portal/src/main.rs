@@ -1,3 +1,117 @@
-fn main() {
-    println!("Salamalecum brother");
+// derive из diesel 1.4 разворачиваются в impl внутри функций, а поля
+// структур по стилю проекта всегда задаются явно (user_id: user_id)
+#![allow(non_local_definitions, clippy::redundant_field_names)]
+
+#[macro_use]
+extern crate diesel;
+#[macro_use]
+extern crate diesel_migrations;
+#[macro_use]
+extern crate log;
+
+pub mod schema;
+pub mod models;
+pub mod routes;
+pub mod errors;
+mod views;
+mod utils;
+mod websocket;
+mod mailer;
+mod notify;
+mod reminders;
+
+use actix::Actor;
+use actix_web::{
+    App,
+    HttpServer,
+    web,
+    middleware::Logger,
+    cookie::Key,
+};
+use actix_session::{
+    SessionMiddleware,
+    storage::CookieSessionStore,
+};
+use actix_files::Files;
+use std::sync::{
+    Arc,
+    Mutex,
+    atomic::{AtomicUsize, Ordering},
+};
+use std::cell::Cell;
+
+use crate::routes::routes;
+use crate::utils::{AppState, Config, create_pool};
+use crate::websocket::Server;
+use crate::mailer::start_mailer;
+use crate::notify::Notifier;
+use crate::reminders::start_reminders;
+
+
+static SERVER_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+// Миграции из папки migrations вшиваются в бинарник,
+// чтобы обновлять базу на сервере командой `meta migrate`.
+embed_migrations!();
+
+#[actix_web::main]
+async fn main() -> std::io::Result<()> {
+    let config = Config::from_env()
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
+
+    let pool = create_pool(&config)?;
+    if std::env::args().nth(1).as_deref() == Some("migrate") {
+        let connection = pool
+            .get()
+            .map_err(std::io::Error::other)?;
+        embedded_migrations::run_with_output(&connection, &mut std::io::stdout())
+            .map_err(std::io::Error::other)?;
+        return Ok(());
+    }
+
+    let session_key = config
+        .session_key
+        .as_deref()
+        .map(|key| Key::from(key.as_bytes()))
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "SESSION_KEY must be set"))?;
+    let media_root = config.media_root.clone();
+    let messages = Arc::new(Mutex::new(vec![]));
+    let websocket_srv = Server::new().start();
+    let notifier = Notifier::from_config(&config, websocket_srv.clone());
+    let pool = web::Data::new(pool);
+    let app_config = web::Data::new(config.clone());
+    start_reminders(pool.clone(), notifier.clone(), &config);
+    start_mailer(pool.clone(), notifier.clone());
+
+    info!("Запуск сервера на {}", &config.bind_address);
+    HttpServer::new(move || {
+        // AppState создается на каждый воркер, поэтому счетчик запросов
+        // у каждого воркера свой, а список сообщений общий.
+        let state = AppState {
+            server_id:     SERVER_COUNTER.fetch_add(1, Ordering::SeqCst),
+            request_count: Cell::new(0),
+            messages:      messages.clone(),
+        };
+        App::new()
+            .app_data(web::Data::new(state))
+            .app_data(pool.clone())
+            .app_data(app_config.clone())
+            .app_data(web::Data::new(notifier.clone()))
+            .app_data(web::Data::new(websocket_srv.clone()))
+            .wrap(Logger::default())
+            .wrap(SessionMiddleware::new(
+                CookieSessionStore::default(),
+                session_key.clone(),
+            ))
+            .service(Files::new("/media", media_root.clone()))
+            .service(Files::new("/static", "static"))
+            .configure(routes)
+    })
+    // HttpServer сам перехватывает SIGINT/SIGTERM: перестает принимать
+    // соединения и ждет завершения текущих запросов не дольше shutdown_timeout.
+    .shutdown_timeout(config.shutdown_timeout)
+    .bind(&config.bind_address)?
+    .run()
+    .await
 }
@@ -1,10 +1,13 @@
-use diesel::{Insertable, Queryable, QueryResult, RunQueryDsl, PgConnection};
+use diesel::{ExpressionMethods, Insertable, OptionalExtension, PgConnection, QueryDsl, Queryable, RunQueryDsl, PgTextExpressionMethods};
 use chrono::NaiveDateTime;
 use bigdecimal::BigDecimal;
-use rand::Rng;
+use serde::Serialize;
+use crate::errors::Error;
+use crate::models::geo::GeoPoint;
+use crate::schema::{advertisements, districts, organizations, streets};
 
 // Структура для модели Объявления
-#[derive(Debug, Queryable)]
+#[derive(Debug, Queryable, Serialize)]
 pub struct Advertisement {
     pub id: i32,                         // Идентификатор объявления
     pub user_id: i32,                    // Идентификатор пользователя, разместившего объявление
@@ -22,6 +25,10 @@ pub struct Advertisement {
     pub video: Option<String>,           // Ссылка на видео объявления, если есть
     pub is_active: bool,                // Флаг активности объявления
     pub created_at: NaiveDateTime,      // Дата и время создания
+    #[serde(with = "crate::models::geo::serde_point_opt")]
+    pub coordinates: Option<GeoPoint>,  // Координаты на карте, если указаны
+    pub organization_id: Option<i32>,   // Организация, от имени которой размещено объявление
+    pub city_id: Option<i32>,           // Город, если координаты не указаны
 }
 
 // Структура для создания новых объявлений
@@ -43,125 +50,155 @@ pub struct NewAdvertisement {
     pub video: Option<String>,
     pub is_active: bool,
     pub created_at: NaiveDateTime,
+    pub coordinates: Option<GeoPoint>,
+    pub organization_id: Option<i32>,
+    pub city_id: Option<i32>,
 }
 
 impl Advertisement {
-    /// Создает новое объявление и сохраняет его в базу данных.
-    pub fn create(new_advertisement: NewAdvertisement, connection: &PgConnection) -> QueryResult<Advertisement> {
-        use crate::schema::advertisements::dsl::*;
-        diesel::insert_into(advertisements)
+    // Создает новое объявление и сохраняет его в базу данных.
+    pub fn create(new_advertisement: NewAdvertisement, connection: &PgConnection) -> Result<Advertisement, Error> {
+        diesel::insert_into(advertisements::table)
             .values(&new_advertisement)
             .get_result(connection)
+            .map_err(Error::from)
     }
 
-    /// Возвращает список всех объявлений пользователя.
-    pub fn find_by_user(user_id: i32, connection: &PgConnection) -> QueryResult<Vec<Advertisement>> {
-        use crate::schema::advertisements::dsl::*;
-        advertisements.filter(user_id.eq(user_id))
+    // Возвращает список всех объявлений пользователя.
+    pub fn find_by_user(user_id: i32, connection: &PgConnection) -> Result<Vec<Advertisement>, Error> {
+        advertisements::table.filter(advertisements::user_id.eq(user_id))
             .load::<Advertisement>(connection)
+            .map_err(Error::from)
     }
 
-    /// Возвращает список всех активных объявлений.
-    pub fn find_active(connection: &PgConnection) -> QueryResult<Vec<Advertisement>> {
-        use crate::schema::advertisements::dsl::*;
-        advertisements.filter(is_active.eq(true))
+    // Возвращает список всех активных объявлений.
+    pub fn find_active(connection: &PgConnection) -> Result<Vec<Advertisement>, Error> {
+        advertisements::table.filter(advertisements::is_active.eq(true))
             .load::<Advertisement>(connection)
+            .map_err(Error::from)
     }
 
-    /// Метод для поиска объявления по его идентификатору
-    pub fn find_by_id(id: i32, connection: &PgConnection) -> QueryResult<Option<Advertisement>> {
-        use crate::schema::advertisements::dsl::*;
-        advertisements.filter(id.eq(id))
+    // Метод для поиска объявления по его идентификатору
+    pub fn find_by_id(id: i32, connection: &PgConnection) -> Result<Option<Advertisement>, Error> {
+        advertisements::table.filter(advertisements::id.eq(id))
             .first(connection)
             .optional()
+            .map_err(Error::from)
     }
-    /// Метод для получения всех объявлений в указанной категории
-    pub fn find_by_category(category: &str, connection: &PgConnection) -> QueryResult<Vec<Advertisement>> {
-        use crate::schema::advertisements::dsl::*;
-        advertisements.filter(category.eq(category))
+    // Метод для получения всех объявлений в указанной категории
+    pub fn find_by_category(category: &str, connection: &PgConnection) -> Result<Vec<Advertisement>, Error> {
+        advertisements::table.filter(advertisements::category.eq(category))
             .load::<Advertisement>(connection)
+            .map_err(Error::from)
     }
 
-    /// Метод для установки статуса активности объявления
-    pub fn set_active_status(id: i32, is_active: bool, connection: &PgConnection) -> QueryResult<()> {
-        use crate::schema::advertisements::dsl::*;
-        diesel::update(advertisements.find(id))
-            .set(is_active.eq(is_active))
+    // Метод для установки статуса активности объявления
+    pub fn set_active_status(id: i32, is_active: bool, connection: &PgConnection) -> Result<(), Error> {
+        diesel::update(advertisements::table.find(id))
+            .set(advertisements::is_active.eq(is_active))
             .execute(connection)?;
         Ok(())
     }
 
-    /// Метод для получения всех объявлений в сообществе
-    pub fn find_by_community(community_id: i32, connection: &PgConnection) -> QueryResult<Vec<Advertisement>> {
-        use crate::schema::advertisements::dsl::*;
-        advertisements.filter(community_id.eq(community_id))
+    // Метод для получения всех объявлений в сообществе
+    pub fn find_by_community(community_id: i32, connection: &PgConnection) -> Result<Vec<Advertisement>, Error> {
+        advertisements::table.filter(advertisements::community_id.eq(community_id))
             .load::<Advertisement>(connection)
+            .map_err(Error::from)
     }
 
-    /// Метод для поиска объявлений по ключевому слову в заголовке или описании
-    pub fn search(keyword: &str, connection: &PgConnection) -> QueryResult<Vec<Advertisement>> {
-        use crate::schema::advertisements::dsl::*;
-        advertisements.filter(title.ilike(format!("%{}%", keyword)))
-            .or_filter(description.ilike(format!("%{}%", keyword)))
+    // Метод для поиска объявлений по ключевому слову в заголовке или описании
+    pub fn search(keyword: &str, connection: &PgConnection) -> Result<Vec<Advertisement>, Error> {
+        advertisements::table.filter(advertisements::title.ilike(format!("%{}%", keyword)))
+            .or_filter(advertisements::description.ilike(format!("%{}%", keyword)))
             .load::<Advertisement>(connection)
+            .map_err(Error::from)
     }
 
-    /// Метод для получения самых новых объявлений
-    pub fn find_newest(limit: i64, connection: &PgConnection) -> QueryResult<Vec<Advertisement>> {
-        use crate::schema::advertisements::dsl::*;
-        advertisements.order(created_at.desc())
+    // Метод для получения самых новых объявлений
+    pub fn find_newest(limit: i64, connection: &PgConnection) -> Result<Vec<Advertisement>, Error> {
+        advertisements::table.order(advertisements::created_at.desc())
             .limit(limit)
             .load::<Advertisement>(connection)
+            .map_err(Error::from)
     }
 
-    /// Метод для получения количества объявлений в указанной категории
-    pub fn count_by_category(category: &str, connection: &PgConnection) -> QueryResult<i64> {
-        use crate::schema::advertisements::dsl::*;
-        advertisements.filter(category.eq(category))
+    // Метод для получения количества объявлений в указанной категории
+    pub fn count_by_category(category: &str, connection: &PgConnection) -> Result<i64, Error> {
+        advertisements::table.filter(advertisements::category.eq(category))
             .count()
             .get_result(connection)
+            .map_err(Error::from)
     }
 
-    /// Метод для получения всех активных объявлений пользователя
-    pub fn find_active_by_user(user_id: i32, connection: &PgConnection) -> QueryResult<Vec<Advertisement>> {
-        use crate::schema::advertisements::dsl::*;
-        advertisements.filter(user_id.eq(user_id))
-            .filter(is_active.eq(true))
+    // Метод для получения всех активных объявлений пользователя
+    pub fn find_active_by_user(user_id: i32, connection: &PgConnection) -> Result<Vec<Advertisement>, Error> {
+        advertisements::table.filter(advertisements::user_id.eq(user_id))
+            .filter(advertisements::is_active.eq(true))
             .load::<Advertisement>(connection)
+            .map_err(Error::from)
     }
 
-    /// Метод для сортировки объявлений по цене (по возрастанию).
-    pub fn sort_by_price_asc(connection: &PgConnection) -> QueryResult<Vec<Advertisement>> {
-        use crate::schema::advertisements::dsl::*;
-        advertisements.order(price.asc())
+    // Метод для сортировки объявлений по цене (по возрастанию).
+    pub fn sort_by_price_asc(connection: &PgConnection) -> Result<Vec<Advertisement>, Error> {
+        advertisements::table.order(advertisements::price.asc())
             .load::<Advertisement>(connection)
+            .map_err(Error::from)
     }
 
-    /// Метод для сортировки объявлений по цене (по убыванию).
-    pub fn sort_by_price_desc(connection: &PgConnection) -> QueryResult<Vec<Advertisement>> {
-        use crate::schema::advertisements::dsl::*;
-        advertisements.order(price.desc())
+    // Метод для сортировки объявлений по цене (по убыванию).
+    pub fn sort_by_price_desc(connection: &PgConnection) -> Result<Vec<Advertisement>, Error> {
+        advertisements::table.order(advertisements::price.desc())
             .load::<Advertisement>(connection)
+            .map_err(Error::from)
     }
 
-    /// Метод для сортировки объявлений по дате создания (по возрастанию).
-    pub fn sort_by_created_at_asc(connection: &PgConnection) -> QueryResult<Vec<Advertisement>> {
-        use crate::schema::advertisements::dsl::*;
-        advertisements.order(created_at.asc())
+    // Метод для сортировки объявлений по дате создания (по возрастанию).
+    pub fn sort_by_created_at_asc(connection: &PgConnection) -> Result<Vec<Advertisement>, Error> {
+        advertisements::table.order(advertisements::created_at.asc())
             .load::<Advertisement>(connection)
+            .map_err(Error::from)
     }
 
-    /// Метод для сортировки объявлений по дате создания (по убыванию).
-    pub fn sort_by_created_at_desc(connection: &PgConnection) -> QueryResult<Vec<Advertisement>> {
-        use crate::schema::advertisements::dsl::*;
-        advertisements.order(created_at.desc())
+    // Метод для сортировки объявлений по дате создания (по убыванию).
+    pub fn sort_by_created_at_desc(connection: &PgConnection) -> Result<Vec<Advertisement>, Error> {
+        advertisements::table.order(advertisements::created_at.desc())
             .load::<Advertisement>(connection)
+            .map_err(Error::from)
+    }
+
+    // Создает объявление от имени организации. Размещать его может только
+    // подтвержденный владелец организации.
+    // Город объявления - город организации (улица -> район -> город).
+    pub fn create_for_organization(mut new_advertisement: NewAdvertisement, connection: &PgConnection) -> Result<Advertisement, Error> {
+        let organization_id = new_advertisement
+            .organization_id
+            .ok_or_else(|| Error::Validation("organization_id is required".to_string()))?;
+        let (owner_id, city_id) = organizations::table
+            .inner_join(streets::table.inner_join(districts::table))
+            .filter(organizations::id.eq(organization_id))
+            .select((organizations::owner_id, districts::city_id))
+            .first::<(Option<i32>, i32)>(connection)?;
+        if owner_id != Some(new_advertisement.user_id) {
+            return Err(Error::Forbidden);
+        }
+        new_advertisement.city_id = Some(city_id);
+        Advertisement::create(new_advertisement, connection)
+    }
+
+    // Возвращает активные объявления организации, новые первыми.
+    pub fn find_by_organization(organization_id: i32, connection: &PgConnection) -> Result<Vec<Advertisement>, Error> {
+        advertisements::table
+            .filter(advertisements::organization_id.eq(organization_id))
+            .filter(advertisements::is_active.eq(true))
+            .order(advertisements::created_at.desc())
+            .load::<Advertisement>(connection)
+            .map_err(Error::from)
     }
 
      // Метод для удаления объявления по его идентификатору.
-     pub fn delete_by_id(id: i32, connection: &PgConnection) -> QueryResult<()> {
-        use crate::schema::advertisements::dsl::*;
-        diesel::delete(advertisements.filter(id.eq(id)))
+     pub fn delete_by_id(id: i32, connection: &PgConnection) -> Result<(), Error> {
+        diesel::delete(advertisements::table.filter(advertisements::id.eq(id)))
             .execute(connection)?;
         Ok(())
     }
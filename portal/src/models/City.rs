@@ -1,12 +1,24 @@
 // models.rs
 
-use diesel::sql_types::*;
+use diesel::prelude::*;
 use diesel::{Insertable, Queryable};
 use serde::{Deserialize, Serialize};
 use bigdecimal::BigDecimal;
-use chrono::NaiveDateTime;
-use postgis::ewkb::Point;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, Utc};
+use crate::models::geo::{GeoMultiLine, GeoMultiPolygon, GeoPoint};
+use crate::models::opening_hours::OpeningHours;
+use crate::models::reviews::{Review, ReviewDetail, ReviewObject};
+use crate::schema::{
+    cities,
+    districts,
+    streets,
+    organizations,
+    goods,
+    user_points,
+    wifi_charging,
+};
 use std::vec::Vec;
+use crate::errors::Error;
 
 // Структура представляющая город------------------------------------------------------------------------------
 #[derive(Debug, Clone, Serialize, Deserialize, Queryable)]
@@ -17,6 +29,7 @@ pub struct City {
     pub area_sq_km: Option<BigDecimal>, // Площадь в квадратных километрах (Option, так как может быть неизвестной)
     pub mayor: Option<String>,      // Имя мэра (Option, так как может быть неизвестным)
     pub founding_date: Option<NaiveDate>, // Дата основания (Option, так как может быть неизвестной)
+    pub utc_offset: i32,            // Смещение местного времени от UTC в минутах
 }
 
 // Структура для вставки новых записей о городах в базу данных
@@ -31,39 +44,70 @@ pub struct NewCity {
 }
 
 impl City {
+    // Метод для чтения города по идентификатору
+    pub fn read_by_id(city_id: i32, connection: &PgConnection) -> Result<City, Error> {
+        cities::table.find(city_id).first(connection)
+            .map_err(Error::from)
+    }
+
     // Метод для получения всех районов города
-     pub fn get_districts(&self, connection: &PgConnection) -> QueryResult<Vec<District>> {
+     pub fn get_districts(&self, connection: &PgConnection) -> Result<Vec<District>, Error> {
         districts::table
             .filter(districts::city_id.eq(self.id))
             .load(connection)
+            .map_err(Error::from)
     }
     // Метод для получения всех улиц данного города
-    pub fn get_streets(&self, connection: &PgConnection) -> QueryResult<Vec<Street>> {
+    pub fn get_streets(&self, connection: &PgConnection) -> Result<Vec<Street>, Error> {
         streets::table.filter(streets::district_id.eq_any(
             districts::table.select(districts::id).filter(districts::city_id.eq(self.id)),
         ))
         .load(connection)
+        .map_err(Error::from)
     }
-    // Метод для получения всех организаций города
-    pub fn get_organizations(&self, connection: &PgConnection) -> QueryResult<Vec<Organization>> {
-        Organization::belonging_to(self)
-            .load(connection)
+    // Местное время города в момент at
+    pub fn local_time(&self, at: DateTime<Utc>) -> NaiveDateTime {
+        match FixedOffset::east_opt(self.utc_offset * 60) {
+            Some(offset) => at.with_timezone(&offset).naive_local(),
+            None => at.naive_utc(),
+        }
+    }
+
+    // Метод для получения всех организаций города.
+    // open_at - только организации, открытые в этот момент (например, сейчас).
+    // Часы работы сравниваются с местным временем города.
+    pub fn get_organizations(&self, connection: &PgConnection, open_at: Option<DateTime<Utc>>) -> Result<Vec<Organization>, Error> {
+        let mut query = organizations::table
+            .filter(organizations::street_id.eq_any(
+                streets::table.select(streets::id).filter(streets::district_id.eq_any(
+                    districts::table.select(districts::id).filter(districts::city_id.eq(self.id)),
+                )),
+            ))
+            .into_boxed();
+        if open_at.is_some() {
+            query = query.filter(organizations::opening_hours.is_not_null());
+        }
+        let organizations = query.load::<Organization>(connection)?;
+        Ok(Organization::filter_open_at(organizations, open_at.map(|at| self.local_time(at))))
     }
 
 }
 
 // Структура представляющая районы---------------------------------------------------------------------------------------------
-#[derive(Debug, Clone, Serialize, Deserialize, Queryable)]
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Identifiable)]
+#[table_name = "districts"]
 pub struct District {
     pub id: i32,                    // Идентификатор района
     pub city_id: i32,               // Идентификатор города
     pub name: String,               // Название района
     pub population: i32,            // Численность населения в районе
     pub area_sq_km: Option<BigDecimal>, // Площадь района в квадратных километрах (Option, так как может быть неизвестной)
+    #[serde(skip)]
+    pub boundary: Option<GeoMultiPolygon>, // Граница района (Option, так как может быть неизвестной), в API отдается через GeoJSON
 }
 
 // Структура для вставки новых записей о районах в базу данных
-#[derive(Debug, Clone, Insertable)]
+#[derive(Debug, Clone, Insertable, AsChangeset)]
 #[table_name = "districts"]
 pub struct NewDistrict {
     pub city_id: i32,               // Идентификатор города
@@ -74,10 +118,11 @@ pub struct NewDistrict {
 // Методы для структуры NewDistrict
 impl NewDistrict {
     // Метод для создания новой записи о районе
-    pub fn create(new_district: NewDistrict, connection: &PgConnection) -> QueryResult<District> {
+    pub fn create(new_district: NewDistrict, connection: &PgConnection) -> Result<District, Error> {
         diesel::insert_into(districts::table)
             .values(&new_district)
             .get_result(connection)
+            .map_err(Error::from)
     }
     
     // Добавьте здесь другие методы, если они вам нужны
@@ -86,80 +131,97 @@ impl NewDistrict {
 impl District {
     
     // Метод для создания нового района
-    pub fn create(new_district: NewDistrict, connection: &PgConnection) -> QueryResult<District> {
+    pub fn create(new_district: NewDistrict, connection: &PgConnection) -> Result<District, Error> {
         diesel::insert_into(districts::table)
             .values(&new_district)
             .get_result(connection)
+            .map_err(Error::from)
     }
 
     // Метод для чтения района по идентификатору
-    pub fn read_by_id(district_id: i32, connection: &PgConnection) -> QueryResult<District> {
+    pub fn read_by_id(district_id: i32, connection: &PgConnection) -> Result<District, Error> {
         districts::table.find(district_id).first(connection)
+            .map_err(Error::from)
     }
 
     // Метод для обновления данных о районе
-    pub fn update(&self, connection: &PgConnection) -> QueryResult<District> {
+    pub fn update(&self, changes: &NewDistrict, connection: &PgConnection) -> Result<District, Error> {
         diesel::update(self)
-            .set(self)
+            .set(changes)
             .get_result(connection)
+            .map_err(Error::from)
     }
 
     // Метод для удаления района по идентификатору
-    pub fn delete(district_id: i32, connection: &PgConnection) -> QueryResult<()> {
+    pub fn delete(district_id: i32, connection: &PgConnection) -> Result<(), Error> {
         diesel::delete(districts::table.filter(districts::id.eq(district_id))).execute(connection)?;
         Ok(())
     }
 
     // Метод для получения города, к которому принадлежит район
-    pub fn get_city(&self, connection: &PgConnection) -> QueryResult<City> {
+    pub fn get_city(&self, connection: &PgConnection) -> Result<City, Error> {
         cities::table
             .filter(cities::id.eq(self.city_id))
             .first(connection)
+            .map_err(Error::from)
     }
 
-// Методы для структуры District
-impl District {
     // Метод для получения всех улиц района
-    pub fn get_streets(&self, connection: &PgConnection) -> QueryResult<Vec<Street>> {
+    pub fn get_streets(&self, connection: &PgConnection) -> Result<Vec<Street>, Error> {
         streets::table
             .filter(streets::district_id.eq(self.id))
             .load(connection)
-    }
-
-    // Метод для получения всех организаций района
-    pub fn get_organizations(&self, connection: &PgConnection) -> QueryResult<Vec<Organization>> {
-        organizations::table
+            .map_err(Error::from)
+    }
+
+    // Метод для получения всех организаций района.
+    // open_at - только организации, открытые в этот момент (например, сейчас).
+    // Часы работы сравниваются с местным временем города района.
+    pub fn get_organizations(&self, connection: &PgConnection, open_at: Option<DateTime<Utc>>) -> Result<Vec<Organization>, Error> {
+        let open_at = match open_at {
+            Some(at) => Some(self.get_city(connection)?.local_time(at)),
+            None => None,
+        };
+        let mut query = organizations::table
             .filter(organizations::street_id.eq_any(
                 streets::table.select(streets::id).filter(streets::district_id.eq(self.id)),
             ))
-            .load(connection)
+            .into_boxed();
+        if open_at.is_some() {
+            query = query.filter(organizations::opening_hours.is_not_null());
+        }
+        let organizations = query.load::<Organization>(connection)?;
+        Ok(Organization::filter_open_at(organizations, open_at))
     }
 
     // Метод для получения всех товаров и услуг района
-    pub fn get_goods_services(&self, connection: &PgConnection) -> QueryResult<Vec<GoodsService>> {
-        goods_services::table
-            .filter(goods_services::street_id.eq_any(
+    pub fn get_goods_services(&self, connection: &PgConnection) -> Result<Vec<Goods>, Error> {
+        goods::table
+            .filter(goods::street_id.eq_any(
                 streets::table.select(streets::id).filter(streets::district_id.eq(self.id)),
             ))
             .load(connection)
+            .map_err(Error::from)
     }
 
     // Метод для получения всех бесплатных Wi-Fi точек и зарядок района
-    pub fn get_wifi_charging(&self, connection: &PgConnection) -> QueryResult<Vec<WifiCharging>> {
+    pub fn get_wifi_charging(&self, connection: &PgConnection) -> Result<Vec<WifiChargingPoint>, Error> {
         wifi_charging::table
             .filter(wifi_charging::street_id.eq_any(
                 streets::table.select(streets::id).filter(streets::district_id.eq(self.id)),
             ))
             .load(connection)
+            .map_err(Error::from)
     }
 
     // Метод для получения всех пользовательских отметок района
-    pub fn get_user_points(&self, connection: &PgConnection) -> QueryResult<Vec<UserPoint>> {
+    pub fn get_user_points(&self, connection: &PgConnection) -> Result<Vec<UserPoint>, Error> {
         user_points::table
             .filter(user_points::street_id.eq_any(
                 streets::table.select(streets::id).filter(streets::district_id.eq(self.id)),
             ))
             .load(connection)
+            .map_err(Error::from)
     }
 
     // Метод для вывода информации о районе
@@ -171,10 +233,6 @@ impl District {
     // Добавьте здесь другие методы, если они вам нужны
 }
 
-
-
-}
-
 // Структура представляющая улицы------------------------------------------------------------------------------------------
 #[derive(Debug, Clone, Serialize, Deserialize, Queryable)]
 pub struct Street {
@@ -184,6 +242,8 @@ pub struct Street {
     pub length_km: Option<BigDecimal>, // Длина улицы в километрах (Option, так как может быть неизвестной)
     pub surface_type: Option<String>, // Тип покрытия улицы (Option, так как может быть неизвестным)
     pub speed_limit: Option<i16>,    // Ограничение скорости на улице (Option, так как может быть неизвестным)
+    #[serde(skip)]
+    pub geometry: Option<GeoMultiLine>, // Линия улицы (Option, так как может быть неизвестной), в API отдается через GeoJSON
 }
 
 // Структура для вставки новых записей о улицах в базу данных
@@ -199,10 +259,11 @@ pub struct NewStreet {
 // Методы для структуры NewStreet
 impl NewStreet {
     // Метод для создания новой улицы
-    pub fn create(new_street: NewStreet, connection: &PgConnection) -> QueryResult<Street> {
+    pub fn create(new_street: NewStreet, connection: &PgConnection) -> Result<Street, Error> {
         diesel::insert_into(streets::table)
             .values(&new_street)
             .get_result(connection)
+            .map_err(Error::from)
     }
 
     // Добавьте здесь другие методы, если они вам нужны
@@ -211,31 +272,35 @@ impl NewStreet {
 // Методы для структуры Street
 impl Street {
     // Метод для получения всех организаций на улице
-    pub fn get_organizations(&self, connection: &PgConnection) -> QueryResult<Vec<Organization>> {
+    pub fn get_organizations(&self, connection: &PgConnection) -> Result<Vec<Organization>, Error> {
         organizations::table
             .filter(organizations::street_id.eq(self.id))
             .load(connection)
+            .map_err(Error::from)
     }
 
     // Метод для получения всех товаров и услуг на улице
-    pub fn get_goods_services(&self, connection: &PgConnection) -> QueryResult<Vec<GoodsService>> {
-        goods_services::table
-            .filter(goods_services::street_id.eq(self.id))
+    pub fn get_goods_services(&self, connection: &PgConnection) -> Result<Vec<Goods>, Error> {
+        goods::table
+            .filter(goods::street_id.eq(self.id))
             .load(connection)
+            .map_err(Error::from)
     }
 
     // Метод для получения всех бесплатных Wi-Fi точек и зарядок на улице
-    pub fn get_wifi_charging(&self, connection: &PgConnection) -> QueryResult<Vec<WifiCharging>> {
+    pub fn get_wifi_charging(&self, connection: &PgConnection) -> Result<Vec<WifiChargingPoint>, Error> {
         wifi_charging::table
             .filter(wifi_charging::street_id.eq(self.id))
             .load(connection)
+            .map_err(Error::from)
     }
 
     // Метод для получения всех пользовательских отметок на улице
-    pub fn get_user_points(&self, connection: &PgConnection) -> QueryResult<Vec<UserPoint>> {
+    pub fn get_user_points(&self, connection: &PgConnection) -> Result<Vec<UserPoint>, Error> {
         user_points::table
             .filter(user_points::street_id.eq(self.id))
             .load(connection)
+            .map_err(Error::from)
     }
 
     // Метод для вывода информации о улице
@@ -244,60 +309,90 @@ impl Street {
         // Вывод другой информации о улице, если необходимо
     }
 
-    // Метод для вывода фотографий улицы (если есть поле для фотографий в структуре Street)
-    pub fn display_photos(&self) {
-        if let Some(photos) = &self.photos {
-            for photo in photos {
-                // Вывод фотографий (здесь можно реализовать вывод в нужном формате)
-                println!("Фотография: {:?}", photo);
-            }
-        }
-    }
-
     // Добавьте здесь другие методы, если они вам нужны
 }
 
 
 
 // Структура представляющая организации на улицах-----------------------------------------------------------------------------------
-#[derive(Debug, Clone, Serialize, Deserialize, Queryable)]
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Identifiable)]
+#[table_name = "organizations"]
 pub struct Organization {
     pub id: i32,                    // Идентификатор организации
     pub street_id: i32,             // Идентификатор улицы
     pub name: String,               // Название организации
     pub description: Option<String>, // Описание организации (Option, так как может быть неизвестным)
     pub contact_info: Option<String>, // Контактная информация (Option, так как может быть неизвестной)
-    pub operating_hours: Option<String>, // Часы работы (Option, так как может быть неизвестными)
+    pub opening_hours_note: Option<String>, // Примечание к часам работы (Option, так как может быть неизвестным)
     pub website: Option<String>,     // Веб-сайт (Option, так как может быть неизвестным)
     pub email: Option<String>,       // Электронная почта (Option, так как может быть неизвестной)
     pub phone: Option<String>,       // Телефон (Option, так как может быть неизвестным)
     pub category: Option<String>,    // Категория организации (Option, так как может быть неизвестной)
-    pub rating: Option<BigDecimal>,  // Рейтинг организации (Option, так как может быть неизвестным)
+    pub rating: Option<BigDecimal>,  // Средняя оценка по отзывам (Option, так как отзывов может не быть)
+    #[serde(with = "crate::models::geo::serde_point_opt")]
+    pub location: Option<GeoPoint>,  // Координаты организации (Option, так как может быть неизвестными)
+    pub opening_hours: Option<OpeningHours>, // Часы работы по дням недели и исключения (Option, так как могут быть неизвестными)
+    pub reviews_count: i32,          // Число отзывов, по которым посчитан рейтинг
+    pub owner_id: Option<i32>,       // Подтвержденный владелец (Option, так как права могут быть не заявлены)
+    pub created_at: Option<NaiveDateTime>, // Дата и время добавления (Option, у старых записей неизвестна)
 }
 
 // Структура для вставки новых записей об организациях в базу данных
-#[derive(Debug, Clone, Insertable)]
+#[derive(Debug, Clone, Insertable, AsChangeset)]
 #[table_name = "organizations"]
 pub struct NewOrganization {
     pub street_id: i32,             // Идентификатор улицы
     pub name: String,               // Название организации
     pub description: Option<String>, // Описание организации (Option, так как может быть неизвестным)
     pub contact_info: Option<String>, // Контактная информация (Option, так как может быть неизвестной)
-    pub operating_hours: Option<String>, // Часы работы (Option, так как может быть неизвестными)
+    pub opening_hours_note: Option<String>, // Примечание к часам работы (Option, так как может быть неизвестным)
     pub website: Option<String>,     // Веб-сайт (Option, так как может быть неизвестным)
     pub email: Option<String>,       // Электронная почта (Option, так как может быть неизвестной)
     pub phone: Option<String>,       // Телефон (Option, так как может быть неизвестным)
     pub category: Option<String>,    // Категория организации (Option, так как может быть неизвестной)
-    pub rating: Option<BigDecimal>,  // Рейтинг организации (Option, так как может быть неизвестным)
+    pub location: Option<GeoPoint>,  // Координаты организации (Option, так как может быть неизвестными)
+    pub opening_hours: Option<OpeningHours>, // Часы работы по дням недели и исключения (Option, так как могут быть неизвестными)
+}
+
+// Структура для изменения профиля организации владельцем. Рейтинг, улица
+// и владелец здесь не меняются.
+#[derive(Debug, Clone, Deserialize, AsChangeset)]
+#[table_name = "organizations"]
+#[changeset_options(treat_none_as_null = "true")]
+pub struct EditOrganization {
+    pub name: String,                // Название организации
+    pub description: Option<String>, // Описание организации
+    pub contact_info: Option<String>, // Контактная информация
+    pub opening_hours_note: Option<String>, // Примечание к часам работы
+    pub website: Option<String>,     // Веб-сайт
+    pub email: Option<String>,       // Электронная почта
+    pub phone: Option<String>,       // Телефон
+    pub category: Option<String>,    // Категория организации
+    #[serde(default, with = "crate::models::geo::serde_point_opt")]
+    pub location: Option<GeoPoint>,  // Координаты организации
+    pub opening_hours: Option<OpeningHours>, // Часы работы
+}
+
+impl EditOrganization {
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.name.trim().is_empty() {
+            return Err(Error::Validation("name is required".to_string()));
+        }
+        if let Some(ref hours) = self.opening_hours {
+            hours.validate()?;
+        }
+        Ok(())
+    }
 }
 
 // Методы для структуры NewOrganization
 impl NewOrganization {
     // Метод для создания новой записи об организации
-    pub fn create(new_organization: NewOrganization, connection: &PgConnection) -> QueryResult<Organization> {
+    pub fn create(new_organization: NewOrganization, connection: &PgConnection) -> Result<Organization, Error> {
         diesel::insert_into(organizations::table)
             .values(&new_organization)
             .get_result(connection)
+            .map_err(Error::from)
     }
 
     // Добавьте здесь другие методы, если они вам нужны
@@ -307,36 +402,56 @@ impl NewOrganization {
 // Методы для структуры Organization
 impl Organization {
     // Метод для создания новой записи об организации
-    pub fn create(new_organization: NewOrganization, connection: &PgConnection) -> QueryResult<Organization> {
+    pub fn create(new_organization: NewOrganization, connection: &PgConnection) -> Result<Organization, Error> {
         diesel::insert_into(organizations::table)
             .values(&new_organization)
             .get_result(connection)
+            .map_err(Error::from)
     }
 
     // Метод для чтения организации по идентификатору
-    pub fn read_by_id(organization_id: i32, connection: &PgConnection) -> QueryResult<Organization> {
+    pub fn read_by_id(organization_id: i32, connection: &PgConnection) -> Result<Organization, Error> {
         organizations::table.find(organization_id).first(connection)
+            .map_err(Error::from)
     }
 
     // Метод для обновления данных об организации
-    pub fn update(&self, connection: &PgConnection) -> QueryResult<Organization> {
+    pub fn update(&self, changes: &NewOrganization, connection: &PgConnection) -> Result<Organization, Error> {
         diesel::update(self)
-            .set(self)
+            .set(changes)
             .get_result(connection)
+            .map_err(Error::from)
     }
 
     // Метод для удаления организации по идентификатору
-    pub fn delete(organization_id: i32, connection: &PgConnection) -> QueryResult<()> {
-        diesel::delete(organizations::table.filter(organizations::id.eq(organization_id)))
-            .execute(connection)?;
+    pub fn delete(organization_id: i32, connection: &PgConnection) -> Result<(), Error> {
+        // отзывы удаляются вместе с объектом триггером в базе
+        diesel::delete(organizations::table.filter(organizations::id.eq(organization_id))).execute(connection)?;
         Ok(())
     }
 
     // Метод для получения улицы, на которой находится организация
-    pub fn get_street(&self, connection: &PgConnection) -> QueryResult<Street> {
+    pub fn get_street(&self, connection: &PgConnection) -> Result<Street, Error> {
         streets::table
             .filter(streets::id.eq(self.street_id))
             .first(connection)
+            .map_err(Error::from)
+    }
+
+    // Открыта ли организация в момент at (местное время).
+    // Если часы работы неизвестны, считается закрытой.
+    pub fn is_open_at(&self, at: NaiveDateTime) -> bool {
+        self.opening_hours
+            .as_ref()
+            .is_some_and(|hours| hours.is_open_at(at))
+    }
+
+    // Оставляет только открытые в момент open_at, без open_at - все
+    pub fn filter_open_at(organizations: Vec<Organization>, open_at: Option<NaiveDateTime>) -> Vec<Organization> {
+        match open_at {
+            Some(at) => organizations.into_iter().filter(|o| o.is_open_at(at)).collect(),
+            None => organizations,
+        }
     }
 
     // Метод для вывода информации об организации
@@ -344,12 +459,41 @@ impl Organization {
         println!("Организация: {}", &self.name);
         println!("Описание: {:?}", &self.description);
         println!("Контактная информация: {:?}", &self.contact_info);
-        println!("Часы работы: {:?}", &self.operating_hours);
+        println!("Часы работы: {:?}", self.opening_hours.as_ref().map(OpeningHours::to_osm));
         println!("Веб-сайт: {:?}", &self.website);
         println!("Электронная почта: {:?}", &self.email);
         println!("Телефон: {:?}", &self.phone);
         println!("Категория: {:?}", &self.category);
         println!("Рейтинг: {:?}", &self.rating);
+        println!("Число отзывов: {}", self.reviews_count);
+    }
+
+    // Метод для проверки, является ли пользователь подтвержденным владельцем
+    pub fn is_owned_by(&self, user_id: i32) -> bool {
+        self.owner_id == Some(user_id)
+    }
+
+    // Метод для изменения профиля организации владельцем. Не владелец - Forbidden.
+    pub fn update_profile(organization_id: i32, user_id: i32, changes: &EditOrganization, connection: &PgConnection) -> Result<Organization, Error> {
+        changes.validate()?;
+        connection.transaction(|| {
+            let organization = organizations::table
+                .find(organization_id)
+                .for_update()
+                .first::<Organization>(connection)?;
+            if !organization.is_owned_by(user_id) {
+                return Err(Error::Forbidden);
+            }
+            diesel::update(organizations::table.find(organization_id))
+                .set(changes)
+                .get_result(connection)
+                .map_err(Error::from)
+        })
+    }
+
+    // Метод для получения отзывов об организации, новые первыми
+    pub fn get_reviews(&self, connection: &PgConnection, offset: i64, limit: i64) -> Result<(Vec<ReviewDetail>, i64), Error> {
+        Review::get_for_object(connection, ReviewObject::Organization, self.id, offset, limit)
     }
 
     // Добавьте здесь другие методы, если они вам нужны
@@ -357,117 +501,147 @@ impl Organization {
 
 
 // Структура представляющая товары и услуги на улицах----------------------------------------------------------------------------------------
-#[derive(Debug, Clone, Serialize, Deserialize, Queryable)]
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Identifiable)]
+#[table_name = "goods"]
 pub struct Goods {
     pub id: i32,                    // Идентификатор товара или услуги
-    pub street_id: i32,             // Идентификатор улицы
+    pub street_id: i32,             // Идентификатор улицы (улица продавца)
     pub name: String,               // Название товара или услуги
     pub description: Option<String>, // Описание товара (Option, так как может быть неизвестным)
+    pub organization_id: Option<i32>, // Продавец (Option, у старых записей может быть неизвестен)
+    pub category_id: Option<i32>,   // Категория (Option, так как может быть не указана)
+    pub price: Option<BigDecimal>,  // Цена (Option, пусто - цена по запросу)
+    pub currency: String,           // Валюта цены
+    pub availability: String,       // Наличие: in_stock, on_order или out_of_stock
+    pub is_service: bool,           // Услуга, а не товар
+    pub created_at: NaiveDateTime,  // Дата и время создания
+    pub updated_at: Option<NaiveDateTime>, // Дата и время последнего изменения
 }
 
 // Структура для вставки новых записей о товарах и услугах на улицах в базу данных
-#[derive(Debug, Clone, Insertable)]
+#[derive(Debug, Clone, Insertable, AsChangeset)]
 #[table_name = "goods"]
 pub struct NewGoods {
     pub street_id: i32,             // Идентификатор улицы
     pub name: String,               // Название товара или услуги
     pub description: Option<String>, // Описание товара (Option, так как может быть неизвестным)
+    pub organization_id: Option<i32>, // Продавец
+    pub category_id: Option<i32>,   // Категория
+    pub price: Option<BigDecimal>,  // Цена
+    pub currency: String,           // Валюта цены
+    pub availability: String,       // Наличие
+    pub is_service: bool,           // Услуга, а не товар
 }
 // Методы для структуры NewGoods
 impl NewGoods {
     // Метод для создания новой записи о товаре или услуге
-    pub fn create(new_goods: NewGoods, connection: &PgConnection) -> QueryResult<Goods> {
+    pub fn create(new_goods: NewGoods, connection: &PgConnection) -> Result<Goods, Error> {
         diesel::insert_into(goods::table)
             .values(&new_goods)
             .get_result(connection)
+            .map_err(Error::from)
     }
 
     // Добавьте здесь другие методы, если они вам нужны
 }
 
-i// Методы для структуры Goods
+// Методы для структуры Goods
 impl Goods {
     // Метод для создания новой записи о товаре или услуге
-    pub fn create(new_goods: NewGoods, connection: &PgConnection) -> QueryResult<Goods> {
+    pub fn create(new_goods: NewGoods, connection: &PgConnection) -> Result<Goods, Error> {
         diesel::insert_into(goods::table)
             .values(&new_goods)
             .get_result(connection)
+            .map_err(Error::from)
     }
 
     // Метод для чтения товара или услуги по идентификатору
-    pub fn read_by_id(goods_id: i32, connection: &PgConnection) -> QueryResult<Goods> {
+    pub fn read_by_id(goods_id: i32, connection: &PgConnection) -> Result<Goods, Error> {
         goods::table.find(goods_id).first(connection)
+            .map_err(Error::from)
     }
 
     // Метод для обновления данных о товаре или услуге
-    pub fn update(&self, connection: &PgConnection) -> QueryResult<Goods> {
+    pub fn update(&self, changes: &NewGoods, connection: &PgConnection) -> Result<Goods, Error> {
         diesel::update(self)
-            .set(self)
+            .set(changes)
             .get_result(connection)
+            .map_err(Error::from)
     }
 
     // Метод для удаления товара или услуги по идентификатору
-    pub fn delete(goods_id: i32, connection: &PgConnection) -> QueryResult<()> {
+    pub fn delete(goods_id: i32, connection: &PgConnection) -> Result<(), Error> {
         diesel::delete(goods::table.filter(goods::id.eq(goods_id)))
             .execute(connection)?;
         Ok(())
     }
 
     // Метод для получения улицы, на которой находится товар или услуга
-    pub fn get_street(&self, connection: &PgConnection) -> QueryResult<Street> {
+    pub fn get_street(&self, connection: &PgConnection) -> Result<Street, Error> {
         streets::table
             .filter(streets::id.eq(self.street_id))
             .first(connection)
+            .map_err(Error::from)
+    }
+
+    // Метод для получения продавца товара или услуги
+    pub fn get_organization(&self, connection: &PgConnection) -> Result<Option<Organization>, Error> {
+        match self.organization_id {
+            Some(organization_id) => organizations::table
+                .find(organization_id)
+                .first(connection)
+                .optional()
+                .map_err(Error::from),
+            None => Ok(None),
+        }
     }
 
     // Добавьте здесь другие методы, если они вам нужны
 }
 
 // Структура представляющая точки отмеченные пользователем и комментарии----------------------------------------------------------------------
-#[derive(Debug, Clone, Serialize, Deserialize, Queryable)]
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Identifiable)]
+#[table_name = "user_points"]
 pub struct UserPoint {
     pub id: i32,                         // Идентификатор точки отмеченной пользователем
     pub street_id: i32,                  // Идентификатор улицы
     pub name: String,                    // Название точки
     pub description: Option<String>,     // Описание точки (Option, так как может быть неизвестным)
     pub category: Option<String>,        // Категория точки (Option, так как может быть неизвестной)
-    pub rating: Option<BigDecimal>,      // Рейтинг точки (Option, так как может быть неизвестным)
+    pub rating: Option<BigDecimal>,      // Средняя оценка по отзывам (Option, так как отзывов может не быть)
     pub photo: Option<Vec<u8>>,          // Фотография точки в виде BLOB (Option, так как может быть неизвестной)
-    pub latitude: f64,                   // Широта точки
-    pub longitude: f64,                  // Долгота точки
     pub created_at: NaiveDateTime,       // Дата и время создания точки
     pub user_id: i32,                    // Идентификатор пользователя, создавшего точку
     pub external_link: Option<String>,    // Ссылка на внешний ресурс (Option, так как может быть неизвестной)
-    pub average_rating: Option<f32>,     // Средний рейтинг точки (Option, так как может быть неизвестным)
-    pub reviews: Option<Vec<String>>,    // Отзывы о точке (Option, так как может быть неизвестными)
+    #[serde(with = "crate::models::geo::serde_point")]
+    pub location: GeoPoint,              // Координаты точки
+    pub reviews_count: i32,              // Число отзывов, по которым посчитан рейтинг
+    pub district_id: Option<i32>,        // Район по координатам, проставляется базой при сохранении
 }
 
 // Структура для вставки новых записей о точках в базу данных
-#[derive(Debug, Clone, Insertable)]
+#[derive(Debug, Clone, Insertable, AsChangeset)]
 #[table_name = "user_points"]
 pub struct NewUserPoint {
     pub street_id: i32,                  // Идентификатор улицы
     pub name: String,                    // Название точки
     pub description: Option<String>,     // Описание точки (Option, так как может быть неизвестным)
     pub category: Option<String>,        // Категория точки (Option, так как может быть неизвестной)
-    pub rating: Option<BigDecimal>,      // Рейтинг точки (Option, так как может быть неизвестным)
     pub photo: Option<Vec<u8>>,          // Фотография точки в виде BLOB (Option, так как может быть неизвестной)
-    pub latitude: f64,                   // Широта точки
-    pub longitude: f64,                  // Долгота точки
     pub created_at: NaiveDateTime,       // Дата и время создания точки
     pub user_id: i32,                    // Идентификатор пользователя, создавшего точку
     pub external_link: Option<String>,    // Ссылка на внешний ресурс (Option, так как может быть неизвестной)
-    pub average_rating: Option<f32>,     // Средний рейтинг точки (Option, так как может быть неизвестным)
-    pub reviews: Option<Vec<String>>,    // Отзывы о точке (Option, так как может быть неизвестными)
+    pub location: GeoPoint,              // Координаты точки
 }
 
 // Методы для структуры NewUserPoint
 impl NewUserPoint {
     // Метод для создания новой записи о точке, отмеченной пользователем
-    pub fn create(new_user_point: NewUserPoint, connection: &PgConnection) -> QueryResult<UserPoint> {
+    pub fn create(new_user_point: NewUserPoint, connection: &PgConnection) -> Result<UserPoint, Error> {
         diesel::insert_into(user_points::table)
             .values(&new_user_point)
             .get_result(connection)
+            .map_err(Error::from)
     }
 
     // Добавьте здесь другие методы, если они вам нужны
@@ -477,47 +651,45 @@ impl NewUserPoint {
 // Методы для структуры UserPoint
 impl UserPoint {
     // Метод для создания новой записи о точке, отмеченной пользователем
-    pub fn create(new_user_point: NewUserPoint, connection: &PgConnection) -> QueryResult<UserPoint> {
+    pub fn create(new_user_point: NewUserPoint, connection: &PgConnection) -> Result<UserPoint, Error> {
         diesel::insert_into(user_points::table)
             .values(&new_user_point)
             .get_result(connection)
+            .map_err(Error::from)
     }
 
     // Метод для чтения точки по идентификатору
-    pub fn read_by_id(user_point_id: i32, connection: &PgConnection) -> QueryResult<UserPoint> {
+    pub fn read_by_id(user_point_id: i32, connection: &PgConnection) -> Result<UserPoint, Error> {
         user_points::table.find(user_point_id).first(connection)
+            .map_err(Error::from)
     }
 
     // Метод для обновления данных о точке
-    pub fn update(&self, connection: &PgConnection) -> QueryResult<UserPoint> {
+    pub fn update(&self, changes: &NewUserPoint, connection: &PgConnection) -> Result<UserPoint, Error> {
         diesel::update(self)
-            .set(self)
+            .set(changes)
             .get_result(connection)
+            .map_err(Error::from)
     }
 
     // Метод для удаления точки по идентификатору
-    pub fn delete(user_point_id: i32, connection: &PgConnection) -> QueryResult<()> {
-        diesel::delete(user_points::table.filter(user_points::id.eq(user_point_id)))
-            .execute(connection)?;
+    pub fn delete(user_point_id: i32, connection: &PgConnection) -> Result<(), Error> {
+        // отзывы удаляются вместе с объектом триггером в базе
+        diesel::delete(user_points::table.filter(user_points::id.eq(user_point_id))).execute(connection)?;
         Ok(())
     }
 
     // Метод для получения улицы, на которой находится точка
-    pub fn get_street(&self, connection: &PgConnection) -> QueryResult<Street> {
+    pub fn get_street(&self, connection: &PgConnection) -> Result<Street, Error> {
         streets::table
             .filter(streets::id.eq(self.street_id))
             .first(connection)
+            .map_err(Error::from)
     }
 
-    // Метод для добавления отзыва о точке
-    pub fn add_review(&mut self, review: String) {
-        if self.reviews.is_none() {
-            self.reviews = Some(vec![review]);
-        } else {
-            if let Some(reviews) = &mut self.reviews {
-                reviews.push(review);
-            }
-        }
+    // Метод для получения отзывов о точке, новые первыми
+    pub fn get_reviews(&self, connection: &PgConnection, offset: i64, limit: i64) -> Result<(Vec<ReviewDetail>, i64), Error> {
+        Review::get_for_object(connection, ReviewObject::UserPoint, self.id, offset, limit)
     }
 
     // Метод для вывода информации о точке
@@ -526,16 +698,12 @@ impl UserPoint {
         println!("Описание: {:?}", &self.description);
         println!("Категория: {:?}", &self.category);
         println!("Рейтинг: {:?}", &self.rating);
-        println!("Широта: {:?}", &self.latitude);
-        println!("Долгота: {:?}", &self.longitude);
+        println!("Широта: {:?}", &self.location.y);
+        println!("Долгота: {:?}", &self.location.x);
         println!("Дата и время создания: {:?}", &self.created_at);
         println!("Идентификатор пользователя: {:?}", &self.user_id);
         println!("Внешняя ссылка: {:?}", &self.external_link);
-        if let Some(reviews) = &self.reviews {
-            for (index, review) in reviews.iter().enumerate() {
-                println!("Отзыв {}: {}", index + 1, review);
-            }
-        }
+        println!("Число отзывов: {}", self.reviews_count);
     }
 
     // Добавьте здесь другие методы, если они вам нужны
@@ -543,35 +711,57 @@ impl UserPoint {
 
 
 // Структура представляющая бесплатные Wi-Fi точки и зарядки------------------------------------------------------------------------------------
-#[derive(Debug, Clone, Serialize, Deserialize, Queryable)]
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Identifiable)]
+#[table_name = "wifi_charging"]
 pub struct WifiChargingPoint {
     pub id: i32,                         // Идентификатор точки
     pub street_id: i32,                  // Идентификатор улицы
     pub name: String,                    // Название точки
     pub description: Option<String>,     // Описание точки (Option, так как может быть неизвестным)
     pub services: Option<String>,        // Доступные услуги (Option, так как может быть неизвестным)
-    pub location: Option<Point>,         // Координаты точки на карте (Option, так как может быть неизвестным)
+    #[serde(with = "crate::models::geo::serde_point_opt")]
+    pub location: Option<GeoPoint>,      // Координаты точки на карте (Option, так как может быть неизвестным)
     pub rating: Option<BigDecimal>,      // Рейтинг точки (Option, так как может быть неизвестным)
+    pub has_wifi: bool,                  // Есть Wi-Fi
+    pub wifi_ssid: Option<String>,       // Имя сети Wi-Fi (Option, так как может быть неизвестным)
+    pub wifi_password_required: bool,    // Для Wi-Fi нужен пароль
+    pub has_charging: bool,              // Есть зарядка для электромобилей
+    pub is_free: bool,                   // Бесплатно
+    pub price: Option<BigDecimal>,       // Стоимость (Option, если бесплатно или неизвестна)
+    pub price_unit: Option<String>,      // За что берется плата: kwh, minute, hour или session
+    pub currency: String,                // Валюта стоимости
+    pub access_hours: Option<OpeningHours>, // Часы доступа (Option, пусто - круглосуточно или неизвестно)
+    pub created_at: Option<NaiveDateTime>, // Дата и время добавления (Option, у старых записей неизвестна)
 }
 
 // Структура для вставки новых записей о бесплатных Wi-Fi точках и зарядках в базу данных
-#[derive(Debug, Clone, Insertable)]
+#[derive(Debug, Clone, Insertable, AsChangeset)]
 #[table_name = "wifi_charging"]
 pub struct NewWifiChargingPoint {
     pub street_id: i32,                  // Идентификатор улицы
     pub name: String,                    // Название точки
     pub description: Option<String>,     // Описание точки (Option, так как может быть неизвестным)
     pub services: Option<String>,        // Доступные услуги (Option, так как может быть неизвестным)
-    pub location: Option<Point>,         // Координаты точки на карте (Option, так как может быть неизвестным)
+    pub location: Option<GeoPoint>,      // Координаты точки на карте (Option, так как может быть неизвестным)
     pub rating: Option<BigDecimal>,      // Рейтинг точки (Option, так как может быть неизвестным)
+    pub has_wifi: bool,                  // Есть Wi-Fi
+    pub wifi_ssid: Option<String>,       // Имя сети Wi-Fi
+    pub wifi_password_required: bool,    // Для Wi-Fi нужен пароль
+    pub has_charging: bool,              // Есть зарядка для электромобилей
+    pub is_free: bool,                   // Бесплатно
+    pub price: Option<BigDecimal>,       // Стоимость
+    pub price_unit: Option<String>,      // За что берется плата
+    pub currency: String,                // Валюта стоимости
+    pub access_hours: Option<OpeningHours>, // Часы доступа
 }
 // Методы для структуры NewWifiChargingPoint
 impl NewWifiChargingPoint {
     // Метод для создания новой записи о бесплатной Wi-Fi точке и зарядке
-    pub fn create(new_wifi_charging_point: NewWifiChargingPoint, connection: &PgConnection) -> QueryResult<WifiChargingPoint> {
+    pub fn create(new_wifi_charging_point: NewWifiChargingPoint, connection: &PgConnection) -> Result<WifiChargingPoint, Error> {
         diesel::insert_into(wifi_charging::table)
             .values(&new_wifi_charging_point)
             .get_result(connection)
+            .map_err(Error::from)
     }
 
     // Добавьте здесь другие методы, если они вам нужны
@@ -580,26 +770,29 @@ impl NewWifiChargingPoint {
 // Методы для структуры WifiChargingPoint
 impl WifiChargingPoint {
     // Метод для создания новой записи о бесплатной Wi-Fi точке и зарядке
-    pub fn create(new_wifi_charging_point: NewWifiChargingPoint, connection: &PgConnection) -> QueryResult<WifiChargingPoint> {
+    pub fn create(new_wifi_charging_point: NewWifiChargingPoint, connection: &PgConnection) -> Result<WifiChargingPoint, Error> {
         diesel::insert_into(wifi_charging::table)
             .values(&new_wifi_charging_point)
             .get_result(connection)
+            .map_err(Error::from)
     }
 
     // Метод для чтения точки по идентификатору
-    pub fn read_by_id(wifi_charging_point_id: i32, connection: &PgConnection) -> QueryResult<WifiChargingPoint> {
+    pub fn read_by_id(wifi_charging_point_id: i32, connection: &PgConnection) -> Result<WifiChargingPoint, Error> {
         wifi_charging::table.find(wifi_charging_point_id).first(connection)
+            .map_err(Error::from)
     }
 
     // Метод для обновления данных о точке
-    pub fn update(&self, connection: &PgConnection) -> QueryResult<WifiChargingPoint> {
+    pub fn update(&self, changes: &NewWifiChargingPoint, connection: &PgConnection) -> Result<WifiChargingPoint, Error> {
         diesel::update(self)
-            .set(self)
+            .set(changes)
             .get_result(connection)
+            .map_err(Error::from)
     }
 
     // Метод для удаления точки по идентификатору
-    pub fn delete(wifi_charging_point_id: i32, connection: &PgConnection) -> QueryResult<()> {
+    pub fn delete(wifi_charging_point_id: i32, connection: &PgConnection) -> Result<(), Error> {
         diesel::delete(wifi_charging::table.filter(wifi_charging::id.eq(wifi_charging_point_id)))
             .execute(connection)?;
         Ok(())
@@ -1,8 +1,18 @@
 // Подключаем необходимые модули
 use chrono::NaiveDateTime;
+use diesel::dsl::{exists, not, sql};
+use diesel::prelude::*;
+use diesel::sql_types::BigInt;
 use serde::{Serialize, Deserialize};
-use diesel::{Queryable, Insertable};
-use crate::schema::forums; // Здесь подставьте имя вашего модуля с описанием схемы базы данных
+use crate::schema::{forum_likes, forum_posts, forum_subscriptions, forum_topics, forums};
+use crate::errors::Error;
+use crate::models::forum_members::{check_forum_access, check_topic_access, visible_forum_ids};
+use crate::models::ForumPostDetail;
+use crate::utils::like_pattern;
+
+// Закрытые форумы видят только их участники: все выборки ниже принимают
+// viewer_id (None для анонима) и отдают только доступные ему форумы,
+// темы и сообщения
 
 // Создаем структуру Форум
 #[derive(Debug, Serialize, Deserialize, Queryable)]
@@ -26,107 +36,128 @@ pub struct NewForum {
     pub is_private: bool,
 }
 
+// Последнее сообщение форума для ленты обновлений
+#[derive(Debug, Serialize, Queryable)]
+pub struct ForumUpdate {
+    pub topic_id: i32,
+    pub topic_title: String,
+    pub post_id: i32,
+    pub content: String,
+    pub created_at: NaiveDateTime,
+}
+
 impl Forum {
 // Функция для создания нового форума
-pub fn create_forum(new_forum: NewForum, connection: &PgConnection) -> Result<Forum, diesel::result::Error> {
+pub fn create_forum(new_forum: NewForum, connection: &PgConnection) -> Result<Forum, Error> {
     diesel::insert_into(forums::table)
         .values(&new_forum)
         .get_result(connection)
+        .map_err(Error::from)
 }
 
 // Функция для получения списка всех форумов
-pub fn get_all_forums(connection: &PgConnection) -> Result<Vec<Forum>, diesel::result::Error> {
-    forums::table.load::<Forum>(connection)
+pub fn get_all_forums(viewer_id: Option<i32>, connection: &PgConnection) -> Result<Vec<Forum>, Error> {
+    forums::table
+        .filter(forums::id.eq_any(visible_forum_ids(connection, viewer_id)?))
+        .load::<Forum>(connection)
+        .map_err(Error::from)
 }
 
 // Получение форума по его идентификатору
-pub fn get_forum_by_id(forum_id: i32, connection: &PgConnection) -> Result<Option<Forum>, diesel::result::Error> {
-    forums::table.find(forum_id).first(connection).optional()
+pub fn get_forum_by_id(forum_id: i32, viewer_id: Option<i32>, connection: &PgConnection) -> Result<Option<Forum>, Error> {
+    match check_forum_access(connection, forum_id, viewer_id) {
+        Ok(forum) => Ok(Some(forum)),
+        Err(Error::NotFound) => Ok(None),
+        Err(e) => Err(e),
+    }
 }
 
 // Удаление форума по его идентификатору
-pub fn delete_forum(forum_id: i32, connection: &PgConnection) -> Result<usize, diesel::result::Error> {
+pub fn delete_forum(forum_id: i32, connection: &PgConnection) -> Result<usize, Error> {
     diesel::delete(forums::table.find(forum_id))
         .execute(connection)
+        .map_err(Error::from)
 }
 
 
 //Получение списка форумов по категории
 
-pub fn get_forums_by_category(category: &str, connection: &PgConnection) -> Result<Vec<Forum>, diesel::result::Error> {
+pub fn get_forums_by_category(category: &str, viewer_id: Option<i32>, connection: &PgConnection) -> Result<Vec<Forum>, Error> {
     forums::table.filter(forums::category.eq(category))
+        .filter(forums::id.eq_any(visible_forum_ids(connection, viewer_id)?))
         .load::<Forum>(connection)
+        .map_err(Error::from)
 }
 
 //Поиск форумов по названию
 
-pub fn search_forums_by_name(query: &str, connection: &PgConnection) -> Result<Vec<Forum>, diesel::result::Error> {
-    forums::table.filter(forums::name.like(format!("%{}%", query)))
+pub fn search_forums_by_name(query: &str, viewer_id: Option<i32>, connection: &PgConnection) -> Result<Vec<Forum>, Error> {
+    forums::table.filter(forums::name.ilike(like_pattern(query)))
+        .filter(forums::id.eq_any(visible_forum_ids(connection, viewer_id)?))
         .load::<Forum>(connection)
+        .map_err(Error::from)
 }
 
-//Получение количества тем на каждом форуме
-
-use diesel::dsl::sql;
-use diesel::sql_types::Integer;
+//Получение количества тем на каждом доступном форуме
 
-pub fn get_forum_topic_count(connection: &PgConnection) -> Result<Vec<(i32, i32)>, diesel::result::Error> {
-    sql::<(Integer, Integer)>("SELECT f.id, COUNT(t.id) FROM forums AS f
-        LEFT JOIN topics AS t ON f.id = t.forum_id
-        GROUP BY f.id")
-        .load(connection)
+pub fn get_forum_topic_count(viewer_id: Option<i32>, connection: &PgConnection) -> Result<Vec<(i32, i64)>, Error> {
+    forums::table
+        .left_join(forum_topics::table)
+        .filter(forums::id.eq_any(visible_forum_ids(connection, viewer_id)?))
+        .group_by(forums::id)
+        .select((forums::id, sql::<BigInt>("COUNT(forum_topics.id)")))
+        .order_by(forums::id)
+        .load::<(i32, i64)>(connection)
+        .map_err(Error::from)
 }
 
 //Получение последних созданных форумов (например, для отображения на главной странице)
 
-use diesel::prelude::*;
-use diesel::dsl::max;
-
-pub fn get_latest_forums(limit: i64, connection: &PgConnection) -> Result<Vec<Forum>, diesel::result::Error> {
+pub fn get_latest_forums(limit: i64, viewer_id: Option<i32>, connection: &PgConnection) -> Result<Vec<Forum>, Error> {
     forums::table
+        .filter(forums::id.eq_any(visible_forum_ids(connection, viewer_id)?))
         .order_by(forums::created_at.desc())
         .limit(limit)
         .load::<Forum>(connection)
+        .map_err(Error::from)
 }
 
-//Получение количества активных участников на форуме
+//Получение количества участников, писавших на форуме
 
-use diesel::dsl::{count, sql};
-use diesel::sql_types::BigInt;
-
-pub fn get_forum_active_users(forum_id: i32, connection: &PgConnection) -> Result<i64, diesel::result::Error> {
-    sql::<BigInt>("SELECT COUNT(DISTINCT user_id) FROM posts WHERE forum_id = ?")
-        .bind::<diesel::sql_types::Int4, _>(forum_id)
+pub fn get_forum_active_users(forum_id: i32, viewer_id: Option<i32>, connection: &PgConnection) -> Result<i64, Error> {
+    check_forum_access(connection, forum_id, viewer_id)?;
+    forum_posts::table
+        .inner_join(forum_topics::table)
+        .filter(forum_topics::forum_id.eq(forum_id))
+        .filter(forum_posts::deleted_at.is_null())
+        .select(sql::<BigInt>("COUNT(DISTINCT forum_posts.user_id)"))
         .get_result(connection)
+        .map_err(Error::from)
 }
 
-//Получение списка последних обновлений на форуме (например, новых сообщений)
+//Получение списка последних обновлений на форуме (новых сообщений)
 
-use diesel::prelude::*;
-
-pub fn get_forum_latest_updates(forum_id: i32, limit: i64, connection: &PgConnection) -> Result<Vec<ForumUpdate>, diesel::result::Error> {
-    forums::table
-        .filter(forums::id.eq(forum_id))
-        .inner_join(posts::table)
-        .order_by(posts::created_at.desc())
+pub fn get_forum_latest_updates(forum_id: i32, limit: i64, viewer_id: Option<i32>, connection: &PgConnection) -> Result<Vec<ForumUpdate>, Error> {
+    check_forum_access(connection, forum_id, viewer_id)?;
+    forum_posts::table
+        .inner_join(forum_topics::table)
+        .filter(forum_topics::forum_id.eq(forum_id))
+        .filter(forum_posts::deleted_at.is_null())
+        .order_by(forum_posts::created_at.desc())
         .limit(limit)
         .select((
-            forums::id,
-            forums::name,
-            posts::created_at,
-            posts::content,
+            forum_topics::id,
+            forum_topics::title,
+            forum_posts::id,
+            forum_posts::content,
+            forum_posts::created_at,
         ))
         .load::<ForumUpdate>(connection)
+        .map_err(Error::from)
 }
 }
 //--------------------------------------------------------------------------------------------------------------
 
-// Подключаем необходимые модули
-use chrono::NaiveDateTime;
-use serde::{Serialize, Deserialize};
-use diesel::{Queryable, Insertable};
-use crate::schema::forum_topics; 
-
 // Создаем структуру "forum_topics" (Темы форума)
 #[derive(Debug, Serialize, Deserialize, Queryable)]
 pub struct ForumTopic {
@@ -137,6 +168,9 @@ pub struct ForumTopic {
     pub user_id: i32,                // Идентификатор пользователя, создавшего тему
     pub created_at: NaiveDateTime,   // Дата и время создания темы
     pub updated_at: NaiveDateTime,   // Дата и время последнего обновления темы
+    pub is_locked: bool,             // Тема закрыта модератором
+    pub locked_at: Option<NaiveDateTime>, // Дата и время закрытия (может отсутствовать)
+    pub locked_by: Option<i32>,      // Модератор, закрывший тему (может отсутствовать)
 }
 
 // Добавляем поддержку вставки данных в базу (Insertable)
@@ -152,180 +186,147 @@ pub struct NewForumTopic {
 // Это описание структуры "forum_topics" и функций, которые могут пригодиться
 impl ForumTopic {
 // Функция для создания новой темы
-pub fn create_forum_topic(new_topic: NewForumTopic, connection: &PgConnection) -> Result<ForumTopic, diesel::result::Error> {
+pub fn create_forum_topic(new_topic: NewForumTopic, connection: &PgConnection) -> Result<ForumTopic, Error> {
     diesel::insert_into(forum_topics::table)
         .values(&new_topic)
         .get_result(connection)
+        .map_err(Error::from)
 }
 
 // Функция для получения списка всех тем форума
-pub fn get_all_forum_topics(connection: &PgConnection) -> Result<Vec<ForumTopic>, diesel::result::Error> {
+pub fn get_all_forum_topics(viewer_id: Option<i32>, connection: &PgConnection) -> Result<Vec<ForumTopic>, Error> {
     forum_topics::table
+    .filter(forum_topics::forum_id.eq_any(visible_forum_ids(connection, viewer_id)?))
     .load::<ForumTopic>(connection)
+    .map_err(Error::from)
 }
 
 //Получение списка тем по идентификатору форума
 
-pub fn get_topics_by_forum_id(forum_id: i32, connection: &PgConnection) -> Result<Vec<ForumTopic>, diesel::result::Error> {
+pub fn get_topics_by_forum_id(forum_id: i32, viewer_id: Option<i32>, connection: &PgConnection) -> Result<Vec<ForumTopic>, Error> {
+    check_forum_access(connection, forum_id, viewer_id)?;
     forum_topics::table
         .filter(forum_topics::forum_id.eq(forum_id))
         .load::<ForumTopic>(connection)
+        .map_err(Error::from)
 }
 
 //Получение темы по её идентификатору
 
-pub fn get_topic_by_id(topic_id: i32, connection: &PgConnection) -> Result<Option<ForumTopic>, diesel::result::Error> {
-    forum_topics::table
-        .find(topic_id)
-        .first(connection)
-        .optional()
+pub fn get_topic_by_id(topic_id: i32, viewer_id: Option<i32>, connection: &PgConnection) -> Result<Option<ForumTopic>, Error> {
+    match check_topic_access(connection, topic_id, viewer_id) {
+        Ok(topic) => Ok(Some(topic)),
+        Err(Error::NotFound) => Ok(None),
+        Err(e) => Err(e),
+    }
 }
 
 //Удаление темы по её идентификатору
 
-pub fn delete_topic(topic_id: i32, connection: &PgConnection) -> Result<usize, diesel::result::Error> {
+pub fn delete_topic(topic_id: i32, connection: &PgConnection) -> Result<usize, Error> {
     diesel::delete(forum_topics::table.find(topic_id))
         .execute(connection)
+        .map_err(Error::from)
 }
 
 //Получение списка тем, созданных определенным пользователем
 
-pub fn get_topics_by_user_id(user_id: i32, connection: &PgConnection) -> Result<Vec<ForumTopic>, diesel::result::Error> {
+pub fn get_topics_by_user_id(user_id: i32, viewer_id: Option<i32>, connection: &PgConnection) -> Result<Vec<ForumTopic>, Error> {
     forum_topics::table
         .filter(forum_topics::user_id.eq(user_id))
+        .filter(forum_topics::forum_id.eq_any(visible_forum_ids(connection, viewer_id)?))
         .load::<ForumTopic>(connection)
+        .map_err(Error::from)
 }
 
 //Получение последних активных тем форума
 
-pub fn get_latest_active_topics(forum_id: i32, limit: i64, connection: &PgConnection) -> Result<Vec<ForumTopic>, diesel::result::Error> {
+pub fn get_latest_active_topics(forum_id: i32, limit: i64, viewer_id: Option<i32>, connection: &PgConnection) -> Result<Vec<ForumTopic>, Error> {
+    check_forum_access(connection, forum_id, viewer_id)?;
     forum_topics::table
         .filter(forum_topics::forum_id.eq(forum_id))
         .order_by(forum_topics::updated_at.desc())
         .limit(limit)
         .load::<ForumTopic>(connection)
+        .map_err(Error::from)
 }
 
 //Получение количества сообщений в каждой теме форума
 
-use diesel::dsl::{sql, count};
-use diesel::sql_types::Integer;
-
-pub fn get_topic_message_counts(forum_id: i32, connection: &PgConnection) -> Result<Vec<(i32, i32)>, diesel::result::Error> {
-    sql::<(Integer, Integer)>("SELECT t.id, COUNT(m.id) FROM forum_topics AS t
-        LEFT JOIN forum_messages AS m ON t.id = m.topic_id
-        WHERE t.forum_id = ?
-        GROUP BY t.id")
-        .bind::<diesel::sql_types::Int4, _>(forum_id)
-        .load(connection)
-}
-//Обратите внимание, что некоторые из этих методов предполагают наличие таблицы forum_messages для хранения сообщений в темах. 
-//Получение списка тем с наибольшим количеством сообщений
-
-pub fn get_topics_with_most_messages(limit: i64, connection: &PgConnection) -> Result<Vec<ForumTopic>, diesel::result::Error> {
-    let subquery = sql::<diesel::sql_types::Int8>("SELECT topic_id, COUNT(id) FROM forum_messages GROUP BY topic_id");
-    
+pub fn get_topic_message_counts(forum_id: i32, viewer_id: Option<i32>, connection: &PgConnection) -> Result<Vec<(i32, i64)>, Error> {
+    check_forum_access(connection, forum_id, viewer_id)?;
     forum_topics::table
-        .filter(forum_topics::id.eq_any(subquery))
-        .order_by(sql::<diesel::sql_types::Int8>("COUNT(id) DESC"))
-        .limit(limit)
-        .load::<ForumTopic>(connection)
+        .left_join(forum_posts::table.on(
+            forum_posts::topic_id.eq(forum_topics::id).and(forum_posts::deleted_at.is_null()),
+        ))
+        .filter(forum_topics::forum_id.eq(forum_id))
+        .group_by(forum_topics::id)
+        .select((forum_topics::id, sql::<BigInt>("COUNT(forum_posts.id)")))
+        .order_by(forum_topics::id)
+        .load::<(i32, i64)>(connection)
+        .map_err(Error::from)
 }
 
+//Получение списка популярных тем: с наибольшим количеством сообщений
 
-//Получение списка популярных тем на форуме (например, наиболее просматриваемых или комментируемых)
-
-
-pub fn get_popular_topics(limit: i64, connection: &PgConnection) -> Result<Vec<ForumTopic>, diesel::result::Error> {
+pub fn get_popular_topics(limit: i64, viewer_id: Option<i32>, connection: &PgConnection) -> Result<Vec<ForumTopic>, Error> {
     forum_topics::table
-        .order_by(sql::<diesel::sql_types::Int8>("(SELECT COUNT(id) FROM forum_messages WHERE topic_id = forum_topics.id) DESC"))
+        .filter(forum_topics::forum_id.eq_any(visible_forum_ids(connection, viewer_id)?))
+        .order_by(sql::<BigInt>(
+            "(SELECT COUNT(*) FROM forum_posts \
+             WHERE forum_posts.topic_id = forum_topics.id AND forum_posts.deleted_at IS NULL) DESC",
+        ))
         .limit(limit)
         .load::<ForumTopic>(connection)
+        .map_err(Error::from)
 }
 
-//Обратите внимание, что для некоторых из этих методов может потребоваться настройка соответствующих таблиц и моделей, 
-//таких как таблица для подписок пользователей (user_subscriptions) и статус модерации (moderation_status).
-
 //Получение списка тем, на которые пользователь подписан
 
-pub fn get_topics_user_subscribed(user_id: i32, connection: &PgConnection) -> Result<Vec<ForumTopic>, diesel::result::Error> {
-    use crate::schema::user_subscriptions;
-
-    user_subscriptions::table
-        .filter(user_subscriptions::user_id.eq(user_id))
-        .select(user_subscriptions::topic_id)
-        .load::<i32>(connection)
-        .and_then(|topic_ids| {
-            forum_topics::table
-                .filter(forum_topics::id.eq_any(topic_ids))
-                .load(connection)
-        })
-}
-
-//Получение списка тем, на которые пользователь подписан
-
-pub fn get_topics_user_subscribed(user_id: i32, connection: &PgConnection) -> Result<Vec<ForumTopic>, diesel::result::Error> {
-    use crate::schema::user_subscriptions;
-
-    user_subscriptions::table
-        .filter(user_subscriptions::user_id.eq(user_id))
-        .select(user_subscriptions::topic_id)
-        .load::<i32>(connection)
-        .and_then(|topic_ids| {
-            forum_topics::table
-                .filter(forum_topics::id.eq_any(topic_ids))
-                .load(connection)
-        })
-}
-
-//Получение списка популярных тем, отсортированных по количеству просмотров
-
-pub fn get_popular_topics_by_views(limit: i64, connection: &PgConnection) -> Result<Vec<ForumTopic>, diesel::result::Error> {
-    forum_topics::table
-        .order_by(forum_topics::views.desc())
-        .limit(limit)
-        .load::<ForumTopic>(connection)
-}
-
-//Получение списка популярных тем, отсортированных по количеству просмотров
-
-pub fn get_popular_topics_by_views(limit: i64, connection: &PgConnection) -> Result<Vec<ForumTopic>, diesel::result::Error> {
+pub fn get_topics_user_subscribed(user_id: i32, connection: &PgConnection) -> Result<Vec<ForumTopic>, Error> {
     forum_topics::table
-        .order_by(forum_topics::views.desc())
-        .limit(limit)
+        .filter(forum_topics::id.eq_any(
+            forum_subscriptions::table
+                .filter(forum_subscriptions::user_id.eq(user_id))
+                .select(forum_subscriptions::topic_id),
+        ))
+        .filter(forum_topics::forum_id.eq_any(visible_forum_ids(connection, Some(user_id))?))
         .load::<ForumTopic>(connection)
+        .map_err(Error::from)
 }
 
 //Получение списка тем, на которые пользователь не отвечал, чтобы побудить его активность
 
-pub fn get_unanswered_topics_for_user(user_id: i32, connection: &PgConnection) -> Result<Vec<ForumTopic>, diesel::result::Error> {
-    use diesel::dsl::{not, exists};
-    use crate::schema::forum_messages;
-
+pub fn get_unanswered_topics_for_user(user_id: i32, connection: &PgConnection) -> Result<Vec<ForumTopic>, Error> {
     forum_topics::table
+        .filter(forum_topics::forum_id.eq_any(visible_forum_ids(connection, Some(user_id))?))
         .filter(not(exists(
-            forum_messages::table
-                .filter(forum_messages::topic_id.eq(forum_topics::id).and(forum_messages::user_id.eq(user_id)))
+            forum_posts::table
+                .filter(forum_posts::topic_id.eq(forum_topics::id).and(forum_posts::user_id.eq(user_id)))
         )))
         .load::<ForumTopic>(connection)
+        .map_err(Error::from)
 }
 
-//Получение списка тем, ожидающих модерацию
+//Получение списка тем, ожидающих модерацию: с неразобранными жалобами на сообщения
+
+pub fn get_topics_pending_moderation(viewer_id: Option<i32>, connection: &PgConnection) -> Result<Vec<ForumTopic>, Error> {
+    use crate::schema::forum_reports;
 
-pub fn get_topics_pending_moderation(connection: &PgConnection) -> Result<Vec<ForumTopic>, diesel::result::Error> {
     forum_topics::table
-        .filter(forum_topics::moderation_status.eq("pending"))
+        .filter(forum_topics::forum_id.eq_any(visible_forum_ids(connection, viewer_id)?))
+        .filter(forum_topics::id.eq_any(
+            forum_reports::table
+                .inner_join(forum_posts::table)
+                .filter(forum_reports::status.eq(crate::models::REPORT_PENDING))
+                .select(forum_posts::topic_id),
+        ))
         .load::<ForumTopic>(connection)
+        .map_err(Error::from)
 }
 }
 //-----------------------------------------------------------------------------------
 
-// Подключаем необходимые модули
-use chrono::NaiveDateTime;
-use serde::{Serialize, Deserialize};
-use diesel::{Queryable, Insertable};
-use crate::schema::forum_posts; // Здесь подставьте имя вашего модуля с описанием схемы базы данных
-
 // Создаем структуру "forum_posts" (Сообщения на форуме)
 #[derive(Debug, Serialize, Deserialize, Queryable)]
 pub struct ForumPost {
@@ -335,6 +336,13 @@ pub struct ForumPost {
     pub content: String,             // Содержание сообщения
     pub created_at: NaiveDateTime,   // Дата и время создания сообщения
     pub updated_at: NaiveDateTime,   // Дата и время последнего обновления сообщения
+    pub parent_id: Option<i32>,      // Сообщение, на которое это ответ (может отсутствовать)
+    pub quoted_post_id: Option<i32>, // Процитированное сообщение (может отсутствовать)
+    pub quote_text: Option<String>,  // Текст цитаты на момент ответа (может отсутствовать)
+    pub edit_count: i32,             // Сколько раз сообщение правили
+    pub edited_at: Option<NaiveDateTime>, // Дата и время последней правки (может отсутствовать)
+    pub deleted_at: Option<NaiveDateTime>, // Дата и время удаления (может отсутствовать)
+    pub deleted_by: Option<i32>,     // Кто удалил: автор или модератор (может отсутствовать)
 }
 
 // Добавляем поддержку вставки данных в базу (Insertable)
@@ -349,112 +357,142 @@ pub struct NewForumPost {
 // Это описание структуры "forum_posts" и функций, которые могут пригодиться
 impl ForumPost {
 // Функция для создания нового сообщения
-pub fn create_forum_post(new_post: NewForumPost, connection: &PgConnection) -> Result<ForumPost, diesel::result::Error> {
+pub fn create_forum_post(new_post: NewForumPost, connection: &PgConnection) -> Result<ForumPost, Error> {
     diesel::insert_into(forum_posts::table)
         .values(&new_post)
         .get_result(connection)
+        .map_err(Error::from)
 }
 
 // Функция для получения сообщения по его идентификатору
-pub fn get_post_by_id(post_id: i32, connection: &PgConnection) -> Result<Option<ForumPost>, diesel::result::Error> {
-    forum_posts::table.find(post_id).first(connection).optional()
+pub fn get_post_by_id(post_id: i32, viewer_id: Option<i32>, connection: &PgConnection) -> Result<Option<ForumPostDetail>, Error> {
+    match ForumPost::get_visible(connection, post_id, viewer_id) {
+        Ok(post) => post.into_detail(connection).map(Some),
+        Err(Error::NotFound) => Ok(None),
+        Err(e) => Err(e),
+    }
 }
 
 //Получение списка сообщений по идентификатору темы
 
-pub fn get_posts_by_topic_id(topic_id: i32, connection: &PgConnection) -> Result<Vec<ForumPost>, diesel::result::Error> {
-    forum_posts::table
+pub fn get_posts_by_topic_id(topic_id: i32, viewer_id: Option<i32>, connection: &PgConnection) -> Result<Vec<ForumPostDetail>, Error> {
+    check_topic_access(connection, topic_id, viewer_id)?;
+    let posts = forum_posts::table
         .filter(forum_posts::topic_id.eq(topic_id))
-        .load::<ForumPost>(connection)
+        .load::<ForumPost>(connection)?;
+    ForumPost::into_details(connection, posts)
 }
 
-//Обновление содержания сообщения по его идентификатору
+//Обновление содержания сообщения по его идентификатору. Прежний текст сохраняется в истории правок
 
-pub fn update_post_content(post_id: i32, updated_content: &str, connection: &PgConnection) -> Result<ForumPost, diesel::result::Error> {
-    diesel::update(forum_posts::table.find(post_id))
-        .set(forum_posts::content.eq(updated_content))
-        .get_result(connection)
+pub fn update_post_content(post_id: i32, editor_id: i32, updated_content: &str, connection: &PgConnection) -> Result<ForumPost, Error> {
+    ForumPost::edit(connection, post_id, editor_id, updated_content)
 }
 
-//Удаление сообщения по его идентификатору
+//Удаление сообщения по его идентификатору. Сообщение остается в ветке без текста
 
-pub fn delete_post_by_id(post_id: i32, connection: &PgConnection) -> Result<(), diesel::result::Error> {
-    diesel::delete(forum_posts::table.find(post_id))
-    .execute(connection)?;
+pub fn delete_post_by_id(post_id: i32, user_id: i32, connection: &PgConnection) -> Result<(), Error> {
+    ForumPost::soft_delete(connection, post_id, user_id)?;
     Ok(())
 }
 
 //Получение количества сообщений, созданных пользователем
 
-pub fn get_post_count_by_user_id(user_id: i32, connection: &PgConnection) -> Result<i64, diesel::result::Error> {
+pub fn get_post_count_by_user_id(user_id: i32, connection: &PgConnection) -> Result<i64, Error> {
     forum_posts::table
         .filter(forum_posts::user_id.eq(user_id))
+        .filter(forum_posts::deleted_at.is_null())
         .count()
         .get_result(connection)
+        .map_err(Error::from)
 }
 
-//Поиск сообщений по ключевому слову в содержании
+//Поиск сообщений по ключевому слову в содержании. % и _ в запросе ищутся как обычные символы
 
-pub fn search_posts_by_keyword(keyword: &str, connection: &PgConnection) -> Result<Vec<ForumPost>, diesel::result::Error> {
-    forum_posts::table
-        .filter(forum_posts::content.ilike(format!("%{}%", keyword)))
-        .load::<ForumPost>(connection)
+pub fn search_posts_by_keyword(keyword: &str, viewer_id: Option<i32>, connection: &PgConnection) -> Result<Vec<ForumPostDetail>, Error> {
+    let posts = forum_posts::table
+        .filter(forum_posts::content.ilike(like_pattern(keyword)))
+        .filter(forum_posts::deleted_at.is_null())
+        .filter(forum_posts::topic_id.eq_any(
+            forum_topics::table
+                .filter(forum_topics::forum_id.eq_any(visible_forum_ids(connection, viewer_id)?))
+                .select(forum_topics::id),
+        ))
+        .load::<ForumPost>(connection)?;
+    ForumPost::into_details(connection, posts)
 }
 
 //Получение списка последних сообщений на форуме
 
-pub fn get_latest_posts(limit: i64, connection: &PgConnection) -> Result<Vec<ForumPost>, diesel::result::Error> {
-    forum_posts::table
+pub fn get_latest_posts(limit: i64, viewer_id: Option<i32>, connection: &PgConnection) -> Result<Vec<ForumPostDetail>, Error> {
+    let posts = forum_posts::table
+        .filter(forum_posts::deleted_at.is_null())
+        .filter(forum_posts::topic_id.eq_any(
+            forum_topics::table
+                .filter(forum_topics::forum_id.eq_any(visible_forum_ids(connection, viewer_id)?))
+                .select(forum_topics::id),
+        ))
         .order_by(forum_posts::created_at.desc())
         .limit(limit)
-        .load::<ForumPost>(connection)
+        .load::<ForumPost>(connection)?;
+    ForumPost::into_details(connection, posts)
 }
 
 //Получение последних сообщений пользователя
 
-use diesel::dsl::sql;
-use diesel::sql_types::Integer;
-
-pub fn get_latest_posts_by_user(user_id: i32, limit: i64, connection: &PgConnection) -> Result<Vec<ForumPost>, diesel::result::Error> {
-    forum_posts::table
+pub fn get_latest_posts_by_user(user_id: i32, limit: i64, viewer_id: Option<i32>, connection: &PgConnection) -> Result<Vec<ForumPostDetail>, Error> {
+    let posts = forum_posts::table
         .filter(forum_posts::user_id.eq(user_id))
+        .filter(forum_posts::deleted_at.is_null())
+        .filter(forum_posts::topic_id.eq_any(
+            forum_topics::table
+                .filter(forum_topics::forum_id.eq_any(visible_forum_ids(connection, viewer_id)?))
+                .select(forum_topics::id),
+        ))
         .order_by(forum_posts::created_at.desc())
         .limit(limit)
-        .load::<ForumPost>(connection)
+        .load::<ForumPost>(connection)?;
+    ForumPost::into_details(connection, posts)
 }
 
 //Получение списка сообщений с наибольшим количеством лайков
 
-pub fn get_posts_with_most_likes(limit: i64, connection: &PgConnection) -> Result<Vec<(ForumPost, i64)>, diesel::result::Error> {
-    use diesel::dsl::count;
-
+pub fn get_posts_with_most_likes(limit: i64, viewer_id: Option<i32>, connection: &PgConnection) -> Result<Vec<(ForumPost, i64)>, Error> {
     forum_posts::table
-        .inner_join(likes::table.on(likes::post_id.eq(forum_posts::id)))
+        .inner_join(forum_likes::table)
+        .filter(forum_posts::deleted_at.is_null())
+        .filter(forum_posts::topic_id.eq_any(
+            forum_topics::table
+                .filter(forum_topics::forum_id.eq_any(visible_forum_ids(connection, viewer_id)?))
+                .select(forum_topics::id),
+        ))
         .group_by(forum_posts::id)
-        .order_by(count(likes::id).desc())
+        .order_by(sql::<BigInt>("COUNT(*) DESC"))
         .limit(limit)
-        .select((forum_posts::all_columns, count(likes::id)))
+        .select((forum_posts::all_columns, sql::<BigInt>("COUNT(*)")))
         .load(connection)
+        .map_err(Error::from)
 }
 
 //Получение списка сообщений, на которые пользователь оставил лайк
 
-pub fn get_posts_user_liked(user_id: i32, connection: &PgConnection) -> Result<Vec<ForumPost>, diesel::result::Error> {
-    use crate::schema::likes;
-
+pub fn get_posts_user_liked(user_id: i32, viewer_id: Option<i32>, connection: &PgConnection) -> Result<Vec<ForumPost>, Error> {
     forum_posts::table
-        .inner_join(likes::table.on(likes::post_id.eq(forum_posts::id)))
-        .filter(likes::user_id.eq(user_id))
+        .inner_join(forum_likes::table)
+        .filter(forum_likes::user_id.eq(user_id))
+        .filter(forum_posts::deleted_at.is_null())
+        .filter(forum_posts::topic_id.eq_any(
+            forum_topics::table
+                .filter(forum_topics::forum_id.eq_any(visible_forum_ids(connection, viewer_id)?))
+                .select(forum_topics::id),
+        ))
+        .select(forum_posts::all_columns)
         .load::<ForumPost>(connection)
+        .map_err(Error::from)
 }
 }
 //---------------------------------------------------------------------------------------------------------------------------
-// Также удостоверьтесь, что у вас есть модуль schema с описанием схемы базы данных и таблицей forum_likes.
-// Подключаем необходимые модули
-use chrono::NaiveDateTime;
-use diesel::{Queryable, Insertable};
-use serde::{Serialize, Deserialize};
-use crate::schema::forum_likes; 
+
 // Создаем структуру "forum_likes" (Лайки к сообщениям на форуме)
 #[derive(Debug, Serialize, Deserialize, Queryable)]
 pub struct ForumLike {
@@ -475,29 +513,25 @@ impl ForumLike {
 // Это описание структуры "forum_likes" и функций, которые могут пригодиться
 
 // Функция для создания нового лайка
-pub fn create_forum_like(new_like: NewForumLike, connection: &PgConnection) -> Result<ForumLike, diesel::result::Error> {
+pub fn create_forum_like(new_like: NewForumLike, connection: &PgConnection) -> Result<ForumLike, Error> {
     diesel::insert_into(forum_likes::table)
         .values(&new_like)
         .get_result(connection)
+        .map_err(Error::from)
 }
 
 //Получение количества лайков для определенного сообщения
 
-pub fn get_like_count_by_post_id(post_id: i32, connection: &PgConnection) -> Result<i64, diesel::result::Error> {
+pub fn get_like_count_by_post_id(post_id: i32, connection: &PgConnection) -> Result<i64, Error> {
     forum_likes::table
         .filter(forum_likes::post_id.eq(post_id))
         .count()
         .get_result(connection)
+        .map_err(Error::from)
 }
 }
 //----------------------------------------------------------------------------------------------------------------------------
 
-
-use crate::schema::forum_subscriptions; 
-
-use chrono::NaiveDateTime;
-use diesel::prelude::*;
-
 // Структура "forum_subscriptions" для описания подписок на темы форума
 #[derive(Queryable)]
 pub struct ForumSubscription {
@@ -507,8 +541,6 @@ pub struct ForumSubscription {
     pub created_at: NaiveDateTime, // Дата и время подписки
 }
 
-use crate::schema::forum_subscriptions; // Замените на имя вашего модуля schema
-
 #[derive(Insertable)]
 #[table_name = "forum_subscriptions"]
 pub struct NewForumSubscription {
@@ -527,75 +559,60 @@ impl NewForumSubscription {
     }
 }
 
-//Метод для получения списка подписок пользователя
-
-use crate::schema::forum_subscriptions; // Замените на имя вашего модуля schema
-
 impl ForumSubscription {
     // Метод для получения списка подписок пользователя по его идентификатору
     pub fn get_subscriptions_by_user_id(
         conn: &PgConnection,
         user_id: i32,
-    ) -> Result<Vec<ForumSubscription>, diesel::result::Error> {
+    ) -> Result<Vec<ForumSubscription>, Error> {
         forum_subscriptions::table
             .filter(forum_subscriptions::user_id.eq(user_id))
             .load::<ForumSubscription>(conn)
+            .map_err(Error::from)
     }
-}
 
-
-impl ForumSubscription {
     // Метод для отмены подписки пользователя на тему
     pub fn unsubscribe(
         conn: &PgConnection,
         user_id: i32,
         topic_id: i32,
-    ) -> Result<usize, diesel::result::Error> {
+    ) -> Result<usize, Error> {
         diesel::delete(
             forum_subscriptions::table
                 .filter(forum_subscriptions::user_id.eq(user_id))
                 .filter(forum_subscriptions::topic_id.eq(topic_id)),
         )
         .execute(conn)
+        .map_err(Error::from)
     }
-}
-
-use crate::schema::forum_subscriptions; 
-use diesel::dsl::sql;
 
-impl ForumSubscription {
     // Метод для получения списка пользователей, подписанных на тему по ее идентификатору
     pub fn get_users_subscribed_to_topic(
         conn: &PgConnection,
         topic_id: i32,
-    ) -> Result<Vec<i32>, diesel::result::Error> {
+    ) -> Result<Vec<i32>, Error> {
         forum_subscriptions::table
             .filter(forum_subscriptions::topic_id.eq(topic_id))
-            .select(sql("user_id"))
+            .select(forum_subscriptions::user_id)
             .distinct()
             .load::<i32>(conn)
+            .map_err(Error::from)
     }
-}
 
-
-impl ForumSubscription {
     // Метод для получения списка всех подписок на форуме
     pub fn get_all_subscriptions(
         conn: &PgConnection,
-    ) -> Result<Vec<ForumSubscription>, diesel::result::Error> {
+    ) -> Result<Vec<ForumSubscription>, Error> {
         forum_subscriptions::table.load::<ForumSubscription>(conn)
+            .map_err(Error::from)
     }
 }
 
 //------------------------------------------------------------------------------------------------------
 
-use chrono::NaiveDateTime;
-use diesel::prelude::*;
-use diesel::pg::data_types::PgTimestamp;
-use crate::schema::*;
-
-// Создаем структуру "forum_reports"
-#[derive(Debug, Queryable)]
+// Создаем структуру "forum_reports". Жалобы создаются и разбираются
+// через ForumReport::create и ForumReport::resolve (forum_moderation.rs)
+#[derive(Debug, Clone, Serialize, Queryable)]
 pub struct ForumReport {
     pub id: i32,
     pub post_id: i32,
@@ -604,26 +621,7 @@ pub struct ForumReport {
     pub description: String,
     pub status: String,
     pub created_at: NaiveDateTime,
+    pub resolved_by: Option<i32>,
+    pub resolved_at: Option<NaiveDateTime>,
+    pub resolution: Option<String>,
 }
-
-// Определяем методы для структуры "forum_reports"
-impl ForumReport {
-    // Конструктор для создания новой жалобы
-    pub fn new(post_id: i32, user_id: i32, reason: String, description: String) -> Self {
-        ForumReport {
-            id: 0,  // Это значение будет автоматически установлено в базе данных
-            post_id,
-            user_id,
-            reason,
-            description,
-            status: String::from("в ожидании"),  // Устанавливаем начальный статус
-            created_at: chrono::Local::now().naive_utc(),  // Устанавливаем текущую дату и время
-        }
-    }
-}
-
-//------------------------------------------------------------------------------------------------------
-
-
-
-
@@ -13,9 +13,20 @@
 
 
 
-use diesel::prelude::*;
 use chrono::NaiveDateTime;
 use serde::Serialize;
+use crate::models::geo::GeoPoint;
+use crate::schema::{
+    layers,
+    maps,
+    route_comments,
+    route_images,
+    route_points,
+    route_reviews,
+    route_tags,
+    routes,
+    user_maps,
+};
 
 // Структура для таблицы "maps"----------------------------------------------------------------------------------------------1
 #[derive(Queryable, Identifiable, Serialize)]
@@ -25,7 +36,8 @@ pub struct Map {
     pub description: String,
     pub user_id: i32,
     pub created_at: NaiveDateTime,
-    pub location: Point,
+    #[serde(with = "crate::models::geo::serde_point")]
+    pub location: GeoPoint,
     pub public: bool,
     pub likes: i32,
     pub views: i32,
@@ -38,7 +50,7 @@ pub struct NewMap<'a> {
     pub title: &'a str,
     pub description: &'a str,
     pub user_id: i32,
-    pub location: Point,
+    pub location: GeoPoint,
     pub public: bool,
 }
 
@@ -166,8 +178,8 @@ pub struct RoutePoint {
     pub route_id: i32,
     pub title: String,
     pub description: String,
-    pub latitude: f64,
-    pub longitude: f64,
+    #[serde(with = "crate::models::geo::serde_point")]
+    pub location: GeoPoint,
     pub elevation: f64,
 }
 
@@ -178,8 +190,7 @@ pub struct NewRoutePoint<'a> {
     pub route_id: i32,
     pub title: &'a str,
     pub description: &'a str,
-    pub latitude: f64,
-    pub longitude: f64,
+    pub location: GeoPoint,
     pub elevation: f64,
 }
 
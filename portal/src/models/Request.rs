@@ -1,9 +1,10 @@
 
 
 use chrono::NaiveDateTime;
-use diesel::sql_types::{Integer, Text};
-use geo_types::Point;
+use diesel::prelude::*;
+use crate::models::geo::GeoPoint;
 use serde::{Deserialize, Serialize};
+use crate::errors::Error;
 
 #[derive(Debug, Queryable, Serialize, Deserialize)]
 pub struct Request {
@@ -16,14 +17,18 @@ pub struct Request {
     pub assigned_to: Option<i32>,
     pub priority: i16,
     pub due_date: Option<chrono::NaiveDate>,
-    pub location: Option<Point<f64>>,
+    #[serde(with = "crate::models::geo::serde_point_opt")]
+    pub location: Option<GeoPoint>,
     pub contact_name: Option<String>,
     pub contact_phone: Option<String>,
     pub contact_email: Option<String>,
     pub comments: Option<String>,
+    pub street_id: Option<i32>,
+    pub address: Option<String>,
+    pub district_id: Option<i32>,   // Район по координатам или улице, проставляется базой
 }
 
-#[derive(Debug, Insertable, Serialize, Deserialize)]
+#[derive(Debug, Insertable, AsChangeset, Serialize, Deserialize)]
 #[table_name = "requests"]
 pub struct NewRequest {
     pub user_id: i32,
@@ -34,11 +39,14 @@ pub struct NewRequest {
     pub assigned_to: Option<i32>,
     pub priority: i16,
     pub due_date: Option<chrono::NaiveDate>,
-    pub location: Option<Point<f64>>,
+    #[serde(with = "crate::models::geo::serde_point_opt")]
+    pub location: Option<GeoPoint>,
     pub contact_name: Option<String>,
     pub contact_phone: Option<String>,
     pub contact_email: Option<String>,
     pub comments: Option<String>,
+    pub street_id: Option<i32>,
+    pub address: Option<String>,
 }
 
 
@@ -48,23 +56,26 @@ impl Request {
 pub fn create_request(
     conn: &diesel::PgConnection,
     new_request: &NewRequest,
-) -> Result<Request, diesel::result::Error> {
+) -> Result<Request, Error> {
     diesel::insert_into(requests::table)
         .values(new_request)
         .get_result(conn)
+        .map_err(Error::from)
 }
 
 // Метод для получения списка всех Заявок
-pub fn get_all_requests(conn: &diesel::PgConnection) -> Result<Vec<Request>, diesel::result::Error> {
+pub fn get_all_requests(conn: &diesel::PgConnection) -> Result<Vec<Request>, Error> {
     requests::table.load::<Request>(conn)
+        .map_err(Error::from)
 }
 
 // Метод для получения Заявки по её ID
 pub fn get_request_by_id(
     conn: &diesel::PgConnection,
     request_id: i32,
-) -> Result<Request, diesel::result::Error> {
+) -> Result<Request, Error> {
     requests::table.find(request_id).first(conn)
+        .map_err(Error::from)
 }
 
 // Метод для обновления Заявки по её ID
@@ -72,17 +83,18 @@ pub fn update_request_by_id(
     conn: &diesel::PgConnection,
     request_id: i32,
     updated_request: &NewRequest,
-) -> Result<Request, diesel::result::Error> {
+) -> Result<Request, Error> {
     diesel::update(requests::table.find(request_id))
         .set(updated_request)
         .get_result(conn)
+        .map_err(Error::from)
 }
 
 // Метод для удаления Заявки по её ID
 pub fn delete_request_by_id(
     conn: &diesel::PgConnection,
     request_id: i32,
-) -> Result<(), diesel::result::Error> {
+) -> Result<(), Error> {
     diesel::delete(requests::table.find(request_id)).execute(conn)?;
     Ok(())
 }
@@ -91,47 +103,51 @@ pub fn delete_request_by_id(
 pub fn get_requests_by_user_id(
     conn: &diesel::PgConnection,
     user_id: i32,
-) -> Result<Vec<Request>, diesel::result::Error> {
+) -> Result<Vec<Request>, Error> {
     requests::table
         .filter(requests::user_id.eq(user_id))
         .load::<Request>(conn)
+        .map_err(Error::from)
 }
 
 // Метод для получения всех Заявок с определенным статусом
 pub fn get_requests_by_status(
     conn: &diesel::PgConnection,
     status: &str,
-) -> Result<Vec<Request>, diesel::result::Error> {
+) -> Result<Vec<Request>, Error> {
     requests::table
         .filter(requests::status.eq(status))
         .load::<Request>(conn)
+        .map_err(Error::from)
 }
 
 // Метод для получения всех Заявок, которые назначены определенному сотруднику
 pub fn get_requests_by_assigned_to(
     conn: &diesel::PgConnection,
     assigned_to: i32,
-) -> Result<Vec<Request>, diesel::result::Error> {
+) -> Result<Vec<Request>, Error> {
     requests::table
         .filter(requests::assigned_to.eq(assigned_to))
         .load::<Request>(conn)
+        .map_err(Error::from)
 }
 
 // Метод для получения всех Заявок с определенным приоритетом
 pub fn get_requests_by_priority(
     conn: &diesel::PgConnection,
     priority: i16,
-) -> Result<Vec<Request>, diesel::result::Error> {
+) -> Result<Vec<Request>, Error> {
     requests::table
         .filter(requests::priority.eq(priority))
         .load::<Request>(conn)
+        .map_err(Error::from)
 }
 
 // Метод для поиска Заявок по ключевым словам в описании или комментариях
 pub fn search_requests_by_keyword(
     conn: &diesel::PgConnection,
     keyword: &str,
-) -> Result<Vec<Request>, diesel::result::Error> {
+) -> Result<Vec<Request>, Error> {
     requests::table
         .filter(
             requests::description
@@ -139,33 +155,36 @@ pub fn search_requests_by_keyword(
                 .or(requests::comments.like(format!("%{}%", keyword))),
         )
         .load::<Request>(conn)
+        .map_err(Error::from)
 }
 
 // Метод для получения количества Заявок в определенном статусе
 pub fn count_requests_by_status(
     conn: &diesel::PgConnection,
     status: &str,
-) -> Result<i64, diesel::result::Error> {
+) -> Result<i64, Error> {
     requests::table
         .filter(requests::status.eq(status))
         .count()
         .get_result(conn)
+        .map_err(Error::from)
 }
 
 // Метод для получения всех Заявок, отсортированных по дате подачи
 pub fn get_requests_sorted_by_date(
     conn: &diesel::PgConnection,
-) -> Result<Vec<Request>, diesel::result::Error> {
+) -> Result<Vec<Request>, Error> {
     requests::table
         .order(requests::request_date)
         .load::<Request>(conn)
+        .map_err(Error::from)
 }
 
 // Метод для удаления всех Заявок, у которых срок выполнения истек
 pub fn delete_expired_requests(
     conn: &diesel::PgConnection,
     current_date: chrono::NaiveDate,
-) -> Result<(), diesel::result::Error> {
+) -> Result<(), Error> {
     diesel::delete(requests::table.filter(requests::due_date.le(current_date)))
         .execute(conn)?;
     Ok(())
@@ -173,30 +192,33 @@ pub fn delete_expired_requests(
 // Метод для получения списка всех уникальных пользователей, которые создали Заявки
 pub fn get_unique_request_users(
     conn: &diesel::PgConnection,
-) -> Result<Vec<String>, diesel::result::Error> {
-    requests::table
+) -> Result<Vec<String>, Error> {
+    let names = requests::table
         .select(requests::contact_name)
         .distinct()
         .filter(requests::contact_name.is_not_null())
-        .load::<String>(conn)
+        .load::<Option<String>>(conn)?;
+    Ok(names.into_iter().flatten().collect())
 }
 
 // Метод для получения списка всех Заявок, у которых есть контактный email
 pub fn get_requests_with_email(
     conn: &diesel::PgConnection,
-) -> Result<Vec<Request>, diesel::result::Error> {
+) -> Result<Vec<Request>, Error> {
     requests::table
         .filter(requests::contact_email.is_not_null())
         .load::<Request>(conn)
+        .map_err(Error::from)
 }
 
 // Метод для получения списка всех Заявок с определенным типом услуги
 pub fn get_requests_by_service_type(
     conn: &diesel::PgConnection,
-    service_type: &str,
-) -> Result<Vec<Request>, diesel::result::Error> {
+    service_id: i32,
+) -> Result<Vec<Request>, Error> {
     requests::table
-        .filter(requests::service_id.eq(service_type))
+        .filter(requests::service_id.eq(service_id))
         .load::<Request>(conn)
+        .map_err(Error::from)
 }
 }
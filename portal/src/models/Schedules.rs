@@ -3,13 +3,16 @@
 
 use chrono::{NaiveDate, NaiveTime};
 use diesel::prelude::*;
-use diesel::PgConnection;
-use diesel::ExpressionMethods;
-use diesel::QueryDsl;
-use diesel::RunQueryDsl;
+use crate::schema::{
+    carrier_information,
+    price_and_tickets,
+    schedules,
+    transport_vehicles,
+}; // Таблицы из schema.rs
+use crate::errors::Error;
 
 // Определение структуры для транспортного средства---------------------------------------------------------------------------
-#[derive(Debug, Queryable, Identifiable, Associations)]
+#[derive(Debug, Queryable, Identifiable)]
 pub struct TransportVehicle {
     pub id: i32,
     pub vehicle_name: String,
@@ -21,6 +24,7 @@ pub struct TransportVehicle {
 
 // Определение структуры для расписания---------------------------------------------------------------------------------------
 #[derive(Debug, Queryable, Identifiable, Associations)]
+#[belongs_to(TransportVehicle, foreign_key = "vehicle_id")]
 pub struct Schedule {
     pub id: i32,
     pub vehicle_id: i32,
@@ -35,6 +39,8 @@ pub struct Schedule {
 
 // Определение структуры для цен и билетов-------------------------------------------------------------------------------------
 #[derive(Debug, Queryable, Identifiable, Associations)]
+#[belongs_to(Schedule)]
+#[table_name = "price_and_tickets"]
 pub struct PriceAndTicket {
     pub id: i32,
     pub schedule_id: i32,
@@ -44,6 +50,8 @@ pub struct PriceAndTicket {
 
 // Определение структуры для информации о перевозчике--------------------------------------------------------------------------
 #[derive(Debug, Queryable, Identifiable, Associations)]
+#[belongs_to(TransportVehicle, foreign_key = "vehicle_id")]
+#[table_name = "carrier_information"]
 pub struct CarrierInformation {
     pub id: i32,
     pub vehicle_id: i32,
@@ -64,6 +72,17 @@ pub struct NewTransportVehicle {
     pub route_number: Option<String>,
 }
 
+// Изменение транспортного средства: None - поле не меняется
+#[derive(Debug, AsChangeset)]
+#[table_name = "transport_vehicles"]
+pub struct UpdateTransportVehicle {
+    pub vehicle_name: Option<String>,
+    pub vehicle_description: Option<String>,
+    pub carrier_id: Option<i32>,
+    pub model: Option<String>,
+    pub route_number: Option<Option<String>>,
+}
+
 impl NewTransportVehicle {
     // Конструктор для создания нового транспортного средства
     pub fn new(
@@ -82,56 +101,48 @@ impl NewTransportVehicle {
         }
     }
     // Создание нового транспортного средства
-    pub fn create(new_vehicle: &NewTransportVehicle, connection: &PgConnection) -> Result<Self, diesel::result::Error> {
-        use crate::schema::transport_vehicles;
-
+    pub fn create(new_vehicle: &NewTransportVehicle, connection: &PgConnection) -> Result<TransportVehicle, Error> {
         diesel::insert_into(transport_vehicles::table)
             .values(new_vehicle)
             .get_result(connection)
+            .map_err(Error::from)
     }
 }
 
 impl TransportVehicle {
     // Поиск транспортного средства по ID
-    pub fn find_by_id(id: i32, connection: &PgConnection) -> Result<Option<Self>, diesel::result::Error> {
-        use crate::schema::transport_vehicles::dsl::*;
-
-        transport_vehicles.filter(id.eq(id)).first(connection).optional()
+    pub fn find_by_id(id: i32, connection: &PgConnection) -> Result<Option<Self>, Error> {
+        transport_vehicles::table.find(id).first(connection).optional()
+            .map_err(Error::from)
     }
 
     // Поиск всех транспортных средств
-    pub fn find_all(connection: &PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
-        use crate::schema::transport_vehicles::dsl::*;
-
-        transport_vehicles.load(connection)
+    pub fn find_all(connection: &PgConnection) -> Result<Vec<Self>, Error> {
+        transport_vehicles::table.load(connection)
+            .map_err(Error::from)
     }
 
     // Поиск транспортных средств по названию
-    pub fn find_by_name(name: &str, connection: &PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
-        use crate::schema::transport_vehicles::dsl::*;
-
-        transport_vehicles.filter(vehicle_name.eq(name)).load(connection)
+    pub fn find_by_name(name: &str, connection: &PgConnection) -> Result<Vec<Self>, Error> {
+        transport_vehicles::table.filter(transport_vehicles::vehicle_name.eq(name)).load(connection)
+            .map_err(Error::from)
     }
 
         // Обновление информации о транспортном средстве
-    pub fn update(id: i32, updated_vehicle: &UpdateTransportVehicle, connection: &PgConnection) -> Result<Self, diesel::result::Error> {
-        use crate::schema::transport_vehicles::dsl::*;
-
-        diesel::update(transport_vehicles.filter(id.eq(id)))
+    pub fn update(id: i32, updated_vehicle: &UpdateTransportVehicle, connection: &PgConnection) -> Result<Self, Error> {
+        diesel::update(transport_vehicles::table.find(id))
             .set(updated_vehicle)
             .get_result(connection)
+            .map_err(Error::from)
     }
 
     // Удаление транспортного средства по ID
-    pub fn delete(id: i32, connection: &PgConnection) -> Result<(), diesel::result::Error> {
-        use crate::schema::transport_vehicles::dsl::*;
-
-        diesel::delete(transport_vehicles.filter(id.eq(id))).execute(connection)?;
+    pub fn delete(id: i32, connection: &PgConnection) -> Result<(), Error> {
+        diesel::delete(transport_vehicles::table.find(id)).execute(connection)?;
 
         Ok(())
     }
 }
-use chrono::{NaiveDate, NaiveTime};
 
 // Определение структуры для создания нового расписания
 #[derive(Debug, Insertable)]
@@ -147,8 +158,22 @@ pub struct NewSchedule {
     pub arrival_time: NaiveTime,
 }
 
+// Изменение расписания: None - поле не меняется
+#[derive(Debug, AsChangeset)]
+#[table_name = "schedules"]
+pub struct UpdateSchedule {
+    pub departure_date: Option<NaiveDate>,
+    pub arrival_date: Option<NaiveDate>,
+    pub departure_location: Option<String>,
+    pub arrival_location: Option<String>,
+    pub intermediate_stops: Option<Option<String>>,
+    pub departure_time: Option<NaiveTime>,
+    pub arrival_time: Option<NaiveTime>,
+}
+
 impl NewSchedule {
     // Конструктор для создания нового расписания
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         vehicle_id: i32,
         departure_date: NaiveDate,
@@ -171,12 +196,11 @@ impl NewSchedule {
         }
     }
     // Создание нового расписания
-    pub fn create(new_schedule: &NewSchedule, connection: &PgConnection) -> Result<Self, diesel::result::Error> {
-        use crate::schema::schedules;
-
+    pub fn create(new_schedule: &NewSchedule, connection: &PgConnection) -> Result<Schedule, Error> {
         diesel::insert_into(schedules::table)
             .values(new_schedule)
             .get_result(connection)
+            .map_err(Error::from)
     }
     
 }
@@ -185,24 +209,21 @@ impl NewSchedule {
 
 impl Schedule {
     // Поиск расписания по ID
-    pub fn find_by_id(id: i32, connection: &PgConnection) -> Result<Option<Self>, diesel::result::Error> {
-        use crate::schema::schedules::dsl::*;
-
-        schedules.filter(id.eq(id)).first(connection).optional()
+    pub fn find_by_id(id: i32, connection: &PgConnection) -> Result<Option<Self>, Error> {
+        schedules::table.find(id).first(connection).optional()
+            .map_err(Error::from)
     }
 
     // Поиск всех расписаний для определенного транспортного средства
-    pub fn find_by_vehicle_id(vehicle_id: i32, connection: &PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
-        use crate::schema::schedules::dsl::*;
-
-        schedules.filter(vehicle_id.eq(vehicle_id)).load(connection)
+    pub fn find_by_vehicle_id(vehicle_id: i32, connection: &PgConnection) -> Result<Vec<Self>, Error> {
+        schedules::table.filter(schedules::vehicle_id.eq(vehicle_id)).load(connection)
+            .map_err(Error::from)
     }
 
     // Поиск расписаний по дате отправления
-    pub fn find_by_departure_date(date: NaiveDate, connection: &PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
-        use crate::schema::schedules::dsl::*;
-
-        schedules.filter(departure_date.eq(date)).load(connection)
+    pub fn find_by_departure_date(date: NaiveDate, connection: &PgConnection) -> Result<Vec<Self>, Error> {
+        schedules::table.filter(schedules::departure_date.eq(date)).load(connection)
+            .map_err(Error::from)
     }
 
     // Поиск расписаний по местам отправления и прибытия
@@ -210,30 +231,27 @@ impl Schedule {
         departure_location: &str,
         arrival_location: &str,
         connection: &PgConnection,
-    ) -> Result<Vec<Self>, diesel::result::Error> {
-        use crate::schema::schedules::dsl::*;
-
-        schedules
-            .filter(departure_location.eq(departure_location).and(arrival_location.eq(arrival_location)))
+    ) -> Result<Vec<Self>, Error> {
+        schedules::table
+            .filter(schedules::departure_location.eq(departure_location))
+            .filter(schedules::arrival_location.eq(arrival_location))
             .load(connection)
+            .map_err(Error::from)
     }
 
     
 
     // Обновление информации о расписании
-    pub fn update(id: i32, updated_schedule: &UpdateSchedule, connection: &PgConnection) -> Result<Self, diesel::result::Error> {
-        use crate::schema::schedules::dsl::*;
-
-        diesel::update(schedules.filter(id.eq(id)))
+    pub fn update(id: i32, updated_schedule: &UpdateSchedule, connection: &PgConnection) -> Result<Self, Error> {
+        diesel::update(schedules::table.find(id))
             .set(updated_schedule)
             .get_result(connection)
+            .map_err(Error::from)
     }
 
     // Удаление расписания по ID
-    pub fn delete(id: i32, connection: &PgConnection) -> Result<(), diesel::result::Error> {
-        use crate::schema::schedules::dsl::*;
-
-        diesel::delete(schedules.filter(id.eq(id))).execute(connection)?;
+    pub fn delete(id: i32, connection: &PgConnection) -> Result<(), Error> {
+        diesel::delete(schedules::table.find(id)).execute(connection)?;
 
         Ok(())
     }
@@ -249,6 +267,14 @@ pub struct NewPriceAndTicket {
     pub ticket_availability: bool,
 }
 
+// Изменение цены и наличия билетов: None - поле не меняется
+#[derive(Debug, AsChangeset)]
+#[table_name = "price_and_tickets"]
+pub struct UpdatePriceAndTicket {
+    pub ticket_price: Option<f64>,
+    pub ticket_availability: Option<bool>,
+}
+
 impl NewPriceAndTicket {
     // Конструктор для создания новой информации о ценах и билетах
     pub fn new(schedule_id: i32, ticket_price: f64, ticket_availability: bool) -> Self {
@@ -259,53 +285,46 @@ impl NewPriceAndTicket {
         }
     }
     // Создание новой информации о цене и билетах
-    pub fn create(new_price_and_ticket: &NewPriceAndTicket, connection: &PgConnection) -> Result<Self, diesel::result::Error> {
-        use crate::schema::price_and_tickets;
-
+    pub fn create(new_price_and_ticket: &NewPriceAndTicket, connection: &PgConnection) -> Result<PriceAndTicket, Error> {
         diesel::insert_into(price_and_tickets::table)
             .values(new_price_and_ticket)
             .get_result(connection)
+            .map_err(Error::from)
     }
 }
 
 impl PriceAndTicket {
     // Поиск информации о цене и билетах по ID расписания
-    pub fn find_by_schedule_id(schedule_id: i32, connection: &PgConnection) -> Result<Option<Self>, diesel::result::Error> {
-        use crate::schema::price_and_tickets::dsl::*;
-
-        price_and_tickets.filter(schedule_id.eq(schedule_id)).first(connection).optional()
+    pub fn find_by_schedule_id(schedule_id: i32, connection: &PgConnection) -> Result<Option<Self>, Error> {
+        price_and_tickets::table.filter(price_and_tickets::schedule_id.eq(schedule_id)).first(connection).optional()
+            .map_err(Error::from)
     }
 
     // Поиск всех доступных билетов
-    pub fn find_available_tickets(connection: &PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
-        use crate::schema::price_and_tickets::dsl::*;
-
-        price_and_tickets.filter(ticket_availability.eq(true)).load(connection)
+    pub fn find_available_tickets(connection: &PgConnection) -> Result<Vec<Self>, Error> {
+        price_and_tickets::table.filter(price_and_tickets::ticket_availability.eq(true)).load(connection)
+            .map_err(Error::from)
     }
 
     // Поиск билетов по цене
-    pub fn find_by_ticket_price(price: f64, connection: &PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
-        use crate::schema::price_and_tickets::dsl::*;
-
-        price_and_tickets.filter(ticket_price.eq(price)).load(connection)
+    pub fn find_by_ticket_price(price: f64, connection: &PgConnection) -> Result<Vec<Self>, Error> {
+        price_and_tickets::table.filter(price_and_tickets::ticket_price.eq(price)).load(connection)
+            .map_err(Error::from)
     }
 
     
 
     // Обновление информации о цене и билетах
-    pub fn update(id: i32, updated_price_and_ticket: &UpdatePriceAndTicket, connection: &PgConnection) -> Result<Self, diesel::result::Error> {
-        use crate::schema::price_and_tickets::dsl::*;
-
-        diesel::update(price_and_tickets.filter(id.eq(id)))
+    pub fn update(id: i32, updated_price_and_ticket: &UpdatePriceAndTicket, connection: &PgConnection) -> Result<Self, Error> {
+        diesel::update(price_and_tickets::table.find(id))
             .set(updated_price_and_ticket)
             .get_result(connection)
+            .map_err(Error::from)
     }
 
     // Удаление информации о цене и билетах по ID
-    pub fn delete(id: i32, connection: &PgConnection) -> Result<(), diesel::result::Error> {
-        use crate::schema::price_and_tickets::dsl::*;
-
-        diesel::delete(price_and_tickets.filter(id.eq(id))).execute(connection)?;
+    pub fn delete(id: i32, connection: &PgConnection) -> Result<(), Error> {
+        diesel::delete(price_and_tickets::table.find(id)).execute(connection)?;
 
         Ok(())
     }
@@ -321,6 +340,14 @@ pub struct NewCarrierInformation {
     pub carrier_contact_information: String,
 }
 
+// Изменение информации о перевозчике: None - поле не меняется
+#[derive(Debug, AsChangeset)]
+#[table_name = "carrier_information"]
+pub struct UpdateCarrierInformation {
+    pub carrier_name: Option<String>,
+    pub carrier_contact_information: Option<String>,
+}
+
 impl NewCarrierInformation {
     // Конструктор для создания новой информации о перевозчике
     pub fn new(vehicle_id: i32, carrier_name: String, carrier_contact_information: String) -> Self {
@@ -331,46 +358,40 @@ impl NewCarrierInformation {
         }
     }
     // Создание новой информации о перевозчике
-    pub fn create(new_carrier_info: &NewCarrierInformation, connection: &PgConnection) -> Result<Self, diesel::result::Error> {
-        use crate::schema::carrier_information;
-
+    pub fn create(new_carrier_info: &NewCarrierInformation, connection: &PgConnection) -> Result<CarrierInformation, Error> {
         diesel::insert_into(carrier_information::table)
             .values(new_carrier_info)
             .get_result(connection)
+            .map_err(Error::from)
     }
 }
 
 impl CarrierInformation {
     // Поиск информации о перевозчике по ID транспортного средства
-    pub fn find_by_vehicle_id(vehicle_id: i32, connection: &PgConnection) -> Result<Option<Self>, diesel::result::Error> {
-        use crate::schema::carrier_information::dsl::*;
-
-        carrier_information.filter(vehicle_id.eq(vehicle_id)).first(connection).optional()
+    pub fn find_by_vehicle_id(vehicle_id: i32, connection: &PgConnection) -> Result<Option<Self>, Error> {
+        carrier_information::table.filter(carrier_information::vehicle_id.eq(vehicle_id)).first(connection).optional()
+            .map_err(Error::from)
     }
 
     // Поиск информации о перевозчике по имени
-    pub fn find_by_carrier_name(name: &str, connection: &PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
-        use crate::schema::carrier_information::dsl::*;
-
-        carrier_information.filter(carrier_name.eq(name)).load(connection)
+    pub fn find_by_carrier_name(name: &str, connection: &PgConnection) -> Result<Vec<Self>, Error> {
+        carrier_information::table.filter(carrier_information::carrier_name.eq(name)).load(connection)
+            .map_err(Error::from)
     }
 
     
 
     // Обновление информации о перевозчике
-    pub fn update(id: i32, updated_carrier_info: &UpdateCarrierInformation, connection: &PgConnection) -> Result<Self, diesel::result::Error> {
-        use crate::schema::carrier_information::dsl::*;
-
-        diesel::update(carrier_information.filter(id.eq(id)))
+    pub fn update(id: i32, updated_carrier_info: &UpdateCarrierInformation, connection: &PgConnection) -> Result<Self, Error> {
+        diesel::update(carrier_information::table.find(id))
             .set(updated_carrier_info)
             .get_result(connection)
+            .map_err(Error::from)
     }
 
     // Удаление информации о перевозчике по ID
-    pub fn delete(id: i32, connection: &PgConnection) -> Result<(), diesel::result::Error> {
-        use crate::schema::carrier_information::dsl::*;
-
-        diesel::delete(carrier_information.filter(id.eq(id))).execute(connection)?;
+    pub fn delete(id: i32, connection: &PgConnection) -> Result<(), Error> {
+        diesel::delete(carrier_information::table.find(id)).execute(connection)?;
 
         Ok(())
     }
@@ -1,60 +1,198 @@
-use chrono::NaiveDateTime; // Для работы с датой и временем
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime}; // Для работы с датой и временем
 use diesel::prelude::*; // Для работы с базой данных
+use diesel::insert_into; // Вставка записей
 use serde::{Deserialize, Serialize}; // Для сериализации и десериализации данных
+use crate::schema::{
+    event_themes,
+    events,
+    user_events,
+    user_event_history,
+    event_moderators,
+    event_calendar,
+    event_recurrences,
+    event_recurrence_exceptions,
+}; // Таблицы из schema.rs
+use crate::errors::Error; // Общий тип ошибки приложения
+use crate::models::geo::{GeoPoint, LatLon}; // Координаты на карте
 
 
 //-------------------------------------------------------------------------------------
 // Структура для таблицы "event_themes"
-#[derive(Debug, Serialize, Deserialize, Queryable)]
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable)]
 pub struct EventTheme {
     pub id: i32, // Идентификатор тематики
     pub name: String, // Название тематики
     pub parent_id: Option<i32>, // Идентификатор родительской тематики
 }
 
-// Структура для создания новой тематики
-#[derive(Debug, Deserialize, Insertable)]
+// Структура для создания и изменения тематики
+#[derive(Debug, Deserialize, Insertable, AsChangeset)]
 #[table_name = "event_themes"]
+#[changeset_options(treat_none_as_null = "true")]
 pub struct NewEventTheme {
     pub name: String,
     pub parent_id: Option<i32>,
 }
 
+// Узел дерева тематик
+#[derive(Debug, Serialize)]
+pub struct EventThemeNode {
+    pub id: i32,
+    pub name: String,
+    pub parent_id: Option<i32>,
+    pub children: Vec<EventThemeNode>,
+}
+
+// Дочерние тематики parent_id (со всеми потомками) из полного списка тематик
+fn build_theme_nodes(themes: &[EventTheme], parent_id: Option<i32>) -> Vec<EventThemeNode> {
+    themes
+        .iter()
+        .filter(|t| t.parent_id == parent_id)
+        .map(|t| EventThemeNode {
+            id: t.id,
+            name: t.name.clone(),
+            parent_id: t.parent_id,
+            children: build_theme_nodes(themes, Some(t.id)),
+        })
+        .collect()
+}
+
 
 impl EventTheme {
     // Метод для создания новой тематики
     pub fn create_new_theme(
         conn: &PgConnection,
         new_theme: NewEventTheme,
-    ) -> QueryResult<EventTheme> {
+    ) -> Result<EventTheme, Error> {
         diesel::insert_into(event_themes::table)
             .values(&new_theme)
             .get_result(conn)
+            .map_err(Error::from)
+    }
+
+    // Метод для получения тематики по идентификатору
+    pub fn get_theme_by_id(conn: &PgConnection, theme_id: i32) -> Result<EventTheme, Error> {
+        event_themes::table
+            .find(theme_id)
+            .first(conn)
+            .map_err(Error::from)
+    }
+
+    // Метод для получения всех тематик, отсортированных по названию.
+    // Тематик немного, поэтому дерево строится в памяти.
+    pub fn get_all_themes(conn: &PgConnection) -> Result<Vec<EventTheme>, Error> {
+        event_themes::table
+            .order(event_themes::name.asc())
+            .load(conn)
+            .map_err(Error::from)
+    }
+
+    // Метод для получения всего дерева тематик
+    pub fn get_theme_tree(conn: &PgConnection) -> Result<Vec<EventThemeNode>, Error> {
+        let themes = EventTheme::get_all_themes(conn)?;
+        Ok(build_theme_nodes(&themes, None))
+    }
+
+    // Метод для получения поддерева тематики (дочерние тематики всех уровней)
+    pub fn get_subtree(&self, conn: &PgConnection) -> Result<Vec<EventThemeNode>, Error> {
+        let themes = EventTheme::get_all_themes(conn)?;
+        Ok(build_theme_nodes(&themes, Some(self.id)))
+    }
+
+    // Метод для получения цепочки тематик от корня до текущей включительно
+    pub fn get_breadcrumbs(&self, conn: &PgConnection) -> Result<Vec<EventTheme>, Error> {
+        let themes = EventTheme::get_all_themes(conn)?;
+        let mut result = vec![self.clone()];
+        let mut parent_id = self.parent_id;
+        while let Some(id) = parent_id {
+            // защита от цикла, если он все же оказался в базе
+            if result.iter().any(|t| t.id == id) {
+                break;
+            }
+            match themes.iter().find(|t| t.id == id) {
+                Some(parent) => {
+                    parent_id = parent.parent_id;
+                    result.push(parent.clone());
+                },
+                None => break,
+            }
+        }
+        result.reverse();
+        Ok(result)
+    }
+
+    // Метод для получения идентификаторов тематики и всех ее потомков
+    pub fn get_descendant_ids(conn: &PgConnection, theme_id: i32) -> Result<Vec<i32>, Error> {
+        let themes = EventTheme::get_all_themes(conn)?;
+        let mut result = vec![theme_id];
+        let mut index = 0;
+        while index < result.len() {
+            let current = result[index];
+            for theme in themes.iter().filter(|t| t.parent_id == Some(current)) {
+                if !result.contains(&theme.id) {
+                    result.push(theme.id);
+                }
+            }
+            index += 1;
+        }
+        Ok(result)
     }
 
-    // Метод для обновления информации о тематике
+    // Метод для обновления информации о тематике. Перенос тематики внутрь
+    // ее же поддерева запрещен: получился бы цикл. Таблица блокируется на
+    // время проверки, чтобы два одновременных переноса не создали цикл.
     pub fn update_theme_info(
         conn: &PgConnection,
         theme_id: i32,
         updated_theme: NewEventTheme,
-    ) -> QueryResult<EventTheme> {
-        diesel::update(event_themes::table.find(theme_id))
-            .set(&updated_theme)
-            .get_result(conn)
+    ) -> Result<EventTheme, Error> {
+        conn.transaction(|| {
+            diesel::sql_query("LOCK TABLE event_themes IN SHARE ROW EXCLUSIVE MODE").execute(conn)?;
+            EventTheme::get_theme_by_id(conn, theme_id)?;
+            if let Some(parent_id) = updated_theme.parent_id {
+                if EventTheme::get_descendant_ids(conn, theme_id)?.contains(&parent_id) {
+                    return Err(Error::Conflict("theme cannot be moved into its own subtree".to_string()));
+                }
+                EventTheme::get_theme_by_id(conn, parent_id)?;
+            }
+            diesel::update(event_themes::table.find(theme_id))
+                .set(&updated_theme)
+                .get_result(conn)
+                .map_err(Error::from)
+        })
     }
 
-    // Метод для удаления тематики по идентификатору
-    pub fn delete_theme_by_id(conn: &PgConnection, theme_id: i32) -> QueryResult<()> {
-        diesel::delete(event_themes::table.find(theme_id)).execute(conn)?;
-        Ok(())
+    // Метод для удаления тематики по идентификатору. Тематику с дочерними
+    // тематиками или событиями удалить нельзя - их сначала нужно перенести.
+    pub fn delete_theme_by_id(conn: &PgConnection, theme_id: i32) -> Result<(), Error> {
+        conn.transaction(|| {
+            EventTheme::get_theme_by_id(conn, theme_id)?;
+            let children = event_themes::table
+                .filter(event_themes::parent_id.eq(theme_id))
+                .count()
+                .get_result::<i64>(conn)?;
+            if children > 0 {
+                return Err(Error::Conflict("theme has child themes".to_string()));
+            }
+            let events_count = events::table
+                .filter(events::theme_id.eq(theme_id))
+                .count()
+                .get_result::<i64>(conn)?;
+            if events_count > 0 {
+                return Err(Error::Conflict("theme has events".to_string()));
+            }
+            diesel::delete(event_themes::table.find(theme_id)).execute(conn)?;
+            Ok(())
+        })
     }
 
     // Дополнительный метод для поиска тематики по имени
-    pub fn find_theme_by_name(conn: &PgConnection, name: &str) -> QueryResult<Option<EventTheme>> {
+    pub fn find_theme_by_name(conn: &PgConnection, name: &str) -> Result<Option<EventTheme>, Error> {
         event_themes::table
             .filter(event_themes::name.eq(name))
             .first(conn)
             .optional()
+            .map_err(Error::from)
     }
 }
 
@@ -79,6 +217,36 @@ pub struct Event {
     pub is_published: bool, // Флаг публикации события
     pub created_at: NaiveDateTime, // Дата и время создания события
     pub updated_at: NaiveDateTime, // Дата и время последнего обновления события
+    pub theme_id: Option<i32>, // Идентификатор тематики события
+    pub user_id: Option<i32>, // Идентификатор пользователя, создавшего событие
+    pub cancelled_at: Option<NaiveDateTime>, // Когда событие отменено
+    pub cancel_reason: Option<String>, // Причина отмены
+    #[serde(with = "crate::models::geo::serde_point_opt")]
+    pub coordinates: Option<GeoPoint>, // Координаты места проведения
+    pub city_id: Option<i32>, // Город, если координаты не указаны
+}
+
+// Изменение одного поля события: для уведомлений посетителям
+#[derive(Debug, Clone, Serialize)]
+pub struct EventChange {
+    pub field: &'static str, // Название поля
+    pub old: serde_json::Value, // Значение до изменения
+    pub new: serde_json::Value, // Значение после изменения
+}
+
+// Поля, об изменении которых сообщается посетителям и подписчикам
+pub const NOTIFIABLE_EVENT_FIELDS: &[&str] = &[
+    "title", "date_start", "date_end", "location", "coordinates", "ticket_price", "currency",
+];
+
+fn push_change<T: PartialEq + Serialize>(changes: &mut Vec<EventChange>, field: &'static str, old: &T, new: &T) {
+    if old != new {
+        changes.push(EventChange {
+            field: field,
+            old: serde_json::to_value(old).unwrap_or(serde_json::Value::Null),
+            new: serde_json::to_value(new).unwrap_or(serde_json::Value::Null),
+        });
+    }
 }
 
 // Структура для создания нового события
@@ -98,6 +266,89 @@ pub struct NewEvent {
     pub contact_email: Option<String>,
     pub image: Option<String>,
     pub is_published: bool,
+    pub theme_id: Option<i32>,
+    pub user_id: Option<i32>,
+    #[serde(default, with = "crate::models::geo::serde_point_opt")]
+    pub coordinates: Option<GeoPoint>,
+    #[serde(default)]
+    pub city_id: Option<i32>,
+}
+
+// Структура для изменения события. Автора события поменять нельзя.
+#[derive(Debug, Deserialize, AsChangeset)]
+#[table_name = "events"]
+#[changeset_options(treat_none_as_null = "true")]
+pub struct EditEvent {
+    pub title: String,
+    pub description: String,
+    pub category: Option<String>,
+    pub date_start: NaiveDateTime,
+    pub date_end: NaiveDateTime,
+    pub location: Option<String>,
+    pub ticket_price: Option<f64>,
+    pub currency: Option<String>,
+    pub contact_name: Option<String>,
+    pub contact_phone: Option<String>,
+    pub contact_email: Option<String>,
+    pub image: Option<String>,
+    pub is_published: bool,
+    pub theme_id: Option<i32>,
+    #[serde(default, with = "crate::models::geo::serde_point_opt")]
+    pub coordinates: Option<GeoPoint>,
+    #[serde(default)]
+    pub city_id: Option<i32>,
+}
+
+impl EditEvent {
+    // Проверка данных события перед сохранением
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.title.trim().is_empty() {
+            return Err(Error::Validation("title is required".to_string()));
+        }
+        if self.date_end < self.date_start {
+            return Err(Error::Validation("date_end must not be earlier than date_start".to_string()));
+        }
+        if let Some(price) = self.ticket_price {
+            if price < 0.0 {
+                return Err(Error::Validation("ticket_price must not be negative".to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    // Новое событие из данных формы от имени пользователя
+    pub fn into_new_event(self, user_id: i32) -> NewEvent {
+        NewEvent {
+            title: self.title,
+            description: self.description,
+            category: self.category,
+            date_start: self.date_start,
+            date_end: self.date_end,
+            location: self.location,
+            ticket_price: self.ticket_price,
+            currency: self.currency,
+            contact_name: self.contact_name,
+            contact_phone: self.contact_phone,
+            contact_email: self.contact_email,
+            image: self.image,
+            is_published: self.is_published,
+            theme_id: self.theme_id,
+            user_id: Some(user_id),
+            coordinates: self.coordinates,
+            city_id: self.city_id,
+        }
+    }
+}
+
+// Фильтры для списка событий. Пустое поле - фильтр не применяется.
+#[derive(Debug, Default, Deserialize)]
+pub struct EventFilter {
+    pub category: Option<String>,
+    pub theme_ids: Option<Vec<i32>>, // Тематика вместе с дочерними
+    pub date_from: Option<NaiveDateTime>,
+    pub date_to: Option<NaiveDateTime>,
+    pub is_published: Option<bool>,
+    pub user_id: Option<i32>, // Только события автора
 }
 
 impl Event {
@@ -105,25 +356,100 @@ impl Event {
     pub fn create_new_event(
         conn: &PgConnection,
         new_event: NewEvent,
-    ) -> QueryResult<Event> {
+    ) -> Result<Event, Error> {
         diesel::insert_into(events::table)
             .values(&new_event)
             .get_result(conn)
+            .map_err(Error::from)
+    }
+
+    // Метод для получения события по идентификатору
+    pub fn get_event_by_id(conn: &PgConnection, event_id: i32) -> Result<Event, Error> {
+        events::table
+            .find(event_id)
+            .first(conn)
+            .map_err(Error::from)
     }
 
     // Метод для обновления информации о событии
+    // Возвращает событие после изменения и список измененных полей.
+    // Отмененное событие изменить нельзя.
     pub fn update_event_info(
         conn: &PgConnection,
         event_id: i32,
-        updated_event: NewEvent,
-    ) -> QueryResult<Event> {
-        diesel::update(events::table.find(event_id))
-            .set(&updated_event)
-            .get_result(conn)
+        updated_event: EditEvent,
+    ) -> Result<(Event, Vec<EventChange>), Error> {
+        conn.transaction(|| {
+            let old = events::table
+                .find(event_id)
+                .for_update()
+                .first::<Event>(conn)?;
+            if old.cancelled_at.is_some() {
+                return Err(Error::Conflict("event is cancelled".to_string()));
+            }
+            let new = diesel::update(events::table.find(event_id))
+                .set((
+                    &updated_event,
+                    events::updated_at.eq(chrono::Local::now().naive_local()),
+                ))
+                .get_result::<Event>(conn)?;
+            let changes = old.diff(&new);
+            Ok((new, changes))
+        })
+    }
+
+    // Поля, которые отличаются у двух версий события
+    pub fn diff(&self, new: &Event) -> Vec<EventChange> {
+        let mut changes = Vec::new();
+        push_change(&mut changes, "title", &self.title, &new.title);
+        push_change(&mut changes, "description", &self.description, &new.description);
+        push_change(&mut changes, "category", &self.category, &new.category);
+        push_change(&mut changes, "date_start", &self.date_start, &new.date_start);
+        push_change(&mut changes, "date_end", &self.date_end, &new.date_end);
+        push_change(&mut changes, "location", &self.location, &new.location);
+        push_change(&mut changes, "ticket_price", &self.ticket_price, &new.ticket_price);
+        push_change(&mut changes, "currency", &self.currency, &new.currency);
+        push_change(&mut changes, "contact_name", &self.contact_name, &new.contact_name);
+        push_change(&mut changes, "contact_phone", &self.contact_phone, &new.contact_phone);
+        push_change(&mut changes, "contact_email", &self.contact_email, &new.contact_email);
+        push_change(&mut changes, "image", &self.image, &new.image);
+        push_change(&mut changes, "is_published", &self.is_published, &new.is_published);
+        push_change(&mut changes, "theme_id", &self.theme_id, &new.theme_id);
+        push_change(&mut changes, "city_id", &self.city_id, &new.city_id);
+        push_change(
+            &mut changes,
+            "coordinates",
+            &self.coordinates.as_ref().map(LatLon::from),
+            &new.coordinates.as_ref().map(LatLon::from),
+        );
+        changes
+    }
+
+    // Отмена события. Событие остается в базе и по ссылке, но пропадает
+    // из списков ближайших событий и напоминаний. Повторная отмена - Conflict.
+    pub fn cancel_event(conn: &PgConnection, event_id: i32, reason: Option<String>) -> Result<Event, Error> {
+        let cancelled = diesel::update(
+            events::table
+                .find(event_id)
+                .filter(events::cancelled_at.is_null()),
+        )
+        .set((
+            events::cancelled_at.eq(chrono::Local::now().naive_local()),
+            events::cancel_reason.eq(reason),
+        ))
+        .get_result::<Event>(conn)
+        .optional()?;
+        match cancelled {
+            Some(event) => Ok(event),
+            None => {
+                Event::get_event_by_id(conn, event_id)?;
+                Err(Error::Conflict("event is already cancelled".to_string()))
+            },
+        }
     }
 
     // Метод для удаления события по идентификатору
-    pub fn delete_event_by_id(conn: &PgConnection, event_id: i32) -> QueryResult<()> {
+    pub fn delete_event_by_id(conn: &PgConnection, event_id: i32) -> Result<(), Error> {
         diesel::delete(events::table.find(event_id)).execute(conn)?;
         Ok(())
     }
@@ -132,31 +458,124 @@ impl Event {
     pub fn find_event_by_title(
         conn: &PgConnection,
         title: &str,
-    ) -> QueryResult<Option<Event>> {
+    ) -> Result<Option<Event>, Error> {
         events::table
             .filter(events::title.eq(title))
             .first(conn)
             .optional()
+            .map_err(Error::from)
+    }
+
+    // Метод для поиска событий по фильтрам с постраничным выводом.
+    // Возвращает события страницы и общее количество подходящих событий.
+    pub fn search(
+        conn: &PgConnection,
+        filter: &EventFilter,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<Event>, i64), Error> {
+        // один и тот же набор фильтров нужен и для выборки, и для подсчета
+        let filtered = || {
+            let mut query = events::table.into_boxed();
+            if let Some(ref category) = filter.category {
+                query = query.filter(events::category.eq(category.clone()));
+            }
+            if let Some(ref theme_ids) = filter.theme_ids {
+                query = query.filter(events::theme_id.eq_any(theme_ids.clone()));
+            }
+            if let Some(date_from) = filter.date_from {
+                query = query.filter(events::date_end.ge(date_from));
+            }
+            if let Some(date_to) = filter.date_to {
+                query = query.filter(events::date_start.le(date_to));
+            }
+            if let Some(is_published) = filter.is_published {
+                query = query.filter(events::is_published.eq(is_published));
+                // отмененные события в опубликованной афише не показываются
+                if is_published {
+                    query = query.filter(events::cancelled_at.is_null());
+                }
+            }
+            if let Some(user_id) = filter.user_id {
+                query = query.filter(events::user_id.eq(user_id));
+            }
+            query
+        };
+
+        let total = filtered().count().get_result::<i64>(conn)?;
+        let items = filtered()
+            .order((events::date_start.asc(), events::id.asc()))
+            .offset(offset)
+            .limit(limit)
+            .load::<Event>(conn)?;
+        Ok((items, total))
+    }
+
+    // Метод для получения опубликованных событий тематик
+    pub fn get_published_by_themes(conn: &PgConnection, theme_ids: &[i32]) -> Result<Vec<Event>, Error> {
+        events::table
+            .filter(events::theme_id.eq_any(theme_ids))
+            .filter(events::is_published.eq(true))
+            .order(events::date_start.asc())
+            .load(conn)
+            .map_err(Error::from)
+    }
+
+    // Метод для получения событий, на которые записан пользователь
+    pub fn get_attended_by_user(conn: &PgConnection, user_id: i32) -> Result<Vec<Event>, Error> {
+        events::table
+            .inner_join(event_attendees::table)
+            .filter(event_attendees::user_id.eq(user_id))
+            .filter(event_attendees::status.eq(ATTENDEE_CONFIRMED))
+            .select(events::all_columns)
+            .order(events::date_start.asc())
+            .load(conn)
+            .map_err(Error::from)
     }
 }
 
-//Метод для получения всех событий, начинающихся после определенной даты
+//Метод для получения страницы событий, начинающихся после определенной даты (по желанию - только в категории)
 
 pub fn get_upcoming_events(
     conn: &PgConnection,
     date: NaiveDateTime,
-) -> QueryResult<Vec<Event>> {
-    use crate::schema::events::dsl::*;
-    events.filter(date_start.gt(date)).load(conn)
+    category: Option<&str>,
+    offset: i64,
+    limit: i64,
+) -> Result<(Vec<Event>, i64), Error> {
+    let filtered = || {
+        let mut query = events::table
+            .filter(events::date_start.gt(date))
+            .filter(events::is_published.eq(true))
+            .filter(events::cancelled_at.is_null())
+            .into_boxed();
+        if let Some(category) = category {
+            query = query.filter(events::category.eq(category));
+        }
+        query
+    };
+
+    let total = filtered().count().get_result::<i64>(conn)?;
+    let items = filtered()
+        .order((events::date_start.asc(), events::id.asc()))
+        .offset(offset)
+        .limit(limit)
+        .load::<Event>(conn)?;
+    Ok((items, total))
 }
 
 //Метод для получения всех событий в определенной категории:
 pub fn get_events_in_category(
     conn: &PgConnection,
     category: &str,
-) -> QueryResult<Vec<Event>> {
-    use crate::schema::events::dsl::*;
-    events.filter(category.eq(category)).load(conn)
+) -> Result<Vec<Event>, Error> {
+    events::table
+        .filter(events::category.eq(category))
+        .filter(events::is_published.eq(true))
+        .filter(events::cancelled_at.is_null())
+        .order(events::date_start.asc())
+        .load(conn)
+        .map_err(Error::from)
 }
 
 //Метод для получения всех событий, созданных определенным пользователем
@@ -164,26 +583,30 @@ pub fn get_events_in_category(
 pub fn get_events_by_user(
     conn: &PgConnection,
     user_id: i32,
-) -> QueryResult<Vec<Event>> {
-    use crate::schema::events::dsl::*;
-    events.filter(user_id.eq(user_id)).load(conn)
+) -> Result<Vec<Event>, Error> {
+    events::table
+        .filter(events::user_id.eq(user_id))
+        .load(conn)
+        .map_err(Error::from)
 }
 
 //Метод для получения всех событий, отсортированных по дате начала в порядке возрастания
 
-pub fn get_events_sorted_by_date_asc(conn: &PgConnection) -> QueryResult<Vec<Event>> {
+pub fn get_events_sorted_by_date_asc(conn: &PgConnection) -> Result<Vec<Event>, Error> {
     use crate::schema::events::dsl::*;
     events.order(date_start.asc()).load(conn)
+        .map_err(Error::from)
 }
 
 //Метод для получения всех событий, отсортированных по цене билета в порядке возрастания
 
-pub fn get_events_sorted_by_ticket_price_asc(conn: &PgConnection) -> QueryResult<Vec<Event>> {
+pub fn get_events_sorted_by_ticket_price_asc(conn: &PgConnection) -> Result<Vec<Event>, Error> {
     use crate::schema::events::dsl::*;
     events
         .filter(ticket_price.is_not_null())
         .order(ticket_price.asc())
         .load(conn)
+        .map_err(Error::from)
 }
 
 //Метод для поиска всех событий, которые начинаются позже заданной даты и имеют определенную категорию
@@ -192,16 +615,22 @@ pub fn get_events_after_date_in_category(
     conn: &PgConnection,
     date: NaiveDateTime,
     category: &str,
-) -> QueryResult<Vec<Event>> {
-    use crate::schema::events::dsl::*;
-    events
-        .filter(date_start.gt(date))
-        .filter(category.eq(category))
+) -> Result<Vec<Event>, Error> {
+    events::table
+        .filter(events::date_start.gt(date))
+        .filter(events::category.eq(category))
         .load(conn)
+        .map_err(Error::from)
 }
 
 //-------------------------------------------------------------------------------------
 
+// Состояния заявки на событие от пользователя
+pub const USER_EVENT_DRAFT: &str = "draft"; // Черновик, автор может его менять
+pub const USER_EVENT_PENDING: &str = "pending"; // Отправлена на проверку
+pub const USER_EVENT_APPROVED: &str = "approved"; // Одобрена, по ней создано событие
+pub const USER_EVENT_REJECTED: &str = "rejected"; // Отклонена, автор может исправить и отправить снова
+
 // Структура для таблицы "user_events"
 #[derive(Debug, Serialize, Deserialize, Queryable)]
 pub struct UserEvent {
@@ -219,14 +648,13 @@ pub struct UserEvent {
     pub contact_phone: Option<String>, // Телефон контактного лица
     pub contact_email: Option<String>, // Email контактного лица
     pub image: Option<String>, // Ссылка на изображение события
-    pub is_published: bool, // Флаг публикации события
     pub created_at: NaiveDateTime, // Дата и время создания события
     pub updated_at: NaiveDateTime, // Дата и время последнего обновления события
+    pub status: String, // Состояние заявки (draft, pending, approved, rejected)
+    pub theme_id: Option<i32>, // Тематика события
+    pub event_id: Option<i32>, // Событие в афише, созданное при одобрении
 }
 
-use diesel::insert_into;
-use diesel::prelude::*;
-
 // Структура для создания нового пользовательского события
 #[derive(Debug, Deserialize, Insertable)]
 #[table_name = "user_events"]
@@ -244,37 +672,325 @@ pub struct NewUserEvent {
     pub contact_phone: Option<String>,
     pub contact_email: Option<String>,
     pub image: Option<String>,
-    pub is_published: bool,
+    pub theme_id: Option<i32>,
+}
+
+// Структура для изменения заявки. Автора и состояние через нее поменять нельзя.
+#[derive(Debug, Deserialize, AsChangeset)]
+#[table_name = "user_events"]
+#[changeset_options(treat_none_as_null = "true")]
+pub struct EditUserEvent {
+    pub title: String,
+    pub description: String,
+    pub category: Option<String>,
+    pub date_start: NaiveDateTime,
+    pub date_end: NaiveDateTime,
+    pub location: Option<String>,
+    pub ticket_price: Option<f64>,
+    pub currency: Option<String>,
+    pub contact_name: Option<String>,
+    pub contact_phone: Option<String>,
+    pub contact_email: Option<String>,
+    pub image: Option<String>,
+    pub theme_id: Option<i32>,
+}
+
+impl EditUserEvent {
+    // Проверка данных заявки перед сохранением
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.title.trim().is_empty() {
+            return Err(Error::Validation("title is required".to_string()));
+        }
+        if self.date_end < self.date_start {
+            return Err(Error::Validation("date_end must not be earlier than date_start".to_string()));
+        }
+        if let Some(price) = self.ticket_price {
+            if price < 0.0 {
+                return Err(Error::Validation("ticket_price must not be negative".to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    // Новая заявка из данных формы от имени пользователя
+    pub fn into_new_user_event(self, user_id: i32) -> NewUserEvent {
+        NewUserEvent {
+            user_id: user_id,
+            title: self.title,
+            description: self.description,
+            category: self.category,
+            date_start: self.date_start,
+            date_end: self.date_end,
+            location: self.location,
+            ticket_price: self.ticket_price,
+            currency: self.currency,
+            contact_name: self.contact_name,
+            contact_phone: self.contact_phone,
+            contact_email: self.contact_email,
+            image: self.image,
+            theme_id: self.theme_id,
+        }
+    }
+}
+
+// Структура для таблицы "user_event_history"
+#[derive(Debug, Serialize, Deserialize, Queryable)]
+pub struct UserEventHistory {
+    pub id: i32, // Идентификатор записи
+    pub user_event_id: i32, // Идентификатор заявки
+    pub actor_id: Option<i32>, // Кто выполнил действие
+    pub from_status: String, // Состояние до перехода
+    pub to_status: String, // Состояние после перехода
+    pub comment: Option<String>, // Комментарий автора или модератора
+    pub created_at: NaiveDateTime, // Дата и время перехода
+}
+
+// Строка для вставки в "user_event_history"
+#[derive(Debug, Insertable)]
+#[table_name = "user_event_history"]
+struct NewUserEventHistory<'a> {
+    user_event_id: i32,
+    actor_id: Option<i32>,
+    from_status: &'a str,
+    to_status: &'a str,
+    comment: Option<String>,
+}
+
+// Является ли пользователь модератором афиши
+pub fn is_event_moderator(conn: &PgConnection, user_id: i32) -> Result<bool, Error> {
+    diesel::select(diesel::dsl::exists(
+        event_moderators::table.filter(event_moderators::user_id.eq(user_id)),
+    ))
+    .get_result(conn)
+    .map_err(Error::from)
+}
+
+// Допустимые переходы между состояниями заявки
+fn is_allowed_transition(from: &str, to: &str) -> bool {
+    matches!(
+        (from, to),
+        (USER_EVENT_DRAFT, USER_EVENT_PENDING)
+            | (USER_EVENT_REJECTED, USER_EVENT_PENDING)
+            | (USER_EVENT_PENDING, USER_EVENT_DRAFT)
+            | (USER_EVENT_PENDING, USER_EVENT_APPROVED)
+            | (USER_EVENT_PENDING, USER_EVENT_REJECTED)
+    )
 }
 
 
 impl UserEvent {
-    // Метод для создания нового пользовательского события
+    // Метод для создания нового пользовательского события (всегда черновик)
     pub fn create_new_user_event(
         conn: &PgConnection,
         new_user_event: NewUserEvent,
-    ) -> QueryResult<UserEvent> {
+    ) -> Result<UserEvent, Error> {
         insert_into(user_events::table)
             .values(&new_user_event)
             .get_result(conn)
+            .map_err(Error::from)
+    }
+
+    // Метод для получения заявки по идентификатору
+    pub fn get_by_id(conn: &PgConnection, user_event_id: i32) -> Result<UserEvent, Error> {
+        user_events::table
+            .find(user_event_id)
+            .first(conn)
+            .map_err(Error::from)
+    }
+
+    // Метод для получения всех заявок пользователя, новые первыми
+    pub fn get_for_user(conn: &PgConnection, user_id: i32) -> Result<Vec<UserEvent>, Error> {
+        user_events::table
+            .filter(user_events::user_id.eq(user_id))
+            .order(user_events::id.desc())
+            .load(conn)
+            .map_err(Error::from)
     }
 
-    // Метод для обновления информации о пользовательском событии
+    // Очередь модерации: заявки в нужном состоянии, старые первыми
+    pub fn get_by_status(
+        conn: &PgConnection,
+        status: &str,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<UserEvent>, i64), Error> {
+        let total = user_events::table
+            .filter(user_events::status.eq(status))
+            .count()
+            .get_result::<i64>(conn)?;
+        let items = user_events::table
+            .filter(user_events::status.eq(status))
+            .order((user_events::updated_at.asc(), user_events::id.asc()))
+            .offset(offset)
+            .limit(limit)
+            .load(conn)?;
+        Ok((items, total))
+    }
+
+    // Метод для обновления информации о пользовательском событии.
+    // Менять можно только черновик или отклоненную заявку.
     pub fn update_user_event_info(
         conn: &PgConnection,
         user_event_id: i32,
-        updated_user_event: NewUserEvent,
-    ) -> QueryResult<UserEvent> {
-        diesel::update(user_events::table.find(user_event_id))
-            .set(&updated_user_event)
-            .get_result(conn)
+        updated_user_event: EditUserEvent,
+    ) -> Result<UserEvent, Error> {
+        updated_user_event.validate()?;
+        let updated = diesel::update(
+            user_events::table
+                .find(user_event_id)
+                .filter(user_events::status.eq_any(vec![USER_EVENT_DRAFT, USER_EVENT_REJECTED])),
+        )
+        .set((
+            &updated_user_event,
+            user_events::updated_at.eq(chrono::Local::now().naive_local()),
+        ))
+        .get_result(conn)
+        .optional()?;
+        match updated {
+            Some(user_event) => Ok(user_event),
+            None => {
+                let user_event = UserEvent::get_by_id(conn, user_event_id)?;
+                Err(Error::Conflict(format!("{} request can not be edited", user_event.status)))
+            },
+        }
     }
 
-    // Метод для удаления пользовательского события по идентификатору
-    pub fn delete_user_event_by_id(conn: &PgConnection, user_event_id: i32) -> QueryResult<()> {
+    // Метод для удаления пользовательского события по идентификатору.
+    // Событие, созданное из одобренной заявки, остается в афише.
+    pub fn delete_user_event_by_id(conn: &PgConnection, user_event_id: i32) -> Result<(), Error> {
         diesel::delete(user_events::table.find(user_event_id)).execute(conn)?;
         Ok(())
     }
+
+    // Смена состояния заявки с записью в историю. Строка блокируется,
+    // чтобы два модератора не обработали одну заявку одновременно.
+    fn transition(
+        conn: &PgConnection,
+        user_event_id: i32,
+        actor_id: i32,
+        to_status: &str,
+        comment: Option<String>,
+    ) -> Result<UserEvent, Error> {
+        let user_event = user_events::table
+            .find(user_event_id)
+            .for_update()
+            .first::<UserEvent>(conn)?;
+        if !is_allowed_transition(&user_event.status, to_status) {
+            return Err(Error::Conflict(format!(
+                "can not move request from {} to {}",
+                user_event.status, to_status
+            )));
+        }
+        insert_into(user_event_history::table)
+            .values(&NewUserEventHistory {
+                user_event_id: user_event_id,
+                actor_id: Some(actor_id),
+                from_status: &user_event.status,
+                to_status: to_status,
+                comment: comment,
+            })
+            .execute(conn)?;
+        diesel::update(user_events::table.find(user_event_id))
+            .set((
+                user_events::status.eq(to_status),
+                user_events::updated_at.eq(chrono::Local::now().naive_local()),
+            ))
+            .get_result(conn)
+            .map_err(Error::from)
+    }
+
+    // Отправка заявки на проверку (черновик или отклоненная заявка)
+    pub fn submit(conn: &PgConnection, user_event_id: i32, actor_id: i32) -> Result<UserEvent, Error> {
+        conn.transaction(|| UserEvent::transition(conn, user_event_id, actor_id, USER_EVENT_PENDING, None))
+    }
+
+    // Автор забирает заявку с проверки обратно в черновики
+    pub fn withdraw(conn: &PgConnection, user_event_id: i32, actor_id: i32) -> Result<UserEvent, Error> {
+        conn.transaction(|| UserEvent::transition(conn, user_event_id, actor_id, USER_EVENT_DRAFT, None))
+    }
+
+    // Одобрение заявки: по ней создается опубликованное событие,
+    // автор заявки становится владельцем события.
+    pub fn approve(
+        conn: &PgConnection,
+        user_event_id: i32,
+        actor_id: i32,
+        comment: Option<String>,
+    ) -> Result<UserEvent, Error> {
+        conn.transaction(|| {
+            let user_event = UserEvent::transition(conn, user_event_id, actor_id, USER_EVENT_APPROVED, comment)?;
+            let event = Event::create_new_event(conn, NewEvent {
+                title: user_event.title.clone(),
+                description: user_event.description.clone(),
+                category: user_event.category.clone(),
+                date_start: user_event.date_start,
+                date_end: user_event.date_end,
+                location: user_event.location.clone(),
+                ticket_price: user_event.ticket_price,
+                currency: user_event.currency.clone(),
+                contact_name: user_event.contact_name.clone(),
+                contact_phone: user_event.contact_phone.clone(),
+                contact_email: user_event.contact_email.clone(),
+                image: user_event.image.clone(),
+                is_published: true,
+                theme_id: user_event.theme_id,
+                user_id: Some(user_event.user_id),
+                coordinates: None,
+                city_id: None,
+            })?;
+            diesel::update(user_events::table.find(user_event_id))
+                .set(user_events::event_id.eq(event.id))
+                .get_result(conn)
+                .map_err(Error::from)
+        })
+    }
+
+    // Отклонение заявки. Причина обязательна - автор должен знать, что исправить.
+    pub fn reject(
+        conn: &PgConnection,
+        user_event_id: i32,
+        actor_id: i32,
+        comment: String,
+    ) -> Result<UserEvent, Error> {
+        if comment.trim().is_empty() {
+            return Err(Error::Validation("comment is required".to_string()));
+        }
+        conn.transaction(|| {
+            UserEvent::transition(conn, user_event_id, actor_id, USER_EVENT_REJECTED, Some(comment))
+        })
+    }
+
+    // Комментарий модератора без смены состояния
+    pub fn add_comment(
+        conn: &PgConnection,
+        user_event_id: i32,
+        actor_id: i32,
+        comment: String,
+    ) -> Result<UserEventHistory, Error> {
+        if comment.trim().is_empty() {
+            return Err(Error::Validation("comment is required".to_string()));
+        }
+        let user_event = UserEvent::get_by_id(conn, user_event_id)?;
+        insert_into(user_event_history::table)
+            .values(&NewUserEventHistory {
+                user_event_id: user_event_id,
+                actor_id: Some(actor_id),
+                from_status: &user_event.status,
+                to_status: &user_event.status,
+                comment: Some(comment),
+            })
+            .get_result(conn)
+            .map_err(Error::from)
+    }
+
+    // История заявки в порядке событий
+    pub fn get_history(&self, conn: &PgConnection) -> Result<Vec<UserEventHistory>, Error> {
+        user_event_history::table
+            .filter(user_event_history::user_event_id.eq(self.id))
+            .order(user_event_history::id.asc())
+            .load(conn)
+            .map_err(Error::from)
+    }
 }
 
 
@@ -291,11 +1007,8 @@ pub struct EventCalendar {
     pub time_end: Option<NaiveTime>, // Время окончания события
 }
 
-use diesel::insert_into;
-use diesel::prelude::*;
-
 // Структура для создания новой записи в календаре событий
-#[derive(Debug, Deserialize, Insertable)]
+#[derive(Debug, Deserialize, Insertable, AsChangeset)]
 #[table_name = "event_calendar"]
 pub struct NewEventCalendar {
     pub event_id: i32,
@@ -306,14 +1019,24 @@ pub struct NewEventCalendar {
 
 
 impl EventCalendar {
+    // Метод для получения дат для списка событий, отсортированных по дате
+    pub fn get_for_events(conn: &PgConnection, event_ids: &[i32]) -> Result<Vec<EventCalendar>, Error> {
+        event_calendar::table
+            .filter(event_calendar::event_id.eq_any(event_ids))
+            .order((event_calendar::date.asc(), event_calendar::time_start.asc()))
+            .load(conn)
+            .map_err(Error::from)
+    }
+
     // Метод для создания новой записи в календаре событий
     pub fn create_new_event_calendar(
         conn: &PgConnection,
         new_event_calendar: NewEventCalendar,
-    ) -> QueryResult<EventCalendar> {
+    ) -> Result<EventCalendar, Error> {
         insert_into(event_calendar::table)
             .values(&new_event_calendar)
             .get_result(conn)
+            .map_err(Error::from)
     }
 
     // Метод для обновления информации о записи в календаре событий
@@ -321,30 +1044,448 @@ impl EventCalendar {
         conn: &PgConnection,
         event_calendar_id: i32,
         updated_event_calendar: NewEventCalendar,
-    ) -> QueryResult<EventCalendar> {
+    ) -> Result<EventCalendar, Error> {
         diesel::update(event_calendar::table.find(event_calendar_id))
             .set(&updated_event_calendar)
             .get_result(conn)
+            .map_err(Error::from)
     }
 
     // Метод для удаления записи из календаря событий по идентификатору
     pub fn delete_event_calendar_by_id(
         conn: &PgConnection,
         event_calendar_id: i32,
-    ) -> QueryResult<()> {
+    ) -> Result<(), Error> {
         diesel::delete(event_calendar::table.find(event_calendar_id)).execute(conn)?;
         Ok(())
     }
+}
 
-//Метод для получения всех событий, запланированных на определенную дату
 
+//-------------------------------------------------------------------------------------
+
+// Структура для таблицы "event_recurrences"
+#[derive(Debug, Serialize, Deserialize, Queryable)]
+pub struct EventRecurrence {
+    pub id: i32, // Идентификатор правила
+    pub event_id: i32, // Идентификатор события
+    pub freq: String, // Частота: daily, weekly или monthly
+    pub repeat_every: i16, // Каждый N-й день / неделю / месяц
+    pub weekdays: Vec<i16>, // Дни недели для weekly (1 - понедельник, 7 - воскресенье)
+    pub month_week: Option<i16>, // Номер недели месяца для monthly (1..5, -1 - последняя)
+    pub month_weekday: Option<i16>, // День недели для monthly (1..7)
+    pub date_start: NaiveDate, // Дата, с которой действует правило
+    pub time_start: Option<NaiveTime>, // Время начала
+    pub time_end: Option<NaiveTime>, // Время окончания
+    pub repeat_until: Option<NaiveDate>, // Последняя дата повторения
+    pub repeat_count: Option<i32>, // Сколько раз повторить
+}
+
+// Структура для создания нового правила повторения
+#[derive(Debug, Deserialize, Insertable)]
+#[table_name = "event_recurrences"]
+pub struct NewEventRecurrence {
+    pub event_id: i32,
+    pub freq: String,
+    pub repeat_every: i16,
+    pub weekdays: Vec<i16>,
+    pub month_week: Option<i16>,
+    pub month_weekday: Option<i16>,
+    pub date_start: NaiveDate,
+    pub time_start: Option<NaiveTime>,
+    pub time_end: Option<NaiveTime>,
+    pub repeat_until: Option<NaiveDate>,
+    pub repeat_count: Option<i32>,
+}
+
+// Структура для таблицы "event_recurrence_exceptions"
+#[derive(Debug, Serialize, Deserialize, Queryable)]
+pub struct EventRecurrenceException {
+    pub id: i32, // Идентификатор исключения
+    pub recurrence_id: i32, // Идентификатор правила
+    pub date: NaiveDate, // Дата, в которую событие не проводится
+}
+
+impl EventRecurrenceException {
+    // Метод для получения исключенных дат списка правил
+    pub fn get_for_recurrences(conn: &PgConnection, recurrence_ids: &[i32]) -> Result<Vec<EventRecurrenceException>, Error> {
+        event_recurrence_exceptions::table
+            .filter(event_recurrence_exceptions::recurrence_id.eq_any(recurrence_ids))
+            .order(event_recurrence_exceptions::date.asc())
+            .load(conn)
+            .map_err(Error::from)
+    }
+}
+
+// Структура для создания нового исключения
+#[derive(Debug, Deserialize, Insertable)]
+#[table_name = "event_recurrence_exceptions"]
+pub struct NewEventRecurrenceException {
+    pub recurrence_id: i32,
+    pub date: NaiveDate,
+}
+
+pub const FREQ_DAILY: &str = "daily";
+pub const FREQ_WEEKLY: &str = "weekly";
+pub const FREQ_MONTHLY: &str = "monthly";
+// Сколько периодов правила просматривается в поисках первой даты
+const MAX_RECURRENCE_PERIODS: i64 = 1000;
+
+// N-й день недели месяца (weekday: 1 - понедельник). n = -1 - последний.
+// None, если такого дня в месяце нет (например, пятого понедельника).
+fn nth_weekday_of_month(year: i32, month: u32, weekday: u32, n: i16) -> Option<NaiveDate> {
+    let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+    if n > 0 {
+        let offset = (weekday + 7 - first.weekday().number_from_monday()) % 7;
+        let day = 1 + offset + (n as u32 - 1) * 7;
+        NaiveDate::from_ymd_opt(year, month, day)
+    }
+    else {
+        let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+        let last = NaiveDate::from_ymd_opt(next_year, next_month, 1)?.pred_opt()?;
+        let offset = (last.weekday().number_from_monday() + 7 - weekday) % 7;
+        let back = offset + (n.unsigned_abs() as u32 - 1) * 7;
+        if back >= last.day() {
+            return None;
+        }
+        NaiveDate::from_ymd_opt(year, month, last.day() - back)
+    }
+}
+
+impl NewEventRecurrence {
+    // Проверка правила перед сохранением
+    pub fn validate(&self) -> Result<(), Error> {
+        let invalid = |msg: &str| Err(Error::Validation(msg.to_string()));
+        if ![FREQ_DAILY, FREQ_WEEKLY, FREQ_MONTHLY].contains(&self.freq.as_str()) {
+            return invalid("freq must be daily, weekly or monthly");
+        }
+        if self.repeat_every < 1 {
+            return invalid("repeat_every must be at least 1");
+        }
+        if self.weekdays.iter().any(|d| *d < 1 || *d > 7) {
+            return invalid("weekdays must be between 1 and 7");
+        }
+        if self.month_week.is_some() != self.month_weekday.is_some() {
+            return invalid("month_week and month_weekday must be set together");
+        }
+        if let Some(week) = self.month_week {
+            if week == 0 || !(-1..=5).contains(&week) {
+                return invalid("month_week must be between 1 and 5 or -1");
+            }
+        }
+        if let Some(weekday) = self.month_weekday {
+            if !(1..=7).contains(&weekday) {
+                return invalid("month_weekday must be between 1 and 7");
+            }
+        }
+        if self.repeat_until.is_some() && self.repeat_count.is_some() {
+            return invalid("repeat_until and repeat_count are mutually exclusive");
+        }
+        if let Some(until) = self.repeat_until {
+            if until < self.date_start {
+                return invalid("repeat_until must not be earlier than date_start");
+            }
+        }
+        if let Some(count) = self.repeat_count {
+            if count < 1 {
+                return invalid("repeat_count must be positive");
+            }
+        }
+        Ok(())
+    }
+}
+
+impl EventRecurrence {
+    // Метод для создания нового правила повторения
+    pub fn create(conn: &PgConnection, new_recurrence: NewEventRecurrence) -> Result<EventRecurrence, Error> {
+        new_recurrence.validate()?;
+        insert_into(event_recurrences::table)
+            .values(&new_recurrence)
+            .get_result(conn)
+            .map_err(Error::from)
+    }
+
+    // Метод для получения правила по идентификатору
+    pub fn get_by_id(conn: &PgConnection, recurrence_id: i32) -> Result<EventRecurrence, Error> {
+        event_recurrences::table
+            .find(recurrence_id)
+            .first(conn)
+            .map_err(Error::from)
+    }
+
+    // Метод для получения всех правил события
+    pub fn get_for_event(conn: &PgConnection, event_id: i32) -> Result<Vec<EventRecurrence>, Error> {
+        event_recurrences::table
+            .filter(event_recurrences::event_id.eq(event_id))
+            .order(event_recurrences::id.asc())
+            .load(conn)
+            .map_err(Error::from)
+    }
+
+    // Метод для получения правил списка событий
+    pub fn get_for_events(conn: &PgConnection, event_ids: &[i32]) -> Result<Vec<EventRecurrence>, Error> {
+        event_recurrences::table
+            .filter(event_recurrences::event_id.eq_any(event_ids))
+            .order(event_recurrences::id.asc())
+            .load(conn)
+            .map_err(Error::from)
+    }
+
+    // Метод для удаления правила (исключения удаляются каскадно)
+    pub fn delete(conn: &PgConnection, recurrence_id: i32) -> Result<(), Error> {
+        diesel::delete(event_recurrences::table.find(recurrence_id)).execute(conn)?;
+        Ok(())
+    }
+
+    // Метод для получения исключенных дат правила
+    pub fn get_exceptions(&self, conn: &PgConnection) -> Result<Vec<EventRecurrenceException>, Error> {
+        event_recurrence_exceptions::table
+            .filter(event_recurrence_exceptions::recurrence_id.eq(self.id))
+            .order(event_recurrence_exceptions::date.asc())
+            .load(conn)
+            .map_err(Error::from)
+    }
+
+    // Метод для исключения даты из правила
+    pub fn add_exception(&self, conn: &PgConnection, date: NaiveDate) -> Result<EventRecurrenceException, Error> {
+        insert_into(event_recurrence_exceptions::table)
+            .values(&NewEventRecurrenceException {
+                recurrence_id: self.id,
+                date: date,
+            })
+            .get_result(conn)
+            .map_err(Error::from)
+    }
+
+    // Метод для возврата исключенной даты. false - такой даты не было.
+    pub fn remove_exception(&self, conn: &PgConnection, date: NaiveDate) -> Result<bool, Error> {
+        let count = diesel::delete(
+            event_recurrence_exceptions::table
+                .filter(event_recurrence_exceptions::recurrence_id.eq(self.id))
+                .filter(event_recurrence_exceptions::date.eq(date)),
+        )
+        .execute(conn)?;
+        Ok(count > 0)
+    }
+
+    // Начало периода номер period (день, неделя с понедельника или месяц)
+    // и даты правила в этом периоде по возрастанию
+    fn period_dates(&self, period: i64) -> Option<(NaiveDate, Vec<NaiveDate>)> {
+        let every = self.repeat_every.max(1) as i64;
+        match self.freq.as_str() {
+            FREQ_WEEKLY => {
+                let monday = self.date_start
+                    - Duration::days(self.date_start.weekday().num_days_from_monday() as i64)
+                    + Duration::weeks(period * every);
+                let mut days: Vec<i16> = if self.weekdays.is_empty() {
+                    vec![self.date_start.weekday().number_from_monday() as i16]
+                } else {
+                    self.weekdays.clone()
+                };
+                days.sort();
+                days.dedup();
+                let dates = days.iter().map(|d| monday + Duration::days(*d as i64 - 1)).collect();
+                Some((monday, dates))
+            },
+            FREQ_MONTHLY => {
+                let months = self.date_start.month0() as i64 + period * every;
+                let year = self.date_start.year() + (months / 12) as i32;
+                let month = (months % 12) as u32 + 1;
+                let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+                let date = match (self.month_week, self.month_weekday) {
+                    (Some(week), Some(weekday)) => nth_weekday_of_month(year, month, weekday as u32, week),
+                    // без номера недели - то же число месяца, что и в date_start
+                    _ => NaiveDate::from_ymd_opt(year, month, self.date_start.day()),
+                };
+                Some((first, date.into_iter().collect()))
+            },
+            _ => {
+                let day = self.date_start + Duration::days(period * every);
+                Some((day, vec![day]))
+            },
+        }
+    }
+
+    // Первая дата проведения по правилу. Она может быть позже date_start,
+    // если date_start не попадает на дни правила.
+    pub fn first_date(&self) -> Option<NaiveDate> {
+        let last = self.repeat_until.unwrap_or(NaiveDate::MAX);
+        (0..MAX_RECURRENCE_PERIODS)
+            .map_while(|period| self.period_dates(period))
+            .take_while(|(period_start, _)| *period_start <= last)
+            .flat_map(|(_, dates)| dates)
+            .find(|date| *date >= self.date_start && *date <= last)
+    }
+
+    // Даты проведения по правилу в промежутке [from, to] без исключенных дат.
+    // repeat_count считается от date_start, исключенные даты в него входят.
+    pub fn dates_between(&self, exceptions: &[NaiveDate], from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+        let last = match self.repeat_until {
+            Some(until) if until < to => until,
+            _ => to,
+        };
+        let mut result = Vec::new();
+        let mut produced = 0;
+        let mut period = 0;
+        while let Some((period_start, dates)) = self.period_dates(period) {
+            if period_start > last {
+                break;
+            }
+            for date in dates {
+                if date < self.date_start {
+                    continue;
+                }
+                if date > last || self.repeat_count.is_some_and(|count| produced >= count) {
+                    return result;
+                }
+                produced += 1;
+                if date >= from && !exceptions.contains(&date) {
+                    result.push(date);
+                }
+            }
+            period += 1;
+        }
+        result
+    }
+}
+
+// Одно проведение события: строка из event_calendar
+// или дата, вычисленная по правилу повторения
+#[derive(Debug, Clone, Serialize)]
+pub struct EventOccurrence {
+    pub event_id: i32, // Идентификатор события
+    pub date: NaiveDate, // Дата проведения
+    pub time_start: Option<NaiveTime>, // Время начала
+    pub time_end: Option<NaiveTime>, // Время окончания
+    pub calendar_id: Option<i32>, // Строка event_calendar, если дата задана вручную
+    pub recurrence_id: Option<i32>, // Правило, по которому вычислена дата
+}
+
+impl EventOccurrence {
+    // Начало и конец проведения. Без времени занимает весь день.
+    pub fn bounds(&self) -> (NaiveDateTime, NaiveDateTime) {
+        let start = self.date.and_time(self.time_start.unwrap_or(NaiveTime::MIN));
+        let end = match self.time_end {
+            Some(time_end) if time_end >= start.time() => self.date.and_time(time_end),
+            _ => self.date.and_time(NaiveTime::from_hms_opt(23, 59, 59).expect("valid time")),
+        };
+        (start, end)
+    }
+}
+
+// Начала опубликованных событий в промежутке (from, to]: проведения из
+// календаря и правил повторения, а для событий без них - date_start.
+pub fn get_event_starts_between(
+    conn: &PgConnection,
+    from: NaiveDateTime,
+    to: NaiveDateTime,
+) -> Result<Vec<(i32, NaiveDateTime)>, Error> {
+    let occurrences = get_event_occurrences(conn, from.date(), to.date(), None)?;
+    let mut scheduled_ids: Vec<i32> = event_calendar::table
+        .select(event_calendar::event_id)
+        .distinct()
+        .load(conn)?;
+    scheduled_ids.extend(
+        event_recurrences::table
+            .select(event_recurrences::event_id)
+            .distinct()
+            .load::<i32>(conn)?,
+    );
+
+    let mut result: Vec<(i32, NaiveDateTime)> = occurrences
+        .iter()
+        .map(|o| (o.event_id, o.bounds().0))
+        .filter(|(_, start)| *start > from && *start <= to)
+        .collect();
+    result.extend(
+        events::table
+            .filter(events::is_published.eq(true))
+            .filter(events::cancelled_at.is_null())
+            .filter(events::date_start.gt(from))
+            .filter(events::date_start.le(to))
+            .filter(diesel::dsl::not(events::id.eq_any(scheduled_ids)))
+            .select((events::id, events::date_start))
+            .load::<(i32, NaiveDateTime)>(conn)?,
+    );
+    result.sort_by_key(|(_, start)| *start);
+    Ok(result)
+}
+
+// Все проведения опубликованных и не отмененных событий в промежутке дат
+// [from, to] (по желанию - только в категории): строки event_calendar плюс
+// даты, вычисленные по правилам.
+pub fn get_event_occurrences(
+    conn: &PgConnection,
+    from: NaiveDate,
+    to: NaiveDate,
+    category: Option<&str>,
+) -> Result<Vec<EventOccurrence>, Error> {
+    let mut calendar_query = event_calendar::table
+        .inner_join(events::table)
+        .filter(event_calendar::date.between(from, to))
+        .filter(events::is_published.eq(true))
+        .filter(events::cancelled_at.is_null())
+        .select(event_calendar::all_columns)
+        .into_boxed();
+    let mut recurrence_query = event_recurrences::table
+        .inner_join(events::table)
+        .filter(event_recurrences::date_start.le(to))
+        .filter(event_recurrences::repeat_until.is_null().or(event_recurrences::repeat_until.ge(from)))
+        .filter(events::is_published.eq(true))
+        .filter(events::cancelled_at.is_null())
+        .select(event_recurrences::all_columns)
+        .into_boxed();
+    if let Some(category) = category {
+        calendar_query = calendar_query.filter(events::category.eq(category));
+        recurrence_query = recurrence_query.filter(events::category.eq(category));
+    }
+    let rows = calendar_query.load::<EventCalendar>(conn)?;
+    let recurrences = recurrence_query.load::<EventRecurrence>(conn)?;
+
+    let recurrence_ids: Vec<i32> = recurrences.iter().map(|r| r.id).collect();
+    let exceptions = event_recurrence_exceptions::table
+        .filter(event_recurrence_exceptions::recurrence_id.eq_any(&recurrence_ids))
+        .load::<EventRecurrenceException>(conn)?;
+
+    let mut result: Vec<EventOccurrence> = rows
+        .into_iter()
+        .map(|row| EventOccurrence {
+            event_id: row.event_id,
+            date: row.date,
+            time_start: row.time_start,
+            time_end: row.time_end,
+            calendar_id: Some(row.id),
+            recurrence_id: None,
+        })
+        .collect();
+    for recurrence in recurrences {
+        let skip: Vec<NaiveDate> = exceptions
+            .iter()
+            .filter(|e| e.recurrence_id == recurrence.id)
+            .map(|e| e.date)
+            .collect();
+        for date in recurrence.dates_between(&skip, from, to) {
+            result.push(EventOccurrence {
+                event_id: recurrence.event_id,
+                date: date,
+                time_start: recurrence.time_start,
+                time_end: recurrence.time_end,
+                calendar_id: None,
+                recurrence_id: Some(recurrence.id),
+            });
+        }
+    }
+    result.sort_by_key(|a| (a.date, a.time_start, a.event_id));
+    Ok(result)
+}
+
+//Метод для получения всех событий, запланированных на определенную дату
 
 pub fn get_events_scheduled_for_date(
     conn: &PgConnection,
     date: NaiveDate,
-) -> QueryResult<Vec<EventCalendar>> {
-    use crate::schema::event_calendar::dsl::*;
-    event_calendar.filter(date.eq(date)).load(conn)
+) -> Result<Vec<EventOccurrence>, Error> {
+    get_event_occurrences(conn, date, date, None)
 }
 
 //Метод для получения всех событий, запланированных на определенную дату и в определенной категории
@@ -353,37 +1494,181 @@ pub fn get_events_scheduled_for_date_in_category(
     conn: &PgConnection,
     date: NaiveDate,
     category: &str,
-) -> QueryResult<Vec<EventCalendar>> {
-    use crate::schema::event_calendar::dsl::*;
-    event_calendar
-        .filter(date.eq(date))
-        .inner_join(events::table)
-        .filter(events::category.eq(category))
-        .load(conn)
+) -> Result<Vec<EventOccurrence>, Error> {
+    get_event_occurrences(conn, date, date, Some(category))
 }
 
-//Метод для получения всех событий, запланированных в определенном временном интервале и в определенной категории
+//Метод для получения всех событий, запланированных в определенном временном интервале и в определенной категории.
+//Проведение попадает в интервал, если пересекается с ним хотя бы частично.
 
 pub fn get_events_scheduled_in_time_interval_in_category(
     conn: &PgConnection,
-    start_time: NaiveTime,
-    end_time: NaiveTime,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
     category: &str,
-) -> QueryResult<Vec<EventCalendar>> {
-    use crate::schema::event_calendar::dsl::*;
-    event_calendar
-        .filter(time_start.le(end_time).and(time_end.ge(start_time)))
-        .inner_join(events::table)
-        .filter(events::category.eq(category))
-        .load(conn)
+) -> Result<Vec<EventOccurrence>, Error> {
+    let occurrences = get_event_occurrences(conn, start.date(), end.date(), Some(category))?;
+    Ok(occurrences
+        .into_iter()
+        .filter(|o| {
+            let (o_start, o_end) = o.bounds();
+            o_start <= end && o_end >= start
+        })
+        .collect())
+}
+
+
+
+//-------------------------------------------------------------------------------------
+use crate::schema::event_ticket_tiers;
+// Структура для таблицы "event_ticket_tiers"
+#[derive(Debug, Serialize, Deserialize, Queryable)]
+pub struct EventTicketTier {
+    pub id: i32, // Идентификатор категории билетов
+    pub event_id: i32, // Идентификатор события
+    pub name: String, // Название категории
+    pub price: f64, // Стоимость билета
+    pub currency: Option<String>, // Валюта
+    pub capacity: i32, // Количество мест
+    pub sold: i32, // Количество занятых мест
+    pub sale_start: Option<NaiveDateTime>, // Начало продаж
+    pub sale_end: Option<NaiveDateTime>, // Окончание продаж
+    pub created_at: NaiveDateTime, // Дата и время создания
+}
+
+// Структура для создания категории билетов
+#[derive(Debug, Deserialize, Insertable)]
+#[table_name = "event_ticket_tiers"]
+pub struct NewEventTicketTier {
+    pub event_id: i32,
+    pub name: String,
+    pub price: f64,
+    pub currency: Option<String>,
+    pub capacity: i32,
+    pub sale_start: Option<NaiveDateTime>,
+    pub sale_end: Option<NaiveDateTime>,
+}
+
+// Структура для изменения категории билетов. Проданные места не меняются.
+#[derive(Debug, Deserialize, AsChangeset)]
+#[table_name = "event_ticket_tiers"]
+#[changeset_options(treat_none_as_null = "true")]
+pub struct EditEventTicketTier {
+    pub name: String,
+    pub price: f64,
+    pub currency: Option<String>,
+    pub capacity: i32,
+    pub sale_start: Option<NaiveDateTime>,
+    pub sale_end: Option<NaiveDateTime>,
+}
+
+// Общая проверка данных категории билетов
+fn validate_tier(
+    name: &str,
+    price: f64,
+    capacity: i32,
+    sale_start: Option<NaiveDateTime>,
+    sale_end: Option<NaiveDateTime>,
+) -> Result<(), Error> {
+    if name.trim().is_empty() {
+        return Err(Error::Validation("name is required".to_string()));
+    }
+    if price < 0.0 {
+        return Err(Error::Validation("price must not be negative".to_string()));
+    }
+    if capacity < 0 {
+        return Err(Error::Validation("capacity must not be negative".to_string()));
+    }
+    if let (Some(start), Some(end)) = (sale_start, sale_end) {
+        if end <= start {
+            return Err(Error::Validation("sale_end must be after sale_start".to_string()));
+        }
+    }
+    Ok(())
+}
+
+impl NewEventTicketTier {
+    pub fn validate(&self) -> Result<(), Error> {
+        validate_tier(&self.name, self.price, self.capacity, self.sale_start, self.sale_end)
+    }
+}
+
+impl EditEventTicketTier {
+    pub fn validate(&self) -> Result<(), Error> {
+        validate_tier(&self.name, self.price, self.capacity, self.sale_start, self.sale_end)
+    }
 }
 
+impl EventTicketTier {
+    // Метод для создания категории билетов
+    pub fn create(conn: &PgConnection, new_tier: NewEventTicketTier) -> Result<EventTicketTier, Error> {
+        new_tier.validate()?;
+        insert_into(event_ticket_tiers::table)
+            .values(&new_tier)
+            .get_result(conn)
+            .map_err(Error::from)
+    }
+
+    // Метод для получения категории по идентификатору
+    pub fn get_by_id(conn: &PgConnection, tier_id: i32) -> Result<EventTicketTier, Error> {
+        event_ticket_tiers::table
+            .find(tier_id)
+            .first(conn)
+            .map_err(Error::from)
+    }
+
+    // Метод для получения всех категорий билетов события
+    pub fn get_for_event(conn: &PgConnection, event_id: i32) -> Result<Vec<EventTicketTier>, Error> {
+        event_ticket_tiers::table
+            .filter(event_ticket_tiers::event_id.eq(event_id))
+            .order((event_ticket_tiers::price.asc(), event_ticket_tiers::id.asc()))
+            .load(conn)
+            .map_err(Error::from)
+    }
+
+    // Метод для изменения категории. Мест нельзя сделать меньше, чем уже занято;
+    // если мест стало больше, на них переводятся люди из листа ожидания.
+    pub fn update(conn: &PgConnection, tier_id: i32, changes: EditEventTicketTier) -> Result<EventTicketTier, Error> {
+        changes.validate()?;
+        conn.transaction(|| {
+            let tier = event_ticket_tiers::table
+                .find(tier_id)
+                .for_update()
+                .first::<EventTicketTier>(conn)?;
+            if changes.capacity < tier.sold {
+                return Err(Error::Conflict(format!("{} tickets are already sold", tier.sold)));
+            }
+            diesel::update(event_ticket_tiers::table.find(tier_id))
+                .set(&changes)
+                .execute(conn)?;
+            for _ in tier.sold..changes.capacity {
+                if EventAttendee::promote_from_waitlist(conn, tier_id)?.is_none() {
+                    break;
+                }
+                diesel::update(event_ticket_tiers::table.find(tier_id))
+                    .set(event_ticket_tiers::sold.eq(event_ticket_tiers::sold + 1))
+                    .execute(conn)?;
+            }
+            EventTicketTier::get_by_id(conn, tier_id)
+        })
+    }
 
+    // Метод для удаления категории. Категорию с выданными билетами
+    // или листом ожидания удалить нельзя (ограничение внешнего ключа).
+    pub fn delete(conn: &PgConnection, tier_id: i32) -> Result<(), Error> {
+        diesel::delete(event_ticket_tiers::table.find(tier_id)).execute(conn)?;
+        Ok(())
+    }
+
+    // Открыта ли продажа билетов в момент now
+    pub fn is_on_sale(&self, now: NaiveDateTime) -> bool {
+        self.sale_start.is_none_or(|start| now >= start)
+            && self.sale_end.is_none_or(|end| now < end)
+    }
+}
 
 //-------------------------------------------------------------------------------------
 use crate::schema::event_attendees;
-use diesel::pg::PgConnection;
-use diesel::prelude::*;
 // Структура для таблицы "event_attendees"
 #[derive(Debug, Serialize, Deserialize, Queryable)]
 pub struct EventAttendee {
@@ -391,47 +1676,225 @@ pub struct EventAttendee {
     pub event_id: i32, // Идентификатор события, которое посещает
     pub user_id: i32, // Идентификатор пользователя, посетившего событие
     pub created_at: NaiveDateTime, // Дата и время посещения события
+    pub tier_id: Option<i32>, // Категория билета
+    pub status: String, // confirmed - есть билет, waitlist - в листе ожидания
+    #[serde(skip_serializing)]
+    pub check_in_token: Option<String>, // Токен для QR-кода на входе, в общие списки не попадает
+    pub checked_in_at: Option<NaiveDateTime>, // Когда посетитель прошел на событие
 }
 
-use diesel::insert_into;
-use diesel::prelude::*;
+pub const ATTENDEE_CONFIRMED: &str = "confirmed";
+pub const ATTENDEE_WAITLIST: &str = "waitlist";
 
-// Структура для создания новой записи о посетителе события
-#[derive(Debug, Deserialize, Insertable)]
-#[table_name = "event_attendees"]
+// Структура для записи на событие. Без категории можно записаться
+// только на событие, у которого нет категорий билетов.
+#[derive(Debug, Deserialize)]
 pub struct NewEventAttendee {
     pub event_id: i32,
     pub user_id: i32,
+    pub tier_id: Option<i32>,
+}
+
+// Строка для вставки в "event_attendees"
+#[derive(Debug, Insertable)]
+#[table_name = "event_attendees"]
+struct InsertEventAttendee {
+    event_id: i32,
+    user_id: i32,
+    tier_id: Option<i32>,
+    status: String,
+    check_in_token: Option<String>,
+}
+
+// Случайный токен для входа на событие. Его можно сразу кодировать в QR-код.
+fn generate_check_in_token() -> String {
+    use rand::{distributions::Alphanumeric, Rng};
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect()
 }
 
 
 impl EventAttendee {
-    // Метод для создания новой записи о посетителе события
+    // Метод для записи на событие. Место в категории занимается одним
+    // условным UPDATE, поэтому лишних билетов не будет даже при одновременной
+    // записи. Если мест нет, пользователь попадает в лист ожидания.
     pub fn create_new_event_attendee(
         conn: &PgConnection,
         new_event_attendee: NewEventAttendee,
-    ) -> QueryResult<EventAttendee> {
-        insert_into(event_attendees::table)
-            .values(&new_event_attendee)
-            .get_result(conn)
+    ) -> Result<EventAttendee, Error> {
+        conn.transaction(|| {
+            let confirmed = match new_event_attendee.tier_id {
+                None => {
+                    if !EventTicketTier::get_for_event(conn, new_event_attendee.event_id)?.is_empty() {
+                        return Err(Error::Validation("tier_id is required".to_string()));
+                    }
+                    true
+                },
+                Some(tier_id) => {
+                    // блокировка категории, как в cancel и update: пока проверяются
+                    // места и решается про лист ожидания, билет никто не вернет и не займет
+                    let tier = event_ticket_tiers::table
+                        .find(tier_id)
+                        .filter(event_ticket_tiers::event_id.eq(new_event_attendee.event_id))
+                        .for_update()
+                        .first::<EventTicketTier>(conn)
+                        .optional()?
+                        .ok_or(Error::NotFound)?;
+                    if !tier.is_on_sale(chrono::Local::now().naive_local()) {
+                        return Err(Error::Validation("ticket sales are closed".to_string()));
+                    }
+                    let reserved = diesel::update(
+                        event_ticket_tiers::table
+                            .find(tier_id)
+                            .filter(event_ticket_tiers::sold.lt(event_ticket_tiers::capacity)),
+                    )
+                    .set(event_ticket_tiers::sold.eq(event_ticket_tiers::sold + 1))
+                    .execute(conn)?;
+                    reserved > 0
+                },
+            };
+            insert_into(event_attendees::table)
+                .values(&InsertEventAttendee {
+                    event_id: new_event_attendee.event_id,
+                    user_id: new_event_attendee.user_id,
+                    tier_id: new_event_attendee.tier_id,
+                    status: if confirmed { ATTENDEE_CONFIRMED } else { ATTENDEE_WAITLIST }.to_string(),
+                    check_in_token: if confirmed { Some(generate_check_in_token()) } else { None },
+                })
+                .get_result(conn)
+                .map_err(Error::from)
+        })
+    }
+
+    // Перевод первого по очереди из листа ожидания категории в посетители.
+    // Вызывается внутри транзакции, место в категории должно быть уже свободно.
+    pub fn promote_from_waitlist(conn: &PgConnection, tier_id: i32) -> Result<Option<EventAttendee>, Error> {
+        let next = event_attendees::table
+            .filter(event_attendees::tier_id.eq(tier_id))
+            .filter(event_attendees::status.eq(ATTENDEE_WAITLIST))
+            .order(event_attendees::id.asc())
+            .for_update()
+            .skip_locked()
+            .first::<EventAttendee>(conn)
+            .optional()?;
+        match next {
+            Some(attendee) => diesel::update(event_attendees::table.find(attendee.id))
+                .set((
+                    event_attendees::status.eq(ATTENDEE_CONFIRMED),
+                    event_attendees::check_in_token.eq(generate_check_in_token()),
+                ))
+                .get_result(conn)
+                .map(Some)
+                .map_err(Error::from),
+            None => Ok(None),
+        }
+    }
+
+    // Отмена записи. Освободившийся билет сразу получает первый из листа
+    // ожидания, а если очереди нет - место возвращается в продажу.
+    // Возвращает пользователя, которому достался билет.
+    fn cancel(conn: &PgConnection, attendee: EventAttendee) -> Result<Option<EventAttendee>, Error> {
+        diesel::delete(event_attendees::table.find(attendee.id)).execute(conn)?;
+        let tier_id = match attendee.tier_id {
+            Some(tier_id) if attendee.status == ATTENDEE_CONFIRMED => tier_id,
+            _ => return Ok(None),
+        };
+        // блокировка категории, чтобы место не заняли между проверкой очереди и возвратом
+        event_ticket_tiers::table
+            .find(tier_id)
+            .for_update()
+            .first::<EventTicketTier>(conn)?;
+        let promoted = EventAttendee::promote_from_waitlist(conn, tier_id)?;
+        if promoted.is_none() {
+            diesel::update(event_ticket_tiers::table.find(tier_id))
+                .set(event_ticket_tiers::sold.eq(event_ticket_tiers::sold - 1))
+                .execute(conn)?;
+        }
+        Ok(promoted)
     }
 
     // Метод для удаления записи о посетителе события по идентификатору
     pub fn delete_event_attendee_by_id(
         conn: &PgConnection,
         event_attendee_id: i32,
-    ) -> QueryResult<()> {
-        diesel::delete(event_attendees::table.find(event_attendee_id)).execute(conn)?;
-        Ok(())
+    ) -> Result<(), Error> {
+        conn.transaction(|| {
+            let attendee = event_attendees::table
+                .find(event_attendee_id)
+                .for_update()
+                .first::<EventAttendee>(conn)?;
+            EventAttendee::cancel(conn, attendee)?;
+            Ok(())
+        })
+    }
+
+    // Метод для удаления записи о посетителе по событию и пользователю.
+    // Возвращает NotFound, если пользователь и так не был записан, и
+    // пользователя из листа ожидания, которому достался освободившийся билет.
+    pub fn delete_for_user(
+        conn: &PgConnection,
+        event_id: i32,
+        user_id: i32,
+    ) -> Result<Option<EventAttendee>, Error> {
+        conn.transaction(|| {
+            let attendee = EventAttendee::get_for_user(conn, event_id, user_id)?;
+            EventAttendee::cancel(conn, attendee)
+        })
+    }
+
+    // Метод для получения записи пользователя на событие
+    pub fn get_for_user(conn: &PgConnection, event_id: i32, user_id: i32) -> Result<EventAttendee, Error> {
+        event_attendees::table
+            .filter(event_attendees::event_id.eq(event_id))
+            .filter(event_attendees::user_id.eq(user_id))
+            .first(conn)
+            .map_err(Error::from)
+    }
+
+    // Место пользователя в листе ожидания (1 - следующий), None - если он не в очереди
+    pub fn waitlist_position(&self, conn: &PgConnection) -> Result<Option<i64>, Error> {
+        if self.status != ATTENDEE_WAITLIST {
+            return Ok(None);
+        }
+        let ahead = event_attendees::table
+            .filter(event_attendees::tier_id.eq(self.tier_id))
+            .filter(event_attendees::status.eq(ATTENDEE_WAITLIST))
+            .filter(event_attendees::id.lt(self.id))
+            .count()
+            .get_result::<i64>(conn)?;
+        Ok(Some(ahead + 1))
+    }
+
+    // Отметка о входе по токену из QR-кода. Повторный вход не допускается.
+    pub fn check_in(conn: &PgConnection, event_id: i32, token: &str) -> Result<EventAttendee, Error> {
+        let attendee = event_attendees::table
+            .filter(event_attendees::event_id.eq(event_id))
+            .filter(event_attendees::check_in_token.eq(token))
+            .first::<EventAttendee>(conn)?;
+        let updated = diesel::update(
+            event_attendees::table
+                .find(attendee.id)
+                .filter(event_attendees::checked_in_at.is_null()),
+        )
+        .set(event_attendees::checked_in_at.eq(chrono::Local::now().naive_local()))
+        .get_result::<EventAttendee>(conn)
+        .optional()?;
+        updated.ok_or_else(|| Error::Conflict("ticket has already been used".to_string()))
     }
 
     // Метод для получения всех записей о посетителях события по идентификатору события
     pub fn get_event_attendees_for_event(
         conn: &PgConnection,
         event_id: i32,
-    ) -> QueryResult<Vec<EventAttendee>> {
-        use crate::schema::event_attendees::dsl::*;
-        event_attendees.filter(event_id.eq(event_id)).load(conn)
+    ) -> Result<Vec<EventAttendee>, Error> {
+        event_attendees::table
+            .filter(event_attendees::event_id.eq(event_id))
+            .order(event_attendees::id.asc())
+            .load(conn)
+            .map_err(Error::from)
     }
 }
 
@@ -439,9 +1902,9 @@ impl EventAttendee {
 pub fn get_events_attended_by_user(
     conn: &PgConnection,
     user_id: i32,
-) -> QueryResult<Vec<EventAttendee>> {
-    use crate::schema::event_attendees::dsl::*;
-    event_attendees.filter(user_id.eq(user_id)).load(conn)
+) -> Result<Vec<EventAttendee>, Error> {
+    event_attendees::table.filter(event_attendees::user_id.eq(user_id)).load(conn)
+        .map_err(Error::from)
 }
 
 //Метод для получения всех пользователей, посетивших определенное событие
@@ -449,9 +1912,9 @@ pub fn get_events_attended_by_user(
 pub fn get_users_attending_event(
     conn: &PgConnection,
     event_id: i32,
-) -> QueryResult<Vec<EventAttendee>> {
-    use crate::schema::event_attendees::dsl::*;
-    event_attendees.filter(event_id.eq(event_id)).load(conn)
+) -> Result<Vec<EventAttendee>, Error> {
+    event_attendees::table.filter(event_attendees::event_id.eq(event_id)).load(conn)
+        .map_err(Error::from)
 }
 
 //Метод для получения количества посетителей для определенного события
@@ -459,16 +1922,18 @@ pub fn get_users_attending_event(
 pub fn get_attendee_count_for_event(
     conn: &PgConnection,
     event_id: i32,
-) -> QueryResult<i64> {
-    use crate::schema::event_attendees::dsl::*;
-    event_attendees.filter(event_id.eq(event_id)).count().get_result(conn)
+) -> Result<i64, Error> {
+    event_attendees::table
+        .filter(event_attendees::event_id.eq(event_id))
+        .filter(event_attendees::status.eq(ATTENDEE_CONFIRMED))
+        .count()
+        .get_result(conn)
+        .map_err(Error::from)
 }
 
 
 //-------------------------------------------------------------------------------------
 use crate::schema::event_subscribers;
-use diesel::pg::PgConnection;
-use diesel::prelude::*;
 // Структура для таблицы "event_subscribers"
 #[derive(Debug, Serialize, Deserialize, Queryable)]
 pub struct EventSubscriber {
@@ -478,9 +1943,6 @@ pub struct EventSubscriber {
     pub created_at: NaiveDateTime, // Дата и время создания подписки
 }
 
-use diesel::insert_into;
-use diesel::prelude::*;
-
 // Структура для создания новой записи подписчика события
 #[derive(Debug, Deserialize, Insertable)]
 #[table_name = "event_subscribers"]
@@ -495,28 +1957,45 @@ impl EventSubscriber {
     pub fn create_new_event_subscriber(
         conn: &PgConnection,
         new_event_subscriber: NewEventSubscriber,
-    ) -> QueryResult<EventSubscriber> {
+    ) -> Result<EventSubscriber, Error> {
         insert_into(event_subscribers::table)
             .values(&new_event_subscriber)
             .get_result(conn)
+            .map_err(Error::from)
     }
 
     // Метод для удаления записи подписчика события по идентификатору
     pub fn delete_event_subscriber_by_id(
         conn: &PgConnection,
         event_subscriber_id: i32,
-    ) -> QueryResult<()> {
+    ) -> Result<(), Error> {
         diesel::delete(event_subscribers::table.find(event_subscriber_id)).execute(conn)?;
         Ok(())
     }
 
+    // Метод для удаления подписки по событию и пользователю.
+    // Возвращает false, если пользователь и так не был подписан.
+    pub fn delete_for_user(
+        conn: &PgConnection,
+        event_id: i32,
+        user_id: i32,
+    ) -> Result<bool, Error> {
+        let count = diesel::delete(
+            event_subscribers::table
+                .filter(event_subscribers::event_id.eq(event_id))
+                .filter(event_subscribers::user_id.eq(user_id)),
+        )
+        .execute(conn)?;
+        Ok(count > 0)
+    }
+
     // Метод для получения всех записей подписчиков для определенного события
     pub fn get_event_subscribers_for_event(
         conn: &PgConnection,
         event_id: i32,
-    ) -> QueryResult<Vec<EventSubscriber>> {
-        use crate::schema::event_subscribers::dsl::*;
-        event_subscribers.filter(event_id.eq(event_id)).load(conn)
+    ) -> Result<Vec<EventSubscriber>, Error> {
+        event_subscribers::table.filter(event_subscribers::event_id.eq(event_id)).load(conn)
+            .map_err(Error::from)
     }
 }
 
@@ -525,9 +2004,9 @@ impl EventSubscriber {
 pub fn get_events_subscribed_by_user(
     conn: &PgConnection,
     user_id: i32,
-) -> QueryResult<Vec<EventSubscriber>> {
-    use crate::schema::event_subscribers::dsl::*;
-    event_subscribers.filter(user_id.eq(user_id)).load(conn)
+) -> Result<Vec<EventSubscriber>, Error> {
+    event_subscribers::table.filter(event_subscribers::user_id.eq(user_id)).load(conn)
+        .map_err(Error::from)
 }
 
 //Метод для получения всех пользователей, подписанных на определенное событие
@@ -535,9 +2014,9 @@ pub fn get_events_subscribed_by_user(
 pub fn get_users_subscribed_to_event(
     conn: &PgConnection,
     event_id: i32,
-) -> QueryResult<Vec<EventSubscriber>> {
-    use crate::schema::event_subscribers::dsl::*;
-    event_subscribers.filter(event_id.eq(event_id)).load(conn)
+) -> Result<Vec<EventSubscriber>, Error> {
+    event_subscribers::table.filter(event_subscribers::event_id.eq(event_id)).load(conn)
+        .map_err(Error::from)
 }
 
 
@@ -546,10 +2025,253 @@ pub fn get_users_subscribed_to_event(
 pub fn get_subscriber_count_for_event(
     conn: &PgConnection,
     event_id: i32,
-) -> QueryResult<i64> {
-    use crate::schema::event_subscribers::dsl::*;
-    event_subscribers.filter(event_id.eq(event_id)).count().get_result(conn)
+) -> Result<i64, Error> {
+    event_subscribers::table.filter(event_subscribers::event_id.eq(event_id)).count().get_result(conn)
+        .map_err(Error::from)
+}
+
+// Кого уведомлять о событии: посетители с подтвержденным билетом
+// и подписчики, каждый пользователь один раз
+pub fn get_event_recipients(conn: &PgConnection, event_id: i32) -> Result<Vec<i32>, Error> {
+    let mut user_ids: Vec<i32> = event_attendees::table
+        .filter(event_attendees::event_id.eq(event_id))
+        .filter(event_attendees::status.eq(ATTENDEE_CONFIRMED))
+        .select(event_attendees::user_id)
+        .load(conn)?;
+    user_ids.extend(
+        event_subscribers::table
+            .filter(event_subscribers::event_id.eq(event_id))
+            .select(event_subscribers::user_id)
+            .load::<i32>(conn)?,
+    );
+    user_ids.sort_unstable();
+    user_ids.dedup();
+    Ok(user_ids)
 }
 
 
 //-------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::geo::geo_point;
+
+    fn event() -> Event {
+        let start = NaiveDate::from_ymd_opt(2026, 11, 7).unwrap().and_hms_opt(19, 0, 0).unwrap();
+        Event {
+            id: 1,
+            title: "Концерт".to_string(),
+            description: "Вечер джаза".to_string(),
+            category: Some("music".to_string()),
+            date_start: start,
+            date_end: start + Duration::hours(2),
+            location: Some("Дом культуры".to_string()),
+            ticket_price: Some(500.0),
+            currency: Some("RUB".to_string()),
+            contact_name: None,
+            contact_phone: None,
+            contact_email: None,
+            image: None,
+            is_published: true,
+            created_at: start - Duration::days(30),
+            updated_at: start - Duration::days(30),
+            theme_id: None,
+            user_id: Some(1),
+            cancelled_at: None,
+            cancel_reason: None,
+            coordinates: Some(geo_point(55.75, 37.61).unwrap()),
+            city_id: None,
+        }
+    }
+
+    #[test]
+    fn diff_same_event() {
+        assert!(event().diff(&event()).is_empty());
+    }
+
+    #[test]
+    fn diff_reports_changed_fields() {
+        let old = event();
+        let mut new = event();
+        new.date_start = old.date_start + Duration::hours(1);
+        new.ticket_price = None;
+        new.updated_at = old.updated_at + Duration::days(1);
+        let changes = old.diff(&new);
+        let fields: Vec<&str> = changes.iter().map(|c| c.field).collect();
+        // updated_at меняется при каждом сохранении и в изменения не попадает
+        assert_eq!(fields, vec!["date_start", "ticket_price"]);
+        assert_eq!(changes[1].old, serde_json::json!(500.0));
+        assert_eq!(changes[1].new, serde_json::Value::Null);
+    }
+
+    #[test]
+    fn diff_coordinates_as_lat_lon() {
+        let old = event();
+        let mut new = event();
+        new.coordinates = Some(geo_point(59.94, 30.31).unwrap());
+        let changes = old.diff(&new);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].field, "coordinates");
+        assert_eq!(changes[0].old, serde_json::json!({"lat": 55.75, "lon": 37.61}));
+        assert_eq!(changes[0].new, serde_json::json!({"lat": 59.94, "lon": 30.31}));
+        assert!(NOTIFIABLE_EVENT_FIELDS.contains(&changes[0].field));
+    }
+
+    #[test]
+    fn diff_description_is_not_notifiable() {
+        let old = event();
+        let mut new = event();
+        new.description = "Вечер классики".to_string();
+        let changes = old.diff(&new);
+        assert_eq!(changes.len(), 1);
+        assert!(!NOTIFIABLE_EVENT_FIELDS.contains(&changes[0].field));
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn recurrence(freq: &str, date_start: NaiveDate) -> EventRecurrence {
+        EventRecurrence {
+            id: 1,
+            event_id: 1,
+            freq: freq.to_string(),
+            repeat_every: 1,
+            weekdays: Vec::new(),
+            month_week: None,
+            month_weekday: None,
+            date_start: date_start,
+            time_start: None,
+            time_end: None,
+            repeat_until: None,
+            repeat_count: None,
+        }
+    }
+
+    #[test]
+    fn nth_weekday_from_start_of_month() {
+        // 1 октября 2026 - четверг
+        assert_eq!(nth_weekday_of_month(2026, 10, 1, 1), Some(date(2026, 10, 5)));
+        assert_eq!(nth_weekday_of_month(2026, 10, 4, 1), Some(date(2026, 10, 1)));
+        assert_eq!(nth_weekday_of_month(2026, 10, 5, 2), Some(date(2026, 10, 9)));
+        assert_eq!(nth_weekday_of_month(2026, 11, 7, 5), Some(date(2026, 11, 29)));
+        // пятого понедельника в октябре 2026 нет
+        assert_eq!(nth_weekday_of_month(2026, 10, 1, 5), None);
+    }
+
+    #[test]
+    fn nth_weekday_last_in_month() {
+        assert_eq!(nth_weekday_of_month(2026, 10, 5, -1), Some(date(2026, 10, 30)));
+        assert_eq!(nth_weekday_of_month(2026, 10, 7, -1), Some(date(2026, 10, 25)));
+        // декабрь: следующий месяц уже в новом году, 31 декабря - четверг
+        assert_eq!(nth_weekday_of_month(2026, 12, 4, -1), Some(date(2026, 12, 31)));
+        assert_eq!(nth_weekday_of_month(2026, 2, 6, -1), Some(date(2026, 2, 28)));
+    }
+
+    #[test]
+    fn period_dates_weekly_starts_on_monday() {
+        let mut rule = recurrence(FREQ_WEEKLY, date(2026, 10, 7));
+        rule.repeat_every = 2;
+        rule.weekdays = vec![5, 1, 3, 3];
+        assert_eq!(
+            rule.period_dates(1),
+            Some((date(2026, 10, 19), vec![date(2026, 10, 19), date(2026, 10, 21), date(2026, 10, 23)])),
+        );
+    }
+
+    #[test]
+    fn period_dates_monthly_without_the_day() {
+        let rule = recurrence(FREQ_MONTHLY, date(2026, 1, 31));
+        assert_eq!(rule.period_dates(1), Some((date(2026, 2, 1), vec![])));
+        assert_eq!(rule.period_dates(2), Some((date(2026, 3, 1), vec![date(2026, 3, 31)])));
+        // месяцы переходят в следующий год
+        assert_eq!(rule.period_dates(12), Some((date(2027, 1, 1), vec![date(2027, 1, 31)])));
+    }
+
+    #[test]
+    fn dates_between_weekly_weekday_set() {
+        let mut rule = recurrence(FREQ_WEEKLY, date(2026, 10, 7));
+        rule.repeat_every = 2;
+        rule.weekdays = vec![1, 3, 5];
+        // понедельник 5 октября раньше date_start и не считается
+        assert_eq!(
+            rule.dates_between(&[], date(2026, 10, 1), date(2026, 10, 31)),
+            vec![
+                date(2026, 10, 7),
+                date(2026, 10, 9),
+                date(2026, 10, 19),
+                date(2026, 10, 21),
+                date(2026, 10, 23),
+            ],
+        );
+    }
+
+    #[test]
+    fn dates_between_weekly_defaults_to_start_weekday() {
+        let rule = recurrence(FREQ_WEEKLY, date(2026, 10, 7));
+        assert_eq!(
+            rule.dates_between(&[], date(2026, 10, 1), date(2026, 10, 31)),
+            vec![date(2026, 10, 7), date(2026, 10, 14), date(2026, 10, 21), date(2026, 10, 28)],
+        );
+    }
+
+    #[test]
+    fn dates_between_monthly_nth_weekday() {
+        let mut rule = recurrence(FREQ_MONTHLY, date(2026, 10, 1));
+        rule.month_week = Some(2);
+        rule.month_weekday = Some(2);
+        assert_eq!(
+            rule.dates_between(&[], date(2026, 10, 1), date(2026, 12, 31)),
+            vec![date(2026, 10, 13), date(2026, 11, 10), date(2026, 12, 8)],
+        );
+    }
+
+    #[test]
+    fn dates_between_monthly_last_weekday() {
+        let mut rule = recurrence(FREQ_MONTHLY, date(2026, 10, 1));
+        rule.month_week = Some(-1);
+        rule.month_weekday = Some(5);
+        assert_eq!(
+            rule.dates_between(&[], date(2026, 10, 1), date(2026, 12, 31)),
+            vec![date(2026, 10, 30), date(2026, 11, 27), date(2026, 12, 25)],
+        );
+    }
+
+    #[test]
+    fn dates_between_monthly_day_31_skips_short_months() {
+        let rule = recurrence(FREQ_MONTHLY, date(2026, 1, 31));
+        assert_eq!(
+            rule.dates_between(&[], date(2026, 1, 1), date(2026, 8, 31)),
+            vec![date(2026, 1, 31), date(2026, 3, 31), date(2026, 5, 31), date(2026, 7, 31), date(2026, 8, 31)],
+        );
+    }
+
+    #[test]
+    fn dates_between_repeat_count_includes_exceptions() {
+        let mut rule = recurrence(FREQ_DAILY, date(2026, 10, 1));
+        rule.repeat_count = Some(5);
+        let exceptions = [date(2026, 10, 2), date(2026, 10, 4)];
+        // исключенные даты входят в repeat_count и не продлевают повторение
+        assert_eq!(
+            rule.dates_between(&exceptions, date(2026, 10, 1), date(2026, 10, 31)),
+            vec![date(2026, 10, 1), date(2026, 10, 3), date(2026, 10, 5)],
+        );
+        // счет идет от date_start, а не от начала промежутка
+        assert_eq!(
+            rule.dates_between(&exceptions, date(2026, 10, 4), date(2026, 10, 31)),
+            vec![date(2026, 10, 5)],
+        );
+    }
+
+    #[test]
+    fn dates_between_stops_at_repeat_until() {
+        let mut rule = recurrence(FREQ_DAILY, date(2026, 10, 1));
+        rule.repeat_every = 3;
+        rule.repeat_until = Some(date(2026, 10, 10));
+        assert_eq!(
+            rule.dates_between(&[date(2026, 10, 4)], date(2026, 9, 1), date(2026, 12, 31)),
+            vec![date(2026, 10, 1), date(2026, 10, 7), date(2026, 10, 10)],
+        );
+    }
+}
@@ -0,0 +1,427 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel::sql_query;
+use diesel::sql_types::{BigInt, Bool, Integer, Nullable, Numeric, Text};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::Error;
+use crate::models::{Goods, NewGoods, Organization};
+use crate::schema::{files, goods, goods_categories, goods_photos, organizations};
+use crate::utils::like_pattern;
+
+
+// Каталог товаров и услуг организаций. Товар принадлежит организации,
+// менять его может только владелец организации. street_id товара - копия
+// улицы организации (при переезде обновляется триггером в базе).
+
+pub const GOODS_IN_STOCK: &str = "in_stock";
+pub const GOODS_ON_ORDER: &str = "on_order";
+pub const GOODS_OUT_OF_STOCK: &str = "out_of_stock";
+
+pub const MAX_GOODS_PHOTOS: usize = 10;
+const MAX_GOODS_NAME_LENGTH: usize = 255;
+// Валюта цены, если она не указана
+const DEFAULT_CURRENCY: &str = "RUB";
+
+// Категория товаров. Категории образуют дерево через parent_id.
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable)]
+pub struct GoodsCategory {
+    pub id: i32,
+    pub name: String,
+    pub parent_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Insertable)]
+#[table_name = "goods_categories"]
+pub struct NewGoodsCategory {
+    pub name: String,
+    pub parent_id: Option<i32>,
+}
+
+impl GoodsCategory {
+    pub fn get_all(conn: &PgConnection) -> Result<Vec<GoodsCategory>, Error> {
+        goods_categories::table
+            .order((goods_categories::parent_id, goods_categories::name))
+            .load(conn)
+            .map_err(Error::from)
+    }
+
+    // Новая категория. Такое же имя у того же родителя - Conflict.
+    pub fn create(conn: &PgConnection, category: &NewGoodsCategory) -> Result<GoodsCategory, Error> {
+        if category.name.trim().is_empty() {
+            return Err(Error::Validation("name is required".to_string()));
+        }
+        if let Some(parent_id) = category.parent_id {
+            goods_categories::table
+                .find(parent_id)
+                .select(goods_categories::id)
+                .first::<i32>(conn)
+                .optional()?
+                .ok_or_else(|| Error::Validation(format!("unknown parent category: {}", parent_id)))?;
+        }
+        diesel::insert_into(goods_categories::table)
+            .values((
+                goods_categories::name.eq(category.name.trim()),
+                goods_categories::parent_id.eq(category.parent_id),
+            ))
+            .get_result(conn)
+            .map_err(Error::from)
+    }
+}
+
+// Данные товара от владельца организации
+#[derive(Debug, Clone, Deserialize)]
+pub struct GoodsForm {
+    pub name: String,
+    pub description: Option<String>,
+    pub category_id: Option<i32>,
+    pub price: Option<BigDecimal>,
+    pub currency: Option<String>,
+    pub availability: Option<String>,
+    #[serde(default)]
+    pub is_service: bool,
+    #[serde(default)]
+    pub photos: Vec<i32>,
+}
+
+impl GoodsForm {
+    pub fn validate(&self) -> Result<(), Error> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err(Error::Validation("name is required".to_string()));
+        }
+        if name.chars().count() > MAX_GOODS_NAME_LENGTH {
+            return Err(Error::Validation(format!("name is longer than {} characters", MAX_GOODS_NAME_LENGTH)));
+        }
+        if self.price.as_ref().is_some_and(|p| *p < BigDecimal::from(0)) {
+            return Err(Error::Validation("price must not be negative".to_string()));
+        }
+        if !self.currency().chars().all(|c| c.is_ascii_uppercase()) || self.currency().len() != 3 {
+            return Err(Error::Validation("currency must be a three-letter code".to_string()));
+        }
+        if ![GOODS_IN_STOCK, GOODS_ON_ORDER, GOODS_OUT_OF_STOCK].contains(&self.availability()) {
+            return Err(Error::Validation(format!("unknown availability: {}", self.availability())));
+        }
+        if self.photos.len() > MAX_GOODS_PHOTOS {
+            return Err(Error::Validation(format!("no more than {} photos per item", MAX_GOODS_PHOTOS)));
+        }
+        Ok(())
+    }
+
+    fn currency(&self) -> &str {
+        self.currency.as_deref().unwrap_or(DEFAULT_CURRENCY)
+    }
+
+    fn availability(&self) -> &str {
+        self.availability.as_deref().unwrap_or(GOODS_IN_STOCK)
+    }
+
+    fn description(&self) -> Option<String> {
+        self.description.as_deref().map(str::trim).filter(|d| !d.is_empty()).map(str::to_string)
+    }
+}
+
+#[derive(Debug, AsChangeset)]
+#[table_name = "goods"]
+#[changeset_options(treat_none_as_null = "true")]
+struct GoodsChanges {
+    name: String,
+    description: Option<String>,
+    category_id: Option<i32>,
+    price: Option<BigDecimal>,
+    currency: String,
+    availability: String,
+    is_service: bool,
+    updated_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "goods_photos"]
+struct NewGoodsPhoto {
+    goods_id: i32,
+    file_id: i32,
+    position: i16,
+}
+
+// Фотография товара
+#[derive(Debug, Clone, Serialize, Queryable)]
+pub struct GoodsPhoto {
+    pub file_id: i32,
+    pub file_url: Option<String>,
+}
+
+// Товар вместе с фотографиями, в таком виде он отдается в API
+#[derive(Debug, Serialize)]
+pub struct GoodsDetail {
+    #[serde(flatten)]
+    pub goods: Goods,
+    pub photos: Vec<GoodsPhoto>,
+}
+
+impl Goods {
+    // Каталог организации: сначала то, что в наличии, затем по названию
+    pub fn get_for_organization(conn: &PgConnection, organization_id: i32) -> Result<Vec<GoodsDetail>, Error> {
+        let items = goods::table
+            .filter(goods::organization_id.eq(organization_id))
+            .order((goods::availability.eq(GOODS_OUT_OF_STOCK), goods::name, goods::id))
+            .load::<Goods>(conn)?;
+        Goods::with_photos(conn, items)
+    }
+
+    pub fn into_detail(self, conn: &PgConnection) -> Result<GoodsDetail, Error> {
+        let mut items = Goods::with_photos(conn, vec![self])?;
+        Ok(items.remove(0))
+    }
+
+    fn with_photos(conn: &PgConnection, items: Vec<Goods>) -> Result<Vec<GoodsDetail>, Error> {
+        let ids: Vec<i32> = items.iter().map(|g| g.id).collect();
+        let photos = goods_photos::table
+            .inner_join(files::table)
+            .filter(goods_photos::goods_id.eq_any(&ids))
+            .filter(files::is_deleted.eq(false))
+            .order((goods_photos::goods_id, goods_photos::position))
+            .select((goods_photos::goods_id, files::id, files::file_url))
+            .load::<(i32, i32, Option<String>)>(conn)?;
+        Ok(items
+            .into_iter()
+            .map(|goods| {
+                let photos = photos
+                    .iter()
+                    .filter(|(goods_id, _, _)| *goods_id == goods.id)
+                    .map(|(_, file_id, file_url)| GoodsPhoto {
+                        file_id:  *file_id,
+                        file_url: file_url.clone(),
+                    })
+                    .collect();
+                GoodsDetail { goods, photos }
+            })
+            .collect())
+    }
+
+    // Фотографии товара заменяются целиком. Прикрепить можно только свои
+    // неудаленные файлы.
+    fn set_photos(conn: &PgConnection, goods_id: i32, user_id: i32, photos: &[i32]) -> Result<(), Error> {
+        let mut unique: Vec<i32> = Vec::with_capacity(photos.len());
+        for file_id in photos {
+            if !unique.contains(file_id) {
+                unique.push(*file_id);
+            }
+        }
+        let owned = files::table
+            .filter(files::id.eq_any(&unique))
+            .filter(files::user_id.eq(user_id))
+            .filter(files::is_deleted.eq(false))
+            .count()
+            .get_result::<i64>(conn)?;
+        if owned as usize != unique.len() {
+            return Err(Error::Validation("photos must be your own uploaded files".to_string()));
+        }
+        diesel::delete(goods_photos::table.filter(goods_photos::goods_id.eq(goods_id))).execute(conn)?;
+        let rows: Vec<NewGoodsPhoto> = unique
+            .iter()
+            .enumerate()
+            .map(|(position, file_id)| NewGoodsPhoto {
+                goods_id: goods_id,
+                file_id:  *file_id,
+                position: position as i16,
+            })
+            .collect();
+        diesel::insert_into(goods_photos::table).values(&rows).execute(conn)?;
+        Ok(())
+    }
+
+    // Организация товара, если пользователь - ее владелец. Иначе Forbidden.
+    fn owned_organization(conn: &PgConnection, organization_id: i32, user_id: i32) -> Result<Organization, Error> {
+        let organization = organizations::table
+            .find(organization_id)
+            .first::<Organization>(conn)?;
+        if !organization.is_owned_by(user_id) {
+            return Err(Error::Forbidden);
+        }
+        Ok(organization)
+    }
+
+    // Новый товар в каталоге организации. Добавлять может только владелец.
+    pub fn create_for_organization(
+        conn: &PgConnection,
+        organization_id: i32,
+        user_id: i32,
+        form: &GoodsForm,
+    ) -> Result<Goods, Error> {
+        form.validate()?;
+        conn.transaction(|| {
+            let organization = Goods::owned_organization(conn, organization_id, user_id)?;
+            let new_goods = NewGoods {
+                street_id:       organization.street_id,
+                name:            form.name.trim().to_string(),
+                description:     form.description(),
+                organization_id: Some(organization.id),
+                category_id:     form.category_id,
+                price:           form.price.clone(),
+                currency:        form.currency().to_string(),
+                availability:    form.availability().to_string(),
+                is_service:      form.is_service,
+            };
+            let goods = Goods::create(new_goods, conn)?;
+            Goods::set_photos(conn, goods.id, user_id, &form.photos)?;
+            Ok(goods)
+        })
+    }
+
+    // Изменение товара владельцем организации. Товар без организации
+    // никому не принадлежит и через API не меняется.
+    pub fn update_for_owner(conn: &PgConnection, goods_id: i32, user_id: i32, form: &GoodsForm) -> Result<Goods, Error> {
+        form.validate()?;
+        conn.transaction(|| {
+            let goods = goods::table
+                .find(goods_id)
+                .for_update()
+                .first::<Goods>(conn)?;
+            let organization_id = goods.organization_id.ok_or(Error::Forbidden)?;
+            Goods::owned_organization(conn, organization_id, user_id)?;
+            let changes = GoodsChanges {
+                name:         form.name.trim().to_string(),
+                description:  form.description(),
+                category_id:  form.category_id,
+                price:        form.price.clone(),
+                currency:     form.currency().to_string(),
+                availability: form.availability().to_string(),
+                is_service:   form.is_service,
+                updated_at:   Some(chrono::Local::now().naive_local()),
+            };
+            let goods = diesel::update(goods::table.find(goods_id))
+                .set(&changes)
+                .get_result::<Goods>(conn)?;
+            Goods::set_photos(conn, goods.id, user_id, &form.photos)?;
+            Ok(goods)
+        })
+    }
+
+    pub fn delete_for_owner(conn: &PgConnection, goods_id: i32, user_id: i32) -> Result<(), Error> {
+        conn.transaction(|| {
+            let goods = Goods::read_by_id(goods_id, conn)?;
+            let organization_id = goods.organization_id.ok_or(Error::Forbidden)?;
+            Goods::owned_organization(conn, organization_id, user_id)?;
+            diesel::delete(goods::table.find(goods_id)).execute(conn)?;
+            Ok(())
+        })
+    }
+}
+
+// Условия поиска по каталогу города
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct GoodsSearch {
+    pub q: Option<String>,
+    pub district_id: Option<i32>,
+    pub category_id: Option<i32>,   // Вместе с подкатегориями
+    pub min_price: Option<BigDecimal>,
+    pub max_price: Option<BigDecimal>,
+    pub currency: Option<String>,
+    #[serde(default)]
+    pub include_out_of_stock: bool,
+}
+
+impl GoodsSearch {
+    // Цены в разных валютах не сравниваются, поэтому поиск всегда идет
+    // в одной валюте, по умолчанию в рублях
+    fn currency(&self) -> &str {
+        self.currency.as_deref().unwrap_or(DEFAULT_CURRENCY)
+    }
+}
+
+// Найденный товар вместе с продавцом и его адресом
+#[derive(Debug, Serialize, QueryableByName)]
+pub struct GoodsSearchItem {
+    #[sql_type = "Integer"]
+    pub id: i32,
+    #[sql_type = "Text"]
+    pub name: String,
+    #[sql_type = "Nullable<Text>"]
+    pub description: Option<String>,
+    #[sql_type = "Nullable<Integer>"]
+    pub category_id: Option<i32>,
+    #[sql_type = "Nullable<Numeric>"]
+    pub price: Option<BigDecimal>,
+    #[sql_type = "Text"]
+    pub currency: String,
+    #[sql_type = "Text"]
+    pub availability: String,
+    #[sql_type = "Bool"]
+    pub is_service: bool,
+    #[sql_type = "Integer"]
+    pub organization_id: i32,
+    #[sql_type = "Text"]
+    pub organization_name: String,
+    #[sql_type = "Integer"]
+    pub street_id: i32,
+    #[sql_type = "Text"]
+    pub street_name: String,
+    #[sql_type = "Integer"]
+    pub district_id: i32,
+    #[sql_type = "Text"]
+    pub district_name: String,
+    #[serde(skip_serializing)]
+    #[sql_type = "BigInt"]
+    pub total: i64,
+}
+
+
+// Кто продает товар в городе: поиск по названию и описанию с фильтрами
+// по району, категории и цене в заданной валюте. Дешевые первыми, товары
+// без цены в конце.
+// Возвращает страницу результатов и общее количество.
+pub fn search_goods(
+    conn: &PgConnection,
+    city_id: i32,
+    search: &GoodsSearch,
+    offset: i64,
+    limit: i64,
+) -> Result<(Vec<GoodsSearchItem>, i64), Error> {
+    let q = search.q.as_deref().map(str::trim).filter(|q| !q.is_empty()).map(like_pattern);
+    let currency = search.currency();
+    if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_uppercase()) {
+        return Err(Error::Validation("currency must be a three-letter code".to_string()));
+    }
+    let items = sql_query(
+        "WITH RECURSIVE categories AS ( \
+             SELECT id FROM goods_categories WHERE id = $3 \
+             UNION \
+             SELECT c.id FROM goods_categories c JOIN categories ON c.parent_id = categories.id \
+         ) \
+         SELECT g.id, g.name::text AS name, g.description, g.category_id, g.price, \
+                g.currency::text AS currency, g.availability::text AS availability, g.is_service, \
+                o.id AS organization_id, o.name::text AS organization_name, \
+                s.id AS street_id, s.name::text AS street_name, \
+                d.id AS district_id, d.name::text AS district_name, \
+                COUNT(*) OVER () AS total \
+         FROM goods g \
+         JOIN organizations o ON o.id = g.organization_id \
+         JOIN streets s ON s.id = g.street_id \
+         JOIN districts d ON d.id = s.district_id \
+         WHERE d.city_id = $1 \
+           AND ($2::int IS NULL OR d.id = $2) \
+           AND ($3::int IS NULL OR g.category_id IN (SELECT id FROM categories)) \
+           AND ($4::text IS NULL OR g.name ILIKE $4 OR g.description ILIKE $4) \
+           AND ($5::numeric IS NULL OR g.price >= $5) \
+           AND ($6::numeric IS NULL OR g.price <= $6) \
+           AND g.currency = $7 \
+           AND ($8 OR g.availability <> 'out_of_stock') \
+         ORDER BY g.price ASC NULLS LAST, g.id \
+         OFFSET $9 LIMIT $10",
+    )
+    .bind::<Integer, _>(city_id)
+    .bind::<Nullable<Integer>, _>(search.district_id)
+    .bind::<Nullable<Integer>, _>(search.category_id)
+    .bind::<Nullable<Text>, _>(q)
+    .bind::<Nullable<Numeric>, _>(search.min_price.clone())
+    .bind::<Nullable<Numeric>, _>(search.max_price.clone())
+    .bind::<Text, _>(currency)
+    .bind::<Bool, _>(search.include_out_of_stock)
+    .bind::<BigInt, _>(offset)
+    .bind::<BigInt, _>(limit)
+    .load::<GoodsSearchItem>(conn)?;
+    // для страницы за пределами выдачи общее количество будет 0
+    let total = items.first().map_or(0, |item| item.total);
+    Ok((items, total))
+}
@@ -1,13 +1,16 @@
-// Для работы с actix-web
-use actix_web::{web, App, HttpServer, HttpResponse, Responder};
-
 // Для работы с библиотекой diesel
 use diesel::prelude::*;
-use diesel::r2d2::{self, ConnectionManager};
-
-// Для работы с PostgreSQL
-use dotenv::dotenv;
-use std::env;
+use crate::schema::{
+    file_comments,
+    file_downloads,
+    file_likes,
+    file_report,
+    file_tags,
+    files,
+    users,
+}; // Таблицы из schema.rs
+use crate::errors::Error;
+use crate::models::User;
 
 
 
@@ -23,18 +26,15 @@ pub struct FileTag {
 }
 
 //Создание новой записи в таблице "file_tags"
-use diesel::{Insertable, Queryable, ExpressionMethods, PgConnection, RunQueryDsl};
-use chrono::Utc;
-
 #[derive(Insertable)]
 #[table_name = "file_tags"]
-struct NewFileTag {
-    file_id: i32,
-    tag_id: i32,
+pub struct NewFileTag {
+    pub file_id: i32,
+    pub tag_id: i32,
 }
 
 impl NewFileTag {
-    fn create(file_id: i32, tag_id: i32, conn: &PgConnection) -> Result<(), diesel::result::Error> {
+    pub fn create(file_id: i32, tag_id: i32, conn: &PgConnection) -> Result<(), Error> {
         let new_file_tag = NewFileTag { file_id, tag_id };
         diesel::insert_into(file_tags::table)
             .values(&new_file_tag)
@@ -46,43 +46,47 @@ impl NewFileTag {
 //Запрос всех тегов для определенного файла по его file_id
 
 impl FileTag {
-    fn find_by_file_id(file_id: i32, conn: &PgConnection) -> Result<Vec<FileTag>, diesel::result::Error> {
+    pub fn find_by_file_id(file_id: i32, conn: &PgConnection) -> Result<Vec<FileTag>, Error> {
         file_tags::table.filter(file_tags::file_id.eq(file_id))
             .load(conn)
+            .map_err(Error::from)
     }
 }
 
 //Запрос всех файлов, к которым применен определенный тег по его tag_id
 
 impl FileTag {
-    fn find_files_by_tag_id(tag_id: i32, conn: &PgConnection) -> Result<Vec<File>, diesel::result::Error> {
+    pub fn find_files_by_tag_id(tag_id: i32, conn: &PgConnection) -> Result<Vec<File>, Error> {
         file_tags::table
             .filter(file_tags::tag_id.eq(tag_id))
             .inner_join(files::table)
             .select(files::all_columns)
             .load(conn)
+            .map_err(Error::from)
     }
 }
 
 //Удаление тега для определенного файла по file_id и tag_id
 
 impl FileTag {
-    fn delete(file_id: i32, tag_id: i32, conn: &PgConnection) -> Result<usize, diesel::result::Error> {
+    pub fn delete(file_id: i32, tag_id: i32, conn: &PgConnection) -> Result<usize, Error> {
         diesel::delete(file_tags::table
             .filter(file_tags::file_id.eq(file_id))
             .filter(file_tags::tag_id.eq(tag_id)))
             .execute(conn)
+            .map_err(Error::from)
     }
 }
 
 //Получение количества тегов для определенного файла
 
 impl FileTag {
-    fn count_tags_for_file(file_id: i32, conn: &PgConnection) -> Result<i64, diesel::result::Error> {
+    pub fn count_tags_for_file(file_id: i32, conn: &PgConnection) -> Result<i64, Error> {
         file_tags::table
             .filter(file_tags::file_id.eq(file_id))
             .count()
             .get_result(conn)
+            .map_err(Error::from)
     }
 }
 
@@ -108,20 +112,20 @@ pub struct File {
 // Структура для создания новой записи файла
 #[derive(Insertable)]
 #[table_name = "files"]
-struct NewFile<'a> {
-    user_id: i32,
-    title: &'a str,
-    description: Option<&'a str>,
-    file_type: &'a str,
-    file_url: Option<&'a str>,
-    is_private: bool,
-    is_approved: Option<bool>,
-    is_deleted: bool,
-    uploaded_at: chrono::NaiveDateTime,
+pub struct NewFile<'a> {
+    pub user_id: i32,
+    pub title: &'a str,
+    pub description: Option<&'a str>,
+    pub file_type: &'a str,
+    pub file_url: Option<&'a str>,
+    pub is_private: bool,
+    pub is_approved: Option<bool>,
+    pub is_deleted: bool,
+    pub uploaded_at: chrono::NaiveDateTime,
 }
 
 impl<'a> NewFile<'a> {
-    fn create(
+    pub fn create(
         user_id: i32,
         title: &'a str,
         description: Option<&'a str>,
@@ -129,7 +133,7 @@ impl<'a> NewFile<'a> {
         file_url: Option<&'a str>,
         is_private: bool,
         conn: &PgConnection,
-    ) -> Result<File, diesel::result::Error> {
+    ) -> Result<File, Error> {
         use diesel::RunQueryDsl;
 
         let uploaded_at = chrono::Utc::now().naive_utc();
@@ -147,16 +151,21 @@ impl<'a> NewFile<'a> {
         diesel::insert_into(files::table)
             .values(&new_file)
             .get_result(conn)
+            .map_err(Error::from)
     }
 }
 
 // Метод для поиска файла по его идентификатору (id)
-fn find_file_by_id(file_id: i32, conn: &PgConnection) -> Result<Option<File>, diesel::result::Error> {
-
+pub fn find_file_by_id(file_id: i32, conn: &PgConnection) -> Result<Option<File>, Error> {
+    files::table
+        .find(file_id)
+        .first(conn)
+        .optional()
+        .map_err(Error::from)
 }
 
 // Метод для обновления информации о файле
-fn update_file(
+pub fn update_file(
     file_id: i32,
     new_title: String,
     new_description: Option<String>,
@@ -164,61 +173,98 @@ fn update_file(
     new_file_url: Option<String>,
     new_is_private: bool,
     conn: &PgConnection,
-) -> Result<(), diesel::result::Error> {
-    
+) -> Result<(), Error> {
+    diesel::update(files::table.find(file_id))
+        .set((
+            files::title.eq(new_title),
+            files::description.eq(new_description),
+            files::file_type.eq(new_file_type),
+            files::file_url.eq(new_file_url),
+            files::is_private.eq(new_is_private),
+        ))
+        .execute(conn)?;
+    Ok(())
 }
 
 // Метод для удаления файла по его идентификатору (id)
-fn delete_file(file_id: i32, conn: &PgConnection) -> Result<(), diesel::result::Error> {
-    
+pub fn delete_file(file_id: i32, conn: &PgConnection) -> Result<(), Error> {
+    diesel::delete(files::table.find(file_id)).execute(conn)?;
+    Ok(())
 }
 
 // Метод для получения списка всех файлов пользователя
-fn get_user_files(user_id: i32, conn: &PgConnection) -> Result<Vec<File>, diesel::result::Error> {
-    
+pub fn get_user_files(user_id: i32, conn: &PgConnection) -> Result<Vec<File>, Error> {
+    files::table
+        .filter(files::user_id.eq(user_id))
+        .order(files::uploaded_at.desc())
+        .load(conn)
+        .map_err(Error::from)
 }
 
 // Метод для получения списка всех файлов в сообществе (если применимо)
-fn get_community_files(
+pub fn get_community_files(
     community_id: i32,
     conn: &PgConnection,
-) -> Result<Vec<File>, diesel::result::Error> {
-    
+) -> Result<Vec<File>, Error> {
+    files::table
+        .filter(files::community_id.eq(community_id))
+        .order(files::uploaded_at.desc())
+        .load(conn)
+        .map_err(Error::from)
 }
 
 // Метод для поиска файлов по их типу (например, "изображение", "документ")
-fn search_files_by_type(
+pub fn search_files_by_type(
     file_type: String,
     conn: &PgConnection,
-) -> Result<Vec<File>, diesel::result::Error> {
-    
+) -> Result<Vec<File>, Error> {
+    files::table
+        .filter(files::file_type.eq(file_type))
+        .order(files::uploaded_at.desc())
+        .load(conn)
+        .map_err(Error::from)
 }
 
 // Метод для одобрения файла администратором
-fn approve_file(file_id: i32, conn: &PgConnection) -> Result<(), diesel::result::Error> {
-
+pub fn approve_file(file_id: i32, conn: &PgConnection) -> Result<(), Error> {
+    diesel::update(files::table.find(file_id))
+        .set(files::is_approved.eq(true))
+        .execute(conn)?;
+    Ok(())
 }
 
 // Метод для получения списка всех файлов с учетом флага приватности
-fn get_all_files_with_privacy(
+pub fn get_all_files_with_privacy(
     is_private: bool,
     conn: &PgConnection,
-) -> Result<Vec<File>, diesel::result::Error> {
-    
+) -> Result<Vec<File>, Error> {
+    files::table
+        .filter(files::is_private.eq(is_private))
+        .order(files::uploaded_at.desc())
+        .load(conn)
+        .map_err(Error::from)
 }
 
 // Метод для получения списка файлов, одобренных администратором
-fn get_approved_files(conn: &PgConnection) -> Result<Vec<File>, diesel::result::Error> {
-    
+pub fn get_approved_files(conn: &PgConnection) -> Result<Vec<File>, Error> {
+    files::table
+        .filter(files::is_approved.eq(true))
+        .order(files::uploaded_at.desc())
+        .load(conn)
+        .map_err(Error::from)
 }
 
 // Метод для получения списка файлов, загруженных в определенное время
-fn get_files_uploaded_between_dates(
+pub fn get_files_uploaded_between_dates(
     start_date: chrono::NaiveDateTime,
     end_date: chrono::NaiveDateTime,
     conn: &PgConnection,
-) -> Result<Vec<File>, diesel::result::Error> {
-    
+) -> Result<Vec<File>, Error> {
+    files::table
+        .filter(files::uploaded_at.between(start_date, end_date))
+        .order(files::uploaded_at.desc())
+        .load(conn)
+        .map_err(Error::from)
 }
 
 //---------------------------------------------------------------------------------------------------------------
@@ -240,25 +286,23 @@ pub struct FileComment {
 
 //Создание нового комментария к файлу
 
-use diesel::Insertable;
-
 #[derive(Insertable)]
 #[table_name = "file_comments"]
-struct NewFileComment<'a> {
-    file_id: i32,
-    user_id: i32,
-    comment_text: &'a str,
-    is_deleted: bool,
-    created_at: chrono::NaiveDateTime,
+pub struct NewFileComment<'a> {
+    pub file_id: i32,
+    pub user_id: i32,
+    pub comment_text: &'a str,
+    pub is_deleted: bool,
+    pub created_at: chrono::NaiveDateTime,
 }
 
 impl<'a> NewFileComment<'a> {
-    fn create(
+    pub fn create(
         file_id: i32,
         user_id: i32,
         comment_text: &'a str,
         conn: &PgConnection,
-    ) -> Result<FileComment, diesel::result::Error> {
+    ) -> Result<FileComment, Error> {
         use diesel::RunQueryDsl;
 
         let created_at = chrono::Utc::now().naive_utc();
@@ -272,29 +316,31 @@ impl<'a> NewFileComment<'a> {
         diesel::insert_into(file_comments::table)
             .values(&new_comment)
             .get_result(conn)
+            .map_err(Error::from)
     }
 }
 
 //Получение комментариев к файлу по его file_id
 
 impl FileComment {
-    fn find_comments_by_file_id(
+    pub fn find_comments_by_file_id(
         file_id: i32,
         conn: &PgConnection,
-    ) -> Result<Vec<FileComment>, diesel::result::Error> {
+    ) -> Result<Vec<FileComment>, Error> {
         file_comments::table
             .filter(file_comments::file_id.eq(file_id))
             .load(conn)
+            .map_err(Error::from)
     }
 }
 
 //Удаление комментария к файлу по его id
 
 impl FileComment {
-    fn delete_comment_by_id(
+    pub fn delete_comment_by_id(
         comment_id: i32,
         conn: &PgConnection,
-    ) -> Result<(), diesel::result::Error> {
+    ) -> Result<(), Error> {
         diesel::delete(file_comments::table.filter(file_comments::id.eq(comment_id)))
             .execute(conn)?;
         Ok(())
@@ -304,23 +350,24 @@ impl FileComment {
 //Получение всех комментариев пользователя по его user_id
 
 impl FileComment {
-    fn find_comments_by_user_id(
+    pub fn find_comments_by_user_id(
         user_id: i32,
         conn: &PgConnection,
-    ) -> Result<Vec<FileComment>, diesel::result::Error> {
+    ) -> Result<Vec<FileComment>, Error> {
         file_comments::table
             .filter(file_comments::user_id.eq(user_id))
             .load(conn)
+            .map_err(Error::from)
     }
 }
 
 //Удаление всех комментариев для определенного файла по его file_id
 
 impl FileComment {
-    fn delete_comments_for_file(
+    pub fn delete_comments_for_file(
         file_id: i32,
         conn: &PgConnection,
-    ) -> Result<(), diesel::result::Error> {
+    ) -> Result<(), Error> {
         diesel::delete(file_comments::table.filter(file_comments::file_id.eq(file_id)))
             .execute(conn)?;
         Ok(())
@@ -343,38 +390,58 @@ pub struct FileLike {
 }
 impl FileLike{
 // Метод для создания нового лайка для файла
-fn create_like_for_file(
+pub fn create_like_for_file(
     file_id: i32,
     user_id: i32,
     conn: &PgConnection,
-) -> Result<FileLike, diesel::result::Error> {
-    
+) -> Result<FileLike, Error> {
+    diesel::insert_into(file_likes::table)
+        .values((
+            file_likes::file_id.eq(file_id),
+            file_likes::user_id.eq(user_id),
+            file_likes::created_at.eq(chrono::Utc::now().naive_utc()),
+        ))
+        .get_result(conn)
+        .map_err(Error::from)
 }
 
 
 // Метод для получения списка пользователей, поставивших лайки для файла
-fn get_users_who_liked_file(
+pub fn get_users_who_liked_file(
     file_id: i32,
     conn: &PgConnection,
-) -> Result<Vec<User>, diesel::result::Error> {
-
+) -> Result<Vec<User>, Error> {
+    file_likes::table
+        .inner_join(users::table)
+        .filter(file_likes::file_id.eq(file_id))
+        .select(users::all_columns)
+        .load(conn)
+        .map_err(Error::from)
 }
 
 // Метод для получения количества лайков для файла
-fn count_likes_for_file(
+pub fn count_likes_for_file(
     file_id: i32,
     conn: &PgConnection,
-) -> Result<i64, diesel::result::Error> {
-    
+) -> Result<i64, Error> {
+    file_likes::table
+        .filter(file_likes::file_id.eq(file_id))
+        .count()
+        .get_result(conn)
+        .map_err(Error::from)
 }
 
 // Метод для удаления лайка пользователя для файла
-fn delete_like_for_user(
+pub fn delete_like_for_user(
     user_id: i32,
     file_id: i32,
     conn: &PgConnection,
-) -> Result<(), diesel::result::Error> {
-    
+) -> Result<(), Error> {
+    diesel::delete(file_likes::table
+        .filter(file_likes::user_id.eq(user_id))
+        .filter(file_likes::file_id.eq(file_id)))
+        .execute(conn)?;
+    Ok(())
 }
 
 }
@@ -395,105 +462,157 @@ pub struct FileDownload {
 }
 impl FileDownload{
     // Метод для создания новой записи о загрузке файла
-    fn create_download_record(
+    pub fn create_download_record(
         file_id: i32,
         user_id: i32,
         conn: &PgConnection,
-    ) -> Result<FileDownload, diesel::result::Error> {
-    
+    ) -> Result<FileDownload, Error> {
+        diesel::insert_into(file_downloads::table)
+            .values((
+                file_downloads::file_id.eq(file_id),
+                file_downloads::user_id.eq(user_id),
+                file_downloads::downloaded_at.eq(chrono::Utc::now().naive_utc()),
+            ))
+            .get_result(conn)
+            .map_err(Error::from)
     }
 
     // Метод для получения списка пользователей, скачавших файл
-    fn get_users_who_downloaded_file(
+    pub fn get_users_who_downloaded_file(
         file_id: i32,
         conn: &PgConnection,
-    ) -> Result<Vec<User>, diesel::result::Error> {
-    
+    ) -> Result<Vec<User>, Error> {
+        file_downloads::table
+            .inner_join(users::table)
+            .filter(file_downloads::file_id.eq(file_id))
+            .select(users::all_columns)
+            .distinct()
+            .load(conn)
+            .map_err(Error::from)
     }
 
 // Метод для получения количества загрузок файла
-fn count_downloads_for_file(
+pub fn count_downloads_for_file(
     file_id: i32,
     conn: &PgConnection,
-) -> Result<i64, diesel::result::Error> {
-    
+) -> Result<i64, Error> {
+    file_downloads::table
+        .filter(file_downloads::file_id.eq(file_id))
+        .count()
+        .get_result(conn)
+        .map_err(Error::from)
 }
 
 // Метод для проверки, скачал ли пользователь файл
-fn user_downloaded_file(
+pub fn user_downloaded_file(
     user_id: i32,
     file_id: i32,
     conn: &PgConnection,
-) -> Result<bool, diesel::result::Error> {
-    
+) -> Result<bool, Error> {
+    diesel::select(diesel::dsl::exists(file_downloads::table
+        .filter(file_downloads::user_id.eq(user_id))
+        .filter(file_downloads::file_id.eq(file_id))))
+        .get_result(conn)
+        .map_err(Error::from)
 }
 
 // Метод для удаления записи о загрузке файла пользователем
-fn delete_download_record_for_user(
+pub fn delete_download_record_for_user(
     user_id: i32,
     file_id: i32,
     conn: &PgConnection,
-) -> Result<(), diesel::result::Error> {
-    
+) -> Result<(), Error> {
+    diesel::delete(file_downloads::table
+        .filter(file_downloads::user_id.eq(user_id))
+        .filter(file_downloads::file_id.eq(file_id)))
+        .execute(conn)?;
+    Ok(())
 }
 
 // Метод для получения списка файлов, которые пользователь скачал
-fn get_user_downloaded_files(
+pub fn get_user_downloaded_files(
     user_id: i32,
     conn: &PgConnection,
-) -> Result<Vec<File>, diesel::result::Error> {
-    
+) -> Result<Vec<File>, Error> {
+    files::table
+        .filter(files::id.eq_any(file_downloads::table
+            .filter(file_downloads::user_id.eq(user_id))
+            .select(file_downloads::file_id)))
+        .load(conn)
+        .map_err(Error::from)
 }
 
 // Метод для получения списка загрузок для определенного файла
-fn get_downloads_for_file(
+pub fn get_downloads_for_file(
     file_id: i32,
     conn: &PgConnection,
-) -> Result<Vec<FileDownload>, diesel::result::Error> {
-    
+) -> Result<Vec<FileDownload>, Error> {
+    file_downloads::table
+        .filter(file_downloads::file_id.eq(file_id))
+        .order(file_downloads::downloaded_at.desc())
+        .load(conn)
+        .map_err(Error::from)
 }
 
 // Метод для получения даты и времени последней загрузки файла пользователем
-fn get_last_download_time_for_user(
+pub fn get_last_download_time_for_user(
     user_id: i32,
     file_id: i32,
     conn: &PgConnection,
-) -> Result<Option<chrono::NaiveDateTime>, diesel::result::Error> {
-    
+) -> Result<Option<chrono::NaiveDateTime>, Error> {
+    file_downloads::table
+        .filter(file_downloads::user_id.eq(user_id))
+        .filter(file_downloads::file_id.eq(file_id))
+        .select(diesel::dsl::max(file_downloads::downloaded_at))
+        .get_result(conn)
+        .map_err(Error::from)
 }
 
 // Метод для удаления всех записей о загрузках для определенного файла
-fn delete_all_download_records_for_file(
+pub fn delete_all_download_records_for_file(
     file_id: i32,
     conn: &PgConnection,
-) -> Result<(), diesel::result::Error> {
-    
+) -> Result<(), Error> {
+    diesel::delete(file_downloads::table.filter(file_downloads::file_id.eq(file_id)))
+        .execute(conn)?;
+    Ok(())
 }
 
 // Метод для получения всех записей о загрузках для пользователя
-fn get_all_downloads_for_user(
+pub fn get_all_downloads_for_user(
     user_id: i32,
     conn: &PgConnection,
-) -> Result<Vec<FileDownload>, diesel::result::Error> {
-    
+) -> Result<Vec<FileDownload>, Error> {
+    file_downloads::table
+        .filter(file_downloads::user_id.eq(user_id))
+        .order(file_downloads::downloaded_at.desc())
+        .load(conn)
+        .map_err(Error::from)
 }
 
 // Метод для получения всех записей о загрузках файла в определенный период времени
-fn get_downloads_for_file_in_period(
+pub fn get_downloads_for_file_in_period(
     file_id: i32,
     start_time: chrono::NaiveDateTime,
     end_time: chrono::NaiveDateTime,
     conn: &PgConnection,
-) -> Result<Vec<FileDownload>, diesel::result::Error> {
-    
+) -> Result<Vec<FileDownload>, Error> {
+    file_downloads::table
+        .filter(file_downloads::file_id.eq(file_id))
+        .filter(file_downloads::downloaded_at.between(start_time, end_time))
+        .order(file_downloads::downloaded_at.desc())
+        .load(conn)
+        .map_err(Error::from)
 }
 
 // Метод для удаления всех записей о загрузках для пользователя
-fn delete_all_download_records_for_user(
+pub fn delete_all_download_records_for_user(
     user_id: i32,
     conn: &PgConnection,
-) -> Result<(), diesel::result::Error> {
-    
+) -> Result<(), Error> {
+    diesel::delete(file_downloads::table.filter(file_downloads::user_id.eq(user_id)))
+        .execute(conn)?;
+    Ok(())
 }
 
 }
@@ -516,11 +635,9 @@ pub struct FileReport {
     pub created_at: chrono::NaiveDateTime,
 }
 
-use crate::schema::{file_report, files, users}; 
-
 impl FileReport {
     // Метод для создания новой записи в таблице file_report
-    pub fn create_report(conn: &PgConnection, file_id: i32, user_id: i32, reason: String, description: Option<String>, status: Option<String>) -> QueryResult<FileReport> {
+    pub fn create_report(conn: &PgConnection, file_id: i32, user_id: i32, reason: String, description: Option<String>, status: Option<String>) -> Result<FileReport, Error> {
         let new_report = NewFileReport {
             file_id,
             user_id,
@@ -532,11 +649,13 @@ impl FileReport {
         diesel::insert_into(file_report::table)
             .values(&new_report)
             .get_result(conn)
+            .map_err(Error::from)
     }
 
     // Метод для получения отчета по идентификатору
-    pub fn get_report_by_id(conn: &PgConnection, report_id: i32) -> QueryResult<FileReport> {
+    pub fn get_report_by_id(conn: &PgConnection, report_id: i32) -> Result<FileReport, Error> {
         file_report::table.find(report_id).first(conn)
+            .map_err(Error::from)
     }
 
 }
@@ -566,36 +685,37 @@ impl NewFileReport {
     }
 
     // Метод для вставки нового отчета в базу данных
-    pub fn insert(&self, conn: &PgConnection) -> QueryResult<FileReport> {
+    pub fn insert(&self, conn: &PgConnection) -> Result<FileReport, Error> {
         diesel::insert_into(file_report::table)
             .values(self)
             .get_result(conn)
+            .map_err(Error::from)
     }
 }
 
-use diesel::prelude::*;
-use crate::schema::{file_report, files, users}; // Подключение необходимых схем и таблиц
-
 impl FileReport {
     // Метод для получения всех отчетов для конкретного файла
-    pub fn get_reports_for_file(conn: &PgConnection, file_id: i32) -> QueryResult<Vec<FileReport>> {
+    pub fn get_reports_for_file(conn: &PgConnection, file_id: i32) -> Result<Vec<FileReport>, Error> {
         file_report::table.filter(file_report::file_id.eq(file_id)).load(conn)
+            .map_err(Error::from)
     }
 
     // Метод для получения всех отчетов, созданных конкретным пользователем
-    pub fn get_reports_by_user(conn: &PgConnection, user_id: i32) -> QueryResult<Vec<FileReport>> {
+    pub fn get_reports_by_user(conn: &PgConnection, user_id: i32) -> Result<Vec<FileReport>, Error> {
         file_report::table.filter(file_report::user_id.eq(user_id)).load(conn)
+            .map_err(Error::from)
     }
 
     // Метод для обновления статуса отчета по его идентификатору
-    pub fn update_report_status(conn: &PgConnection, report_id: i32, new_status: String) -> QueryResult<FileReport> {
+    pub fn update_report_status(conn: &PgConnection, report_id: i32, new_status: String) -> Result<FileReport, Error> {
         diesel::update(file_report::table.filter(file_report::id.eq(report_id)))
             .set(file_report::status.eq(new_status))
             .get_result(conn)
+            .map_err(Error::from)
     }
 
     // Метод для удаления отчета по его идентификатору
-    pub fn delete_report(conn: &PgConnection, report_id: i32) -> QueryResult<()> {
+    pub fn delete_report(conn: &PgConnection, report_id: i32) -> Result<(), Error> {
         diesel::delete(file_report::table.filter(file_report::id.eq(report_id))).execute(conn)?;
         Ok(())
     }
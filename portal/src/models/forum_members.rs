@@ -0,0 +1,434 @@
+use chrono::{Duration, NaiveDateTime};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::Error;
+use crate::models::forum_moderation::{is_forum_moderator, log_member_action, ACTION_ADD_MEMBER, ACTION_REMOVE_MEMBER};
+use crate::models::organization_claims::is_administrator;
+use crate::models::{ForumModel as Forum, ForumTopic};
+use crate::schema::{
+    forum_invites,
+    forum_join_requests,
+    forum_members,
+    forum_moderators,
+    forum_topics,
+    forums,
+};
+
+
+// Закрытые форумы. Попасть в закрытый форум можно по ссылке-приглашению
+// или по заявке, которую принимает модератор. Темы и сообщения закрытого
+// форума видят только участники, модераторы форума и администраторы, для
+// остальных форума нет: все чтения возвращают NotFound, а списки его не
+// показывают. forum_members хранит только членство, роли (owner, lead,
+// moderator) назначаются в forum_moderators через forum_moderation.
+
+pub const JOIN_PENDING: &str = "pending";
+pub const JOIN_APPROVED: &str = "approved";
+pub const JOIN_REJECTED: &str = "rejected";
+
+const INVITE_TOKEN_LENGTH: usize = 32;
+pub const MAX_INVITE_DAYS: i64 = 365;
+const MAX_JOIN_MESSAGE_LENGTH: usize = 1000;
+
+//-------------------------------------------------------------------------------------
+// Доступ
+
+pub fn is_member(conn: &PgConnection, forum_id: i32, user_id: i32) -> Result<bool, Error> {
+    diesel::select(diesel::dsl::exists(forum_members::table.find((forum_id, user_id))))
+        .get_result(conn)
+        .map_err(Error::from)
+}
+
+// Форумы, которые видит пользователь: открытые, закрытые, где он участник
+// или модератор, а администратор - все. Аноним видит только открытые.
+pub fn visible_forum_ids(conn: &PgConnection, viewer_id: Option<i32>) -> Result<Vec<i32>, Error> {
+    let mut query = forums::table.select(forums::id).into_boxed();
+    match viewer_id {
+        Some(viewer_id) if is_administrator(conn, viewer_id)? => {},
+        Some(viewer_id) => {
+            query = query.filter(
+                forums::is_private
+                    .eq(false)
+                    .or(forums::id.eq_any(
+                        forum_members::table
+                            .filter(forum_members::user_id.eq(viewer_id))
+                            .select(forum_members::forum_id),
+                    ))
+                    .or(forums::id.eq_any(
+                        forum_moderators::table
+                            .filter(forum_moderators::user_id.eq(viewer_id))
+                            .select(forum_moderators::forum_id),
+                    )),
+            );
+        },
+        None => query = query.filter(forums::is_private.eq(false)),
+    }
+    query.load(conn).map_err(Error::from)
+}
+
+pub fn can_read_forum(conn: &PgConnection, forum: &Forum, viewer_id: Option<i32>) -> Result<bool, Error> {
+    if !forum.is_private {
+        return Ok(true);
+    }
+    match viewer_id {
+        Some(viewer_id) => Ok(is_member(conn, forum.id, viewer_id)? || is_forum_moderator(conn, viewer_id, forum.id)?),
+        None => Ok(false),
+    }
+}
+
+// Форум, если пользователь может его читать. Закрытый форум для чужих
+// не отличается от несуществующего.
+pub fn check_forum_access(conn: &PgConnection, forum_id: i32, viewer_id: Option<i32>) -> Result<Forum, Error> {
+    let forum = forums::table.find(forum_id).first::<Forum>(conn)?;
+    if !can_read_forum(conn, &forum, viewer_id)? {
+        return Err(Error::NotFound);
+    }
+    Ok(forum)
+}
+
+pub fn check_topic_access(conn: &PgConnection, topic_id: i32, viewer_id: Option<i32>) -> Result<ForumTopic, Error> {
+    let topic = forum_topics::table.find(topic_id).first::<ForumTopic>(conn)?;
+    check_forum_access(conn, topic.forum_id, viewer_id)?;
+    Ok(topic)
+}
+
+//-------------------------------------------------------------------------------------
+// Участники
+
+#[derive(Debug, Clone, Serialize, Queryable)]
+pub struct ForumMember {
+    pub forum_id: i32,
+    pub user_id: i32,
+    pub added_by: Option<i32>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "forum_members"]
+struct NewForumMember {
+    forum_id: i32,
+    user_id: i32,
+    added_by: Option<i32>,
+}
+
+// Принимать и удалять участников могут модераторы форума и администраторы
+fn check_can_manage(conn: &PgConnection, forum_id: i32, actor_id: i32) -> Result<(), Error> {
+    if !is_forum_moderator(conn, actor_id, forum_id)? {
+        return Err(Error::Forbidden);
+    }
+    Ok(())
+}
+
+// Добавление участника. Ok(None), если он уже участник форума.
+fn add_member(conn: &PgConnection, forum_id: i32, user_id: i32, added_by: Option<i32>) -> Result<Option<ForumMember>, Error> {
+    diesel::insert_into(forum_members::table)
+        .values(&NewForumMember {
+            forum_id: forum_id,
+            user_id:  user_id,
+            added_by: added_by,
+        })
+        .on_conflict_do_nothing()
+        .get_result(conn)
+        .optional()
+        .map_err(Error::from)
+}
+
+// Список участников видят те, кто может читать форум
+pub fn get_forum_members(
+    conn: &PgConnection,
+    forum_id: i32,
+    viewer_id: Option<i32>,
+    offset: i64,
+    limit: i64,
+) -> Result<(Vec<ForumMember>, i64), Error> {
+    check_forum_access(conn, forum_id, viewer_id)?;
+    let total = forum_members::table
+        .filter(forum_members::forum_id.eq(forum_id))
+        .count()
+        .get_result::<i64>(conn)?;
+    let items = forum_members::table
+        .filter(forum_members::forum_id.eq(forum_id))
+        .order(forum_members::created_at.asc())
+        .offset(offset)
+        .limit(limit)
+        .load(conn)?;
+    Ok((items, total))
+}
+
+// Добавить участника. Каждое добавление пишется в журнал модерации.
+pub fn add_forum_member(conn: &PgConnection, forum_id: i32, actor_id: i32, user_id: i32) -> Result<ForumMember, Error> {
+    conn.transaction(|| {
+        check_can_manage(conn, forum_id, actor_id)?;
+        match add_member(conn, forum_id, user_id, Some(actor_id))? {
+            Some(member) => {
+                log_member_action(conn, forum_id, actor_id, ACTION_ADD_MEMBER, user_id)?;
+                Ok(member)
+            },
+            None => forum_members::table.find((forum_id, user_id)).first(conn).map_err(Error::from),
+        }
+    })
+}
+
+// Удалить участника или выйти из форума самому. Удаление пишется в журнал
+// модерации. Роль в forum_moderators при этом не снимается.
+pub fn remove_member(conn: &PgConnection, forum_id: i32, actor_id: i32, user_id: i32) -> Result<(), Error> {
+    conn.transaction(|| {
+        if actor_id != user_id {
+            check_can_manage(conn, forum_id, actor_id)?;
+        }
+        let deleted = diesel::delete(forum_members::table.find((forum_id, user_id))).execute(conn)?;
+        if deleted == 0 {
+            return Err(Error::NotFound);
+        }
+        log_member_action(conn, forum_id, actor_id, ACTION_REMOVE_MEMBER, user_id)
+    })
+}
+
+//-------------------------------------------------------------------------------------
+// Приглашения
+
+#[derive(Debug, Clone, Serialize, Queryable)]
+pub struct ForumInvite {
+    pub id: i32,
+    pub forum_id: i32,
+    pub token: String,
+    pub created_by: Option<i32>,
+    pub created_at: NaiveDateTime,
+    pub expires_at: Option<NaiveDateTime>,
+    pub max_uses: Option<i32>,
+    pub uses: i32,
+    pub revoked_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "forum_invites"]
+struct NewForumInvite {
+    forum_id: i32,
+    token: String,
+    created_by: Option<i32>,
+    created_at: NaiveDateTime,
+    expires_at: Option<NaiveDateTime>,
+    max_uses: Option<i32>,
+}
+
+// expires_days - срок действия в днях, max_uses - сколько раз можно вступить.
+// Без них приглашение действует, пока его не отзовут.
+#[derive(Debug, Clone, Deserialize)]
+pub struct InviteForm {
+    pub expires_days: Option<i64>,
+    pub max_uses: Option<i32>,
+}
+
+fn generate_invite_token() -> String {
+    use rand::{distributions::Alphanumeric, Rng};
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(INVITE_TOKEN_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+impl ForumInvite {
+    pub fn is_active(&self, now: NaiveDateTime) -> bool {
+        self.revoked_at.is_none()
+            && self.expires_at.is_none_or(|expires_at| expires_at > now)
+            && self.max_uses.is_none_or(|max_uses| self.uses < max_uses)
+    }
+
+    pub fn create(conn: &PgConnection, forum_id: i32, actor_id: i32, form: &InviteForm, now: NaiveDateTime) -> Result<ForumInvite, Error> {
+        if form.expires_days.is_some_and(|days| !(1..=MAX_INVITE_DAYS).contains(&days)) {
+            return Err(Error::Validation(format!("expires_days must be between 1 and {}", MAX_INVITE_DAYS)));
+        }
+        if form.max_uses.is_some_and(|uses| uses < 1) {
+            return Err(Error::Validation("max_uses must be positive".to_string()));
+        }
+        let forum = forums::table.find(forum_id).first::<Forum>(conn)?;
+        if !forum.is_private {
+            return Err(Error::Validation("forum is public".to_string()));
+        }
+        check_can_manage(conn, forum.id, actor_id)?;
+        diesel::insert_into(forum_invites::table)
+            .values(&NewForumInvite {
+                forum_id:   forum.id,
+                token:      generate_invite_token(),
+                created_by: Some(actor_id),
+                created_at: now,
+                expires_at: form.expires_days.map(|days| now + Duration::days(days)),
+                max_uses:   form.max_uses,
+            })
+            .get_result(conn)
+            .map_err(Error::from)
+    }
+
+    // Действующие приглашения форума
+    pub fn get_active(conn: &PgConnection, forum_id: i32, actor_id: i32, now: NaiveDateTime) -> Result<Vec<ForumInvite>, Error> {
+        check_can_manage(conn, forum_id, actor_id)?;
+        let invites = forum_invites::table
+            .filter(forum_invites::forum_id.eq(forum_id))
+            .filter(forum_invites::revoked_at.is_null())
+            .order(forum_invites::created_at.desc())
+            .load::<ForumInvite>(conn)?;
+        Ok(invites.into_iter().filter(|invite| invite.is_active(now)).collect())
+    }
+
+    pub fn revoke(conn: &PgConnection, forum_id: i32, invite_id: i32, actor_id: i32, now: NaiveDateTime) -> Result<ForumInvite, Error> {
+        check_can_manage(conn, forum_id, actor_id)?;
+        let invite = forum_invites::table
+            .find(invite_id)
+            .filter(forum_invites::forum_id.eq(forum_id))
+            .first::<ForumInvite>(conn)?;
+        if invite.revoked_at.is_some() {
+            return Ok(invite);
+        }
+        diesel::update(forum_invites::table.find(invite.id))
+            .set(forum_invites::revoked_at.eq(Some(now)))
+            .get_result(conn)
+            .map_err(Error::from)
+    }
+
+    // Вступление по приглашению. Участник повторно приглашение не тратит,
+    // его неразобранная заявка на этот форум считается принятой.
+    pub fn accept(conn: &PgConnection, token: &str, user_id: i32, now: NaiveDateTime) -> Result<ForumMember, Error> {
+        conn.transaction(|| {
+            let invite = forum_invites::table
+                .filter(forum_invites::token.eq(token))
+                .for_update()
+                .first::<ForumInvite>(conn)?;
+            if let Some(member) = forum_members::table.find((invite.forum_id, user_id)).first::<ForumMember>(conn).optional()? {
+                return Ok(member);
+            }
+            if !invite.is_active(now) {
+                return Err(Error::Conflict("invite has expired".to_string()));
+            }
+            let member = add_member(conn, invite.forum_id, user_id, invite.created_by)?
+                .ok_or_else(|| Error::Conflict("you are already a member of this forum".to_string()))?;
+            diesel::update(forum_invites::table.find(invite.id))
+                .set(forum_invites::uses.eq(forum_invites::uses + 1))
+                .execute(conn)?;
+            diesel::update(
+                forum_join_requests::table
+                    .filter(forum_join_requests::forum_id.eq(invite.forum_id))
+                    .filter(forum_join_requests::user_id.eq(user_id))
+                    .filter(forum_join_requests::status.eq(JOIN_PENDING)),
+            )
+            .set((
+                forum_join_requests::status.eq(JOIN_APPROVED),
+                forum_join_requests::decided_by.eq(invite.created_by),
+                forum_join_requests::decided_at.eq(Some(now)),
+            ))
+            .execute(conn)?;
+            Ok(member)
+        })
+    }
+}
+
+//-------------------------------------------------------------------------------------
+// Заявки на вступление
+
+#[derive(Debug, Clone, Serialize, Queryable)]
+pub struct ForumJoinRequest {
+    pub id: i32,
+    pub forum_id: i32,
+    pub user_id: i32,
+    pub message: Option<String>,
+    pub status: String,
+    pub created_at: NaiveDateTime,
+    pub decided_by: Option<i32>,
+    pub decided_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "forum_join_requests"]
+struct NewForumJoinRequest<'a> {
+    forum_id: i32,
+    user_id: i32,
+    message: Option<&'a str>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct JoinRequestForm {
+    pub message: Option<String>,
+}
+
+impl ForumJoinRequest {
+    // Заявка подается в закрытый форум. Участник заявку не подает,
+    // повторная заявка до разбора первой - Conflict.
+    pub fn create(conn: &PgConnection, forum_id: i32, user_id: i32, form: &JoinRequestForm) -> Result<ForumJoinRequest, Error> {
+        let message = form.message.as_deref().map(str::trim).filter(|m| !m.is_empty());
+        if message.is_some_and(|m| m.chars().count() > MAX_JOIN_MESSAGE_LENGTH) {
+            return Err(Error::Validation(format!("message is longer than {} characters", MAX_JOIN_MESSAGE_LENGTH)));
+        }
+        let forum = forums::table.find(forum_id).first::<Forum>(conn)?;
+        if !forum.is_private {
+            return Err(Error::Validation("forum is public".to_string()));
+        }
+        if is_member(conn, forum.id, user_id)? {
+            return Err(Error::Conflict("you are already a member of this forum".to_string()));
+        }
+        let pending = forum_join_requests::table
+            .filter(forum_join_requests::forum_id.eq(forum.id))
+            .filter(forum_join_requests::user_id.eq(user_id))
+            .filter(forum_join_requests::status.eq(JOIN_PENDING))
+            .count()
+            .get_result::<i64>(conn)?;
+        if pending > 0 {
+            return Err(Error::Conflict("join request is already pending".to_string()));
+        }
+        diesel::insert_into(forum_join_requests::table)
+            .values(&NewForumJoinRequest {
+                forum_id: forum.id,
+                user_id:  user_id,
+                message:  message,
+            })
+            .get_result(conn)
+            .map_err(Error::from)
+    }
+
+    // Заявки форума для модераторов, старые первыми
+    pub fn get_for_forum(
+        conn: &PgConnection,
+        forum_id: i32,
+        actor_id: i32,
+        status: &str,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<ForumJoinRequest>, i64), Error> {
+        check_can_manage(conn, forum_id, actor_id)?;
+        let query = forum_join_requests::table
+            .filter(forum_join_requests::forum_id.eq(forum_id))
+            .filter(forum_join_requests::status.eq(status));
+        let total = query.count().get_result::<i64>(conn)?;
+        let items = query
+            .order((forum_join_requests::created_at.asc(), forum_join_requests::id.asc()))
+            .offset(offset)
+            .limit(limit)
+            .load(conn)?;
+        Ok((items, total))
+    }
+
+    // Принять или отклонить заявку. Принятый пользователь становится участником.
+    pub fn decide(conn: &PgConnection, request_id: i32, actor_id: i32, approve: bool, now: NaiveDateTime) -> Result<ForumJoinRequest, Error> {
+        conn.transaction(|| {
+            let request = forum_join_requests::table
+                .find(request_id)
+                .for_update()
+                .first::<ForumJoinRequest>(conn)?;
+            check_can_manage(conn, request.forum_id, actor_id)?;
+            if request.status != JOIN_PENDING {
+                return Err(Error::Conflict("join request has already been decided".to_string()));
+            }
+            if approve && add_member(conn, request.forum_id, request.user_id, Some(actor_id))?.is_some() {
+                log_member_action(conn, request.forum_id, actor_id, ACTION_ADD_MEMBER, request.user_id)?;
+            }
+            diesel::update(forum_join_requests::table.find(request.id))
+                .set((
+                    forum_join_requests::status.eq(if approve { JOIN_APPROVED } else { JOIN_REJECTED }),
+                    forum_join_requests::decided_by.eq(Some(actor_id)),
+                    forum_join_requests::decided_at.eq(Some(now)),
+                ))
+                .get_result(conn)
+                .map_err(Error::from)
+        })
+    }
+}
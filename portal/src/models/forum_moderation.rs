@@ -0,0 +1,746 @@
+use chrono::{Duration, NaiveDateTime};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::Error;
+use crate::models::forum_members::check_topic_access;
+use crate::models::organization_claims::is_administrator;
+use crate::models::{ForumPost, ForumReport, ForumTopic};
+use crate::schema::{
+    forum_bans,
+    forum_moderation_log,
+    forum_moderators,
+    forum_posts,
+    forum_reports,
+    forum_topics,
+    forum_warnings,
+    forums,
+};
+
+
+// Модерация форума. Пользователи жалуются на сообщения, жалобы попадают
+// в очередь форума, модераторы разбирают их одним из действий: отклонить,
+// скрыть сообщение, закрыть тему, предупредить автора или временно
+// забанить его на форуме. Модераторов назначают на каждый форум отдельно,
+// администраторы модерируют все форумы. Каждое действие пишется в журнал.
+// Все роли на форуме (owner, lead, moderator) хранятся в forum_moderators.
+
+pub const REPORT_PENDING: &str = "pending";
+pub const REPORT_RESOLVED: &str = "resolved";
+pub const REPORT_DISMISSED: &str = "dismissed";
+
+pub const ROLE_MODERATOR: &str = "moderator";
+pub const ROLE_LEAD: &str = "lead";
+pub const ROLE_OWNER: &str = "owner";
+
+pub const ACTION_DISMISS: &str = "dismiss";
+pub const ACTION_HIDE_POST: &str = "hide_post";
+pub const ACTION_LOCK_TOPIC: &str = "lock_topic";
+pub const ACTION_UNLOCK_TOPIC: &str = "unlock_topic";
+pub const ACTION_WARN_USER: &str = "warn_user";
+pub const ACTION_BAN_USER: &str = "ban_user";
+pub const ACTION_LIFT_BAN: &str = "lift_ban";
+pub const ACTION_GRANT_ROLE: &str = "grant_role";
+pub const ACTION_REVOKE_ROLE: &str = "revoke_role";
+pub const ACTION_ADD_MEMBER: &str = "add_member";
+pub const ACTION_REMOVE_MEMBER: &str = "remove_member";
+pub const ACTION_EDIT_POST: &str = "edit_post";
+pub const ACTION_DELETE_POST: &str = "delete_post";
+
+// Действия, которыми разбирается жалоба
+const REPORT_ACTIONS: &[&str] = &[ACTION_DISMISS, ACTION_HIDE_POST, ACTION_LOCK_TOPIC, ACTION_WARN_USER, ACTION_BAN_USER];
+
+pub const MAX_BAN_DAYS: i64 = 365;
+const MAX_REASON_LENGTH: usize = 1000;
+
+//-------------------------------------------------------------------------------------
+// Роли
+
+#[derive(Debug, Clone, Serialize, Queryable)]
+pub struct ForumModerator {
+    pub forum_id: i32,
+    pub user_id: i32,
+    pub role: String,
+    pub granted_by: Option<i32>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "forum_moderators"]
+struct NewForumModerator<'a> {
+    forum_id: i32,
+    user_id: i32,
+    role: &'a str,
+    granted_by: Option<i32>,
+}
+
+pub fn forum_role(conn: &PgConnection, forum_id: i32, user_id: i32) -> Result<Option<String>, Error> {
+    forum_moderators::table
+        .find((forum_id, user_id))
+        .select(forum_moderators::role)
+        .first(conn)
+        .optional()
+        .map_err(Error::from)
+}
+
+// Модератор форума: назначенный на этот форум в forum_moderators
+// либо администратор. Роли берутся только из forum_moderators.
+pub fn is_forum_moderator(conn: &PgConnection, user_id: i32, forum_id: i32) -> Result<bool, Error> {
+    if forum_role(conn, forum_id, user_id)?.is_some() {
+        return Ok(true);
+    }
+    is_administrator(conn, user_id)
+}
+
+pub fn check_forum_moderator(conn: &PgConnection, user_id: i32, forum_id: i32) -> Result<(), Error> {
+    if !is_forum_moderator(conn, user_id, forum_id)? {
+        return Err(Error::Forbidden);
+    }
+    Ok(())
+}
+
+pub fn get_forum_moderators(conn: &PgConnection, forum_id: i32) -> Result<Vec<ForumModerator>, Error> {
+    forum_moderators::table
+        .filter(forum_moderators::forum_id.eq(forum_id))
+        .order((forum_moderators::role.asc(), forum_moderators::created_at.asc()))
+        .load(conn)
+        .map_err(Error::from)
+}
+
+// Кто может назначать: администратор и владелец форума - любую роль,
+// lead форума - только модераторов, и снимать тоже только их
+fn check_can_assign(conn: &PgConnection, forum_id: i32, actor_id: i32, role: &str) -> Result<(), Error> {
+    if is_administrator(conn, actor_id)? {
+        return Ok(());
+    }
+    match forum_role(conn, forum_id, actor_id)?.as_deref() {
+        Some(ROLE_OWNER) => Ok(()),
+        Some(ROLE_LEAD) if role == ROLE_MODERATOR => Ok(()),
+        _ => Err(Error::Forbidden),
+    }
+}
+
+// У форума с владельцами должен остаться хотя бы один
+fn check_not_last_owner(conn: &PgConnection, forum_id: i32, user_id: i32) -> Result<(), Error> {
+    if forum_role(conn, forum_id, user_id)?.as_deref() != Some(ROLE_OWNER) {
+        return Ok(());
+    }
+    let owners = forum_moderators::table
+        .filter(forum_moderators::forum_id.eq(forum_id))
+        .filter(forum_moderators::role.eq(ROLE_OWNER))
+        .count()
+        .get_result::<i64>(conn)?;
+    if owners <= 1 {
+        return Err(Error::Conflict("forum must have an owner".to_string()));
+    }
+    Ok(())
+}
+
+pub fn set_forum_role(conn: &PgConnection, forum_id: i32, actor_id: i32, user_id: i32, role: &str) -> Result<ForumModerator, Error> {
+    if ![ROLE_OWNER, ROLE_LEAD, ROLE_MODERATOR].contains(&role) {
+        return Err(Error::Validation(format!("unknown role: {}", role)));
+    }
+    conn.transaction(|| {
+        forums::table.find(forum_id).select(forums::id).for_update().first::<i32>(conn)?;
+        check_can_assign(conn, forum_id, actor_id, role)?;
+        if let Some(current) = forum_role(conn, forum_id, user_id)? {
+            check_can_assign(conn, forum_id, actor_id, &current)?;
+            if role != ROLE_OWNER {
+                check_not_last_owner(conn, forum_id, user_id)?;
+            }
+        }
+        let moderator = diesel::insert_into(forum_moderators::table)
+            .values(&NewForumModerator {
+                forum_id:   forum_id,
+                user_id:    user_id,
+                role:       role,
+                granted_by: Some(actor_id),
+            })
+            .on_conflict((forum_moderators::forum_id, forum_moderators::user_id))
+            .do_update()
+            .set((forum_moderators::role.eq(role), forum_moderators::granted_by.eq(Some(actor_id))))
+            .get_result::<ForumModerator>(conn)?;
+        log_action(conn, LogEntry {
+            forum_id:       forum_id,
+            moderator_id:   actor_id,
+            action:         ACTION_GRANT_ROLE,
+            target_user_id: Some(user_id),
+            details:        Some(role.to_string()),
+            ..LogEntry::default()
+        })?;
+        Ok(moderator)
+    })
+}
+
+pub fn remove_forum_role(conn: &PgConnection, forum_id: i32, actor_id: i32, user_id: i32) -> Result<(), Error> {
+    conn.transaction(|| {
+        forums::table.find(forum_id).select(forums::id).for_update().first::<i32>(conn)?;
+        let role = forum_role(conn, forum_id, user_id)?.ok_or(Error::NotFound)?;
+        check_can_assign(conn, forum_id, actor_id, &role)?;
+        check_not_last_owner(conn, forum_id, user_id)?;
+        diesel::delete(forum_moderators::table.find((forum_id, user_id))).execute(conn)?;
+        log_action(conn, LogEntry {
+            forum_id:       forum_id,
+            moderator_id:   actor_id,
+            action:         ACTION_REVOKE_ROLE,
+            target_user_id: Some(user_id),
+            details:        Some(role),
+            ..LogEntry::default()
+        })
+    })
+}
+
+//-------------------------------------------------------------------------------------
+// Журнал
+
+#[derive(Debug, Clone, Serialize, Queryable)]
+pub struct ModerationLogEntry {
+    pub id: i32,
+    pub forum_id: i32,
+    pub moderator_id: Option<i32>,
+    pub action: String,
+    pub report_id: Option<i32>,
+    pub topic_id: Option<i32>,
+    pub post_id: Option<i32>,
+    pub target_user_id: Option<i32>,
+    pub details: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Default, Insertable)]
+#[table_name = "forum_moderation_log"]
+struct LogEntry<'a> {
+    forum_id: i32,
+    moderator_id: i32,
+    action: &'a str,
+    report_id: Option<i32>,
+    topic_id: Option<i32>,
+    post_id: Option<i32>,
+    target_user_id: Option<i32>,
+    details: Option<String>,
+}
+
+fn log_action(conn: &PgConnection, entry: LogEntry) -> Result<(), Error> {
+    diesel::insert_into(forum_moderation_log::table).values(&entry).execute(conn)?;
+    Ok(())
+}
+
+// Изменение состава участников закрытого форума (ACTION_ADD_MEMBER или ACTION_REMOVE_MEMBER)
+pub fn log_member_action(conn: &PgConnection, forum_id: i32, actor_id: i32, action: &str, user_id: i32) -> Result<(), Error> {
+    log_action(conn, LogEntry {
+        forum_id:       forum_id,
+        moderator_id:   actor_id,
+        action:         action,
+        target_user_id: Some(user_id),
+        ..LogEntry::default()
+    })
+}
+
+// Правка или удаление чужого сообщения модератором (ACTION_EDIT_POST или ACTION_DELETE_POST)
+pub fn log_post_action(conn: &PgConnection, forum_id: i32, actor_id: i32, action: &str, post: &ForumPost) -> Result<(), Error> {
+    log_action(conn, LogEntry {
+        forum_id:       forum_id,
+        moderator_id:   actor_id,
+        action:         action,
+        topic_id:       Some(post.topic_id),
+        post_id:        Some(post.id),
+        target_user_id: Some(post.user_id),
+        ..LogEntry::default()
+    })
+}
+
+// Журнал форума, новые записи первыми. Возвращает записи и их общее число.
+pub fn get_moderation_log(conn: &PgConnection, forum_id: i32, offset: i64, limit: i64) -> Result<(Vec<ModerationLogEntry>, i64), Error> {
+    let total = forum_moderation_log::table
+        .filter(forum_moderation_log::forum_id.eq(forum_id))
+        .count()
+        .get_result::<i64>(conn)?;
+    let items = forum_moderation_log::table
+        .filter(forum_moderation_log::forum_id.eq(forum_id))
+        .order((forum_moderation_log::created_at.desc(), forum_moderation_log::id.desc()))
+        .offset(offset)
+        .limit(limit)
+        .load(conn)?;
+    Ok((items, total))
+}
+
+//-------------------------------------------------------------------------------------
+// Предупреждения и баны
+
+#[derive(Debug, Clone, Serialize, Queryable)]
+pub struct ForumWarning {
+    pub id: i32,
+    pub forum_id: i32,
+    pub user_id: i32,
+    pub moderator_id: Option<i32>,
+    pub report_id: Option<i32>,
+    pub reason: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "forum_warnings"]
+struct NewForumWarning<'a> {
+    forum_id: i32,
+    user_id: i32,
+    moderator_id: Option<i32>,
+    report_id: Option<i32>,
+    reason: &'a str,
+}
+
+#[derive(Debug, Clone, Serialize, Queryable)]
+pub struct ForumBan {
+    pub id: i32,
+    pub forum_id: i32,
+    pub user_id: i32,
+    pub moderator_id: Option<i32>,
+    pub report_id: Option<i32>,
+    pub reason: String,
+    pub created_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+    pub lifted_at: Option<NaiveDateTime>,
+    pub lifted_by: Option<i32>,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "forum_bans"]
+struct NewForumBan<'a> {
+    forum_id: i32,
+    user_id: i32,
+    moderator_id: Option<i32>,
+    report_id: Option<i32>,
+    reason: &'a str,
+    created_at: NaiveDateTime,
+    expires_at: NaiveDateTime,
+}
+
+pub fn get_active_ban(conn: &PgConnection, forum_id: i32, user_id: i32, now: NaiveDateTime) -> Result<Option<ForumBan>, Error> {
+    forum_bans::table
+        .filter(forum_bans::forum_id.eq(forum_id))
+        .filter(forum_bans::user_id.eq(user_id))
+        .filter(forum_bans::lifted_at.is_null())
+        .filter(forum_bans::expires_at.gt(now))
+        .order(forum_bans::expires_at.desc())
+        .first(conn)
+        .optional()
+        .map_err(Error::from)
+}
+
+// Забаненный на форуме не может писать и править сообщения
+pub fn check_not_banned(conn: &PgConnection, forum_id: i32, user_id: i32, now: NaiveDateTime) -> Result<(), Error> {
+    if get_active_ban(conn, forum_id, user_id, now)?.is_some() {
+        return Err(Error::Forbidden);
+    }
+    Ok(())
+}
+
+pub fn get_active_bans(conn: &PgConnection, forum_id: i32, now: NaiveDateTime) -> Result<Vec<ForumBan>, Error> {
+    forum_bans::table
+        .filter(forum_bans::forum_id.eq(forum_id))
+        .filter(forum_bans::lifted_at.is_null())
+        .filter(forum_bans::expires_at.gt(now))
+        .order(forum_bans::expires_at.asc())
+        .load(conn)
+        .map_err(Error::from)
+}
+
+pub fn get_user_warnings(conn: &PgConnection, forum_id: i32, user_id: i32) -> Result<Vec<ForumWarning>, Error> {
+    forum_warnings::table
+        .filter(forum_warnings::forum_id.eq(forum_id))
+        .filter(forum_warnings::user_id.eq(user_id))
+        .order(forum_warnings::created_at.desc())
+        .load(conn)
+        .map_err(Error::from)
+}
+
+// Досрочное снятие бана
+pub fn lift_ban(conn: &PgConnection, forum_id: i32, ban_id: i32, moderator_id: i32, now: NaiveDateTime) -> Result<ForumBan, Error> {
+    conn.transaction(|| {
+        let ban = forum_bans::table
+            .find(ban_id)
+            .filter(forum_bans::forum_id.eq(forum_id))
+            .for_update()
+            .first::<ForumBan>(conn)?;
+        check_forum_moderator(conn, moderator_id, ban.forum_id)?;
+        if ban.lifted_at.is_some() || ban.expires_at <= now {
+            return Err(Error::Conflict("ban is not active".to_string()));
+        }
+        let ban = diesel::update(forum_bans::table.find(ban.id))
+            .set((forum_bans::lifted_at.eq(Some(now)), forum_bans::lifted_by.eq(Some(moderator_id))))
+            .get_result::<ForumBan>(conn)?;
+        log_action(conn, LogEntry {
+            forum_id:       ban.forum_id,
+            moderator_id:   moderator_id,
+            action:         ACTION_LIFT_BAN,
+            report_id:      ban.report_id,
+            target_user_id: Some(ban.user_id),
+            ..LogEntry::default()
+        })?;
+        Ok(ban)
+    })
+}
+
+//-------------------------------------------------------------------------------------
+// Закрытие тем
+
+// Закрыть или открыть тему. Повторное действие ничего не меняет и в журнал не пишется.
+pub fn set_topic_locked(
+    conn: &PgConnection,
+    topic_id: i32,
+    moderator_id: i32,
+    locked: bool,
+    reason: Option<&str>,
+    report_id: Option<i32>,
+) -> Result<ForumTopic, Error> {
+    conn.transaction(|| {
+        let topic = forum_topics::table.find(topic_id).for_update().first::<ForumTopic>(conn)?;
+        check_forum_moderator(conn, moderator_id, topic.forum_id)?;
+        if topic.is_locked == locked {
+            return Ok(topic);
+        }
+        let now = chrono::Local::now().naive_local();
+        let topic = diesel::update(forum_topics::table.find(topic.id))
+            .set((
+                forum_topics::is_locked.eq(locked),
+                forum_topics::locked_at.eq(if locked { Some(now) } else { None }),
+                forum_topics::locked_by.eq(if locked { Some(moderator_id) } else { None }),
+            ))
+            .get_result::<ForumTopic>(conn)?;
+        log_action(conn, LogEntry {
+            forum_id:     topic.forum_id,
+            moderator_id: moderator_id,
+            action:       if locked { ACTION_LOCK_TOPIC } else { ACTION_UNLOCK_TOPIC },
+            report_id:    report_id,
+            topic_id:     Some(topic.id),
+            details:      reason.map(str::to_string),
+            ..LogEntry::default()
+        })?;
+        Ok(topic)
+    })
+}
+
+//-------------------------------------------------------------------------------------
+// Жалобы
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReportForm {
+    pub reason: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "forum_reports"]
+struct NewForumReport<'a> {
+    post_id: i32,
+    user_id: i32,
+    reason: &'a str,
+    description: &'a str,
+}
+
+// Решение модератора по жалобе. reason - пояснение для журнала,
+// предупреждения или бана; ban_days - срок бана в днях.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ResolveForm {
+    pub action: String,
+    pub reason: Option<String>,
+    pub ban_days: Option<i64>,
+}
+
+impl ResolveForm {
+    pub fn validate(&self) -> Result<(), Error> {
+        if !REPORT_ACTIONS.contains(&self.action.as_str()) {
+            return Err(Error::Validation(format!("unknown action: {}", self.action)));
+        }
+        if self.reason.as_ref().is_some_and(|r| r.chars().count() > MAX_REASON_LENGTH) {
+            return Err(Error::Validation(format!("reason is longer than {} characters", MAX_REASON_LENGTH)));
+        }
+        if self.action == ACTION_BAN_USER && !(1..=MAX_BAN_DAYS).contains(&self.ban_days.unwrap_or(0)) {
+            return Err(Error::Validation(format!("ban_days must be between 1 and {}", MAX_BAN_DAYS)));
+        }
+        Ok(())
+    }
+
+    fn reason(&self) -> Option<&str> {
+        self.reason.as_deref().map(str::trim).filter(|r| !r.is_empty())
+    }
+}
+
+// Жалоба в очереди вместе с сообщением, на которое она подана
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueItem {
+    #[serde(flatten)]
+    pub report: ForumReport,
+    pub topic_id: i32,
+    pub topic_title: String,
+    pub post_user_id: i32,
+    pub post_content: Option<String>,   // Пусто, если сообщение уже скрыто
+    pub post_is_deleted: bool,
+    pub pending_reports: i64,           // Сколько всего неразобранных жалоб на это сообщение
+}
+
+// Переход жалобы при разборе: разбирается только ожидающая жалоба,
+// отклонение дает dismissed, любое другое действие - resolved
+fn resolved_status(status: &str, action: &str) -> Result<&'static str, Error> {
+    if status != REPORT_PENDING {
+        return Err(Error::Conflict("report has already been resolved".to_string()));
+    }
+    Ok(if action == ACTION_DISMISS { REPORT_DISMISSED } else { REPORT_RESOLVED })
+}
+
+// Запись в журнал о бане: срок и причина
+fn ban_details(days: i64, reason: Option<&str>) -> String {
+    format!("{} days{}", days, reason.map(|r| format!(": {}", r)).unwrap_or_default())
+}
+
+impl ForumReport {
+    // Жалоба на сообщение. На свое сообщение, на удаленное и на сообщение
+    // из недоступного закрытого форума жаловаться нельзя,
+    // повторная жалоба до разбора первой - Conflict.
+    pub fn create(conn: &PgConnection, post_id: i32, user_id: i32, form: &ReportForm) -> Result<ForumReport, Error> {
+        let reason = form.reason.trim();
+        if reason.is_empty() {
+            return Err(Error::Validation("reason is required".to_string()));
+        }
+        let description = form.description.as_deref().map(str::trim).unwrap_or_default();
+        if reason.chars().count() > 255 || description.chars().count() > MAX_REASON_LENGTH {
+            return Err(Error::Validation("report is too long".to_string()));
+        }
+        let post = ForumPost::get_by_id(conn, post_id)?;
+        check_topic_access(conn, post.topic_id, Some(user_id))?;
+        if post.is_deleted() {
+            return Err(Error::NotFound);
+        }
+        if post.user_id == user_id {
+            return Err(Error::Validation("you cannot report your own message".to_string()));
+        }
+        let pending = forum_reports::table
+            .filter(forum_reports::post_id.eq(post.id))
+            .filter(forum_reports::user_id.eq(user_id))
+            .filter(forum_reports::status.eq(REPORT_PENDING))
+            .count()
+            .get_result::<i64>(conn)?;
+        if pending > 0 {
+            return Err(Error::Conflict("you have already reported this message".to_string()));
+        }
+        diesel::insert_into(forum_reports::table)
+            .values(&NewForumReport {
+                post_id:     post.id,
+                user_id:     user_id,
+                reason:      reason,
+                description: description,
+            })
+            .get_result(conn)
+            .map_err(Error::from)
+    }
+
+    // Очередь форума: по умолчанию неразобранные жалобы, старые первыми
+    pub fn get_queue(
+        conn: &PgConnection,
+        forum_id: i32,
+        status: &str,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<QueueItem>, i64), Error> {
+        let query = forum_reports::table
+            .inner_join(forum_posts::table.inner_join(forum_topics::table))
+            .filter(forum_topics::forum_id.eq(forum_id))
+            .filter(forum_reports::status.eq(status));
+        let total = query.count().get_result::<i64>(conn)?;
+        let rows = query
+            .order((forum_reports::created_at.asc(), forum_reports::id.asc()))
+            .offset(offset)
+            .limit(limit)
+            .select((
+                forum_reports::all_columns,
+                forum_topics::id,
+                forum_topics::title,
+                forum_posts::user_id,
+                forum_posts::content,
+                forum_posts::deleted_at,
+            ))
+            .load::<(ForumReport, i32, String, i32, String, Option<NaiveDateTime>)>(conn)?;
+        let post_ids: Vec<i32> = rows.iter().map(|row| row.0.post_id).collect();
+        let counts = forum_reports::table
+            .filter(forum_reports::post_id.eq_any(&post_ids))
+            .filter(forum_reports::status.eq(REPORT_PENDING))
+            .group_by(forum_reports::post_id)
+            .select((forum_reports::post_id, diesel::dsl::sql::<diesel::sql_types::BigInt>("COUNT(*)")))
+            .load::<(i32, i64)>(conn)?;
+        let items = rows
+            .into_iter()
+            .map(|(report, topic_id, topic_title, post_user_id, content, deleted_at)| QueueItem {
+                pending_reports: counts
+                    .iter()
+                    .find(|(post_id, _)| *post_id == report.post_id)
+                    .map_or(0, |(_, count)| *count),
+                report:          report,
+                topic_id:        topic_id,
+                topic_title:     topic_title,
+                post_user_id:    post_user_id,
+                post_content:    if deleted_at.is_some() { None } else { Some(content) },
+                post_is_deleted: deleted_at.is_some(),
+            })
+            .collect();
+        Ok((items, total))
+    }
+
+    // Разбор жалобы. Скрытие сообщения закрывает и остальные жалобы на него.
+    // Предупредить или забанить модератора этого форума нельзя.
+    pub fn resolve(conn: &PgConnection, report_id: i32, moderator_id: i32, form: &ResolveForm) -> Result<ForumReport, Error> {
+        form.validate()?;
+        conn.transaction(|| {
+            let report = forum_reports::table.find(report_id).for_update().first::<ForumReport>(conn)?;
+            let status = resolved_status(&report.status, &form.action)?;
+            let post = ForumPost::get_by_id(conn, report.post_id)?;
+            let forum_id = forum_topics::table
+                .find(post.topic_id)
+                .select(forum_topics::forum_id)
+                .first::<i32>(conn)?;
+            check_forum_moderator(conn, moderator_id, forum_id)?;
+
+            let action = form.action.as_str();
+            let reason = form.reason();
+            let now = chrono::Local::now().naive_local();
+            if (action == ACTION_WARN_USER || action == ACTION_BAN_USER) && is_forum_moderator(conn, post.user_id, forum_id)? {
+                return Err(Error::Validation("moderators cannot be warned or banned".to_string()));
+            }
+            let mut details = reason.map(str::to_string);
+            match action {
+                ACTION_HIDE_POST => {
+                    ForumPost::soft_delete(conn, post.id, moderator_id)?;
+                },
+                ACTION_LOCK_TOPIC => {
+                    // в журнал пишет set_topic_locked, здесь только итог по жалобе
+                    set_topic_locked(conn, post.topic_id, moderator_id, true, reason, Some(report.id))?;
+                },
+                ACTION_WARN_USER => {
+                    diesel::insert_into(forum_warnings::table)
+                        .values(&NewForumWarning {
+                            forum_id:     forum_id,
+                            user_id:      post.user_id,
+                            moderator_id: Some(moderator_id),
+                            report_id:    Some(report.id),
+                            reason:       reason.unwrap_or(&report.reason),
+                        })
+                        .execute(conn)?;
+                },
+                ACTION_BAN_USER => {
+                    let days = form.ban_days.unwrap_or(1);
+                    diesel::insert_into(forum_bans::table)
+                        .values(&NewForumBan {
+                            forum_id:     forum_id,
+                            user_id:      post.user_id,
+                            moderator_id: Some(moderator_id),
+                            report_id:    Some(report.id),
+                            reason:       reason.unwrap_or(&report.reason),
+                            created_at:   now,
+                            expires_at:   now + Duration::days(days),
+                        })
+                        .execute(conn)?;
+                    details = Some(ban_details(days, reason));
+                },
+                _ => {},
+            }
+
+            let resolved = diesel::update(forum_reports::table.find(report.id))
+                .set((
+                    forum_reports::status.eq(status),
+                    forum_reports::resolved_by.eq(Some(moderator_id)),
+                    forum_reports::resolved_at.eq(Some(now)),
+                    forum_reports::resolution.eq(Some(action)),
+                ))
+                .get_result::<ForumReport>(conn)?;
+            if action == ACTION_HIDE_POST {
+                diesel::update(
+                    forum_reports::table
+                        .filter(forum_reports::post_id.eq(post.id))
+                        .filter(forum_reports::status.eq(REPORT_PENDING)),
+                )
+                .set((
+                    forum_reports::status.eq(REPORT_RESOLVED),
+                    forum_reports::resolved_by.eq(Some(moderator_id)),
+                    forum_reports::resolved_at.eq(Some(now)),
+                    forum_reports::resolution.eq(Some(action)),
+                ))
+                .execute(conn)?;
+            }
+            if action != ACTION_LOCK_TOPIC {
+                log_action(conn, LogEntry {
+                    forum_id:       forum_id,
+                    moderator_id:   moderator_id,
+                    action:         action,
+                    report_id:      Some(report.id),
+                    topic_id:       Some(post.topic_id),
+                    post_id:        Some(post.id),
+                    target_user_id: Some(post.user_id),
+                    details:        details,
+                })?;
+            }
+            Ok(resolved)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn form(action: &str, reason: Option<&str>, ban_days: Option<i64>) -> ResolveForm {
+        ResolveForm {
+            action:   action.to_string(),
+            reason:   reason.map(str::to_string),
+            ban_days: ban_days,
+        }
+    }
+
+    #[test]
+    fn pending_report_transitions() {
+        assert_eq!(resolved_status(REPORT_PENDING, ACTION_DISMISS).unwrap(), REPORT_DISMISSED);
+        for action in &[ACTION_HIDE_POST, ACTION_LOCK_TOPIC, ACTION_WARN_USER, ACTION_BAN_USER] {
+            assert_eq!(resolved_status(REPORT_PENDING, action).unwrap(), REPORT_RESOLVED);
+        }
+    }
+
+    #[test]
+    fn closed_report_is_final() {
+        for status in &[REPORT_RESOLVED, REPORT_DISMISSED] {
+            for action in REPORT_ACTIONS {
+                assert!(matches!(resolved_status(status, action), Err(Error::Conflict(_))));
+            }
+        }
+    }
+
+    #[test]
+    fn resolve_form_actions() {
+        for action in REPORT_ACTIONS.iter().filter(|a| **a != ACTION_BAN_USER) {
+            assert!(form(action, None, None).validate().is_ok());
+        }
+        // действия журнала, которыми жалобу не разобрать
+        for action in &[ACTION_UNLOCK_TOPIC, ACTION_LIFT_BAN, ACTION_GRANT_ROLE, "delete_forum"] {
+            assert!(matches!(form(action, None, None).validate(), Err(Error::Validation(_))));
+        }
+    }
+
+    #[test]
+    fn resolve_form_ban_days() {
+        assert!(form(ACTION_BAN_USER, None, Some(1)).validate().is_ok());
+        assert!(form(ACTION_BAN_USER, None, Some(MAX_BAN_DAYS)).validate().is_ok());
+        for days in &[None, Some(0), Some(-1), Some(MAX_BAN_DAYS + 1)] {
+            assert!(matches!(form(ACTION_BAN_USER, None, *days).validate(), Err(Error::Validation(_))));
+        }
+    }
+
+    #[test]
+    fn resolve_form_reason() {
+        let long = "я".repeat(MAX_REASON_LENGTH + 1);
+        assert!(matches!(form(ACTION_WARN_USER, Some(&long), None).validate(), Err(Error::Validation(_))));
+        assert_eq!(form(ACTION_WARN_USER, Some("  "), None).reason(), None);
+        assert_eq!(form(ACTION_WARN_USER, Some(" спам "), None).reason(), Some("спам"));
+    }
+
+    #[test]
+    fn ban_log_details() {
+        assert_eq!(ban_details(7, None), "7 days");
+        assert_eq!(ban_details(7, Some("спам")), "7 days: спам");
+    }
+}
@@ -0,0 +1,608 @@
+use chrono::NaiveDateTime;
+use diesel::dsl::sql;
+use diesel::prelude::*;
+use diesel::sql_types::BigInt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::errors::Error;
+use crate::models::forum_members::check_topic_access;
+use crate::models::forum_moderation::{check_not_banned, is_forum_moderator, log_post_action, ACTION_DELETE_POST, ACTION_EDIT_POST};
+use crate::models::{ForumPost, ForumTopic};
+use crate::schema::{forum_post_revisions, forum_posts, forum_topics};
+
+
+// Ветки обсуждения на форуме: ответ на конкретное сообщение, цитата
+// с ссылкой на исходное сообщение, история правок и мягкое удаление.
+// Удаленное сообщение остается в ветке без текста, чтобы ответы на него
+// не теряли смысла.
+
+pub const MAX_POST_LENGTH: usize = 20000;
+const MAX_QUOTE_LENGTH: usize = 2000;
+// Глубже ответы показываются на этом уровне, чтобы ветка не уезжала вправо
+pub const MAX_THREAD_DEPTH: usize = 8;
+// Больше этого числа сравнений разница показывается как замена целиком
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+// Писать в тему: только тем, кто видит форум, забаненным на форуме нельзя,
+// в закрытую тему - только модераторам
+fn check_can_write(conn: &PgConnection, topic: &ForumTopic, user_id: i32) -> Result<(), Error> {
+    check_topic_access(conn, topic.id, Some(user_id))?;
+    if is_forum_moderator(conn, user_id, topic.forum_id)? {
+        return Ok(());
+    }
+    check_not_banned(conn, topic.forum_id, user_id, chrono::Local::now().naive_local())?;
+    if topic.is_locked {
+        return Err(Error::Conflict("topic is locked".to_string()));
+    }
+    Ok(())
+}
+
+fn post_text(content: &str) -> Result<String, Error> {
+    let content = content.trim();
+    if content.is_empty() {
+        return Err(Error::Validation("message is empty".to_string()));
+    }
+    if content.chars().count() > MAX_POST_LENGTH {
+        return Err(Error::Validation(format!("message is longer than {} characters", MAX_POST_LENGTH)));
+    }
+    Ok(content.to_string())
+}
+
+// Цитата: сообщение и выделенный в нем фрагмент. Без фрагмента цитируется
+// начало сообщения.
+#[derive(Debug, Clone, Deserialize)]
+pub struct QuoteForm {
+    pub post_id: i32,
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ForumPostForm {
+    pub content: String,
+    pub parent_id: Option<i32>,
+    pub quote: Option<QuoteForm>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ForumPostEditForm {
+    pub content: String,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "forum_posts"]
+struct NewThreadPost<'a> {
+    topic_id: i32,
+    user_id: i32,
+    content: &'a str,
+    parent_id: Option<i32>,
+    quoted_post_id: Option<i32>,
+    quote_text: Option<&'a str>,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "forum_post_revisions"]
+struct NewRevision<'a> {
+    post_id: i32,
+    editor_id: Option<i32>,
+    content: &'a str,
+    created_at: NaiveDateTime,
+}
+
+// Прежняя версия текста сообщения
+#[derive(Debug, Clone, Serialize, Queryable)]
+pub struct ForumPostRevision {
+    pub id: i32,
+    pub post_id: i32,
+    pub editor_id: Option<i32>,
+    pub content: String,        // Текст до правки
+    pub created_at: NaiveDateTime, // Время правки
+}
+
+// Цитата в том виде, в котором она отдается в API. Ссылка ведет
+// на процитированное сообщение; если его удалили, текст цитаты скрыт.
+#[derive(Debug, Clone, Serialize)]
+pub struct ForumQuote {
+    pub post_id: Option<i32>,
+    pub user_id: Option<i32>,
+    pub link: Option<String>,
+    pub text: Option<String>,
+    pub is_deleted: bool,
+}
+
+// Сообщение в ветке. У удаленного сообщения текста и цитаты нет.
+#[derive(Debug, Clone, Serialize)]
+pub struct ForumPostDetail {
+    pub id: i32,
+    pub topic_id: i32,
+    pub user_id: i32,
+    pub parent_id: Option<i32>,
+    pub depth: usize,
+    pub content: Option<String>,
+    pub quote: Option<ForumQuote>,
+    pub created_at: NaiveDateTime,
+    pub edited_at: Option<NaiveDateTime>,
+    pub edit_count: i32,
+    pub is_deleted: bool,
+    pub deleted_by_moderator: bool,
+    pub replies_count: usize,
+}
+
+// Кусок разницы между версиями: equal, insert или delete
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffChunk {
+    pub op: &'static str,
+    pub text: String,
+}
+
+// Версия сообщения в истории правок и ее отличия от предыдущей
+#[derive(Debug, Clone, Serialize)]
+pub struct ForumPostVersion {
+    pub version: usize,
+    pub editor_id: Option<i32>,
+    pub created_at: NaiveDateTime,
+    pub content: String,
+    pub diff: Vec<DiffChunk>,
+}
+
+//-------------------------------------------------------------------------------------
+// Разница между версиями
+
+// Слова, пробелы и отдельные знаки препинания; склеенные вместе дают исходный текст
+fn diff_tokens(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut previous: Option<u8> = None;
+    for (index, c) in text.char_indices() {
+        let class = if c.is_alphanumeric() { 0 } else if c.is_whitespace() { 1 } else { 2 };
+        if index > start && (previous != Some(class) || class == 2) {
+            tokens.push(&text[start..index]);
+            start = index;
+        }
+        previous = Some(class);
+    }
+    if start < text.len() {
+        tokens.push(&text[start..]);
+    }
+    tokens
+}
+
+fn push_chunk(chunks: &mut Vec<DiffChunk>, op: &'static str, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    match chunks.last_mut() {
+        Some(last) if last.op == op => last.text.push_str(text),
+        _ => chunks.push(DiffChunk { op, text: text.to_string() }),
+    }
+}
+
+// Разница по словам через наибольшую общую подпоследовательность
+pub fn diff_text(old: &str, new: &str) -> Vec<DiffChunk> {
+    let a = diff_tokens(old);
+    let b = diff_tokens(new);
+    let prefix = a.iter().zip(b.iter()).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (middle_a, middle_b) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+    let mut chunks = Vec::new();
+    push_chunk(&mut chunks, "equal", &a[..prefix].concat());
+    if middle_a.len() * middle_b.len() > MAX_DIFF_CELLS {
+        push_chunk(&mut chunks, "delete", &middle_a.concat());
+        push_chunk(&mut chunks, "insert", &middle_b.concat());
+    }
+    else {
+        let (n, m) = (middle_a.len(), middle_b.len());
+        // lcs[i][j] - общая длина хвостов middle_a[i..] и middle_b[j..]
+        let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i][j] = if middle_a[i] == middle_b[j] {
+                    lcs[i + 1][j + 1] + 1
+                }
+                else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            if i < n && j < m && middle_a[i] == middle_b[j] {
+                push_chunk(&mut chunks, "equal", middle_a[i]);
+                i += 1;
+                j += 1;
+            }
+            else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+                push_chunk(&mut chunks, "delete", middle_a[i]);
+                i += 1;
+            }
+            else {
+                push_chunk(&mut chunks, "insert", middle_b[j]);
+                j += 1;
+            }
+        }
+    }
+    push_chunk(&mut chunks, "equal", &a[a.len() - suffix..].concat());
+    chunks
+}
+
+//-------------------------------------------------------------------------------------
+// Сообщения
+
+impl ForumPost {
+    pub fn get_by_id(conn: &PgConnection, post_id: i32) -> Result<ForumPost, Error> {
+        forum_posts::table
+            .find(post_id)
+            .first(conn)
+            .map_err(Error::from)
+    }
+
+    // Сообщение, если пользователь видит форум, в котором оно написано
+    pub fn get_visible(conn: &PgConnection, post_id: i32, viewer_id: Option<i32>) -> Result<ForumPost, Error> {
+        let post = ForumPost::get_by_id(conn, post_id)?;
+        check_topic_access(conn, post.topic_id, viewer_id)?;
+        Ok(post)
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+
+    // Ветка темы постранично: страница - это сообщения верхнего уровня,
+    // вместе с каждым идут все ответы на него в порядке обхода дерева.
+    // Возвращает сообщения и число сообщений верхнего уровня.
+    pub fn get_thread(
+        conn: &PgConnection,
+        topic_id: i32,
+        viewer_id: Option<i32>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<ForumPostDetail>, i64), Error> {
+        check_topic_access(conn, topic_id, viewer_id)?;
+        let roots = forum_posts::table
+            .filter(forum_posts::topic_id.eq(topic_id))
+            .filter(forum_posts::parent_id.is_null());
+        let total = roots.count().get_result::<i64>(conn)?;
+        let root_ids = roots
+            .order((forum_posts::created_at.asc(), forum_posts::id.asc()))
+            .offset(offset)
+            .limit(limit)
+            .select(forum_posts::id)
+            .load::<i32>(conn)?;
+        let posts = forum_posts::table
+            .filter(forum_posts::topic_id.eq(topic_id))
+            .order((forum_posts::created_at.asc(), forum_posts::id.asc()))
+            .load::<ForumPost>(conn)?;
+
+        let index: HashMap<i32, usize> = posts.iter().enumerate().map(|(i, p)| (p.id, i)).collect();
+        let mut replies: HashMap<i32, Vec<usize>> = HashMap::new();
+        for (i, post) in posts.iter().enumerate() {
+            if let Some(parent_id) = post.parent_id {
+                replies.entry(parent_id).or_default().push(i);
+            }
+        }
+
+        let mut items = Vec::new();
+        for root_id in root_ids {
+            // обход в глубину, ответы в порядке написания
+            let mut stack: Vec<(usize, usize)> = index.get(&root_id).map(|i| (*i, 0)).into_iter().collect();
+            while let Some((i, depth)) = stack.pop() {
+                let post = &posts[i];
+                let children = replies.get(&post.id).map_or(&[][..], Vec::as_slice);
+                items.push(post.detail(&posts, depth.min(MAX_THREAD_DEPTH), children.len()));
+                stack.extend(children.iter().rev().map(|child| (*child, depth + 1)));
+            }
+        }
+        Ok((items, total))
+    }
+
+    // Сообщение для API. posts - сообщения, среди которых ищется
+    // процитированное (обычно вся тема).
+    fn detail(&self, posts: &[ForumPost], depth: usize, replies_count: usize) -> ForumPostDetail {
+        let deleted = self.is_deleted();
+        let quote = match (deleted, &self.quote_text) {
+            (false, Some(text)) => {
+                let quoted = self.quoted_post_id.and_then(|id| posts.iter().find(|p| p.id == id));
+                let quoted_deleted = quoted.is_none_or(ForumPost::is_deleted);
+                Some(ForumQuote {
+                    post_id:    quoted.map(|p| p.id),
+                    user_id:    quoted.map(|p| p.user_id),
+                    link:       quoted.map(|p| format!("/api/v1/forum/posts/{}/", p.id)),
+                    text:       if quoted_deleted { None } else { Some(text.clone()) },
+                    is_deleted: quoted_deleted,
+                })
+            },
+            _ => None,
+        };
+        ForumPostDetail {
+            id:                   self.id,
+            topic_id:             self.topic_id,
+            user_id:              self.user_id,
+            parent_id:            self.parent_id,
+            depth:                depth,
+            content:              if deleted { None } else { Some(self.content.clone()) },
+            quote:                quote,
+            created_at:           self.created_at,
+            edited_at:            self.edited_at,
+            edit_count:           self.edit_count,
+            is_deleted:           deleted,
+            deleted_by_moderator: deleted && self.deleted_by != Some(self.user_id),
+            replies_count:        replies_count,
+        }
+    }
+
+    // Одно сообщение в том же виде, что и в ветке
+    pub fn into_detail(self, conn: &PgConnection) -> Result<ForumPostDetail, Error> {
+        let quoted = match self.quoted_post_id {
+            Some(quoted_id) => forum_posts::table.find(quoted_id).load::<ForumPost>(conn)?,
+            None => Vec::new(),
+        };
+        let replies = forum_posts::table
+            .filter(forum_posts::parent_id.eq(self.id))
+            .count()
+            .get_result::<i64>(conn)?;
+        Ok(self.detail(&quoted, 0, replies as usize))
+    }
+
+    // Сообщения списком (поиск, последние сообщения) в том же виде, что и
+    // в ветке: у удаленных нет текста, цитата из удаленного скрыта
+    pub fn into_details(conn: &PgConnection, posts: Vec<ForumPost>) -> Result<Vec<ForumPostDetail>, Error> {
+        let quoted_ids: Vec<i32> = posts.iter().filter_map(|p| p.quoted_post_id).collect();
+        let quoted = forum_posts::table
+            .filter(forum_posts::id.eq_any(quoted_ids))
+            .load::<ForumPost>(conn)?;
+        let post_ids: Vec<i32> = posts.iter().map(|p| p.id).collect();
+        let replies: HashMap<i32, i64> = forum_posts::table
+            .filter(forum_posts::parent_id.eq_any(post_ids))
+            .group_by(forum_posts::parent_id)
+            .select((forum_posts::parent_id, sql::<BigInt>("COUNT(*)")))
+            .load::<(Option<i32>, i64)>(conn)?
+            .into_iter()
+            .filter_map(|(parent_id, count)| parent_id.map(|id| (id, count)))
+            .collect();
+        Ok(posts
+            .iter()
+            .map(|p| p.detail(&quoted, 0, replies.get(&p.id).copied().unwrap_or(0) as usize))
+            .collect())
+    }
+
+    // Новое сообщение в теме: ответ на сообщение той же темы и цитата
+    // из сообщения той же темы. Отвечать на удаленное и цитировать его нельзя.
+    // В закрытую тему пишут только модераторы, забаненные на форуме не пишут.
+    pub fn create_in_topic(conn: &PgConnection, topic_id: i32, user_id: i32, form: &ForumPostForm) -> Result<ForumPost, Error> {
+        let content = post_text(&form.content)?;
+        conn.transaction(|| {
+            let topic = forum_topics::table
+                .find(topic_id)
+                .for_update()
+                .first::<ForumTopic>(conn)?;
+            check_can_write(conn, &topic, user_id)?;
+            let topic = topic.id;
+            let in_topic = |post_id: i32, what: &str| -> Result<ForumPost, Error> {
+                let post = forum_posts::table
+                    .find(post_id)
+                    .first::<ForumPost>(conn)
+                    .optional()?
+                    .filter(|p| p.topic_id == topic)
+                    .ok_or_else(|| Error::Validation(format!("{} is not in this topic", what)))?;
+                if post.is_deleted() {
+                    return Err(Error::Validation(format!("{} has been deleted", what)));
+                }
+                Ok(post)
+            };
+            if let Some(parent_id) = form.parent_id {
+                in_topic(parent_id, "parent message")?;
+            }
+            let quote = match form.quote {
+                Some(ref quote) => {
+                    let quoted = in_topic(quote.post_id, "quoted message")?;
+                    let text = match quote.text.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+                        Some(text) if quoted.content.contains(text) => text.to_string(),
+                        Some(_) => return Err(Error::Validation("quote does not match the message".to_string())),
+                        None => quoted.content.clone(),
+                    };
+                    let text: String = text.chars().take(MAX_QUOTE_LENGTH).collect();
+                    Some((quoted.id, text))
+                },
+                None => None,
+            };
+            let post = diesel::insert_into(forum_posts::table)
+                .values(&NewThreadPost {
+                    topic_id:       topic,
+                    user_id:        user_id,
+                    content:        &content,
+                    parent_id:      form.parent_id,
+                    quoted_post_id: quote.as_ref().map(|(id, _)| *id),
+                    quote_text:     quote.as_ref().map(|(_, text)| text.as_str()),
+                })
+                .get_result::<ForumPost>(conn)?;
+            diesel::update(forum_topics::table.find(topic))
+                .set(forum_topics::updated_at.eq(post.created_at))
+                .execute(conn)?;
+            Ok(post)
+        })
+    }
+
+    // Правка текста. Прежний текст уходит в историю. Править может автор
+    // или модератор, удаленное сообщение и сообщение в закрытой теме
+    // правит только модератор.
+    pub fn edit(conn: &PgConnection, post_id: i32, editor_id: i32, content: &str) -> Result<ForumPost, Error> {
+        let content = post_text(content)?;
+        conn.transaction(|| {
+            let post = forum_posts::table
+                .find(post_id)
+                .for_update()
+                .first::<ForumPost>(conn)?;
+            let topic = forum_topics::table.find(post.topic_id).first::<ForumTopic>(conn)?;
+            if post.user_id != editor_id && !is_forum_moderator(conn, editor_id, topic.forum_id)? {
+                return Err(Error::Forbidden);
+            }
+            if post.is_deleted() {
+                return Err(Error::Conflict("message has been deleted".to_string()));
+            }
+            check_can_write(conn, &topic, editor_id)?;
+            if post.content == content {
+                return Ok(post);
+            }
+            let now = chrono::Local::now().naive_local();
+            diesel::insert_into(forum_post_revisions::table)
+                .values(&NewRevision {
+                    post_id:    post.id,
+                    editor_id:  Some(editor_id),
+                    content:    &post.content,
+                    created_at: now,
+                })
+                .execute(conn)?;
+            let edited = diesel::update(forum_posts::table.find(post.id))
+                .set((
+                    forum_posts::content.eq(&content),
+                    forum_posts::edited_at.eq(Some(now)),
+                    forum_posts::edit_count.eq(forum_posts::edit_count + 1),
+                ))
+                .get_result::<ForumPost>(conn)?;
+            // чужое сообщение правит модератор, это пишется в журнал
+            if post.user_id != editor_id {
+                log_post_action(conn, topic.forum_id, editor_id, ACTION_EDIT_POST, &post)?;
+            }
+            Ok(edited)
+        })
+    }
+
+    // Мягкое удаление автором или модератором. Текст остается в базе
+    // и в истории, но в ветке не показывается.
+    pub fn soft_delete(conn: &PgConnection, post_id: i32, user_id: i32) -> Result<ForumPost, Error> {
+        conn.transaction(|| {
+            let post = forum_posts::table
+                .find(post_id)
+                .for_update()
+                .first::<ForumPost>(conn)?;
+            let topic = check_topic_access(conn, post.topic_id, Some(user_id))?;
+            if post.user_id != user_id && !is_forum_moderator(conn, user_id, topic.forum_id)? {
+                return Err(Error::Forbidden);
+            }
+            if post.is_deleted() {
+                return Ok(post);
+            }
+            let deleted = diesel::update(forum_posts::table.find(post.id))
+                .set((
+                    forum_posts::deleted_at.eq(Some(chrono::Local::now().naive_local())),
+                    forum_posts::deleted_by.eq(Some(user_id)),
+                ))
+                .get_result::<ForumPost>(conn)?;
+            if post.user_id != user_id {
+                log_post_action(conn, topic.forum_id, user_id, ACTION_DELETE_POST, &post)?;
+            }
+            Ok(deleted)
+        })
+    }
+
+    // История правок от первой версии к текущей, у каждой версии - отличия
+    // от предыдущей. Историю удаленного сообщения видят только модераторы.
+    pub fn get_history(conn: &PgConnection, post_id: i32, viewer_id: Option<i32>) -> Result<Vec<ForumPostVersion>, Error> {
+        let post = ForumPost::get_by_id(conn, post_id)?;
+        let topic = check_topic_access(conn, post.topic_id, viewer_id)?;
+        if post.is_deleted() {
+            let allowed = match viewer_id {
+                Some(viewer_id) => is_forum_moderator(conn, viewer_id, topic.forum_id)?,
+                None => false,
+            };
+            if !allowed {
+                return Err(Error::NotFound);
+            }
+        }
+        let revisions = forum_post_revisions::table
+            .filter(forum_post_revisions::post_id.eq(post.id))
+            .order(forum_post_revisions::id.asc())
+            .load::<ForumPostRevision>(conn)?;
+
+        // версия k+1 появилась правкой revisions[k], ее текст - это текст
+        // до следующей правки или текущий
+        let mut versions: Vec<ForumPostVersion> = Vec::with_capacity(revisions.len() + 1);
+        let first = revisions.first().map_or(&post.content, |r| &r.content);
+        versions.push(ForumPostVersion {
+            version:    1,
+            editor_id:  Some(post.user_id),
+            created_at: post.created_at,
+            content:    first.clone(),
+            diff:       Vec::new(),
+        });
+        for (index, revision) in revisions.iter().enumerate() {
+            let content = revisions.get(index + 1).map_or(&post.content, |r| &r.content);
+            versions.push(ForumPostVersion {
+                version:    index + 2,
+                editor_id:  revision.editor_id,
+                created_at: revision.created_at,
+                content:    content.clone(),
+                diff:       diff_text(&revision.content, content),
+            });
+        }
+        Ok(versions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunks(old: &str, new: &str) -> Vec<(&'static str, String)> {
+        diff_text(old, new).into_iter().map(|c| (c.op, c.text)).collect()
+    }
+
+    fn side(chunks: &[DiffChunk], skip: &str) -> String {
+        chunks.iter().filter(|c| c.op != skip).map(|c| c.text.as_str()).collect()
+    }
+
+    #[test]
+    fn tokens_join_back() {
+        let text = "Привет, мир!  Как дела?";
+        assert_eq!(diff_tokens(text), vec!["Привет", ",", " ", "мир", "!", "  ", "Как", " ", "дела", "?"]);
+        assert_eq!(diff_tokens(text).concat(), text);
+        assert!(diff_tokens("").is_empty());
+    }
+
+    #[test]
+    fn same_text() {
+        assert_eq!(chunks("Привет, мир", "Привет, мир"), vec![("equal", "Привет, мир".to_string())]);
+        assert!(chunks("", "").is_empty());
+    }
+
+    #[test]
+    fn replaced_word() {
+        assert_eq!(chunks("встреча в пятницу вечером", "встреча в субботу вечером"), vec![
+            ("equal", "встреча в ".to_string()),
+            ("delete", "пятницу".to_string()),
+            ("insert", "субботу".to_string()),
+            ("equal", " вечером".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn insert_into_empty() {
+        assert_eq!(chunks("", "новый текст"), vec![("insert", "новый текст".to_string())]);
+        assert_eq!(chunks("старый текст", ""), vec![("delete", "старый текст".to_string())]);
+    }
+
+    #[test]
+    fn sides_rebuild_both_versions() {
+        let old = "Сбор у фонтана в 10:00, берите воду и зонты.";
+        let new = "Сбор у входа в парк в 11:00, берите воду, зонты и хорошее настроение!";
+        let diff = diff_text(old, new);
+        assert_eq!(side(&diff, "insert"), old);
+        assert_eq!(side(&diff, "delete"), new);
+        // соседние куски одного вида склеены
+        assert!(diff.windows(2).all(|pair| pair[0].op != pair[1].op));
+    }
+
+    #[test]
+    fn large_change_without_table() {
+        let old: Vec<String> = (0..1500).map(|i| format!("a{}", i)).collect();
+        let new: Vec<String> = (0..1500).map(|i| format!("b{}", i)).collect();
+        let (old, new) = (old.join(" "), new.join(" "));
+        assert_eq!(chunks(&old, &new), vec![("delete", old.clone()), ("insert", new.clone())]);
+    }
+}
@@ -0,0 +1,333 @@
+use diesel::prelude::*;
+use diesel::deserialize::{self, FromSql};
+use diesel::pg::Pg;
+use diesel::serialize::{self, IsNull, Output, ToSql};
+use diesel::sql_query;
+use diesel::sql_types::{Double, Integer, Text, BigInt};
+use postgis::ewkb::{self, AsEwkbPoint, EwkbRead, EwkbWrite};
+use postgis_diesel::sql_types::Geometry;
+use serde::{Deserialize, Serialize};
+use std::io::{Cursor, Write};
+
+use crate::errors::Error;
+
+
+// Единый тип координат для всех моделей: точка PostGIS в WGS 84 (SRID 4326).
+// x - долгота, y - широта. В JSON точка выглядит как {"lat": .., "lon": ..}.
+// postgis_diesel 0.2 дает только тип колонки Geometry, поэтому чтение и
+// запись точки в EWKB реализованы здесь.
+#[derive(Debug, Clone, Copy, PartialEq, FromSqlRow, AsExpression)]
+#[sql_type = "Geometry"]
+pub struct GeoPoint {
+    pub x:    f64,
+    pub y:    f64,
+    pub srid: Option<i32>,
+}
+
+impl FromSql<Geometry, Pg> for GeoPoint {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+        let bytes = not_none!(bytes);
+        let point = ewkb::Point::read_ewkb(&mut Cursor::new(bytes))?;
+        Ok(GeoPoint {
+            x:    point.x,
+            y:    point.y,
+            srid: point.srid,
+        })
+    }
+}
+
+impl ToSql<Geometry, Pg> for GeoPoint {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        ewkb::Point::new(self.x, self.y, self.srid).as_ewkb().write_ewkb(out)?;
+        Ok(IsNull::No)
+    }
+}
+
+// Линии улиц и границы районов хранятся как мульти-геометрии и только читаются,
+// пишутся они через ST_GeomFromGeoJSON (см. geometry.rs)
+pub type GeoMultiLine = postgis_diesel::MultiLineStringC<ewkb::MultiLineString>;
+pub type GeoMultiPolygon = postgis_diesel::MultiPolygonC<ewkb::MultiPolygon>;
+
+pub const SRID: i32 = 4326;
+
+// Радиус поиска по умолчанию и максимальный, в метрах
+pub const DEFAULT_RADIUS: f64 = 1000.0;
+pub const MAX_RADIUS: f64 = 50_000.0;
+
+// Точка из широты и долготы с проверкой диапазонов
+pub fn geo_point(lat: f64, lon: f64) -> Result<GeoPoint, Error> {
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+        return Err(Error::Validation(format!("invalid coordinates: {}, {}", lat, lon)));
+    }
+    Ok(GeoPoint {
+        x:    lon,
+        y:    lat,
+        srid: Some(SRID),
+    })
+}
+
+// Координаты в том виде, в котором они приходят и уходят в API
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LatLon {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+impl From<&GeoPoint> for LatLon {
+    fn from(point: &GeoPoint) -> LatLon {
+        LatLon {
+            lat: point.y,
+            lon: point.x,
+        }
+    }
+}
+
+// Сериализация GeoPoint в {"lat", "lon"} для полей моделей:
+// #[serde(with = "crate::models::geo::serde_point")]
+pub mod serde_point {
+    use super::{geo_point, GeoPoint, LatLon};
+    use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(point: &GeoPoint, serializer: S) -> Result<S::Ok, S::Error> {
+        LatLon::from(point).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<GeoPoint, D::Error> {
+        let value = LatLon::deserialize(deserializer)?;
+        geo_point(value.lat, value.lon).map_err(D::Error::custom)
+    }
+}
+
+// То же для необязательных координат (Option<GeoPoint>)
+pub mod serde_point_opt {
+    use super::{geo_point, GeoPoint, LatLon};
+    use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(point: &Option<GeoPoint>, serializer: S) -> Result<S::Ok, S::Error> {
+        point.as_ref().map(LatLon::from).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<GeoPoint>, D::Error> {
+        match Option::<LatLon>::deserialize(deserializer)? {
+            Some(value) => geo_point(value.lat, value.lon).map(Some).map_err(D::Error::custom),
+            None => Ok(None),
+        }
+    }
+}
+
+// Прямоугольник на карте: minLon,minLat,maxLon,maxLat (как bbox в GeoJSON)
+#[derive(Debug, Clone, Copy)]
+pub struct BoundingBox {
+    pub min_lon: f64,
+    pub min_lat: f64,
+    pub max_lon: f64,
+    pub max_lat: f64,
+}
+
+impl BoundingBox {
+    pub fn parse(value: &str) -> Result<BoundingBox, Error> {
+        let invalid = || Error::Validation(format!("invalid bbox: {}", value));
+        let parts: Vec<f64> = value
+            .split(',')
+            .map(|part| part.trim().parse::<f64>())
+            .collect::<Result<_, _>>()
+            .map_err(|_| invalid())?;
+        if parts.len() != 4 {
+            return Err(invalid());
+        }
+        let bbox = BoundingBox {
+            min_lon: parts[0],
+            min_lat: parts[1],
+            max_lon: parts[2],
+            max_lat: parts[3],
+        };
+        geo_point(bbox.min_lat, bbox.min_lon)?;
+        geo_point(bbox.max_lat, bbox.max_lon)?;
+        if bbox.min_lon > bbox.max_lon || bbox.min_lat > bbox.max_lat {
+            return Err(invalid());
+        }
+        Ok(bbox)
+    }
+}
+
+// Виды объектов, которые ищутся рядом с точкой
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NearbyKind {
+    Organization,
+    UserPoint,
+    WifiCharging,
+    Event,
+    Advertisement,
+}
+
+pub const ALL_NEARBY_KINDS: &[NearbyKind] = &[
+    NearbyKind::Organization,
+    NearbyKind::UserPoint,
+    NearbyKind::WifiCharging,
+    NearbyKind::Event,
+    NearbyKind::Advertisement,
+];
+
+impl NearbyKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NearbyKind::Organization  => "organization",
+            NearbyKind::UserPoint     => "user_point",
+            NearbyKind::WifiCharging  => "wifi_charging",
+            NearbyKind::Event         => "event",
+            NearbyKind::Advertisement => "advertisement",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<NearbyKind, Error> {
+        ALL_NEARBY_KINDS
+            .iter()
+            .find(|kind| kind.as_str() == value)
+            .copied()
+            .ok_or_else(|| Error::Validation(format!("unknown kind: {}", value)))
+    }
+
+    // Список видов через запятую; пустой список - все виды
+    pub fn parse_list(value: Option<&str>) -> Result<Vec<NearbyKind>, Error> {
+        match value.map(str::trim).filter(|v| !v.is_empty()) {
+            Some(value) => value.split(',').map(|kind| NearbyKind::parse(kind.trim())).collect(),
+            None => Ok(ALL_NEARBY_KINDS.to_vec()),
+        }
+    }
+
+    // Таблица, колонки и условие видимости для запроса. Только константы,
+    // пользовательские значения передаются через bind.
+    fn source(&self) -> (&'static str, &'static str, &'static str, &'static str) {
+        match self {
+            NearbyKind::Organization  => ("organizations", "name", "location", "TRUE"),
+            NearbyKind::UserPoint     => ("user_points", "name", "location", "TRUE"),
+            NearbyKind::WifiCharging  => ("wifi_charging", "name", "location", "TRUE"),
+            // только опубликованные, не отмененные и еще не закончившиеся события
+            NearbyKind::Event         => ("events", "title", "coordinates",
+                "is_published AND cancelled_at IS NULL AND date_end >= NOW()"),
+            NearbyKind::Advertisement => ("advertisements", "title", "coordinates", "is_active"),
+        }
+    }
+}
+
+// Найденный объект с расстоянием до точки поиска в метрах
+#[derive(Debug, Serialize, QueryableByName)]
+pub struct NearbyItem {
+    #[sql_type = "Text"]
+    pub kind: String,
+    #[sql_type = "Integer"]
+    pub id: i32,
+    #[sql_type = "Text"]
+    pub name: String,
+    #[sql_type = "Double"]
+    pub lat: f64,
+    #[sql_type = "Double"]
+    pub lon: f64,
+    #[sql_type = "Double"]
+    pub distance: f64,
+}
+
+// Объекты в радиусе radius метров от точки, ближайшие первыми.
+// Расстояние считается по geography (на сфере), поэтому метры честные.
+pub fn find_nearby(
+    conn: &PgConnection,
+    center: &GeoPoint,
+    radius: f64,
+    kinds: &[NearbyKind],
+    limit: i64,
+) -> Result<Vec<NearbyItem>, Error> {
+    let mut result = Vec::new();
+    for kind in kinds {
+        let (table, name, column, condition) = kind.source();
+        let query = format!(
+            "SELECT '{kind}'::text AS kind, id, {name}::text AS name, \
+                    ST_Y({column}) AS lat, ST_X({column}) AS lon, \
+                    ST_Distance({column}::geography, ST_SetSRID(ST_MakePoint($1, $2), 4326)::geography) AS distance \
+             FROM {table} \
+             WHERE {column} IS NOT NULL AND {condition} \
+               AND ST_DWithin({column}::geography, ST_SetSRID(ST_MakePoint($1, $2), 4326)::geography, $3) \
+             ORDER BY distance \
+             LIMIT $4",
+            kind = kind.as_str(),
+            name = name,
+            column = column,
+            table = table,
+            condition = condition,
+        );
+        result.extend(
+            sql_query(query)
+                .bind::<Double, _>(center.x)
+                .bind::<Double, _>(center.y)
+                .bind::<Double, _>(radius)
+                .bind::<BigInt, _>(limit)
+                .load::<NearbyItem>(conn)?,
+        );
+    }
+    result.sort_by(|a, b| a.distance.partial_cmp(&b.distance).unwrap_or(std::cmp::Ordering::Equal));
+    result.truncate(limit as usize);
+    Ok(result)
+}
+
+// Объекты внутри прямоугольника. Если задан center, они сортируются
+// по расстоянию до него, иначе distance = 0.
+pub fn find_in_bbox(
+    conn: &PgConnection,
+    bbox: &BoundingBox,
+    center: Option<&GeoPoint>,
+    kinds: &[NearbyKind],
+    limit: i64,
+) -> Result<Vec<NearbyItem>, Error> {
+    let mut result = Vec::new();
+    for kind in kinds {
+        let (table, name, column, condition) = kind.source();
+        let query = format!(
+            "SELECT '{kind}'::text AS kind, id, {name}::text AS name, \
+                    ST_Y({column}) AS lat, ST_X({column}) AS lon, \
+                    COALESCE(ST_Distance({column}::geography, ST_SetSRID(ST_MakePoint($5, $6), 4326)::geography), 0) AS distance \
+             FROM {table} \
+             WHERE {column} IS NOT NULL AND {condition} \
+               AND {column} && ST_MakeEnvelope($1, $2, $3, $4, 4326) \
+             ORDER BY distance, id \
+             LIMIT $7",
+            kind = kind.as_str(),
+            name = name,
+            column = column,
+            table = table,
+            condition = condition,
+        );
+        result.extend(
+            sql_query(query)
+                .bind::<Double, _>(bbox.min_lon)
+                .bind::<Double, _>(bbox.min_lat)
+                .bind::<Double, _>(bbox.max_lon)
+                .bind::<Double, _>(bbox.max_lat)
+                .bind::<diesel::sql_types::Nullable<Double>, _>(center.map(|c| c.x))
+                .bind::<diesel::sql_types::Nullable<Double>, _>(center.map(|c| c.y))
+                .bind::<BigInt, _>(limit)
+                .load::<NearbyItem>(conn)?,
+        );
+    }
+    result.sort_by(|a, b| a.distance.partial_cmp(&b.distance).unwrap_or(std::cmp::Ordering::Equal));
+    result.truncate(limit as usize);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_bbox() {
+        let bbox = BoundingBox::parse("37.5, 55.7,37.7,55.8").unwrap();
+        assert_eq!((bbox.min_lon, bbox.min_lat, bbox.max_lon, bbox.max_lat), (37.5, 55.7, 37.7, 55.8));
+    }
+
+    #[test]
+    fn parse_invalid_bbox() {
+        for value in &["", "37.5,55.7,37.7", "37.5,55.7,37.7,55.8,1", "a,55.7,37.7,55.8",
+                       "37.7,55.7,37.5,55.8", "37.5,55.8,37.7,55.7", "-181,0,0,1", "0,-91,1,0", "NaN,0,1,1"] {
+            assert!(matches!(BoundingBox::parse(value), Err(Error::Validation(_))), "{}", value);
+        }
+    }
+}
@@ -0,0 +1,935 @@
+use diesel::prelude::*;
+use diesel::sql_query;
+use diesel::sql_types::{Array, BigInt, Double, Integer, Nullable, Text};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::Error;
+use crate::models::geo::{geo_point, GeoPoint};
+use crate::models::{NewOrganization, NewRequest, NewUserPoint, Organization, RequestModel as Request, UserPoint};
+use crate::schema::{cities, districts, street_addresses, streets};
+
+
+// Геокодер по собственному реестру улиц, без внешних сервисов.
+// Прямой поиск: адрес разбирается на город, улицу и дом, улица ищется
+// нечетко (триграммы, как в pg_trgm) с учетом сокращений типа улицы,
+// координаты берутся из точки дома, интерполяцией по диапазону номеров
+// или как центр известных домов улицы.
+// Обратный поиск: ближайший дом, диапазон номеров или объект на улице.
+
+pub const PRECISION_HOUSE: &str = "house";
+pub const PRECISION_INTERPOLATED: &str = "interpolated";
+pub const PRECISION_STREET: &str = "street";
+
+pub const DEFAULT_GEOCODE_LIMIT: i64 = 5;
+pub const MAX_GEOCODE_LIMIT: i64 = 20;
+
+// Ниже этой оценки улица не считается найденной
+const MIN_STREET_SCORE: f64 = 0.45;
+// Порог word_similarity в pg_trgm для отбора кандидатов, заметно ниже
+// MIN_STREET_SCORE, чтобы не терять улицы с опечатками
+const CANDIDATE_THRESHOLD: &str = "0.3";
+// Сколько улиц-кандидатов база отдает на окончательную оценку
+const MAX_CANDIDATES: i64 = 200;
+// Радиус обратного поиска, в метрах
+const REVERSE_RADIUS: f64 = 150.0;
+// Дом выигрывает у диапазона, если дальше него не больше чем на столько метров
+const HOUSE_PREFERENCE: f64 = 10.0;
+
+// Типы улиц: полное название и варианты написания (без точек)
+const STREET_TYPES: &[(&str, &[&str])] = &[
+    ("улица",      &["ул", "улица"]),
+    ("проспект",   &["пр-т", "пр-кт", "пркт", "просп", "проспект"]),
+    ("переулок",   &["пер", "переулок"]),
+    ("бульвар",    &["б-р", "бул", "бульвар"]),
+    ("шоссе",      &["ш", "шоссе"]),
+    ("площадь",    &["пл", "площадь"]),
+    ("набережная", &["наб", "набережная"]),
+    ("проезд",     &["пр-д", "проезд"]),
+    ("тупик",      &["туп", "тупик"]),
+    ("аллея",      &["ал", "аллея"]),
+    ("микрорайон", &["мкр", "мкрн", "мкр-н", "микрорайон"]),
+    ("квартал",    &["кв-л", "квартал"]),
+];
+// "пр." пишут и для проспекта, и для проезда: сокращение убирается, но тип не задает
+const AMBIGUOUS_TYPES: &[&str] = &["пр"];
+const NOISE_WORDS: &[&str] = &["им", "имени"];
+const CITY_MARKERS: &[&str] = &["г", "город"];
+const HOUSE_MARKERS: &[&str] = &["д", "дом"];
+// Части номера дома: корпус, строение, литера
+const BUILDING_MARKERS: &[(&str, &str)] = &[
+    ("корпус", "к"),
+    ("корп", "к"),
+    ("к", "к"),
+    ("строение", "с"),
+    ("стр", "с"),
+    ("с", "с"),
+    ("литера", ""),
+    ("лит", ""),
+];
+
+//-------------------------------------------------------------------------------------
+// Разбор адреса
+
+// Слова в нижнем регистре, ё -> е, без знаков препинания. Дефис и дробь
+// остаются: "пр-т", "1-я", "12/3".
+fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .replace('ё', "е")
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '/' { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .map(|t| t.trim_matches('-').to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+fn starts_with_digit(token: &str) -> bool {
+    token.starts_with(|c: char| c.is_ascii_digit())
+}
+
+fn is_house_token(token: &str) -> bool {
+    starts_with_digit(token)
+        || BUILDING_MARKERS.iter().any(|(marker, _)| *marker == token)
+        || token.chars().count() == 1
+}
+
+// Номер дома в нормальном виде и его числовая часть:
+// "12 корп. 1" -> ("12к1", 12), "5 А" -> ("5а", 5)
+fn normalize_house(tokens: &[String]) -> Option<(String, i32)> {
+    if !tokens.first().is_some_and(|t| starts_with_digit(t)) {
+        return None;
+    }
+    let mut house = String::new();
+    for token in tokens {
+        match BUILDING_MARKERS.iter().find(|(marker, _)| marker == token) {
+            Some((_, short)) => house.push_str(short),
+            None => house.push_str(token),
+        }
+    }
+    for (marker, short) in BUILDING_MARKERS.iter().filter(|(marker, _)| marker.chars().count() > 1) {
+        house = house.replace(marker, short);
+    }
+    let number = house
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<String>()
+        .parse::<i32>()
+        .ok()
+        .filter(|n| *n > 0)?;
+    Some((house, number))
+}
+
+// Название улицы без типа и лишних слов
+#[derive(Debug, Clone)]
+struct StreetName {
+    name: String,
+    kind: Option<&'static str>,
+}
+
+fn street_name(tokens: &[String]) -> StreetName {
+    let mut kind = None;
+    let mut words = Vec::new();
+    for token in tokens {
+        if let Some((full, _)) = STREET_TYPES.iter().find(|(_, variants)| variants.contains(&token.as_str())) {
+            kind = Some(*full);
+        }
+        else if !AMBIGUOUS_TYPES.contains(&token.as_str()) && !NOISE_WORDS.contains(&token.as_str()) {
+            words.push(token.as_str());
+        }
+    }
+    StreetName {
+        name: words.join(" "),
+        kind: kind,
+    }
+}
+
+#[derive(Debug)]
+struct ParsedAddress {
+    city: Option<String>,
+    street: StreetName,
+    house: Option<(String, i32)>,
+}
+
+// Разбор адреса через запятые: "г. Москва, ул. Ленина, д. 12к1",
+// "Ленина 12 корп 1", "пр-т Мира 5а, Москва". Город - по "г." или
+// лишняя часть без номера дома.
+fn parse_address(input: &str) -> Result<ParsedAddress, Error> {
+    let mut city = None;
+    let mut house = None;
+    let mut candidates: Vec<Vec<String>> = Vec::new();
+    for part in input.split(',') {
+        let mut tokens = tokenize(part);
+        if tokens.is_empty() {
+            continue;
+        }
+        if CITY_MARKERS.contains(&tokens[0].as_str()) {
+            city = Some(tokens[1..].join(" "));
+            continue;
+        }
+        if HOUSE_MARKERS.contains(&tokens[0].as_str()) {
+            house = normalize_house(&tokens[1..]);
+            continue;
+        }
+        if house.is_none() && !candidates.is_empty() && tokens.iter().all(|t| is_house_token(t)) {
+            house = normalize_house(&tokens);
+            continue;
+        }
+        // номер дома в конце части: "Ленина 12 к 1"
+        let mut split = tokens.len();
+        while split > 0 && is_house_token(&tokens[split - 1]) {
+            split -= 1;
+        }
+        while split < tokens.len() && !starts_with_digit(&tokens[split]) {
+            split += 1;
+        }
+        if split > 0 && split < tokens.len() && house.is_none() {
+            house = normalize_house(&tokens[split..]);
+            tokens.truncate(split);
+        }
+        candidates.push(tokens);
+    }
+    let street_index = candidates
+        .iter()
+        .position(|tokens| street_name(tokens).kind.is_some())
+        .or_else(|| candidates.len().checked_sub(1))
+        .ok_or_else(|| Error::Validation("street is not recognized".to_string()))?;
+    let street = street_name(&candidates.remove(street_index));
+    if street.name.is_empty() {
+        return Err(Error::Validation("street is not recognized".to_string()));
+    }
+    if city.is_none() {
+        city = candidates.first().map(|tokens| tokens.join(" "));
+    }
+    Ok(ParsedAddress { city, street, house })
+}
+
+// Сходство строк по триграммам слов (как similarity в pg_trgm), от 0 до 1
+fn similarity(a: &str, b: &str) -> f64 {
+    if a == b {
+        return 1.0;
+    }
+    let trigrams = |text: &str| {
+        let mut result: Vec<[char; 3]> = Vec::new();
+        for word in text.split_whitespace() {
+            let chars: Vec<char> = "  ".chars().chain(word.chars()).chain(" ".chars()).collect();
+            result.extend(chars.windows(3).map(|w| [w[0], w[1], w[2]]));
+        }
+        result.sort();
+        result.dedup();
+        result
+    };
+    let (a, b) = (trigrams(a), trigrams(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let common = a.iter().filter(|t| b.binary_search(t).is_ok()).count();
+    common as f64 / (a.len() + b.len() - common) as f64
+}
+
+//-------------------------------------------------------------------------------------
+// Поиск
+
+// Улица вместе с районом и городом
+#[derive(Debug, Clone, Serialize, Queryable, QueryableByName)]
+pub struct StreetPlace {
+    #[sql_type = "Integer"]
+    pub street_id: i32,
+    #[sql_type = "Text"]
+    pub street_name: String,
+    #[sql_type = "Integer"]
+    pub district_id: i32,
+    #[sql_type = "Text"]
+    pub district_name: String,
+    #[sql_type = "Integer"]
+    pub city_id: i32,
+    #[sql_type = "Text"]
+    pub city_name: String,
+}
+
+impl StreetPlace {
+    fn address(&self, house: Option<&str>) -> String {
+        match house {
+            Some(house) => format!("{}, {}, {}", self.city_name, self.street_name, house),
+            None => format!("{}, {}", self.city_name, self.street_name),
+        }
+    }
+}
+
+fn load_places(conn: &PgConnection, city_id: Option<i32>, street_id: Option<i32>) -> Result<Vec<StreetPlace>, Error> {
+    let mut query = streets::table
+        .inner_join(districts::table.inner_join(cities::table))
+        .select((streets::id, streets::name, districts::id, districts::name, cities::id, cities::name))
+        .into_boxed();
+    if let Some(city_id) = city_id {
+        query = query.filter(cities::id.eq(city_id));
+    }
+    if let Some(street_id) = street_id {
+        query = query.filter(streets::id.eq(street_id));
+    }
+    query.load(conn).map_err(Error::from)
+}
+
+// Улицы, похожие на название из запроса, по индексу idx_streets_name_trgm.
+// Окончательную оценку с учетом типа улицы и города дает geocode.
+fn street_candidates(conn: &PgConnection, name: &str, city_id: Option<i32>) -> Result<Vec<StreetPlace>, Error> {
+    conn.transaction(|| {
+        sql_query("SELECT set_config('pg_trgm.word_similarity_threshold', $1, true)")
+            .bind::<Text, _>(CANDIDATE_THRESHOLD)
+            .execute(conn)?;
+        sql_query(
+            "SELECT s.id AS street_id, s.name AS street_name, d.id AS district_id, \
+                    d.name AS district_name, c.id AS city_id, c.name AS city_name \
+             FROM streets s \
+             JOIN districts d ON d.id = s.district_id \
+             JOIN cities c ON c.id = d.city_id \
+             WHERE $1 <% translate(lower(s.name), 'ё', 'е') \
+               AND ($2::int IS NULL OR c.id = $2) \
+             ORDER BY word_similarity($1, translate(lower(s.name), 'ё', 'е')) DESC, s.id \
+             LIMIT $3",
+        )
+        .bind::<Text, _>(name)
+        .bind::<Nullable<Integer>, _>(city_id)
+        .bind::<BigInt, _>(MAX_CANDIDATES)
+        .load(conn)
+        .map_err(Error::from)
+    })
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GeocodeResult {
+    #[serde(flatten)]
+    pub place: StreetPlace,
+    pub house: Option<String>,
+    pub address: String,
+    pub precision: &'static str,
+    pub score: f64,
+    #[serde(with = "crate::models::geo::serde_point_opt")]
+    pub location: Option<GeoPoint>,
+}
+
+#[derive(Debug, QueryableByName)]
+struct PointRow {
+    #[sql_type = "Double"]
+    lat: f64,
+    #[sql_type = "Double"]
+    lon: f64,
+}
+
+fn to_point(row: Option<PointRow>) -> Result<Option<GeoPoint>, Error> {
+    match row {
+        Some(row) => Ok(Some(geo_point(row.lat, row.lon)?)),
+        None => Ok(None),
+    }
+}
+
+// Координаты дома на улице: точка дома, интерполяция по диапазону
+// номеров с той же четностью или середина улицы по ее линии и домам
+fn house_location(conn: &PgConnection, street_id: i32, house: Option<&(String, i32)>) -> Result<(Option<GeoPoint>, &'static str), Error> {
+    if let Some((house, number)) = house {
+        let exact = sql_query(
+            "SELECT ST_Y(location) AS lat, ST_X(location) AS lon \
+             FROM street_addresses WHERE street_id = $1 AND house = $2",
+        )
+        .bind::<Integer, _>(street_id)
+        .bind::<Text, _>(house)
+        .get_result::<PointRow>(conn)
+        .optional()?;
+        if exact.is_some() {
+            return Ok((to_point(exact)?, PRECISION_HOUSE));
+        }
+        // самый короткий подходящий диапазон точнее
+        let interpolated = sql_query(
+            "SELECT ST_Y(p) AS lat, ST_X(p) AS lon FROM ( \
+                 SELECT ST_LineInterpolatePoint(geometry, CASE WHEN to_number = from_number THEN 0.5 \
+                        ELSE ($2 - from_number)::float8 / (to_number - from_number) END) AS p \
+                 FROM house_ranges \
+                 WHERE street_id = $1 AND $2 BETWEEN from_number AND to_number \
+                   AND (parity = 'all' OR (parity = 'odd') = ($2 % 2 = 1)) \
+                 ORDER BY to_number - from_number, id \
+                 LIMIT 1 \
+             ) r",
+        )
+        .bind::<Integer, _>(street_id)
+        .bind::<Integer, _>(*number)
+        .get_result::<PointRow>(conn)
+        .optional()?;
+        if interpolated.is_some() {
+            return Ok((to_point(interpolated)?, PRECISION_INTERPOLATED));
+        }
+    }
+    // середина улицы, прижатая к ее линии
+    let center = sql_query(
+        "SELECT ST_Y(c) AS lat, ST_X(c) AS lon FROM ( \
+             SELECT ST_ClosestPoint(ST_Collect(g), ST_Centroid(ST_Collect(g))) AS c FROM ( \
+                 SELECT geometry AS g FROM streets WHERE id = $1 AND geometry IS NOT NULL \
+                 UNION ALL SELECT location FROM street_addresses WHERE street_id = $1 \
+                 UNION ALL SELECT geometry FROM house_ranges WHERE street_id = $1 \
+             ) street \
+         ) center WHERE c IS NOT NULL",
+    )
+    .bind::<Integer, _>(street_id)
+    .get_result::<PointRow>(conn)
+    .optional()?;
+    Ok((to_point(center)?, PRECISION_STREET))
+}
+
+// Прямой поиск: лучшие совпадения, лучшие первыми
+pub fn geocode(conn: &PgConnection, query: &str, city_id: Option<i32>, limit: i64) -> Result<Vec<GeocodeResult>, Error> {
+    let parsed = parse_address(query)?;
+    let mut scored: Vec<(f64, StreetPlace)> = street_candidates(conn, &parsed.street.name, city_id)?
+        .into_iter()
+        .map(|place| {
+            let target = street_name(&tokenize(&place.street_name));
+            let mut score = similarity(&parsed.street.name, &target.name);
+            match (parsed.street.kind, target.kind) {
+                (Some(a), Some(b)) if a == b => score += 0.1,
+                (Some(_), Some(_)) => score -= 0.15,
+                _ => {},
+            }
+            if let Some(ref city) = parsed.city {
+                let city_name = tokenize(&place.city_name).join(" ");
+                score += if similarity(city, &city_name) >= 0.5 { 0.05 } else { -0.1 };
+            }
+            (score.clamp(0.0, 1.0), place)
+        })
+        .filter(|(score, _)| *score >= MIN_STREET_SCORE)
+        .collect();
+    scored.sort_by(|a, b| {
+        b.0.partial_cmp(&a.0)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.1.street_id.cmp(&b.1.street_id))
+    });
+    scored.truncate(limit.max(1) as usize);
+
+    let mut result = Vec::with_capacity(scored.len());
+    for (score, place) in scored {
+        let (location, precision) = house_location(conn, place.street_id, parsed.house.as_ref())?;
+        let house = parsed.house.as_ref().map(|(house, _)| house.clone());
+        result.push(GeocodeResult {
+            address:   place.address(house.as_deref()),
+            place:     place,
+            house:     house,
+            precision: precision,
+            score:     (score * 100.0).round() / 100.0,
+            location:  location,
+        });
+    }
+    Ok(result)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReverseGeocodeResult {
+    #[serde(flatten)]
+    pub place: StreetPlace,
+    pub house: Option<String>,
+    pub address: String,
+    pub precision: &'static str,
+    pub distance: f64,   // От точки до найденного, в метрах
+}
+
+#[derive(Debug, QueryableByName)]
+struct NearestRow {
+    #[sql_type = "Integer"]
+    street_id: i32,
+    #[sql_type = "Nullable<Text>"]
+    house: Option<String>,
+    #[sql_type = "Integer"]
+    from_number: i32,
+    #[sql_type = "Integer"]
+    to_number: i32,
+    #[sql_type = "Text"]
+    parity: String,
+    #[sql_type = "Double"]
+    fraction: f64,
+    #[sql_type = "Double"]
+    distance: f64,
+}
+
+// Номер дома в точке fraction отрезка диапазона с нужной четностью
+fn interpolate_number(row: &NearestRow) -> i32 {
+    let (from, to) = (row.from_number, row.to_number);
+    let span = to.checked_sub(from).filter(|span| *span >= 0).unwrap_or(0);
+    let mut number = from.saturating_add((span as f64 * row.fraction.clamp(0.0, 1.0)).round() as i32);
+    let remainder = match row.parity.as_str() {
+        "odd" => Some(1),
+        "even" => Some(0),
+        _ => None,
+    };
+    if let Some(remainder) = remainder {
+        if number % 2 != remainder {
+            number = if number < to { number + 1 } else { number - 1 };
+        }
+    }
+    number.max(from).min(to)
+}
+
+fn nearest(conn: &PgConnection, query: &str, point: &GeoPoint) -> Result<Option<NearestRow>, Error> {
+    sql_query(query)
+        .bind::<Double, _>(point.x)
+        .bind::<Double, _>(point.y)
+        .bind::<Double, _>(REVERSE_RADIUS)
+        .get_result::<NearestRow>(conn)
+        .optional()
+        .map_err(Error::from)
+}
+
+// Обратный поиск: адрес у точки на карте. Без домов и диапазонов
+// рядом - ближайшая линия улицы, улица ближайшей организации или отметки.
+pub fn reverse_geocode(conn: &PgConnection, point: &GeoPoint) -> Result<Option<ReverseGeocodeResult>, Error> {
+    let house = nearest(
+        conn,
+        "SELECT street_id, house, 0 AS from_number, 0 AS to_number, 'all'::text AS parity, 0::float8 AS fraction, \
+                ST_Distance(location::geography, ST_SetSRID(ST_MakePoint($1, $2), 4326)::geography) AS distance \
+         FROM street_addresses \
+         WHERE ST_DWithin(location::geography, ST_SetSRID(ST_MakePoint($1, $2), 4326)::geography, $3) \
+         ORDER BY distance LIMIT 1",
+        point,
+    )?;
+    let range = nearest(
+        conn,
+        "SELECT street_id, NULL::text AS house, from_number, to_number, parity::text AS parity, \
+                ST_LineLocatePoint(geometry, ST_SetSRID(ST_MakePoint($1, $2), 4326)) AS fraction, \
+                ST_Distance(geometry::geography, ST_SetSRID(ST_MakePoint($1, $2), 4326)::geography) AS distance \
+         FROM house_ranges \
+         WHERE ST_DWithin(geometry::geography, ST_SetSRID(ST_MakePoint($1, $2), 4326)::geography, $3) \
+         ORDER BY distance LIMIT 1",
+        point,
+    )?;
+    let (row, house, precision) = match (house, range) {
+        (Some(house), Some(range)) if range.distance + HOUSE_PREFERENCE < house.distance => {
+            let number = interpolate_number(&range);
+            (range, Some(number.to_string()), PRECISION_INTERPOLATED)
+        },
+        (Some(house), _) => {
+            let number = house.house.clone();
+            (house, number, PRECISION_HOUSE)
+        },
+        (None, Some(range)) => {
+            let number = interpolate_number(&range);
+            (range, Some(number.to_string()), PRECISION_INTERPOLATED)
+        },
+        (None, None) => {
+            let object = nearest(
+                conn,
+                "SELECT street_id, NULL::text AS house, 0 AS from_number, 0 AS to_number, 'all'::text AS parity, \
+                        0::float8 AS fraction, distance FROM ( \
+                     SELECT street_id, ST_Distance(location::geography, ST_SetSRID(ST_MakePoint($1, $2), 4326)::geography) AS distance \
+                     FROM organizations WHERE location IS NOT NULL \
+                       AND ST_DWithin(location::geography, ST_SetSRID(ST_MakePoint($1, $2), 4326)::geography, $3) \
+                     UNION ALL \
+                     SELECT street_id, ST_Distance(location::geography, ST_SetSRID(ST_MakePoint($1, $2), 4326)::geography) \
+                     FROM user_points \
+                     WHERE ST_DWithin(location::geography, ST_SetSRID(ST_MakePoint($1, $2), 4326)::geography, $3) \
+                     UNION ALL \
+                     SELECT id, ST_Distance(geometry::geography, ST_SetSRID(ST_MakePoint($1, $2), 4326)::geography) \
+                     FROM streets WHERE geometry IS NOT NULL \
+                       AND ST_DWithin(geometry::geography, ST_SetSRID(ST_MakePoint($1, $2), 4326)::geography, $3) \
+                 ) objects ORDER BY distance LIMIT 1",
+                point,
+            )?;
+            match object {
+                Some(object) => (object, None, PRECISION_STREET),
+                None => return Ok(None),
+            }
+        },
+    };
+    let place = load_places(conn, None, Some(row.street_id))?
+        .into_iter()
+        .next()
+        .ok_or(Error::NotFound)?;
+    Ok(Some(ReverseGeocodeResult {
+        address:   place.address(house.as_deref()),
+        place:     place,
+        house:     house,
+        precision: precision,
+        distance:  (row.distance * 10.0).round() / 10.0,
+    }))
+}
+
+//-------------------------------------------------------------------------------------
+// Адрес при создании объектов
+
+// Где находится новый объект: текстовый адрес, точка на карте или оба
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AddressForm {
+    pub city_id: Option<i32>,
+    pub address: Option<String>,
+    #[serde(default, with = "crate::models::geo::serde_point_opt")]
+    pub location: Option<GeoPoint>,
+}
+
+// Найденная улица и координаты объекта. Если точка указана на карте,
+// она точнее найденной по адресу и остается как есть.
+#[derive(Debug, Clone)]
+pub struct Placement {
+    pub street_id: i32,
+    pub location: Option<GeoPoint>,
+    pub address: String,
+}
+
+impl AddressForm {
+    pub fn locate(&self, conn: &PgConnection) -> Result<Placement, Error> {
+        let address = self.address.as_deref().map(str::trim).filter(|a| !a.is_empty());
+        match (address, self.location) {
+            (Some(address), location) => {
+                let found = geocode(conn, address, self.city_id, 1)?
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| Error::Validation(format!("address not found: {}", address)))?;
+                Ok(Placement {
+                    street_id: found.place.street_id,
+                    location:  location.or(found.location),
+                    address:   found.address,
+                })
+            },
+            (None, Some(location)) => {
+                let found = reverse_geocode(conn, &location)?
+                    .ok_or_else(|| Error::Validation("no street found near the point".to_string()))?;
+                Ok(Placement {
+                    street_id: found.place.street_id,
+                    location:  Some(location),
+                    address:   found.address,
+                })
+            },
+            (None, None) => Err(Error::Validation("address or location is required".to_string())),
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------
+// Дома и диапазоны номеров: загрузка и выгрузка
+
+#[derive(Debug, Clone, Queryable)]
+pub struct StreetAddress {
+    pub id: i32,
+    pub street_id: i32,
+    pub house: String,
+    pub number: i32,
+    pub location: GeoPoint,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "street_addresses"]
+struct NewStreetAddress<'a> {
+    street_id: i32,
+    house: &'a str,
+    number: i32,
+    location: &'a GeoPoint,
+}
+
+// Дом на улице: новый создается, у известного обновляется точка
+pub fn save_street_address(conn: &PgConnection, street_id: i32, house: &str, location: &GeoPoint) -> Result<(&'static str, i32), Error> {
+    let (house, number) = normalize_house(&tokenize(house))
+        .ok_or_else(|| Error::Validation(format!("invalid house number: {}", house)))?;
+    let found = street_addresses::table
+        .filter(street_addresses::street_id.eq(street_id))
+        .filter(street_addresses::house.eq(&house))
+        .select(street_addresses::id)
+        .first::<i32>(conn)
+        .optional()?;
+    match found {
+        Some(id) => {
+            diesel::update(street_addresses::table.find(id))
+                .set(street_addresses::location.eq(location))
+                .execute(conn)?;
+            Ok(("updated", id))
+        },
+        None => {
+            let id = diesel::insert_into(street_addresses::table)
+                .values(&NewStreetAddress {
+                    street_id: street_id,
+                    house:     &house,
+                    number:    number,
+                    location:  location,
+                })
+                .returning(street_addresses::id)
+                .get_result::<i32>(conn)?;
+            Ok(("created", id))
+        },
+    }
+}
+
+pub fn get_street_addresses(conn: &PgConnection, street_ids: &[i32]) -> Result<Vec<StreetAddress>, Error> {
+    street_addresses::table
+        .filter(street_addresses::street_id.eq_any(street_ids))
+        .order((street_addresses::street_id, street_addresses::number, street_addresses::house))
+        .load(conn)
+        .map_err(Error::from)
+}
+
+// Диапазон номеров с геометрией в виде GeoJSON
+#[derive(Debug, Clone, QueryableByName)]
+pub struct HouseRange {
+    #[sql_type = "Integer"]
+    pub id: i32,
+    #[sql_type = "Integer"]
+    pub street_id: i32,
+    #[sql_type = "Integer"]
+    pub from_number: i32,
+    #[sql_type = "Integer"]
+    pub to_number: i32,
+    #[sql_type = "Text"]
+    pub parity: String,
+    #[sql_type = "Text"]
+    pub geometry: String,
+}
+
+#[derive(Debug, QueryableByName)]
+struct IdRow {
+    #[sql_type = "Integer"]
+    id: i32,
+}
+
+// Диапазон номеров на улице. Диапазон с теми же номерами и четностью
+// обновляется. geometry - LineString в GeoJSON.
+pub fn save_house_range(
+    conn: &PgConnection,
+    street_id: i32,
+    from_number: i32,
+    to_number: i32,
+    parity: &str,
+    geometry: &str,
+) -> Result<(&'static str, i32), Error> {
+    if from_number < 1 || to_number < from_number {
+        return Err(Error::Validation(format!("invalid house range: {}-{}", from_number, to_number)));
+    }
+    if !["all", "odd", "even"].contains(&parity) {
+        return Err(Error::Validation(format!("unknown parity: {}", parity)));
+    }
+    let found = sql_query(
+        "UPDATE house_ranges SET geometry = ST_SetSRID(ST_GeomFromGeoJSON($5), 4326) \
+         WHERE street_id = $1 AND from_number = $2 AND to_number = $3 AND parity = $4 \
+         RETURNING id",
+    )
+    .bind::<Integer, _>(street_id)
+    .bind::<Integer, _>(from_number)
+    .bind::<Integer, _>(to_number)
+    .bind::<Text, _>(parity)
+    .bind::<Text, _>(geometry)
+    .get_result::<IdRow>(conn)
+    .optional()?;
+    if let Some(row) = found {
+        return Ok(("updated", row.id));
+    }
+    let row = sql_query(
+        "INSERT INTO house_ranges (street_id, from_number, to_number, parity, geometry) \
+         VALUES ($1, $2, $3, $4, ST_SetSRID(ST_GeomFromGeoJSON($5), 4326)) \
+         RETURNING id",
+    )
+    .bind::<Integer, _>(street_id)
+    .bind::<Integer, _>(from_number)
+    .bind::<Integer, _>(to_number)
+    .bind::<Text, _>(parity)
+    .bind::<Text, _>(geometry)
+    .get_result::<IdRow>(conn)?;
+    Ok(("created", row.id))
+}
+
+pub fn get_house_ranges(conn: &PgConnection, street_ids: &[i32]) -> Result<Vec<HouseRange>, Error> {
+    sql_query(
+        "SELECT id, street_id, from_number, to_number, parity::text AS parity, ST_AsGeoJSON(geometry) AS geometry \
+         FROM house_ranges WHERE street_id = ANY($1) \
+         ORDER BY street_id, from_number, id",
+    )
+    .bind::<Array<Integer>, _>(street_ids)
+    .load(conn)
+    .map_err(Error::from)
+}
+
+//-------------------------------------------------------------------------------------
+// Создание объектов по адресу
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserPointForm {
+    pub name: String,
+    pub description: Option<String>,
+    pub category: Option<String>,
+    pub external_link: Option<String>,
+    #[serde(flatten)]
+    pub place: AddressForm,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OrganizationForm {
+    pub name: String,
+    pub description: Option<String>,
+    pub contact_info: Option<String>,
+    pub website: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub category: Option<String>,
+    #[serde(flatten)]
+    pub place: AddressForm,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RequestForm {
+    pub service_id: i32,
+    pub description: Option<String>,
+    pub priority: Option<i16>,
+    pub due_date: Option<chrono::NaiveDate>,
+    pub contact_name: Option<String>,
+    pub contact_phone: Option<String>,
+    pub contact_email: Option<String>,
+    #[serde(flatten)]
+    pub place: AddressForm,
+}
+
+fn required_name(name: &str) -> Result<String, Error> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(Error::Validation("name is required".to_string()));
+    }
+    Ok(name.to_string())
+}
+
+impl UserPoint {
+    // Точке нужны координаты: если их нет ни в форме, ни у найденного дома,
+    // адрес надо уточнить или отметить точку на карте
+    pub fn create_at_address(conn: &PgConnection, user_id: i32, form: &UserPointForm) -> Result<UserPoint, Error> {
+        let name = required_name(&form.name)?;
+        let placement = form.place.locate(conn)?;
+        let location = placement
+            .location
+            .ok_or_else(|| Error::Validation("house location is unknown, mark the point on the map".to_string()))?;
+        UserPoint::create(
+            NewUserPoint {
+                street_id:     placement.street_id,
+                name:          name,
+                description:   form.description.clone(),
+                category:      form.category.clone(),
+                photo:         None,
+                created_at:    chrono::Local::now().naive_local(),
+                user_id:       user_id,
+                external_link: form.external_link.clone(),
+                location:      location,
+            },
+            conn,
+        )
+    }
+}
+
+impl Organization {
+    pub fn create_at_address(conn: &PgConnection, form: &OrganizationForm) -> Result<Organization, Error> {
+        let name = required_name(&form.name)?;
+        let placement = form.place.locate(conn)?;
+        Organization::create(
+            NewOrganization {
+                street_id:          placement.street_id,
+                name:               name,
+                description:        form.description.clone(),
+                contact_info:       form.contact_info.clone(),
+                opening_hours_note: None,
+                website:            form.website.clone(),
+                email:              form.email.clone(),
+                phone:              form.phone.clone(),
+                category:           form.category.clone(),
+                location:           placement.location,
+                opening_hours:      None,
+            },
+            conn,
+        )
+    }
+}
+
+impl Request {
+    // Заявка хранит найденную улицу и адрес в нормальном виде
+    pub fn create_at_address(conn: &PgConnection, user_id: i32, form: &RequestForm) -> Result<Request, Error> {
+        let placement = form.place.locate(conn)?;
+        Request::create_request(
+            conn,
+            &NewRequest {
+                user_id:       user_id,
+                service_id:    form.service_id,
+                request_date:  chrono::Local::now().naive_local(),
+                description:   form.description.clone(),
+                status:        "new".to_string(),
+                assigned_to:   None,
+                priority:      form.priority.unwrap_or(0),
+                due_date:      form.due_date,
+                location:      placement.location,
+                contact_name:  form.contact_name.clone(),
+                contact_phone: form.contact_phone.clone(),
+                contact_email: form.contact_email.clone(),
+                comments:      None,
+                street_id:     Some(placement.street_id),
+                address:       Some(placement.address),
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(from_number: i32, to_number: i32, parity: &str, fraction: f64) -> NearestRow {
+        NearestRow {
+            street_id:   1,
+            house:       None,
+            from_number: from_number,
+            to_number:   to_number,
+            parity:      parity.to_string(),
+            fraction:    fraction,
+            distance:    0.0,
+        }
+    }
+
+    #[test]
+    fn parse_address_with_markers() {
+        let parsed = parse_address("г. Москва, ул. Ленина, д. 12к1").unwrap();
+        assert_eq!(parsed.city.as_deref(), Some("москва"));
+        assert_eq!(parsed.street.name, "ленина");
+        assert_eq!(parsed.street.kind, Some("улица"));
+        assert_eq!(parsed.house, Some(("12к1".to_string(), 12)));
+    }
+
+    #[test]
+    fn parse_address_house_after_street() {
+        let parsed = parse_address("Ленина 12 корп 1").unwrap();
+        assert_eq!(parsed.city, None);
+        assert_eq!(parsed.street.name, "ленина");
+        assert_eq!(parsed.street.kind, None);
+        assert_eq!(parsed.house, Some(("12к1".to_string(), 12)));
+    }
+
+    #[test]
+    fn parse_address_city_after_street() {
+        let parsed = parse_address("пр-т Мира 5а, Москва").unwrap();
+        assert_eq!(parsed.city.as_deref(), Some("москва"));
+        assert_eq!(parsed.street.name, "мира");
+        assert_eq!(parsed.street.kind, Some("проспект"));
+        assert_eq!(parsed.house, Some(("5а".to_string(), 5)));
+    }
+
+    #[test]
+    fn parse_address_without_street() {
+        assert!(matches!(parse_address(", ,"), Err(Error::Validation(_))));
+        assert!(matches!(parse_address("ул."), Err(Error::Validation(_))));
+    }
+
+    #[test]
+    fn similarity_bounds() {
+        assert_eq!(similarity("ленина", "ленина"), 1.0);
+        assert_eq!(similarity("ленина", "мира"), similarity("мира", "ленина"));
+        assert_eq!(similarity("", "ленина"), 0.0);
+        assert_eq!(similarity("abc", "xyz"), 0.0);
+        let typo = similarity("ленина", "ленена");
+        assert!(typo > 0.0 && typo < 1.0);
+    }
+
+    #[test]
+    fn interpolate_number_keeps_parity() {
+        assert_eq!(interpolate_number(&range(1, 11, "all", 0.5)), 6);
+        assert_eq!(interpolate_number(&range(1, 11, "odd", 0.5)), 7);
+        assert_eq!(interpolate_number(&range(2, 20, "even", 1.0)), 20);
+        assert_eq!(interpolate_number(&range(1, 9, "odd", 1.0)), 9);
+    }
+
+    #[test]
+    fn interpolate_number_does_not_overflow() {
+        assert_eq!(interpolate_number(&range(1, i32::MAX, "all", 1.0)), i32::MAX);
+        assert_eq!(interpolate_number(&range(1, 10, "all", 2.0)), 10);
+    }
+}
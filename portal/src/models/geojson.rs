@@ -0,0 +1,1040 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::str::FromStr;
+
+use crate::errors::Error;
+use crate::models::geo::{geo_point, GeoPoint};
+use crate::models::{
+    CityModel as City,
+    District,
+    NewCity,
+    NewDistrict,
+    NewOrganization,
+    NewStreet,
+    NewUserPoint,
+    NewWifiChargingPoint,
+    OpeningHours,
+    Organization,
+    Street,
+    UserPoint,
+    WifiChargingForm,
+    WifiChargingPoint,
+};
+use crate::models::geocoder::{get_house_ranges, get_street_addresses, save_house_range, save_street_address};
+use crate::models::geometry::{district_boundaries, street_geometries};
+use crate::models::wifi_charging::{get_connectors, set_connectors, ConnectorForm};
+use crate::schema::{cities, districts, organizations, streets, user_points, wifi_charging};
+
+
+// Выгрузка и загрузка городских данных в GeoJSON (RFC 7946).
+// Каждый объект - Feature, вид объекта лежит в properties.kind,
+// родитель задается названиями: properties.city, .district, .street.
+// У районов геометрия - граница (Polygon / MultiPolygon), у улиц - линия
+// (LineString / MultiLineString); площадь и длина по ней пересчитываются.
+// У городов геометрии нет, geometry = null.
+// Дома улицы - точки (name - номер дома), диапазоны номеров - линии
+// (name - подпись, номера в from_number, to_number и parity).
+
+const KIND_CITY: &str = "city";
+const KIND_DISTRICT: &str = "district";
+const KIND_STREET: &str = "street";
+const KIND_ADDRESS: &str = "address";
+const KIND_HOUSE_RANGE: &str = "house_range";
+const KIND_ORGANIZATION: &str = "organization";
+const KIND_USER_POINT: &str = "user_point";
+const KIND_WIFI_CHARGING: &str = "wifi_charging";
+
+// Порядок загрузки: сначала родители, потом объекты на улицах
+const IMPORT_ORDER: &[&str] = &[
+    KIND_CITY,
+    KIND_DISTRICT,
+    KIND_STREET,
+    KIND_ADDRESS,
+    KIND_HOUSE_RANGE,
+    KIND_ORGANIZATION,
+    KIND_USER_POINT,
+    KIND_WIFI_CHARGING,
+];
+
+//-------------------------------------------------------------------------------------
+// Выгрузка
+
+fn point_geometry(point: Option<&GeoPoint>) -> Value {
+    match point {
+        Some(point) => json!({ "type": "Point", "coordinates": [point.x, point.y] }),
+        None => Value::Null,
+    }
+}
+
+pub(crate) fn feature(kind: &str, id: i32, geometry: Value, mut properties: Map<String, Value>) -> Value {
+    properties.insert("kind".to_string(), json!(kind));
+    json!({
+        "type": "Feature",
+        "id": format!("{}/{}", kind, id),
+        "geometry": geometry,
+        "properties": properties,
+    })
+}
+
+// Свойства без пустых значений
+fn properties(pairs: Vec<(&str, Value)>) -> Map<String, Value> {
+    pairs
+        .into_iter()
+        .filter(|(_, value)| !value.is_null())
+        .map(|(key, value)| (key.to_string(), value))
+        .collect()
+}
+
+fn decimal_value(value: &Option<BigDecimal>) -> Value {
+    value.as_ref().map(|v| json!(v.to_string())).unwrap_or(Value::Null)
+}
+
+fn city_feature(city: &City) -> Value {
+    feature(KIND_CITY, city.id, Value::Null, properties(vec![
+        ("name",          json!(city.name)),
+        ("population",    json!(city.population)),
+        ("area_sq_km",    decimal_value(&city.area_sq_km)),
+        ("mayor",         json!(city.mayor)),
+        ("founding_date", json!(city.founding_date)),
+    ]))
+}
+
+// Выгрузка районов и всего, что на их улицах. Названия родителей
+// пишутся в свойства, чтобы файл можно было загрузить обратно.
+fn districts_features(conn: &PgConnection, city: &City, districts: Vec<District>) -> Result<Vec<Value>, Error> {
+    let district_ids: Vec<i32> = districts.iter().map(|d| d.id).collect();
+    let boundaries = district_boundaries(conn, &district_ids)?;
+    let streets = streets::table
+        .filter(streets::district_id.eq_any(&district_ids))
+        .order(streets::id.asc())
+        .load::<Street>(conn)?;
+    let street_ids: Vec<i32> = streets.iter().map(|s| s.id).collect();
+    let street_lines = street_geometries(conn, &street_ids)?;
+    let addresses = get_street_addresses(conn, &street_ids)?;
+    let house_ranges = get_house_ranges(conn, &street_ids)?;
+    let organizations = organizations::table
+        .filter(organizations::street_id.eq_any(&street_ids))
+        .order(organizations::id.asc())
+        .load::<Organization>(conn)?;
+    let user_points = user_points::table
+        .filter(user_points::street_id.eq_any(&street_ids))
+        .order(user_points::id.asc())
+        .load::<UserPoint>(conn)?;
+    let wifi_points = wifi_charging::table
+        .filter(wifi_charging::street_id.eq_any(&street_ids))
+        .order(wifi_charging::id.asc())
+        .load::<WifiChargingPoint>(conn)?;
+    let wifi_ids: Vec<i32> = wifi_points.iter().map(|p| p.id).collect();
+    let connectors = get_connectors(conn, &wifi_ids)?;
+
+    let district_name = |id: i32| districts.iter().find(|d| d.id == id).map(|d| d.name.clone());
+    let street = |id: i32| streets.iter().find(|s| s.id == id);
+    // свойства родителей объекта на улице
+    let parents = |street_id: i32| -> Vec<(&'static str, Value)> {
+        let street = street(street_id);
+        vec![
+            ("city",     json!(city.name)),
+            ("district", json!(street.and_then(|s| district_name(s.district_id)))),
+            ("street",   json!(street.map(|s| s.name.clone()))),
+        ]
+    };
+
+    let mut features = Vec::new();
+    for district in &districts {
+        let geometry = boundaries.iter().find(|(id, _)| *id == district.id).map(|(_, g)| g.clone());
+        features.push(feature(KIND_DISTRICT, district.id, geometry.unwrap_or(Value::Null), properties(vec![
+            ("name",       json!(district.name)),
+            ("city",       json!(city.name)),
+            ("population", json!(district.population)),
+            ("area_sq_km", decimal_value(&district.area_sq_km)),
+        ])));
+    }
+    for street in &streets {
+        let geometry = street_lines.iter().find(|(id, _)| *id == street.id).map(|(_, g)| g.clone());
+        features.push(feature(KIND_STREET, street.id, geometry.unwrap_or(Value::Null), properties(vec![
+            ("name",         json!(street.name)),
+            ("city",         json!(city.name)),
+            ("district",     json!(district_name(street.district_id))),
+            ("length_km",    decimal_value(&street.length_km)),
+            ("surface_type", json!(street.surface_type)),
+            ("speed_limit",  json!(street.speed_limit)),
+        ])));
+    }
+    for address in &addresses {
+        let mut props = parents(address.street_id);
+        props.push(("name", json!(address.house)));
+        features.push(feature(KIND_ADDRESS, address.id, point_geometry(Some(&address.location)), properties(props)));
+    }
+    for range in &house_ranges {
+        let mut props = parents(range.street_id);
+        props.extend(vec![
+            ("name",        json!(format!("{}-{}", range.from_number, range.to_number))),
+            ("from_number", json!(range.from_number)),
+            ("to_number",   json!(range.to_number)),
+            ("parity",      json!(range.parity)),
+        ]);
+        let geometry = serde_json::from_str(&range.geometry).unwrap_or(Value::Null);
+        features.push(feature(KIND_HOUSE_RANGE, range.id, geometry, properties(props)));
+    }
+    for organization in &organizations {
+        let mut props = parents(organization.street_id);
+        props.extend(vec![
+            ("name",            json!(organization.name)),
+            ("description",     json!(organization.description)),
+            ("contact_info",    json!(organization.contact_info)),
+            ("opening_hours",   json!(organization.opening_hours.as_ref().map(OpeningHours::to_osm))),
+            ("opening_hours_note", json!(organization.opening_hours_note)),
+            ("website",         json!(organization.website)),
+            ("email",           json!(organization.email)),
+            ("phone",           json!(organization.phone)),
+            ("category",        json!(organization.category)),
+        ]);
+        features.push(feature(KIND_ORGANIZATION, organization.id, point_geometry(organization.location.as_ref()), properties(props)));
+    }
+    for point in &user_points {
+        let mut props = parents(point.street_id);
+        props.extend(vec![
+            ("name",          json!(point.name)),
+            ("description",   json!(point.description)),
+            ("category",      json!(point.category)),
+            ("external_link", json!(point.external_link)),
+        ]);
+        features.push(feature(KIND_USER_POINT, point.id, point_geometry(Some(&point.location)), properties(props)));
+    }
+    for point in &wifi_points {
+        let mut props = parents(point.street_id);
+        props.extend(vec![
+            ("name",        json!(point.name)),
+            ("description", json!(point.description)),
+            ("services",    json!(point.services)),
+            ("has_wifi",    json!(point.has_wifi)),
+            ("wifi_ssid",   json!(point.wifi_ssid)),
+            ("wifi_password_required", json!(point.wifi_password_required)),
+            ("has_charging", json!(point.has_charging)),
+            ("connectors",  json!(connectors
+                .iter()
+                .filter(|c| c.point_id == point.id)
+                .map(|c| json!({
+                    "connector_type": c.connector_type,
+                    "power_kw":       c.power_kw.to_string(),
+                    "quantity":       c.quantity,
+                }))
+                .collect::<Vec<_>>())),
+            ("is_free",     json!(point.is_free)),
+            ("price",       decimal_value(&point.price)),
+            ("price_unit",  json!(point.price_unit)),
+            ("currency",    json!(point.currency)),
+            ("access_hours", json!(point.access_hours.as_ref().map(OpeningHours::to_osm))),
+        ]);
+        features.push(feature(KIND_WIFI_CHARGING, point.id, point_geometry(point.location.as_ref()), properties(props)));
+    }
+    Ok(features)
+}
+
+fn feature_collection(features: Vec<Value>) -> Value {
+    json!({
+        "type": "FeatureCollection",
+        "features": features,
+    })
+}
+
+// Весь город: сам город, районы, улицы и объекты на улицах
+pub fn export_city_geojson(conn: &PgConnection, city_id: i32) -> Result<Value, Error> {
+    let city = cities::table.find(city_id).first::<City>(conn)?;
+    let districts = districts::table
+        .filter(districts::city_id.eq(city_id))
+        .order(districts::id.asc())
+        .load::<District>(conn)?;
+    let mut features = vec![city_feature(&city)];
+    features.extend(districts_features(conn, &city, districts)?);
+    Ok(feature_collection(features))
+}
+
+// Один район. Город тоже попадает в файл, чтобы при загрузке было к чему привязать район.
+pub fn export_district_geojson(conn: &PgConnection, district_id: i32) -> Result<Value, Error> {
+    let district = districts::table.find(district_id).first::<District>(conn)?;
+    let city = cities::table.find(district.city_id).first::<City>(conn)?;
+    let mut features = vec![city_feature(&city)];
+    features.extend(districts_features(conn, &city, vec![district])?);
+    Ok(feature_collection(features))
+}
+
+//-------------------------------------------------------------------------------------
+// Загрузка
+
+// Результат загрузки одного Feature
+#[derive(Debug, Serialize)]
+pub struct GeoJsonImportItem {
+    pub index:   usize,            // Номер Feature в файле
+    pub kind:    String,
+    pub name:    String,
+    pub action:  &'static str,     // created, updated, conflict или error
+    pub id:      Option<i32>,      // Идентификатор созданной или найденной строки
+    pub message: Option<String>,   // Причина конфликта или ошибки
+}
+
+// Отчет о загрузке. При dry_run изменения откатываются, но отчет тот же.
+#[derive(Debug, Serialize)]
+pub struct GeoJsonImportReport {
+    pub dry_run:   bool,
+    pub created:   usize,
+    pub updated:   usize,
+    pub conflicts: usize,
+    pub errors:    usize,
+    pub items:     Vec<GeoJsonImportItem>,
+}
+
+// Ошибка загрузки одного Feature: конфликт с данными в базе или неверные данные
+#[derive(Debug)]
+enum ItemError {
+    Conflict(String),
+    Invalid(String),
+}
+
+impl From<Error> for ItemError {
+    fn from(error: Error) -> ItemError {
+        match error {
+            Error::Validation(message) => ItemError::Invalid(message),
+            Error::Conflict(message) => ItemError::Conflict(message),
+            other => ItemError::Invalid(other.to_string()),
+        }
+    }
+}
+
+impl From<diesel::result::Error> for ItemError {
+    fn from(error: diesel::result::Error) -> ItemError {
+        ItemError::from(Error::from(error))
+    }
+}
+
+// Свойства одного Feature с разбором значений
+struct Props<'a>(&'a Map<String, Value>);
+
+impl<'a> Props<'a> {
+    fn string(&self, key: &str) -> Option<String> {
+        match self.0.get(key) {
+            Some(Value::String(value)) if !value.trim().is_empty() => Some(value.trim().to_string()),
+            Some(Value::Number(value)) => Some(value.to_string()),
+            _ => None,
+        }
+    }
+
+    fn required(&self, key: &str) -> Result<String, ItemError> {
+        self.string(key)
+            .ok_or_else(|| ItemError::Invalid(format!("property \"{}\" is required", key)))
+    }
+
+    fn parse<T: FromStr>(&self, key: &str) -> Result<Option<T>, ItemError> {
+        match self.string(key) {
+            Some(value) => value
+                .parse()
+                .map(Some)
+                .map_err(|_| ItemError::Invalid(format!("invalid {}: {}", key, value))),
+            None => Ok(None),
+        }
+    }
+
+    fn decimal(&self, key: &str) -> Result<Option<BigDecimal>, ItemError> {
+        self.parse::<BigDecimal>(key)
+    }
+
+    fn date(&self, key: &str) -> Result<Option<NaiveDate>, ItemError> {
+        self.parse::<NaiveDate>(key)
+    }
+
+    fn flag(&self, key: &str) -> Result<Option<bool>, ItemError> {
+        match self.0.get(key) {
+            Some(Value::Bool(value)) => Ok(Some(*value)),
+            _ => self.parse::<bool>(key),
+        }
+    }
+
+    // Часы работы в формате OSM opening_hours
+    fn opening_hours(&self, key: &str) -> Result<Option<OpeningHours>, ItemError> {
+        match self.string(key) {
+            Some(value) => Ok(Some(OpeningHours::from_osm(&value)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+// Точка из geometry. Поддерживается только Point, пустая геометрия - None.
+fn parse_point(geometry: Option<&Value>) -> Result<Option<GeoPoint>, ItemError> {
+    let geometry = match geometry {
+        None | Some(Value::Null) => return Ok(None),
+        Some(geometry) => geometry,
+    };
+    if geometry.get("type").and_then(Value::as_str) != Some("Point") {
+        return Err(ItemError::Invalid("only Point geometry is supported".to_string()));
+    }
+    let coordinates = geometry
+        .get("coordinates")
+        .and_then(Value::as_array)
+        .filter(|c| c.len() >= 2)
+        .ok_or_else(|| ItemError::Invalid("invalid Point coordinates".to_string()))?;
+    match (coordinates[0].as_f64(), coordinates[1].as_f64()) {
+        (Some(lon), Some(lat)) => Ok(Some(geo_point(lat, lon)?)),
+        _ => Err(ItemError::Invalid("invalid Point coordinates".to_string())),
+    }
+}
+
+// Единственная строка из найденных. Несколько - конфликт: непонятно, какую обновлять.
+fn single(ids: Vec<i32>, what: &str, name: &str) -> Result<Option<i32>, ItemError> {
+    match ids.len() {
+        0 => Ok(None),
+        1 => Ok(Some(ids[0])),
+        n => Err(ItemError::Conflict(format!("{} {} \"{}\" found", n, what, name))),
+    }
+}
+
+fn find_city(conn: &PgConnection, name: &str) -> Result<Option<i32>, ItemError> {
+    let ids = cities::table
+        .filter(cities::name.eq(name))
+        .select(cities::id)
+        .load(conn)?;
+    single(ids, "cities", name)
+}
+
+fn find_district(conn: &PgConnection, name: &str, city_id: i32) -> Result<Option<i32>, ItemError> {
+    let ids = districts::table
+        .filter(districts::name.eq(name))
+        .filter(districts::city_id.eq(city_id))
+        .select(districts::id)
+        .load(conn)?;
+    single(ids, "districts", name)
+}
+
+fn find_street(conn: &PgConnection, name: &str, district_id: i32) -> Result<Option<i32>, ItemError> {
+    let ids = streets::table
+        .filter(streets::name.eq(name))
+        .filter(streets::district_id.eq(district_id))
+        .select(streets::id)
+        .load(conn)?;
+    single(ids, "streets", name)
+}
+
+// Родитель по названию. Для загрузки объекта он уже должен быть в базе
+// (или выше в этом же файле).
+fn require_parent(found: Option<i32>, what: &str, name: &str) -> Result<i32, ItemError> {
+    found.ok_or_else(|| ItemError::Conflict(format!("{} \"{}\" not found", what, name)))
+}
+
+fn resolve_city(conn: &PgConnection, props: &Props) -> Result<i32, ItemError> {
+    let name = props.required("city")?;
+    require_parent(find_city(conn, &name)?, "city", &name)
+}
+
+// Город и район из свойств. Оба свойства проверяются до запросов к базе.
+fn district_names(props: &Props) -> Result<(String, String), ItemError> {
+    Ok((props.required("city")?, props.required("district")?))
+}
+
+fn resolve_district(conn: &PgConnection, props: &Props) -> Result<i32, ItemError> {
+    let (city, name) = district_names(props)?;
+    let city_id = require_parent(find_city(conn, &city)?, "city", &city)?;
+    require_parent(find_district(conn, &name, city_id)?, "district", &name)
+}
+
+fn resolve_street(conn: &PgConnection, props: &Props) -> Result<i32, ItemError> {
+    let name = props.required("street")?;
+    let district_id = resolve_district(conn, props)?;
+    require_parent(find_street(conn, &name, district_id)?, "street", &name)
+}
+
+// Изменения при обновлении найденной строки. Свойства, которых нет
+// в Feature, не трогаются (None в changeset пропускается).
+#[derive(AsChangeset)]
+#[table_name = "cities"]
+struct CityChanges {
+    name: String,
+    population: Option<i32>,
+    area_sq_km: Option<BigDecimal>,
+    mayor: Option<String>,
+    founding_date: Option<NaiveDate>,
+}
+
+#[derive(AsChangeset)]
+#[table_name = "districts"]
+struct DistrictChanges {
+    name: String,
+    population: Option<i32>,
+    area_sq_km: Option<BigDecimal>,
+}
+
+#[derive(AsChangeset)]
+#[table_name = "streets"]
+struct StreetChanges {
+    name: String,
+    length_km: Option<BigDecimal>,
+    surface_type: Option<String>,
+    speed_limit: Option<i16>,
+}
+
+#[derive(AsChangeset)]
+#[table_name = "organizations"]
+struct OrganizationChanges {
+    name: String,
+    description: Option<String>,
+    contact_info: Option<String>,
+    opening_hours: Option<OpeningHours>,
+    opening_hours_note: Option<String>,
+    website: Option<String>,
+    email: Option<String>,
+    phone: Option<String>,
+    category: Option<String>,
+    location: Option<GeoPoint>,
+}
+
+#[derive(AsChangeset)]
+#[table_name = "user_points"]
+struct UserPointChanges {
+    name: String,
+    description: Option<String>,
+    category: Option<String>,
+    external_link: Option<String>,
+    location: Option<GeoPoint>,
+}
+
+#[derive(AsChangeset)]
+#[table_name = "wifi_charging"]
+struct WifiChargingChanges {
+    name: String,
+    description: Option<String>,
+    services: Option<String>,
+    location: Option<GeoPoint>,
+    has_wifi: bool,
+    wifi_ssid: Option<String>,
+    wifi_password_required: bool,
+    has_charging: bool,
+    is_free: bool,
+    price: Option<BigDecimal>,
+    price_unit: Option<String>,
+    currency: String,
+    access_hours: Option<OpeningHours>,
+}
+
+// Загрузка одного Feature. Возвращает действие и идентификатор строки.
+fn import_feature(
+    conn: &PgConnection,
+    kind: &str,
+    name: &str,
+    props: &Props,
+    geometry: Option<&Value>,
+    user_id: i32,
+) -> Result<(&'static str, i32), ItemError> {
+    let name = name.to_string();
+    match kind {
+        KIND_CITY => {
+            let changes = CityChanges {
+                name:          name.clone(),
+                population:    props.parse("population")?,
+                area_sq_km:    props.decimal("area_sq_km")?,
+                mayor:         props.string("mayor"),
+                founding_date: props.date("founding_date")?,
+            };
+            match find_city(conn, &name)? {
+                Some(id) => {
+                    diesel::update(cities::table.find(id)).set(&changes).execute(conn)?;
+                    Ok(("updated", id))
+                },
+                None => {
+                    let city = diesel::insert_into(cities::table)
+                        .values(&NewCity {
+                            name:          changes.name,
+                            population:    changes.population,
+                            area_sq_km:    changes.area_sq_km,
+                            mayor:         changes.mayor,
+                            founding_date: changes.founding_date,
+                        })
+                        .get_result::<City>(conn)?;
+                    Ok(("created", city.id))
+                },
+            }
+        },
+        KIND_DISTRICT => {
+            let city_id = resolve_city(conn, props)?;
+            let changes = DistrictChanges {
+                name:       name.clone(),
+                population: props.parse("population")?,
+                area_sq_km: props.decimal("area_sq_km")?,
+            };
+            let (action, id) = match find_district(conn, &name, city_id)? {
+                Some(id) => {
+                    diesel::update(districts::table.find(id)).set(&changes).execute(conn)?;
+                    ("updated", id)
+                },
+                None => {
+                    let district = diesel::insert_into(districts::table)
+                        .values(&NewDistrict {
+                            city_id:    city_id,
+                            name:       changes.name,
+                            population: changes.population.unwrap_or(0),
+                            area_sq_km: changes.area_sq_km,
+                        })
+                        .get_result::<District>(conn)?;
+                    ("created", district.id)
+                },
+            };
+            if let Some(geometry) = geometry.filter(|g| !g.is_null()) {
+                District::set_boundary(conn, id, geometry)?;
+            }
+            Ok((action, id))
+        },
+        KIND_STREET => {
+            let district_id = resolve_district(conn, props)?;
+            let changes = StreetChanges {
+                name:         name.clone(),
+                length_km:    props.decimal("length_km")?,
+                surface_type: props.string("surface_type"),
+                speed_limit:  props.parse("speed_limit")?,
+            };
+            let (action, id) = match find_street(conn, &name, district_id)? {
+                Some(id) => {
+                    diesel::update(streets::table.find(id)).set(&changes).execute(conn)?;
+                    ("updated", id)
+                },
+                None => {
+                    let street = diesel::insert_into(streets::table)
+                        .values(&NewStreet {
+                            district_id:  district_id,
+                            name:         changes.name,
+                            length_km:    changes.length_km,
+                            surface_type: changes.surface_type,
+                            speed_limit:  changes.speed_limit,
+                        })
+                        .get_result::<Street>(conn)?;
+                    ("created", street.id)
+                },
+            };
+            if let Some(geometry) = geometry.filter(|g| !g.is_null()) {
+                Street::set_geometry(conn, id, geometry)?;
+            }
+            Ok((action, id))
+        },
+        KIND_ADDRESS => {
+            let street_id = resolve_street(conn, props)?;
+            let location = parse_point(geometry)?
+                .ok_or_else(|| ItemError::Invalid("Point geometry is required".to_string()))?;
+            Ok(save_street_address(conn, street_id, &name, &location)?)
+        },
+        KIND_HOUSE_RANGE => {
+            let street_id = resolve_street(conn, props)?;
+            let geometry = match geometry {
+                Some(geometry) if geometry.get("type").and_then(Value::as_str) == Some("LineString") => geometry,
+                _ => return Err(ItemError::Invalid("LineString geometry is required".to_string())),
+            };
+            let from_number = props
+                .parse::<i32>("from_number")?
+                .ok_or_else(|| ItemError::Invalid("property \"from_number\" is required".to_string()))?;
+            let to_number = props
+                .parse::<i32>("to_number")?
+                .ok_or_else(|| ItemError::Invalid("property \"to_number\" is required".to_string()))?;
+            let parity = props.string("parity").unwrap_or_else(|| "all".to_string());
+            Ok(save_house_range(conn, street_id, from_number, to_number, &parity, &geometry.to_string())?)
+        },
+        KIND_ORGANIZATION => {
+            let street_id = resolve_street(conn, props)?;
+            let changes = OrganizationChanges {
+                name:            name.clone(),
+                description:     props.string("description"),
+                contact_info:    props.string("contact_info"),
+                opening_hours:   props.opening_hours("opening_hours")?,
+                opening_hours_note: props.string("opening_hours_note"),
+                website:         props.string("website"),
+                email:           props.string("email"),
+                phone:           props.string("phone"),
+                category:        props.string("category"),
+                location:        parse_point(geometry)?,
+            };
+            let ids = organizations::table
+                .filter(organizations::name.eq(&name))
+                .filter(organizations::street_id.eq(street_id))
+                .select(organizations::id)
+                .load(conn)?;
+            match single(ids, "organizations", &name)? {
+                Some(id) => {
+                    diesel::update(organizations::table.find(id)).set(&changes).execute(conn)?;
+                    Ok(("updated", id))
+                },
+                None => {
+                    let organization = diesel::insert_into(organizations::table)
+                        .values(&NewOrganization {
+                            street_id:       street_id,
+                            name:            changes.name,
+                            description:     changes.description,
+                            contact_info:    changes.contact_info,
+                            opening_hours:   changes.opening_hours,
+                            opening_hours_note: changes.opening_hours_note,
+                            website:         changes.website,
+                            email:           changes.email,
+                            phone:           changes.phone,
+                            category:        changes.category,
+                            location:        changes.location,
+                        })
+                        .get_result::<Organization>(conn)?;
+                    Ok(("created", organization.id))
+                },
+            }
+        },
+        KIND_USER_POINT => {
+            let street_id = resolve_street(conn, props)?;
+            let changes = UserPointChanges {
+                name:          name.clone(),
+                description:   props.string("description"),
+                category:      props.string("category"),
+                external_link: props.string("external_link"),
+                location:      parse_point(geometry)?,
+            };
+            let ids = user_points::table
+                .filter(user_points::name.eq(&name))
+                .filter(user_points::street_id.eq(street_id))
+                .select(user_points::id)
+                .load(conn)?;
+            match single(ids, "user points", &name)? {
+                Some(id) => {
+                    diesel::update(user_points::table.find(id)).set(&changes).execute(conn)?;
+                    Ok(("updated", id))
+                },
+                None => {
+                    let location = changes
+                        .location
+                        .ok_or_else(|| ItemError::Invalid("user point requires Point geometry".to_string()))?;
+                    // новые отметки записываются на пользователя, который загружает файл
+                    let point = diesel::insert_into(user_points::table)
+                        .values(&NewUserPoint {
+                            street_id:      street_id,
+                            name:           changes.name,
+                            description:    changes.description,
+                            category:       changes.category,
+                            photo:          None,
+                            created_at:     chrono::Local::now().naive_local(),
+                            user_id:        user_id,
+                            external_link:  changes.external_link,
+                            location:       location,
+                        })
+                        .get_result::<UserPoint>(conn)?;
+                    Ok(("created", point.id))
+                },
+            }
+        },
+        KIND_WIFI_CHARGING => {
+            let street_id = resolve_street(conn, props)?;
+            // разъемы заменяются, только если они есть в файле
+            let connectors = match props.0.get("connectors") {
+                None | Some(Value::Null) => None,
+                Some(value) => Some(serde_json::from_value::<Vec<ConnectorForm>>(value.clone())
+                    .map_err(|e| ItemError::Invalid(format!("invalid connectors: {}", e)))?),
+            };
+            let details = WifiChargingForm {
+                services:               props.string("services"),
+                has_wifi:               props.flag("has_wifi")?.unwrap_or(false),
+                wifi_ssid:              props.string("wifi_ssid"),
+                wifi_password_required: props.flag("wifi_password_required")?.unwrap_or(false),
+                has_charging:           props.flag("has_charging")?.unwrap_or(false),
+                connectors:             connectors.clone().unwrap_or_default(),
+                is_free:                props.flag("is_free")?.unwrap_or(true),
+                price:                  props.decimal("price")?,
+                price_unit:             props.string("price_unit"),
+                currency:               props.string("currency"),
+                access_hours:           props.opening_hours("access_hours")?,
+            };
+            details.validate()?;
+            let changes = WifiChargingChanges {
+                name:                   name.clone(),
+                description:            props.string("description"),
+                services:               details.services,
+                location:               parse_point(geometry)?,
+                has_wifi:               details.has_wifi,
+                wifi_ssid:              details.wifi_ssid,
+                wifi_password_required: details.wifi_password_required,
+                has_charging:           details.has_charging,
+                is_free:                details.is_free,
+                price:                  details.price,
+                price_unit:             details.price_unit,
+                currency:               details.currency.unwrap_or_else(|| "RUB".to_string()),
+                access_hours:           details.access_hours,
+            };
+            let ids = wifi_charging::table
+                .filter(wifi_charging::name.eq(&name))
+                .filter(wifi_charging::street_id.eq(street_id))
+                .select(wifi_charging::id)
+                .load(conn)?;
+            match single(ids, "wifi/charging points", &name)? {
+                Some(id) => {
+                    diesel::update(wifi_charging::table.find(id)).set(&changes).execute(conn)?;
+                    if let Some(ref connectors) = connectors {
+                        set_connectors(conn, id, connectors)?;
+                    }
+                    Ok(("updated", id))
+                },
+                None => {
+                    let point = diesel::insert_into(wifi_charging::table)
+                        .values(&NewWifiChargingPoint {
+                            street_id:              street_id,
+                            name:                   changes.name,
+                            description:            changes.description,
+                            services:               changes.services,
+                            location:               changes.location,
+                            rating:                 None,
+                            has_wifi:               changes.has_wifi,
+                            wifi_ssid:              changes.wifi_ssid,
+                            wifi_password_required: changes.wifi_password_required,
+                            has_charging:           changes.has_charging,
+                            is_free:                changes.is_free,
+                            price:                  changes.price,
+                            price_unit:             changes.price_unit,
+                            currency:               changes.currency,
+                            access_hours:           changes.access_hours,
+                        })
+                        .get_result::<WifiChargingPoint>(conn)?;
+                    if let Some(ref connectors) = connectors {
+                        set_connectors(conn, point.id, connectors)?;
+                    }
+                    Ok(("created", point.id))
+                },
+            }
+        },
+        other => Err(ItemError::Invalid(format!("unknown kind: {}", other))),
+    }
+}
+
+// Загрузка FeatureCollection. Найденные по названию и родителю строки
+// обновляются, остальные создаются. Конфликты и ошибки не прерывают
+// загрузку, а попадают в отчет. Каждый Feature выполняется в своей точке
+// сохранения, так что ошибка одного не откатывает остальные.
+// При dry_run все изменения откатываются в конце.
+pub fn import_geojson(
+    conn: &PgConnection,
+    collection: &Value,
+    user_id: i32,
+    dry_run: bool,
+) -> Result<GeoJsonImportReport, Error> {
+    if collection.get("type").and_then(Value::as_str) != Some("FeatureCollection") {
+        return Err(Error::Validation("FeatureCollection expected".to_string()));
+    }
+    let features = collection
+        .get("features")
+        .and_then(Value::as_array)
+        .ok_or_else(|| Error::Validation("features array expected".to_string()))?;
+
+    // родители загружаются раньше детей независимо от порядка в файле
+    let mut ordered: Vec<(usize, &Value)> = features.iter().enumerate().collect();
+    ordered.sort_by_key(|(index, feature)| {
+        let kind = feature.pointer("/properties/kind").and_then(Value::as_str).unwrap_or("");
+        let rank = IMPORT_ORDER.iter().position(|k| *k == kind).unwrap_or(IMPORT_ORDER.len());
+        (rank, *index)
+    });
+
+    let mut report = GeoJsonImportReport {
+        dry_run:   dry_run,
+        created:   0,
+        updated:   0,
+        conflicts: 0,
+        errors:    0,
+        items:     Vec::with_capacity(features.len()),
+    };
+    let empty = Map::new();
+    let result = conn.transaction::<(), Error, _>(|| {
+        // одна строка не должна обновляться дважды за загрузку
+        let mut seen: Vec<(&'static str, i32)> = Vec::new();
+        for (index, feature) in ordered {
+            let props = Props(feature.get("properties").and_then(Value::as_object).unwrap_or(&empty));
+            let kind = props.string("kind").unwrap_or_default();
+            let name = props.string("name").unwrap_or_default();
+            let kind_key = IMPORT_ORDER.iter().find(|k| **k == kind).copied().unwrap_or("");
+            let outcome = if name.is_empty() {
+                Err(ItemError::Invalid("property \"name\" is required".to_string()))
+            }
+            else {
+                // повтор откатывает свою точку сохранения, чтобы первое
+                // значение из файла не было перезаписано
+                conn.transaction(|| {
+                    let (action, id) = import_feature(conn, &kind, &name, &props, feature.get("geometry"), user_id)?;
+                    if seen.contains(&(kind_key, id)) {
+                        return Err(ItemError::Conflict("duplicate feature in file".to_string()));
+                    }
+                    Ok((action, id))
+                })
+            };
+            let mut item = GeoJsonImportItem {
+                index:   index,
+                kind:    kind,
+                name:    name,
+                action:  "error",
+                id:      None,
+                message: None,
+            };
+            match outcome {
+                Ok((action, id)) => {
+                    if action == "created" { report.created += 1 } else { report.updated += 1 }
+                    item.action = action;
+                    item.id = Some(id);
+                    seen.push((kind_key, id));
+                },
+                Err(ItemError::Conflict(message)) => {
+                    item.action = "conflict";
+                    item.message = Some(message);
+                    report.conflicts += 1;
+                },
+                Err(ItemError::Invalid(message)) => {
+                    item.message = Some(message);
+                    report.errors += 1;
+                },
+            }
+            report.items.push(item);
+        }
+        if dry_run {
+            return Err(Error::Database(diesel::result::Error::RollbackTransaction));
+        }
+        Ok(())
+    });
+    match result {
+        Ok(()) => (),
+        Err(Error::Database(diesel::result::Error::RollbackTransaction)) if dry_run => (),
+        Err(e) => return Err(e),
+    }
+    report.items.sort_by_key(|item| item.index);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn props(value: Value) -> Map<String, Value> {
+        value.as_object().cloned().unwrap()
+    }
+
+    fn invalid<T: std::fmt::Debug>(result: Result<T, ItemError>) -> String {
+        match result {
+            Err(ItemError::Invalid(message)) => message,
+            other => panic!("expected invalid, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn props_string_trims_and_skips_blank_values() {
+        let map = props(json!({
+            "name": "  Ленина  ",
+            "blank": "   ",
+            "population": 12000,
+            "flag": true,
+            "nested": {"a": 1},
+            "empty": null,
+        }));
+        let props = Props(&map);
+        assert_eq!(props.string("name").as_deref(), Some("Ленина"));
+        assert_eq!(props.string("population").as_deref(), Some("12000"));
+        assert_eq!(props.string("blank"), None);
+        assert_eq!(props.string("flag"), None);
+        assert_eq!(props.string("nested"), None);
+        assert_eq!(props.string("empty"), None);
+        assert_eq!(props.string("missing"), None);
+    }
+
+    #[test]
+    fn props_required_reports_missing_property() {
+        let map = props(json!({"city": "Москва", "district": " "}));
+        let props = Props(&map);
+        assert_eq!(props.required("city").unwrap(), "Москва");
+        assert_eq!(invalid(props.required("district")), "property \"district\" is required");
+        assert_eq!(invalid(props.required("street")), "property \"street\" is required");
+    }
+
+    #[test]
+    fn props_parse_typed_values() {
+        let map = props(json!({
+            "population": "12000",
+            "area_sq_km": 25.5,
+            "founding_date": "1147-04-04",
+            "has_wifi": true,
+            "is_free": "false",
+            "access_hours": "Mo-Fr 09:00-18:00",
+        }));
+        let props = Props(&map);
+        assert_eq!(props.parse::<i32>("population").unwrap(), Some(12000));
+        assert_eq!(props.parse::<i32>("missing").unwrap(), None);
+        assert_eq!(props.decimal("area_sq_km").unwrap(), Some(BigDecimal::from_str("25.5").unwrap()));
+        assert_eq!(props.date("founding_date").unwrap(), NaiveDate::from_ymd_opt(1147, 4, 4));
+        assert_eq!(props.flag("has_wifi").unwrap(), Some(true));
+        assert_eq!(props.flag("is_free").unwrap(), Some(false));
+        assert!(props.opening_hours("access_hours").unwrap().is_some());
+    }
+
+    #[test]
+    fn props_parse_rejects_malformed_values() {
+        let map = props(json!({
+            "population": "много",
+            "area_sq_km": "25,5",
+            "founding_date": "2026-13-01",
+            "has_wifi": "yes",
+            "access_hours": "круглосуточно",
+        }));
+        let props = Props(&map);
+        assert_eq!(invalid(props.parse::<i32>("population")), "invalid population: много");
+        invalid(props.decimal("area_sq_km"));
+        assert_eq!(invalid(props.date("founding_date")), "invalid founding_date: 2026-13-01");
+        assert_eq!(invalid(props.flag("has_wifi")), "invalid has_wifi: yes");
+        invalid(props.opening_hours("access_hours"));
+    }
+
+    #[test]
+    fn parse_point_lon_lat_order() {
+        let geometry = json!({"type": "Point", "coordinates": [37.61, 55.75]});
+        let point = parse_point(Some(&geometry)).unwrap().unwrap();
+        assert_eq!((point.y, point.x), (55.75, 37.61));
+        assert!(parse_point(None).unwrap().is_none());
+        assert!(parse_point(Some(&Value::Null)).unwrap().is_none());
+    }
+
+    #[test]
+    fn parse_point_rejects_other_geometry_types() {
+        for geometry in &[
+            json!({"type": "LineString", "coordinates": [[37.61, 55.75], [37.62, 55.76]]}),
+            json!({"type": "Polygon", "coordinates": [[[0, 0], [1, 0], [1, 1], [0, 0]]]}),
+            json!({"coordinates": [37.61, 55.75]}),
+            json!([37.61, 55.75]),
+            json!("Point"),
+        ] {
+            assert_eq!(invalid(parse_point(Some(geometry))), "only Point geometry is supported");
+        }
+    }
+
+    #[test]
+    fn parse_point_rejects_malformed_coordinates() {
+        for geometry in &[
+            json!({"type": "Point"}),
+            json!({"type": "Point", "coordinates": [37.61]}),
+            json!({"type": "Point", "coordinates": "37.61, 55.75"}),
+            json!({"type": "Point", "coordinates": ["37.61", "55.75"]}),
+        ] {
+            assert_eq!(invalid(parse_point(Some(geometry))), "invalid Point coordinates");
+        }
+        // широта вне диапазона
+        let geometry = json!({"type": "Point", "coordinates": [37.61, 95.0]});
+        invalid(parse_point(Some(&geometry)));
+    }
+
+    #[test]
+    fn single_conflicts_on_several_rows() {
+        assert_eq!(single(vec![], "cities", "Москва").unwrap(), None);
+        assert_eq!(single(vec![7], "cities", "Москва").unwrap(), Some(7));
+        match single(vec![7, 8], "cities", "Москва") {
+            Err(ItemError::Conflict(message)) => assert_eq!(message, "2 cities \"Москва\" found"),
+            other => panic!("expected conflict, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn resolve_district_requires_city_and_district() {
+        let map = props(json!({"kind": "street", "name": "Тверская"}));
+        assert_eq!(invalid(district_names(&Props(&map))), "property \"city\" is required");
+        let map = props(json!({"kind": "street", "name": "Тверская", "city": "Москва"}));
+        assert_eq!(invalid(district_names(&Props(&map))), "property \"district\" is required");
+        let map = props(json!({"city": "Москва", "district": "Тверской"}));
+        assert_eq!(district_names(&Props(&map)).unwrap(), ("Москва".to_string(), "Тверской".to_string()));
+    }
+}
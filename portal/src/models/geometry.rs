@@ -0,0 +1,319 @@
+use diesel::prelude::*;
+use diesel::sql_query;
+use diesel::sql_types::{Array, Bool, Double, Integer, Nullable, Text};
+use serde_json::{json, Value};
+use std::f64::consts::PI;
+
+use crate::errors::Error;
+use crate::models::geo::{BoundingBox, ALL_NEARBY_KINDS};
+use crate::models::geojson::feature;
+use crate::models::{find_in_bbox, District, Street};
+use crate::schema::{districts, streets};
+
+
+// Линии улиц и границы районов. Геометрия приходит и уходит в GeoJSON,
+// в базе хранится как MultiLineString / MultiPolygon в SRID 4326.
+// Длину улицы и площадь района пересчитывает база при сохранении,
+// она же проставляет район новым отметкам и заявкам по координатам.
+
+const STREET_GEOMETRY_TYPES: &[&str] = &["LineString", "MultiLineString"];
+const DISTRICT_GEOMETRY_TYPES: &[&str] = &["Polygon", "MultiPolygon"];
+
+#[derive(Debug, QueryableByName)]
+struct ValidityRow {
+    #[sql_type = "Bool"]
+    valid: bool,
+    #[sql_type = "Text"]
+    reason: String,
+}
+
+#[derive(Debug, QueryableByName)]
+struct GeometryRow {
+    #[sql_type = "Integer"]
+    id: i32,
+    #[sql_type = "Text"]
+    geometry: String,
+}
+
+// Проверка GeoJSON-геометрии: тип из allowed и правильная геометрия по мнению PostGIS
+fn validate_geometry(conn: &PgConnection, geometry: &Value, allowed: &[&str]) -> Result<String, Error> {
+    let kind = geometry.get("type").and_then(Value::as_str).unwrap_or("");
+    if !allowed.contains(&kind) {
+        return Err(Error::Validation(format!("{} geometry expected", allowed.join(" or "))));
+    }
+    if !geometry.get("coordinates").is_some_and(Value::is_array) {
+        return Err(Error::Validation("geometry coordinates expected".to_string()));
+    }
+    let text = geometry.to_string();
+    let check = sql_query(
+        "SELECT ST_IsValid(g) AS valid, ST_IsValidReason(g) AS reason \
+         FROM (SELECT ST_GeomFromGeoJSON($1) AS g) geometry",
+    )
+    .bind::<Text, _>(&text)
+    .get_result::<ValidityRow>(conn)
+    .map_err(|_| Error::Validation("invalid GeoJSON geometry".to_string()))?;
+    if !check.valid {
+        return Err(Error::Validation(format!("invalid geometry: {}", check.reason)));
+    }
+    Ok(text)
+}
+
+fn store_geometry(conn: &PgConnection, table: &str, column: &str, id: i32, geometry: &str) -> Result<(), Error> {
+    let updated = sql_query(format!(
+        "UPDATE {table} SET {column} = ST_Multi(ST_SetSRID(ST_GeomFromGeoJSON($2), 4326)) WHERE id = $1",
+        table = table,
+        column = column,
+    ))
+    .bind::<Integer, _>(id)
+    .bind::<Text, _>(geometry)
+    .execute(conn)?;
+    if updated == 0 {
+        return Err(Error::NotFound);
+    }
+    Ok(())
+}
+
+impl Street {
+    // Линия улицы, LineString или MultiLineString. Длина пересчитывается.
+    pub fn set_geometry(conn: &PgConnection, street_id: i32, geometry: &Value) -> Result<Street, Error> {
+        let geometry = validate_geometry(conn, geometry, STREET_GEOMETRY_TYPES)?;
+        store_geometry(conn, "streets", "geometry", street_id, &geometry)?;
+        streets::table.find(street_id).first(conn).map_err(Error::from)
+    }
+}
+
+impl District {
+    // Граница района, Polygon или MultiPolygon. Площадь пересчитывается,
+    // отметки и заявки внутри старой и новой границы заново раскладываются
+    // по районам.
+    pub fn set_boundary(conn: &PgConnection, district_id: i32, geometry: &Value) -> Result<District, Error> {
+        let geometry = validate_geometry(conn, geometry, DISTRICT_GEOMETRY_TYPES)?;
+        conn.transaction(|| {
+            store_geometry(conn, "districts", "boundary", district_id, &geometry)?;
+            reassign_districts(conn, district_id)?;
+            districts::table.find(district_id).first(conn).map_err(Error::from)
+        })
+    }
+}
+
+fn reassign_districts(conn: &PgConnection, district_id: i32) -> Result<(), Error> {
+    for table in &["user_points", "requests"] {
+        sql_query(format!(
+            "UPDATE {table} SET district_id = district_at(location, street_id) \
+             WHERE location IS NOT NULL \
+               AND (district_id = $1 OR location && (SELECT boundary FROM districts WHERE id = $1))",
+            table = table,
+        ))
+        .bind::<Integer, _>(district_id)
+        .execute(conn)?;
+    }
+    Ok(())
+}
+
+fn load_geometries(conn: &PgConnection, table: &str, column: &str, ids: &[i32]) -> Result<Vec<(i32, Value)>, Error> {
+    let rows = sql_query(format!(
+        "SELECT id, ST_AsGeoJSON({column}, 6) AS geometry FROM {table} \
+         WHERE id = ANY($1) AND {column} IS NOT NULL",
+        table = table,
+        column = column,
+    ))
+    .bind::<Array<Integer>, _>(ids)
+    .load::<GeometryRow>(conn)?;
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| serde_json::from_str(&row.geometry).ok().map(|geometry| (row.id, geometry)))
+        .collect())
+}
+
+// Линии улиц в GeoJSON для выгрузки
+pub fn street_geometries(conn: &PgConnection, street_ids: &[i32]) -> Result<Vec<(i32, Value)>, Error> {
+    load_geometries(conn, "streets", "geometry", street_ids)
+}
+
+// Границы районов в GeoJSON для выгрузки
+pub fn district_boundaries(conn: &PgConnection, district_ids: &[i32]) -> Result<Vec<(i32, Value)>, Error> {
+    load_geometries(conn, "districts", "boundary", district_ids)
+}
+
+//-------------------------------------------------------------------------------------
+// Плитки для карты
+
+// Плитки в обычной схеме z/x/y (как у OSM). Районы видны всегда,
+// улицы и объекты - начиная с крупных масштабов.
+pub const MAX_TILE_ZOOM: u32 = 22;
+pub const TILE_LAYER_DISTRICTS: &str = "districts";
+pub const TILE_LAYER_STREETS: &str = "streets";
+pub const TILE_LAYER_POINTS: &str = "points";
+pub const TILE_LAYERS: &[&str] = &[TILE_LAYER_DISTRICTS, TILE_LAYER_STREETS, TILE_LAYER_POINTS];
+
+const STREETS_MIN_ZOOM: u32 = 12;
+const POINTS_MIN_ZOOM: u32 = 14;
+const TILE_POINTS_LIMIT: i64 = 1000;
+// Плитка 256 пикселей; линии упрощаются до пикселя и берутся с запасом
+// в несколько пикселей, чтобы на стыках плиток не было разрывов
+const TILE_SIZE: f64 = 256.0;
+const TILE_BUFFER: f64 = 8.0;
+
+// Долгота и широта северо-западного угла плитки
+fn tile_corner(z: u32, x: u32, y: u32) -> (f64, f64) {
+    let n = 2f64.powi(z as i32);
+    let lon = x as f64 / n * 360.0 - 180.0;
+    let lat = (PI * (1.0 - 2.0 * y as f64 / n)).sinh().atan().to_degrees();
+    (lon, lat)
+}
+
+pub fn tile_bbox(z: u32, x: u32, y: u32) -> Result<BoundingBox, Error> {
+    if z > MAX_TILE_ZOOM || x >= 1 << z || y >= 1 << z {
+        return Err(Error::Validation(format!("invalid tile: {}/{}/{}", z, x, y)));
+    }
+    let (min_lon, max_lat) = tile_corner(z, x, y);
+    let (max_lon, min_lat) = tile_corner(z, x + 1, y + 1);
+    Ok(BoundingBox { min_lon, min_lat, max_lon, max_lat })
+}
+
+pub fn parse_tile_layers(value: Option<&str>) -> Result<Vec<&'static str>, Error> {
+    match value {
+        None => Ok(TILE_LAYERS.to_vec()),
+        Some(value) => value
+            .split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .map(|part| {
+                TILE_LAYERS
+                    .iter()
+                    .find(|layer| **layer == part)
+                    .copied()
+                    .ok_or_else(|| Error::Validation(format!("unknown layer: {}", part)))
+            })
+            .collect(),
+    }
+}
+
+#[derive(Debug, QueryableByName)]
+struct TileRow {
+    #[sql_type = "Integer"]
+    id: i32,
+    #[sql_type = "Nullable<Text>"]
+    geometry: Option<String>,
+    #[sql_type = "Text"]
+    properties: String,
+}
+
+// Районы или улицы в плитке: геометрия упрощается до размера пикселя
+// и обрезается по плитке с запасом
+fn tile_features(conn: &PgConnection, kind: &str, query: &str, bbox: &BoundingBox, pixel: f64) -> Result<Vec<Value>, Error> {
+    let rows = sql_query(query)
+        .bind::<Double, _>(bbox.min_lon - pixel * TILE_BUFFER)
+        .bind::<Double, _>(bbox.min_lat - pixel * TILE_BUFFER)
+        .bind::<Double, _>(bbox.max_lon + pixel * TILE_BUFFER)
+        .bind::<Double, _>(bbox.max_lat + pixel * TILE_BUFFER)
+        .bind::<Double, _>(pixel)
+        .load::<TileRow>(conn)?;
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| {
+            let geometry: Value = serde_json::from_str(row.geometry.as_deref()?).ok()?;
+            let properties = serde_json::from_str::<Value>(&row.properties).ok()?.as_object()?.clone();
+            Some(feature(kind, row.id, geometry, properties))
+        })
+        .collect())
+}
+
+// Плитка GeoJSON: районы, улицы и объекты в границах плитки z/x/y
+pub fn get_tile(conn: &PgConnection, z: u32, x: u32, y: u32, layers: &[&str]) -> Result<Value, Error> {
+    let bbox = tile_bbox(z, x, y)?;
+    let pixel = (bbox.max_lon - bbox.min_lon) / TILE_SIZE;
+    let mut features = Vec::new();
+    if layers.contains(&TILE_LAYER_DISTRICTS) {
+        features.extend(tile_features(
+            conn,
+            "district",
+            "SELECT d.id, ST_AsGeoJSON(ST_Intersection(ST_SimplifyPreserveTopology(d.boundary, $5), e.g), 6) AS geometry, \
+                    json_build_object('name', d.name, 'city_id', d.city_id, 'population', d.population, \
+                                      'area_sq_km', d.area_sq_km)::text AS properties \
+             FROM districts d, (SELECT ST_MakeEnvelope($1, $2, $3, $4, 4326) AS g) e \
+             WHERE d.boundary && e.g AND ST_Intersects(d.boundary, e.g) \
+             ORDER BY d.id",
+            &bbox,
+            pixel,
+        )?);
+    }
+    if layers.contains(&TILE_LAYER_STREETS) && z >= STREETS_MIN_ZOOM {
+        features.extend(tile_features(
+            conn,
+            "street",
+            "SELECT s.id, ST_AsGeoJSON(ST_Intersection(ST_Simplify(s.geometry, $5), e.g), 6) AS geometry, \
+                    json_build_object('name', s.name, 'district_id', s.district_id, 'length_km', s.length_km, \
+                                      'surface_type', s.surface_type, 'speed_limit', s.speed_limit)::text AS properties \
+             FROM streets s, (SELECT ST_MakeEnvelope($1, $2, $3, $4, 4326) AS g) e \
+             WHERE s.geometry && e.g AND ST_Intersects(s.geometry, e.g) \
+             ORDER BY s.id",
+            &bbox,
+            pixel,
+        )?);
+    }
+    if layers.contains(&TILE_LAYER_POINTS) && z >= POINTS_MIN_ZOOM {
+        for item in find_in_bbox(conn, &bbox, None, ALL_NEARBY_KINDS, TILE_POINTS_LIMIT)? {
+            let geometry = json!({ "type": "Point", "coordinates": [item.lon, item.lat] });
+            let properties = json!({ "name": item.name });
+            features.push(feature(&item.kind, item.id, geometry, properties.as_object().cloned().unwrap_or_default()));
+        }
+    }
+    Ok(json!({
+        "type": "FeatureCollection",
+        "bbox": [bbox.min_lon, bbox.min_lat, bbox.max_lon, bbox.max_lat],
+        "features": features,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WEB_MERCATOR_MAX_LAT: f64 = 85.051_128_78;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    #[test]
+    fn whole_world_tile() {
+        let bbox = tile_bbox(0, 0, 0).unwrap();
+        assert!(close(bbox.min_lon, -180.0) && close(bbox.max_lon, 180.0));
+        assert!(close(bbox.min_lat, -WEB_MERCATOR_MAX_LAT) && close(bbox.max_lat, WEB_MERCATOR_MAX_LAT));
+    }
+
+    #[test]
+    fn quarter_tile() {
+        // z=1: x растет на восток, y - на юг
+        let bbox = tile_bbox(1, 1, 0).unwrap();
+        assert!(close(bbox.min_lon, 0.0) && close(bbox.max_lon, 180.0));
+        assert!(close(bbox.min_lat, 0.0) && close(bbox.max_lat, WEB_MERCATOR_MAX_LAT));
+    }
+
+    #[test]
+    fn neighbour_tiles_share_edges() {
+        let tile = tile_bbox(12, 2476, 1280).unwrap();
+        let east = tile_bbox(12, 2477, 1280).unwrap();
+        let south = tile_bbox(12, 2476, 1281).unwrap();
+        assert_eq!(tile.max_lon, east.min_lon);
+        assert_eq!(tile.min_lat, south.max_lat);
+        assert!(tile.min_lon < tile.max_lon && tile.min_lat < tile.max_lat);
+    }
+
+    #[test]
+    fn invalid_tiles() {
+        assert!(matches!(tile_bbox(MAX_TILE_ZOOM + 1, 0, 0), Err(Error::Validation(_))));
+        assert!(matches!(tile_bbox(1, 2, 0), Err(Error::Validation(_))));
+        assert!(matches!(tile_bbox(1, 0, 2), Err(Error::Validation(_))));
+        let last = (1 << MAX_TILE_ZOOM) - 1;
+        assert!(tile_bbox(MAX_TILE_ZOOM, last, last).is_ok());
+    }
+
+    #[test]
+    fn tile_layers() {
+        assert_eq!(parse_tile_layers(None).unwrap(), TILE_LAYERS.to_vec());
+        assert_eq!(parse_tile_layers(Some("points, streets,")).unwrap(), vec![TILE_LAYER_POINTS, TILE_LAYER_STREETS]);
+        assert!(matches!(parse_tile_layers(Some("houses")), Err(Error::Validation(_))));
+    }
+}
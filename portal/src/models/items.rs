@@ -0,0 +1,280 @@
+use chrono::NaiveDateTime;
+use diesel::dsl::sql;
+use diesel::prelude::*;
+use diesel::sql_types::BigInt;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::Error;
+use crate::models::{Serve, Tag};
+use crate::schema::{categories, category_items, item_files, items, serve, serve_items, tag_object, tags};
+
+
+// Объекты шаблонных страниц сайта (views/pages.rs, views/for_kostia.rs):
+// блоги, услуги, товары, статьи и работы. Тип объекта совпадает с типом
+// категорий, в которые он входит. Теги объекта хранятся в tag_object,
+// картинки галереи - в files через item_files.
+
+pub const ITEM_BLOG: i16 = 1;
+pub const ITEM_SERVICE: i16 = 2;
+pub const ITEM_STORE: i16 = 3;
+pub const ITEM_WIKI: i16 = 4;
+pub const ITEM_WORK: i16 = 5;
+pub const ITEM_HELP: i16 = 6;
+
+// Картинка для объектов и категорий, у которых нет своей
+pub const DEFAULT_ITEM_IMAGE: &str = "/static/images/dark/store.jpg";
+// object_type тегов объекта в tag_object
+const ITEM_TAG_OBJECT: &str = "item";
+const MAX_ITEM_TITLE_LENGTH: usize = 100;
+
+// Категория объектов
+#[derive(Debug, Clone, Serialize, Queryable, Identifiable)]
+#[table_name = "categories"]
+pub struct Categories {
+    pub id: i32,
+    pub name: String,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub types: i16,
+    pub position: i16,
+}
+
+// Категория для списков: вместо описания число опубликованных объектов
+#[derive(Debug, Clone, Serialize)]
+pub struct Cat {
+    pub id: i32,
+    pub name: String,
+    pub image: String,
+    pub count: i64,
+}
+
+impl Categories {
+    pub fn get_image(&self) -> String {
+        self.image.clone().unwrap_or_else(|| DEFAULT_ITEM_IMAGE.to_string())
+    }
+
+    pub fn get(conn: &PgConnection, category_id: i32) -> Result<Categories, Error> {
+        categories::table
+            .find(category_id)
+            .first(conn)
+            .map_err(Error::from)
+    }
+
+    pub fn get_all(conn: &PgConnection) -> Result<Vec<Categories>, Error> {
+        categories::table
+            .order((categories::types, categories::position))
+            .load(conn)
+            .map_err(Error::from)
+    }
+
+    pub fn get_for_types(conn: &PgConnection, types: i16) -> Result<Vec<Categories>, Error> {
+        categories::table
+            .filter(categories::types.eq(types))
+            .order(categories::position)
+            .load(conn)
+            .map_err(Error::from)
+    }
+
+    // Категории одного типа с числом опубликованных объектов в каждой
+    pub fn get_categories_for_types(conn: &PgConnection, types: i16) -> Result<Vec<Cat>, Error> {
+        let _categories = Categories::get_for_types(conn, types)?;
+        let ids: Vec<i32> = _categories.iter().map(|c| c.id).collect();
+        let counts: Vec<(i32, i64)> = category_items::table
+            .inner_join(items::table)
+            .filter(category_items::category_id.eq_any(ids))
+            .filter(items::is_active.eq(true))
+            .group_by(category_items::category_id)
+            .select((category_items::category_id, sql::<BigInt>("COUNT(*)")))
+            .load(conn)?;
+
+        Ok(_categories
+            .into_iter()
+            .map(|c| Cat {
+                count: counts
+                    .iter()
+                    .find(|(id, _)| *id == c.id)
+                    .map(|(_, count)| *count)
+                    .unwrap_or(0),
+                image: c.get_image(),
+                id:    c.id,
+                name:  c.name,
+            })
+            .collect())
+    }
+}
+
+// Объект сайта
+#[derive(Debug, Clone, Serialize, Queryable, Identifiable)]
+pub struct Item {
+    pub id: i32,
+    pub user_id: i32,
+    pub title: String,
+    pub description: Option<String>,
+    pub content: Option<String>,
+    pub image: Option<String>,
+    pub types: i16,
+    pub is_active: bool,
+    pub created_at: NaiveDateTime,
+}
+
+// Данные объекта из формы создания или изменения
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ItemForm {
+    pub title: String,
+    pub description: Option<String>,
+    pub content: Option<String>,
+    pub image: Option<String>,
+}
+
+impl ItemForm {
+    pub fn validate(&self) -> Result<(), Error> {
+        let title = self.title.trim();
+        if title.is_empty() {
+            return Err(Error::Validation("title is required".to_string()));
+        }
+        if title.chars().count() > MAX_ITEM_TITLE_LENGTH {
+            return Err(Error::Validation(format!("title is longer than {} characters", MAX_ITEM_TITLE_LENGTH)));
+        }
+        Ok(())
+    }
+}
+
+impl Item {
+    pub fn get_image(&self) -> String {
+        self.image.clone().unwrap_or_else(|| DEFAULT_ITEM_IMAGE.to_string())
+    }
+
+    pub fn get(conn: &PgConnection, item_id: i32) -> Result<Item, Error> {
+        items::table
+            .find(item_id)
+            .first(conn)
+            .map_err(Error::from)
+    }
+
+    // Объект заданного типа: блог по ссылке на статью и т.п. Другой тип - NotFound.
+    pub fn get_with_types(conn: &PgConnection, item_id: i32, types: i16) -> Result<Item, Error> {
+        items::table
+            .find(item_id)
+            .filter(items::types.eq(types))
+            .first(conn)
+            .map_err(Error::from)
+    }
+
+    // Последние объекты типа. Неопубликованные видят только администраторы.
+    pub fn get_last(conn: &PgConnection, types: i16, limit: i64, is_admin: bool) -> Result<Vec<Item>, Error> {
+        let mut query = items::table
+            .filter(items::types.eq(types))
+            .into_boxed();
+        if !is_admin {
+            query = query.filter(items::is_active.eq(true));
+        }
+        query
+            .order((items::created_at.desc(), items::id.desc()))
+            .limit(limit)
+            .load(conn)
+            .map_err(Error::from)
+    }
+
+    // Объект, в галерею которого входит файл
+    pub fn get_by_image(conn: &PgConnection, file_id: i32) -> Result<Item, Error> {
+        items::table
+            .filter(items::id.eq_any(
+                item_files::table
+                    .filter(item_files::file_id.eq(file_id))
+                    .select(item_files::item_id),
+            ))
+            .first(conn)
+            .map_err(Error::from)
+    }
+
+    pub fn get_categories_obj(&self, conn: &PgConnection) -> Result<Vec<Categories>, Error> {
+        categories::table
+            .filter(categories::id.eq_any(
+                category_items::table
+                    .filter(category_items::item_id.eq(self.id))
+                    .select(category_items::category_id),
+            ))
+            .order(categories::position)
+            .load(conn)
+            .map_err(Error::from)
+    }
+
+    pub fn get_tags_obj(&self, conn: &PgConnection) -> Result<Vec<Tag>, Error> {
+        tags::table
+            .filter(tags::id.eq_any(
+                tag_object::table
+                    .filter(tag_object::object_type.eq(ITEM_TAG_OBJECT))
+                    .filter(tag_object::object_id.eq(self.id))
+                    .select(tag_object::tag_id),
+            ))
+            .order(tags::name)
+            .load(conn)
+            .map_err(Error::from)
+    }
+
+    pub fn get_serves(&self, conn: &PgConnection) -> Result<Vec<Serve>, Error> {
+        serve::table
+            .filter(serve::id.eq_any(
+                serve_items::table
+                    .filter(serve_items::item_id.eq(self.id))
+                    .select(serve_items::serve_id),
+            ))
+            .order(serve::position)
+            .load(conn)
+            .map_err(Error::from)
+    }
+
+    // id файлов галереи в порядке показа
+    pub fn get_images_ids(&self, conn: &PgConnection) -> Result<Vec<i32>, Error> {
+        item_files::table
+            .filter(item_files::item_id.eq(self.id))
+            .order((item_files::position, item_files::file_id))
+            .select(item_files::file_id)
+            .load(conn)
+            .map_err(Error::from)
+    }
+
+    pub fn create(conn: &PgConnection, user_id: i32, types: i16, form: &ItemForm) -> Result<Item, Error> {
+        form.validate()?;
+        diesel::insert_into(items::table)
+            .values((
+                items::user_id.eq(user_id),
+                items::title.eq(form.title.trim()),
+                items::description.eq(&form.description),
+                items::content.eq(&form.content),
+                items::image.eq(&form.image),
+                items::types.eq(types),
+            ))
+            .get_result(conn)
+            .map_err(Error::from)
+    }
+
+    // Изменение объекта. Картинка меняется, только если загружена новая.
+    pub fn edit(&self, conn: &PgConnection, form: &ItemForm) -> Result<Item, Error> {
+        form.validate()?;
+        diesel::update(self)
+            .set((
+                items::title.eq(form.title.trim()),
+                items::description.eq(&form.description),
+                items::content.eq(&form.content),
+                items::image.eq(form.image.as_ref().or(self.image.as_ref())),
+            ))
+            .get_result(conn)
+            .map_err(Error::from)
+    }
+
+    // Удаление объекта. Связи с категориями, услугами и галереей удаляет база,
+    // теги в tag_object не связаны внешним ключом и удаляются здесь же.
+    pub fn delete(&self, conn: &PgConnection) -> Result<(), Error> {
+        conn.transaction(|| {
+            diesel::delete(
+                tag_object::table
+                    .filter(tag_object::object_type.eq(ITEM_TAG_OBJECT))
+                    .filter(tag_object::object_id.eq(self.id)),
+            )
+            .execute(conn)?;
+            diesel::delete(self).execute(conn)?;
+            Ok(())
+        })
+    }
+}
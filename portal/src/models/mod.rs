@@ -0,0 +1,70 @@
+#![allow(non_snake_case, hidden_glob_reexports)]
+
+mod afisha;
+mod Advertisement;
+mod catalogue;
+mod City;
+mod Communites;
+mod files;
+mod Forum;
+mod forum_members;
+mod forum_moderation;
+mod forum_threads;
+pub mod geo;
+mod geocoder;
+mod geometry;
+mod geojson;
+mod items;
+mod MapsRoutes;
+mod notifications;
+mod opening_hours;
+mod organization_claims;
+mod Request;
+mod reviews;
+mod Schedules;
+mod serve;
+mod site_stats;
+mod statistics;
+mod subscriptions;
+mod tags;
+mod users;
+mod wifi_charging;
+
+pub use self::{
+    afisha::*,
+    Advertisement::*,
+    catalogue::*,
+    City::*,
+    files::*,
+    Forum::*,
+    forum_members::*,
+    forum_moderation::*,
+    forum_threads::*,
+    geo::*,
+    geocoder::*,
+    geometry::*,
+    geojson::*,
+    items::*,
+    MapsRoutes::*,
+    notifications::*,
+    opening_hours::*,
+    organization_claims::*,
+    Request::*,
+    reviews::*,
+    Schedules::*,
+    serve::*,
+    site_stats::*,
+    statistics::*,
+    subscriptions::*,
+    tags::*,
+    users::*,
+    wifi_charging::*,
+};
+
+// Модули City, Forum, Request и Advertisement называются так же, как их
+// основные структуры, и имя модуля закрывает структуру из реэкспорта выше.
+// Поэтому сами структуры реэкспортируются под именами с суффиксом Model.
+pub use self::City::City as CityModel;
+pub use self::Advertisement::Advertisement as AdvertisementModel;
+pub use self::Request::Request as RequestModel;
+pub use self::Forum::Forum as ForumModel;
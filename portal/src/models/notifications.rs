@@ -0,0 +1,252 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel::insert_into;
+use serde::Serialize;
+
+use crate::schema::{email_queue, event_reminders, notifications, users};
+use crate::errors::Error;
+
+
+// Структура для таблицы "notifications" - уведомления в личном кабинете
+#[derive(Debug, Serialize, Queryable)]
+pub struct Notification {
+    pub id: i32, // Идентификатор уведомления
+    pub user_id: i32, // Кому адресовано
+    pub kind: String, // Тип уведомления
+    pub event_id: Option<i32>, // Событие, к которому относится
+    pub title: String, // Заголовок
+    pub body: String, // Текст
+    pub is_read: bool, // Прочитано ли
+    pub created_at: NaiveDateTime, // Дата и время создания
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "notifications"]
+pub struct NewNotification<'a> {
+    pub user_id: i32,
+    pub kind: &'a str,
+    pub event_id: Option<i32>,
+    pub title: &'a str,
+    pub body: &'a str,
+}
+
+impl Notification {
+    pub fn create(conn: &PgConnection, new_notification: NewNotification) -> Result<Notification, Error> {
+        insert_into(notifications::table)
+            .values(&new_notification)
+            .get_result(conn)
+            .map_err(Error::from)
+    }
+
+    // Уведомления пользователя, новые первыми, и их общее количество
+    pub fn get_for_user(
+        conn: &PgConnection,
+        user_id: i32,
+        only_unread: bool,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<Notification>, i64), Error> {
+        let mut count_query = notifications::table
+            .filter(notifications::user_id.eq(user_id))
+            .into_boxed();
+        let mut query = notifications::table
+            .filter(notifications::user_id.eq(user_id))
+            .into_boxed();
+        if only_unread {
+            count_query = count_query.filter(notifications::is_read.eq(false));
+            query = query.filter(notifications::is_read.eq(false));
+        }
+        let total = count_query.count().get_result::<i64>(conn)?;
+        let items = query
+            .order(notifications::id.desc())
+            .offset(offset)
+            .limit(limit)
+            .load(conn)?;
+        Ok((items, total))
+    }
+
+    // Отметка о прочтении. Чужое уведомление - NotFound.
+    pub fn mark_read(conn: &PgConnection, notification_id: i32, user_id: i32) -> Result<Notification, Error> {
+        diesel::update(
+            notifications::table
+                .find(notification_id)
+                .filter(notifications::user_id.eq(user_id)),
+        )
+        .set(notifications::is_read.eq(true))
+        .get_result(conn)
+        .map_err(Error::from)
+    }
+
+    // Отметка о прочтении всех уведомлений. Возвращает количество отмеченных.
+    pub fn mark_all_read(conn: &PgConnection, user_id: i32) -> Result<usize, Error> {
+        diesel::update(
+            notifications::table
+                .filter(notifications::user_id.eq(user_id))
+                .filter(notifications::is_read.eq(false)),
+        )
+        .set(notifications::is_read.eq(true))
+        .execute(conn)
+        .map_err(Error::from)
+    }
+}
+
+// Структура для таблицы "event_reminders" - уже отправленные напоминания
+#[derive(Debug, Serialize, Queryable)]
+pub struct EventReminder {
+    pub id: i32,
+    pub event_id: i32,
+    pub user_id: i32,
+    pub starts_at: NaiveDateTime,
+    pub window_minutes: i32,
+    pub sent_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "event_reminders"]
+struct NewEventReminder {
+    event_id: i32,
+    user_id: i32,
+    starts_at: NaiveDateTime,
+    window_minutes: i32,
+}
+
+impl EventReminder {
+    // Попытка занять напоминание до отправки. false - его уже отправил
+    // этот или другой экземпляр планировщика.
+    pub fn claim(
+        conn: &PgConnection,
+        event_id: i32,
+        user_id: i32,
+        starts_at: NaiveDateTime,
+        window_minutes: i32,
+    ) -> Result<bool, Error> {
+        let inserted = insert_into(event_reminders::table)
+            .values(&NewEventReminder {
+                event_id: event_id,
+                user_id: user_id,
+                starts_at: starts_at,
+                window_minutes: window_minutes,
+            })
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+        Ok(inserted > 0)
+    }
+
+    // Отмена записи, если напоминание не удалось доставить ни одним способом,
+    // чтобы планировщик попробовал еще раз
+    pub fn release(
+        conn: &PgConnection,
+        event_id: i32,
+        user_id: i32,
+        starts_at: NaiveDateTime,
+        window_minutes: i32,
+    ) -> Result<(), Error> {
+        diesel::delete(
+            event_reminders::table
+                .filter(event_reminders::event_id.eq(event_id))
+                .filter(event_reminders::user_id.eq(user_id))
+                .filter(event_reminders::starts_at.eq(starts_at))
+                .filter(event_reminders::window_minutes.eq(window_minutes)),
+        )
+        .execute(conn)?;
+        Ok(())
+    }
+}
+
+// После стольких неудачных попыток письмо остается в очереди неотправленным
+pub const MAX_EMAIL_ATTEMPTS: i32 = 5;
+
+// Структура для таблицы "email_queue" - письма, ожидающие отправки
+#[derive(Debug, Serialize, Queryable)]
+pub struct QueuedEmail {
+    pub id: i32,
+    pub user_id: Option<i32>,
+    pub email: String,
+    pub kind: String,
+    pub subject: String,
+    pub body: String,
+    pub created_at: NaiveDateTime,
+    pub attempts: i32,
+    pub next_attempt_at: NaiveDateTime,
+    pub sent_at: Option<NaiveDateTime>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "email_queue"]
+pub struct NewQueuedEmail<'a> {
+    pub user_id: Option<i32>,
+    pub email: &'a str,
+    pub kind: &'a str,
+    pub subject: &'a str,
+    pub body: &'a str,
+}
+
+impl QueuedEmail {
+    // Письмо попадает в очередь в транзакции вызывающего, так что при
+    // откате транзакции оно не уйдет
+    pub fn enqueue(conn: &PgConnection, new_email: NewQueuedEmail) -> Result<QueuedEmail, Error> {
+        insert_into(email_queue::table)
+            .values(&new_email)
+            .get_result(conn)
+            .map_err(Error::from)
+    }
+
+    // Письма, которые пора отправить. Взятые письма откладываются на lease,
+    // чтобы другой экземпляр их не взял; если отправка оборвется, письмо
+    // снова станет доступно после lease.
+    pub fn lease_due(
+        conn: &PgConnection,
+        now: NaiveDateTime,
+        lease: chrono::Duration,
+        limit: i64,
+    ) -> Result<Vec<QueuedEmail>, Error> {
+        conn.transaction(|| {
+            let ids: Vec<i32> = email_queue::table
+                .filter(email_queue::sent_at.is_null())
+                .filter(email_queue::attempts.lt(MAX_EMAIL_ATTEMPTS))
+                .filter(email_queue::next_attempt_at.le(now))
+                .order(email_queue::id.asc())
+                .limit(limit)
+                .select(email_queue::id)
+                .for_update()
+                .skip_locked()
+                .load(conn)?;
+            diesel::update(email_queue::table.filter(email_queue::id.eq_any(ids)))
+                .set((
+                    email_queue::attempts.eq(email_queue::attempts + 1),
+                    email_queue::next_attempt_at.eq(now + lease),
+                ))
+                .get_results(conn)
+                .map_err(Error::from)
+        })
+    }
+
+    pub fn mark_sent(conn: &PgConnection, email_id: i32, now: NaiveDateTime) -> Result<(), Error> {
+        diesel::update(email_queue::table.find(email_id))
+            .set((email_queue::sent_at.eq(now), email_queue::last_error.eq(None::<String>)))
+            .execute(conn)?;
+        Ok(())
+    }
+
+    // Неудачная попытка: следующая через 2^attempts минут
+    pub fn mark_failed(conn: &PgConnection, email: &QueuedEmail, error: &str, now: NaiveDateTime) -> Result<(), Error> {
+        let delay = chrono::Duration::minutes(1 << email.attempts.clamp(0, MAX_EMAIL_ATTEMPTS));
+        diesel::update(email_queue::table.find(email.id))
+            .set((
+                email_queue::next_attempt_at.eq(now + delay),
+                email_queue::last_error.eq(error),
+            ))
+            .execute(conn)?;
+        Ok(())
+    }
+}
+
+// Адреса почты пользователей (идентификатор, email)
+pub fn get_user_emails(conn: &PgConnection, user_ids: &[i32]) -> Result<Vec<(i32, String)>, Error> {
+    users::table
+        .filter(users::id.eq_any(user_ids))
+        .select((users::id, users::email))
+        .load(conn)
+        .map_err(Error::from)
+}
@@ -0,0 +1,415 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
+use diesel::deserialize::{self, FromSql};
+use diesel::pg::Pg;
+use diesel::serialize::{self, Output, ToSql};
+use diesel::sql_types::Jsonb;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+
+use crate::errors::Error;
+
+
+// Режим работы организации: часы по дням недели и исключения по датам
+// (праздники, сокращенные дни). Хранится в organizations.opening_hours (JSONB),
+// для импорта и выгрузки переводится в строку формата OSM opening_hours:
+// "Mo-Fr 09:00-18:00; Sa 10:00-16:00; Jan 01 off; 2026 Dec 31 10:00-15:00".
+// Поддерживается подмножество формата: дни недели, часы, "off", "24/7"
+// и конкретные даты. Праздники (PH), недели и восход/закат не поддерживаются.
+
+const WEEKDAYS: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+// Промежуток работы в течение дня. closes <= opens - работа через полночь
+// (closes = 00:00 - до конца дня), opens == closes - круглые сутки.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeRange {
+    pub opens:  NaiveTime,
+    pub closes: NaiveTime,
+}
+
+impl TimeRange {
+    fn is_overnight(&self) -> bool {
+        self.closes <= self.opens
+    }
+
+    // Открыто ли в этот день в момент time. Хвост после полуночи
+    // относится к следующему дню и проверяется отдельно.
+    fn contains(&self, time: NaiveTime) -> bool {
+        time >= self.opens && (self.is_overnight() || time < self.closes)
+    }
+
+    fn to_osm(self) -> String {
+        let closes = if self.closes == NaiveTime::MIN {
+            "24:00".to_string()
+        }
+        else {
+            self.closes.format("%H:%M").to_string()
+        };
+        format!("{}-{}", self.opens.format("%H:%M"), closes)
+    }
+}
+
+// Особый день: праздник или сокращенный день. Без года - повторяется каждый год.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HoursException {
+    pub year:   Option<i32>,
+    pub month:  u32,
+    pub day:    u32,
+    pub ranges: Vec<TimeRange>,   // Пустой список - выходной
+}
+
+impl HoursException {
+    fn validate(&self) -> Result<(), Error> {
+        // для повторяющихся дат проверка по високосному году, чтобы 29 февраля было допустимо
+        if NaiveDate::from_ymd_opt(self.year.unwrap_or(2000), self.month, self.day).is_none() {
+            return Err(Error::Validation(format!("invalid date: {}-{}", self.month, self.day)));
+        }
+        Ok(())
+    }
+
+    fn matches(&self, date: NaiveDate) -> bool {
+        self.month == date.month() && self.day == date.day()
+            && self.year.is_none_or(|year| year == date.year())
+    }
+
+    fn same_date(&self, other: &HoursException) -> bool {
+        self.year == other.year && self.month == other.month && self.day == other.day
+    }
+
+    fn to_osm(&self) -> String {
+        let date = format!("{} {:02}", MONTHS[self.month as usize - 1], self.day);
+        let date = match self.year {
+            Some(year) => format!("{} {}", year, date),
+            None => date,
+        };
+        format!("{} {}", date, ranges_to_osm(&self.ranges))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, AsExpression, FromSqlRow)]
+#[sql_type = "Jsonb"]
+pub struct OpeningHours {
+    pub week:       [Vec<TimeRange>; 7],   // С понедельника по воскресенье, пустой день - выходной
+    #[serde(default)]
+    pub exceptions: Vec<HoursException>,
+}
+
+impl FromSql<Jsonb, Pg> for OpeningHours {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+        let value = <serde_json::Value as FromSql<Jsonb, Pg>>::from_sql(bytes)?;
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+impl ToSql<Jsonb, Pg> for OpeningHours {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        let value = serde_json::to_value(self)?;
+        <serde_json::Value as ToSql<Jsonb, Pg>>::to_sql(&value, out)
+    }
+}
+
+impl OpeningHours {
+    // Проверка расписания, пришедшего в JSON
+    pub fn validate(&self) -> Result<(), Error> {
+        for exception in &self.exceptions {
+            exception.validate()?;
+        }
+        Ok(())
+    }
+
+    // Часы работы в конкретный день: исключение на эту дату
+    // (с годом важнее повторяющегося) или обычный день недели
+    pub fn ranges_on(&self, date: NaiveDate) -> &[TimeRange] {
+        let exception = self
+            .exceptions
+            .iter()
+            .filter(|e| e.matches(date))
+            .max_by_key(|e| e.year.is_some());
+        match exception {
+            Some(exception) => &exception.ranges,
+            None => &self.week[date.weekday().num_days_from_monday() as usize],
+        }
+    }
+
+    // Открыто ли в момент at (местное время). Учитываются промежутки
+    // предыдущего дня, которые заканчиваются после полуночи.
+    pub fn is_open_at(&self, at: NaiveDateTime) -> bool {
+        let time = at.time();
+        if self.ranges_on(at.date()).iter().any(|r| r.contains(time)) {
+            return true;
+        }
+        match at.date().pred_opt() {
+            Some(yesterday) => self
+                .ranges_on(yesterday)
+                .iter()
+                .any(|r| r.is_overnight() && time < r.closes),
+            None => false,
+        }
+    }
+
+    // Разбор строки OSM opening_hours. Правила через ";", более позднее
+    // правило заменяет часы тех же дней, как в OSM.
+    pub fn from_osm(value: &str) -> Result<OpeningHours, Error> {
+        let mut hours = OpeningHours::default();
+        let mut has_rules = false;
+        for rule in value.split(';').map(str::trim).filter(|r| !r.is_empty()) {
+            has_rules = true;
+            if rule == "24/7" {
+                let full_day = TimeRange {
+                    opens:  NaiveTime::MIN,
+                    closes: NaiveTime::MIN,
+                };
+                hours.week = Default::default();
+                hours.week.iter_mut().for_each(|day| day.push(full_day));
+                continue;
+            }
+            let tokens: Vec<&str> = rule.split_whitespace().collect();
+            let split = tokens
+                .iter()
+                .position(|t| is_times_token(t))
+                .ok_or_else(|| Error::Validation(format!("no hours in rule: {}", rule)))?;
+            let ranges = parse_ranges(&tokens[split..].concat())?;
+            match parse_selector(&tokens[..split])? {
+                Selector::Weekdays(days) => {
+                    for day in days {
+                        hours.week[day] = ranges.clone();
+                    }
+                },
+                Selector::Date(year, month, day) => {
+                    let exception = HoursException {
+                        year:   year,
+                        month:  month,
+                        day:    day,
+                        ranges: ranges,
+                    };
+                    exception.validate()?;
+                    hours.exceptions.retain(|e| !e.same_date(&exception));
+                    hours.exceptions.push(exception);
+                },
+            }
+        }
+        if !has_rules {
+            return Err(Error::Validation("empty opening hours".to_string()));
+        }
+        Ok(hours)
+    }
+
+    // Строка OSM opening_hours. Соседние дни с одинаковыми часами
+    // объединяются в диапазон, выходные дни не пишутся.
+    pub fn to_osm(&self) -> String {
+        let full_day = |ranges: &Vec<TimeRange>| ranges.len() == 1 && ranges[0].opens == ranges[0].closes
+            && ranges[0].opens == NaiveTime::MIN;
+        let mut rules = Vec::new();
+        if self.week.iter().all(full_day) {
+            rules.push("24/7".to_string());
+        }
+        else {
+            let mut start = 0;
+            while start < 7 {
+                let mut end = start;
+                while end + 1 < 7 && self.week[end + 1] == self.week[start] {
+                    end += 1;
+                }
+                if !self.week[start].is_empty() {
+                    let days = if start == end {
+                        WEEKDAYS[start].to_string()
+                    }
+                    else {
+                        format!("{}-{}", WEEKDAYS[start], WEEKDAYS[end])
+                    };
+                    rules.push(format!("{} {}", days, ranges_to_osm(&self.week[start])));
+                }
+                start = end + 1;
+            }
+        }
+        rules.extend(self.exceptions.iter().map(HoursException::to_osm));
+        if rules.is_empty() {
+            return "off".to_string();
+        }
+        rules.join("; ")
+    }
+}
+
+enum Selector {
+    Weekdays(Vec<usize>),
+    Date(Option<i32>, u32, u32),
+}
+
+fn is_times_token(token: &str) -> bool {
+    token == "off" || token == "closed"
+        || (token.starts_with(|c: char| c.is_ascii_digit()) && token.contains(':'))
+}
+
+fn weekday_index(value: &str) -> Result<usize, Error> {
+    WEEKDAYS
+        .iter()
+        .position(|d| *d == value)
+        .ok_or_else(|| Error::Validation(format!("unsupported day selector: {}", value)))
+}
+
+// Дни правила: "Mo-Fr,Su", "Jan 01", "2026 Dec 31". Без дней - каждый день.
+fn parse_selector(tokens: &[&str]) -> Result<Selector, Error> {
+    match tokens {
+        [] => Ok(Selector::Weekdays((0..7).collect())),
+        [days] if !days.starts_with(|c: char| c.is_ascii_digit()) && !MONTHS.contains(days) => {
+            let mut result = Vec::new();
+            for part in days.split(',') {
+                match part.split_once('-') {
+                    Some((from, to)) => {
+                        let (from, to) = (weekday_index(from)?, weekday_index(to)?);
+                        // диапазон может переходить через воскресенье: Sa-Mo
+                        let mut day = from;
+                        loop {
+                            result.push(day);
+                            if day == to { break }
+                            day = (day + 1) % 7;
+                        }
+                    },
+                    None => result.push(weekday_index(part)?),
+                }
+            }
+            Ok(Selector::Weekdays(result))
+        },
+        [month, day] => Ok(Selector::Date(None, parse_month(month)?, parse_day(day)?)),
+        [year, month, day] => {
+            let year = year
+                .parse()
+                .map_err(|_| Error::Validation(format!("invalid year: {}", year)))?;
+            Ok(Selector::Date(Some(year), parse_month(month)?, parse_day(day)?))
+        },
+        _ => Err(Error::Validation(format!("unsupported selector: {}", tokens.join(" ")))),
+    }
+}
+
+fn parse_month(value: &str) -> Result<u32, Error> {
+    MONTHS
+        .iter()
+        .position(|m| *m == value)
+        .map(|m| m as u32 + 1)
+        .ok_or_else(|| Error::Validation(format!("invalid month: {}", value)))
+}
+
+fn parse_day(value: &str) -> Result<u32, Error> {
+    value
+        .parse()
+        .map_err(|_| Error::Validation(format!("invalid day: {}", value)))
+}
+
+// "09:00-13:00,14:00-18:00" или "off"
+fn parse_ranges(value: &str) -> Result<Vec<TimeRange>, Error> {
+    if value == "off" || value == "closed" {
+        return Ok(Vec::new());
+    }
+    value
+        .split(',')
+        .map(|range| {
+            let (opens, closes) = range
+                .split_once('-')
+                .ok_or_else(|| Error::Validation(format!("invalid time range: {}", range)))?;
+            let opens = parse_time(opens)?;
+            let closes = if closes == "24:00" { NaiveTime::MIN } else { parse_time(closes)? };
+            Ok(TimeRange { opens, closes })
+        })
+        .collect()
+}
+
+fn parse_time(value: &str) -> Result<NaiveTime, Error> {
+    NaiveTime::parse_from_str(value, "%H:%M")
+        .map_err(|_| Error::Validation(format!("invalid time: {}", value)))
+}
+
+fn ranges_to_osm(ranges: &[TimeRange]) -> String {
+    if ranges.is_empty() {
+        return "off".to_string();
+    }
+    ranges.iter().copied().map(TimeRange::to_osm).collect::<Vec<_>>().join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap().and_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn weekly_hours_round_trip() {
+        let value = "Mo-Fr 09:00-18:00; Sa 10:00-13:00,14:00-16:00";
+        let hours = OpeningHours::from_osm(value).unwrap();
+        assert_eq!(hours.week[0].len(), 1);
+        assert_eq!(hours.week[5].len(), 2);
+        assert!(hours.week[6].is_empty());
+        assert_eq!(hours.to_osm(), value);
+    }
+
+    #[test]
+    fn full_week_round_trip() {
+        let hours = OpeningHours::from_osm("24/7").unwrap();
+        assert_eq!(hours.to_osm(), "24/7");
+        assert!(hours.is_open_at(at(2026, 10, 18, 3, 30)));
+    }
+
+    #[test]
+    fn weekday_range_wraps_over_sunday() {
+        let hours = OpeningHours::from_osm("Sa-Mo 10:00-12:00").unwrap();
+        assert!(!hours.week[5].is_empty());
+        assert!(!hours.week[6].is_empty());
+        assert!(!hours.week[0].is_empty());
+        assert!(hours.week[1].is_empty());
+    }
+
+    #[test]
+    fn later_rule_replaces_days() {
+        let hours = OpeningHours::from_osm("Mo-Su 09:00-18:00; Su off").unwrap();
+        assert!(hours.week[6].is_empty());
+        assert_eq!(hours.to_osm(), "Mo-Sa 09:00-18:00");
+    }
+
+    #[test]
+    fn overnight_range() {
+        // 16 октября 2026 - пятница
+        let hours = OpeningHours::from_osm("Fr 22:00-02:00").unwrap();
+        assert_eq!(hours.to_osm(), "Fr 22:00-02:00");
+        assert!(!hours.is_open_at(at(2026, 10, 16, 21, 59)));
+        assert!(hours.is_open_at(at(2026, 10, 16, 23, 0)));
+        assert!(hours.is_open_at(at(2026, 10, 17, 1, 59)));
+        assert!(!hours.is_open_at(at(2026, 10, 17, 2, 0)));
+        // хвост после полуночи есть только у пятницы
+        assert!(!hours.is_open_at(at(2026, 10, 16, 1, 0)));
+    }
+
+    #[test]
+    fn until_midnight() {
+        let hours = OpeningHours::from_osm("Mo-Su 18:00-24:00").unwrap();
+        assert_eq!(hours.to_osm(), "Mo-Su 18:00-24:00");
+        assert!(hours.is_open_at(at(2026, 10, 16, 23, 59)));
+        assert!(!hours.is_open_at(at(2026, 10, 17, 0, 0)));
+    }
+
+    #[test]
+    fn exceptions() {
+        let hours = OpeningHours::from_osm("Mo-Su 10:00-20:00; Dec 31 off; 2026 Dec 31 10:00-15:00").unwrap();
+        assert_eq!(hours.to_osm(), "Mo-Su 10:00-20:00; Dec 31 off; 2026 Dec 31 10:00-15:00");
+        // дата с годом важнее повторяющейся
+        assert!(hours.is_open_at(at(2026, 12, 31, 14, 0)));
+        assert!(!hours.is_open_at(at(2026, 12, 31, 16, 0)));
+        assert!(!hours.is_open_at(at(2027, 12, 31, 12, 0)));
+        assert!(hours.is_open_at(at(2027, 1, 1, 12, 0)));
+    }
+
+    #[test]
+    fn exception_cuts_overnight_tail() {
+        let hours = OpeningHours::from_osm("Mo-Su 22:00-02:00; 2026 Jan 01 off").unwrap();
+        assert!(!hours.is_open_at(at(2026, 1, 2, 1, 0)));
+        assert!(hours.is_open_at(at(2026, 1, 3, 1, 0)));
+    }
+
+    #[test]
+    fn invalid_values() {
+        for value in &["", " ; ", "Mo 25:00-26:00", "Feb 30 off", "Xx 10:00-12:00", "Mo-Fr", "PH off"] {
+            assert!(matches!(OpeningHours::from_osm(value), Err(Error::Validation(_))), "{}", value);
+        }
+        assert!(OpeningHours::from_osm("Feb 29 off").is_ok());
+    }
+}
@@ -0,0 +1,358 @@
+use chrono::{Duration, NaiveDateTime};
+use diesel::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::Error;
+use crate::schema::{administrators, files, organization_claims, organizations};
+
+
+// Заявки на права владельца организации. Пользователь подтверждает права
+// документом (файл из модуля файлов) или кодом, отправленным на почту
+// организации, после чего заявку одобряет администратор.
+
+pub const CLAIM_DOCUMENT: &str = "document";
+pub const CLAIM_EMAIL: &str = "email";
+
+pub const CLAIM_PENDING: &str = "pending";
+pub const CLAIM_APPROVED: &str = "approved";
+pub const CLAIM_REJECTED: &str = "rejected";
+pub const CLAIM_CANCELLED: &str = "cancelled";
+
+// Код из письма действует полчаса, ввести его можно пять раз,
+// повторно отправить - не чаще раза в минуту
+const CODE_TTL_MINUTES: i64 = 30;
+const MAX_CODE_ATTEMPTS: i32 = 5;
+const CODE_RESEND_SECONDS: i64 = 60;
+
+// Является ли пользователь администратором сайта
+pub fn is_administrator(conn: &PgConnection, user_id: i32) -> Result<bool, Error> {
+    diesel::select(diesel::dsl::exists(
+        administrators::table.filter(administrators::user_id.eq(user_id)),
+    ))
+    .get_result(conn)
+    .map_err(Error::from)
+}
+
+// Структура для таблицы "organization_claims"
+#[derive(Debug, Clone, Serialize, Queryable)]
+pub struct OrganizationClaim {
+    pub id: i32, // Идентификатор заявки
+    pub organization_id: i32, // Организация
+    pub user_id: i32, // Кто заявляет права
+    pub method: String, // Способ подтверждения: document или email
+    pub file_id: Option<i32>, // Документ, подтверждающий права
+    pub email: Option<String>, // Адрес, на который отправлен код
+    #[serde(skip_serializing)]
+    pub email_code: Option<String>, // Код из письма
+    pub code_sent_at: Option<NaiveDateTime>, // Когда отправлен код
+    #[serde(skip_serializing)]
+    pub code_attempts: i32, // Число попыток ввода кода
+    pub email_verified_at: Option<NaiveDateTime>, // Когда код подтвержден
+    pub status: String, // pending, approved, rejected или cancelled
+    pub comment: Option<String>, // Комментарий администратора
+    pub reviewed_by: Option<i32>, // Администратор, рассмотревший заявку
+    pub reviewed_at: Option<NaiveDateTime>, // Когда рассмотрена
+    pub created_at: NaiveDateTime, // Дата и время создания
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "organization_claims"]
+struct NewOrganizationClaim<'a> {
+    organization_id: i32,
+    user_id: i32,
+    method: &'a str,
+    file_id: Option<i32>,
+    email: Option<&'a str>,
+    email_code: Option<&'a str>,
+    code_sent_at: Option<NaiveDateTime>,
+}
+
+// Заявка от пользователя: способ подтверждения и документ для способа document
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClaimForm {
+    pub method:  String,
+    pub file_id: Option<i32>,
+}
+
+// Шестизначный код для письма
+fn generate_code() -> String {
+    format!("{:06}", rand::thread_rng().gen_range(0..1_000_000))
+}
+
+impl OrganizationClaim {
+    pub fn get_by_id(conn: &PgConnection, claim_id: i32) -> Result<OrganizationClaim, Error> {
+        organization_claims::table
+            .find(claim_id)
+            .first(conn)
+            .map_err(Error::from)
+    }
+
+    // Заявки пользователя, новые первыми
+    pub fn get_for_user(conn: &PgConnection, user_id: i32) -> Result<Vec<OrganizationClaim>, Error> {
+        organization_claims::table
+            .filter(organization_claims::user_id.eq(user_id))
+            .order(organization_claims::id.desc())
+            .load(conn)
+            .map_err(Error::from)
+    }
+
+    // Открытые заявки для администратора, старые первыми, и их общее количество
+    pub fn get_pending(conn: &PgConnection, offset: i64, limit: i64) -> Result<(Vec<OrganizationClaim>, i64), Error> {
+        let total = organization_claims::table
+            .filter(organization_claims::status.eq(CLAIM_PENDING))
+            .count()
+            .get_result::<i64>(conn)?;
+        let items = organization_claims::table
+            .filter(organization_claims::status.eq(CLAIM_PENDING))
+            .order(organization_claims::id.asc())
+            .offset(offset)
+            .limit(limit)
+            .load(conn)?;
+        Ok((items, total))
+    }
+
+    // Открытая заявка пользователя. Чужая заявка - NotFound.
+    fn get_own_pending(conn: &PgConnection, claim_id: i32, user_id: i32) -> Result<OrganizationClaim, Error> {
+        let claim = organization_claims::table
+            .find(claim_id)
+            .for_update()
+            .first::<OrganizationClaim>(conn)?;
+        if claim.user_id != user_id {
+            return Err(Error::NotFound);
+        }
+        if claim.status != CLAIM_PENDING {
+            return Err(Error::Conflict(format!("claim is {}", claim.status)));
+        }
+        Ok(claim)
+    }
+
+    // Новая заявка. У организации не должно быть владельца, у пользователя -
+    // другой открытой заявки на нее. Для способа email код сохраняется
+    // в заявке, отправить его на почту организации должен вызывающий.
+    pub fn create(conn: &PgConnection, organization_id: i32, user_id: i32, form: &ClaimForm) -> Result<OrganizationClaim, Error> {
+        conn.transaction(|| {
+            let (owner_id, org_email) = organizations::table
+                .find(organization_id)
+                .select((organizations::owner_id, organizations::email))
+                .for_update()
+                .first::<(Option<i32>, Option<String>)>(conn)?;
+            match owner_id {
+                Some(owner_id) if owner_id == user_id => {
+                    return Err(Error::Conflict("you already own this organization".to_string()));
+                },
+                Some(_) => return Err(Error::Conflict("organization already has an owner".to_string())),
+                None => (),
+            }
+            let pending = organization_claims::table
+                .filter(organization_claims::organization_id.eq(organization_id))
+                .filter(organization_claims::user_id.eq(user_id))
+                .filter(organization_claims::status.eq(CLAIM_PENDING))
+                .count()
+                .get_result::<i64>(conn)?;
+            if pending > 0 {
+                return Err(Error::Conflict("you already have a pending claim".to_string()));
+            }
+
+            let code = generate_code();
+            let mut new_claim = NewOrganizationClaim {
+                organization_id: organization_id,
+                user_id:         user_id,
+                method:          CLAIM_DOCUMENT,
+                file_id:         None,
+                email:           None,
+                email_code:      None,
+                code_sent_at:    None,
+            };
+            match form.method.as_str() {
+                CLAIM_DOCUMENT => {
+                    let file_id = form
+                        .file_id
+                        .ok_or_else(|| Error::Validation("file_id is required".to_string()))?;
+                    let own_file = files::table
+                        .filter(files::id.eq(file_id))
+                        .filter(files::user_id.eq(user_id))
+                        .filter(files::is_deleted.eq(false))
+                        .count()
+                        .get_result::<i64>(conn)?;
+                    if own_file == 0 {
+                        return Err(Error::Validation("document must be your own uploaded file".to_string()));
+                    }
+                    new_claim.file_id = Some(file_id);
+                },
+                CLAIM_EMAIL => {
+                    let email = org_email
+                        .as_deref()
+                        .map(str::trim)
+                        .filter(|e| !e.is_empty())
+                        .ok_or_else(|| Error::Validation("organization has no email".to_string()))?;
+                    new_claim.method = CLAIM_EMAIL;
+                    new_claim.email = Some(email);
+                    new_claim.email_code = Some(code.as_str());
+                    new_claim.code_sent_at = Some(chrono::Local::now().naive_local());
+                },
+                other => return Err(Error::Validation(format!("unknown claim method: {}", other))),
+            }
+            diesel::insert_into(organization_claims::table)
+                .values(&new_claim)
+                .get_result(conn)
+                .map_err(Error::from)
+        })
+    }
+
+    // Новый код для заявки по почте. Прежний код перестает действовать.
+    pub fn renew_code(conn: &PgConnection, claim_id: i32, user_id: i32) -> Result<OrganizationClaim, Error> {
+        conn.transaction(|| {
+            let claim = OrganizationClaim::get_own_pending(conn, claim_id, user_id)?;
+            if claim.method != CLAIM_EMAIL {
+                return Err(Error::Validation("claim is not confirmed by email".to_string()));
+            }
+            if claim.email_verified_at.is_some() {
+                return Err(Error::Conflict("email is already confirmed".to_string()));
+            }
+            let now = chrono::Local::now().naive_local();
+            if claim.code_sent_at.is_some_and(|sent| now - sent < Duration::seconds(CODE_RESEND_SECONDS)) {
+                return Err(Error::Conflict("code was sent less than a minute ago".to_string()));
+            }
+            diesel::update(organization_claims::table.find(claim_id))
+                .set((
+                    organization_claims::email_code.eq(generate_code()),
+                    organization_claims::code_sent_at.eq(now),
+                    organization_claims::code_attempts.eq(0),
+                ))
+                .get_result(conn)
+                .map_err(Error::from)
+        })
+    }
+
+    // Проверка кода из письма. Попытка засчитывается до сравнения,
+    // поэтому перебор ограничен и при параллельных запросах.
+    pub fn verify_code(conn: &PgConnection, claim_id: i32, user_id: i32, code: &str) -> Result<OrganizationClaim, Error> {
+        let claim = OrganizationClaim::get_by_id(conn, claim_id)?;
+        if claim.user_id != user_id {
+            return Err(Error::NotFound);
+        }
+        if claim.status != CLAIM_PENDING || claim.method != CLAIM_EMAIL {
+            return Err(Error::Conflict("claim does not wait for an email code".to_string()));
+        }
+        if claim.email_verified_at.is_some() {
+            return Ok(claim);
+        }
+        let now = chrono::Local::now().naive_local();
+        if claim.code_sent_at.is_none_or(|sent| now - sent > Duration::minutes(CODE_TTL_MINUTES)) {
+            return Err(Error::Conflict("code has expired, request a new one".to_string()));
+        }
+        let claim = diesel::update(
+            organization_claims::table
+                .find(claim_id)
+                .filter(organization_claims::code_attempts.lt(MAX_CODE_ATTEMPTS)),
+        )
+        .set(organization_claims::code_attempts.eq(organization_claims::code_attempts + 1))
+        .get_result::<OrganizationClaim>(conn)
+        .optional()?
+        .ok_or_else(|| Error::Conflict("too many attempts, request a new code".to_string()))?;
+        if claim.email_code.as_deref() != Some(code.trim()) {
+            return Err(Error::Validation("invalid code".to_string()));
+        }
+        diesel::update(organization_claims::table.find(claim_id))
+            .set(organization_claims::email_verified_at.eq(now))
+            .get_result(conn)
+            .map_err(Error::from)
+    }
+
+    // Отзыв своей заявки
+    pub fn cancel(conn: &PgConnection, claim_id: i32, user_id: i32) -> Result<OrganizationClaim, Error> {
+        conn.transaction(|| {
+            OrganizationClaim::get_own_pending(conn, claim_id, user_id)?;
+            diesel::update(organization_claims::table.find(claim_id))
+                .set(organization_claims::status.eq(CLAIM_CANCELLED))
+                .get_result(conn)
+                .map_err(Error::from)
+        })
+    }
+
+    // Одобрение администратором: пользователь становится владельцем
+    // организации, остальные открытые заявки на нее отклоняются.
+    // Возвращает одобренную заявку и отклоненные вместе с ней.
+    pub fn approve(
+        conn: &PgConnection,
+        claim_id: i32,
+        admin_id: i32,
+        comment: Option<&str>,
+    ) -> Result<(OrganizationClaim, Vec<OrganizationClaim>), Error> {
+        conn.transaction(|| {
+            let claim = OrganizationClaim::get_pending_for_review(conn, claim_id)?;
+            let confirmed = match claim.method.as_str() {
+                CLAIM_DOCUMENT => claim.file_id.is_some(),
+                _ => claim.email_verified_at.is_some(),
+            };
+            if !confirmed {
+                return Err(Error::Conflict("claim is not confirmed yet".to_string()));
+            }
+            let owner_id = organizations::table
+                .find(claim.organization_id)
+                .select(organizations::owner_id)
+                .for_update()
+                .first::<Option<i32>>(conn)?;
+            if owner_id.is_some_and(|owner_id| owner_id != claim.user_id) {
+                return Err(Error::Conflict("organization already has an owner".to_string()));
+            }
+            let now = chrono::Local::now().naive_local();
+            diesel::update(organizations::table.find(claim.organization_id))
+                .set(organizations::owner_id.eq(claim.user_id))
+                .execute(conn)?;
+            let claim = diesel::update(organization_claims::table.find(claim_id))
+                .set((
+                    organization_claims::status.eq(CLAIM_APPROVED),
+                    organization_claims::comment.eq(comment),
+                    organization_claims::reviewed_by.eq(admin_id),
+                    organization_claims::reviewed_at.eq(now),
+                ))
+                .get_result::<OrganizationClaim>(conn)?;
+            let rejected = diesel::update(
+                organization_claims::table
+                    .filter(organization_claims::organization_id.eq(claim.organization_id))
+                    .filter(organization_claims::status.eq(CLAIM_PENDING)),
+            )
+            .set((
+                organization_claims::status.eq(CLAIM_REJECTED),
+                organization_claims::comment.eq("organization was assigned to another owner"),
+                organization_claims::reviewed_by.eq(admin_id),
+                organization_claims::reviewed_at.eq(now),
+            ))
+            .get_results::<OrganizationClaim>(conn)?;
+            Ok((claim, rejected))
+        })
+    }
+
+    // Отклонение администратором. Причина обязательна, ее увидит заявитель.
+    pub fn reject(conn: &PgConnection, claim_id: i32, admin_id: i32, comment: &str) -> Result<OrganizationClaim, Error> {
+        let comment = comment.trim();
+        if comment.is_empty() {
+            return Err(Error::Validation("comment is required".to_string()));
+        }
+        conn.transaction(|| {
+            OrganizationClaim::get_pending_for_review(conn, claim_id)?;
+            diesel::update(organization_claims::table.find(claim_id))
+                .set((
+                    organization_claims::status.eq(CLAIM_REJECTED),
+                    organization_claims::comment.eq(comment),
+                    organization_claims::reviewed_by.eq(admin_id),
+                    organization_claims::reviewed_at.eq(chrono::Local::now().naive_local()),
+                ))
+                .get_result(conn)
+                .map_err(Error::from)
+        })
+    }
+
+    fn get_pending_for_review(conn: &PgConnection, claim_id: i32) -> Result<OrganizationClaim, Error> {
+        let claim = organization_claims::table
+            .find(claim_id)
+            .for_update()
+            .first::<OrganizationClaim>(conn)?;
+        if claim.status != CLAIM_PENDING {
+            return Err(Error::Conflict(format!("claim is {}", claim.status)));
+        }
+        Ok(claim)
+    }
+}
@@ -0,0 +1,380 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDateTime;
+use diesel::dsl::sql;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Nullable, Numeric};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::Error;
+use crate::schema::{files, organizations, review_photos, reviews, user_points};
+
+
+// Отзывы об организациях и пользовательских точках. Объект задается видом
+// и идентификатором, как в tag_object. Средняя оценка и число отзывов
+// хранятся у самого объекта и пересчитываются в той же транзакции, что и отзыв.
+
+pub const MAX_REVIEW_PHOTOS: usize = 10;
+const MAX_REVIEW_LENGTH: usize = 5000;
+
+// Вид объекта, к которому относится отзыв
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewObject {
+    Organization,
+    UserPoint,
+}
+
+impl ReviewObject {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewObject::Organization => "organization",
+            ReviewObject::UserPoint => "user_point",
+        }
+    }
+
+    // Блокирует строку объекта до конца транзакции, чтобы параллельные
+    // отзывы пересчитывали среднее по очереди. Возвращает владельца объекта,
+    // который может отвечать на отзывы: автора точки или подтвержденного
+    // владельца организации.
+    fn lock(&self, conn: &PgConnection, object_id: i32) -> Result<Option<i32>, Error> {
+        match self {
+            ReviewObject::Organization => organizations::table
+                .find(object_id)
+                .select(organizations::owner_id)
+                .for_update()
+                .first::<Option<i32>>(conn)
+                .map_err(Error::from),
+            ReviewObject::UserPoint => {
+                let owner_id = user_points::table
+                    .find(object_id)
+                    .select(user_points::user_id)
+                    .for_update()
+                    .first::<i32>(conn)?;
+                Ok(Some(owner_id))
+            },
+        }
+    }
+
+    // Есть ли объект с таким идентификатором
+    pub fn exists(&self, conn: &PgConnection, object_id: i32) -> Result<bool, Error> {
+        let found = match self {
+            ReviewObject::Organization => diesel::select(diesel::dsl::exists(organizations::table.find(object_id)))
+                .get_result(conn)?,
+            ReviewObject::UserPoint => diesel::select(diesel::dsl::exists(user_points::table.find(object_id)))
+                .get_result(conn)?,
+        };
+        Ok(found)
+    }
+
+    // Пересчет средней оценки и числа отзывов. Вызывается под блокировкой объекта.
+    // При удалении отзыва то же делает триггер reviews_deleted.
+    fn refresh_rating(&self, conn: &PgConnection, object_id: i32) -> Result<(), Error> {
+        let (rating, count) = reviews::table
+            .filter(reviews::object_type.eq(self.as_str()))
+            .filter(reviews::object_id.eq(object_id))
+            .select(sql::<(Nullable<Numeric>, BigInt)>("AVG(reviews.rating), COUNT(*)"))
+            .first::<(Option<BigDecimal>, i64)>(conn)?;
+        let rating = rating.map(|r| r.with_scale(2));
+        let count = count as i32;
+        match self {
+            ReviewObject::Organization => diesel::update(organizations::table.find(object_id))
+                .set((organizations::rating.eq(rating), organizations::reviews_count.eq(count)))
+                .execute(conn)?,
+            ReviewObject::UserPoint => diesel::update(user_points::table.find(object_id))
+                .set((user_points::rating.eq(rating), user_points::reviews_count.eq(count)))
+                .execute(conn)?,
+        };
+        Ok(())
+    }
+}
+
+// Структура для таблицы "reviews"
+#[derive(Debug, Clone, Serialize, Queryable)]
+pub struct Review {
+    pub id: i32, // Идентификатор отзыва
+    pub object_type: String, // Вид объекта: organization или user_point
+    pub object_id: i32, // Идентификатор объекта
+    pub user_id: i32, // Автор
+    pub rating: i16, // Оценка от 1 до 5
+    pub body: Option<String>, // Текст
+    pub created_at: NaiveDateTime, // Дата и время создания
+    pub updated_at: Option<NaiveDateTime>, // Дата и время последнего изменения
+    pub reply: Option<String>, // Ответ владельца
+    pub reply_user_id: Option<i32>, // Кто ответил
+    pub replied_at: Option<NaiveDateTime>, // Когда ответил
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "reviews"]
+struct NewReview<'a> {
+    object_type: &'a str,
+    object_id: i32,
+    user_id: i32,
+    rating: i16,
+    body: Option<&'a str>,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "review_photos"]
+struct NewReviewPhoto {
+    review_id: i32,
+    file_id: i32,
+    position: i16,
+}
+
+// Фотография к отзыву
+#[derive(Debug, Clone, Serialize, Queryable)]
+pub struct ReviewPhoto {
+    pub file_id: i32,
+    pub file_url: Option<String>,
+}
+
+// Отзыв вместе с фотографиями, в таком виде он отдается в API
+#[derive(Debug, Serialize)]
+pub struct ReviewDetail {
+    #[serde(flatten)]
+    pub review: Review,
+    pub photos: Vec<ReviewPhoto>,
+}
+
+// Данные отзыва от автора: оценка, текст и файлы с фотографиями
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReviewForm {
+    pub rating: i16,
+    pub body: Option<String>,
+    #[serde(default)]
+    pub photos: Vec<i32>,
+}
+
+impl ReviewForm {
+    pub fn validate(&self) -> Result<(), Error> {
+        if !(1..=5).contains(&self.rating) {
+            return Err(Error::Validation("rating must be between 1 and 5".to_string()));
+        }
+        if self.body.as_ref().is_some_and(|b| b.chars().count() > MAX_REVIEW_LENGTH) {
+            return Err(Error::Validation(format!("review is longer than {} characters", MAX_REVIEW_LENGTH)));
+        }
+        if self.photos.len() > MAX_REVIEW_PHOTOS {
+            return Err(Error::Validation(format!("no more than {} photos per review", MAX_REVIEW_PHOTOS)));
+        }
+        Ok(())
+    }
+
+    fn body(&self) -> Option<&str> {
+        self.body.as_deref().map(str::trim).filter(|b| !b.is_empty())
+    }
+}
+
+impl Review {
+    pub fn get_by_id(conn: &PgConnection, review_id: i32) -> Result<Review, Error> {
+        reviews::table
+            .find(review_id)
+            .first(conn)
+            .map_err(Error::from)
+    }
+
+    // Отзывы об объекте, новые первыми, и их общее количество
+    pub fn get_for_object(
+        conn: &PgConnection,
+        object: ReviewObject,
+        object_id: i32,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<ReviewDetail>, i64), Error> {
+        let total = reviews::table
+            .filter(reviews::object_type.eq(object.as_str()))
+            .filter(reviews::object_id.eq(object_id))
+            .count()
+            .get_result::<i64>(conn)?;
+        let items = reviews::table
+            .filter(reviews::object_type.eq(object.as_str()))
+            .filter(reviews::object_id.eq(object_id))
+            .order((reviews::created_at.desc(), reviews::id.desc()))
+            .offset(offset)
+            .limit(limit)
+            .load::<Review>(conn)?;
+        Ok((Review::with_photos(conn, items)?, total))
+    }
+
+    // Отзыв пользователя об объекте, если он есть
+    pub fn get_for_user(
+        conn: &PgConnection,
+        object: ReviewObject,
+        object_id: i32,
+        user_id: i32,
+    ) -> Result<Option<Review>, Error> {
+        reviews::table
+            .filter(reviews::object_type.eq(object.as_str()))
+            .filter(reviews::object_id.eq(object_id))
+            .filter(reviews::user_id.eq(user_id))
+            .first(conn)
+            .optional()
+            .map_err(Error::from)
+    }
+
+    pub fn into_detail(self, conn: &PgConnection) -> Result<ReviewDetail, Error> {
+        let mut items = Review::with_photos(conn, vec![self])?;
+        Ok(items.remove(0))
+    }
+
+    fn with_photos(conn: &PgConnection, items: Vec<Review>) -> Result<Vec<ReviewDetail>, Error> {
+        let ids: Vec<i32> = items.iter().map(|r| r.id).collect();
+        let photos = review_photos::table
+            .inner_join(files::table)
+            .filter(review_photos::review_id.eq_any(&ids))
+            .filter(files::is_deleted.eq(false))
+            .order((review_photos::review_id, review_photos::position))
+            .select((review_photos::review_id, files::id, files::file_url))
+            .load::<(i32, i32, Option<String>)>(conn)?;
+        Ok(items
+            .into_iter()
+            .map(|review| {
+                let photos = photos
+                    .iter()
+                    .filter(|(review_id, _, _)| *review_id == review.id)
+                    .map(|(_, file_id, file_url)| ReviewPhoto {
+                        file_id:  *file_id,
+                        file_url: file_url.clone(),
+                    })
+                    .collect();
+                ReviewDetail { review, photos }
+            })
+            .collect())
+    }
+
+    // Фотографии отзыва заменяются целиком. Прикрепить можно только свои
+    // неудаленные файлы.
+    fn set_photos(conn: &PgConnection, review_id: i32, user_id: i32, photos: &[i32]) -> Result<(), Error> {
+        let mut unique: Vec<i32> = Vec::with_capacity(photos.len());
+        for file_id in photos {
+            if !unique.contains(file_id) {
+                unique.push(*file_id);
+            }
+        }
+        let owned = files::table
+            .filter(files::id.eq_any(&unique))
+            .filter(files::user_id.eq(user_id))
+            .filter(files::is_deleted.eq(false))
+            .count()
+            .get_result::<i64>(conn)?;
+        if owned as usize != unique.len() {
+            return Err(Error::Validation("photos must be your own uploaded files".to_string()));
+        }
+        diesel::delete(review_photos::table.filter(review_photos::review_id.eq(review_id))).execute(conn)?;
+        let rows: Vec<NewReviewPhoto> = unique
+            .iter()
+            .enumerate()
+            .map(|(position, file_id)| NewReviewPhoto {
+                review_id: review_id,
+                file_id:   *file_id,
+                position:  position as i16,
+            })
+            .collect();
+        diesel::insert_into(review_photos::table).values(&rows).execute(conn)?;
+        Ok(())
+    }
+
+    // Новый отзыв. Один пользователь - один отзыв на объект (иначе Conflict),
+    // владелец не может оценивать свой объект.
+    pub fn create(
+        conn: &PgConnection,
+        object: ReviewObject,
+        object_id: i32,
+        user_id: i32,
+        form: &ReviewForm,
+    ) -> Result<Review, Error> {
+        form.validate()?;
+        conn.transaction(|| {
+            let owner_id = object.lock(conn, object_id)?;
+            if owner_id == Some(user_id) {
+                return Err(Error::Forbidden);
+            }
+            if Review::get_for_user(conn, object, object_id, user_id)?.is_some() {
+                return Err(Error::Conflict("you have already reviewed this".to_string()));
+            }
+            let review = diesel::insert_into(reviews::table)
+                .values(&NewReview {
+                    object_type: object.as_str(),
+                    object_id:   object_id,
+                    user_id:     user_id,
+                    rating:      form.rating,
+                    body:        form.body(),
+                })
+                .get_result::<Review>(conn)?;
+            Review::set_photos(conn, review.id, user_id, &form.photos)?;
+            object.refresh_rating(conn, object_id)?;
+            Ok(review)
+        })
+    }
+
+    // Изменение своего отзыва. Чужой отзыв - NotFound.
+    pub fn update(conn: &PgConnection, review_id: i32, user_id: i32, form: &ReviewForm) -> Result<Review, Error> {
+        form.validate()?;
+        conn.transaction(|| {
+            let review = Review::get_by_id(conn, review_id)?;
+            if review.user_id != user_id {
+                return Err(Error::NotFound);
+            }
+            let object = review.object()?;
+            object.lock(conn, review.object_id)?;
+            let review = diesel::update(reviews::table.find(review_id))
+                .set((
+                    reviews::rating.eq(form.rating),
+                    reviews::body.eq(form.body()),
+                    reviews::updated_at.eq(chrono::Local::now().naive_local()),
+                ))
+                .get_result::<Review>(conn)?;
+            Review::set_photos(conn, review.id, user_id, &form.photos)?;
+            object.refresh_rating(conn, review.object_id)?;
+            Ok(review)
+        })
+    }
+
+    // Удаление своего отзыва. Чужой отзыв - NotFound. Оценку объекта
+    // пересчитывает триггер в базе, как и при удалении отзывов вместе с автором.
+    pub fn delete(conn: &PgConnection, review_id: i32, user_id: i32) -> Result<(), Error> {
+        conn.transaction(|| {
+            let review = Review::get_by_id(conn, review_id)?;
+            if review.user_id != user_id {
+                return Err(Error::NotFound);
+            }
+            review.object()?.lock(conn, review.object_id)?;
+            diesel::delete(reviews::table.find(review_id)).execute(conn)?;
+            Ok(())
+        })
+    }
+
+    // Ответ владельца объекта на отзыв. None убирает ответ.
+    pub fn reply(conn: &PgConnection, review_id: i32, user_id: i32, reply: Option<&str>) -> Result<Review, Error> {
+        let reply = reply.map(str::trim).filter(|r| !r.is_empty());
+        if reply.is_some_and(|r| r.chars().count() > MAX_REVIEW_LENGTH) {
+            return Err(Error::Validation(format!("reply is longer than {} characters", MAX_REVIEW_LENGTH)));
+        }
+        conn.transaction(|| {
+            let review = Review::get_by_id(conn, review_id)?;
+            if review.object()?.lock(conn, review.object_id)? != Some(user_id) {
+                return Err(Error::Forbidden);
+            }
+            let (reply_user_id, replied_at) = match reply {
+                Some(_) => (Some(user_id), Some(chrono::Local::now().naive_local())),
+                None => (None, None),
+            };
+            diesel::update(reviews::table.find(review_id))
+                .set((
+                    reviews::reply.eq(reply),
+                    reviews::reply_user_id.eq(reply_user_id),
+                    reviews::replied_at.eq(replied_at),
+                ))
+                .get_result(conn)
+                .map_err(Error::from)
+        })
+    }
+
+    pub fn object(&self) -> Result<ReviewObject, Error> {
+        match self.object_type.as_str() {
+            "organization" => Ok(ReviewObject::Organization),
+            "user_point" => Ok(ReviewObject::UserPoint),
+            other => Err(Error::Validation(format!("unknown review object: {}", other))),
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use diesel::prelude::*;
+use serde::Serialize;
+
+use crate::errors::Error;
+use crate::schema::{serve, serve_categories, tech_categories};
+
+
+// Каталог услуг для страницы /serve_list/: технические категории
+// (по уровню сложности) -> категории услуг -> услуги.
+
+#[derive(Debug, Clone, Serialize, Queryable, Identifiable)]
+#[table_name = "tech_categories"]
+pub struct TechCategories {
+    pub id: i32,
+    pub name: String,
+    pub description: Option<String>,
+    pub level: i16,
+    pub position: i16,
+}
+
+#[derive(Debug, Clone, Serialize, Queryable, Identifiable)]
+#[table_name = "serve_categories"]
+pub struct ServeCategories {
+    pub id: i32,
+    pub name: String,
+    pub description: Option<String>,
+    pub tech_cat_id: i32,
+    pub position: i16,
+}
+
+#[derive(Debug, Clone, Serialize, Queryable, Identifiable)]
+#[table_name = "serve"]
+pub struct Serve {
+    pub id: i32,
+    pub name: String,
+    pub description: Option<String>,
+    pub price: i32,
+    pub man_hours: i32,
+    pub category_id: i32,
+    pub tech_cat_id: i32,
+    pub position: i16,
+}
+
+impl TechCategories {
+    pub fn get(conn: &PgConnection, tech_cat_id: i32) -> Result<TechCategories, Error> {
+        tech_categories::table
+            .find(tech_cat_id)
+            .first(conn)
+            .map_err(Error::from)
+    }
+
+    pub fn get_all(conn: &PgConnection) -> Result<Vec<TechCategories>, Error> {
+        tech_categories::table
+            .order((tech_categories::level, tech_categories::position))
+            .load(conn)
+            .map_err(Error::from)
+    }
+
+    // Технические категории того же уровня сложности
+    pub fn get_for_level(conn: &PgConnection, level: i16) -> Result<Vec<TechCategories>, Error> {
+        tech_categories::table
+            .filter(tech_categories::level.eq(level))
+            .order(tech_categories::position)
+            .load(conn)
+            .map_err(Error::from)
+    }
+}
+
+impl ServeCategories {
+    pub fn get(conn: &PgConnection, category_id: i32) -> Result<ServeCategories, Error> {
+        serve_categories::table
+            .find(category_id)
+            .first(conn)
+            .map_err(Error::from)
+    }
+}
+
+impl Serve {
+    pub fn get(conn: &PgConnection, serve_id: i32) -> Result<Serve, Error> {
+        serve::table
+            .find(serve_id)
+            .first(conn)
+            .map_err(Error::from)
+    }
+}
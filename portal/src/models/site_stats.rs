@@ -0,0 +1,179 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::Serialize;
+
+use crate::errors::Error;
+use crate::schema::{cookie_stats, cookie_users, feedbacks, stat_pages};
+
+
+// Статистика шаблонных страниц сайта: счетчики страниц, посетители по cookie
+// и их история просмотров, а также сообщения с формы обратной связи.
+
+pub const STAT_PAGE_MAIN: i16 = 1;
+pub const STAT_PAGE_INFO: i16 = 10;
+
+pub const DEVICE_DESCTOP: i16 = 1;
+pub const DEVICE_MOBILE: i16 = 2;
+
+#[derive(Debug, Clone, Serialize, Queryable, Identifiable)]
+pub struct StatPage {
+    pub id: i32,
+    pub types: i16,
+    pub view: i32,
+    pub height: f64,
+    pub seconds: i32,
+    pub now_u: i32,
+}
+
+impl StatPage {
+    // Счетчик страницы по ее типу. Если записи еще нет, она создается.
+    // increment - прибавить ли текущего посетителя к now_u.
+    pub fn get_or_create(conn: &PgConnection, types: i16, increment: bool) -> Result<StatPage, Error> {
+        let added = if increment { 1 } else { 0 };
+        diesel::insert_into(stat_pages::table)
+            .values((stat_pages::types.eq(types), stat_pages::now_u.eq(added)))
+            .on_conflict(stat_pages::types)
+            .do_update()
+            .set(stat_pages::now_u.eq(stat_pages::now_u + added))
+            .get_result(conn)
+            .map_err(Error::from)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Queryable, Identifiable)]
+pub struct Feedback {
+    pub id: i32,
+    pub username: String,
+    pub email: String,
+    pub message: String,
+    pub created_at: NaiveDateTime,
+}
+
+impl Feedback {
+    pub fn get_all(conn: &PgConnection) -> Result<Vec<Feedback>, Error> {
+        feedbacks::table
+            .order(feedbacks::created_at.desc())
+            .load(conn)
+            .map_err(Error::from)
+    }
+}
+
+// Посетитель сайта по cookie, в том числе не вошедший
+#[derive(Debug, Clone, Serialize, Queryable, Identifiable)]
+pub struct CookieUser {
+    pub id: i32,
+    pub user_id: Option<i32>,
+    pub ip: String,
+    pub device: i16,
+    pub created_at: NaiveDateTime,
+}
+
+// Просмотр страницы посетителем
+#[derive(Debug, Clone, Serialize, Queryable, Identifiable)]
+pub struct CookieStat {
+    pub id: i32,
+    pub user_id: i32,
+    pub page: i16,
+    pub link: String,
+    pub title: String,
+    pub height: f64,
+    pub seconds: i32,
+    pub created_at: NaiveDateTime,
+}
+
+// Номер следующей страницы списка или 0, если эта последняя.
+// Списки загружаются с одной лишней строкой, по ней и видно продолжение.
+fn split_page<T>(mut rows: Vec<T>, page: i32, limit: i64) -> (Vec<T>, i32) {
+    if rows.len() as i64 > limit {
+        rows.truncate(limit as usize);
+        (rows, page + 1)
+    }
+    else {
+        (rows, 0)
+    }
+}
+
+fn page_offset(page: i32, limit: i64) -> i64 {
+    (page.max(1) as i64 - 1) * limit
+}
+
+impl CookieUser {
+    pub fn get(conn: &PgConnection, cookie_user_id: i32) -> Result<CookieUser, Error> {
+        cookie_users::table
+            .find(cookie_user_id)
+            .first(conn)
+            .map_err(Error::from)
+    }
+
+    // Посетитель из cookie. Если cookie нет или запись удалена, создается новый.
+    // Вошедший пользователь привязывается к записи при первом визите после входа.
+    pub fn get_or_create(
+        conn: &PgConnection,
+        cookie_user_id: Option<i32>,
+        user_id: Option<i32>,
+        ip: &str,
+        device: i16,
+    ) -> Result<CookieUser, Error> {
+        let existing = match cookie_user_id {
+            Some(id) => cookie_users::table.find(id).first::<CookieUser>(conn).optional()?,
+            None => None,
+        };
+        match existing {
+            Some(cookie_user) if cookie_user.user_id.is_some() || user_id.is_none() => Ok(cookie_user),
+            Some(cookie_user) => diesel::update(&cookie_user)
+                .set(cookie_users::user_id.eq(user_id))
+                .get_result(conn)
+                .map_err(Error::from),
+            None => diesel::insert_into(cookie_users::table)
+                .values((
+                    cookie_users::user_id.eq(user_id),
+                    cookie_users::ip.eq(ip),
+                    cookie_users::device.eq(device),
+                ))
+                .get_result(conn)
+                .map_err(Error::from),
+        }
+    }
+
+    // Посетители сайта, новые сначала
+    pub fn get_users_list(conn: &PgConnection, page: i32, limit: i64) -> Result<(Vec<CookieUser>, i32), Error> {
+        let rows = cookie_users::table
+            .order(cookie_users::id.desc())
+            .limit(limit + 1)
+            .offset(page_offset(page, limit))
+            .load(conn)?;
+        Ok(split_page(rows, page, limit))
+    }
+}
+
+impl CookieStat {
+    // История просмотров посетителя, последние сначала
+    pub fn get_stat_list(conn: &PgConnection, cookie_user_id: i32, page: i32, limit: i64) -> Result<(Vec<CookieStat>, i32), Error> {
+        let rows = cookie_stats::table
+            .filter(cookie_stats::user_id.eq(cookie_user_id))
+            .order((cookie_stats::created_at.desc(), cookie_stats::id.desc()))
+            .limit(limit + 1)
+            .offset(page_offset(page, limit))
+            .load(conn)?;
+        Ok(split_page(rows, page, limit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_page_reports_next_page_only_when_rows_remain() {
+        assert_eq!(split_page(vec![1, 2, 3], 1, 2), (vec![1, 2], 2));
+        assert_eq!(split_page(vec![1, 2], 1, 2), (vec![1, 2], 0));
+        assert_eq!(split_page(Vec::<i32>::new(), 3, 2), (vec![], 0));
+    }
+
+    #[test]
+    fn page_offset_starts_from_the_first_page() {
+        assert_eq!(page_offset(1, 20), 0);
+        assert_eq!(page_offset(3, 20), 40);
+        assert_eq!(page_offset(0, 20), 0);
+    }
+}
@@ -0,0 +1,367 @@
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
+use diesel::prelude::*;
+use diesel::sql_query;
+use diesel::sql_types::{BigInt, Double, Integer, Nullable, Text, Timestamp};
+use serde::Serialize;
+
+use crate::errors::Error;
+use crate::models::{CityModel as City, District};
+use crate::schema::{cities, districts};
+
+
+// Статистика города для администраторов: плотность населения по районам
+// и количество объектов с помесячными рядами за последние months месяцев.
+// Объекты на улицах относятся к городу через улицу и район. Объявления
+// без организации, события и заявки без адреса относятся к городу по
+// координатам: по границам его районов, а пока границы не заданы - по
+// прямоугольнику вокруг известных объектов города с запасом около 2 км.
+// События и объявления без координат берутся по указанному у них городу.
+
+pub const DEFAULT_STATISTICS_MONTHS: u32 = 12;
+pub const MAX_STATISTICS_MONTHS: u32 = 60;
+
+// Запас вокруг объектов города, в градусах
+const EXTENT_MARGIN: f64 = 0.02;
+
+// Улицы города, $1 - идентификатор города
+const CITY_STREETS: &str = "(SELECT s.id FROM streets s JOIN districts d ON d.id = s.district_id WHERE d.city_id = $1)";
+
+// Плотность населения района
+#[derive(Debug, Clone, Serialize)]
+pub struct DistrictDensity {
+    pub id: i32,
+    pub name: String,
+    pub population: i32,
+    pub area_sq_km: Option<BigDecimal>,
+    pub density: Option<f64>,   // Человек на кв. км, пусто - площадь неизвестна
+}
+
+// Показатель: текущее значение и помесячный ряд, выровненный по months
+#[derive(Debug, Clone, Serialize)]
+pub struct StatMetric {
+    pub total: i64,
+    pub series: Vec<i64>,
+}
+
+// Показатель в разбивке по ключу: категории или статусу
+#[derive(Debug, Clone, Serialize)]
+pub struct StatGroup {
+    pub key: String,
+    #[serde(flatten)]
+    pub metric: StatMetric,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CityStatistics {
+    pub city_id: i32,
+    pub city_name: String,
+    pub population: Option<i32>,
+    pub area_sq_km: Option<BigDecimal>,
+    pub density: Option<f64>,
+    pub months: Vec<String>,                     // "2026-01", старые первыми
+    pub districts: Vec<DistrictDensity>,
+    pub organizations: StatMetric,               // Ряд - добавленные за месяц
+    pub organizations_by_category: Vec<StatGroup>,
+    pub user_points: StatMetric,                 // Ряд - добавленные за месяц
+    pub wifi_charging: StatMetric,               // Ряд - добавленные за месяц
+    pub active_advertisements: StatMetric,       // Итог - активные сейчас, ряд - поданные за месяц
+    pub upcoming_events: StatMetric,             // Итог - еще не начавшиеся, ряд - начавшиеся в месяце
+    pub requests_by_status: Vec<StatGroup>,      // Ряд - поданные за месяц
+}
+
+#[derive(Debug, QueryableByName)]
+struct CountRow {
+    #[sql_type = "Text"]
+    key: String,
+    #[sql_type = "Nullable<Text>"]
+    month: Option<String>,
+    #[sql_type = "BigInt"]
+    count: i64,
+}
+
+#[derive(Debug, QueryableByName)]
+struct ExtentRow {
+    #[sql_type = "Nullable<Double>"]
+    min_lon: Option<f64>,
+    #[sql_type = "Nullable<Double>"]
+    min_lat: Option<f64>,
+    #[sql_type = "Nullable<Double>"]
+    max_lon: Option<f64>,
+    #[sql_type = "Nullable<Double>"]
+    max_lat: Option<f64>,
+}
+
+// Один показатель: откуда считать (таблица с псевдонимом t и условие
+// принадлежности городу), что входит в итог и по какой дате строится ряд.
+struct MetricQuery {
+    from: String,
+    scope: String,
+    current: &'static str,
+    date: &'static str,
+    key: &'static str,
+}
+
+fn density(population: Option<i32>, area_sq_km: Option<&BigDecimal>) -> Option<f64> {
+    let area = area_sq_km.and_then(|a| a.to_f64()).filter(|a| *a > 0.0)?;
+    population.map(|p| ((p as f64 / area) * 10.0).round() / 10.0)
+}
+
+// Первые дни последних months месяцев, старые первыми
+fn month_starts(now: NaiveDateTime, months: u32) -> Vec<NaiveDate> {
+    let mut result = Vec::with_capacity(months as usize);
+    let (mut year, mut month) = (now.year(), now.month());
+    for _ in 0..months {
+        result.push(NaiveDate::from_ymd_opt(year, month, 1).expect("first day of month"));
+        if month == 1 {
+            year -= 1;
+            month = 12;
+        }
+        else {
+            month -= 1;
+        }
+    }
+    result.reverse();
+    result
+}
+
+// Прямоугольник вокруг организаций, отметок и точек Wi-Fi города
+fn city_extent(conn: &PgConnection, city_id: i32) -> Result<ExtentRow, Error> {
+    let query = format!(
+        "SELECT ST_XMin(e) AS min_lon, ST_YMin(e) AS min_lat, ST_XMax(e) AS max_lon, ST_YMax(e) AS max_lat \
+         FROM (SELECT ST_Expand(ST_Extent(location)::geometry, $2) AS e FROM ( \
+             SELECT location FROM organizations WHERE street_id IN {streets} \
+             UNION ALL SELECT location FROM user_points WHERE street_id IN {streets} \
+             UNION ALL SELECT location FROM wifi_charging WHERE street_id IN {streets} \
+         ) points) extent",
+        streets = CITY_STREETS,
+    );
+    sql_query(query)
+        .bind::<Integer, _>(city_id)
+        .bind::<Double, _>(EXTENT_MARGIN)
+        .get_result(conn)
+        .map_err(Error::from)
+}
+
+impl MetricQuery {
+    // Итог и помесячный ряд по каждому ключу. Параметры запросов:
+    // $1 - город, $2..$5 - прямоугольник города, $6 - начало ряда, $7 - сейчас.
+    fn run(
+        &self,
+        conn: &PgConnection,
+        city_id: i32,
+        extent: &ExtentRow,
+        months: &[NaiveDate],
+        now: NaiveDateTime,
+    ) -> Result<Vec<StatGroup>, Error> {
+        let since = months[0].and_time(NaiveTime::MIN);
+        let load = |query: String| -> Result<Vec<CountRow>, Error> {
+            sql_query(query)
+                .bind::<Integer, _>(city_id)
+                .bind::<Nullable<Double>, _>(extent.min_lon)
+                .bind::<Nullable<Double>, _>(extent.min_lat)
+                .bind::<Nullable<Double>, _>(extent.max_lon)
+                .bind::<Nullable<Double>, _>(extent.max_lat)
+                .bind::<Timestamp, _>(since)
+                .bind::<Timestamp, _>(now)
+                .load::<CountRow>(conn)
+                .map_err(Error::from)
+        };
+        let totals = load(format!(
+            "SELECT {key} AS key, NULL::text AS month, COUNT(*) AS count \
+             FROM {from} WHERE ({scope}) AND ({current}) GROUP BY 1",
+            key = self.key,
+            from = self.from,
+            scope = self.scope,
+            current = self.current,
+        ))?;
+        let series = load(format!(
+            "SELECT {key} AS key, to_char(date_trunc('month', {date}), 'YYYY-MM') AS month, COUNT(*) AS count \
+             FROM {from} WHERE ({scope}) AND {date} >= $6 AND {date} <= $7 GROUP BY 1, 2",
+            key = self.key,
+            from = self.from,
+            scope = self.scope,
+            date = self.date,
+        ))?;
+
+        let labels: Vec<String> = months.iter().map(|m| m.format("%Y-%m").to_string()).collect();
+        let mut groups: Vec<StatGroup> = Vec::new();
+        for row in totals.iter().chain(series.iter()) {
+            if !groups.iter().any(|g| g.key == row.key) {
+                groups.push(StatGroup {
+                    key:    row.key.clone(),
+                    metric: StatMetric {
+                        total:  0,
+                        series: vec![0; labels.len()],
+                    },
+                });
+            }
+            let group = groups.iter_mut().find(|g| g.key == row.key).unwrap();
+            match row.month {
+                None => group.metric.total = row.count,
+                Some(ref month) => {
+                    if let Some(index) = labels.iter().position(|l| l == month) {
+                        group.metric.series[index] = row.count;
+                    }
+                },
+            }
+        }
+        groups.sort_by(|a, b| b.metric.total.cmp(&a.metric.total).then_with(|| a.key.cmp(&b.key)));
+        Ok(groups)
+    }
+
+    // Показатель без разбивки
+    fn run_single(
+        &self,
+        conn: &PgConnection,
+        city_id: i32,
+        extent: &ExtentRow,
+        months: &[NaiveDate],
+        now: NaiveDateTime,
+    ) -> Result<StatMetric, Error> {
+        Ok(self
+            .run(conn, city_id, extent, months, now)?
+            .into_iter()
+            .next()
+            .map(|g| g.metric)
+            .unwrap_or_else(|| StatMetric {
+                total:  0,
+                series: vec![0; months.len()],
+            }))
+    }
+}
+
+// Условие "внутри города" для геометрии column: в границах одного из районов
+// или, если границ у районов нет, в прямоугольнике города
+fn in_city(column: &str) -> String {
+    format!(
+        "CASE WHEN EXISTS (SELECT 1 FROM districts d WHERE d.city_id = $1 AND d.boundary IS NOT NULL) \
+         THEN EXISTS (SELECT 1 FROM districts d WHERE d.city_id = $1 AND ST_Covers(d.boundary, {column})) \
+         ELSE {column} && ST_MakeEnvelope($2, $3, $4, $5, 4326) END",
+        column = column,
+    )
+}
+
+// Для событий и объявлений: по координатам, а если их нет - по city_id
+fn located_in_city(column: &str) -> String {
+    format!(
+        "CASE WHEN {column} IS NOT NULL THEN {in_city} ELSE t.city_id = $1 END",
+        column = column,
+        in_city = in_city(column),
+    )
+}
+
+pub fn get_city_statistics(conn: &PgConnection, city_id: i32, months: u32, now: NaiveDateTime) -> Result<CityStatistics, Error> {
+    let months = month_starts(now, months.clamp(1, MAX_STATISTICS_MONTHS));
+    let city = cities::table.find(city_id).first::<City>(conn)?;
+    let districts = districts::table
+        .filter(districts::city_id.eq(city_id))
+        .order(districts::name)
+        .load::<District>(conn)?;
+    let extent = city_extent(conn, city_id)?;
+    let on_city_street = format!("t.street_id IN {}", CITY_STREETS);
+
+    let metric = |from: &str, scope: String, current: &'static str, date: &'static str, key: &'static str| MetricQuery {
+        from:    from.to_string(),
+        scope:   scope,
+        current: current,
+        date:    date,
+        key:     key,
+    };
+    let organizations = metric("organizations t", on_city_street.clone(), "TRUE", "t.created_at", "''");
+    let by_category = metric("organizations t", on_city_street.clone(), "TRUE", "t.created_at", "COALESCE(t.category, '')");
+    let user_points = metric("user_points t", on_city_street.clone(), "TRUE", "t.created_at", "''");
+    let wifi_charging = metric("wifi_charging t", on_city_street.clone(), "TRUE", "t.created_at", "''");
+    let advertisements = metric(
+        "advertisements t",
+        format!(
+            "t.organization_id IN (SELECT o.id FROM organizations o WHERE o.street_id IN {}) OR {}",
+            CITY_STREETS,
+            located_in_city("t.coordinates"),
+        ),
+        "t.is_active",
+        "t.created_at",
+        "''",
+    );
+    let events = metric(
+        "events t",
+        format!("t.is_published AND t.cancelled_at IS NULL AND {}", located_in_city("t.coordinates")),
+        "t.date_start >= $7",
+        "t.date_start",
+        "''",
+    );
+    let requests = metric(
+        "requests t",
+        format!(
+            "t.district_id IN (SELECT d.id FROM districts d WHERE d.city_id = $1) OR t.street_id IN {} OR {}",
+            CITY_STREETS,
+            in_city("t.location"),
+        ),
+        "TRUE",
+        "t.request_date",
+        "t.status::text",
+    );
+
+    Ok(CityStatistics {
+        city_id:                   city.id,
+        city_name:                 city.name.clone(),
+        population:                city.population,
+        area_sq_km:                city.area_sq_km.clone(),
+        density:                   density(city.population, city.area_sq_km.as_ref()),
+        districts:                 districts
+            .into_iter()
+            .map(|d| DistrictDensity {
+                density:    density(Some(d.population), d.area_sq_km.as_ref()),
+                id:         d.id,
+                name:       d.name,
+                population: d.population,
+                area_sq_km: d.area_sq_km,
+            })
+            .collect(),
+        organizations:             organizations.run_single(conn, city_id, &extent, &months, now)?,
+        organizations_by_category: by_category.run(conn, city_id, &extent, &months, now)?,
+        user_points:               user_points.run_single(conn, city_id, &extent, &months, now)?,
+        wifi_charging:             wifi_charging.run_single(conn, city_id, &extent, &months, now)?,
+        active_advertisements:     advertisements.run_single(conn, city_id, &extent, &months, now)?,
+        upcoming_events:           events.run_single(conn, city_id, &extent, &months, now)?,
+        requests_by_status:        requests.run(conn, city_id, &extent, &months, now)?,
+        months:                    months.iter().map(|m| m.format("%Y-%m").to_string()).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(now: NaiveDateTime, months: u32) -> Vec<String> {
+        month_starts(now, months).iter().map(|m| m.format("%Y-%m-%d").to_string()).collect()
+    }
+
+    #[test]
+    fn month_starts_within_year() {
+        let now = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap().and_hms_opt(15, 30, 0).unwrap();
+        assert_eq!(labels(now, 3), vec!["2026-08-01", "2026-09-01", "2026-10-01"]);
+    }
+
+    #[test]
+    fn month_starts_across_year() {
+        let now = NaiveDate::from_ymd_opt(2026, 2, 28).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        assert_eq!(labels(now, 4), vec!["2025-11-01", "2025-12-01", "2026-01-01", "2026-02-01"]);
+    }
+
+    #[test]
+    fn month_starts_current_only() {
+        let now = NaiveDate::from_ymd_opt(2026, 1, 31).unwrap().and_hms_opt(23, 59, 59).unwrap();
+        assert_eq!(labels(now, 1), vec!["2026-01-01"]);
+        assert_eq!(month_starts(now, MAX_STATISTICS_MONTHS).len(), MAX_STATISTICS_MONTHS as usize);
+        assert_eq!(labels(now, 13)[0], "2025-01-01");
+    }
+
+    #[test]
+    fn density_needs_area() {
+        assert_eq!(density(Some(1000), Some(&BigDecimal::from(3))), Some(333.3));
+        assert_eq!(density(Some(1000), Some(&BigDecimal::from(0))), None);
+        assert_eq!(density(Some(1000), None), None);
+        assert_eq!(density(None, Some(&BigDecimal::from(3))), None);
+    }
+}
@@ -1,6 +1,10 @@
 use chrono::NaiveDateTime;
 use diesel::prelude::*;
 use diesel::insert_into;
+use diesel::dsl::sql;
+use diesel::sql_types::BigInt;
+use crate::schema::subscriptions; // Подключение схемы базы данных
+use crate::errors::Error;
 
 #[derive(Debug, Queryable)]
 pub struct Subscription {
@@ -20,9 +24,27 @@ pub struct Subscription {
     pub description: Option<String>,
 }
 
+#[derive(Insertable)]
+#[table_name = "subscriptions"]
+pub struct NewSubscription<'a> {
+    pub user_id: i32,
+    pub object_id: i32,
+    pub object_type: &'a str,
+    pub status: &'a str,
+    pub name: &'a str,
+    pub types: i16,
+    pub link: &'a str,
+    pub image: &'a str,
+    pub category_id: i32,
+    pub lists: i16,
+    pub members: i32,
+    pub description: Option<&'a str>,
+}
+
 //Создание подписки (Create)
 
 impl Subscription {
+    #[allow(clippy::too_many_arguments)]
     pub fn create_subscription(
         conn: &PgConnection,
         user_id: i32,
@@ -34,10 +56,10 @@ impl Subscription {
         link: &str,
         image: &str,
         category_id: i32,
-        lists: i32,
-        members: i16,
-        description: &str,
-    ) -> Result<Subscription, diesel::result::Error> {
+        lists: i16,
+        members: i32,
+        description: Option<&str>,
+    ) -> Result<Subscription, Error> {
         let new_subscription = NewSubscription {
             user_id,
             object_id,
@@ -68,7 +90,7 @@ impl Subscription {
     pub fn get_subscription_by_id(
         conn: &PgConnection,
         subscription_id: i32,
-    ) -> Result<Option<Subscription>, diesel::result::Error> {
+    ) -> Result<Option<Subscription>, Error> {
         let subscription = subscriptions::table
             .filter(subscriptions::id.eq(subscription_id))
             .first(conn)
@@ -87,7 +109,7 @@ impl Subscription {
         conn: &PgConnection,
         subscription_id: i32,
         new_status: &str,
-    ) -> Result<usize, diesel::result::Error> {
+    ) -> Result<usize, Error> {
         let updated_rows = diesel::update(subscriptions::table)
             .filter(subscriptions::id.eq(subscription_id))
             .set(subscriptions::status.eq(new_status))
@@ -101,16 +123,18 @@ impl Subscription {
 //Удаление подписки (Delete)
 
 impl Subscription {
-    pub fn delete(&self, connection: &PgConnection) -> Result<usize, diesel::result::Error> {
+    pub fn delete(&self, connection: &PgConnection) -> Result<usize, Error> {
         diesel::delete(subscriptions::table.find(self.id)).execute(connection)
+            .map_err(Error::from)
     }
 }
 
 //Получение всех подписок пользователя
 
 impl Subscription {
-    pub fn get_subscriptions_by_user(user_id: i32, connection: &PgConnection) -> Result<Vec<Subscription>, diesel::result::Error> {
+    pub fn get_subscriptions_by_user(user_id: i32, connection: &PgConnection) -> Result<Vec<Subscription>, Error> {
         subscriptions::table.filter(subscriptions::user_id.eq(user_id)).load(connection)
+            .map_err(Error::from)
     }
 }
 
@@ -118,20 +142,22 @@ impl Subscription {
 //Поиск подписок по имени или описанию
 
 impl Subscription {
-    pub fn search_subscriptions(keyword: &str, connection: &PgConnection) -> Result<Vec<Subscription>, diesel::result::Error> {
+    pub fn search_subscriptions(keyword: &str, connection: &PgConnection) -> Result<Vec<Subscription>, Error> {
         subscriptions::table.filter(
             subscriptions::name.ilike(format!("%{}%", keyword)).or(
                 subscriptions::description.ilike(format!("%{}%", keyword)),
             ),
         ).load(connection)
+            .map_err(Error::from)
     }
 }
 
 //Фильтрация подписок по статусу
 
 impl Subscription {
-    pub fn filter_subscriptions_by_status(status: &str, connection: &PgConnection) -> Result<Vec<Subscription>, diesel::result::Error> {
+    pub fn filter_subscriptions_by_status(status: &str, connection: &PgConnection) -> Result<Vec<Subscription>, Error> {
         subscriptions::table.filter(subscriptions::status.eq(status)).load(connection)
+            .map_err(Error::from)
     }
 }
 
@@ -139,19 +165,21 @@ impl Subscription {
 //Подсчет количества подписок определенного типа
 
 impl Subscription {
-    pub fn count_subscriptions_by_type(object_type: &str, connection: &PgConnection) -> Result<i64, diesel::result::Error> {
+    pub fn count_subscriptions_by_type(object_type: &str, connection: &PgConnection) -> Result<i64, Error> {
         subscriptions::table.filter(subscriptions::object_type.eq(object_type)).count().get_result(connection)
+            .map_err(Error::from)
     }
 }
 
 //Подсчет активных подписок пользователя
 
 impl Subscription {
-    pub fn count_active_subscriptions_by_user(user_id: i32, connection: &PgConnection) -> Result<i64, diesel::result::Error> {
+    pub fn count_active_subscriptions_by_user(user_id: i32, connection: &PgConnection) -> Result<i64, Error> {
         subscriptions::table.filter(
             subscriptions::user_id.eq(user_id)
             .and(subscriptions::status.eq("active")) // Подставь нужный статус
         ).count().get_result(connection)
+            .map_err(Error::from)
     }
 }
 
@@ -159,12 +187,13 @@ impl Subscription {
 //Получение популярных объектов с наибольшим количеством подписчиков
 
 impl Subscription {
-    pub fn get_popular_objects(limit: i64, connection: &PgConnection) -> Result<Vec<(String, i64)>, diesel::result::Error> {
+    pub fn get_popular_objects(limit: i64, connection: &PgConnection) -> Result<Vec<(String, i64)>, Error> {
         subscriptions::table
-            .select((subscriptions::name, diesel::dsl::count_star()))
+            .select((subscriptions::name, sql::<BigInt>("COUNT(*)")))
             .group_by(subscriptions::name)
-            .order(diesel::dsl::count_star().desc())
+            .order(sql::<BigInt>("COUNT(*)").desc())
             .limit(limit)
             .load(connection)
+            .map_err(Error::from)
     }
 }
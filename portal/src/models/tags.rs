@@ -1,6 +1,7 @@
 use diesel::{self, prelude::*};
 use chrono::NaiveDateTime;
 use crate::schema::{tags, tag_object}; // Подключение схемы базы данных
+use crate::errors::Error; // Общий тип ошибки приложения
 
 
 //--------------------------------------------------------------------------------------------------------------------
@@ -32,39 +33,44 @@ pub struct UpdateTag {
 
 impl Tag {
     // Метод для создания нового тега
-    pub fn create(new_tag: NewTag, connection: &PgConnection) -> Result<Tag, diesel::result::Error> {
+    pub fn create(new_tag: NewTag, connection: &PgConnection) -> Result<Tag, Error> {
         diesel::insert_into(tags::table)
             .values(&new_tag)
             .get_result(connection)
+            .map_err(Error::from)
     }
 
     // Метод для поиска тега по ID
-    pub fn find_by_id(tag_id: i32, connection: &PgConnection) -> Result<Tag, diesel::result::Error> {
+    pub fn find_by_id(tag_id: i32, connection: &PgConnection) -> Result<Tag, Error> {
         tags::table.find(tag_id).get_result(connection)
+            .map_err(Error::from)
     }
 
     // Метод для обновления существующего тега по ID
-    pub fn update(tag_id: i32, updated_tag: UpdateTag, connection: &PgConnection) -> Result<Tag, diesel::result::Error> {
+    pub fn update(tag_id: i32, updated_tag: UpdateTag, connection: &PgConnection) -> Result<Tag, Error> {
         diesel::update(tags::table.find(tag_id))
             .set(&updated_tag)
             .get_result(connection)
+            .map_err(Error::from)
     }
 
     // Метод для удаления тега по ID
-    pub fn delete(tag_id: i32, connection: &PgConnection) -> Result<(), diesel::result::Error> {
+    pub fn delete(tag_id: i32, connection: &PgConnection) -> Result<(), Error> {
         diesel::delete(tags::table.find(tag_id)).execute(connection)?;
         Ok(())
     }
 
     // Метод для получения всех тегов
-    pub fn all(connection: &PgConnection) -> Result<Vec<Tag>, diesel::result::Error> {
+    pub fn all(connection: &PgConnection) -> Result<Vec<Tag>, Error> {
         tags::table.load::<Tag>(connection)
+            .map_err(Error::from)
     }
 }
 
 //--------------------------------------------------------------------------------------------------------------------
 
 #[derive(Debug, Queryable, Identifiable)]
+#[table_name = "tag_object"]
 pub struct TagObject {
     pub id: i32,
     pub tag_id: i32,
@@ -94,26 +100,29 @@ pub struct UpdateTagObject {
 
 impl TagObject {
     // Метод для создания новой связи объекта с тегом
-    pub fn create(new_tag_object: NewTagObject, connection: &PgConnection) -> Result<TagObject, diesel::result::Error> {
+    pub fn create(new_tag_object: NewTagObject, connection: &PgConnection) -> Result<TagObject, Error> {
         diesel::insert_into(tag_object::table)
             .values(&new_tag_object)
             .get_result(connection)
+            .map_err(Error::from)
     }
 
     // Метод для поиска связи объекта с тегом по ID
-    pub fn find_by_id(tag_object_id: i32, connection: &PgConnection) -> Result<TagObject, diesel::result::Error> {
+    pub fn find_by_id(tag_object_id: i32, connection: &PgConnection) -> Result<TagObject, Error> {
         tag_object::table.find(tag_object_id).get_result(connection)
+            .map_err(Error::from)
     }
 
     // Метод для обновления существующей связи объекта с тегом по ID
-    pub fn update(tag_object_id: i32, updated_tag_object: UpdateTagObject, connection: &PgConnection) -> Result<TagObject, diesel::result::Error> {
+    pub fn update(tag_object_id: i32, updated_tag_object: UpdateTagObject, connection: &PgConnection) -> Result<TagObject, Error> {
         diesel::update(tag_object::table.find(tag_object_id))
             .set(&updated_tag_object)
             .get_result(connection)
+            .map_err(Error::from)
     }
 
     // Метод для удаления связи объекта с тегом по ID
-    pub fn delete(tag_object_id: i32, connection: &PgConnection) -> Result<(), diesel::result::Error> {
+    pub fn delete(tag_object_id: i32, connection: &PgConnection) -> Result<(), Error> {
         diesel::delete(tag_object::table.find(tag_object_id)).execute(connection)?;
         Ok(())
     }
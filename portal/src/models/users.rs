@@ -0,0 +1,32 @@
+use diesel::prelude::*;
+use serde::Serialize;
+use crate::schema::users; // Подключение схемы базы данных
+use crate::errors::Error; // Общий тип ошибки приложения
+
+
+//--------------------------------------------------------------------------------------------------------------------
+// Модель для таблицы "users". Пользователи создаются вне портала,
+// поэтому здесь только чтение (для связей в files, лайков и загрузок).
+#[derive(Debug, Clone, Serialize, Queryable, Identifiable)]
+pub struct User {
+    pub id: i32,
+    pub first_name: String,
+    pub last_name: String,
+    pub middle_name: String,
+    #[serde(skip)]
+    pub email: String,
+    #[serde(skip)]
+    pub phone_number: String,
+    pub description: String,
+    pub photo_link: String,
+}
+
+impl User {
+    // Метод для получения пользователя по идентификатору
+    pub fn get_user(connection: &PgConnection, user_id: i32) -> Result<User, Error> {
+        users::table
+            .find(user_id)
+            .first(connection)
+            .map_err(Error::from)
+    }
+}
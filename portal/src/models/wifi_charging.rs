@@ -0,0 +1,544 @@
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::{Duration, NaiveDateTime};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::Error;
+use crate::models::opening_hours::OpeningHours;
+use crate::models::WifiChargingPoint;
+use crate::schema::{charging_connectors, streets, wifi_charging, wifi_charging_reports};
+
+
+// Подробности о точках Wi-Fi и зарядках и отметки пользователей о состоянии.
+// Публичное состояние точки не хранится, а считается по свежим отметкам:
+// вес отметки убывает вдвое за период полураспада, у каждого состояния свой
+// ("занято" устаревает быстрее, чем "сломано"). От одного пользователя
+// учитывается только последняя отметка.
+
+pub const CONNECTOR_TYPES: [&str; 8] = ["type1", "type2", "ccs1", "ccs2", "chademo", "gbt", "tesla", "schuko"];
+pub const PRICE_UNITS: [&str; 4] = ["kwh", "minute", "hour", "session"];
+
+pub const POINT_WORKING: &str = "working";
+pub const POINT_BROKEN: &str = "broken";
+pub const POINT_OCCUPIED: &str = "occupied";
+pub const POINT_UNKNOWN: &str = "unknown";
+
+// Отметки старше этого срока не учитываются совсем
+const STATUS_WINDOW_HOURS: i64 = 24;
+// Меньше этого суммарного веса у лучшего состояния - состояние неизвестно
+const MIN_STATUS_WEIGHT: f64 = 0.3;
+// Повторная отметка одного пользователя не раньше, чем через столько минут
+const REPORT_INTERVAL_MINUTES: i64 = 5;
+const MAX_REPORT_COMMENT_LENGTH: usize = 500;
+// Имя сети Wi-Fi - не больше 32 байт
+const MAX_SSID_BYTES: usize = 32;
+
+// Период полураспада веса отметки в минутах
+fn half_life_minutes(status: &str) -> f64 {
+    match status {
+        POINT_OCCUPIED => 30.0,
+        POINT_WORKING => 180.0,
+        _ => 720.0,
+    }
+}
+
+// Разъем зарядной станции
+#[derive(Debug, Clone, Serialize, Queryable)]
+pub struct ChargingConnector {
+    pub id: i32,
+    pub point_id: i32,
+    pub connector_type: String,
+    pub power_kw: BigDecimal,
+    pub quantity: i16,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectorForm {
+    pub connector_type: String,
+    pub power_kw: BigDecimal,
+    #[serde(default = "default_quantity")]
+    pub quantity: i16,
+}
+
+fn default_quantity() -> i16 {
+    1
+}
+
+impl ConnectorForm {
+    fn validate(&self) -> Result<(), Error> {
+        if !CONNECTOR_TYPES.contains(&self.connector_type.as_str()) {
+            return Err(Error::Validation(format!("unknown connector type: {}", self.connector_type)));
+        }
+        if self.power_kw <= BigDecimal::from(0) {
+            return Err(Error::Validation("connector power must be positive".to_string()));
+        }
+        if self.quantity < 1 {
+            return Err(Error::Validation("connector quantity must be positive".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "charging_connectors"]
+struct NewChargingConnector<'a> {
+    point_id: i32,
+    connector_type: &'a str,
+    power_kw: &'a BigDecimal,
+    quantity: i16,
+}
+
+// Разъемы точки заменяются целиком
+pub fn set_connectors(conn: &PgConnection, point_id: i32, connectors: &[ConnectorForm]) -> Result<(), Error> {
+    for connector in connectors {
+        connector.validate()?;
+    }
+    diesel::delete(charging_connectors::table.filter(charging_connectors::point_id.eq(point_id))).execute(conn)?;
+    let rows: Vec<NewChargingConnector> = connectors
+        .iter()
+        .map(|c| NewChargingConnector {
+            point_id:       point_id,
+            connector_type: &c.connector_type,
+            power_kw:       &c.power_kw,
+            quantity:       c.quantity,
+        })
+        .collect();
+    diesel::insert_into(charging_connectors::table).values(&rows).execute(conn)?;
+    Ok(())
+}
+
+pub fn get_connectors(conn: &PgConnection, point_ids: &[i32]) -> Result<Vec<ChargingConnector>, Error> {
+    charging_connectors::table
+        .filter(charging_connectors::point_id.eq_any(point_ids))
+        .order((charging_connectors::point_id, charging_connectors::power_kw.desc(), charging_connectors::id))
+        .load(conn)
+        .map_err(Error::from)
+}
+
+// Данные точки, которые правят администраторы
+#[derive(Debug, Clone, Deserialize)]
+pub struct WifiChargingForm {
+    pub services: Option<String>,
+    #[serde(default)]
+    pub has_wifi: bool,
+    pub wifi_ssid: Option<String>,
+    #[serde(default)]
+    pub wifi_password_required: bool,
+    #[serde(default)]
+    pub has_charging: bool,
+    #[serde(default)]
+    pub connectors: Vec<ConnectorForm>,
+    #[serde(default = "default_is_free")]
+    pub is_free: bool,
+    pub price: Option<BigDecimal>,
+    pub price_unit: Option<String>,
+    pub currency: Option<String>,
+    pub access_hours: Option<OpeningHours>,
+}
+
+fn default_is_free() -> bool {
+    true
+}
+
+impl WifiChargingForm {
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.wifi_ssid.as_ref().is_some_and(|ssid| ssid.len() > MAX_SSID_BYTES) {
+            return Err(Error::Validation(format!("SSID is longer than {} bytes", MAX_SSID_BYTES)));
+        }
+        if !self.has_wifi && (self.wifi_ssid.is_some() || self.wifi_password_required) {
+            return Err(Error::Validation("Wi-Fi details given for a point without Wi-Fi".to_string()));
+        }
+        if !self.has_charging && !self.connectors.is_empty() {
+            return Err(Error::Validation("connectors given for a point without charging".to_string()));
+        }
+        for connector in &self.connectors {
+            connector.validate()?;
+        }
+        if self.is_free && self.price.is_some() {
+            return Err(Error::Validation("free point can not have a price".to_string()));
+        }
+        if let Some(ref price) = self.price {
+            if *price < BigDecimal::from(0) {
+                return Err(Error::Validation("price must not be negative".to_string()));
+            }
+            if self.price_unit.is_none() {
+                return Err(Error::Validation("price_unit is required with price".to_string()));
+            }
+        }
+        if let Some(ref unit) = self.price_unit {
+            if !PRICE_UNITS.contains(&unit.as_str()) {
+                return Err(Error::Validation(format!("unknown price unit: {}", unit)));
+            }
+        }
+        if !self.currency().chars().all(|c| c.is_ascii_uppercase()) || self.currency().len() != 3 {
+            return Err(Error::Validation("currency must be a three-letter code".to_string()));
+        }
+        if let Some(ref hours) = self.access_hours {
+            hours.validate()?;
+        }
+        Ok(())
+    }
+
+    fn currency(&self) -> &str {
+        self.currency.as_deref().unwrap_or("RUB")
+    }
+}
+
+#[derive(Debug, AsChangeset)]
+#[table_name = "wifi_charging"]
+#[changeset_options(treat_none_as_null = "true")]
+struct WifiChargingDetailsChanges {
+    services: Option<String>,
+    has_wifi: bool,
+    wifi_ssid: Option<String>,
+    wifi_password_required: bool,
+    has_charging: bool,
+    is_free: bool,
+    price: Option<BigDecimal>,
+    price_unit: Option<String>,
+    currency: String,
+    access_hours: Option<OpeningHours>,
+}
+
+// Отметка пользователя о состоянии точки
+#[derive(Debug, Clone, Serialize, Queryable)]
+pub struct StatusReport {
+    pub id: i32,
+    pub point_id: i32,
+    pub user_id: i32,
+    pub status: String,
+    pub comment: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "wifi_charging_reports"]
+struct NewStatusReport<'a> {
+    point_id: i32,
+    user_id: i32,
+    status: &'a str,
+    comment: Option<&'a str>,
+    created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StatusReportForm {
+    pub status: String,
+    pub comment: Option<String>,
+}
+
+impl StatusReportForm {
+    pub fn validate(&self) -> Result<(), Error> {
+        if ![POINT_WORKING, POINT_BROKEN, POINT_OCCUPIED].contains(&self.status.as_str()) {
+            return Err(Error::Validation(format!("unknown status: {}", self.status)));
+        }
+        if self.comment.as_ref().is_some_and(|c| c.chars().count() > MAX_REPORT_COMMENT_LENGTH) {
+            return Err(Error::Validation(format!("comment is longer than {} characters", MAX_REPORT_COMMENT_LENGTH)));
+        }
+        Ok(())
+    }
+
+    fn comment(&self) -> Option<&str> {
+        self.comment.as_deref().map(str::trim).filter(|c| !c.is_empty())
+    }
+}
+
+// Публичное состояние точки. confidence - доля веса победившего состояния
+// среди всех учтенных отметок.
+#[derive(Debug, Clone, Serialize)]
+pub struct PointStatus {
+    pub status: String,
+    pub confidence: f64,
+    pub reports: usize,
+    pub last_report_at: Option<NaiveDateTime>,
+}
+
+impl PointStatus {
+    // Состояние по отметкам одной точки на момент now
+    pub fn from_reports(reports: &[StatusReport], now: NaiveDateTime) -> PointStatus {
+        let since = now - Duration::hours(STATUS_WINDOW_HOURS);
+        let mut latest: Vec<&StatusReport> = Vec::new();
+        for report in reports.iter().filter(|r| r.created_at > since && r.created_at <= now) {
+            match latest.iter_mut().find(|r| r.user_id == report.user_id) {
+                Some(known) if known.created_at < report.created_at => *known = report,
+                Some(_) => {},
+                None => latest.push(report),
+            }
+        }
+        let mut weights = [(POINT_WORKING, 0.0), (POINT_BROKEN, 0.0), (POINT_OCCUPIED, 0.0)];
+        for report in &latest {
+            let age = (now - report.created_at).num_seconds() as f64 / 60.0;
+            let weight = 0.5_f64.powf(age / half_life_minutes(&report.status));
+            if let Some(entry) = weights.iter_mut().find(|(status, _)| *status == report.status) {
+                entry.1 += weight;
+            }
+        }
+        let total: f64 = weights.iter().map(|(_, weight)| weight).sum();
+        let (best, best_weight) = weights
+            .iter()
+            .cloned()
+            .fold((POINT_UNKNOWN, 0.0), |best, entry| if entry.1 > best.1 { entry } else { best });
+        let (status, confidence) = if best_weight < MIN_STATUS_WEIGHT {
+            (POINT_UNKNOWN, 0.0)
+        }
+        else {
+            (best, ((best_weight / total) * 100.0).round() / 100.0)
+        };
+        PointStatus {
+            status:         status.to_string(),
+            confidence:     confidence,
+            reports:        latest.len(),
+            last_report_at: latest.iter().map(|r| r.created_at).max(),
+        }
+    }
+}
+
+impl StatusReport {
+    // Новая отметка. Чаще раза в REPORT_INTERVAL_MINUTES от одного
+    // пользователя по одной точке - Conflict.
+    pub fn create(conn: &PgConnection, point_id: i32, user_id: i32, form: &StatusReportForm) -> Result<StatusReport, Error> {
+        form.validate()?;
+        let now = chrono::Local::now().naive_local();
+        conn.transaction(|| {
+            wifi_charging::table
+                .find(point_id)
+                .select(wifi_charging::id)
+                .for_update()
+                .first::<i32>(conn)?;
+            let recent = wifi_charging_reports::table
+                .filter(wifi_charging_reports::point_id.eq(point_id))
+                .filter(wifi_charging_reports::user_id.eq(user_id))
+                .filter(wifi_charging_reports::created_at.gt(now - Duration::minutes(REPORT_INTERVAL_MINUTES)))
+                .count()
+                .get_result::<i64>(conn)?;
+            if recent > 0 {
+                return Err(Error::Conflict("you have reported this point a moment ago".to_string()));
+            }
+            diesel::insert_into(wifi_charging_reports::table)
+                .values(&NewStatusReport {
+                    point_id:   point_id,
+                    user_id:    user_id,
+                    status:     &form.status,
+                    comment:    form.comment(),
+                    created_at: now,
+                })
+                .get_result(conn)
+                .map_err(Error::from)
+        })
+    }
+
+    // История отметок точки, новые первыми, и их общее количество
+    pub fn get_for_point(conn: &PgConnection, point_id: i32, offset: i64, limit: i64) -> Result<(Vec<StatusReport>, i64), Error> {
+        let total = wifi_charging_reports::table
+            .filter(wifi_charging_reports::point_id.eq(point_id))
+            .count()
+            .get_result::<i64>(conn)?;
+        let items = wifi_charging_reports::table
+            .filter(wifi_charging_reports::point_id.eq(point_id))
+            .order((wifi_charging_reports::created_at.desc(), wifi_charging_reports::id.desc()))
+            .offset(offset)
+            .limit(limit)
+            .load(conn)?;
+        Ok((items, total))
+    }
+
+    // Отметки, которые еще влияют на состояние точек
+    fn get_recent(conn: &PgConnection, point_ids: &[i32], now: NaiveDateTime) -> Result<Vec<StatusReport>, Error> {
+        wifi_charging_reports::table
+            .filter(wifi_charging_reports::point_id.eq_any(point_ids))
+            .filter(wifi_charging_reports::created_at.gt(now - Duration::hours(STATUS_WINDOW_HOURS)))
+            .load(conn)
+            .map_err(Error::from)
+    }
+}
+
+// Точка вместе с разъемами и текущим состоянием, в таком виде она отдается в API.
+// accessible_now - открыта ли точка по часам доступа, пусто - часы неизвестны.
+#[derive(Debug, Serialize)]
+pub struct WifiChargingDetail {
+    #[serde(flatten)]
+    pub point: WifiChargingPoint,
+    pub connectors: Vec<ChargingConnector>,
+    pub max_power_kw: Option<f64>,
+    pub accessible_now: Option<bool>,
+    pub live_status: PointStatus,
+}
+
+// Отбор точек в списке
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct WifiChargingFilter {
+    pub has_wifi: Option<bool>,
+    pub has_charging: Option<bool>,
+    pub connector: Option<String>,
+    pub min_power_kw: Option<f64>,
+    pub status: Option<String>,
+}
+
+impl WifiChargingFilter {
+    fn matches(&self, detail: &WifiChargingDetail) -> bool {
+        self.has_wifi.is_none_or(|v| detail.point.has_wifi == v)
+            && self.has_charging.is_none_or(|v| detail.point.has_charging == v)
+            && self.connector.as_ref().is_none_or(|t| {
+                detail.connectors.iter().any(|c| &c.connector_type == t
+                    && self.min_power_kw.is_none_or(|min| c.power_kw.to_f64().unwrap_or(0.0) >= min))
+            })
+            && self.min_power_kw.is_none_or(|min| detail.max_power_kw.is_some_and(|max| max >= min))
+            && self.status.as_ref().is_none_or(|s| &detail.live_status.status == s)
+    }
+}
+
+impl WifiChargingPoint {
+    pub fn into_detail(self, conn: &PgConnection, now: NaiveDateTime) -> Result<WifiChargingDetail, Error> {
+        let mut items = WifiChargingPoint::with_details(conn, vec![self], now)?;
+        Ok(items.remove(0))
+    }
+
+    // Разъемы и состояние для списка точек за два запроса
+    pub fn with_details(conn: &PgConnection, points: Vec<WifiChargingPoint>, now: NaiveDateTime) -> Result<Vec<WifiChargingDetail>, Error> {
+        let ids: Vec<i32> = points.iter().map(|p| p.id).collect();
+        let connectors = get_connectors(conn, &ids)?;
+        let reports = StatusReport::get_recent(conn, &ids, now)?;
+        Ok(points
+            .into_iter()
+            .map(|point| {
+                let connectors: Vec<ChargingConnector> = connectors
+                    .iter()
+                    .filter(|c| c.point_id == point.id)
+                    .cloned()
+                    .collect();
+                let point_reports: Vec<StatusReport> = reports
+                    .iter()
+                    .filter(|r| r.point_id == point.id)
+                    .cloned()
+                    .collect();
+                let max_power_kw = connectors
+                    .iter()
+                    .filter_map(|c| c.power_kw.to_f64())
+                    .fold(None, |max: Option<f64>, power| Some(max.map_or(power, |m| m.max(power))));
+                WifiChargingDetail {
+                    max_power_kw:   max_power_kw,
+                    accessible_now: point.access_hours.as_ref().map(|hours| hours.is_open_at(now)),
+                    live_status:    PointStatus::from_reports(&point_reports, now),
+                    connectors:     connectors,
+                    point:          point,
+                }
+            })
+            .collect())
+    }
+
+    // Точки района с подробностями, отобранные по filter
+    pub fn get_for_district(
+        conn: &PgConnection,
+        district_id: i32,
+        filter: &WifiChargingFilter,
+        now: NaiveDateTime,
+    ) -> Result<Vec<WifiChargingDetail>, Error> {
+        let points = wifi_charging::table
+            .filter(wifi_charging::street_id.eq_any(
+                streets::table.select(streets::id).filter(streets::district_id.eq(district_id)),
+            ))
+            .order(wifi_charging::id)
+            .load::<WifiChargingPoint>(conn)?;
+        Ok(WifiChargingPoint::with_details(conn, points, now)?
+            .into_iter()
+            .filter(|detail| filter.matches(detail))
+            .collect())
+    }
+
+    // Изменение подробностей точки вместе с разъемами
+    pub fn update_details(conn: &PgConnection, point_id: i32, form: &WifiChargingForm) -> Result<WifiChargingPoint, Error> {
+        form.validate()?;
+        conn.transaction(|| {
+            let changes = WifiChargingDetailsChanges {
+                services:               form.services.clone(),
+                has_wifi:               form.has_wifi,
+                wifi_ssid:              form.wifi_ssid.clone(),
+                wifi_password_required: form.wifi_password_required,
+                has_charging:           form.has_charging,
+                is_free:                form.is_free,
+                price:                  form.price.clone(),
+                price_unit:             form.price_unit.clone(),
+                currency:               form.currency().to_string(),
+                access_hours:           form.access_hours.clone(),
+            };
+            let point = diesel::update(wifi_charging::table.find(point_id))
+                .set(&changes)
+                .get_result::<WifiChargingPoint>(conn)?;
+            set_connectors(conn, point_id, &form.connectors)?;
+            Ok(point)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 18).unwrap().and_hms_opt(12, 0, 0).unwrap()
+    }
+
+    fn report(user_id: i32, status: &str, minutes_ago: i64) -> StatusReport {
+        StatusReport {
+            id:         user_id,
+            point_id:   1,
+            user_id:    user_id,
+            status:     status.to_string(),
+            comment:    None,
+            created_at: now() - Duration::minutes(minutes_ago),
+        }
+    }
+
+    #[test]
+    fn no_reports() {
+        let status = PointStatus::from_reports(&[], now());
+        assert_eq!(status.status, POINT_UNKNOWN);
+        assert_eq!(status.confidence, 0.0);
+        assert_eq!(status.reports, 0);
+        assert_eq!(status.last_report_at, None);
+    }
+
+    #[test]
+    fn single_fresh_report() {
+        let status = PointStatus::from_reports(&[report(1, POINT_WORKING, 0)], now());
+        assert_eq!(status.status, POINT_WORKING);
+        assert_eq!(status.confidence, 1.0);
+        assert_eq!(status.reports, 1);
+        assert_eq!(status.last_report_at, Some(now()));
+    }
+
+    #[test]
+    fn latest_report_per_user() {
+        let reports = [report(1, POINT_WORKING, 5), report(1, POINT_BROKEN, 60)];
+        let status = PointStatus::from_reports(&reports, now());
+        assert_eq!(status.status, POINT_WORKING);
+        assert_eq!(status.reports, 1);
+        assert_eq!(status.last_report_at, Some(now() - Duration::minutes(5)));
+    }
+
+    #[test]
+    fn old_and_future_reports_ignored() {
+        let reports = [report(1, POINT_BROKEN, 24 * 60 + 1), report(2, POINT_BROKEN, -10)];
+        let status = PointStatus::from_reports(&reports, now());
+        assert_eq!(status.status, POINT_UNKNOWN);
+        assert_eq!(status.reports, 0);
+    }
+
+    #[test]
+    fn occupied_expires_faster_than_broken() {
+        let status = PointStatus::from_reports(&[report(1, POINT_OCCUPIED, 180)], now());
+        assert_eq!(status.status, POINT_UNKNOWN);
+        let status = PointStatus::from_reports(&[report(1, POINT_BROKEN, 180)], now());
+        assert_eq!(status.status, POINT_BROKEN);
+    }
+
+    #[test]
+    fn majority_with_confidence() {
+        let reports = [report(1, POINT_WORKING, 0), report(2, POINT_WORKING, 0), report(3, POINT_BROKEN, 0)];
+        let status = PointStatus::from_reports(&reports, now());
+        assert_eq!(status.status, POINT_WORKING);
+        assert_eq!(status.confidence, 0.67);
+        assert_eq!(status.reports, 3);
+    }
+}
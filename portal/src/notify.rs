@@ -0,0 +1,385 @@
+use actix::Addr;
+use diesel::PgConnection;
+use lettre::{
+    message::Mailbox,
+    transport::smtp::authentication::Credentials,
+    Message,
+    SmtpTransport,
+    Transport,
+};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::errors::Error;
+use crate::models::{
+    get_user_emails,
+    Event,
+    EventChange,
+    NewNotification,
+    NewQueuedEmail,
+    Notification,
+    Organization,
+    OrganizationClaim,
+    QueuedEmail,
+    CLAIM_APPROVED,
+    NOTIFIABLE_EVENT_FIELDS,
+};
+use crate::utils::Config;
+use crate::websocket::{MessageToClient, MessageToUser, Server};
+
+
+// Уведомление пользователям. Способы доставки (почта, кабинет, websocket)
+// подключаются к Notifier и не знают, откуда пришло уведомление.
+#[derive(Debug, Clone)]
+pub struct Notice {
+    pub kind:     String,      // Тип уведомления, например "event_reminder"
+    pub event_id: Option<i32>, // Событие, к которому относится
+    pub title:    String,
+    pub body:     String,
+}
+
+// Получатель уведомления
+#[derive(Debug, Clone)]
+pub struct Recipient {
+    pub user_id: i32,
+    pub email:   String,
+}
+
+// Способ доставки уведомлений
+pub trait NotificationChannel: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn send(&self, conn: &PgConnection, recipient: &Recipient, notice: &Notice) -> Result<(), Error>;
+
+    // Ok от send означает, что уведомление доставлено. Каналы, которые
+    // не знают, дошло ли сообщение, не считаются доставкой в Notifier::send.
+    fn confirms_delivery(&self) -> bool {
+        true
+    }
+}
+
+// Уведомление в личном кабинете (таблица notifications)
+pub struct SiteChannel;
+
+impl NotificationChannel for SiteChannel {
+    fn name(&self) -> &'static str {
+        "site"
+    }
+
+    fn send(&self, conn: &PgConnection, recipient: &Recipient, notice: &Notice) -> Result<(), Error> {
+        Notification::create(conn, NewNotification {
+            user_id:  recipient.user_id,
+            kind:     &notice.kind,
+            event_id: notice.event_id,
+            title:    &notice.title,
+            body:     &notice.body,
+        })?;
+        Ok(())
+    }
+}
+
+// Сообщение в открытые вкладки пользователя через websocket::Server.
+// Если пользователя нет на сайте, сообщение просто никуда не уйдет.
+// Ошибка - только если сервер websocket остановлен или перегружен.
+// Поэтому Ok здесь не подтверждает доставку, и напоминание без других
+// каналов будет отправлено повторно (например, по почте).
+pub struct WebsocketChannel {
+    server: Addr<Server>,
+}
+
+impl WebsocketChannel {
+    pub fn new(server: Addr<Server>) -> WebsocketChannel {
+        WebsocketChannel { server: server }
+    }
+}
+
+impl NotificationChannel for WebsocketChannel {
+    fn name(&self) -> &'static str {
+        "websocket"
+    }
+
+    fn confirms_delivery(&self) -> bool {
+        false
+    }
+
+    fn send(&self, _conn: &PgConnection, recipient: &Recipient, notice: &Notice) -> Result<(), Error> {
+        let data = json!({
+            "kind":  notice.kind,
+            "title": notice.title,
+            "body":  notice.body,
+        });
+        self.server
+            .try_send(MessageToUser {
+                user_id: recipient.user_id,
+                msg:     MessageToClient::new("notification", notice.event_id.unwrap_or(0), data),
+            })
+            .map_err(|e| {
+                warn!("Websocket сервер не принял уведомление: {}", e);
+                Error::Unavailable
+            })
+    }
+}
+
+// Письмо через SMTP. Канал только ставит письмо в очередь (email_queue),
+// отправляет его фоновая задача mailer через deliver, уже без соединения
+// с базой и после фиксации транзакции, в которой письмо поставлено.
+#[derive(Clone)]
+pub struct EmailChannel {
+    mailer: SmtpTransport,
+    from:   Mailbox,
+}
+
+impl EmailChannel {
+    // Канал из настроек. None, если SMTP не настроен.
+    pub fn from_config(config: &Config) -> Option<EmailChannel> {
+        let host = config.smtp_host.as_ref()?;
+        let mut builder = match SmtpTransport::relay(host) {
+            Ok(builder) => builder,
+            Err(e) => {
+                error!("SMTP {} недоступен: {}", host, e);
+                return None;
+            },
+        };
+        if let (Some(user), Some(password)) = (&config.smtp_user, &config.smtp_password) {
+            builder = builder.credentials(Credentials::new(user.clone(), password.clone()));
+        }
+        let from = match config.mail_from.parse() {
+            Ok(from) => from,
+            Err(e) => {
+                error!("Неверный MAIL_FROM {}: {}", config.mail_from, e);
+                return None;
+            },
+        };
+        Some(EmailChannel {
+            mailer: builder.build(),
+            from:   from,
+        })
+    }
+
+    // Письмо в очередь на любой адрес, не обязательно пользователя сайта
+    pub fn send_to(&self, conn: &PgConnection, user_id: Option<i32>, email: &str, notice: &Notice) -> Result<(), Error> {
+        email
+            .parse::<Mailbox>()
+            .map_err(|_| Error::Validation(format!("invalid email: {}", email)))?;
+        QueuedEmail::enqueue(conn, NewQueuedEmail {
+            user_id: user_id,
+            email:   email,
+            kind:    &notice.kind,
+            subject: &notice.title,
+            body:    &notice.body,
+        })?;
+        Ok(())
+    }
+
+    // Отправка письма из очереди по SMTP. Блокирует поток до ответа сервера.
+    pub fn deliver(&self, email: &QueuedEmail) -> Result<(), String> {
+        let to: Mailbox = email.email.parse().map_err(|e| format!("invalid email: {}", e))?;
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(email.subject.as_str())
+            .body(email.body.clone())
+            .map_err(|e| e.to_string())?;
+        self.mailer.send(&message).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
+
+impl NotificationChannel for EmailChannel {
+    fn name(&self) -> &'static str {
+        "email"
+    }
+
+    fn send(&self, conn: &PgConnection, recipient: &Recipient, notice: &Notice) -> Result<(), Error> {
+        self.send_to(conn, Some(recipient.user_id), &recipient.email, notice)
+    }
+}
+
+// Рассылка уведомлений по всем подключенным каналам.
+// Один Notifier на приложение, в обработчики передается через Data<Arc<Notifier>>.
+pub struct Notifier {
+    channels: Vec<Box<dyn NotificationChannel>>,
+    email:    Option<EmailChannel>,   // Для писем на адреса, не привязанные к пользователям
+}
+
+impl Notifier {
+    pub fn new() -> Notifier {
+        Notifier {
+            channels: Vec::new(),
+            email:    None,
+        }
+    }
+
+    pub fn with_channel<C: NotificationChannel + 'static>(mut self, channel: C) -> Notifier {
+        self.channels.push(Box::new(channel));
+        self
+    }
+
+    // Все каналы из настроек: кабинет и websocket всегда, почта - если настроен SMTP
+    pub fn from_config(config: &Config, server: Addr<Server>) -> Arc<Notifier> {
+        let mut notifier = Notifier::new()
+            .with_channel(SiteChannel)
+            .with_channel(WebsocketChannel::new(server));
+        if let Some(email) = EmailChannel::from_config(config) {
+            notifier.email = Some(email.clone());
+            notifier = notifier.with_channel(email);
+        }
+        Arc::new(notifier)
+    }
+
+    // Письмо на произвольный адрес, например почту организации.
+    // Unavailable, если SMTP не настроен.
+    pub fn send_email(&self, conn: &PgConnection, email: &str, notice: &Notice) -> Result<(), Error> {
+        match self.email {
+            Some(ref channel) => channel.send_to(conn, None, email, notice),
+            None => Err(Error::Unavailable),
+        }
+    }
+
+    // Канал почты для отправки очереди. None, если SMTP не настроен.
+    pub fn email(&self) -> Option<&EmailChannel> {
+        self.email.as_ref()
+    }
+
+    // Получатели с адресами почты
+    pub fn recipients(&self, conn: &PgConnection, user_ids: &[i32]) -> Result<Vec<Recipient>, Error> {
+        Ok(get_user_emails(conn, user_ids)?
+            .into_iter()
+            .map(|(user_id, email)| Recipient { user_id: user_id, email: email })
+            .collect())
+    }
+
+    // Отправка одному получателю. Ошибка одного канала не мешает остальным;
+    // true, если уведомление дошло хотя бы одним способом, подтверждающим доставку.
+    pub fn send(&self, conn: &PgConnection, recipient: &Recipient, notice: &Notice) -> bool {
+        let mut delivered = false;
+        for channel in &self.channels {
+            match channel.send(conn, recipient, notice) {
+                Ok(()) => delivered |= channel.confirms_delivery(),
+                Err(e) => warn!(
+                    "Уведомление {} пользователю {} не отправлено через {}: {}",
+                    notice.kind, recipient.user_id, channel.name(), e
+                ),
+            }
+        }
+        delivered
+    }
+
+    // Отправка нескольким пользователям. Возвращает, скольким дошло.
+    pub fn notify(&self, conn: &PgConnection, user_ids: &[i32], notice: &Notice) -> Result<usize, Error> {
+        let recipients = self.recipients(conn, user_ids)?;
+        Ok(recipients
+            .iter()
+            .filter(|recipient| self.send(conn, recipient, notice))
+            .count())
+    }
+}
+
+// Название поля события для текста уведомления
+fn event_field_label(field: &str) -> &str {
+    match field {
+        "title"        => "Название",
+        "date_start"   => "Начало",
+        "date_end"     => "Окончание",
+        "location"     => "Место",
+        "coordinates"  => "Точка на карте",
+        "ticket_price" => "Стоимость билета",
+        "currency"     => "Валюта",
+        other          => other,
+    }
+}
+
+// Значение поля для текста уведомления: даты в привычном виде, пустое - прочерком
+fn event_field_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => "—".to_string(),
+        serde_json::Value::String(text) => {
+            match chrono::NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S") {
+                Ok(datetime) => datetime.format("%d.%m.%Y %H:%M").to_string(),
+                Err(_) => text.clone(),
+            }
+        },
+        other => other.to_string(),
+    }
+}
+
+// Уведомление об изменении события. None, если среди изменений нет
+// важных для посетителей (время, место, цена, название).
+pub fn event_changed_notice(event: &Event, changes: &[EventChange]) -> Option<Notice> {
+    let important: Vec<&EventChange> = changes
+        .iter()
+        .filter(|change| NOTIFIABLE_EVENT_FIELDS.contains(&change.field))
+        .collect();
+    if important.is_empty() {
+        return None;
+    }
+    let mut body = format!("В событии «{}» изменилось:", event.title);
+    for change in important {
+        body.push_str(&format!(
+            "\n{}: {} → {}",
+            event_field_label(change.field),
+            event_field_value(&change.old),
+            event_field_value(&change.new),
+        ));
+    }
+    Some(Notice {
+        kind:     "event_changed".to_string(),
+        event_id: Some(event.id),
+        title:    format!("Изменения в событии: {}", event.title),
+        body:     body,
+    })
+}
+
+// Уведомление об отмене события
+pub fn event_cancelled_notice(event: &Event) -> Notice {
+    let mut body = format!(
+        "Событие «{}» ({}) отменено.",
+        event.title,
+        event.date_start.format("%d.%m.%Y %H:%M"),
+    );
+    if let Some(ref reason) = event.cancel_reason {
+        body.push_str(&format!("\nПричина: {}", reason));
+    }
+    Notice {
+        kind:     "event_cancelled".to_string(),
+        event_id: Some(event.id),
+        title:    format!("Событие отменено: {}", event.title),
+        body:     body,
+    }
+}
+
+// Письмо с кодом подтверждения на почту организации
+pub fn claim_code_notice(organization: &Organization, claim: &OrganizationClaim) -> Notice {
+    Notice {
+        kind:     "organization_claim_code".to_string(),
+        event_id: None,
+        title:    format!("Код подтверждения для «{}»", organization.name),
+        body:     format!(
+            "Кто-то заявил права на организацию «{}» на городском портале.\n\
+             Код подтверждения: {}\n\
+             Если это не вы, просто проигнорируйте письмо.",
+            organization.name,
+            claim.email_code.as_deref().unwrap_or(""),
+        ),
+    }
+}
+
+// Уведомление заявителю о решении по заявке на организацию
+pub fn claim_reviewed_notice(organization: &Organization, claim: &OrganizationClaim) -> Notice {
+    let mut body = match claim.status.as_str() {
+        CLAIM_APPROVED => format!(
+            "Вы подтверждены как владелец организации «{}». Теперь вы можете менять её профиль, \
+             отвечать на отзывы и размещать объявления от её имени.",
+            organization.name,
+        ),
+        _ => format!("Заявка на права владельца организации «{}» отклонена.", organization.name),
+    };
+    if let Some(ref comment) = claim.comment {
+        body.push_str(&format!("\nКомментарий: {}", comment));
+    }
+    Notice {
+        kind:     "organization_claim".to_string(),
+        event_id: None,
+        title:    format!("Заявка на организацию «{}»", organization.name),
+        body:     body,
+    }
+}
@@ -0,0 +1,109 @@
+use actix_web::web;
+use chrono::{Duration, NaiveDateTime};
+use diesel::PgConnection;
+use std::sync::Arc;
+
+use crate::errors::Error;
+use crate::models::{get_event_recipients, get_event_starts_between, Event, EventReminder};
+use crate::notify::{Notice, Notifier};
+use crate::utils::{db, Config, DbPool};
+
+
+// Планировщик напоминаний о событиях. Раз в reminder_interval секунд ищет
+// проведения, которые начнутся в пределах окон напоминаний (по умолчанию
+// за сутки и за час), и напоминает посетителям и подписчикам.
+pub fn start_reminders(pool: web::Data<DbPool>, notifier: Arc<Notifier>, config: &Config) {
+    let mut windows = config.reminder_windows.clone();
+    windows.retain(|w| *w > 0);
+    windows.sort_unstable();
+    windows.dedup();
+    if windows.is_empty() {
+        info!("Напоминания о событиях отключены");
+        return;
+    }
+    let interval = std::time::Duration::from_secs(config.reminder_interval.max(1));
+
+    actix_rt::spawn(async move {
+        let mut timer = actix_rt::time::interval(interval);
+        loop {
+            timer.tick().await;
+            let notifier = notifier.clone();
+            let windows = windows.clone();
+            let result = db(&pool, move |_connection| {
+                let now = chrono::Local::now().naive_local();
+                send_due_reminders(_connection, &notifier, &windows, now)
+            }).await;
+            match result {
+                Ok(0) => (),
+                Ok(sent) => info!("Отправлено напоминаний о событиях: {}", sent),
+                Err(e) => warn!("Ошибка при отправке напоминаний: {}", e),
+            }
+        }
+    });
+}
+
+// Одна проверка. Для каждого проведения и пользователя берется наименьшее
+// окно, в которое попадает начало, так что пользователь, записавшийся за
+// 30 минут до начала, получит одно напоминание, а не два сразу.
+// windows - окна в минутах по возрастанию. Возвращает число отправленных.
+pub fn send_due_reminders(
+    conn: &PgConnection,
+    notifier: &Notifier,
+    windows: &[i64],
+    now: NaiveDateTime,
+) -> Result<usize, Error> {
+    let widest = match windows.last() {
+        Some(widest) => *widest,
+        None => return Ok(0),
+    };
+    let mut sent = 0;
+    for (event_id, starts_at) in get_event_starts_between(conn, now, now + Duration::minutes(widest))? {
+        let window = match windows.iter().find(|w| starts_at <= now + Duration::minutes(**w)) {
+            Some(window) => *window as i32,
+            None => continue,
+        };
+        let user_ids = get_event_recipients(conn, event_id)?;
+        if user_ids.is_empty() {
+            continue;
+        }
+        let event = Event::get_event_by_id(conn, event_id)?;
+        let notice = reminder_notice(&event, starts_at, now);
+        for recipient in notifier.recipients(conn, &user_ids)? {
+            if !EventReminder::claim(conn, event_id, recipient.user_id, starts_at, window)? {
+                continue;
+            }
+            if notifier.send(conn, &recipient, &notice) {
+                sent += 1;
+            }
+            else {
+                EventReminder::release(conn, event_id, recipient.user_id, starts_at, window)?;
+            }
+        }
+    }
+    Ok(sent)
+}
+
+fn reminder_notice(event: &Event, starts_at: NaiveDateTime, now: NaiveDateTime) -> Notice {
+    let left = starts_at - now;
+    let when = if left.num_hours() >= 1 {
+        format!("через {} ч.", left.num_hours())
+    }
+    else {
+        format!("через {} мин.", left.num_minutes().max(1))
+    };
+    let mut body = format!(
+        "Событие «{}» начнется {} ({}).",
+        event.title,
+        when,
+        starts_at.format("%d.%m.%Y %H:%M"),
+    );
+    if let Some(ref location) = event.location {
+        body.push_str(&format!("\nМесто: {}", location));
+    }
+    Notice {
+        kind:     "event_reminder".to_string(),
+        event_id: Some(event.id),
+        title:    format!("Напоминание: {}", event.title),
+        body:     body,
+    }
+}
@@ -0,0 +1,19 @@
+use actix_web::web;
+
+use crate::views::{
+    state_routes,
+    pages_routes,
+    test_routes,
+    api_routes,
+};
+use crate::websocket::websocket_routes;
+
+
+pub fn routes(config: &mut web::ServiceConfig) {
+    config.configure(state_routes);
+    config.configure(test_routes);
+    config.configure(api_routes);
+    config.configure(websocket_routes);
+    // в pages_routes задается страница 404 для всех остальных адресов
+    config.configure(pages_routes);
+}
@@ -0,0 +1,1285 @@
+table! {
+    administrators (user_id) {
+        user_id -> Int4,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use postgis_diesel::sql_types::*;
+
+    advertisements (id) {
+        id -> Int4,
+        user_id -> Int4,
+        community_id -> Nullable<Int4>,
+        title -> Varchar,
+        description -> Nullable<Text>,
+        category -> Nullable<Varchar>,
+        price -> Nullable<Numeric>,
+        currency -> Nullable<Varchar>,
+        location -> Nullable<Varchar>,
+        contact_name -> Nullable<Varchar>,
+        contact_phone -> Nullable<Varchar>,
+        contact_email -> Nullable<Varchar>,
+        images -> Array<Text>,
+        video -> Nullable<Varchar>,
+        is_active -> Bool,
+        created_at -> Timestamp,
+        coordinates -> Nullable<Geometry>,
+        organization_id -> Nullable<Int4>,
+        city_id -> Nullable<Int4>,
+    }
+}
+
+table! {
+    carrier_information (id) {
+        id -> Int4,
+        vehicle_id -> Int4,
+        carrier_name -> Varchar,
+        carrier_contact_information -> Text,
+    }
+}
+
+table! {
+    categories (id) {
+        id -> Int4,
+        name -> Varchar,
+        description -> Nullable<Text>,
+        image -> Nullable<Varchar>,
+        types -> Int2,
+        position -> Int2,
+    }
+}
+
+table! {
+    category_items (category_id, item_id) {
+        category_id -> Int4,
+        item_id -> Int4,
+    }
+}
+
+table! {
+    charging_connectors (id) {
+        id -> Int4,
+        point_id -> Int4,
+        connector_type -> Varchar,
+        power_kw -> Numeric,
+        quantity -> Int2,
+    }
+}
+
+table! {
+    cities (id) {
+        id -> Int4,
+        name -> Varchar,
+        population -> Nullable<Int4>,
+        area_sq_km -> Nullable<Numeric>,
+        mayor -> Nullable<Varchar>,
+        founding_date -> Nullable<Date>,
+        utc_offset -> Int4,
+    }
+}
+
+table! {
+    communities (id) {
+        id -> Int4,
+        name -> Varchar,
+        description -> Nullable<Text>,
+        user_id -> Int4,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    cookie_stats (id) {
+        id -> Int4,
+        user_id -> Int4,
+        page -> Int2,
+        link -> Varchar,
+        title -> Varchar,
+        height -> Float8,
+        seconds -> Int4,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    cookie_users (id) {
+        id -> Int4,
+        user_id -> Nullable<Int4>,
+        ip -> Varchar,
+        device -> Int2,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use postgis_diesel::sql_types::*;
+
+    districts (id) {
+        id -> Int4,
+        city_id -> Int4,
+        name -> Varchar,
+        population -> Int4,
+        area_sq_km -> Nullable<Numeric>,
+        boundary -> Nullable<Geometry>,
+    }
+}
+
+table! {
+    email_queue (id) {
+        id -> Int4,
+        user_id -> Nullable<Int4>,
+        email -> Varchar,
+        kind -> Varchar,
+        subject -> Text,
+        body -> Text,
+        created_at -> Timestamp,
+        attempts -> Int4,
+        next_attempt_at -> Timestamp,
+        sent_at -> Nullable<Timestamp>,
+        last_error -> Nullable<Text>,
+    }
+}
+
+table! {
+    event_attendees (id) {
+        id -> Int4,
+        event_id -> Int4,
+        user_id -> Int4,
+        created_at -> Timestamp,
+        tier_id -> Nullable<Int4>,
+        status -> Varchar,
+        check_in_token -> Nullable<Varchar>,
+        checked_in_at -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    event_calendar (id) {
+        id -> Int4,
+        event_id -> Int4,
+        date -> Date,
+        time_start -> Nullable<Time>,
+        time_end -> Nullable<Time>,
+    }
+}
+
+table! {
+    event_moderators (user_id) {
+        user_id -> Int4,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    event_recurrence_exceptions (id) {
+        id -> Int4,
+        recurrence_id -> Int4,
+        date -> Date,
+    }
+}
+
+table! {
+    event_recurrences (id) {
+        id -> Int4,
+        event_id -> Int4,
+        freq -> Varchar,
+        repeat_every -> Int2,
+        weekdays -> Array<Int2>,
+        month_week -> Nullable<Int2>,
+        month_weekday -> Nullable<Int2>,
+        date_start -> Date,
+        time_start -> Nullable<Time>,
+        time_end -> Nullable<Time>,
+        repeat_until -> Nullable<Date>,
+        repeat_count -> Nullable<Int4>,
+    }
+}
+
+table! {
+    event_reminders (id) {
+        id -> Int4,
+        event_id -> Int4,
+        user_id -> Int4,
+        starts_at -> Timestamp,
+        window_minutes -> Int4,
+        sent_at -> Timestamp,
+    }
+}
+
+table! {
+    event_subscribers (id) {
+        id -> Int4,
+        event_id -> Int4,
+        user_id -> Int4,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    event_themes (id) {
+        id -> Int4,
+        name -> Varchar,
+        parent_id -> Nullable<Int4>,
+    }
+}
+
+table! {
+    event_ticket_tiers (id) {
+        id -> Int4,
+        event_id -> Int4,
+        name -> Varchar,
+        price -> Float8,
+        currency -> Nullable<Varchar>,
+        capacity -> Int4,
+        sold -> Int4,
+        sale_start -> Nullable<Timestamp>,
+        sale_end -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use postgis_diesel::sql_types::*;
+
+    events (id) {
+        id -> Int4,
+        title -> Varchar,
+        description -> Text,
+        category -> Nullable<Varchar>,
+        date_start -> Timestamp,
+        date_end -> Timestamp,
+        location -> Nullable<Varchar>,
+        ticket_price -> Nullable<Float8>,
+        currency -> Nullable<Varchar>,
+        contact_name -> Nullable<Varchar>,
+        contact_phone -> Nullable<Varchar>,
+        contact_email -> Nullable<Varchar>,
+        image -> Nullable<Varchar>,
+        is_published -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        theme_id -> Nullable<Int4>,
+        user_id -> Nullable<Int4>,
+        cancelled_at -> Nullable<Timestamp>,
+        cancel_reason -> Nullable<Text>,
+        coordinates -> Nullable<Geometry>,
+        city_id -> Nullable<Int4>,
+    }
+}
+
+table! {
+    feedbacks (id) {
+        id -> Int4,
+        username -> Varchar,
+        email -> Varchar,
+        message -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    file_comments (id) {
+        id -> Int4,
+        file_id -> Int4,
+        user_id -> Int4,
+        comment_text -> Nullable<Text>,
+        is_deleted -> Bool,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    file_downloads (id) {
+        id -> Int4,
+        file_id -> Int4,
+        user_id -> Int4,
+        downloaded_at -> Timestamp,
+    }
+}
+
+table! {
+    file_likes (id) {
+        id -> Int4,
+        file_id -> Int4,
+        user_id -> Int4,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    file_report (id) {
+        id -> Int4,
+        file_id -> Int4,
+        user_id -> Int4,
+        reason -> Varchar,
+        description -> Nullable<Text>,
+        status -> Nullable<Varchar>,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    file_tags (id) {
+        id -> Int4,
+        file_id -> Int4,
+        tag_id -> Int4,
+    }
+}
+
+table! {
+    files (id) {
+        id -> Int4,
+        user_id -> Int4,
+        community_id -> Nullable<Int4>,
+        title -> Varchar,
+        description -> Nullable<Text>,
+        file_type -> Varchar,
+        file_url -> Nullable<Text>,
+        is_private -> Bool,
+        is_approved -> Nullable<Bool>,
+        is_deleted -> Bool,
+        uploaded_at -> Timestamp,
+    }
+}
+
+table! {
+    forum_likes (id) {
+        id -> Int4,
+        post_id -> Int4,
+        user_id -> Int4,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    forum_bans (id) {
+        id -> Int4,
+        forum_id -> Int4,
+        user_id -> Int4,
+        moderator_id -> Nullable<Int4>,
+        report_id -> Nullable<Int4>,
+        reason -> Text,
+        created_at -> Timestamp,
+        expires_at -> Timestamp,
+        lifted_at -> Nullable<Timestamp>,
+        lifted_by -> Nullable<Int4>,
+    }
+}
+
+table! {
+    forum_invites (id) {
+        id -> Int4,
+        forum_id -> Int4,
+        token -> Varchar,
+        created_by -> Nullable<Int4>,
+        created_at -> Timestamp,
+        expires_at -> Nullable<Timestamp>,
+        max_uses -> Nullable<Int4>,
+        uses -> Int4,
+        revoked_at -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    forum_join_requests (id) {
+        id -> Int4,
+        forum_id -> Int4,
+        user_id -> Int4,
+        message -> Nullable<Text>,
+        status -> Varchar,
+        created_at -> Timestamp,
+        decided_by -> Nullable<Int4>,
+        decided_at -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    forum_members (forum_id, user_id) {
+        forum_id -> Int4,
+        user_id -> Int4,
+        added_by -> Nullable<Int4>,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    forum_moderation_log (id) {
+        id -> Int4,
+        forum_id -> Int4,
+        moderator_id -> Nullable<Int4>,
+        action -> Varchar,
+        report_id -> Nullable<Int4>,
+        topic_id -> Nullable<Int4>,
+        post_id -> Nullable<Int4>,
+        target_user_id -> Nullable<Int4>,
+        details -> Nullable<Text>,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    forum_moderators (forum_id, user_id) {
+        forum_id -> Int4,
+        user_id -> Int4,
+        role -> Varchar,
+        granted_by -> Nullable<Int4>,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    forum_post_revisions (id) {
+        id -> Int4,
+        post_id -> Int4,
+        editor_id -> Nullable<Int4>,
+        content -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    forum_posts (id) {
+        id -> Int4,
+        topic_id -> Int4,
+        user_id -> Int4,
+        content -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        parent_id -> Nullable<Int4>,
+        quoted_post_id -> Nullable<Int4>,
+        quote_text -> Nullable<Text>,
+        edit_count -> Int4,
+        edited_at -> Nullable<Timestamp>,
+        deleted_at -> Nullable<Timestamp>,
+        deleted_by -> Nullable<Int4>,
+    }
+}
+
+table! {
+    forum_reports (id) {
+        id -> Int4,
+        post_id -> Int4,
+        user_id -> Int4,
+        reason -> Varchar,
+        description -> Text,
+        status -> Varchar,
+        created_at -> Timestamp,
+        resolved_by -> Nullable<Int4>,
+        resolved_at -> Nullable<Timestamp>,
+        resolution -> Nullable<Varchar>,
+    }
+}
+
+table! {
+    forum_subscriptions (id) {
+        id -> Int4,
+        user_id -> Int4,
+        topic_id -> Int4,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    forum_topics (id) {
+        id -> Int4,
+        forum_id -> Int4,
+        title -> Varchar,
+        description -> Nullable<Text>,
+        user_id -> Int4,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        is_locked -> Bool,
+        locked_at -> Nullable<Timestamp>,
+        locked_by -> Nullable<Int4>,
+    }
+}
+
+table! {
+    forum_warnings (id) {
+        id -> Int4,
+        forum_id -> Int4,
+        user_id -> Int4,
+        moderator_id -> Nullable<Int4>,
+        report_id -> Nullable<Int4>,
+        reason -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    forums (id) {
+        id -> Int4,
+        name -> Varchar,
+        description -> Nullable<Text>,
+        category -> Nullable<Varchar>,
+        is_private -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    goods (id) {
+        id -> Int4,
+        street_id -> Int4,
+        name -> Varchar,
+        description -> Nullable<Text>,
+        organization_id -> Nullable<Int4>,
+        category_id -> Nullable<Int4>,
+        price -> Nullable<Numeric>,
+        currency -> Varchar,
+        availability -> Varchar,
+        is_service -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    goods_categories (id) {
+        id -> Int4,
+        name -> Varchar,
+        parent_id -> Nullable<Int4>,
+    }
+}
+
+table! {
+    goods_photos (goods_id, file_id) {
+        goods_id -> Int4,
+        file_id -> Int4,
+        position -> Int2,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use postgis_diesel::sql_types::*;
+
+    house_ranges (id) {
+        id -> Int4,
+        street_id -> Int4,
+        from_number -> Int4,
+        to_number -> Int4,
+        parity -> Varchar,
+        geometry -> Geometry,
+    }
+}
+
+table! {
+    item_files (item_id, file_id) {
+        item_id -> Int4,
+        file_id -> Int4,
+        position -> Int2,
+    }
+}
+
+table! {
+    items (id) {
+        id -> Int4,
+        user_id -> Int4,
+        title -> Varchar,
+        description -> Nullable<Text>,
+        content -> Nullable<Text>,
+        image -> Nullable<Varchar>,
+        types -> Int2,
+        is_active -> Bool,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    layers (id) {
+        id -> Int4,
+        map_id -> Int4,
+        title -> Varchar,
+        description -> Text,
+        layer_type -> Varchar,
+        user_id -> Int4,
+        created_at -> Timestamp,
+        visibility -> Bool,
+        opacity -> Float8,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use postgis_diesel::sql_types::*;
+
+    maps (id) {
+        id -> Int4,
+        title -> Varchar,
+        description -> Text,
+        user_id -> Int4,
+        created_at -> Timestamp,
+        location -> Geometry,
+        public -> Bool,
+        likes -> Int4,
+        views -> Int4,
+    }
+}
+
+table! {
+    notifications (id) {
+        id -> Int4,
+        user_id -> Int4,
+        kind -> Varchar,
+        event_id -> Nullable<Int4>,
+        title -> Varchar,
+        body -> Text,
+        is_read -> Bool,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    organization_claims (id) {
+        id -> Int4,
+        organization_id -> Int4,
+        user_id -> Int4,
+        method -> Varchar,
+        file_id -> Nullable<Int4>,
+        email -> Nullable<Varchar>,
+        email_code -> Nullable<Varchar>,
+        code_sent_at -> Nullable<Timestamp>,
+        code_attempts -> Int4,
+        email_verified_at -> Nullable<Timestamp>,
+        status -> Varchar,
+        comment -> Nullable<Text>,
+        reviewed_by -> Nullable<Int4>,
+        reviewed_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use postgis_diesel::sql_types::*;
+
+    organizations (id) {
+        id -> Int4,
+        street_id -> Int4,
+        name -> Varchar,
+        description -> Nullable<Text>,
+        contact_info -> Nullable<Varchar>,
+        opening_hours_note -> Nullable<Varchar>,
+        website -> Nullable<Varchar>,
+        email -> Nullable<Varchar>,
+        phone -> Nullable<Varchar>,
+        category -> Nullable<Varchar>,
+        rating -> Nullable<Numeric>,
+        location -> Nullable<Geometry>,
+        opening_hours -> Nullable<Jsonb>,
+        reviews_count -> Int4,
+        owner_id -> Nullable<Int4>,
+        created_at -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    price_and_tickets (id) {
+        id -> Int4,
+        schedule_id -> Int4,
+        ticket_price -> Float8,
+        ticket_availability -> Bool,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use postgis_diesel::sql_types::*;
+
+    requests (id) {
+        id -> Int4,
+        user_id -> Int4,
+        service_id -> Int4,
+        request_date -> Timestamp,
+        description -> Nullable<Text>,
+        status -> Varchar,
+        assigned_to -> Nullable<Int4>,
+        priority -> Int2,
+        due_date -> Nullable<Date>,
+        location -> Nullable<Geometry>,
+        contact_name -> Nullable<Varchar>,
+        contact_phone -> Nullable<Varchar>,
+        contact_email -> Nullable<Varchar>,
+        comments -> Nullable<Text>,
+        street_id -> Nullable<Int4>,
+        address -> Nullable<Varchar>,
+        district_id -> Nullable<Int4>,
+    }
+}
+
+table! {
+    review_photos (review_id, file_id) {
+        review_id -> Int4,
+        file_id -> Int4,
+        position -> Int2,
+    }
+}
+
+table! {
+    reviews (id) {
+        id -> Int4,
+        object_type -> Varchar,
+        object_id -> Int4,
+        user_id -> Int4,
+        rating -> Int2,
+        body -> Nullable<Text>,
+        created_at -> Timestamp,
+        updated_at -> Nullable<Timestamp>,
+        reply -> Nullable<Text>,
+        reply_user_id -> Nullable<Int4>,
+        replied_at -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    route_comments (id) {
+        id -> Int4,
+        route_id -> Int4,
+        user_id -> Int4,
+        comment_text -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    route_images (id) {
+        id -> Int4,
+        route_id -> Int4,
+        image_url -> Varchar,
+        caption -> Nullable<Text>,
+        user_id -> Int4,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use postgis_diesel::sql_types::*;
+
+    route_points (id) {
+        id -> Int4,
+        route_id -> Int4,
+        title -> Varchar,
+        description -> Text,
+        location -> Geometry,
+        elevation -> Float8,
+    }
+}
+
+table! {
+    route_reviews (id) {
+        id -> Int4,
+        route_id -> Int4,
+        user_id -> Int4,
+        rating -> Float8,
+        comment -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    route_tags (id) {
+        id -> Int4,
+        route_id -> Int4,
+        tag_name -> Varchar,
+        category -> Nullable<Varchar>,
+    }
+}
+
+table! {
+    routes (id) {
+        id -> Int4,
+        map_id -> Int4,
+        title -> Varchar,
+        description -> Text,
+        user_id -> Int4,
+        route_data -> Jsonb,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    schedules (id) {
+        id -> Int4,
+        vehicle_id -> Int4,
+        departure_date -> Date,
+        arrival_date -> Date,
+        departure_location -> Varchar,
+        arrival_location -> Varchar,
+        intermediate_stops -> Nullable<Text>,
+        departure_time -> Time,
+        arrival_time -> Time,
+    }
+}
+
+table! {
+    serve (id) {
+        id -> Int4,
+        name -> Varchar,
+        description -> Nullable<Text>,
+        price -> Int4,
+        man_hours -> Int4,
+        category_id -> Int4,
+        tech_cat_id -> Int4,
+        position -> Int2,
+    }
+}
+
+table! {
+    serve_categories (id) {
+        id -> Int4,
+        name -> Varchar,
+        description -> Nullable<Text>,
+        tech_cat_id -> Int4,
+        position -> Int2,
+    }
+}
+
+table! {
+    serve_items (serve_id, item_id) {
+        serve_id -> Int4,
+        item_id -> Int4,
+    }
+}
+
+table! {
+    services (id) {
+        id -> Int4,
+        name -> Varchar,
+        description -> Nullable<Text>,
+    }
+}
+
+table! {
+    stat_pages (id) {
+        id -> Int4,
+        types -> Int2,
+        view -> Int4,
+        height -> Float8,
+        seconds -> Int4,
+        now_u -> Int4,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use postgis_diesel::sql_types::*;
+
+    street_addresses (id) {
+        id -> Int4,
+        street_id -> Int4,
+        house -> Varchar,
+        number -> Int4,
+        location -> Geometry,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use postgis_diesel::sql_types::*;
+
+    streets (id) {
+        id -> Int4,
+        district_id -> Int4,
+        name -> Varchar,
+        length_km -> Nullable<Numeric>,
+        surface_type -> Nullable<Varchar>,
+        speed_limit -> Nullable<Int2>,
+        geometry -> Nullable<Geometry>,
+    }
+}
+
+table! {
+    subscriptions (id) {
+        id -> Int4,
+        user_id -> Int4,
+        object_id -> Int4,
+        object_type -> Varchar,
+        status -> Varchar,
+        created_at -> Timestamp,
+        name -> Varchar,
+        types -> Int2,
+        link -> Varchar,
+        image -> Varchar,
+        category_id -> Int4,
+        lists -> Int2,
+        members -> Int4,
+        description -> Nullable<Text>,
+    }
+}
+
+table! {
+    tag_object (id) {
+        id -> Int4,
+        tag_id -> Int4,
+        object_id -> Int4,
+        object_type -> Varchar,
+        created_at -> Timestamp,
+        created_by -> Nullable<Int4>,
+    }
+}
+
+table! {
+    tags (id) {
+        id -> Int4,
+        name -> Varchar,
+        description -> Nullable<Text>,
+        created_at -> Timestamp,
+        created_by -> Nullable<Int4>,
+    }
+}
+
+table! {
+    tech_categories (id) {
+        id -> Int4,
+        name -> Varchar,
+        description -> Nullable<Text>,
+        level -> Int2,
+        position -> Int2,
+    }
+}
+
+table! {
+    transport_vehicles (id) {
+        id -> Int4,
+        vehicle_name -> Varchar,
+        vehicle_description -> Text,
+        carrier_id -> Int4,
+        model -> Varchar,
+        route_number -> Nullable<Varchar>,
+    }
+}
+
+table! {
+    user_events (id) {
+        id -> Int4,
+        user_id -> Int4,
+        title -> Varchar,
+        description -> Text,
+        category -> Nullable<Varchar>,
+        date_start -> Timestamp,
+        date_end -> Timestamp,
+        location -> Nullable<Varchar>,
+        ticket_price -> Nullable<Float8>,
+        currency -> Nullable<Varchar>,
+        contact_name -> Nullable<Varchar>,
+        contact_phone -> Nullable<Varchar>,
+        contact_email -> Nullable<Varchar>,
+        image -> Nullable<Varchar>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        status -> Varchar,
+        theme_id -> Nullable<Int4>,
+        event_id -> Nullable<Int4>,
+    }
+}
+
+table! {
+    user_event_history (id) {
+        id -> Int4,
+        user_event_id -> Int4,
+        actor_id -> Nullable<Int4>,
+        from_status -> Varchar,
+        to_status -> Varchar,
+        comment -> Nullable<Text>,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    user_maps (id) {
+        id -> Int4,
+        user_id -> Int4,
+        map_id -> Int4,
+        can_edit -> Bool,
+        can_view -> Bool,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use postgis_diesel::sql_types::*;
+
+    user_points (id) {
+        id -> Int4,
+        street_id -> Int4,
+        name -> Varchar,
+        description -> Nullable<Text>,
+        category -> Nullable<Varchar>,
+        rating -> Nullable<Numeric>,
+        photo -> Nullable<Bytea>,
+        created_at -> Timestamp,
+        user_id -> Int4,
+        external_link -> Nullable<Varchar>,
+        location -> Geometry,
+        reviews_count -> Int4,
+        district_id -> Nullable<Int4>,
+    }
+}
+
+table! {
+    users (id) {
+        id -> Int4,
+        first_name -> Varchar,
+        last_name -> Varchar,
+        middle_name -> Varchar,
+        email -> Varchar,
+        phone_number -> Varchar,
+        description -> Text,
+        photo_link -> Varchar,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use postgis_diesel::sql_types::*;
+
+    wifi_charging (id) {
+        id -> Int4,
+        street_id -> Int4,
+        name -> Varchar,
+        description -> Nullable<Text>,
+        services -> Nullable<Varchar>,
+        location -> Nullable<Geometry>,
+        rating -> Nullable<Numeric>,
+        has_wifi -> Bool,
+        wifi_ssid -> Nullable<Varchar>,
+        wifi_password_required -> Bool,
+        has_charging -> Bool,
+        is_free -> Bool,
+        price -> Nullable<Numeric>,
+        price_unit -> Nullable<Varchar>,
+        currency -> Varchar,
+        access_hours -> Nullable<Jsonb>,
+        created_at -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    wifi_charging_reports (id) {
+        id -> Int4,
+        point_id -> Int4,
+        user_id -> Int4,
+        status -> Varchar,
+        comment -> Nullable<Text>,
+        created_at -> Timestamp,
+    }
+}
+
+joinable!(administrators -> users (user_id));
+joinable!(advertisements -> cities (city_id));
+joinable!(advertisements -> communities (community_id));
+joinable!(advertisements -> organizations (organization_id));
+joinable!(advertisements -> users (user_id));
+joinable!(category_items -> categories (category_id));
+joinable!(category_items -> items (item_id));
+joinable!(charging_connectors -> wifi_charging (point_id));
+joinable!(communities -> users (user_id));
+joinable!(cookie_stats -> cookie_users (user_id));
+joinable!(cookie_users -> users (user_id));
+joinable!(districts -> cities (city_id));
+joinable!(email_queue -> users (user_id));
+joinable!(event_attendees -> event_ticket_tiers (tier_id));
+joinable!(event_attendees -> events (event_id));
+joinable!(event_attendees -> users (user_id));
+joinable!(event_calendar -> events (event_id));
+joinable!(event_moderators -> users (user_id));
+joinable!(event_recurrence_exceptions -> event_recurrences (recurrence_id));
+joinable!(event_recurrences -> events (event_id));
+joinable!(event_reminders -> events (event_id));
+joinable!(event_reminders -> users (user_id));
+joinable!(event_subscribers -> events (event_id));
+joinable!(event_subscribers -> users (user_id));
+joinable!(event_ticket_tiers -> events (event_id));
+joinable!(events -> cities (city_id));
+joinable!(events -> event_themes (theme_id));
+joinable!(events -> users (user_id));
+joinable!(file_comments -> files (file_id));
+joinable!(file_comments -> users (user_id));
+joinable!(file_downloads -> files (file_id));
+joinable!(file_downloads -> users (user_id));
+joinable!(file_likes -> files (file_id));
+joinable!(file_likes -> users (user_id));
+joinable!(file_report -> files (file_id));
+joinable!(file_report -> users (user_id));
+joinable!(file_tags -> files (file_id));
+joinable!(file_tags -> tags (tag_id));
+joinable!(files -> communities (community_id));
+joinable!(files -> users (user_id));
+joinable!(forum_bans -> forum_reports (report_id));
+joinable!(forum_bans -> forums (forum_id));
+joinable!(forum_invites -> forums (forum_id));
+joinable!(forum_invites -> users (created_by));
+joinable!(forum_join_requests -> forums (forum_id));
+joinable!(forum_join_requests -> users (user_id));
+joinable!(forum_likes -> forum_posts (post_id));
+joinable!(forum_likes -> users (user_id));
+joinable!(forum_members -> forums (forum_id));
+joinable!(forum_members -> users (user_id));
+joinable!(forum_moderation_log -> forum_reports (report_id));
+joinable!(forum_moderation_log -> forums (forum_id));
+joinable!(forum_moderators -> forums (forum_id));
+joinable!(forum_moderators -> users (user_id));
+joinable!(forum_post_revisions -> forum_posts (post_id));
+joinable!(forum_post_revisions -> users (editor_id));
+joinable!(forum_posts -> forum_topics (topic_id));
+joinable!(forum_posts -> users (user_id));
+joinable!(forum_reports -> forum_posts (post_id));
+joinable!(forum_reports -> users (user_id));
+joinable!(forum_subscriptions -> forum_topics (topic_id));
+joinable!(forum_subscriptions -> users (user_id));
+joinable!(forum_topics -> forums (forum_id));
+joinable!(forum_topics -> users (user_id));
+joinable!(forum_warnings -> forum_reports (report_id));
+joinable!(forum_warnings -> forums (forum_id));
+joinable!(goods -> goods_categories (category_id));
+joinable!(goods -> organizations (organization_id));
+joinable!(goods -> streets (street_id));
+joinable!(goods_photos -> files (file_id));
+joinable!(goods_photos -> goods (goods_id));
+joinable!(house_ranges -> streets (street_id));
+joinable!(item_files -> files (file_id));
+joinable!(item_files -> items (item_id));
+joinable!(items -> users (user_id));
+joinable!(layers -> maps (map_id));
+joinable!(layers -> users (user_id));
+joinable!(maps -> users (user_id));
+joinable!(notifications -> events (event_id));
+joinable!(notifications -> users (user_id));
+joinable!(organization_claims -> files (file_id));
+joinable!(organization_claims -> organizations (organization_id));
+joinable!(organizations -> streets (street_id));
+joinable!(price_and_tickets -> schedules (schedule_id));
+joinable!(requests -> districts (district_id));
+joinable!(requests -> services (service_id));
+joinable!(requests -> streets (street_id));
+joinable!(review_photos -> files (file_id));
+joinable!(review_photos -> reviews (review_id));
+joinable!(reviews -> users (user_id));
+joinable!(route_comments -> routes (route_id));
+joinable!(route_comments -> users (user_id));
+joinable!(route_images -> routes (route_id));
+joinable!(route_images -> users (user_id));
+joinable!(route_points -> routes (route_id));
+joinable!(route_reviews -> routes (route_id));
+joinable!(route_reviews -> users (user_id));
+joinable!(route_tags -> routes (route_id));
+joinable!(routes -> maps (map_id));
+joinable!(routes -> users (user_id));
+joinable!(schedules -> transport_vehicles (vehicle_id));
+joinable!(serve -> serve_categories (category_id));
+joinable!(serve -> tech_categories (tech_cat_id));
+joinable!(serve_categories -> tech_categories (tech_cat_id));
+joinable!(serve_items -> items (item_id));
+joinable!(serve_items -> serve (serve_id));
+joinable!(street_addresses -> streets (street_id));
+joinable!(streets -> districts (district_id));
+joinable!(subscriptions -> users (user_id));
+joinable!(tag_object -> tags (tag_id));
+joinable!(user_event_history -> user_events (user_event_id));
+joinable!(user_event_history -> users (actor_id));
+joinable!(user_events -> event_themes (theme_id));
+joinable!(user_events -> events (event_id));
+joinable!(user_events -> users (user_id));
+joinable!(user_maps -> maps (map_id));
+joinable!(user_maps -> users (user_id));
+joinable!(user_points -> districts (district_id));
+joinable!(user_points -> streets (street_id));
+joinable!(user_points -> users (user_id));
+joinable!(wifi_charging -> streets (street_id));
+joinable!(wifi_charging_reports -> users (user_id));
+joinable!(wifi_charging_reports -> wifi_charging (point_id));
+
+allow_tables_to_appear_in_same_query!(
+    administrators,
+    advertisements,
+    carrier_information,
+    categories,
+    category_items,
+    charging_connectors,
+    cities,
+    communities,
+    cookie_stats,
+    cookie_users,
+    districts,
+    email_queue,
+    event_attendees,
+    event_calendar,
+    event_moderators,
+    event_recurrence_exceptions,
+    event_recurrences,
+    event_reminders,
+    event_subscribers,
+    event_themes,
+    event_ticket_tiers,
+    events,
+    feedbacks,
+    file_comments,
+    file_downloads,
+    file_likes,
+    file_report,
+    file_tags,
+    files,
+    forum_bans,
+    forum_invites,
+    forum_join_requests,
+    forum_likes,
+    forum_members,
+    forum_moderation_log,
+    forum_moderators,
+    forum_post_revisions,
+    forum_posts,
+    forum_reports,
+    forum_subscriptions,
+    forum_topics,
+    forum_warnings,
+    forums,
+    goods,
+    goods_categories,
+    goods_photos,
+    house_ranges,
+    item_files,
+    items,
+    layers,
+    maps,
+    notifications,
+    organization_claims,
+    organizations,
+    price_and_tickets,
+    requests,
+    review_photos,
+    reviews,
+    route_comments,
+    route_images,
+    route_points,
+    route_reviews,
+    route_tags,
+    routes,
+    schedules,
+    serve,
+    serve_categories,
+    serve_items,
+    services,
+    stat_pages,
+    street_addresses,
+    streets,
+    subscriptions,
+    tag_object,
+    tags,
+    tech_categories,
+    transport_vehicles,
+    user_event_history,
+    user_events,
+    user_maps,
+    user_points,
+    users,
+    wifi_charging,
+    wifi_charging_reports,
+);
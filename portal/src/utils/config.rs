@@ -0,0 +1,71 @@
+use dotenv::dotenv;
+use std::env;
+
+
+// Настройки сервера, которые читаются из переменных окружения (или .env)
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub bind_address:     String, // Адрес, на котором слушает сервер
+    pub database_url:     String, // Строка подключения к Postgres
+    pub session_key:      Option<String>, // Ключ для подписи cookie сессий (не меньше 64 байт), нужен только серверу
+    pub media_root:       String, // Папка, куда складываются загруженные файлы
+    pub db_pool_size:     u32,    // Максимум соединений в пуле
+    pub db_timeout:       u64,    // Сколько секунд ждать свободное соединение
+    pub shutdown_timeout: u64,    // Сколько секунд ждать запросы при остановке
+    pub smtp_host:        Option<String>, // SMTP сервер для писем (без него письма не отправляются)
+    pub smtp_user:        Option<String>, // Логин SMTP
+    pub smtp_password:    Option<String>, // Пароль SMTP
+    pub mail_from:        String,  // Адрес отправителя писем
+    pub reminder_windows: Vec<i64>, // За сколько минут до начала напоминать о событии
+    pub reminder_interval: u64,    // Как часто (в секундах) проверять, кому пора напомнить
+}
+
+impl Config {
+    // Метод для чтения настроек. Без DATABASE_URL ничего не работает,
+    // SESSION_KEY нужен только серверу (миграции запускаются и без него),
+    // остальные параметры имеют значения по умолчанию.
+    pub fn from_env() -> Result<Config, String> {
+        dotenv().ok();
+
+        let session_key = env::var("SESSION_KEY").ok();
+        if session_key.as_ref().is_some_and(|key| key.len() < 64) {
+            return Err("SESSION_KEY must be at least 64 bytes long".to_string());
+        }
+
+        Ok(Config {
+            bind_address:     env::var("BIND_ADDRESS")
+                .unwrap_or("127.0.0.1:8000".to_string()),
+            database_url:     env::var("DATABASE_URL")
+                .map_err(|_| "DATABASE_URL must be set".to_string())?,
+            session_key:      session_key,
+            media_root:       env::var("MEDIA_ROOT")
+                .unwrap_or("./media".to_string()),
+            db_pool_size:     env::var("DB_POOL_SIZE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(10),
+            db_timeout:       env::var("DB_TIMEOUT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(5),
+            shutdown_timeout: env::var("SHUTDOWN_TIMEOUT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(30),
+            smtp_host:        env::var("SMTP_HOST").ok(),
+            smtp_user:        env::var("SMTP_USER").ok(),
+            smtp_password:    env::var("SMTP_PASSWORD").ok(),
+            mail_from:        env::var("MAIL_FROM")
+                .unwrap_or("noreply@localhost".to_string()),
+            // список через запятую, по умолчанию за сутки и за час
+            reminder_windows: env::var("REMINDER_WINDOWS")
+                .ok()
+                .map(|v| v.split(',').filter_map(|w| w.trim().parse().ok()).collect())
+                .unwrap_or(vec![24 * 60, 60]),
+            reminder_interval: env::var("REMINDER_INTERVAL")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(60),
+        })
+    }
+}
@@ -0,0 +1,49 @@
+use actix_web::web;
+use diesel::{
+    PgConnection,
+    r2d2::{self, ConnectionManager},
+};
+use std::{io, time::Duration};
+
+use crate::errors::Error;
+use crate::utils::Config;
+
+
+pub type DbPool = r2d2::Pool<ConnectionManager<PgConnection>>;
+
+// Создание пула соединений с базой. Пул один на все воркеры.
+// Если база недоступна при старте, сервер завершается с ошибкой, а не паникой.
+pub fn create_pool(config: &Config) -> io::Result<DbPool> {
+    let manager = ConnectionManager::<PgConnection>::new(&config.database_url);
+    r2d2::Pool::builder()
+        .max_size(config.db_pool_size)
+        .connection_timeout(Duration::from_secs(config.db_timeout))
+        .build(manager)
+        .map_err(io::Error::other)
+}
+
+// Выполнение запросов к базе в пуле потоков actix, чтобы не блокировать воркер.
+// Соединение берется из пула внутри блока и возвращается туда после запроса.
+// Если свободного соединения нет дольше db_timeout, вернется Error::Unavailable (503).
+pub async fn db<F, R, E>(pool: &web::Data<DbPool>, f: F) -> Result<R, Error>
+where
+    F: FnOnce(&PgConnection) -> Result<R, E> + Send + 'static,
+    E: Into<Error> + Send + 'static,
+    R: Send + 'static,
+{
+    let pool = pool.clone();
+    web::block(move || {
+        let _connection = pool.get()?;
+        f(&_connection).map_err(Into::into)
+    })
+    .await?
+}
+
+// Шаблон LIKE/ILIKE для подстроки: спецсимволы из запроса экранируются
+pub fn like_pattern(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
@@ -0,0 +1,562 @@
+use chrono::{Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+
+use crate::errors::Error;
+use crate::models::{
+    geo_point,
+    Event,
+    EventCalendar,
+    EventRecurrence,
+    EventRecurrenceException,
+    GeoPoint,
+    FREQ_MONTHLY,
+    FREQ_WEEKLY,
+};
+
+
+// Выгрузка и загрузка событий в формате iCalendar (RFC 5545).
+// Время в базе хранится без часового пояса, поэтому и в файл оно
+// пишется "плавающим" (без Z и TZID) - календарь покажет его как местное.
+// При загрузке время в UTC переводится по смещению, которое передал
+// пользователь, а TZID (кроме UTC) не поддерживается.
+
+const PRODID: &str = "-//вебсервисы.рф//Afisha//RU";
+const UID_DOMAIN: &str = "вебсервисы.рф";
+// Максимальная длина строки в байтах, после которой строка переносится
+const LINE_LIMIT: usize = 75;
+// Дни недели в RRULE, с понедельника
+const WEEKDAYS: [&str; 7] = ["MO", "TU", "WE", "TH", "FR", "SA", "SU"];
+// Самое длинное событие, которое можно задать через DURATION
+const MAX_DURATION_DAYS: i64 = 366;
+
+// Событие, прочитанное из .ics файла, до сохранения в базу
+#[derive(Debug)]
+pub struct IcalEvent {
+    pub title:       String,
+    pub description: String,
+    pub category:    Option<String>,
+    pub location:    Option<String>,
+    pub date_start:  NaiveDateTime,
+    pub date_end:    NaiveDateTime,
+    pub all_day:     bool,
+    pub extra_dates: Vec<NaiveDateTime>, // Даты из RDATE
+    pub geo:         Option<GeoPoint>,   // Координаты из GEO
+}
+
+// Экранирование текста по правилам RFC 5545 (раздел 3.3.11)
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+fn unescape_text(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') | Some('N') => result.push('\n'),
+                Some(other) => result.push(other),
+                None => result.push('\\'),
+            }
+        }
+        else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+// Перенос длинной строки: не больше 75 байт, продолжение начинается с пробела.
+// Резать можно только по границе символа, иначе сломается UTF-8.
+fn fold_line(line: &str, out: &mut String) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > LINE_LIMIT {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+fn format_datetime(value: &NaiveDateTime) -> String {
+    value.format("%Y%m%dT%H%M%S").to_string()
+}
+
+fn weekday_code(weekday: i16) -> &'static str {
+    WEEKDAYS[(weekday.clamp(1, 7) - 1) as usize]
+}
+
+// Значение RRULE для правила повторения. Первой датой правила должен быть
+// DTSTART, тогда COUNT считается так же, как в dates_between.
+// UNTIL при "плавающем" DTSTART тоже пишется без часового пояса.
+fn recurrence_rule(rule: &EventRecurrence) -> String {
+    let mut parts = vec![format!("FREQ={}", rule.freq.to_uppercase())];
+    if rule.repeat_every > 1 {
+        parts.push(format!("INTERVAL={}", rule.repeat_every));
+    }
+    if rule.freq == FREQ_WEEKLY {
+        let mut days: Vec<i16> = if rule.weekdays.is_empty() {
+            vec![rule.date_start.weekday().number_from_monday() as i16]
+        } else {
+            rule.weekdays.clone()
+        };
+        days.sort();
+        days.dedup();
+        let days: Vec<&str> = days.into_iter().map(weekday_code).collect();
+        parts.push(format!("BYDAY={}", days.join(",")));
+        // недели правила начинаются с понедельника
+        parts.push("WKST=MO".to_string());
+    }
+    else if rule.freq == FREQ_MONTHLY {
+        match (rule.month_week, rule.month_weekday) {
+            (Some(week), Some(weekday)) => parts.push(format!("BYDAY={}{}", week, weekday_code(weekday))),
+            _ => parts.push(format!("BYMONTHDAY={}", rule.date_start.day())),
+        }
+    }
+    if let Some(until) = rule.repeat_until {
+        parts.push(format!("UNTIL={}", format_datetime(&until.and_hms_opt(23, 59, 59).expect("valid time"))));
+    }
+    if let Some(count) = rule.repeat_count {
+        parts.push(format!("COUNT={}", count));
+    }
+    parts.join(";")
+}
+
+// extra - строки повторения (RRULE, EXDATE) сразу после времени события
+fn push_event(
+    out: &mut String,
+    uid: String,
+    stamp: &str,
+    event: &Event,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+    extra: &[String],
+) {
+    fold_line("BEGIN:VEVENT", out);
+    fold_line(&format!("UID:{}", uid), out);
+    fold_line(&format!("DTSTAMP:{}", stamp), out);
+    fold_line(&format!("DTSTART:{}", format_datetime(&start)), out);
+    fold_line(&format!("DTEND:{}", format_datetime(&end)), out);
+    for line in extra {
+        fold_line(line, out);
+    }
+    fold_line(&format!("SUMMARY:{}", escape_text(&event.title)), out);
+    if event.cancelled_at.is_some() {
+        fold_line("STATUS:CANCELLED", out);
+    }
+    if !event.description.is_empty() {
+        fold_line(&format!("DESCRIPTION:{}", escape_text(&event.description)), out);
+    }
+    if let Some(ref location) = event.location {
+        fold_line(&format!("LOCATION:{}", escape_text(location)), out);
+    }
+    if let Some(ref point) = event.coordinates {
+        fold_line(&format!("GEO:{};{}", point.y, point.x), out);
+    }
+    if let Some(ref category) = event.category {
+        fold_line(&format!("CATEGORIES:{}", escape_text(category)), out);
+    }
+    fold_line("END:VEVENT", out);
+}
+
+// Календарь из списка событий. Каждая строка event_calendar становится
+// отдельным VEVENT, каждое правило повторения - VEVENT с RRULE и EXDATE.
+// Если у события нет ни того, ни другого, берутся date_start/date_end.
+pub fn events_to_ical(
+    name: &str,
+    events: &[Event],
+    dates: &[EventCalendar],
+    recurrences: &[EventRecurrence],
+    exceptions: &[EventRecurrenceException],
+) -> String {
+    let stamp = format!("{}Z", format_datetime(&chrono::Utc::now().naive_utc()));
+    let mut out = String::new();
+    fold_line("BEGIN:VCALENDAR", &mut out);
+    fold_line("VERSION:2.0", &mut out);
+    fold_line(&format!("PRODID:{}", PRODID), &mut out);
+    fold_line("CALSCALE:GREGORIAN", &mut out);
+    fold_line("METHOD:PUBLISH", &mut out);
+    fold_line(&format!("X-WR-CALNAME:{}", escape_text(name)), &mut out);
+
+    for event in events {
+        let event_dates: Vec<&EventCalendar> = dates
+            .iter()
+            .filter(|d| d.event_id == event.id)
+            .collect();
+        let event_recurrences: Vec<&EventRecurrence> = recurrences
+            .iter()
+            .filter(|r| r.event_id == event.id)
+            .collect();
+        if event_dates.is_empty() && event_recurrences.is_empty() {
+            push_event(
+                &mut out,
+                format!("event-{}@{}", event.id, UID_DOMAIN),
+                &stamp,
+                event,
+                event.date_start,
+                event.date_end,
+                &[],
+            );
+            continue;
+        }
+        // продолжительность события нужна, если у даты не указано время окончания
+        let duration = event.date_end - event.date_start;
+        let bounds = |date: NaiveDate, time_start: Option<NaiveTime>, time_end: Option<NaiveTime>| {
+            let start = date.and_time(time_start.unwrap_or(event.date_start.time()));
+            let end = match time_end {
+                Some(time_end) if time_end > start.time() => date.and_time(time_end),
+                _ => start + duration,
+            };
+            (start, end)
+        };
+        for date in event_dates {
+            let (start, end) = bounds(date.date, date.time_start, date.time_end);
+            push_event(
+                &mut out,
+                format!("event-{}-{}@{}", event.id, date.id, UID_DOMAIN),
+                &stamp,
+                event,
+                start,
+                end,
+                &[],
+            );
+        }
+        for rule in event_recurrences {
+            let first = match rule.first_date() {
+                Some(first) => first,
+                None => continue,
+            };
+            let (start, end) = bounds(first, rule.time_start, rule.time_end);
+            let mut extra = vec![format!("RRULE:{}", recurrence_rule(rule))];
+            let skipped: Vec<String> = exceptions
+                .iter()
+                .filter(|e| e.recurrence_id == rule.id && e.date >= first)
+                .map(|e| format_datetime(&e.date.and_time(start.time())))
+                .collect();
+            if !skipped.is_empty() {
+                extra.push(format!("EXDATE:{}", skipped.join(",")));
+            }
+            push_event(
+                &mut out,
+                format!("event-{}-r{}@{}", event.id, rule.id, UID_DOMAIN),
+                &stamp,
+                event,
+                start,
+                end,
+                &extra,
+            );
+        }
+    }
+
+    fold_line("END:VCALENDAR", &mut out);
+    out
+}
+
+// Разбор значения даты: 20261018T190000, 20261018T160000Z или 20261018 (весь день).
+// Время в UTC переводится в местное по utc_offset, без него такое время не принимается.
+// TZID разрешен только для UTC: базы часовых поясов у сервиса нет.
+fn parse_datetime(value: &str, params: &str, utc_offset: Option<FixedOffset>) -> Result<(NaiveDateTime, bool), Error> {
+    let value = value.trim();
+    let invalid = || Error::Validation(format!("invalid date: {}", value));
+    let tzid = params
+        .split(';')
+        .find_map(|param| param.strip_prefix("TZID="))
+        .map(|tzid| tzid.trim_matches('"'));
+    if value.len() == 8 {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").map_err(|_| invalid())?;
+        return Ok((date.and_time(NaiveTime::MIN), true));
+    }
+    let is_utc = match tzid {
+        None => value.ends_with('Z'),
+        Some("UTC") | Some("ETC/UTC") | Some("GMT") => true,
+        Some(tzid) => {
+            return Err(Error::Validation(format!(
+                "TZID {} is not supported, use floating or UTC time",
+                tzid
+            )))
+        },
+    };
+    let datetime = NaiveDateTime::parse_from_str(value.trim_end_matches('Z'), "%Y%m%dT%H%M%S").map_err(|_| invalid())?;
+    if !is_utc {
+        return Ok((datetime, false));
+    }
+    let offset = utc_offset
+        .ok_or_else(|| Error::Validation(format!("UTC time {} requires utc_offset", value)))?;
+    let local = datetime
+        .checked_add_signed(Duration::seconds(offset.local_minus_utc() as i64))
+        .ok_or_else(invalid)?;
+    Ok((local, false))
+}
+
+// Склейка перенесенных строк обратно (строка, начинающаяся с пробела
+// или табуляции, - продолжение предыдущей)
+fn unfold_lines(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in text.split('\n') {
+        let raw = raw.trim_end_matches('\r');
+        if raw.starts_with(' ') || raw.starts_with('\t') {
+            if let Some(last) = lines.last_mut() {
+                last.push_str(&raw[1..]);
+                continue;
+            }
+        }
+        if !raw.is_empty() {
+            lines.push(raw.to_string());
+        }
+    }
+    lines
+}
+
+// Разбор .ics файла. Из каждого VEVENT получается одно событие,
+// вложенные компоненты (VALARM и т.п.) пропускаются.
+// utc_offset - смещение местного времени площадки от UTC.
+pub fn parse_ical(text: &str, utc_offset: Option<FixedOffset>) -> Result<Vec<IcalEvent>, Error> {
+    let mut result = Vec::new();
+    let mut current: Option<Vec<(String, String, String)>> = None;
+    let mut nested = 0;
+
+    for line in unfold_lines(text) {
+        let (head, value) = match line.find(':') {
+            Some(pos) => (&line[..pos], &line[pos + 1..]),
+            None => continue,
+        };
+        let mut parts = head.splitn(2, ';');
+        let name = parts.next().unwrap_or("").to_uppercase();
+        let params = parts.next().unwrap_or("").to_uppercase();
+
+        match (name.as_str(), value.trim().to_uppercase().as_str()) {
+            ("BEGIN", "VEVENT") => current = Some(Vec::new()),
+            ("END", "VEVENT") => {
+                if let Some(props) = current.take() {
+                    result.push(build_event(props, utc_offset)?);
+                }
+            },
+            ("BEGIN", _) if current.is_some() => nested += 1,
+            ("END", _) if current.is_some() && nested > 0 => nested -= 1,
+            _ => {
+                if let Some(ref mut props) = current {
+                    if nested == 0 {
+                        props.push((name, params, value.to_string()));
+                    }
+                }
+            },
+        }
+    }
+    if result.is_empty() {
+        return Err(Error::Validation("no VEVENT found in file".to_string()));
+    }
+    Ok(result)
+}
+
+fn build_event(props: Vec<(String, String, String)>, utc_offset: Option<FixedOffset>) -> Result<IcalEvent, Error> {
+    let mut title = None;
+    let mut description = String::new();
+    let mut category = None;
+    let mut location = None;
+    let mut start = None;
+    let mut end = None;
+    let mut duration = None;
+    let mut extra_dates = Vec::new();
+    let mut geo = None;
+
+    for (name, params, value) in props {
+        match name.as_str() {
+            "SUMMARY"     => title = Some(unescape_text(&value)),
+            "DESCRIPTION" => description = unescape_text(&value),
+            "LOCATION"    => location = Some(unescape_text(&value)),
+            // берем только первую категорию из списка
+            "CATEGORIES"  => category = value.split(',').next().map(|c| unescape_text(c.trim())),
+            "DTSTART"     => start = Some(parse_datetime(&value, &params, utc_offset)?),
+            "DTEND"       => end = Some(parse_datetime(&value, &params, utc_offset)?),
+            "DURATION"    => duration = Some(parse_duration(&value)?),
+            "GEO"         => geo = Some(parse_geo(&value)?),
+            "RDATE" => {
+                for item in value.split(',') {
+                    extra_dates.push(parse_datetime(item, &params, utc_offset)?.0);
+                }
+            },
+            _ => (),
+        }
+    }
+
+    let title = title.ok_or_else(|| Error::Validation("VEVENT without SUMMARY".to_string()))?;
+    let (date_start, all_day) = start
+        .ok_or_else(|| Error::Validation(format!("VEVENT \"{}\" without DTSTART", title)))?;
+    // DTEND у события на весь день не включается, поэтому конец - последняя секунда дня
+    let date_end = match (end, duration) {
+        (Some((end, true)), _) => end.checked_sub_signed(Duration::seconds(1)),
+        (Some((end, false)), _) => Some(end),
+        (None, Some(duration)) => date_start.checked_add_signed(duration),
+        (None, None) if all_day => date_start.checked_add_signed(Duration::days(1) - Duration::seconds(1)),
+        (None, None) => Some(date_start),
+    };
+    let date_end = date_end.ok_or_else(|| Error::Validation(format!("VEVENT \"{}\" has invalid end", title)))?;
+
+    Ok(IcalEvent {
+        title:       title,
+        description: description,
+        category:    category,
+        location:    location,
+        date_start:  date_start,
+        date_end:    date_end,
+        all_day:     all_day,
+        extra_dates: extra_dates,
+        geo:         geo,
+    })
+}
+
+// Разбор GEO:широта;долгота
+fn parse_geo(value: &str) -> Result<GeoPoint, Error> {
+    let invalid = || Error::Validation(format!("invalid GEO: {}", value));
+    let mut parts = value.split(';');
+    let lat: f64 = parts.next().and_then(|v| v.trim().parse().ok()).ok_or_else(invalid)?;
+    let lon: f64 = parts.next().and_then(|v| v.trim().parse().ok()).ok_or_else(invalid)?;
+    geo_point(lat, lon)
+}
+
+// Разбор продолжительности вида P1D, PT2H30M, P1W. Дольше MAX_DURATION_DAYS нельзя.
+fn parse_duration(value: &str) -> Result<Duration, Error> {
+    let invalid = || Error::Validation(format!("invalid duration: {}", value));
+    let value = value.trim().trim_start_matches('+');
+    let body = value.strip_prefix('P').ok_or_else(invalid)?;
+    let mut total: i64 = 0;
+    let mut number = String::new();
+    let mut time = false;
+    for c in body.chars() {
+        let unit = match c {
+            '0'..='9' => {
+                number.push(c);
+                continue;
+            },
+            'T' if !time && number.is_empty() => {
+                time = true;
+                continue;
+            },
+            'W' if !time => 7 * 24 * 3600,
+            'D' if !time => 24 * 3600,
+            'H' if time => 3600,
+            'M' if time => 60,
+            'S' if time => 1,
+            _ => return Err(invalid()),
+        };
+        let n: i64 = number.parse().map_err(|_| invalid())?;
+        number.clear();
+        total = n
+            .checked_mul(unit)
+            .and_then(|seconds| total.checked_add(seconds))
+            .filter(|total| *total <= MAX_DURATION_DAYS * 24 * 3600)
+            .ok_or_else(|| Error::Validation(format!("duration is longer than {} days: {}", MAX_DURATION_DAYS, value)))?;
+    }
+    if !number.is_empty() || body.is_empty() || body.ends_with('T') {
+        return Err(invalid());
+    }
+    Ok(Duration::seconds(total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn datetime(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn parse_duration_units() {
+        assert_eq!(parse_duration("P1W").unwrap(), Duration::weeks(1));
+        assert_eq!(parse_duration("P1D").unwrap(), Duration::days(1));
+        assert_eq!(parse_duration("PT2H30M").unwrap(), Duration::minutes(150));
+        assert_eq!(parse_duration("+P1DT1S").unwrap(), Duration::seconds(86401));
+    }
+
+    #[test]
+    fn parse_duration_rejects_malformed() {
+        for value in &["", "P", "PT", "1D", "P1", "PT1D", "P1H", "P1M", "-P1D", "P1X"] {
+            assert!(matches!(parse_duration(value), Err(Error::Validation(_))), "{}", value);
+        }
+    }
+
+    #[test]
+    fn parse_duration_is_bounded() {
+        assert_eq!(parse_duration("P366D").unwrap(), Duration::days(366));
+        assert!(matches!(parse_duration("P367D"), Err(Error::Validation(_))));
+        assert!(matches!(parse_duration("P99999999999999999W"), Err(Error::Validation(_))));
+        assert!(matches!(parse_duration("P9223372036854775807W"), Err(Error::Validation(_))));
+    }
+
+    #[test]
+    fn parse_datetime_floating_and_date() {
+        assert_eq!(parse_datetime("20261018T190000", "", None).unwrap(), (datetime("2026-10-18 19:00:00"), false));
+        assert_eq!(parse_datetime("20261018", "VALUE=DATE", None).unwrap(), (datetime("2026-10-18 00:00:00"), true));
+    }
+
+    #[test]
+    fn parse_datetime_utc_uses_given_offset() {
+        let moscow = FixedOffset::east_opt(3 * 3600).unwrap();
+        assert_eq!(
+            parse_datetime("20261018T160000Z", "", Some(moscow)).unwrap(),
+            (datetime("2026-10-18 19:00:00"), false),
+        );
+        assert_eq!(
+            parse_datetime("20261018T160000", "TZID=UTC", Some(moscow)).unwrap(),
+            (datetime("2026-10-18 19:00:00"), false),
+        );
+        assert!(matches!(parse_datetime("20261018T160000Z", "", None), Err(Error::Validation(_))));
+    }
+
+    #[test]
+    fn parse_datetime_rejects_tzid() {
+        let result = parse_datetime("20261018T190000", "TZID=EUROPE/MOSCOW", Some(FixedOffset::east_opt(0).unwrap()));
+        assert!(matches!(result, Err(Error::Validation(_))));
+    }
+
+    fn rule(freq: &str, date_start: &str) -> EventRecurrence {
+        EventRecurrence {
+            id:            1,
+            event_id:      1,
+            freq:          freq.to_string(),
+            repeat_every:  1,
+            weekdays:      Vec::new(),
+            month_week:    None,
+            month_weekday: None,
+            date_start:    NaiveDate::parse_from_str(date_start, "%Y-%m-%d").unwrap(),
+            time_start:    None,
+            time_end:      None,
+            repeat_until:  None,
+            repeat_count:  None,
+        }
+    }
+
+    #[test]
+    fn recurrence_rule_weekly() {
+        let mut weekly = rule(FREQ_WEEKLY, "2026-10-07");
+        assert_eq!(recurrence_rule(&weekly), "FREQ=WEEKLY;BYDAY=WE;WKST=MO");
+        weekly.repeat_every = 2;
+        weekly.weekdays = vec![5, 1, 3];
+        weekly.repeat_count = Some(6);
+        assert_eq!(recurrence_rule(&weekly), "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE,FR;WKST=MO;COUNT=6");
+    }
+
+    #[test]
+    fn recurrence_rule_monthly() {
+        let mut monthly = rule(FREQ_MONTHLY, "2026-01-31");
+        assert_eq!(recurrence_rule(&monthly), "FREQ=MONTHLY;BYMONTHDAY=31");
+        monthly.month_week = Some(-1);
+        monthly.month_weekday = Some(5);
+        monthly.repeat_until = Some(NaiveDate::from_ymd_opt(2026, 12, 31).unwrap());
+        assert_eq!(recurrence_rule(&monthly), "FREQ=MONTHLY;BYDAY=-1FR;UNTIL=20261231T235959");
+    }
+
+    #[test]
+    fn recurrence_rule_daily() {
+        let mut daily = rule(crate::models::FREQ_DAILY, "2026-10-01");
+        daily.repeat_every = 3;
+        assert_eq!(recurrence_rule(&daily), "FREQ=DAILY;INTERVAL=3");
+    }
+}
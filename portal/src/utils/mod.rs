@@ -0,0 +1,32 @@
+mod config;
+mod db;
+mod ical;
+mod pages;
+
+pub use self::{
+    config::*,
+    db::*,
+    ical::*,
+    pages::*,
+};
+
+use serde::Serialize;
+use std::{
+    cell::Cell,
+    sync::{Arc, Mutex},
+};
+
+
+// Состояние воркера: номер сервера, счетчик запросов и общий список сообщений
+pub struct AppState {
+    pub server_id:     usize,
+    pub request_count: Cell<usize>,
+    pub messages:      Arc<Mutex<Vec<String>>>,
+}
+
+#[derive(Serialize)]
+pub struct IndexResponse {
+    pub server_id:     usize,
+    pub request_count: usize,
+    pub messages:      Vec<String>,
+}
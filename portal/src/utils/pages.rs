@@ -0,0 +1,164 @@
+use actix_session::Session;
+use actix_web::{
+    HttpRequest,
+    HttpResponse,
+    dev::ConnectionInfo,
+    web,
+};
+use sailfish::TemplateOnce;
+use std::collections::HashMap;
+
+use crate::errors::Error;
+use crate::models::{
+    CookieUser,
+    User,
+    is_administrator,
+    DEVICE_DESCTOP,
+    DEVICE_MOBILE,
+};
+use crate::utils::{db, DbPool};
+
+
+// Общие функции шаблонных страниц сайта (views/pages.rs, views/for_kostia.rs).
+// Страница сначала отдается оболочкой (get_first_load_page), а содержимое
+// браузер догружает тем же адресом с параметром ajax=1 (или 2 для вложенных окон).
+
+// Cookie с id посетителя из cookie_users
+const COOKIE_USER: &str = "user";
+// Cookie с выбранным оформлением сайта
+const COOKIE_TEMPLATE: &str = "template";
+const DEFAULT_TEMPLATE: i16 = 1;
+
+fn get_query(req: &HttpRequest) -> HashMap<String, String> {
+    web::Query::<HashMap<String, String>>::from_query(req.query_string())
+        .map(|q| q.into_inner())
+        .unwrap_or_default()
+}
+
+// Компьютер или телефон (по User-Agent) и режим догрузки из параметра ajax
+pub fn get_device_and_ajax(req: &HttpRequest) -> (bool, i32) {
+    let is_desctop = !req
+        .headers()
+        .get("user-agent")
+        .and_then(|agent| agent.to_str().ok())
+        .is_some_and(|agent| agent.contains("Mobile"));
+    let is_ajax = get_query(req)
+        .get("ajax")
+        .and_then(|ajax| ajax.parse().ok())
+        .unwrap_or(0);
+    (is_desctop, is_ajax)
+}
+
+// Номер оформления сайта из cookie
+pub fn get_template(req: &HttpRequest) -> i16 {
+    req.cookie(COOKIE_TEMPLATE)
+        .and_then(|cookie| cookie.value().parse().ok())
+        .unwrap_or(DEFAULT_TEMPLATE)
+}
+
+// Номер страницы списка из параметра page, с первой
+pub fn get_page(req: &HttpRequest) -> i32 {
+    get_query(req)
+        .get("page")
+        .and_then(|page| page.parse().ok())
+        .filter(|page: &i32| *page > 0)
+        .unwrap_or(1)
+}
+
+pub fn is_signed_in(session: &Session) -> bool {
+    session.get::<i32>("user_id").ok().flatten().is_some()
+}
+
+// Вошедший пользователь и признак администратора.
+// Без входа - Forbidden, поэтому вызывать после is_signed_in.
+pub async fn get_request_user_data(pool: &web::Data<DbPool>, session: &Session) -> Result<(User, bool), Error> {
+    let user_id = session
+        .get::<i32>("user_id")
+        .ok()
+        .flatten()
+        .ok_or(Error::Forbidden)?;
+    db(pool, move |_connection| -> Result<_, Error> {
+        let _user = User::get_user(_connection, user_id)?;
+        let is_admin = is_administrator(_connection, user_id)?;
+        Ok((_user, is_admin))
+    }).await
+}
+
+// id посетителя из cookie. Нового посетителя создаем по ip и устройству,
+// cookie ему ставит скрипт оболочки страницы.
+pub async fn get_or_create_cookie_user_id(
+    pool: &web::Data<DbPool>,
+    conn: &ConnectionInfo,
+    req: &HttpRequest,
+    session: &Session,
+) -> Result<i32, Error> {
+    let cookie_user_id = req
+        .cookie(COOKIE_USER)
+        .and_then(|cookie| cookie.value().parse::<i32>().ok());
+    let user_id = session.get::<i32>("user_id").ok().flatten();
+    let ip = conn.realip_remote_addr().unwrap_or("").to_string();
+    let (is_desctop, _) = get_device_and_ajax(req);
+    let device = if is_desctop { DEVICE_DESCTOP } else { DEVICE_MOBILE };
+
+    let _cookie_user = db(pool, move |_connection| {
+        CookieUser::get_or_create(_connection, cookie_user_id, user_id, &ip, device)
+    }).await?;
+    Ok(_cookie_user.id)
+}
+
+// Оболочка страницы при первом заходе: заголовок, описание и картинка для
+// поисковиков и соцсетей. Содержимое страницы скрипт догружает по uri.
+pub async fn get_first_load_page(
+    session: &Session,
+    is_desctop: bool,
+    title: String,
+    description: String,
+    uri: String,
+    image: String,
+    template_types: i16,
+) -> Result<HttpResponse, Error> {
+    let is_signed_in = is_signed_in(session);
+    let body = if is_desctop {
+        #[derive(TemplateOnce)]
+        #[template(path = "desctop/generic/first_load.stpl")]
+        struct Template {
+            title:          String,
+            description:    String,
+            uri:            String,
+            image:          String,
+            is_signed_in:   bool,
+            template_types: i16,
+        }
+        Template {
+            title:          title,
+            description:    description,
+            uri:            uri,
+            image:          image,
+            is_signed_in:   is_signed_in,
+            template_types: template_types,
+        }
+        .render_once()?
+    }
+    else {
+        #[derive(TemplateOnce)]
+        #[template(path = "mobile/generic/first_load.stpl")]
+        struct Template {
+            title:          String,
+            description:    String,
+            uri:            String,
+            image:          String,
+            is_signed_in:   bool,
+            template_types: i16,
+        }
+        Template {
+            title:          title,
+            description:    description,
+            uri:            uri,
+            image:          image,
+            is_signed_in:   is_signed_in,
+            template_types: template_types,
+        }
+        .render_once()?
+    };
+    Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+}
@@ -0,0 +1,771 @@
+use actix_session::Session;
+use actix_web::{
+    web,
+    web::{Bytes, Data, Json, Path, Query},
+    HttpResponse,
+};
+use chrono::{FixedOffset, NaiveDate, NaiveDateTime};
+use diesel::{Connection, PgConnection};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::errors::Error;
+use crate::models::{
+    Event,
+    EditEvent,
+    EventChange,
+    EventFilter,
+    EventTheme,
+    EventCalendar,
+    NewEventCalendar,
+    EventOccurrence,
+    EventRecurrence,
+    EventRecurrenceException,
+    NewEventRecurrence,
+    EventAttendee,
+    NewEventAttendee,
+    EventTicketTier,
+    NewEventTicketTier,
+    EditEventTicketTier,
+    EventSubscriber,
+    NewEventSubscriber,
+    get_upcoming_events,
+    get_attendee_count_for_event,
+    get_event_recipients,
+    get_subscriber_count_for_event,
+    get_events_scheduled_for_date,
+    get_events_scheduled_for_date_in_category,
+    get_events_scheduled_in_time_interval_in_category,
+    is_administrator,
+    is_event_moderator,
+};
+use crate::notify::{event_cancelled_notice, event_changed_notice, Notifier};
+use crate::utils::{db, DbPool, events_to_ical, parse_ical};
+use super::{get_session_user_id, PageResponse, Pagination};
+
+
+pub fn events_routes(config: &mut web::ServiceConfig) {
+    config.route("/api/v1/events/", web::get().to(list_events));
+    config.route("/api/v1/events/", web::post().to(create_event));
+    config.route("/api/v1/events/upcoming/", web::get().to(upcoming_events));
+    config.route("/api/v1/events/import/", web::post().to(import_events));
+    config.route("/api/v1/events/schedule/", web::get().to(event_schedule));
+    config.route("/api/v1/events/{id}/ics/", web::get().to(event_ics));
+    config.route("/api/v1/themes/{id}/ics/", web::get().to(theme_ics));
+    config.route("/api/v1/users/{id}/ics/", web::get().to(user_ics));
+    config.route("/api/v1/events/{id}/", web::get().to(get_event));
+    config.route("/api/v1/events/{id}/", web::put().to(update_event));
+    config.route("/api/v1/events/{id}/", web::delete().to(delete_event));
+    config.route("/api/v1/events/{id}/attendees/", web::get().to(event_attendees));
+    config.route("/api/v1/events/{id}/attend/", web::post().to(attend_event));
+    config.route("/api/v1/events/{id}/attend/", web::delete().to(unattend_event));
+    config.route("/api/v1/events/{id}/ticket/", web::get().to(event_ticket));
+    config.route("/api/v1/events/{id}/check-in/", web::post().to(check_in_event));
+    config.route("/api/v1/events/{id}/tiers/", web::get().to(event_tiers));
+    config.route("/api/v1/events/{id}/tiers/", web::post().to(create_tier));
+    config.route("/api/v1/events/{id}/tiers/{tid}/", web::put().to(update_tier));
+    config.route("/api/v1/events/{id}/tiers/{tid}/", web::delete().to(delete_tier));
+    config.route("/api/v1/events/{id}/subscribe/", web::post().to(subscribe_event));
+    config.route("/api/v1/events/{id}/subscribe/", web::delete().to(unsubscribe_event));
+    config.route("/api/v1/events/{id}/recurrences/", web::get().to(event_recurrences));
+    config.route("/api/v1/events/{id}/recurrences/", web::post().to(create_recurrence));
+    config.route("/api/v1/events/{id}/recurrences/{rid}/", web::delete().to(delete_recurrence));
+    config.route("/api/v1/events/{id}/recurrences/{rid}/exceptions/", web::post().to(add_recurrence_exception));
+    config.route("/api/v1/events/{id}/recurrences/{rid}/exceptions/{date}/", web::delete().to(remove_recurrence_exception));
+}
+
+// Параметры списка событий: фильтры и страница.
+// Без is_published показываются только опубликованные события.
+// Неопубликованные автор видит только свои, модераторы и администраторы - все.
+#[derive(Debug, Deserialize)]
+pub struct EventListQuery {
+    pub category:     Option<String>,
+    pub theme_id:     Option<i32>,
+    pub date_from:    Option<NaiveDateTime>,
+    pub date_to:      Option<NaiveDateTime>,
+    pub is_published: Option<bool>,
+    pub page:         Option<i64>,
+    pub limit:        Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpcomingQuery {
+    pub category: Option<String>,
+    pub page:     Option<i64>,
+    pub limit:    Option<i64>,
+}
+
+// Событие вместе с количеством посетителей и подписчиков
+#[derive(Serialize)]
+pub struct EventDetail {
+    #[serde(flatten)]
+    pub event:       Event,
+    pub attendees:   i64,
+    pub subscribers: i64,
+}
+
+// Событие, которое может менять только его автор
+fn get_own_event(_connection: &PgConnection, event_id: i32, user_id: i32) -> Result<Event, Error> {
+    let event = Event::get_event_by_id(_connection, event_id)?;
+    if event.user_id != Some(user_id) {
+        return Err(Error::Forbidden);
+    }
+    Ok(event)
+}
+
+// Все неопубликованные события видят модераторы афиши и администраторы
+fn can_see_unpublished(_connection: &PgConnection, user_id: i32) -> Result<bool, Error> {
+    Ok(is_event_moderator(_connection, user_id)? || is_administrator(_connection, user_id)?)
+}
+
+// Неопубликованное событие видно автору, модераторам и администраторам, остальным - 404
+fn get_visible_event(_connection: &PgConnection, event_id: i32, user_id: Option<i32>) -> Result<Event, Error> {
+    let event = Event::get_event_by_id(_connection, event_id)?;
+    if event.is_published {
+        return Ok(event);
+    }
+    match user_id {
+        Some(user_id) if event.user_id == Some(user_id) || can_see_unpublished(_connection, user_id)? => Ok(event),
+        _ => Err(Error::NotFound),
+    }
+}
+
+pub async fn list_events(session: Session, pool: Data<DbPool>, query: Query<EventListQuery>) -> Result<HttpResponse, Error> {
+    let viewer_id = get_session_user_id(&session).ok();
+    let query = query.into_inner();
+    let is_published = query.is_published.unwrap_or(true);
+    if !is_published && viewer_id.is_none() {
+        return Err(Error::Forbidden);
+    }
+    let pagination = Pagination::new(query.page, query.limit);
+    let (items, total) = db(&pool, move |_connection| {
+        let author_id = match viewer_id {
+            Some(viewer_id) if !is_published && !can_see_unpublished(_connection, viewer_id)? => Some(viewer_id),
+            _ => None,
+        };
+        // события тематики ищутся вместе с событиями ее дочерних тематик
+        let theme_ids = match query.theme_id {
+            Some(theme_id) => Some(EventTheme::get_descendant_ids(_connection, theme_id)?),
+            None => None,
+        };
+        let filter = EventFilter {
+            category:     query.category,
+            theme_ids:    theme_ids,
+            date_from:    query.date_from,
+            date_to:      query.date_to,
+            is_published: Some(is_published),
+            user_id:      author_id,
+        };
+        Event::search(_connection, &filter, pagination.offset(), pagination.limit)
+    }).await?;
+    Ok(HttpResponse::Ok().json(PageResponse::new(items, pagination, total)))
+}
+
+pub async fn upcoming_events(pool: Data<DbPool>, query: Query<UpcomingQuery>) -> Result<HttpResponse, Error> {
+    let query = query.into_inner();
+    let pagination = Pagination::new(query.page, query.limit);
+    let now = chrono::Local::now().naive_local();
+    let (items, total) = db(&pool, move |_connection| {
+        get_upcoming_events(_connection, now, query.category.as_deref(), pagination.offset(), pagination.limit)
+    }).await?;
+    Ok(HttpResponse::Ok().json(PageResponse::new(items, pagination, total)))
+}
+
+pub async fn get_event(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let event_id = _id.into_inner();
+    let user_id = get_session_user_id(&session).ok();
+    let detail = db(&pool, move |_connection| -> Result<_, Error> {
+        let event = get_visible_event(_connection, event_id, user_id)?;
+        Ok(EventDetail {
+            event:       event,
+            attendees:   get_attendee_count_for_event(_connection, event_id)?,
+            subscribers: get_subscriber_count_for_event(_connection, event_id)?,
+        })
+    }).await?;
+    Ok(HttpResponse::Ok().json(detail))
+}
+
+// Публикуют события модераторы афиши, остальные попадают в афишу
+// только через одобрение заявки (user_events)
+pub async fn create_event(session: Session, pool: Data<DbPool>, form: Json<EditEvent>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let mut form = form.into_inner();
+    form.validate()?;
+    let event = db(&pool, move |_connection| {
+        if form.is_published && !is_event_moderator(_connection, user_id)? {
+            form.is_published = false;
+        }
+        Event::create_new_event(_connection, form.into_new_event(user_id))
+    }).await?;
+    Ok(HttpResponse::Created().json(event))
+}
+
+// Ответ на изменение события: событие и список измененных полей
+#[derive(Serialize)]
+pub struct EventUpdate {
+    #[serde(flatten)]
+    pub event:   Event,
+    pub changes: Vec<EventChange>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct CancelForm {
+    pub reason: Option<String>,
+}
+
+// Изменение события. Если поменялись время, место, цена или название,
+// посетители и подписчики получают уведомление со списком изменений.
+// Автор, не являющийся модератором, может снять событие с публикации,
+// но не опубликовать его.
+pub async fn update_event(
+    session: Session,
+    pool: Data<DbPool>,
+    notifier: Data<Arc<Notifier>>,
+    _id: Path<i32>,
+    form: Json<EditEvent>,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let event_id = _id.into_inner();
+    let mut form = form.into_inner();
+    form.validate()?;
+    let notifier = notifier.get_ref().clone();
+    let (event, changes) = db(&pool, move |_connection| -> Result<_, Error> {
+        let current = get_own_event(_connection, event_id, user_id)?;
+        if form.is_published && !current.is_published && !is_event_moderator(_connection, user_id)? {
+            form.is_published = false;
+        }
+        let (event, changes) = Event::update_event_info(_connection, event_id, form)?;
+        if let Some(notice) = event_changed_notice(&event, &changes) {
+            // владелец сам внес изменения, ему уведомление не нужно
+            let user_ids: Vec<i32> = get_event_recipients(_connection, event_id)?
+                .into_iter()
+                .filter(|id| *id != user_id)
+                .collect();
+            notifier.notify(_connection, &user_ids, &notice)?;
+        }
+        Ok((event, changes))
+    }).await?;
+    Ok(HttpResponse::Ok().json(EventUpdate {
+        event:   event,
+        changes: changes,
+    }))
+}
+
+// Отмена события вместо удаления. Посетители и подписчики получают уведомление.
+pub async fn delete_event(
+    session: Session,
+    pool: Data<DbPool>,
+    notifier: Data<Arc<Notifier>>,
+    _id: Path<i32>,
+    form: Option<Json<CancelForm>>,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let event_id = _id.into_inner();
+    let reason = form
+        .map(Json::into_inner)
+        .unwrap_or_default()
+        .reason
+        .filter(|r| !r.trim().is_empty());
+    let notifier = notifier.get_ref().clone();
+    let event = db(&pool, move |_connection| -> Result<_, Error> {
+        get_own_event(_connection, event_id, user_id)?;
+        let event = Event::cancel_event(_connection, event_id, reason)?;
+        let user_ids: Vec<i32> = get_event_recipients(_connection, event_id)?
+            .into_iter()
+            .filter(|id| *id != user_id)
+            .collect();
+        notifier.notify(_connection, &user_ids, &event_cancelled_notice(&event))?;
+        Ok(event)
+    }).await?;
+    Ok(HttpResponse::Ok().json(event))
+}
+
+// На отмененное событие нельзя записаться или подписаться
+fn check_not_cancelled(event: &Event) -> Result<(), Error> {
+    if event.cancelled_at.is_some() {
+        return Err(Error::Conflict("event is cancelled".to_string()));
+    }
+    Ok(())
+}
+
+pub async fn event_attendees(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let event_id = _id.into_inner();
+    let user_id = get_session_user_id(&session).ok();
+    let attendees = db(&pool, move |_connection| {
+        get_visible_event(_connection, event_id, user_id)?;
+        EventAttendee::get_event_attendees_for_event(_connection, event_id)
+    }).await?;
+    Ok(HttpResponse::Ok().json(attendees))
+}
+
+// Тело запроса на запись. Категорию можно не указывать,
+// если у события нет категорий билетов.
+#[derive(Debug, Default, Deserialize)]
+pub struct AttendForm {
+    pub tier_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CheckInForm {
+    pub token: String,
+}
+
+// Билет пользователя: запись на событие, токен для входа
+// и место в листе ожидания, если билет еще не подтвержден
+#[derive(Serialize)]
+pub struct TicketDetail {
+    #[serde(flatten)]
+    pub attendee:          EventAttendee,
+    pub check_in_token:    Option<String>,
+    pub waitlist_position: Option<i64>,
+}
+
+fn ticket_detail(_connection: &PgConnection, attendee: EventAttendee) -> Result<TicketDetail, Error> {
+    Ok(TicketDetail {
+        waitlist_position: attendee.waitlist_position(_connection)?,
+        check_in_token:    attendee.check_in_token.clone(),
+        attendee:          attendee,
+    })
+}
+
+pub async fn attend_event(session: Session, pool: Data<DbPool>, _id: Path<i32>, form: Option<Json<AttendForm>>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let event_id = _id.into_inner();
+    let form = form.map(Json::into_inner).unwrap_or_default();
+    let ticket = db(&pool, move |_connection| {
+        check_not_cancelled(&get_visible_event(_connection, event_id, Some(user_id))?)?;
+        let attendee = EventAttendee::create_new_event_attendee(_connection, NewEventAttendee {
+            event_id: event_id,
+            user_id:  user_id,
+            tier_id:  form.tier_id,
+        })?;
+        ticket_detail(_connection, attendee)
+    }).await?;
+    Ok(HttpResponse::Created().json(ticket))
+}
+
+// Отмена записи. Освободившийся билет переходит первому из листа ожидания.
+pub async fn unattend_event(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let event_id = _id.into_inner();
+    db(&pool, move |_connection| {
+        EventAttendee::delete_for_user(_connection, event_id, user_id)
+    }).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub async fn event_ticket(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let event_id = _id.into_inner();
+    let ticket = db(&pool, move |_connection| {
+        let attendee = EventAttendee::get_for_user(_connection, event_id, user_id)?;
+        ticket_detail(_connection, attendee)
+    }).await?;
+    Ok(HttpResponse::Ok().json(ticket))
+}
+
+// Отметка о входе по токену из QR-кода. Доступна только владельцу события.
+pub async fn check_in_event(session: Session, pool: Data<DbPool>, _id: Path<i32>, form: Json<CheckInForm>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let event_id = _id.into_inner();
+    let token = form.into_inner().token;
+    let attendee = db(&pool, move |_connection| {
+        get_own_event(_connection, event_id, user_id)?;
+        EventAttendee::check_in(_connection, event_id, token.trim())
+    }).await?;
+    Ok(HttpResponse::Ok().json(attendee))
+}
+
+pub async fn event_tiers(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let event_id = _id.into_inner();
+    let user_id = get_session_user_id(&session).ok();
+    let tiers = db(&pool, move |_connection| {
+        get_visible_event(_connection, event_id, user_id)?;
+        EventTicketTier::get_for_event(_connection, event_id)
+    }).await?;
+    Ok(HttpResponse::Ok().json(tiers))
+}
+
+pub async fn create_tier(session: Session, pool: Data<DbPool>, _id: Path<i32>, form: Json<EditEventTicketTier>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let event_id = _id.into_inner();
+    let form = form.into_inner();
+    let tier = db(&pool, move |_connection| {
+        get_own_event(_connection, event_id, user_id)?;
+        EventTicketTier::create(_connection, NewEventTicketTier {
+            event_id:   event_id,
+            name:       form.name,
+            price:      form.price,
+            currency:   form.currency,
+            capacity:   form.capacity,
+            sale_start: form.sale_start,
+            sale_end:   form.sale_end,
+        })
+    }).await?;
+    Ok(HttpResponse::Created().json(tier))
+}
+
+// Категория билетов, принадлежащая событию владельца
+fn get_own_tier(_connection: &PgConnection, event_id: i32, tier_id: i32, user_id: i32) -> Result<EventTicketTier, Error> {
+    get_own_event(_connection, event_id, user_id)?;
+    let tier = EventTicketTier::get_by_id(_connection, tier_id)?;
+    if tier.event_id != event_id {
+        return Err(Error::NotFound);
+    }
+    Ok(tier)
+}
+
+pub async fn update_tier(session: Session, pool: Data<DbPool>, path: Path<(i32, i32)>, form: Json<EditEventTicketTier>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let (event_id, tier_id) = path.into_inner();
+    let form = form.into_inner();
+    let tier = db(&pool, move |_connection| {
+        get_own_tier(_connection, event_id, tier_id, user_id)?;
+        EventTicketTier::update(_connection, tier_id, form)
+    }).await?;
+    Ok(HttpResponse::Ok().json(tier))
+}
+
+pub async fn delete_tier(session: Session, pool: Data<DbPool>, path: Path<(i32, i32)>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let (event_id, tier_id) = path.into_inner();
+    db(&pool, move |_connection| {
+        get_own_tier(_connection, event_id, tier_id, user_id)?;
+        EventTicketTier::delete(_connection, tier_id)
+    }).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub async fn subscribe_event(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let event_id = _id.into_inner();
+    let subscriber = db(&pool, move |_connection| {
+        check_not_cancelled(&get_visible_event(_connection, event_id, Some(user_id))?)?;
+        EventSubscriber::create_new_event_subscriber(_connection, NewEventSubscriber {
+            event_id: event_id,
+            user_id:  user_id,
+        })
+    }).await?;
+    Ok(HttpResponse::Created().json(subscriber))
+}
+
+pub async fn unsubscribe_event(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let event_id = _id.into_inner();
+    let deleted = db(&pool, move |_connection| {
+        EventSubscriber::delete_for_user(_connection, event_id, user_id)
+    }).await?;
+    if !deleted {
+        return Err(Error::NotFound);
+    }
+    Ok(HttpResponse::NoContent().finish())
+}
+
+// Ответ с календарем. Content-Disposition нужен, чтобы браузер
+// предложил сохранить файл, а телефон - открыть его в календаре.
+fn ics_response(filename: &str, body: String) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/calendar; charset=utf-8")
+        .insert_header(("Content-Disposition", format!("attachment; filename=\"{}\"", filename)))
+        .body(body)
+}
+
+// Календарь из событий вместе с их датами из event_calendar
+// и правилами повторения с исключенными датами
+fn build_ics(_connection: &PgConnection, name: &str, events: Vec<Event>) -> Result<String, Error> {
+    let ids: Vec<i32> = events.iter().map(|e| e.id).collect();
+    let dates = EventCalendar::get_for_events(_connection, &ids)?;
+    let recurrences = EventRecurrence::get_for_events(_connection, &ids)?;
+    let recurrence_ids: Vec<i32> = recurrences.iter().map(|r| r.id).collect();
+    let exceptions = EventRecurrenceException::get_for_recurrences(_connection, &recurrence_ids)?;
+    Ok(events_to_ical(name, &events, &dates, &recurrences, &exceptions))
+}
+
+pub async fn event_ics(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let event_id = _id.into_inner();
+    let user_id = get_session_user_id(&session).ok();
+    let body = db(&pool, move |_connection| {
+        let event = get_visible_event(_connection, event_id, user_id)?;
+        let name = event.title.clone();
+        build_ics(_connection, &name, vec![event])
+    }).await?;
+    Ok(ics_response(&format!("event-{}.ics", event_id), body))
+}
+
+pub async fn theme_ics(pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let theme_id = _id.into_inner();
+    let body = db(&pool, move |_connection| {
+        let theme = EventTheme::get_theme_by_id(_connection, theme_id)?;
+        let theme_ids = EventTheme::get_descendant_ids(_connection, theme_id)?;
+        let events = Event::get_published_by_themes(_connection, &theme_ids)?;
+        build_ics(_connection, &theme.name, events)
+    }).await?;
+    Ok(ics_response(&format!("theme-{}.ics", theme_id), body))
+}
+
+// Календарь событий, на которые записан пользователь. Список чужих
+// посещений не отдаем.
+pub async fn user_ics(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    if user_id != _id.into_inner() {
+        return Err(Error::Forbidden);
+    }
+    let body = db(&pool, move |_connection| {
+        let events = Event::get_attended_by_user(_connection, user_id)?;
+        build_ics(_connection, "Мои события", events)
+    }).await?;
+    Ok(ics_response("my-events.ics", body))
+}
+
+#[derive(Serialize)]
+pub struct ImportResult {
+    pub created: Vec<i32>,
+}
+
+// utc_offset - смещение местного времени площадки от UTC в минутах,
+// нужно, если в файле время указано в UTC. city_id - город площадки
+// для событий без координат.
+#[derive(Debug, Deserialize)]
+pub struct EventImportQuery {
+    pub utc_offset: Option<i32>,
+    pub city_id:    Option<i32>,
+}
+
+// Загрузка программы площадки из .ics файла (тело запроса - сам файл).
+// Все события сохраняются в одной транзакции неопубликованными,
+// публикует их автор после проверки.
+pub async fn import_events(
+    session: Session,
+    pool: Data<DbPool>,
+    query: Query<EventImportQuery>,
+    body: Bytes,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let utc_offset = match query.utc_offset {
+        Some(minutes) => Some(
+            minutes
+                .checked_mul(60)
+                .and_then(FixedOffset::east_opt)
+                .ok_or_else(|| Error::Validation(format!("invalid utc_offset: {}", minutes)))?,
+        ),
+        None => None,
+    };
+    let text = std::str::from_utf8(&body)
+        .map_err(|_| Error::Validation("file must be UTF-8 encoded".to_string()))?;
+    let imported = parse_ical(text, utc_offset)?;
+
+    let mut forms = Vec::with_capacity(imported.len());
+    for item in imported {
+        let form = EditEvent {
+            title:         item.title,
+            description:   item.description,
+            category:      item.category,
+            date_start:    item.date_start,
+            date_end:      item.date_end,
+            location:      item.location,
+            ticket_price:  None,
+            currency:      None,
+            contact_name:  None,
+            contact_phone: None,
+            contact_email: None,
+            image:         None,
+            is_published:  false,
+            theme_id:      None,
+            coordinates:   item.geo,
+            city_id:       query.city_id,
+        };
+        form.validate()?;
+        // первая дата события и даты из RDATE становятся строками event_calendar
+        let mut dates = vec![item.date_start];
+        dates.extend(item.extra_dates);
+        let times = if item.all_day {
+            (None, None)
+        }
+        else {
+            let time_end = if item.date_end.date() == item.date_start.date() {
+                Some(item.date_end.time())
+            } else {
+                None
+            };
+            (Some(item.date_start.time()), time_end)
+        };
+        forms.push((form, dates, times));
+    }
+
+    let created = db(&pool, move |_connection| {
+        _connection.transaction::<_, Error, _>(|| {
+            let mut created = Vec::with_capacity(forms.len());
+            for (form, dates, (time_start, time_end)) in forms {
+                let event = Event::create_new_event(_connection, form.into_new_event(user_id))?;
+                for date in dates {
+                    EventCalendar::create_new_event_calendar(_connection, NewEventCalendar {
+                        event_id:   event.id,
+                        date:       date.date(),
+                        time_start: time_start,
+                        time_end:   time_end,
+                    })?;
+                }
+                created.push(event.id);
+            }
+            Ok(created)
+        })
+    }).await?;
+    Ok(HttpResponse::Created().json(ImportResult { created: created }))
+}
+
+// Параметры расписания: либо один день (date), либо промежуток времени
+// (date_from и date_to, только вместе с категорией)
+#[derive(Debug, Deserialize)]
+pub struct ScheduleQuery {
+    pub date:      Option<NaiveDate>,
+    pub date_from: Option<NaiveDateTime>,
+    pub date_to:   Option<NaiveDateTime>,
+    pub category:  Option<String>,
+}
+
+// Самый длинный промежуток для расписания - правила без конца
+// разворачиваются по дням, поэтому промежуток ограничен
+const MAX_SCHEDULE_DAYS: i64 = 92;
+
+pub async fn event_schedule(pool: Data<DbPool>, query: Query<ScheduleQuery>) -> Result<HttpResponse, Error> {
+    let query = query.into_inner();
+    let occurrences = db(&pool, move |_connection| -> Result<Vec<EventOccurrence>, Error> {
+        match (query.date, query.date_from, query.date_to, query.category) {
+            (Some(date), None, None, None) => get_events_scheduled_for_date(_connection, date),
+            (Some(date), None, None, Some(category)) => {
+                get_events_scheduled_for_date_in_category(_connection, date, &category)
+            },
+            (None, Some(from), Some(to), Some(category)) => {
+                if to < from || (to - from).num_days() > MAX_SCHEDULE_DAYS {
+                    return Err(Error::Validation(format!(
+                        "date_to must be after date_from and within {} days", MAX_SCHEDULE_DAYS
+                    )));
+                }
+                get_events_scheduled_in_time_interval_in_category(_connection, from, to, &category)
+            },
+            _ => Err(Error::Validation(
+                "either date or date_from, date_to and category are required".to_string()
+            )),
+        }
+    }).await?;
+    Ok(HttpResponse::Ok().json(occurrences))
+}
+
+// Форма правила повторения, event_id берется из пути
+#[derive(Debug, Deserialize)]
+pub struct RecurrenceForm {
+    pub freq:          String,
+    pub repeat_every:  Option<i16>,
+    pub weekdays:      Option<Vec<i16>>,
+    pub month_week:    Option<i16>,
+    pub month_weekday: Option<i16>,
+    pub date_start:    NaiveDate,
+    pub time_start:    Option<chrono::NaiveTime>,
+    pub time_end:      Option<chrono::NaiveTime>,
+    pub repeat_until:  Option<NaiveDate>,
+    pub repeat_count:  Option<i32>,
+}
+
+// Правило вместе с исключенными датами
+#[derive(Serialize)]
+pub struct RecurrenceDetail {
+    #[serde(flatten)]
+    pub recurrence: EventRecurrence,
+    pub exceptions: Vec<NaiveDate>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExceptionForm {
+    pub date: NaiveDate,
+}
+
+// Правило события, которое принадлежит пользователю
+fn get_own_recurrence(_connection: &PgConnection, event_id: i32, recurrence_id: i32, user_id: i32) -> Result<EventRecurrence, Error> {
+    get_own_event(_connection, event_id, user_id)?;
+    let recurrence = EventRecurrence::get_by_id(_connection, recurrence_id)?;
+    if recurrence.event_id != event_id {
+        return Err(Error::NotFound);
+    }
+    Ok(recurrence)
+}
+
+pub async fn event_recurrences(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let event_id = _id.into_inner();
+    let user_id = get_session_user_id(&session).ok();
+    let recurrences = db(&pool, move |_connection| -> Result<_, Error> {
+        get_visible_event(_connection, event_id, user_id)?;
+        let mut result = Vec::new();
+        for recurrence in EventRecurrence::get_for_event(_connection, event_id)? {
+            let exceptions = recurrence
+                .get_exceptions(_connection)?
+                .into_iter()
+                .map(|e| e.date)
+                .collect();
+            result.push(RecurrenceDetail {
+                recurrence: recurrence,
+                exceptions: exceptions,
+            });
+        }
+        Ok(result)
+    }).await?;
+    Ok(HttpResponse::Ok().json(recurrences))
+}
+
+pub async fn create_recurrence(session: Session, pool: Data<DbPool>, _id: Path<i32>, form: Json<RecurrenceForm>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let event_id = _id.into_inner();
+    let form = form.into_inner();
+    let new_recurrence = NewEventRecurrence {
+        event_id:      event_id,
+        freq:          form.freq,
+        repeat_every:  form.repeat_every.unwrap_or(1),
+        weekdays:      form.weekdays.unwrap_or_default(),
+        month_week:    form.month_week,
+        month_weekday: form.month_weekday,
+        date_start:    form.date_start,
+        time_start:    form.time_start,
+        time_end:      form.time_end,
+        repeat_until:  form.repeat_until,
+        repeat_count:  form.repeat_count,
+    };
+    new_recurrence.validate()?;
+    let recurrence = db(&pool, move |_connection| {
+        get_own_event(_connection, event_id, user_id)?;
+        EventRecurrence::create(_connection, new_recurrence)
+    }).await?;
+    Ok(HttpResponse::Created().json(recurrence))
+}
+
+pub async fn delete_recurrence(session: Session, pool: Data<DbPool>, path: Path<(i32, i32)>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let (event_id, recurrence_id) = path.into_inner();
+    db(&pool, move |_connection| {
+        get_own_recurrence(_connection, event_id, recurrence_id, user_id)?;
+        EventRecurrence::delete(_connection, recurrence_id)
+    }).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub async fn add_recurrence_exception(session: Session, pool: Data<DbPool>, path: Path<(i32, i32)>, form: Json<ExceptionForm>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let (event_id, recurrence_id) = path.into_inner();
+    let date = form.into_inner().date;
+    let exception: EventRecurrenceException = db(&pool, move |_connection| {
+        let recurrence = get_own_recurrence(_connection, event_id, recurrence_id, user_id)?;
+        recurrence.add_exception(_connection, date)
+    }).await?;
+    Ok(HttpResponse::Created().json(exception))
+}
+
+pub async fn remove_recurrence_exception(session: Session, pool: Data<DbPool>, path: Path<(i32, i32, NaiveDate)>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let (event_id, recurrence_id, date) = path.into_inner();
+    let deleted = db(&pool, move |_connection| {
+        let recurrence = get_own_recurrence(_connection, event_id, recurrence_id, user_id)?;
+        recurrence.remove_exception(_connection, date)
+    }).await?;
+    if !deleted {
+        return Err(Error::NotFound);
+    }
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,189 @@
+use actix_session::Session;
+use actix_web::{
+    web,
+    web::{Data, Json, Path, Query},
+    HttpResponse,
+};
+use serde::Deserialize;
+
+use crate::errors::Error;
+use crate::models::{ForumModel as Forum, ForumPost, ForumPostEditForm, ForumPostForm, ForumTopic};
+use crate::utils::{db, DbPool};
+use super::{get_session_user_id, PageResponse, Pagination};
+
+
+// Форумы и ветки обсуждений: ответы, цитаты, правки с историей и удаление.
+// Закрытые форумы и все их темы и сообщения видны только участникам.
+pub fn forum_routes(config: &mut web::ServiceConfig) {
+    config.route("/api/v1/forums/", web::get().to(list_forums));
+    config.route("/api/v1/forums/{id}/", web::get().to(get_forum));
+    config.route("/api/v1/forums/{id}/topics/", web::get().to(list_forum_topics));
+    config.route("/api/v1/forum/search/", web::get().to(search_posts));
+    config.route("/api/v1/forum/posts/latest/", web::get().to(latest_posts));
+    config.route("/api/v1/forum/topics/{id}/posts/", web::get().to(topic_posts));
+    config.route("/api/v1/forum/topics/{id}/posts/", web::post().to(create_post));
+    config.route("/api/v1/forum/posts/{id}/", web::get().to(get_post));
+    config.route("/api/v1/forum/posts/{id}/", web::put().to(edit_post));
+    config.route("/api/v1/forum/posts/{id}/", web::delete().to(delete_post));
+    config.route("/api/v1/forum/posts/{id}/history/", web::get().to(post_history));
+}
+
+// q - поиск по названию, category - фильтр по категории
+#[derive(Debug, Deserialize)]
+pub struct ForumsQuery {
+    pub q:        Option<String>,
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ThreadQuery {
+    pub page:  Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ForumSearchQuery {
+    pub q:     String,
+    pub page:  Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LatestPostsQuery {
+    pub limit: Option<i64>,
+}
+
+pub async fn list_forums(session: Session, pool: Data<DbPool>, query: Query<ForumsQuery>) -> Result<HttpResponse, Error> {
+    let viewer_id = get_session_user_id(&session).ok();
+    let query = query.into_inner();
+    let forums = db(&pool, move |_connection| -> Result<Vec<Forum>, Error> {
+        let forums = match query.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+            Some(q) => Forum::search_forums_by_name(q, viewer_id, _connection)?,
+            None => Forum::get_all_forums(viewer_id, _connection)?,
+        };
+        Ok(match query.category {
+            Some(ref category) => forums.into_iter().filter(|f| f.category.as_ref() == Some(category)).collect(),
+            None => forums,
+        })
+    }).await?;
+    Ok(HttpResponse::Ok().json(forums))
+}
+
+pub async fn get_forum(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let viewer_id = get_session_user_id(&session).ok();
+    let forum_id = _id.into_inner();
+    let forum = db(&pool, move |_connection| -> Result<Forum, Error> {
+        Forum::get_forum_by_id(forum_id, viewer_id, _connection)?.ok_or(Error::NotFound)
+    }).await?;
+    Ok(HttpResponse::Ok().json(forum))
+}
+
+// Темы форума, недавно обновленные первыми
+pub async fn list_forum_topics(
+    session: Session,
+    pool: Data<DbPool>,
+    _id: Path<i32>,
+    query: Query<ThreadQuery>,
+) -> Result<HttpResponse, Error> {
+    let viewer_id = get_session_user_id(&session).ok();
+    let forum_id = _id.into_inner();
+    let pagination = Pagination::new(query.page, query.limit);
+    let mut topics = db(&pool, move |_connection| {
+        ForumTopic::get_topics_by_forum_id(forum_id, viewer_id, _connection)
+    }).await?;
+    topics.sort_by_key(|topic| std::cmp::Reverse(topic.updated_at));
+    Ok(HttpResponse::Ok().json(PageResponse::from_vec(topics, pagination)))
+}
+
+// Поиск по тексту сообщений в доступных пользователю форумах
+pub async fn search_posts(session: Session, pool: Data<DbPool>, query: Query<ForumSearchQuery>) -> Result<HttpResponse, Error> {
+    let viewer_id = get_session_user_id(&session).ok();
+    let pagination = Pagination::new(query.page, query.limit);
+    let q = query.q.trim().to_string();
+    if q.is_empty() {
+        return Err(Error::Validation("search query is empty".to_string()));
+    }
+    let posts = db(&pool, move |_connection| ForumPost::search_posts_by_keyword(&q, viewer_id, _connection)).await?;
+    Ok(HttpResponse::Ok().json(PageResponse::from_vec(posts, pagination)))
+}
+
+pub async fn latest_posts(session: Session, pool: Data<DbPool>, query: Query<LatestPostsQuery>) -> Result<HttpResponse, Error> {
+    let viewer_id = get_session_user_id(&session).ok();
+    let limit = Pagination::new(None, query.limit).limit;
+    let posts = db(&pool, move |_connection| ForumPost::get_latest_posts(limit, viewer_id, _connection)).await?;
+    Ok(HttpResponse::Ok().json(posts))
+}
+
+// Страница - сообщения верхнего уровня вместе со всеми ответами на них
+pub async fn topic_posts(
+    session: Session,
+    pool: Data<DbPool>,
+    _id: Path<i32>,
+    query: Query<ThreadQuery>,
+) -> Result<HttpResponse, Error> {
+    let viewer_id = get_session_user_id(&session).ok();
+    let topic_id = _id.into_inner();
+    let pagination = Pagination::new(query.page, query.limit);
+    let (items, total) = db(&pool, move |_connection| {
+        ForumPost::get_thread(_connection, topic_id, viewer_id, pagination.offset(), pagination.limit)
+    }).await?;
+    Ok(HttpResponse::Ok().json(PageResponse::new(items, pagination, total)))
+}
+
+// parent_id - ответ на сообщение, quote: {post_id, text} - цитата из сообщения
+pub async fn create_post(
+    session: Session,
+    pool: Data<DbPool>,
+    _id: Path<i32>,
+    data: Json<ForumPostForm>,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let topic_id = _id.into_inner();
+    let form = data.into_inner();
+    let post = db(&pool, move |_connection| {
+        ForumPost::create_in_topic(_connection, topic_id, user_id, &form)?.into_detail(_connection)
+    }).await?;
+    Ok(HttpResponse::Created().json(post))
+}
+
+pub async fn get_post(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let viewer_id = get_session_user_id(&session).ok();
+    let post_id = _id.into_inner();
+    let post = db(&pool, move |_connection| {
+        ForumPost::get_visible(_connection, post_id, viewer_id)?.into_detail(_connection)
+    }).await?;
+    Ok(HttpResponse::Ok().json(post))
+}
+
+pub async fn edit_post(
+    session: Session,
+    pool: Data<DbPool>,
+    _id: Path<i32>,
+    data: Json<ForumPostEditForm>,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let post_id = _id.into_inner();
+    let form = data.into_inner();
+    let post = db(&pool, move |_connection| {
+        ForumPost::edit(_connection, post_id, user_id, &form.content)?.into_detail(_connection)
+    }).await?;
+    Ok(HttpResponse::Ok().json(post))
+}
+
+// Сообщение скрывается, но остается в ветке
+pub async fn delete_post(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let post_id = _id.into_inner();
+    let post = db(&pool, move |_connection| {
+        ForumPost::soft_delete(_connection, post_id, user_id)?.into_detail(_connection)
+    }).await?;
+    Ok(HttpResponse::Ok().json(post))
+}
+
+// Все версии сообщения с отличиями каждой от предыдущей
+pub async fn post_history(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let viewer_id = get_session_user_id(&session).ok();
+    let post_id = _id.into_inner();
+    let versions = db(&pool, move |_connection| ForumPost::get_history(_connection, post_id, viewer_id)).await?;
+    Ok(HttpResponse::Ok().json(versions))
+}
@@ -0,0 +1,174 @@
+use actix_session::Session;
+use actix_web::{
+    web,
+    web::{Data, Json, Path, Query},
+    HttpResponse,
+};
+use serde::Deserialize;
+
+use crate::errors::Error;
+use crate::models::{
+    add_forum_member,
+    get_forum_members,
+    remove_member,
+    ForumInvite,
+    ForumJoinRequest,
+    InviteForm,
+    JoinRequestForm,
+    JOIN_APPROVED,
+    JOIN_PENDING,
+    JOIN_REJECTED,
+};
+use crate::utils::{db, DbPool};
+use super::{get_session_user_id, PageResponse, Pagination};
+
+
+// Участники закрытых форумов: состав, приглашения по ссылке и заявки на вступление
+pub fn forum_members_routes(config: &mut web::ServiceConfig) {
+    config.route("/api/v1/forums/{id}/members/", web::get().to(list_forum_members));
+    config.route("/api/v1/forums/{id}/members/{user_id}/", web::put().to(put_member));
+    config.route("/api/v1/forums/{id}/members/{user_id}/", web::delete().to(delete_member));
+    config.route("/api/v1/forums/{id}/invites/", web::get().to(list_invites));
+    config.route("/api/v1/forums/{id}/invites/", web::post().to(create_invite));
+    config.route("/api/v1/forums/{id}/invites/{invite_id}/", web::delete().to(revoke_invite));
+    config.route("/api/v1/forum/invites/{token}/accept/", web::post().to(accept_invite));
+    config.route("/api/v1/forums/{id}/join-requests/", web::get().to(list_join_requests));
+    config.route("/api/v1/forums/{id}/join-requests/", web::post().to(create_join_request));
+    config.route("/api/v1/forum/join-requests/{id}/approve/", web::post().to(approve_join_request));
+    config.route("/api/v1/forum/join-requests/{id}/reject/", web::post().to(reject_join_request));
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MembersQuery {
+    pub page:  Option<i64>,
+    pub limit: Option<i64>,
+}
+
+// status - pending (по умолчанию), approved или rejected
+#[derive(Debug, Deserialize)]
+pub struct JoinRequestsQuery {
+    pub status: Option<String>,
+    pub page:   Option<i64>,
+    pub limit:  Option<i64>,
+}
+
+pub async fn list_forum_members(
+    session: Session,
+    pool: Data<DbPool>,
+    _id: Path<i32>,
+    query: Query<MembersQuery>,
+) -> Result<HttpResponse, Error> {
+    let viewer_id = get_session_user_id(&session).ok();
+    let forum_id = _id.into_inner();
+    let pagination = Pagination::new(query.page, query.limit);
+    let (items, total) = db(&pool, move |_connection| {
+        get_forum_members(_connection, forum_id, viewer_id, pagination.offset(), pagination.limit)
+    }).await?;
+    Ok(HttpResponse::Ok().json(PageResponse::new(items, pagination, total)))
+}
+
+// Добавление участника. Роли назначаются через /api/v1/forums/{id}/moderators/
+pub async fn put_member(session: Session, pool: Data<DbPool>, path: Path<(i32, i32)>) -> Result<HttpResponse, Error> {
+    let actor_id = get_session_user_id(&session)?;
+    let (forum_id, user_id) = path.into_inner();
+    let member = db(&pool, move |_connection| add_forum_member(_connection, forum_id, actor_id, user_id)).await?;
+    Ok(HttpResponse::Ok().json(member))
+}
+
+// Удаление участника или выход из форума, если user_id - свой
+pub async fn delete_member(session: Session, pool: Data<DbPool>, path: Path<(i32, i32)>) -> Result<HttpResponse, Error> {
+    let actor_id = get_session_user_id(&session)?;
+    let (forum_id, user_id) = path.into_inner();
+    db(&pool, move |_connection| remove_member(_connection, forum_id, actor_id, user_id)).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub async fn list_invites(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let forum_id = _id.into_inner();
+    let now = chrono::Local::now().naive_local();
+    let invites = db(&pool, move |_connection| ForumInvite::get_active(_connection, forum_id, user_id, now)).await?;
+    Ok(HttpResponse::Ok().json(invites))
+}
+
+// expires_days и max_uses необязательны
+pub async fn create_invite(
+    session: Session,
+    pool: Data<DbPool>,
+    _id: Path<i32>,
+    data: Json<InviteForm>,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let forum_id = _id.into_inner();
+    let form = data.into_inner();
+    let now = chrono::Local::now().naive_local();
+    let invite = db(&pool, move |_connection| ForumInvite::create(_connection, forum_id, user_id, &form, now)).await?;
+    Ok(HttpResponse::Created().json(invite))
+}
+
+pub async fn revoke_invite(session: Session, pool: Data<DbPool>, path: Path<(i32, i32)>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let (forum_id, invite_id) = path.into_inner();
+    let now = chrono::Local::now().naive_local();
+    let invite = db(&pool, move |_connection| ForumInvite::revoke(_connection, forum_id, invite_id, user_id, now)).await?;
+    Ok(HttpResponse::Ok().json(invite))
+}
+
+pub async fn accept_invite(session: Session, pool: Data<DbPool>, token: Path<String>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let token = token.into_inner();
+    let now = chrono::Local::now().naive_local();
+    let member = db(&pool, move |_connection| ForumInvite::accept(_connection, &token, user_id, now)).await?;
+    Ok(HttpResponse::Ok().json(member))
+}
+
+pub async fn list_join_requests(
+    session: Session,
+    pool: Data<DbPool>,
+    _id: Path<i32>,
+    query: Query<JoinRequestsQuery>,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let forum_id = _id.into_inner();
+    let status = match query.status.as_deref() {
+        None | Some(JOIN_PENDING) => JOIN_PENDING,
+        Some(JOIN_APPROVED) => JOIN_APPROVED,
+        Some(JOIN_REJECTED) => JOIN_REJECTED,
+        Some(status) => return Err(Error::Validation(format!("unknown status: {}", status))),
+    };
+    let pagination = Pagination::new(query.page, query.limit);
+    let (items, total) = db(&pool, move |_connection| {
+        ForumJoinRequest::get_for_forum(_connection, forum_id, user_id, status, pagination.offset(), pagination.limit)
+    }).await?;
+    Ok(HttpResponse::Ok().json(PageResponse::new(items, pagination, total)))
+}
+
+pub async fn create_join_request(
+    session: Session,
+    pool: Data<DbPool>,
+    _id: Path<i32>,
+    data: Json<JoinRequestForm>,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let forum_id = _id.into_inner();
+    let form = data.into_inner();
+    let request = db(&pool, move |_connection| ForumJoinRequest::create(_connection, forum_id, user_id, &form)).await?;
+    Ok(HttpResponse::Created().json(request))
+}
+
+async fn decide_join_request(session: Session, pool: Data<DbPool>, request_id: i32, approve: bool) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let now = chrono::Local::now().naive_local();
+    let request = db(&pool, move |_connection| {
+        ForumJoinRequest::decide(_connection, request_id, user_id, approve, now)
+    }).await?;
+    Ok(HttpResponse::Ok().json(request))
+}
+
+pub async fn approve_join_request(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    decide_join_request(session, pool, _id.into_inner(), true).await
+}
+
+pub async fn reject_join_request(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    decide_join_request(session, pool, _id.into_inner(), false).await
+}
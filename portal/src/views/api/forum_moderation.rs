@@ -0,0 +1,216 @@
+use actix_session::Session;
+use actix_web::{
+    web,
+    web::{Data, Json, Path, Query},
+    HttpResponse,
+};
+use serde::Deserialize;
+
+use crate::errors::Error;
+use crate::models::{
+    check_forum_access,
+    check_forum_moderator,
+    get_active_bans,
+    get_forum_moderators,
+    get_moderation_log,
+    lift_ban,
+    remove_forum_role,
+    set_forum_role,
+    set_topic_locked,
+    ForumReport,
+    ReportForm,
+    ResolveForm,
+    REPORT_DISMISSED,
+    REPORT_PENDING,
+    REPORT_RESOLVED,
+};
+use crate::utils::{db, DbPool};
+use super::{get_session_user_id, PageResponse, Pagination};
+
+
+// Модерация форума: жалобы и очередь, закрытие тем, баны, модераторы и журнал
+pub fn forum_moderation_routes(config: &mut web::ServiceConfig) {
+    config.route("/api/v1/forum/posts/{id}/reports/", web::post().to(report_post));
+    config.route("/api/v1/forum/reports/{id}/resolve/", web::post().to(resolve_report));
+    config.route("/api/v1/forum/topics/{id}/lock/", web::post().to(lock_topic));
+    config.route("/api/v1/forum/topics/{id}/unlock/", web::post().to(unlock_topic));
+    config.route("/api/v1/forums/{id}/moderation/queue/", web::get().to(forum_moderation_queue));
+    config.route("/api/v1/forums/{id}/moderation/log/", web::get().to(forum_moderation_log));
+    config.route("/api/v1/forums/{id}/bans/", web::get().to(list_forum_bans));
+    config.route("/api/v1/forums/{id}/bans/{ban_id}/", web::delete().to(delete_ban));
+    config.route("/api/v1/forums/{id}/moderators/", web::get().to(list_forum_moderators));
+    config.route("/api/v1/forums/{id}/moderators/{user_id}/", web::put().to(put_moderator));
+    config.route("/api/v1/forums/{id}/moderators/{user_id}/", web::delete().to(delete_moderator));
+}
+
+// status - pending (по умолчанию), resolved или dismissed
+#[derive(Debug, Deserialize)]
+pub struct ModerationQueueQuery {
+    pub status: Option<String>,
+    pub page:   Option<i64>,
+    pub limit:  Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ModerationLogQuery {
+    pub page:  Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LockForm {
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ModeratorRoleForm {
+    pub role: String,
+}
+
+pub async fn report_post(
+    session: Session,
+    pool: Data<DbPool>,
+    _id: Path<i32>,
+    data: Json<ReportForm>,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let post_id = _id.into_inner();
+    let form = data.into_inner();
+    let report = db(&pool, move |_connection| ForumReport::create(_connection, post_id, user_id, &form)).await?;
+    Ok(HttpResponse::Created().json(report))
+}
+
+pub async fn forum_moderation_queue(
+    session: Session,
+    pool: Data<DbPool>,
+    _id: Path<i32>,
+    query: Query<ModerationQueueQuery>,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let forum_id = _id.into_inner();
+    let status = match query.status.as_deref() {
+        None | Some(REPORT_PENDING) => REPORT_PENDING,
+        Some(REPORT_RESOLVED) => REPORT_RESOLVED,
+        Some(REPORT_DISMISSED) => REPORT_DISMISSED,
+        Some(status) => return Err(Error::Validation(format!("unknown status: {}", status))),
+    };
+    let pagination = Pagination::new(query.page, query.limit);
+    let (items, total) = db(&pool, move |_connection| {
+        check_forum_moderator(_connection, user_id, forum_id)?;
+        ForumReport::get_queue(_connection, forum_id, status, pagination.offset(), pagination.limit)
+    }).await?;
+    Ok(HttpResponse::Ok().json(PageResponse::new(items, pagination, total)))
+}
+
+// action: dismiss, hide_post, lock_topic, warn_user или ban_user (с ban_days)
+pub async fn resolve_report(
+    session: Session,
+    pool: Data<DbPool>,
+    _id: Path<i32>,
+    data: Json<ResolveForm>,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let report_id = _id.into_inner();
+    let form = data.into_inner();
+    let report = db(&pool, move |_connection| ForumReport::resolve(_connection, report_id, user_id, &form)).await?;
+    Ok(HttpResponse::Ok().json(report))
+}
+
+async fn set_locked(
+    session: Session,
+    pool: Data<DbPool>,
+    topic_id: i32,
+    locked: bool,
+    reason: Option<String>,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let topic = db(&pool, move |_connection| {
+        set_topic_locked(_connection, topic_id, user_id, locked, reason.as_deref(), None)
+    }).await?;
+    Ok(HttpResponse::Ok().json(topic))
+}
+
+pub async fn lock_topic(
+    session: Session,
+    pool: Data<DbPool>,
+    _id: Path<i32>,
+    data: Option<Json<LockForm>>,
+) -> Result<HttpResponse, Error> {
+    let reason = data.and_then(|data| data.into_inner().reason);
+    set_locked(session, pool, _id.into_inner(), true, reason).await
+}
+
+pub async fn unlock_topic(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    set_locked(session, pool, _id.into_inner(), false, None).await
+}
+
+// Действующие баны форума
+pub async fn list_forum_bans(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let forum_id = _id.into_inner();
+    let now = chrono::Local::now().naive_local();
+    let bans = db(&pool, move |_connection| {
+        check_forum_moderator(_connection, user_id, forum_id)?;
+        get_active_bans(_connection, forum_id, now)
+    }).await?;
+    Ok(HttpResponse::Ok().json(bans))
+}
+
+// Досрочное снятие бана
+pub async fn delete_ban(session: Session, pool: Data<DbPool>, path: Path<(i32, i32)>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let (forum_id, ban_id) = path.into_inner();
+    let now = chrono::Local::now().naive_local();
+    let ban = db(&pool, move |_connection| lift_ban(_connection, forum_id, ban_id, user_id, now)).await?;
+    Ok(HttpResponse::Ok().json(ban))
+}
+
+// Модераторов форума видят все, кто может читать форум
+pub async fn list_forum_moderators(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let viewer_id = get_session_user_id(&session).ok();
+    let forum_id = _id.into_inner();
+    let moderators = db(&pool, move |_connection| {
+        check_forum_access(_connection, forum_id, viewer_id)?;
+        get_forum_moderators(_connection, forum_id)
+    }).await?;
+    Ok(HttpResponse::Ok().json(moderators))
+}
+
+// role: owner, lead или moderator
+pub async fn put_moderator(
+    session: Session,
+    pool: Data<DbPool>,
+    path: Path<(i32, i32)>,
+    data: Json<ModeratorRoleForm>,
+) -> Result<HttpResponse, Error> {
+    let actor_id = get_session_user_id(&session)?;
+    let (forum_id, user_id) = path.into_inner();
+    let form = data.into_inner();
+    let moderator = db(&pool, move |_connection| {
+        set_forum_role(_connection, forum_id, actor_id, user_id, &form.role)
+    }).await?;
+    Ok(HttpResponse::Ok().json(moderator))
+}
+
+pub async fn delete_moderator(session: Session, pool: Data<DbPool>, path: Path<(i32, i32)>) -> Result<HttpResponse, Error> {
+    let actor_id = get_session_user_id(&session)?;
+    let (forum_id, user_id) = path.into_inner();
+    db(&pool, move |_connection| remove_forum_role(_connection, forum_id, actor_id, user_id)).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub async fn forum_moderation_log(
+    session: Session,
+    pool: Data<DbPool>,
+    _id: Path<i32>,
+    query: Query<ModerationLogQuery>,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let forum_id = _id.into_inner();
+    let pagination = Pagination::new(query.page, query.limit);
+    let (items, total) = db(&pool, move |_connection| {
+        check_forum_moderator(_connection, user_id, forum_id)?;
+        get_moderation_log(_connection, forum_id, pagination.offset(), pagination.limit)
+    }).await?;
+    Ok(HttpResponse::Ok().json(PageResponse::new(items, pagination, total)))
+}
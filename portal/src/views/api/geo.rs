@@ -0,0 +1,71 @@
+use actix_web::{
+    web,
+    web::{Data, Query},
+    HttpResponse,
+};
+use serde::Deserialize;
+
+use crate::errors::Error;
+use crate::models::{
+    find_in_bbox,
+    find_nearby,
+    geo_point,
+    BoundingBox,
+    NearbyKind,
+    DEFAULT_RADIUS,
+    MAX_RADIUS,
+};
+use crate::utils::{db, DbPool};
+
+
+pub fn geo_routes(config: &mut web::ServiceConfig) {
+    config.route("/api/v1/nearby", web::get().to(nearby));
+    config.route("/api/v1/nearby/", web::get().to(nearby));
+}
+
+// Параметры поиска рядом: точка и радиус в метрах или прямоугольник
+// bbox=minLon,minLat,maxLon,maxLat. kinds - виды объектов через запятую.
+#[derive(Debug, Deserialize)]
+pub struct NearbyQuery {
+    pub lat:    Option<f64>,
+    pub lon:    Option<f64>,
+    pub radius: Option<f64>,
+    pub bbox:   Option<String>,
+    pub kinds:  Option<String>,
+    pub limit:  Option<i64>,
+}
+
+const DEFAULT_NEARBY_LIMIT: i64 = 50;
+const MAX_NEARBY_LIMIT: i64 = 500;
+
+pub async fn nearby(pool: Data<DbPool>, query: Query<NearbyQuery>) -> Result<HttpResponse, Error> {
+    let query = query.into_inner();
+    let kinds = NearbyKind::parse_list(query.kinds.as_deref())?;
+    let limit = query.limit.unwrap_or(DEFAULT_NEARBY_LIMIT).clamp(1, MAX_NEARBY_LIMIT);
+    let center = match (query.lat, query.lon) {
+        (Some(lat), Some(lon)) => Some(geo_point(lat, lon)?),
+        (None, None) => None,
+        _ => return Err(Error::Validation("lat and lon must be given together".to_string())),
+    };
+    let bbox = match query.bbox {
+        Some(ref bbox) => Some(BoundingBox::parse(bbox)?),
+        None => None,
+    };
+    // с прямоугольником радиус не применяется, молча его не игнорируем
+    if bbox.is_some() && query.radius.is_some() {
+        return Err(Error::Validation("radius cannot be combined with bbox".to_string()));
+    }
+    let radius = query.radius.unwrap_or(DEFAULT_RADIUS);
+    if radius <= 0.0 || radius > MAX_RADIUS {
+        return Err(Error::Validation(format!("radius must be between 0 and {}", MAX_RADIUS)));
+    }
+
+    let items = db(&pool, move |_connection| {
+        match (bbox, center) {
+            (Some(bbox), center) => find_in_bbox(_connection, &bbox, center.as_ref(), &kinds, limit),
+            (None, Some(center)) => find_nearby(_connection, &center, radius, &kinds, limit),
+            (None, None) => Err(Error::Validation("lat and lon or bbox are required".to_string())),
+        }
+    }).await?;
+    Ok(HttpResponse::Ok().json(items))
+}
@@ -0,0 +1,78 @@
+use actix_session::Session;
+use actix_web::{
+    web,
+    web::{Data, Json, Query},
+    HttpResponse,
+};
+use serde::Deserialize;
+
+use crate::errors::Error;
+use crate::models::{
+    geo_point,
+    geocode,
+    reverse_geocode,
+    RequestModel as Request,
+    RequestForm,
+    UserPoint,
+    UserPointForm,
+    DEFAULT_GEOCODE_LIMIT,
+    MAX_GEOCODE_LIMIT,
+};
+use crate::utils::{db, DbPool};
+use super::get_session_user_id;
+
+
+// Поиск адресов по реестру улиц и создание объектов по адресу
+pub fn geocoder_routes(config: &mut web::ServiceConfig) {
+    config.route("/api/v1/geocode/", web::get().to(geocode_address));
+    config.route("/api/v1/reverse-geocode/", web::get().to(reverse_geocode_point));
+    config.route("/api/v1/user-points/", web::post().to(create_user_point));
+    config.route("/api/v1/requests/", web::post().to(create_request));
+}
+
+// q - адрес в свободной форме: "ул. Ленина, 12к1", "пр-т Мира 5а, Москва"
+#[derive(Debug, Deserialize)]
+pub struct GeocodeQuery {
+    pub q:       String,
+    pub city_id: Option<i32>,
+    pub limit:   Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReverseGeocodeQuery {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+pub async fn geocode_address(pool: Data<DbPool>, query: Query<GeocodeQuery>) -> Result<HttpResponse, Error> {
+    let query = query.into_inner();
+    let limit = query.limit.unwrap_or(DEFAULT_GEOCODE_LIMIT).clamp(1, MAX_GEOCODE_LIMIT);
+    let items = db(&pool, move |_connection| geocode(_connection, &query.q, query.city_id, limit)).await?;
+    Ok(HttpResponse::Ok().json(items))
+}
+
+pub async fn reverse_geocode_point(pool: Data<DbPool>, query: Query<ReverseGeocodeQuery>) -> Result<HttpResponse, Error> {
+    let point = geo_point(query.lat, query.lon)?;
+    let found = db(&pool, move |_connection| reverse_geocode(_connection, &point)).await?;
+    match found {
+        Some(found) => Ok(HttpResponse::Ok().json(found)),
+        None => Err(Error::NotFound),
+    }
+}
+
+// Улица точки находится по адресу или по отметке на карте
+pub async fn create_user_point(session: Session, pool: Data<DbPool>, data: Json<UserPointForm>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let form = data.into_inner();
+    let point = db(&pool, move |_connection| UserPoint::create_at_address(_connection, user_id, &form)).await?;
+    Ok(HttpResponse::Created().json(point))
+}
+
+pub async fn create_request(session: Session, pool: Data<DbPool>, data: Json<RequestForm>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let form = data.into_inner();
+    let request = db(&pool, move |_connection| -> Result<Request, Error> {
+        Request::create_at_address(_connection, user_id, &form)
+    }).await?;
+    Ok(HttpResponse::Created().json(request))
+}
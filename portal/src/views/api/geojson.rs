@@ -0,0 +1,67 @@
+use actix_session::Session;
+use actix_web::{
+    http::header,
+    web,
+    web::{Data, Json, Path, Query},
+    HttpResponse,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::errors::Error;
+use crate::models::{
+    export_city_geojson,
+    export_district_geojson,
+    import_geojson,
+};
+use crate::utils::{db, DbPool};
+use super::get_session_user_id;
+use super::organizations::check_administrator;
+
+
+pub fn geojson_routes(config: &mut web::ServiceConfig) {
+    config.route("/api/v1/geojson/import/", web::post().to(import_city_data));
+    config.route("/api/v1/cities/{id}/geojson/", web::get().to(city_geojson));
+    config.route("/api/v1/districts/{id}/geojson/", web::get().to(district_geojson));
+}
+
+fn geojson_response(collection: Value) -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header((header::CONTENT_TYPE, "application/geo+json"))
+        .body(collection.to_string())
+}
+
+pub async fn city_geojson(pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let city_id = _id.into_inner();
+    let collection = db(&pool, move |_connection| export_city_geojson(_connection, city_id)).await?;
+    Ok(geojson_response(collection))
+}
+
+pub async fn district_geojson(pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let district_id = _id.into_inner();
+    let collection = db(&pool, move |_connection| export_district_geojson(_connection, district_id)).await?;
+    Ok(geojson_response(collection))
+}
+
+// Загрузка доступна только администраторам.
+// dry_run=true - только отчет, без записи в базу
+#[derive(Debug, Deserialize)]
+pub struct ImportQuery {
+    pub dry_run: Option<bool>,
+}
+
+pub async fn import_city_data(
+    session: Session,
+    pool: Data<DbPool>,
+    query: Query<ImportQuery>,
+    data: Json<Value>,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let dry_run = query.dry_run.unwrap_or(false);
+    let collection = data.into_inner();
+    let report = db(&pool, move |_connection| {
+        check_administrator(_connection, user_id)?;
+        import_geojson(_connection, &collection, user_id, dry_run)
+    }).await?;
+    Ok(HttpResponse::Ok().json(report))
+}
@@ -0,0 +1,76 @@
+use actix_session::Session;
+use actix_web::{
+    http::header,
+    web,
+    web::{Data, Json, Path, Query},
+    HttpResponse,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::errors::Error;
+use crate::models::{get_tile, parse_tile_layers, District, Street};
+use crate::utils::{db, DbPool};
+use super::organizations::check_administrator;
+use super::get_session_user_id;
+
+
+// Линии улиц, границы районов и плитки GeoJSON для карты
+pub fn geometry_routes(config: &mut web::ServiceConfig) {
+    config.route("/api/v1/streets/{id}/geometry/", web::put().to(set_street_geometry));
+    config.route("/api/v1/districts/{id}/boundary/", web::put().to(set_district_boundary));
+    config.route(r"/api/v1/tiles/{z:\d+}/{x:\d+}/{y:\d+}.geojson", web::get().to(tile));
+}
+
+// Плитки меняются редко, браузер и прокси могут держать их пять минут
+const TILE_MAX_AGE: u32 = 300;
+
+// В теле - GeoJSON-геометрия, LineString или MultiLineString
+pub async fn set_street_geometry(
+    session: Session,
+    pool: Data<DbPool>,
+    _id: Path<i32>,
+    data: Json<Value>,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let street_id = _id.into_inner();
+    let geometry = data.into_inner();
+    let street = db(&pool, move |_connection| -> Result<_, Error> {
+        check_administrator(_connection, user_id)?;
+        Street::set_geometry(_connection, street_id, &geometry)
+    }).await?;
+    Ok(HttpResponse::Ok().json(street))
+}
+
+// В теле - GeoJSON-геометрия, Polygon или MultiPolygon
+pub async fn set_district_boundary(
+    session: Session,
+    pool: Data<DbPool>,
+    _id: Path<i32>,
+    data: Json<Value>,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let district_id = _id.into_inner();
+    let geometry = data.into_inner();
+    let district = db(&pool, move |_connection| -> Result<_, Error> {
+        check_administrator(_connection, user_id)?;
+        District::set_boundary(_connection, district_id, &geometry)
+    }).await?;
+    Ok(HttpResponse::Ok().json(district))
+}
+
+// layers - слои через запятую: districts, streets, points (по умолчанию все)
+#[derive(Debug, Deserialize)]
+pub struct TileQuery {
+    pub layers: Option<String>,
+}
+
+pub async fn tile(pool: Data<DbPool>, path: Path<(u32, u32, u32)>, query: Query<TileQuery>) -> Result<HttpResponse, Error> {
+    let (z, x, y) = path.into_inner();
+    let layers = parse_tile_layers(query.layers.as_deref())?;
+    let collection = db(&pool, move |_connection| get_tile(_connection, z, x, y, &layers)).await?;
+    Ok(HttpResponse::Ok()
+        .insert_header((header::CONTENT_TYPE, "application/geo+json"))
+        .insert_header((header::CACHE_CONTROL, format!("public, max-age={}", TILE_MAX_AGE)))
+        .body(collection.to_string()))
+}
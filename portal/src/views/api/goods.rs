@@ -0,0 +1,117 @@
+use actix_session::Session;
+use actix_web::{
+    web,
+    web::{Data, Json, Path, Query},
+    HttpResponse,
+};
+use bigdecimal::BigDecimal;
+use serde::Deserialize;
+
+use crate::errors::Error;
+use crate::models::{search_goods, Goods, GoodsCategory, GoodsForm, GoodsSearch, NewGoodsCategory};
+use crate::utils::{db, DbPool};
+use super::organizations::check_administrator;
+use super::{get_session_user_id, PageResponse, Pagination};
+
+
+// Каталог товаров и услуг организаций и поиск по ценам в городе
+pub fn goods_routes(config: &mut web::ServiceConfig) {
+    config.route("/api/v1/organizations/{id}/goods/", web::get().to(organization_goods));
+    config.route("/api/v1/organizations/{id}/goods/", web::post().to(create_goods));
+    config.route("/api/v1/goods/categories/", web::get().to(goods_categories));
+    config.route("/api/v1/goods/categories/", web::post().to(create_goods_category));
+    config.route("/api/v1/goods/{id}/", web::get().to(get_goods));
+    config.route("/api/v1/goods/{id}/", web::put().to(update_goods));
+    config.route("/api/v1/goods/{id}/", web::delete().to(delete_goods));
+    config.route("/api/v1/cities/{id}/goods/search/", web::get().to(search_city_goods));
+}
+
+pub async fn organization_goods(pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let organization_id = _id.into_inner();
+    let items = db(&pool, move |_connection| Goods::get_for_organization(_connection, organization_id)).await?;
+    Ok(HttpResponse::Ok().json(items))
+}
+
+pub async fn create_goods(session: Session, pool: Data<DbPool>, _id: Path<i32>, data: Json<GoodsForm>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let organization_id = _id.into_inner();
+    let form = data.into_inner();
+    let goods = db(&pool, move |_connection| {
+        Goods::create_for_organization(_connection, organization_id, user_id, &form)?.into_detail(_connection)
+    }).await?;
+    Ok(HttpResponse::Created().json(goods))
+}
+
+pub async fn get_goods(pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let goods_id = _id.into_inner();
+    let goods = db(&pool, move |_connection| Goods::read_by_id(goods_id, _connection)?.into_detail(_connection)).await?;
+    Ok(HttpResponse::Ok().json(goods))
+}
+
+pub async fn update_goods(session: Session, pool: Data<DbPool>, _id: Path<i32>, data: Json<GoodsForm>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let goods_id = _id.into_inner();
+    let form = data.into_inner();
+    let goods = db(&pool, move |_connection| {
+        Goods::update_for_owner(_connection, goods_id, user_id, &form)?.into_detail(_connection)
+    }).await?;
+    Ok(HttpResponse::Ok().json(goods))
+}
+
+pub async fn delete_goods(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let goods_id = _id.into_inner();
+    db(&pool, move |_connection| Goods::delete_for_owner(_connection, goods_id, user_id)).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub async fn goods_categories(pool: Data<DbPool>) -> Result<HttpResponse, Error> {
+    let categories = db(&pool, GoodsCategory::get_all).await?;
+    Ok(HttpResponse::Ok().json(categories))
+}
+
+// Категории общие для всего портала, их заводят администраторы
+pub async fn create_goods_category(session: Session, pool: Data<DbPool>, data: Json<NewGoodsCategory>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let category = data.into_inner();
+    let category = db(&pool, move |_connection| -> Result<_, Error> {
+        check_administrator(_connection, user_id)?;
+        GoodsCategory::create(_connection, &category)
+    }).await?;
+    Ok(HttpResponse::Created().json(category))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GoodsSearchQuery {
+    pub q:                    Option<String>,
+    pub district_id:          Option<i32>,
+    pub category_id:          Option<i32>,
+    pub min_price:            Option<BigDecimal>,
+    pub max_price:            Option<BigDecimal>,
+    pub currency:             Option<String>,
+    #[serde(default)]
+    pub include_out_of_stock: bool,
+    pub page:                 Option<i64>,
+    pub limit:                Option<i64>,
+}
+
+// Кто продает товар в городе или районе, дешевые первыми:
+// /api/v1/cities/1/goods/search/?q=хлеб&district_id=3
+pub async fn search_city_goods(pool: Data<DbPool>, _id: Path<i32>, query: Query<GoodsSearchQuery>) -> Result<HttpResponse, Error> {
+    let city_id = _id.into_inner();
+    let query = query.into_inner();
+    let pagination = Pagination::new(query.page, query.limit);
+    let search = GoodsSearch {
+        q:                    query.q,
+        district_id:          query.district_id,
+        category_id:          query.category_id,
+        min_price:            query.min_price,
+        max_price:            query.max_price,
+        currency:             query.currency,
+        include_out_of_stock: query.include_out_of_stock,
+    };
+    let (items, total) = db(&pool, move |_connection| {
+        search_goods(_connection, city_id, &search, pagination.offset(), pagination.limit)
+    }).await?;
+    Ok(HttpResponse::Ok().json(PageResponse::new(items, pagination, total)))
+}
@@ -0,0 +1,128 @@
+mod events;
+mod forum;
+mod forum_members;
+mod forum_moderation;
+mod geo;
+mod geocoder;
+mod geometry;
+mod geojson;
+mod goods;
+mod notifications;
+mod organizations;
+mod reviews;
+mod statistics;
+mod themes;
+mod user_events;
+mod wifi_charging;
+
+pub use self::{
+    events::*,
+    forum::*,
+    forum_members::*,
+    forum_moderation::*,
+    geo::*,
+    geocoder::*,
+    geometry::*,
+    geojson::*,
+    goods::*,
+    notifications::*,
+    organizations::*,
+    reviews::*,
+    statistics::*,
+    themes::*,
+    user_events::*,
+    wifi_charging::*,
+};
+
+use actix_session::Session;
+use actix_web::web;
+use serde::Serialize;
+
+use crate::errors::Error;
+
+
+pub fn api_routes(config: &mut web::ServiceConfig) {
+    config.configure(events_routes);
+    config.configure(forum_routes);
+    config.configure(forum_members_routes);
+    config.configure(forum_moderation_routes);
+    config.configure(geo_routes);
+    config.configure(geocoder_routes);
+    config.configure(geometry_routes);
+    config.configure(geojson_routes);
+    config.configure(goods_routes);
+    config.configure(notifications_routes);
+    config.configure(organizations_routes);
+    config.configure(reviews_routes);
+    config.configure(statistics_routes);
+    config.configure(themes_routes);
+    config.configure(user_events_routes);
+    config.configure(wifi_charging_routes);
+}
+
+// Страница по умолчанию и ограничение на размер страницы для всех списков API
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 100;
+
+// Параметры постраничного вывода, уже приведенные к допустимым значениям
+#[derive(Debug, Clone, Copy)]
+pub struct Pagination {
+    pub page:  i64,
+    pub limit: i64,
+}
+
+impl Pagination {
+    pub fn new(page: Option<i64>, limit: Option<i64>) -> Pagination {
+        Pagination {
+            page:  page.unwrap_or(1).max(1),
+            limit: limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT),
+        }
+    }
+
+    // page приходит из запроса как есть, поэтому без переполнения
+    pub fn offset(&self) -> i64 {
+        (self.page - 1).saturating_mul(self.limit)
+    }
+}
+
+// Ответ со списком объектов и данными для перехода по страницам
+#[derive(Serialize)]
+pub struct PageResponse<T: Serialize> {
+    pub items: Vec<T>,
+    pub page:  i64,
+    pub limit: i64,
+    pub total: i64,
+    pub pages: i64,
+}
+
+impl<T: Serialize> PageResponse<T> {
+    pub fn new(items: Vec<T>, pagination: Pagination, total: i64) -> PageResponse<T> {
+        PageResponse {
+            items: items,
+            page:  pagination.page,
+            limit: pagination.limit,
+            total: total,
+            pages: (total + pagination.limit - 1) / pagination.limit,
+        }
+    }
+
+    // Постраничный вывод списка, который уже целиком загружен из базы
+    pub fn from_vec(items: Vec<T>, pagination: Pagination) -> PageResponse<T> {
+        let total = items.len() as i64;
+        let items = items
+            .into_iter()
+            .skip(pagination.offset() as usize)
+            .take(pagination.limit as usize)
+            .collect();
+        PageResponse::new(items, pagination, total)
+    }
+}
+
+// Идентификатор пользователя из сессии. Для анонимов - Error::Forbidden.
+pub fn get_session_user_id(session: &Session) -> Result<i32, Error> {
+    session
+        .get::<i32>("user_id")
+        .ok()
+        .flatten()
+        .ok_or(Error::Forbidden)
+}
@@ -0,0 +1,53 @@
+use actix_session::Session;
+use actix_web::{
+    web,
+    web::{Data, Path, Query},
+    HttpResponse,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::errors::Error;
+use crate::models::Notification;
+use crate::utils::{db, DbPool};
+use super::{get_session_user_id, PageResponse, Pagination};
+
+
+// Уведомления в личном кабинете
+pub fn notifications_routes(config: &mut web::ServiceConfig) {
+    config.route("/api/v1/notifications/", web::get().to(list_notifications));
+    config.route("/api/v1/notifications/read/", web::post().to(read_all_notifications));
+    config.route("/api/v1/notifications/{id}/read/", web::post().to(read_notification));
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NotificationsQuery {
+    pub unread: Option<bool>,
+    pub page:   Option<i64>,
+    pub limit:  Option<i64>,
+}
+
+pub async fn list_notifications(session: Session, pool: Data<DbPool>, query: Query<NotificationsQuery>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let pagination = Pagination::new(query.page, query.limit);
+    let only_unread = query.unread.unwrap_or(false);
+    let (items, total) = db(&pool, move |_connection| {
+        Notification::get_for_user(_connection, user_id, only_unread, pagination.offset(), pagination.limit)
+    }).await?;
+    Ok(HttpResponse::Ok().json(PageResponse::new(items, pagination, total)))
+}
+
+pub async fn read_notification(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let notification_id = _id.into_inner();
+    let notification = db(&pool, move |_connection| {
+        Notification::mark_read(_connection, notification_id, user_id)
+    }).await?;
+    Ok(HttpResponse::Ok().json(notification))
+}
+
+pub async fn read_all_notifications(session: Session, pool: Data<DbPool>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let updated = db(&pool, move |_connection| Notification::mark_all_read(_connection, user_id)).await?;
+    Ok(HttpResponse::Ok().json(json!({ "updated": updated })))
+}
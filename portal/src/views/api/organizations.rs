@@ -0,0 +1,329 @@
+use actix_session::Session;
+use actix_web::{
+    web,
+    web::{Data, Json, Path, Query},
+    HttpResponse,
+};
+use bigdecimal::BigDecimal;
+use diesel::{Connection, PgConnection};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::errors::Error;
+use crate::models::{
+    is_administrator,
+    AdvertisementModel as Advertisement,
+    CityModel as City,
+    ClaimForm,
+    District,
+    EditOrganization,
+    GeoPoint,
+    NewAdvertisement,
+    Organization,
+    OrganizationClaim,
+    OrganizationForm,
+    CLAIM_EMAIL,
+};
+use crate::notify::{claim_code_notice, claim_reviewed_notice, Notifier};
+use crate::utils::{db, DbPool};
+use super::{get_session_user_id, PageResponse, Pagination};
+
+
+// Профили организаций, заявки на права владельца и объявления от имени организации
+pub fn organizations_routes(config: &mut web::ServiceConfig) {
+    config.route("/api/v1/cities/{id}/organizations/", web::get().to(city_organizations));
+    config.route("/api/v1/districts/{id}/organizations/", web::get().to(district_organizations));
+    config.route("/api/v1/organizations/", web::post().to(create_organization));
+    config.route("/api/v1/organizations/{id}/", web::get().to(get_organization));
+    config.route("/api/v1/organizations/{id}/", web::put().to(update_organization));
+    config.route("/api/v1/organizations/{id}/claims/", web::post().to(create_claim));
+    config.route("/api/v1/organizations/{id}/advertisements/", web::get().to(organization_advertisements));
+    config.route("/api/v1/organizations/{id}/advertisements/", web::post().to(create_organization_advertisement));
+
+    config.route("/api/v1/organization-claims/", web::get().to(my_claims));
+    config.route("/api/v1/organization-claims/pending/", web::get().to(pending_claims));
+    config.route("/api/v1/organization-claims/{id}/verify/", web::post().to(verify_claim));
+    config.route("/api/v1/organization-claims/{id}/resend/", web::post().to(resend_claim_code));
+    config.route("/api/v1/organization-claims/{id}/cancel/", web::post().to(cancel_claim));
+    config.route("/api/v1/organization-claims/{id}/approve/", web::post().to(approve_claim));
+    config.route("/api/v1/organization-claims/{id}/reject/", web::post().to(reject_claim));
+}
+
+pub(super) fn check_administrator(_connection: &PgConnection, user_id: i32) -> Result<(), Error> {
+    if !is_administrator(_connection, user_id)? {
+        return Err(Error::Forbidden);
+    }
+    Ok(())
+}
+
+// Код подтверждения на почту организации (через очередь писем)
+fn send_claim_code(_connection: &PgConnection, notifier: &Notifier, claim: &OrganizationClaim) -> Result<(), Error> {
+    let organization = Organization::read_by_id(claim.organization_id, _connection)?;
+    let email = claim.email.as_deref().unwrap_or_default();
+    notifier.send_email(_connection, email, &claim_code_notice(&organization, claim))
+}
+
+// open_now=true - только открытые сейчас по местному времени города
+#[derive(Debug, Deserialize)]
+pub struct OrganizationsQuery {
+    #[serde(default)]
+    pub open_now: bool,
+    pub page:     Option<i64>,
+    pub limit:    Option<i64>,
+}
+
+pub async fn city_organizations(pool: Data<DbPool>, _id: Path<i32>, query: Query<OrganizationsQuery>) -> Result<HttpResponse, Error> {
+    let city_id = _id.into_inner();
+    let pagination = Pagination::new(query.page, query.limit);
+    let open_at = if query.open_now { Some(chrono::Utc::now()) } else { None };
+    let organizations = db(&pool, move |_connection| {
+        City::read_by_id(city_id, _connection)?.get_organizations(_connection, open_at)
+    }).await?;
+    Ok(HttpResponse::Ok().json(PageResponse::from_vec(organizations, pagination)))
+}
+
+pub async fn district_organizations(pool: Data<DbPool>, _id: Path<i32>, query: Query<OrganizationsQuery>) -> Result<HttpResponse, Error> {
+    let district_id = _id.into_inner();
+    let pagination = Pagination::new(query.page, query.limit);
+    let open_at = if query.open_now { Some(chrono::Utc::now()) } else { None };
+    let organizations = db(&pool, move |_connection| {
+        District::read_by_id(district_id, _connection)?.get_organizations(_connection, open_at)
+    }).await?;
+    Ok(HttpResponse::Ok().json(PageResponse::from_vec(organizations, pagination)))
+}
+
+pub async fn get_organization(pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let organization_id = _id.into_inner();
+    let organization = db(&pool, move |_connection| Organization::read_by_id(organization_id, _connection)).await?;
+    Ok(HttpResponse::Ok().json(organization))
+}
+
+// Новые организации добавляют администраторы. Улица находится по адресу
+// или по точке на карте.
+pub async fn create_organization(session: Session, pool: Data<DbPool>, data: Json<OrganizationForm>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let form = data.into_inner();
+    let organization = db(&pool, move |_connection| -> Result<_, Error> {
+        check_administrator(_connection, user_id)?;
+        Organization::create_at_address(_connection, &form)
+    }).await?;
+    Ok(HttpResponse::Created().json(organization))
+}
+
+pub async fn update_organization(
+    session: Session,
+    pool: Data<DbPool>,
+    _id: Path<i32>,
+    data: Json<EditOrganization>,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let organization_id = _id.into_inner();
+    let changes = data.into_inner();
+    let organization = db(&pool, move |_connection| {
+        Organization::update_profile(organization_id, user_id, &changes, _connection)
+    }).await?;
+    Ok(HttpResponse::Ok().json(organization))
+}
+
+// Заявка на права владельца. Для способа email письмо с кодом ставится
+// в очередь в той же транзакции, что и заявка, и уходит после ее фиксации.
+// Без SMTP заявка по почте не создается.
+pub async fn create_claim(
+    session: Session,
+    pool: Data<DbPool>,
+    notifier: Data<Arc<Notifier>>,
+    _id: Path<i32>,
+    data: Json<ClaimForm>,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let organization_id = _id.into_inner();
+    let form = data.into_inner();
+    let claim = db(&pool, move |_connection| {
+        _connection.transaction::<_, Error, _>(|| {
+            let claim = OrganizationClaim::create(_connection, organization_id, user_id, &form)?;
+            if claim.method == CLAIM_EMAIL {
+                send_claim_code(_connection, &notifier, &claim)?;
+            }
+            Ok(claim)
+        })
+    }).await?;
+    Ok(HttpResponse::Created().json(claim))
+}
+
+pub async fn my_claims(session: Session, pool: Data<DbPool>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let claims = db(&pool, move |_connection| OrganizationClaim::get_for_user(_connection, user_id)).await?;
+    Ok(HttpResponse::Ok().json(claims))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ClaimsQuery {
+    pub page:  Option<i64>,
+    pub limit: Option<i64>,
+}
+
+pub async fn pending_claims(session: Session, pool: Data<DbPool>, query: Query<ClaimsQuery>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let pagination = Pagination::new(query.page, query.limit);
+    let (items, total) = db(&pool, move |_connection| {
+        check_administrator(_connection, user_id)?;
+        OrganizationClaim::get_pending(_connection, pagination.offset(), pagination.limit)
+    }).await?;
+    Ok(HttpResponse::Ok().json(PageResponse::new(items, pagination, total)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VerifyClaimForm {
+    pub code: String,
+}
+
+pub async fn verify_claim(session: Session, pool: Data<DbPool>, _id: Path<i32>, data: Json<VerifyClaimForm>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let claim_id = _id.into_inner();
+    let form = data.into_inner();
+    let claim = db(&pool, move |_connection| {
+        OrganizationClaim::verify_code(_connection, claim_id, user_id, &form.code)
+    }).await?;
+    Ok(HttpResponse::Ok().json(claim))
+}
+
+pub async fn resend_claim_code(
+    session: Session,
+    pool: Data<DbPool>,
+    notifier: Data<Arc<Notifier>>,
+    _id: Path<i32>,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let claim_id = _id.into_inner();
+    let claim = db(&pool, move |_connection| {
+        _connection.transaction::<_, Error, _>(|| {
+            let claim = OrganizationClaim::renew_code(_connection, claim_id, user_id)?;
+            send_claim_code(_connection, &notifier, &claim)?;
+            Ok(claim)
+        })
+    }).await?;
+    Ok(HttpResponse::Ok().json(claim))
+}
+
+pub async fn cancel_claim(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let claim_id = _id.into_inner();
+    let claim = db(&pool, move |_connection| OrganizationClaim::cancel(_connection, claim_id, user_id)).await?;
+    Ok(HttpResponse::Ok().json(claim))
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ReviewClaimForm {
+    pub comment: Option<String>,
+}
+
+// Одобрение заявки. Заявителю и тем, чьи заявки отклонены вместе с ней,
+// приходит уведомление.
+pub async fn approve_claim(
+    session: Session,
+    pool: Data<DbPool>,
+    notifier: Data<Arc<Notifier>>,
+    _id: Path<i32>,
+    data: Option<Json<ReviewClaimForm>>,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let claim_id = _id.into_inner();
+    let form = data.map(Json::into_inner).unwrap_or_default();
+    let claim = db(&pool, move |_connection| -> Result<_, Error> {
+        check_administrator(_connection, user_id)?;
+        let (claim, rejected) = OrganizationClaim::approve(_connection, claim_id, user_id, form.comment.as_deref())?;
+        let organization = Organization::read_by_id(claim.organization_id, _connection)?;
+        for reviewed in std::iter::once(&claim).chain(rejected.iter()) {
+            notifier.notify(_connection, &[reviewed.user_id], &claim_reviewed_notice(&organization, reviewed))?;
+        }
+        Ok(claim)
+    }).await?;
+    Ok(HttpResponse::Ok().json(claim))
+}
+
+pub async fn reject_claim(
+    session: Session,
+    pool: Data<DbPool>,
+    notifier: Data<Arc<Notifier>>,
+    _id: Path<i32>,
+    data: Json<ReviewClaimForm>,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let claim_id = _id.into_inner();
+    let form = data.into_inner();
+    let claim = db(&pool, move |_connection| -> Result<_, Error> {
+        check_administrator(_connection, user_id)?;
+        let comment = form.comment.unwrap_or_default();
+        let claim = OrganizationClaim::reject(_connection, claim_id, user_id, &comment)?;
+        let organization = Organization::read_by_id(claim.organization_id, _connection)?;
+        notifier.notify(_connection, &[claim.user_id], &claim_reviewed_notice(&organization, &claim))?;
+        Ok(claim)
+    }).await?;
+    Ok(HttpResponse::Ok().json(claim))
+}
+
+pub async fn organization_advertisements(pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let organization_id = _id.into_inner();
+    let advertisements = db(&pool, move |_connection| -> Result<Vec<Advertisement>, Error> {
+        Advertisement::find_by_organization(organization_id, _connection)
+    }).await?;
+    Ok(HttpResponse::Ok().json(advertisements))
+}
+
+// Объявление от имени организации
+#[derive(Debug, Deserialize)]
+pub struct OrganizationAdForm {
+    pub title:         String,
+    pub description:   Option<String>,
+    pub category:      Option<String>,
+    pub price:         Option<BigDecimal>,
+    pub currency:      Option<String>,
+    pub location:      Option<String>,
+    pub contact_name:  Option<String>,
+    pub contact_phone: Option<String>,
+    pub contact_email: Option<String>,
+    #[serde(default)]
+    pub images:        Vec<String>,
+    pub video:         Option<String>,
+    #[serde(default, with = "crate::models::geo::serde_point_opt")]
+    pub coordinates:   Option<GeoPoint>,
+}
+
+pub async fn create_organization_advertisement(
+    session: Session,
+    pool: Data<DbPool>,
+    _id: Path<i32>,
+    data: Json<OrganizationAdForm>,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let organization_id = _id.into_inner();
+    let form = data.into_inner();
+    if form.title.trim().is_empty() {
+        return Err(Error::Validation("title is required".to_string()));
+    }
+    let new_advertisement = NewAdvertisement {
+        user_id:         user_id,
+        community_id:    None,
+        title:           form.title,
+        description:     form.description,
+        category:        form.category,
+        price:           form.price,
+        currency:        form.currency,
+        location:        form.location,
+        contact_name:    form.contact_name,
+        contact_phone:   form.contact_phone,
+        contact_email:   form.contact_email,
+        images:          form.images,
+        video:           form.video,
+        is_active:       true,
+        created_at:      chrono::Local::now().naive_local(),
+        coordinates:     form.coordinates,
+        organization_id: Some(organization_id),
+        // город берется по улице организации в create_for_organization
+        city_id:         None,
+    };
+    let advertisement = db(&pool, move |_connection| -> Result<Advertisement, Error> {
+        Advertisement::create_for_organization(new_advertisement, _connection)
+    }).await?;
+    Ok(HttpResponse::Created().json(advertisement))
+}
@@ -0,0 +1,127 @@
+use actix_session::Session;
+use actix_web::{
+    web,
+    web::{Data, Json, Path, Query},
+    HttpResponse,
+};
+use serde::Deserialize;
+
+use crate::errors::Error;
+use crate::models::{Review, ReviewForm, ReviewObject};
+use crate::utils::{db, DbPool};
+use super::{get_session_user_id, PageResponse, Pagination};
+
+
+// Отзывы об организациях и пользовательских точках
+pub fn reviews_routes(config: &mut web::ServiceConfig) {
+    config.route("/api/v1/organizations/{id}/reviews/", web::get().to(organization_reviews));
+    config.route("/api/v1/organizations/{id}/reviews/", web::post().to(create_organization_review));
+    config.route("/api/v1/user-points/{id}/reviews/", web::get().to(user_point_reviews));
+    config.route("/api/v1/user-points/{id}/reviews/", web::post().to(create_user_point_review));
+    config.route("/api/v1/reviews/{id}/", web::put().to(update_review));
+    config.route("/api/v1/reviews/{id}/", web::delete().to(delete_review));
+    config.route("/api/v1/reviews/{id}/reply/", web::post().to(reply_review));
+    config.route("/api/v1/reviews/{id}/reply/", web::delete().to(delete_reply));
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewsQuery {
+    pub page:  Option<i64>,
+    pub limit: Option<i64>,
+}
+
+async fn list_reviews(pool: Data<DbPool>, object: ReviewObject, object_id: i32, query: ReviewsQuery) -> Result<HttpResponse, Error> {
+    let pagination = Pagination::new(query.page, query.limit);
+    let (items, total) = db(&pool, move |_connection| {
+        if !object.exists(_connection, object_id)? {
+            return Err(Error::NotFound);
+        }
+        Review::get_for_object(_connection, object, object_id, pagination.offset(), pagination.limit)
+    }).await?;
+    Ok(HttpResponse::Ok().json(PageResponse::new(items, pagination, total)))
+}
+
+async fn create_review(
+    session: Session,
+    pool: Data<DbPool>,
+    object: ReviewObject,
+    object_id: i32,
+    form: ReviewForm,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let review = db(&pool, move |_connection| {
+        Review::create(_connection, object, object_id, user_id, &form)?.into_detail(_connection)
+    }).await?;
+    Ok(HttpResponse::Created().json(review))
+}
+
+pub async fn organization_reviews(pool: Data<DbPool>, _id: Path<i32>, query: Query<ReviewsQuery>) -> Result<HttpResponse, Error> {
+    list_reviews(pool, ReviewObject::Organization, _id.into_inner(), query.into_inner()).await
+}
+
+pub async fn user_point_reviews(pool: Data<DbPool>, _id: Path<i32>, query: Query<ReviewsQuery>) -> Result<HttpResponse, Error> {
+    list_reviews(pool, ReviewObject::UserPoint, _id.into_inner(), query.into_inner()).await
+}
+
+pub async fn create_organization_review(
+    session: Session,
+    pool: Data<DbPool>,
+    _id: Path<i32>,
+    data: Json<ReviewForm>,
+) -> Result<HttpResponse, Error> {
+    create_review(session, pool, ReviewObject::Organization, _id.into_inner(), data.into_inner()).await
+}
+
+pub async fn create_user_point_review(
+    session: Session,
+    pool: Data<DbPool>,
+    _id: Path<i32>,
+    data: Json<ReviewForm>,
+) -> Result<HttpResponse, Error> {
+    create_review(session, pool, ReviewObject::UserPoint, _id.into_inner(), data.into_inner()).await
+}
+
+pub async fn update_review(session: Session, pool: Data<DbPool>, _id: Path<i32>, data: Json<ReviewForm>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let review_id = _id.into_inner();
+    let form = data.into_inner();
+    let review = db(&pool, move |_connection| {
+        Review::update(_connection, review_id, user_id, &form)?.into_detail(_connection)
+    }).await?;
+    Ok(HttpResponse::Ok().json(review))
+}
+
+pub async fn delete_review(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let review_id = _id.into_inner();
+    db(&pool, move |_connection| Review::delete(_connection, review_id, user_id)).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReplyForm {
+    pub reply: String,
+}
+
+// Ответ владельца объекта. Повторный ответ заменяет прежний.
+pub async fn reply_review(session: Session, pool: Data<DbPool>, _id: Path<i32>, data: Json<ReplyForm>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let review_id = _id.into_inner();
+    let form = data.into_inner();
+    if form.reply.trim().is_empty() {
+        return Err(Error::Validation("reply is required".to_string()));
+    }
+    let review = db(&pool, move |_connection| {
+        Review::reply(_connection, review_id, user_id, Some(&form.reply))?.into_detail(_connection)
+    }).await?;
+    Ok(HttpResponse::Ok().json(review))
+}
+
+pub async fn delete_reply(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let review_id = _id.into_inner();
+    let review = db(&pool, move |_connection| {
+        Review::reply(_connection, review_id, user_id, None)?.into_detail(_connection)
+    }).await?;
+    Ok(HttpResponse::Ok().json(review))
+}
@@ -0,0 +1,42 @@
+use actix_session::Session;
+use actix_web::{
+    web,
+    web::{Data, Path, Query},
+    HttpResponse,
+};
+use serde::Deserialize;
+
+use crate::errors::Error;
+use crate::models::{get_city_statistics, DEFAULT_STATISTICS_MONTHS};
+use crate::utils::{db, DbPool};
+use super::organizations::check_administrator;
+use super::get_session_user_id;
+
+
+// Статистика города для администраторов
+pub fn statistics_routes(config: &mut web::ServiceConfig) {
+    config.route("/api/v1/cities/{id}/statistics/", web::get().to(city_statistics));
+}
+
+// months - за сколько последних месяцев строить ряды, включая текущий
+#[derive(Debug, Deserialize)]
+pub struct StatisticsQuery {
+    pub months: Option<u32>,
+}
+
+pub async fn city_statistics(
+    session: Session,
+    pool: Data<DbPool>,
+    _id: Path<i32>,
+    query: Query<StatisticsQuery>,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let city_id = _id.into_inner();
+    let months = query.months.unwrap_or(DEFAULT_STATISTICS_MONTHS);
+    let now = chrono::Local::now().naive_local();
+    let statistics = db(&pool, move |_connection| -> Result<_, Error> {
+        check_administrator(_connection, user_id)?;
+        get_city_statistics(_connection, city_id, months, now)
+    }).await?;
+    Ok(HttpResponse::Ok().json(statistics))
+}
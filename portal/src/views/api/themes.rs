@@ -0,0 +1,141 @@
+use actix_session::Session;
+use actix_web::{
+    web,
+    web::{Data, Json, Path, Query},
+    HttpResponse,
+};
+use diesel::PgConnection;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::Error;
+use crate::models::{
+    is_administrator,
+    is_event_moderator,
+    Event,
+    EventFilter,
+    EventTheme,
+    EventThemeNode,
+    NewEventTheme,
+};
+use crate::utils::{db, DbPool};
+use super::{get_session_user_id, PageResponse, Pagination};
+
+
+pub fn themes_routes(config: &mut web::ServiceConfig) {
+    config.route("/api/v1/themes/", web::get().to(theme_tree));
+    config.route("/api/v1/themes/", web::post().to(create_theme));
+    config.route("/api/v1/themes/{id}/", web::get().to(get_theme));
+    config.route("/api/v1/themes/{id}/", web::put().to(update_theme));
+    config.route("/api/v1/themes/{id}/", web::delete().to(delete_theme));
+    config.route("/api/v1/themes/{id}/breadcrumbs/", web::get().to(theme_breadcrumbs));
+    config.route("/api/v1/themes/{id}/events/", web::get().to(theme_events));
+}
+
+// Тематика с цепочкой родителей и дочерними тематиками всех уровней
+#[derive(Serialize)]
+pub struct ThemeDetail {
+    #[serde(flatten)]
+    pub theme:       EventTheme,
+    pub breadcrumbs: Vec<EventTheme>,
+    pub children:    Vec<EventThemeNode>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ThemeEventsQuery {
+    pub page:  Option<i64>,
+    pub limit: Option<i64>,
+}
+
+fn validate_theme(form: &NewEventTheme) -> Result<(), Error> {
+    if form.name.trim().is_empty() {
+        return Err(Error::Validation("name is required".to_string()));
+    }
+    Ok(())
+}
+
+pub async fn theme_tree(pool: Data<DbPool>) -> Result<HttpResponse, Error> {
+    let tree = db(&pool, EventTheme::get_theme_tree).await?;
+    Ok(HttpResponse::Ok().json(tree))
+}
+
+pub async fn get_theme(pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let theme_id = _id.into_inner();
+    let detail = db(&pool, move |_connection| -> Result<_, Error> {
+        let theme = EventTheme::get_theme_by_id(_connection, theme_id)?;
+        Ok(ThemeDetail {
+            breadcrumbs: theme.get_breadcrumbs(_connection)?,
+            children:    theme.get_subtree(_connection)?,
+            theme:       theme,
+        })
+    }).await?;
+    Ok(HttpResponse::Ok().json(detail))
+}
+
+pub async fn theme_breadcrumbs(pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let theme_id = _id.into_inner();
+    let breadcrumbs = db(&pool, move |_connection| {
+        EventTheme::get_theme_by_id(_connection, theme_id)?.get_breadcrumbs(_connection)
+    }).await?;
+    Ok(HttpResponse::Ok().json(breadcrumbs))
+}
+
+// Опубликованные события тематики и всех ее дочерних тематик
+pub async fn theme_events(pool: Data<DbPool>, _id: Path<i32>, query: Query<ThemeEventsQuery>) -> Result<HttpResponse, Error> {
+    let theme_id = _id.into_inner();
+    let pagination = Pagination::new(query.page, query.limit);
+    let (items, total) = db(&pool, move |_connection| {
+        EventTheme::get_theme_by_id(_connection, theme_id)?;
+        let filter = EventFilter {
+            theme_ids:    Some(EventTheme::get_descendant_ids(_connection, theme_id)?),
+            is_published: Some(true),
+            ..Default::default()
+        };
+        Event::search(_connection, &filter, pagination.offset(), pagination.limit)
+    }).await?;
+    Ok(HttpResponse::Ok().json(PageResponse::new(items, pagination, total)))
+}
+
+// Дерево тематик меняют только модераторы афиши и администраторы
+fn check_theme_editor(_connection: &PgConnection, user_id: i32) -> Result<(), Error> {
+    if !is_event_moderator(_connection, user_id)? && !is_administrator(_connection, user_id)? {
+        return Err(Error::Forbidden);
+    }
+    Ok(())
+}
+
+pub async fn create_theme(session: Session, pool: Data<DbPool>, form: Json<NewEventTheme>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let form = form.into_inner();
+    validate_theme(&form)?;
+    let theme = db(&pool, move |_connection| {
+        check_theme_editor(_connection, user_id)?;
+        if let Some(parent_id) = form.parent_id {
+            EventTheme::get_theme_by_id(_connection, parent_id)?;
+        }
+        EventTheme::create_new_theme(_connection, form)
+    }).await?;
+    Ok(HttpResponse::Created().json(theme))
+}
+
+// Переименование и перенос тематики (смена parent_id)
+pub async fn update_theme(session: Session, pool: Data<DbPool>, _id: Path<i32>, form: Json<NewEventTheme>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let theme_id = _id.into_inner();
+    let form = form.into_inner();
+    validate_theme(&form)?;
+    let theme = db(&pool, move |_connection| {
+        check_theme_editor(_connection, user_id)?;
+        EventTheme::update_theme_info(_connection, theme_id, form)
+    }).await?;
+    Ok(HttpResponse::Ok().json(theme))
+}
+
+pub async fn delete_theme(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let theme_id = _id.into_inner();
+    db(&pool, move |_connection| {
+        check_theme_editor(_connection, user_id)?;
+        EventTheme::delete_theme_by_id(_connection, theme_id)
+    }).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
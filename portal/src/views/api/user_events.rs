@@ -0,0 +1,221 @@
+use actix_session::Session;
+use actix_web::{
+    web,
+    web::{Data, Json, Path, Query},
+    HttpResponse,
+};
+use diesel::PgConnection;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::Error;
+use crate::models::{
+    EditUserEvent,
+    EventTheme,
+    UserEvent,
+    UserEventHistory,
+    is_event_moderator,
+    USER_EVENT_PENDING,
+};
+use crate::utils::{db, DbPool};
+use super::{get_session_user_id, PageResponse, Pagination};
+
+
+// Заявки пользователей на события и их модерация
+pub fn user_events_routes(config: &mut web::ServiceConfig) {
+    config.route("/api/v1/user-events/", web::get().to(my_user_events));
+    config.route("/api/v1/user-events/", web::post().to(create_user_event));
+    config.route("/api/v1/user-events/moderation/", web::get().to(moderation_queue));
+    config.route("/api/v1/user-events/{id}/", web::get().to(get_user_event));
+    config.route("/api/v1/user-events/{id}/", web::put().to(update_user_event));
+    config.route("/api/v1/user-events/{id}/", web::delete().to(delete_user_event));
+    config.route("/api/v1/user-events/{id}/history/", web::get().to(user_event_history));
+    config.route("/api/v1/user-events/{id}/submit/", web::post().to(submit_user_event));
+    config.route("/api/v1/user-events/{id}/withdraw/", web::post().to(withdraw_user_event));
+    config.route("/api/v1/user-events/{id}/approve/", web::post().to(approve_user_event));
+    config.route("/api/v1/user-events/{id}/reject/", web::post().to(reject_user_event));
+    config.route("/api/v1/user-events/{id}/comments/", web::post().to(comment_user_event));
+}
+
+// Параметры очереди модерации. По умолчанию - заявки на проверке.
+#[derive(Debug, Deserialize)]
+pub struct ModerationQuery {
+    pub status: Option<String>,
+    pub page:   Option<i64>,
+    pub limit:  Option<i64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ModerationForm {
+    pub comment: Option<String>,
+}
+
+// Заявка вместе с историей переходов
+#[derive(Serialize)]
+pub struct UserEventDetail {
+    #[serde(flatten)]
+    pub user_event: UserEvent,
+    pub history:    Vec<UserEventHistory>,
+}
+
+// Заявка, которую может смотреть пользователь: своя или любая для модератора
+fn get_visible_user_event(_connection: &PgConnection, user_event_id: i32, user_id: i32) -> Result<UserEvent, Error> {
+    let user_event = UserEvent::get_by_id(_connection, user_event_id)?;
+    if user_event.user_id != user_id && !is_event_moderator(_connection, user_id)? {
+        return Err(Error::NotFound);
+    }
+    Ok(user_event)
+}
+
+// Заявка, принадлежащая пользователю
+fn get_own_user_event(_connection: &PgConnection, user_event_id: i32, user_id: i32) -> Result<UserEvent, Error> {
+    let user_event = UserEvent::get_by_id(_connection, user_event_id)?;
+    if user_event.user_id != user_id {
+        return Err(Error::Forbidden);
+    }
+    Ok(user_event)
+}
+
+fn check_moderator(_connection: &PgConnection, user_id: i32) -> Result<(), Error> {
+    if !is_event_moderator(_connection, user_id)? {
+        return Err(Error::Forbidden);
+    }
+    Ok(())
+}
+
+fn check_theme(_connection: &PgConnection, form: &EditUserEvent) -> Result<(), Error> {
+    if let Some(theme_id) = form.theme_id {
+        EventTheme::get_theme_by_id(_connection, theme_id)
+            .map_err(|_| Error::Validation("unknown theme_id".to_string()))?;
+    }
+    Ok(())
+}
+
+pub async fn my_user_events(session: Session, pool: Data<DbPool>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let items = db(&pool, move |_connection| UserEvent::get_for_user(_connection, user_id)).await?;
+    Ok(HttpResponse::Ok().json(items))
+}
+
+pub async fn create_user_event(session: Session, pool: Data<DbPool>, form: Json<EditUserEvent>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let form = form.into_inner();
+    form.validate()?;
+    let user_event = db(&pool, move |_connection| {
+        check_theme(_connection, &form)?;
+        UserEvent::create_new_user_event(_connection, form.into_new_user_event(user_id))
+    }).await?;
+    Ok(HttpResponse::Created().json(user_event))
+}
+
+pub async fn moderation_queue(session: Session, pool: Data<DbPool>, query: Query<ModerationQuery>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let query = query.into_inner();
+    let pagination = Pagination::new(query.page, query.limit);
+    let status = query.status.unwrap_or_else(|| USER_EVENT_PENDING.to_string());
+    let (items, total) = db(&pool, move |_connection| {
+        check_moderator(_connection, user_id)?;
+        UserEvent::get_by_status(_connection, &status, pagination.offset(), pagination.limit)
+    }).await?;
+    Ok(HttpResponse::Ok().json(PageResponse::new(items, pagination, total)))
+}
+
+pub async fn get_user_event(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let user_event_id = _id.into_inner();
+    let detail = db(&pool, move |_connection| -> Result<_, Error> {
+        let user_event = get_visible_user_event(_connection, user_event_id, user_id)?;
+        Ok(UserEventDetail {
+            history:    user_event.get_history(_connection)?,
+            user_event: user_event,
+        })
+    }).await?;
+    Ok(HttpResponse::Ok().json(detail))
+}
+
+pub async fn update_user_event(session: Session, pool: Data<DbPool>, _id: Path<i32>, form: Json<EditUserEvent>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let user_event_id = _id.into_inner();
+    let form = form.into_inner();
+    let user_event = db(&pool, move |_connection| {
+        get_own_user_event(_connection, user_event_id, user_id)?;
+        check_theme(_connection, &form)?;
+        UserEvent::update_user_event_info(_connection, user_event_id, form)
+    }).await?;
+    Ok(HttpResponse::Ok().json(user_event))
+}
+
+pub async fn delete_user_event(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let user_event_id = _id.into_inner();
+    db(&pool, move |_connection| {
+        get_own_user_event(_connection, user_event_id, user_id)?;
+        UserEvent::delete_user_event_by_id(_connection, user_event_id)
+    }).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub async fn user_event_history(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let user_event_id = _id.into_inner();
+    let history = db(&pool, move |_connection| {
+        get_visible_user_event(_connection, user_event_id, user_id)?.get_history(_connection)
+    }).await?;
+    Ok(HttpResponse::Ok().json(history))
+}
+
+pub async fn submit_user_event(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let user_event_id = _id.into_inner();
+    let user_event = db(&pool, move |_connection| {
+        get_own_user_event(_connection, user_event_id, user_id)?;
+        UserEvent::submit(_connection, user_event_id, user_id)
+    }).await?;
+    Ok(HttpResponse::Ok().json(user_event))
+}
+
+pub async fn withdraw_user_event(session: Session, pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let user_event_id = _id.into_inner();
+    let user_event = db(&pool, move |_connection| {
+        get_own_user_event(_connection, user_event_id, user_id)?;
+        UserEvent::withdraw(_connection, user_event_id, user_id)
+    }).await?;
+    Ok(HttpResponse::Ok().json(user_event))
+}
+
+pub async fn approve_user_event(session: Session, pool: Data<DbPool>, _id: Path<i32>, form: Option<Json<ModerationForm>>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let user_event_id = _id.into_inner();
+    let comment = form
+        .map(Json::into_inner)
+        .unwrap_or_default()
+        .comment
+        .filter(|c| !c.trim().is_empty());
+    let user_event = db(&pool, move |_connection| {
+        check_moderator(_connection, user_id)?;
+        UserEvent::approve(_connection, user_event_id, user_id, comment)
+    }).await?;
+    Ok(HttpResponse::Ok().json(user_event))
+}
+
+pub async fn reject_user_event(session: Session, pool: Data<DbPool>, _id: Path<i32>, form: Json<ModerationForm>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let user_event_id = _id.into_inner();
+    let comment = form.into_inner().comment.unwrap_or_default();
+    let user_event = db(&pool, move |_connection| {
+        check_moderator(_connection, user_id)?;
+        UserEvent::reject(_connection, user_event_id, user_id, comment)
+    }).await?;
+    Ok(HttpResponse::Ok().json(user_event))
+}
+
+pub async fn comment_user_event(session: Session, pool: Data<DbPool>, _id: Path<i32>, form: Json<ModerationForm>) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let user_event_id = _id.into_inner();
+    let comment = form.into_inner().comment.unwrap_or_default();
+    let entry = db(&pool, move |_connection| {
+        check_moderator(_connection, user_id)?;
+        UserEvent::add_comment(_connection, user_event_id, user_id, comment)
+    }).await?;
+    Ok(HttpResponse::Created().json(entry))
+}
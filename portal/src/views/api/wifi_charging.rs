@@ -0,0 +1,93 @@
+use actix_session::Session;
+use actix_web::{
+    web,
+    web::{Data, Json, Path, Query},
+    HttpResponse,
+};
+use serde::Deserialize;
+
+use crate::errors::Error;
+use crate::models::{StatusReport, StatusReportForm, WifiChargingFilter, WifiChargingForm, WifiChargingPoint};
+use crate::utils::{db, DbPool};
+use super::organizations::check_administrator;
+use super::{get_session_user_id, PageResponse, Pagination};
+
+
+// Точки Wi-Fi и зарядки: подробности, состояние по отметкам пользователей
+pub fn wifi_charging_routes(config: &mut web::ServiceConfig) {
+    config.route("/api/v1/districts/{id}/wifi-charging/", web::get().to(district_wifi_charging));
+    config.route("/api/v1/wifi-charging/{id}/", web::get().to(get_wifi_charging));
+    config.route("/api/v1/wifi-charging/{id}/", web::put().to(update_wifi_charging));
+    config.route("/api/v1/wifi-charging/{id}/reports/", web::get().to(wifi_charging_reports));
+    config.route("/api/v1/wifi-charging/{id}/reports/", web::post().to(create_wifi_charging_report));
+}
+
+// Точки района с отбором: ?has_charging=true&connector=ccs2&min_power_kw=50&status=working
+pub async fn district_wifi_charging(pool: Data<DbPool>, _id: Path<i32>, query: Query<WifiChargingFilter>) -> Result<HttpResponse, Error> {
+    let district_id = _id.into_inner();
+    let filter = query.into_inner();
+    let now = chrono::Local::now().naive_local();
+    let items = db(&pool, move |_connection| {
+        WifiChargingPoint::get_for_district(_connection, district_id, &filter, now)
+    }).await?;
+    Ok(HttpResponse::Ok().json(items))
+}
+
+pub async fn get_wifi_charging(pool: Data<DbPool>, _id: Path<i32>) -> Result<HttpResponse, Error> {
+    let point_id = _id.into_inner();
+    let now = chrono::Local::now().naive_local();
+    let point = db(&pool, move |_connection| {
+        WifiChargingPoint::read_by_id(point_id, _connection)?.into_detail(_connection, now)
+    }).await?;
+    Ok(HttpResponse::Ok().json(point))
+}
+
+// Подробности точки правят администраторы
+pub async fn update_wifi_charging(
+    session: Session,
+    pool: Data<DbPool>,
+    _id: Path<i32>,
+    data: Json<WifiChargingForm>,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let point_id = _id.into_inner();
+    let form = data.into_inner();
+    let now = chrono::Local::now().naive_local();
+    let point = db(&pool, move |_connection| -> Result<_, Error> {
+        check_administrator(_connection, user_id)?;
+        WifiChargingPoint::update_details(_connection, point_id, &form)?.into_detail(_connection, now)
+    }).await?;
+    Ok(HttpResponse::Ok().json(point))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReportsQuery {
+    pub page:  Option<i64>,
+    pub limit: Option<i64>,
+}
+
+pub async fn wifi_charging_reports(pool: Data<DbPool>, _id: Path<i32>, query: Query<ReportsQuery>) -> Result<HttpResponse, Error> {
+    let point_id = _id.into_inner();
+    let pagination = Pagination::new(query.page, query.limit);
+    let (items, total) = db(&pool, move |_connection| {
+        StatusReport::get_for_point(_connection, point_id, pagination.offset(), pagination.limit)
+    }).await?;
+    Ok(HttpResponse::Ok().json(PageResponse::new(items, pagination, total)))
+}
+
+// Отметка "работает / сломано / занято". В ответе точка с пересчитанным состоянием.
+pub async fn create_wifi_charging_report(
+    session: Session,
+    pool: Data<DbPool>,
+    _id: Path<i32>,
+    data: Json<StatusReportForm>,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_session_user_id(&session)?;
+    let point_id = _id.into_inner();
+    let form = data.into_inner();
+    let point = db(&pool, move |_connection| -> Result<_, Error> {
+        let report = StatusReport::create(_connection, point_id, user_id, &form)?;
+        WifiChargingPoint::read_by_id(point_id, _connection)?.into_detail(_connection, report.created_at)
+    }).await?;
+    Ok(HttpResponse::Created().json(point))
+}
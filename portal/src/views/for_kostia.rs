@@ -0,0 +1,347 @@
+use actix_web::{
+    web,
+    web::Data,
+    HttpRequest,
+    HttpResponse,
+};
+use crate::errors::Error;
+use crate::models::{
+    Categories,
+    Cat,
+    Item,
+    ItemForm,
+    Tag,
+    User,
+    ITEM_BLOG,
+};
+use crate::utils::{
+    db,
+    Config,
+    DbPool,
+    get_device_and_ajax,
+    get_request_user_data,
+    is_signed_in,
+};
+use actix_session::Session;
+use sailfish::TemplateOnce;
+use actix_multipart::{Field, Multipart};
+use futures::StreamExt;
+use std::{
+    io::Write,
+    fs::create_dir_all,
+    path::Path,
+    str,
+};
+
+
+// Блоги для нового фронтенда: список категорий блогов, страница блога
+// и изменение блогов автором. Пользователь берется из сессии, как в api.
+pub fn test_routes(config: &mut web::ServiceConfig) {
+    config.route("/all-blogs/", web::get().to(all_blogs_page));
+    config.route("/blog/{id}", web::get().to(get_blog_page));
+    config.route("/create-blog/", web::post().to(create_blog));
+    config.route("/edit-blog/{id}/", web::post().to(edit_blog));
+    config.route("/delete-blog/{id}/", web::post().to(delete_blog));
+}
+
+pub async fn all_blogs_page(req: HttpRequest, session: Session, pool: Data<DbPool>) -> Result<HttpResponse, Error> {
+    let (is_desctop, _) = get_device_and_ajax(&req);
+    let (cats, tags) = db(&pool, |_connection| -> Result<_, Error> {
+        Ok((
+            Categories::get_categories_for_types(_connection, ITEM_BLOG)?,
+            Tag::all(_connection)?,
+        ))
+    }).await?;
+
+    if is_signed_in(&session) {
+        let (_request_user, _) = get_request_user_data(&pool, &session).await?;
+        if is_desctop {
+            #[derive(TemplateOnce)]
+            #[template(path = "desctop/blogs/categories.stpl")]
+            struct Template {
+                request_user:   User,
+                cats:           Vec<Cat>,
+                all_tags:       Vec<Tag>,
+            }
+            let body = Template {
+                request_user:   _request_user,
+                cats:           cats,
+                all_tags:       tags,
+            }
+            .render_once()?;
+            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+        }
+        else {
+            #[derive(TemplateOnce)]
+            #[template(path = "mobile/blogs/categories.stpl")]
+            struct Template {
+                request_user:   User,
+                cats:           Vec<Cat>,
+                all_tags:       Vec<Tag>,
+            }
+            let body = Template {
+                request_user:   _request_user,
+                cats:           cats,
+                all_tags:       tags,
+            }
+            .render_once()?;
+            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+        }
+    }
+    else {
+        if is_desctop {
+            #[derive(TemplateOnce)]
+            #[template(path = "desctop/blogs/anon_categories.stpl")]
+            struct Template {
+                cats:           Vec<Cat>,
+                all_tags:       Vec<Tag>,
+            }
+            let body = Template {
+                cats:           cats,
+                all_tags:       tags,
+            }
+            .render_once()?;
+            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+        }
+        else {
+            #[derive(TemplateOnce)]
+            #[template(path = "mobile/blogs/anon_categories.stpl")]
+            struct Template {
+                cats:           Vec<Cat>,
+                all_tags:       Vec<Tag>,
+            }
+            let body = Template {
+                cats:           cats,
+                all_tags:       tags,
+            }
+            .render_once()?;
+            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+        }
+    }
+}
+
+pub async fn get_blog_page(req: HttpRequest, session: Session, pool: Data<DbPool>, _id: web::Path<i32>) -> Result<HttpResponse, Error> {
+    let (is_desctop, _) = get_device_and_ajax(&req);
+    let blog_id = *_id;
+    let (cats, tags, blog) = db(&pool, move |_connection| -> Result<_, Error> {
+        Ok((
+            Categories::get_categories_for_types(_connection, ITEM_BLOG)?,
+            Tag::all(_connection)?,
+            Item::get_with_types(_connection, blog_id, ITEM_BLOG)?,
+        ))
+    }).await?;
+
+    if is_signed_in(&session) {
+        let (_request_user, is_admin) = get_request_user_data(&pool, &session).await?;
+        // неопубликованный блог видят только автор и администраторы
+        if !blog.is_active && !is_admin && blog.user_id != _request_user.id {
+            return Err(Error::NotFound);
+        }
+        if is_desctop {
+            #[derive(TemplateOnce)]
+            #[template(path = "desctop/blogs/blog.stpl")]
+            struct Template {
+                request_user:   User,
+                cats:           Vec<Cat>,
+                all_tags:       Vec<Tag>,
+                blog:           Item,
+            }
+            let body = Template {
+                request_user:   _request_user,
+                cats:           cats,
+                all_tags:       tags,
+                blog:           blog,
+            }
+            .render_once()?;
+            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+        }
+        else {
+            #[derive(TemplateOnce)]
+            #[template(path = "mobile/blogs/blog.stpl")]
+            struct Template {
+                request_user:   User,
+                cats:           Vec<Cat>,
+                all_tags:       Vec<Tag>,
+                blog:           Item,
+            }
+            let body = Template {
+                request_user:   _request_user,
+                cats:           cats,
+                all_tags:       tags,
+                blog:           blog,
+            }
+            .render_once()?;
+            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+        }
+    }
+    else {
+        if !blog.is_active {
+            return Err(Error::NotFound);
+        }
+        if is_desctop {
+            #[derive(TemplateOnce)]
+            #[template(path = "desctop/blogs/anon_blog.stpl")]
+            struct Template {
+                cats:           Vec<Cat>,
+                all_tags:       Vec<Tag>,
+                blog:           Item,
+            }
+            let body = Template {
+                cats:           cats,
+                all_tags:       tags,
+                blog:           blog,
+            }
+            .render_once()?;
+            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+        }
+        else {
+            #[derive(TemplateOnce)]
+            #[template(path = "mobile/blogs/anon_blog.stpl")]
+            struct Template {
+                cats:           Vec<Cat>,
+                all_tags:       Vec<Tag>,
+                blog:           Item,
+            }
+            let body = Template {
+                cats:           cats,
+                all_tags:       tags,
+                blog:           blog,
+            }
+            .render_once()?;
+            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+        }
+    }
+}
+
+
+// Загруженный файл: где он лежит на диске и по какой ссылке отдается (/media/...)
+#[derive(Debug, Clone)]
+pub struct UploadedFiles {
+    pub path: String,
+    pub url:  String,
+}
+impl UploadedFiles {
+    fn new(media_root: &str, filename: &str, owner_id: i32) -> Result<UploadedFiles, Error> {
+        use chrono::Datelike;
+
+        // от имени файла оставляем только само имя, без каталогов
+        let filename = Path::new(filename)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| Error::Validation("invalid file name".to_string()))?;
+        let now = chrono::Local::now().naive_utc();
+        let folder = format!("{}/{}/{}/{}", owner_id, now.year(), now.month(), now.day());
+        let format_folder = format!("{}/{}", media_root.trim_end_matches('/'), folder);
+        create_dir_all(&format_folder).map_err(|e| {
+            error!("Не удалось создать папку {}: {}", format_folder, e);
+            Error::Unavailable
+        })?;
+
+        Ok(UploadedFiles {
+            path: format!("{}/{}", format_folder, filename),
+            url:  format!("/media/{}/{}", folder, filename),
+        })
+    }
+}
+
+// Форма блога из multipart: поля title, description, content и картинка image
+pub async fn item_form(payload: &mut Multipart, owner_id: i32, media_root: &str) -> Result<ItemForm, Error> {
+    let mut form = ItemForm::default();
+
+    while let Some(item) = payload.next().await {
+        let mut field: Field = item.map_err(|e| Error::Validation(e.to_string()))?;
+        let name = field.name().to_string();
+
+        if name == "image" {
+            let filename = field
+                .content_disposition()
+                .get_filename()
+                .unwrap_or("")
+                .to_string();
+            if filename.is_empty() {
+                continue;
+            }
+            let file = UploadedFiles::new(media_root, &filename, owner_id)?;
+            let file_path = file.path.clone();
+            let mut f = web::block(move || std::fs::File::create(file_path))
+                .await?
+                .map_err(|e| {
+                    error!("Не удалось создать файл {}: {}", file.path, e);
+                    Error::Unavailable
+                })?;
+            while let Some(chunk) = field.next().await {
+                let data = chunk.map_err(|e| Error::Validation(e.to_string()))?;
+                f = web::block(move || f.write_all(&data).map(|_| f))
+                    .await?
+                    .map_err(|e| {
+                        error!("Не удалось записать файл: {}", e);
+                        Error::Unavailable
+                    })?;
+            }
+            form.image = Some(file.url);
+        }
+        else {
+            let mut value = Vec::new();
+            while let Some(chunk) = field.next().await {
+                value.extend_from_slice(&chunk.map_err(|e| Error::Validation(e.to_string()))?);
+            }
+            let value = str::from_utf8(&value)
+                .map_err(|_| Error::Validation(format!("{} is not valid utf-8", name)))?
+                .to_string();
+            match name.as_str() {
+                "title" => form.title = value,
+                "description" => form.description = Some(value),
+                "content" => form.content = Some(value),
+                _ => (),
+            }
+        }
+    }
+    Ok(form)
+}
+
+pub async fn create_blog(session: Session, pool: Data<DbPool>, config: Data<Config>, mut payload: Multipart) -> Result<HttpResponse, Error> {
+    if !is_signed_in(&session) {
+        return Err(Error::Forbidden);
+    }
+    let (_request_user, is_admin) = get_request_user_data(&pool, &session).await?;
+    if !is_admin {
+        return Err(Error::Forbidden);
+    }
+    let form = item_form(&mut payload, _request_user.id, &config.media_root).await?;
+    let user_id = _request_user.id;
+    let blog = db(&pool, move |_connection| Item::create(_connection, user_id, ITEM_BLOG, &form)).await?;
+    Ok(HttpResponse::Ok().json(blog))
+}
+
+pub async fn edit_blog(session: Session, pool: Data<DbPool>, config: Data<Config>, _id: web::Path<i32>, mut payload: Multipart) -> Result<HttpResponse, Error> {
+    if !is_signed_in(&session) {
+        return Err(Error::Forbidden);
+    }
+    let (_request_user, _) = get_request_user_data(&pool, &session).await?;
+    let blog_id = *_id;
+    let blog = db(&pool, move |_connection| Item::get_with_types(_connection, blog_id, ITEM_BLOG)).await?;
+    if _request_user.id != blog.user_id {
+        return Err(Error::Forbidden);
+    }
+    let form = item_form(&mut payload, _request_user.id, &config.media_root).await?;
+    let blog = db(&pool, move |_connection| blog.edit(_connection, &form)).await?;
+    Ok(HttpResponse::Ok().json(blog))
+}
+
+pub async fn delete_blog(session: Session, pool: Data<DbPool>, _id: web::Path<i32>) -> Result<HttpResponse, Error> {
+    if !is_signed_in(&session) {
+        return Err(Error::Forbidden);
+    }
+    let (_request_user, _) = get_request_user_data(&pool, &session).await?;
+    let blog_id = *_id;
+    let user_id = _request_user.id;
+    db(&pool, move |_connection| -> Result<_, Error> {
+        let blog = Item::get_with_types(_connection, blog_id, ITEM_BLOG)?;
+        if user_id != blog.user_id {
+            return Err(Error::Forbidden);
+        }
+        blog.delete(_connection)
+    }).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,11 @@
+mod pages;
+mod for_kostia;
+mod api;
+mod state;
+
+pub use self::{
+    pages::*,
+    for_kostia::*,
+    api::*,
+    state::*,
+};
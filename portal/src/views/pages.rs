@@ -3,12 +3,9 @@ use actix_web::{
     HttpRequest,
     HttpResponse,
     web,
-    error::InternalError,
-    http::StatusCode,
-    web::{block, Data},
-    Result,
+    web::Data,
 };
-use crate::schema;
+use crate::errors::Error;
 use crate::models::{
     User,
     Item,
@@ -16,21 +13,29 @@ use crate::models::{
     Tag,
     StatPage,
     Cat,
+    File,
+    find_file_by_id,
+    ITEM_BLOG,
+    ITEM_HELP,
+    ITEM_SERVICE,
+    ITEM_STORE,
+    ITEM_WIKI,
+    ITEM_WORK,
+    STAT_PAGE_INFO,
+    STAT_PAGE_MAIN,
+    CityStatistics,
+    get_city_statistics,
+    DEFAULT_STATISTICS_MONTHS,
 };
+use super::StatisticsQuery;
 use crate::utils::{
-    establish_connection,
+    db,
+    DbPool,
     get_device_and_ajax,
     get_request_user_data,
     is_signed_in,
     get_first_load_page,
     get_template,
-    IndexResponse,
-    AppState,
-};
-use crate::diesel::{
-    RunQueryDsl,
-    ExpressionMethods,
-    QueryDsl,
 };
 use actix_session::Session;
 use sailfish::TemplateOnce;
@@ -39,9 +44,8 @@ use serde_json::to_value;
 use crate::websocket::Server;
 
 
+// Шаблонные страницы сайта. Служебная /test/ подключена в state_routes.
 pub fn pages_routes(config: &mut web::ServiceConfig) {
-    //config.route("/test/", web::get().to(test_page));
-    config.route("/test/", web::get().to(test_page));
     config.route("/", web::get().to(index_page));
     config.route("/info/", web::get().to(info_page));
     config.route("/history/", web::get().to(history_page));
@@ -65,10 +69,18 @@ pub fn pages_routes(config: &mut web::ServiceConfig) {
 
     config.route("/edit_file/{id}/", web::get().to(edit_file_page));
     config.route("/image/{id}/", web::get().to(image_page));
+
+    config.route("/cities/{id}/statistics/", web::get().to(city_statistics_page));
+
+    config.default_service(web::route().to(not_found));
+}
+
+fn permission_denied() -> Result<HttpResponse, Error> {
+    Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied."))
 }
 
 
-pub async fn not_found(req: HttpRequest, session: Session) -> actix_web::Result<HttpResponse> {
+pub async fn not_found(req: HttpRequest, session: Session, pool: Data<DbPool>) -> Result<HttpResponse, Error> {
     let (is_desctop, is_ajax) = get_device_and_ajax(&req);
 
     let template_types = get_template(&req);
@@ -85,7 +97,7 @@ pub async fn not_found(req: HttpRequest, session: Session) -> actix_web::Result<
     }
     else {
         if is_signed_in(&session) {
-            let _request_user = get_request_user_data(&session);
+            let (_request_user, _) = get_request_user_data(&pool, &session).await?;
             if is_desctop {
                 #[derive(TemplateOnce)]
                 #[template(path = "desctop/pages/404.stpl")]
@@ -93,16 +105,13 @@ pub async fn not_found(req: HttpRequest, session: Session) -> actix_web::Result<
                     request_user:   User,
                     is_ajax:        i32,
                     template_types: i16,
-
                 }
                 let body = Template {
                     request_user:   _request_user,
                     is_ajax:        is_ajax,
                     template_types: template_types,
-    
                 }
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+                .render_once()?;
                 Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
             }
             else {
@@ -116,8 +125,7 @@ pub async fn not_found(req: HttpRequest, session: Session) -> actix_web::Result<
                     is_ajax:        is_ajax,
                     template_types: template_types,
                 }
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+                .render_once()?;
                 Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
             }
         }
@@ -132,9 +140,8 @@ pub async fn not_found(req: HttpRequest, session: Session) -> actix_web::Result<
                 let body = Template {
                     is_ajax:        is_ajax,
                     template_types: template_types,
-                } 
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+                }
+                .render_once()?;
                 Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
             }
             else {
@@ -148,30 +155,18 @@ pub async fn not_found(req: HttpRequest, session: Session) -> actix_web::Result<
                     is_ajax:        is_ajax,
                     template_types: template_types,
                 }
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+                .render_once()?;
                 Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
             }
         }
     }
 }
 
-pub async fn test_page(state: web::Data<AppState>) -> Result<web::Json<IndexResponse>> {
-    let request_count = state.request_count.get() + 1;
-    state.request_count.set(request_count);
-    let ms = state.messages.lock().unwrap();
-
-    Ok(web::Json(IndexResponse {
-        server_id: state.server_id,
-        request_count,
-        messages: ms.clone(),
-    }))
-}
-
 pub async fn index_page (
     req: HttpRequest,
     session: Session,
-    websocket_srv: Data<Addr<Server>>) -> actix_web::Result<HttpResponse> {
+    pool: Data<DbPool>,
+    websocket_srv: Data<Addr<Server>>) -> Result<HttpResponse, Error> {
     let (is_desctop, is_ajax) = get_device_and_ajax(&req);
     let template_types = get_template(&req);
 
@@ -187,63 +182,43 @@ pub async fn index_page (
         ).await
     }
     else {
-        use crate::schema::stat_pages::dsl::stat_pages;
-        use crate::models::{Blog, Service, Store, Wiki, Work};
         use crate::websocket::MessageToClient;
 
-        let _connection = establish_connection();
-        let _stat: StatPage;
-
-        let _stats = stat_pages
-            .filter(schema::stat_pages::types.eq(1))
-            .first::<StatPage>(&_connection);
-        if _stats.is_ok() {
-            _stat = _stats.expect("E");
-            diesel::update(&_stat)
-                .set(schema::stat_pages::now_u.eq(_stat.now_u + 1))
-                .get_result::<StatPage>(&_connection)
-                .expect("Error.");
+        let request_user_data = if is_signed_in(&session) {
+            Some(get_request_user_data(&pool, &session).await?)
         }
         else {
-            use crate::models::NewStatPage;
-            let form = NewStatPage {
-                types:   1,
-                view:    0,
-                height:  0.0,
-                seconds: 0,
-                now_u:   1,
-            };
-            _stat = diesel::insert_into(schema::stat_pages::table)
-                .values(&form)
-                .get_result::<StatPage>(&_connection)
-                .expect("Error.");
-
+            None
+        };
+        // неопубликованные объекты видят только администраторы
+        let is_admin = request_user_data.as_ref().is_some_and(|(_, is_admin)| *is_admin);
+        let (_stat, _last_works, _last_services, _last_wikis, _last_blogs, _last_stores) = db(&pool, move |_connection| -> Result<_, Error> {
+            Ok((
+                StatPage::get_or_create(_connection, STAT_PAGE_MAIN, true)?,
+                Item::get_last(_connection, ITEM_WORK, 3, is_admin)?,
+                Item::get_last(_connection, ITEM_SERVICE, 3, is_admin)?,
+                Item::get_last(_connection, ITEM_WIKI, 3, is_admin)?,
+                Item::get_last(_connection, ITEM_BLOG, 3, is_admin)?,
+                Item::get_last(_connection, ITEM_STORE, 3, is_admin)?,
+            ))
+        }).await?;
+        if let Ok(res) = to_value(_stat.now_u.to_string()) {
+            let msg = MessageToClient::new("page_view", _stat.types.into(), res);
+            websocket_srv.do_send(msg);
         }
-        //if let Ok(res) = to_value(_stat.now_u.to_string()) {
-        //    let msg = MessageToClient::new("page_view", _stat.types.into(), res);
-        //    websocket_srv.do_send(msg);
-        //}
 
-        if is_signed_in(&session) {
-            let _request_user = get_request_user_data(&session);
-            let is_admin = _request_user.is_superuser();
-            //User::create_superuser(_request_user.id);
-            let _last_works = Item::get_works(3, 0, is_admin);
-            let _last_services = Item::get_services(3, 0, is_admin);
-            let _last_wikis = Item::get_wikis(3, 0, is_admin);
-            let _last_blogs = Item::get_blogs(3, 0, is_admin);
-            let _last_stores = Item::get_stores(3, 0, is_admin);
+        if let Some((_request_user, _)) = request_user_data {
 
             if is_desctop {
                 #[derive(TemplateOnce)]
                 #[template(path = "desctop/main/mainpage.stpl")]
                 struct Template {
                     request_user:   User,
-                    last_works:     Vec<Work>,
-                    last_services:  Vec<Service>,
-                    last_wikis:     Vec<Wiki>,
-                    last_blogs:     Vec<Blog>,
-                    last_stores:    Vec<Store>,
+                    last_works:     Vec<Item>,
+                    last_services:  Vec<Item>,
+                    last_wikis:     Vec<Item>,
+                    last_blogs:     Vec<Item>,
+                    last_stores:    Vec<Item>,
                     is_ajax:        i32,
                     stat:           StatPage,
                     template_types: i16,
@@ -259,8 +234,7 @@ pub async fn index_page (
                     stat:           _stat,
                     template_types: template_types,
                 }
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+                .render_once()?;
                 Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
             }
             else {
@@ -268,11 +242,11 @@ pub async fn index_page (
                 #[template(path = "mobile/main/mainpage.stpl")]
                 struct Template {
                     request_user:   User,
-                    last_works:     Vec<Work>,
-                    last_services:  Vec<Service>,
-                    last_wikis:     Vec<Wiki>,
-                    last_blogs:     Vec<Blog>,
-                    last_stores:    Vec<Store>,
+                    last_works:     Vec<Item>,
+                    last_services:  Vec<Item>,
+                    last_wikis:     Vec<Item>,
+                    last_blogs:     Vec<Item>,
+                    last_stores:    Vec<Item>,
                     is_ajax:        i32,
                     stat:           StatPage,
                     template_types: i16,
@@ -288,27 +262,20 @@ pub async fn index_page (
                     stat:           _stat,
                     template_types: template_types,
                 }
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+                .render_once()?;
                 Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
             }
         }
         else {
-            let _last_works = Item::get_works(3, 0, false);
-            let _last_services = Item::get_services(3, 0, false);
-            let _last_wikis = Item::get_wikis(3, 0, false);
-            let _last_blogs = Item::get_blogs(3, 0, false);
-            let _last_stores = Item::get_stores(3, 0, false);
-
             if is_desctop {
                 #[derive(TemplateOnce)]
                 #[template(path = "desctop/main/anon_mainpage.stpl")]
                 struct Template {
-                    last_works:     Vec<Work>,
-                    last_services:  Vec<Service>,
-                    last_wikis:     Vec<Wiki>,
-                    last_blogs:     Vec<Blog>,
-                    last_stores:    Vec<Store>,
+                    last_works:     Vec<Item>,
+                    last_services:  Vec<Item>,
+                    last_wikis:     Vec<Item>,
+                    last_blogs:     Vec<Item>,
+                    last_stores:    Vec<Item>,
                     is_ajax:        i32,
                     stat:           StatPage,
                     template_types: i16,
@@ -323,19 +290,18 @@ pub async fn index_page (
                     stat:           _stat,
                     template_types: template_types,
                 }
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+                .render_once()?;
                 Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
             }
             else {
                 #[derive(TemplateOnce)]
                 #[template(path = "mobile/main/anon_mainpage.stpl")]
                 struct Template {
-                    last_works:     Vec<Work>,
-                    last_services:  Vec<Service>,
-                    last_wikis:     Vec<Wiki>,
-                    last_blogs:     Vec<Blog>,
-                    last_stores:    Vec<Store>,
+                    last_works:     Vec<Item>,
+                    last_services:  Vec<Item>,
+                    last_wikis:     Vec<Item>,
+                    last_blogs:     Vec<Item>,
+                    last_stores:    Vec<Item>,
                     is_ajax:        i32,
                     stat:           StatPage,
                     template_types: i16,
@@ -350,15 +316,14 @@ pub async fn index_page (
                     stat:           _stat,
                     template_types: template_types,
                 }
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+                .render_once()?;
                 Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
             }
         }
     }
 }
 
-pub async fn info_page(req: HttpRequest, session: Session) -> actix_web::Result<HttpResponse> {
+pub async fn info_page(req: HttpRequest, session: Session, pool: Data<DbPool>) -> Result<HttpResponse, Error> {
     let (is_desctop, is_ajax) = get_device_and_ajax(&req);
     let template_types = get_template(&req);
 
@@ -373,154 +338,97 @@ pub async fn info_page(req: HttpRequest, session: Session) -> actix_web::Result<
             template_types,
         ).await
     }
-    else if is_signed_in(&session) {
-        use schema::stat_pages::dsl::stat_pages;
-
-        let _connection = establish_connection();
-        let _stat: StatPage;
-        let _stats = stat_pages
-            .filter(schema::stat_pages::types.eq(10))
-            .first::<StatPage>(&_connection);
-        if _stats.is_ok() {
-            _stat = _stats.expect("E");
-        }
-        else {
-            use crate::models::NewStatPage;
-            let form = NewStatPage {
-                types:   10,
-                view:    0,
-                height:  0.0,
-                seconds: 0,
-                now_u:   0,
-            };
-            _stat = diesel::insert_into(schema::stat_pages::table)
-                .values(&form)
-                .get_result::<StatPage>(&_connection)
-                .expect("Error.");
-        }
-        let _help_cats: Vec<Cat>;
-        let cats_res = block(move || Categories::get_categories_for_types(6)).await?;
-        let _help_cats = match cats_res {
-            Ok(_ok) => _ok,
-            Err(_error) => Vec::new(),
-        };
-
-        let _request_user = get_request_user_data(&session);
-        if is_desctop {
-            #[derive(TemplateOnce)]
-            #[template(path = "desctop/pages/info.stpl")]
-            struct Template {
-                request_user:   User,
-                is_ajax:        i32,
-                help_cats:      Vec<Cat>,
-                stat:           StatPage,
-                template_types: i16,
-            }
-            let body = Template {
-                request_user:   _request_user,
-                is_ajax:        is_ajax,
-                help_cats:      _help_cats,
-                stat:           _stat,
-                template_types: template_types,
-            }
-            .render_once()
-            .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
-        }
-        else {
-            #[derive(TemplateOnce)]
-            #[template(path = "mobile/pages/info.stpl")]
-            struct Template {
-                is_ajax:        i32,
-                help_cats:      Vec<Cat>,
-                stat:           StatPage,
-                template_types: i16,
-            }
-            let body = Template {
-                is_ajax:        is_ajax,
-                help_cats:      _help_cats,
-                stat:           _stat,
-                template_types: template_types,
-            }
-            .render_once()
-            .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
-        }
-    }
     else {
-        use schema::stat_pages::dsl::stat_pages;
-
-        let _connection = establish_connection();
-        let _stat: StatPage;
-        let _stats = stat_pages
-            .filter(schema::stat_pages::types.eq(10))
-            .first::<StatPage>(&_connection);
-        if _stats.is_ok() {
-            _stat = _stats.expect("E");
-        }
-        else {
-            use crate::models::NewStatPage;
-            let form = NewStatPage {
-                types:   10,
-                view:    0,
-                height:  0.0,
-                seconds: 0,
-                now_u:   0,
-            };
-            _stat = diesel::insert_into(schema::stat_pages::table)
-                .values(&form)
-                .get_result::<StatPage>(&_connection)
-                .expect("Error.");
-        }
-        let _help_cats: Vec<Cat>;
-        let cats_res = block(move || Categories::get_categories_for_types(6)).await?;
-        let _help_cats = match cats_res {
-            Ok(_ok) => _ok,
-            Err(_error) => Vec::new(),
-        };
+        let (_stat, _help_cats) = db(&pool, |_connection| -> Result<_, Error> {
+            Ok((
+                StatPage::get_or_create(_connection, STAT_PAGE_INFO, false)?,
+                Categories::get_categories_for_types(_connection, ITEM_HELP)?,
+            ))
+        }).await?;
 
-        if is_desctop {
-            #[derive(TemplateOnce)]
-            #[template(path = "desctop/pages/anon_info.stpl")]
-            struct Template {
-                is_ajax:        i32,
-                help_cats:      Vec<Cat>,
-                stat:           StatPage,
-                template_types: i16,
+        if is_signed_in(&session) {
+            let (_request_user, _) = get_request_user_data(&pool, &session).await?;
+            if is_desctop {
+                #[derive(TemplateOnce)]
+                #[template(path = "desctop/pages/info.stpl")]
+                struct Template {
+                    request_user:   User,
+                    is_ajax:        i32,
+                    help_cats:      Vec<Cat>,
+                    stat:           StatPage,
+                    template_types: i16,
+                }
+                let body = Template {
+                    request_user:   _request_user,
+                    is_ajax:        is_ajax,
+                    help_cats:      _help_cats,
+                    stat:           _stat,
+                    template_types: template_types,
+                }
+                .render_once()?;
+                Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
             }
-            let body = Template {
-                is_ajax:        is_ajax,
-                help_cats:      _help_cats,
-                stat:           _stat,
-                template_types: template_types,
+            else {
+                #[derive(TemplateOnce)]
+                #[template(path = "mobile/pages/info.stpl")]
+                struct Template {
+                    is_ajax:        i32,
+                    help_cats:      Vec<Cat>,
+                    stat:           StatPage,
+                    template_types: i16,
+                }
+                let body = Template {
+                    is_ajax:        is_ajax,
+                    help_cats:      _help_cats,
+                    stat:           _stat,
+                    template_types: template_types,
+                }
+                .render_once()?;
+                Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
             }
-            .render_once()
-            .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
         }
         else {
-            #[derive(TemplateOnce)]
-            #[template(path = "mobile/pages/anon_info.stpl")]
-            struct Template {
-                help_cats:      Vec<Cat>,
-                is_ajax:        i32,
-                stat:           StatPage,
-                template_types: i16,
+            if is_desctop {
+                #[derive(TemplateOnce)]
+                #[template(path = "desctop/pages/anon_info.stpl")]
+                struct Template {
+                    is_ajax:        i32,
+                    help_cats:      Vec<Cat>,
+                    stat:           StatPage,
+                    template_types: i16,
+                }
+                let body = Template {
+                    is_ajax:        is_ajax,
+                    help_cats:      _help_cats,
+                    stat:           _stat,
+                    template_types: template_types,
+                }
+                .render_once()?;
+                Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
             }
-            let body = Template {
-                is_ajax:        is_ajax,
-                help_cats:      _help_cats,
-                stat:           _stat,
-                template_types: template_types,
+            else {
+                #[derive(TemplateOnce)]
+                #[template(path = "mobile/pages/anon_info.stpl")]
+                struct Template {
+                    help_cats:      Vec<Cat>,
+                    is_ajax:        i32,
+                    stat:           StatPage,
+                    template_types: i16,
+                }
+                let body = Template {
+                    is_ajax:        is_ajax,
+                    help_cats:      _help_cats,
+                    stat:           _stat,
+                    template_types: template_types,
+                }
+                .render_once()?;
+                Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
             }
-            .render_once()
-            .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
         }
     }
 }
 
-pub async fn history_page(conn: ConnectionInfo, req: HttpRequest, session: Session) -> actix_web::Result<HttpResponse> {
+pub async fn history_page(conn: ConnectionInfo, req: HttpRequest, session: Session, pool: Data<DbPool>) -> Result<HttpResponse, Error> {
     let (is_desctop, is_ajax) = get_device_and_ajax(&req);
 
     let template_types = get_template(&req);
@@ -536,28 +444,20 @@ pub async fn history_page(conn: ConnectionInfo, req: HttpRequest, session: Sessi
         ).await
     }
     else {
-        use schema::cookie_users::dsl::cookie_users;
         use crate::models::{CookieUser, CookieStat};
         use crate::utils::{get_page, get_or_create_cookie_user_id};
 
-        let user_id = get_or_create_cookie_user_id(conn, &req).await;
-        let _connection = establish_connection();
-        let _cookie_user = cookie_users
-            .filter(schema::cookie_users::id.eq(&user_id))
-            .first::<CookieUser>(&_connection)
-            .expect("Error");
-
-            let object_list: Vec<CookieStat>;
-            let next_page_number: i32;
-            let page = get_page(&req);
-            let _res = block(move || CookieStat::get_stat_list(user_id, page, 20)).await?;
-            let _dict = match _res {
-                Ok(_ok) => {object_list = _ok.0; next_page_number = _ok.1},
-                Err(_error) => {object_list = Vec::new(); next_page_number = 0},
-            };
+        let user_id = get_or_create_cookie_user_id(&pool, &conn, &req, &session).await?;
+        let page = get_page(&req);
+        let (_cookie_user, (object_list, next_page_number)) = db(&pool, move |_connection| -> Result<_, Error> {
+            Ok((
+                CookieUser::get(_connection, user_id)?,
+                CookieStat::get_stat_list(_connection, user_id, page, 20)?,
+            ))
+        }).await?;
 
         if is_signed_in(&session) {
-            let _request_user = get_request_user_data(&session);
+            let (_request_user, _) = get_request_user_data(&pool, &session).await?;
             if is_desctop {
                 #[derive(TemplateOnce)]
                 #[template(path = "desctop/pages/history.stpl")]
@@ -568,7 +468,6 @@ pub async fn history_page(conn: ConnectionInfo, req: HttpRequest, session: Sessi
                     is_ajax:          i32,
                     next_page_number: i32,
                     template_types:   i16,
-
                 }
                 let body = Template {
                     request_user:     _request_user,
@@ -578,8 +477,7 @@ pub async fn history_page(conn: ConnectionInfo, req: HttpRequest, session: Sessi
                     next_page_number: next_page_number,
                     template_types:   template_types,
                 }
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+                .render_once()?;
                 Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
             }
             else {
@@ -599,8 +497,7 @@ pub async fn history_page(conn: ConnectionInfo, req: HttpRequest, session: Sessi
                     next_page_number: next_page_number,
                     template_types:   template_types,
                 }
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+                .render_once()?;
                 Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
             }
         }
@@ -622,8 +519,7 @@ pub async fn history_page(conn: ConnectionInfo, req: HttpRequest, session: Sessi
                     next_page_number: next_page_number,
                     template_types:   template_types,
                 }
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+                .render_once()?;
                 Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
             }
             else {
@@ -643,86 +539,70 @@ pub async fn history_page(conn: ConnectionInfo, req: HttpRequest, session: Sessi
                     next_page_number: next_page_number,
                     template_types:   template_types,
                 }
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+                .render_once()?;
                 Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
             }
         }
     }
 }
 
-pub async fn feedback_list_page(req: HttpRequest, session: Session) -> actix_web::Result<HttpResponse> {
-        if !is_signed_in(&session) {
-            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied"))
+pub async fn feedback_list_page(req: HttpRequest, session: Session, pool: Data<DbPool>) -> Result<HttpResponse, Error> {
+    if !is_signed_in(&session) {
+        return permission_denied();
+    }
+    use crate::models::Feedback;
+
+    let template_types = get_template(&req);
+    let (is_desctop, is_ajax) = get_device_and_ajax(&req);
+    let (_request_user, is_admin) = get_request_user_data(&pool, &session).await?;
+    if !is_admin {
+        return permission_denied();
+    }
+    let _feedbacks = db(&pool, Feedback::get_all).await?;
+
+    if is_desctop {
+        #[derive(TemplateOnce)]
+        #[template(path = "desctop/main/feedback_list.stpl")]
+        struct Template {
+            request_user:   User,
+            is_ajax:        i32,
+            feedback_list:  Vec<Feedback>,
+            template_types: i16,
         }
-        else {
-            use crate::schema::feedbacks::dsl::feedbacks;
-            use crate::models::Feedback;
-
-            let _connection = establish_connection();
-            let template_types = get_template(&req);
-            let _feedbacks = feedbacks
-                .load::<Feedback>(&_connection)
-                .expect("E");
-
-            let _request_user = get_request_user_data(&session);
-            let (is_desctop, is_ajax) = get_device_and_ajax(&req);
-            if _request_user.perm < 60 {
-                Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied"))
-            }
-            else if is_desctop {
-                #[derive(TemplateOnce)]
-                #[template(path = "desctop/main/feedback_list.stpl")]
-                struct Template {
-                    request_user:   User,
-                    is_ajax:        i32,
-                    feedback_list:  Vec<Feedback>,
-                    template_types: i16,
-                }
-                let body = Template {
-                    request_user:   _request_user,
-                    is_ajax:        is_ajax,
-                    feedback_list:  _feedbacks,
-                    template_types: template_types,
-                }
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-                Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
-            }
-            else {
-                #[derive(TemplateOnce)]
-                #[template(path = "mobile/main/feedback_list.stpl")]
-                struct Template {
-                    is_ajax:        i32,
-                    feedback_list:  Vec<Feedback>,
-                    template_types: i16,
-                }
-                let body = Template {
-                    is_ajax:        is_ajax,
-                    feedback_list:  _feedbacks,
-                    template_types: template_types,
-                }
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-                Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
-            }
+        let body = Template {
+            request_user:   _request_user,
+            is_ajax:        is_ajax,
+            feedback_list:  _feedbacks,
+            template_types: template_types,
+        }
+        .render_once()?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+    }
+    else {
+        #[derive(TemplateOnce)]
+        #[template(path = "mobile/main/feedback_list.stpl")]
+        struct Template {
+            is_ajax:        i32,
+            feedback_list:  Vec<Feedback>,
+            template_types: i16,
+        }
+        let body = Template {
+            is_ajax:        is_ajax,
+            feedback_list:  _feedbacks,
+            template_types: template_types,
         }
+        .render_once()?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+    }
 }
 
-pub async fn serve_list_page(req: HttpRequest, session: Session) -> actix_web::Result<HttpResponse> {
+pub async fn serve_list_page(req: HttpRequest, session: Session, pool: Data<DbPool>) -> Result<HttpResponse, Error> {
     use crate::models::TechCategories;
-    use crate::schema::tech_categories::dsl::tech_categories;
 
-    let _connection = establish_connection();
     let template_types = get_template(&req);
-    let all_tech_categories = tech_categories
-        .order(schema::tech_categories::level.asc())
-        .load::<TechCategories>(&_connection)
-        .expect("E.");
-
     let (is_desctop, is_ajax) = get_device_and_ajax(&req);
     if is_ajax == 0 {
-        get_first_load_page (
+        return get_first_load_page (
             &session,
             is_desctop,
             "Список опций и услуг".to_string(),
@@ -730,10 +610,12 @@ pub async fn serve_list_page(req: HttpRequest, session: Session) -> actix_web::R
             "/serve_list/".to_string(),
             "/static/images/dark/store.jpg".to_string(),
             template_types,
-        ).await
+        ).await;
     }
-    else if is_signed_in(&session) {
-        let _request_user = get_request_user_data(&session);
+
+    let all_tech_categories = db(&pool, TechCategories::get_all).await?;
+    if is_signed_in(&session) {
+        let (_request_user, _) = get_request_user_data(&pool, &session).await?;
         if is_desctop {
             #[derive(TemplateOnce)]
             #[template(path = "desctop/main/serve_list.stpl")]
@@ -749,8 +631,7 @@ pub async fn serve_list_page(req: HttpRequest, session: Session) -> actix_web::R
                 tech_cats:      all_tech_categories,
                 template_types: template_types,
             }
-            .render_once()
-            .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+            .render_once()?;
             Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
         }
         else {
@@ -768,8 +649,7 @@ pub async fn serve_list_page(req: HttpRequest, session: Session) -> actix_web::R
                 tech_cats:      all_tech_categories,
                 template_types: template_types,
             }
-            .render_once()
-            .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+            .render_once()?;
             Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
         }
     }
@@ -787,8 +667,7 @@ pub async fn serve_list_page(req: HttpRequest, session: Session) -> actix_web::R
                 tech_cats:      all_tech_categories,
                 template_types: template_types,
             }
-            .render_once()
-            .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+            .render_once()?;
             Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
         }
         else {
@@ -804,23 +683,18 @@ pub async fn serve_list_page(req: HttpRequest, session: Session) -> actix_web::R
                 tech_cats:      all_tech_categories,
                 template_types: template_types,
             }
-            .render_once()
-            .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+            .render_once()?;
             Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
         }
     }
 }
 
-pub async fn get_tech_category_page(req: HttpRequest, _id: web::Path<i32>) -> actix_web::Result<HttpResponse> {
+pub async fn get_tech_category_page(req: HttpRequest, pool: Data<DbPool>, _id: web::Path<i32>) -> Result<HttpResponse, Error> {
     use crate::models::TechCategories;
-    use crate::schema::tech_categories::dsl::tech_categories;
 
-    let _connection = establish_connection();
     let template_types = get_template(&req);
-    let tech_category = tech_categories
-        .filter(schema::tech_categories::id.eq(*_id))
-        .first::<TechCategories>(&_connection)
-        .expect("E.");
+    let tech_cat_id = *_id;
+    let tech_category = db(&pool, move |_connection| TechCategories::get(_connection, tech_cat_id)).await?;
 
     #[derive(TemplateOnce)]
     #[template(path = "desctop/load/tech_category.stpl")]
@@ -832,21 +706,16 @@ pub async fn get_tech_category_page(req: HttpRequest, _id: web::Path<i32>) -> ac
         object:         tech_category,
         template_types: template_types,
     }
-    .render_once()
-    .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    .render_once()?;
     Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
 }
 
-pub async fn get_serve_category_page(req: HttpRequest, _id: web::Path<i32>) -> actix_web::Result<HttpResponse> {
+pub async fn get_serve_category_page(req: HttpRequest, pool: Data<DbPool>, _id: web::Path<i32>) -> Result<HttpResponse, Error> {
     use crate::models::ServeCategories;
-    use crate::schema::serve_categories::dsl::serve_categories;
 
-    let _connection = establish_connection();
     let template_types = get_template(&req);
-    let serve_category = serve_categories
-        .filter(schema::serve_categories::id.eq(*_id))
-        .first::<ServeCategories>(&_connection)
-        .expect("E.");
+    let category_id = *_id;
+    let serve_category = db(&pool, move |_connection| ServeCategories::get(_connection, category_id)).await?;
 
     #[derive(TemplateOnce)]
     #[template(path = "desctop/load/serve_category.stpl")]
@@ -858,21 +727,16 @@ pub async fn get_serve_category_page(req: HttpRequest, _id: web::Path<i32>) -> a
         object:         serve_category,
         template_types: template_types,
     }
-    .render_once()
-    .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    .render_once()?;
     Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
 }
 
-pub async fn get_serve_page(req: HttpRequest, _id: web::Path<i32>) -> actix_web::Result<HttpResponse> {
+pub async fn get_serve_page(req: HttpRequest, pool: Data<DbPool>, _id: web::Path<i32>) -> Result<HttpResponse, Error> {
     use crate::models::Serve;
-    use crate::schema::serve::dsl::serve;
 
-    let _connection = establish_connection();
     let template_types = get_template(&req);
-    let _serve = serve
-        .filter(schema::serve::id.eq(*_id))
-        .first::<Serve>(&_connection)
-        .expect("E.");
+    let serve_id = *_id;
+    let _serve = db(&pool, move |_connection| Serve::get(_connection, serve_id)).await?;
 
     #[derive(TemplateOnce)]
     #[template(path = "desctop/load/serve.stpl")]
@@ -884,12 +748,11 @@ pub async fn get_serve_page(req: HttpRequest, _id: web::Path<i32>) -> actix_web:
         object:         _serve,
         template_types: template_types,
     }
-    .render_once()
-    .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    .render_once()?;
     Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
 }
 
-pub async fn get_feedback_page(req: HttpRequest) -> actix_web::Result<HttpResponse> {
+pub async fn get_feedback_page(req: HttpRequest) -> Result<HttpResponse, Error> {
     let template_types = get_template(&req);
     #[derive(TemplateOnce)]
     #[template(path = "desctop/load/feedback.stpl")]
@@ -899,20 +762,18 @@ pub async fn get_feedback_page(req: HttpRequest) -> actix_web::Result<HttpRespon
     let body = Template {
         template_types: template_types,
     }
-    .render_once()
-    .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    .render_once()?;
     Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
 }
 
-pub async fn cookie_users_list_page(session: Session, req: HttpRequest) -> actix_web::Result<HttpResponse> {
+pub async fn cookie_users_list_page(session: Session, req: HttpRequest, pool: Data<DbPool>) -> Result<HttpResponse, Error> {
     use crate::utils::get_page;
     use crate::models::CookieUser;
 
     let (is_desctop, is_ajax) = get_device_and_ajax(&req);
     let template_types = get_template(&req);
-    let _connection = establish_connection();
     if is_ajax == 0 {
-        get_first_load_page (
+        return get_first_load_page (
             &session,
             is_desctop,
             "Общая статистика сайта".to_string(),
@@ -920,158 +781,143 @@ pub async fn cookie_users_list_page(session: Session, req: HttpRequest) -> actix
             "/cookie_users_list/".to_string(),
             "/static/images/dark/store.jpg".to_string(),
             template_types,
-        ).await
+        ).await;
     }
-    else {
-        let (object_list, next_page_number) = CookieUser::get_users_list(get_page(&req), 20);
 
-        if is_signed_in(&session) {
-            let _request_user = get_request_user_data(&session);
-            if is_desctop {
-                #[derive(TemplateOnce)]
-                #[template(path = "desctop/pages/stat.stpl")]
-                struct Template {
-                    request_user:     User,
-                    object_list:      Vec<CookieUser>,
-                    next_page_number: i32,
-                    is_ajax:          i32,
-                    template_types:   i16,
-                }
-                let body = Template {
-                    request_user:     _request_user,
-                    object_list:      object_list,
-                    next_page_number: next_page_number,
-                    is_ajax:          is_ajax,
-                    template_types:   template_types,
-                }
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-                Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
-            }
-            else {
-                #[derive(TemplateOnce)]
-                #[template(path = "mobile/pages/stat.stpl")]
-                struct Template {
-                    request_user:     User,
-                    object_list:      Vec<CookieUser>,
-                    next_page_number: i32,
-                    is_ajax:          i32,
-                    template_types:   i16,
-                }
-                let body = Template {
-                    request_user:     _request_user,
-                    object_list:      object_list,
-                    next_page_number: next_page_number,
-                    is_ajax:          is_ajax,
-                    template_types:   template_types,
-                }
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-                Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
-            }
-        }
-        else {
-            if is_desctop {
-                #[derive(TemplateOnce)]
-                #[template(path = "desctop/pages/anon_stat.stpl")]
-                struct Template {
-                    object_list:      Vec<CookieUser>,
-                    next_page_number: i32,
-                    is_ajax:          i32,
-                    template_types:   i16,
-                }
-                let body = Template {
-                    object_list:      object_list,
-                    next_page_number: next_page_number,
-                    is_ajax:          is_ajax,
-                    template_types:   template_types,
-                }
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-                Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
-            }
-            else {
-                #[derive(TemplateOnce)]
-                #[template(path = "mobile/pages/anon_stat.stpl")]
-                struct Template {
-                    object_list:      Vec<CookieUser>,
-                    next_page_number: i32,
-                    is_ajax:          i32,
-                    template_types:   i16,
-                }
-                let body = Template {
-                    object_list:      object_list,
-                    next_page_number: next_page_number,
-                    is_ajax:          is_ajax,
-                    template_types:   template_types,
-                }
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-                Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
-            }
-        }
-    }
-}
+    let page = get_page(&req);
+    let (object_list, next_page_number) = db(&pool, move |_connection| {
+        CookieUser::get_users_list(_connection, page, 20)
+    }).await?;
 
-pub async fn get_user_history_page(session: Session, req: HttpRequest, user_id: web::Path<i32>) -> actix_web::Result<HttpResponse> {
     if is_signed_in(&session) {
-        let _request_user = get_request_user_data(&session);
-        let template_types = get_template(&req);
-        if _request_user.is_superuser() {
-            use crate::utils::get_page;
-            use crate::models::CookieStat;
-
-            let object_list: Vec<CookieStat>;
-            let next_page_number: i32;
-            let page = get_page(&req);
-            let _res = block(move || CookieStat::get_stat_list(*user_id, page, 20)).await?;
-            let _dict = match _res {
-                Ok(_ok) => {object_list = _ok.0; next_page_number = _ok.1},
-                Err(_error) => {object_list = Vec::new(); next_page_number = 0},
-            };
-
+        let (_request_user, _) = get_request_user_data(&pool, &session).await?;
+        if is_desctop {
             #[derive(TemplateOnce)]
-            #[template(path = "desctop/load/user_stat.stpl")]
+            #[template(path = "desctop/pages/stat.stpl")]
             struct Template {
-                object_list:      Vec<CookieStat>,
+                request_user:     User,
+                object_list:      Vec<CookieUser>,
                 next_page_number: i32,
+                is_ajax:          i32,
                 template_types:   i16,
             }
             let body = Template {
+                request_user:     _request_user,
                 object_list:      object_list,
                 next_page_number: next_page_number,
+                is_ajax:          is_ajax,
                 template_types:   template_types,
             }
-            .render_once()
-            .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+            .render_once()?;
             Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
         }
         else {
-            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied"))
+            #[derive(TemplateOnce)]
+            #[template(path = "mobile/pages/stat.stpl")]
+            struct Template {
+                request_user:     User,
+                object_list:      Vec<CookieUser>,
+                next_page_number: i32,
+                is_ajax:          i32,
+                template_types:   i16,
+            }
+            let body = Template {
+                request_user:     _request_user,
+                object_list:      object_list,
+                next_page_number: next_page_number,
+                is_ajax:          is_ajax,
+                template_types:   template_types,
+            }
+            .render_once()?;
+            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
         }
     }
     else {
-        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied"))
-    }
-}
-
-pub async fn get_tech_objects_page(req: HttpRequest, session: Session, _id: web::Path<i32>) -> actix_web::Result<HttpResponse> {
-    use crate::models::TechCategories;
-    use crate::schema::tech_categories::dsl::tech_categories;
+        if is_desctop {
+            #[derive(TemplateOnce)]
+            #[template(path = "desctop/pages/anon_stat.stpl")]
+            struct Template {
+                object_list:      Vec<CookieUser>,
+                next_page_number: i32,
+                is_ajax:          i32,
+                template_types:   i16,
+            }
+            let body = Template {
+                object_list:      object_list,
+                next_page_number: next_page_number,
+                is_ajax:          is_ajax,
+                template_types:   template_types,
+            }
+            .render_once()?;
+            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+        }
+        else {
+            #[derive(TemplateOnce)]
+            #[template(path = "mobile/pages/anon_stat.stpl")]
+            struct Template {
+                object_list:      Vec<CookieUser>,
+                next_page_number: i32,
+                is_ajax:          i32,
+                template_types:   i16,
+            }
+            let body = Template {
+                object_list:      object_list,
+                next_page_number: next_page_number,
+                is_ajax:          is_ajax,
+                template_types:   template_types,
+            }
+            .render_once()?;
+            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+        }
+    }
+}
+
+pub async fn get_user_history_page(session: Session, req: HttpRequest, pool: Data<DbPool>, user_id: web::Path<i32>) -> Result<HttpResponse, Error> {
+    if !is_signed_in(&session) {
+        return permission_denied();
+    }
+    let (_, is_admin) = get_request_user_data(&pool, &session).await?;
+    if !is_admin {
+        return permission_denied();
+    }
+    use crate::utils::get_page;
+    use crate::models::CookieStat;
 
-    let mut is_admin = false;
     let template_types = get_template(&req);
-    if is_signed_in(&session) {
-        let _request_user = get_request_user_data(&session);
-        if _request_user.is_superuser() {
-            is_admin = true;
-        }
+    let page = get_page(&req);
+    let cookie_user_id = *user_id;
+    let (object_list, next_page_number) = db(&pool, move |_connection| {
+        CookieStat::get_stat_list(_connection, cookie_user_id, page, 20)
+    }).await?;
+
+    #[derive(TemplateOnce)]
+    #[template(path = "desctop/load/user_stat.stpl")]
+    struct Template {
+        object_list:      Vec<CookieStat>,
+        next_page_number: i32,
+        template_types:   i16,
+    }
+    let body = Template {
+        object_list:      object_list,
+        next_page_number: next_page_number,
+        template_types:   template_types,
     }
-    let _connection = establish_connection();
-    let _cat = tech_categories
-        .filter(schema::tech_categories::id.eq(*_id))
-        .first::<TechCategories>(&_connection)
-        .expect("E.");
+    .render_once()?;
+    Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+}
+
+pub async fn get_tech_objects_page(req: HttpRequest, session: Session, pool: Data<DbPool>, _id: web::Path<i32>) -> Result<HttpResponse, Error> {
+    use crate::models::TechCategories;
+
+    let template_types = get_template(&req);
+    let is_admin = if is_signed_in(&session) {
+        get_request_user_data(&pool, &session).await?.1
+    }
+    else {
+        false
+    };
+    let tech_cat_id = *_id;
+    let _cat = db(&pool, move |_connection| TechCategories::get(_connection, tech_cat_id)).await?;
 
     #[derive(TemplateOnce)]
     #[template(path = "desctop/load/tech_category_objects.stpl")]
@@ -1085,59 +931,46 @@ pub async fn get_tech_objects_page(req: HttpRequest, session: Session, _id: web:
         is_admin:       is_admin,
         template_types: template_types,
     }
-    .render_once()
-    .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    .render_once()?;
     Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
 }
 
-pub async fn unical_object_form_page(req: HttpRequest, session: Session, _id: web::Path<i16>) -> actix_web::Result<HttpResponse> {
+pub async fn unical_object_form_page(req: HttpRequest, session: Session, pool: Data<DbPool>, _id: web::Path<i16>) -> Result<HttpResponse, Error> {
+    if !is_signed_in(&session) {
+        return permission_denied();
+    }
+    let (_, is_admin) = get_request_user_data(&pool, &session).await?;
+    if !is_admin {
+        return permission_denied();
+    }
+
     let template_types = get_template(&req);
-    if is_signed_in(&session) {
-        let _request_user = get_request_user_data(&session);
-        if !_request_user.is_superuser() {
-            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied"))
-        }
-        else {
-            let _connection = establish_connection();
-            let types = *_id;
-            let mut biznes_mode = false;
-            if vec![2,3,5].iter().any(|i| i==&types) {
-                biznes_mode = true;
-            }
-            let _cats: Vec<Cat>;
-            let cats_res = block(move || Categories::get_categories_for_types(types)).await?;
-            let _cats = match cats_res {
-                Ok(_ok) => _ok,
-                Err(_error) => Vec::new(),
-            };
+    let types = *_id;
+    // услуги, товары и работы - объекты бизнеса, у них своя часть формы
+    let biznes_mode = [ITEM_SERVICE, ITEM_STORE, ITEM_WORK].contains(&types);
+    let _cats = db(&pool, move |_connection| Categories::get_categories_for_types(_connection, types)).await?;
 
-            #[derive(TemplateOnce)]
-            #[template(path = "desctop/load/unical_object_form.stpl")]
-            struct Template {
-                cats:           Vec<Cat>,
-                biznes_mode:    bool,
-                template_types: i16,
-            }
-            let body = Template {
-                cats:           _cats,
-                biznes_mode:    biznes_mode,
-                template_types: template_types,
-            }
-            .render_once()
-            .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
-        }
+    #[derive(TemplateOnce)]
+    #[template(path = "desctop/load/unical_object_form.stpl")]
+    struct Template {
+        cats:           Vec<Cat>,
+        biznes_mode:    bool,
+        template_types: i16,
     }
-    else {
-        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied"))
+    let body = Template {
+        cats:           _cats,
+        biznes_mode:    biznes_mode,
+        template_types: template_types,
     }
+    .render_once()?;
+    Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
 }
 
-pub async fn create_category_page(session: Session, req: HttpRequest) -> actix_web::Result<HttpResponse> {
+pub async fn create_category_page(session: Session, req: HttpRequest, pool: Data<DbPool>) -> Result<HttpResponse, Error> {
     let (is_desctop, is_ajax) = get_device_and_ajax(&req);
     let template_types = get_template(&req);
     if is_ajax == 0 {
-        get_first_load_page (
+        return get_first_load_page (
             &session,
             is_desctop,
             "Создание категории".to_string(),
@@ -1145,148 +978,124 @@ pub async fn create_category_page(session: Session, req: HttpRequest) -> actix_w
             "/create_category/".to_string(),
             "/static/images/dark/store.jpg".to_string(),
             template_types,
-        ).await
+        ).await;
     }
-    else if is_signed_in(&session) {
-        let _request_user = get_request_user_data(&session);
-        if _request_user.perm == 60 {
-            use schema::categories::dsl::categories;
-
-            let _connection = establish_connection();
-            let _cats = categories
-                .load::<Categories>(&_connection)
-                .expect("Error");
-
-            if is_desctop {
-                #[derive(TemplateOnce)]
-                #[template(path = "desctop/pages/create_category.stpl")]
-                struct Template {
-                    request_user:   User,
-                    cats:           Vec<Categories>,
-                    is_ajax:        i32,
-                    template_types: i16,
-                }
-                let body = Template {
-                    request_user:   _request_user,
-                    cats:           _cats,
-                    is_ajax:        is_ajax,
-                    template_types: template_types,
-                }
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-                Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
-            }
-            else {
-                #[derive(TemplateOnce)]
-                #[template(path = "mobile/pages/create_category.stpl")]
-                struct Template {
-                    cats:           Vec<Categories>,
-                    is_ajax:        i32,
-                    template_types: i16,
-                }
-                let body = Template {
-                    cats:           _cats,
-                    is_ajax:        is_ajax,
-                    template_types: template_types,
-                }
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-                Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
-            }
+    if !is_signed_in(&session) {
+        return permission_denied();
+    }
+    let (_request_user, is_admin) = get_request_user_data(&pool, &session).await?;
+    if !is_admin {
+        return permission_denied();
+    }
+    let _cats = db(&pool, Categories::get_all).await?;
+
+    if is_desctop {
+        #[derive(TemplateOnce)]
+        #[template(path = "desctop/pages/create_category.stpl")]
+        struct Template {
+            request_user:   User,
+            cats:           Vec<Categories>,
+            is_ajax:        i32,
+            template_types: i16,
         }
-        else {
-            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied."))
+        let body = Template {
+            request_user:   _request_user,
+            cats:           _cats,
+            is_ajax:        is_ajax,
+            template_types: template_types,
         }
+        .render_once()?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
     }
     else {
-        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied."))
+        #[derive(TemplateOnce)]
+        #[template(path = "mobile/pages/create_category.stpl")]
+        struct Template {
+            cats:           Vec<Categories>,
+            is_ajax:        i32,
+            template_types: i16,
+        }
+        let body = Template {
+            cats:           _cats,
+            is_ajax:        is_ajax,
+            template_types: template_types,
+        }
+        .render_once()?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
     }
 }
 
-pub async fn edit_category_page(session: Session, req: HttpRequest, _id: web::Path<i32>) -> actix_web::Result<HttpResponse> {
+pub async fn edit_category_page(session: Session, req: HttpRequest, pool: Data<DbPool>, _id: web::Path<i32>) -> Result<HttpResponse, Error> {
     let (is_desctop, is_ajax) = get_device_and_ajax(&req);
     let template_types = get_template(&req);
     let cat_id: i32 = *_id;
-    let _connection = establish_connection();
-    let _cats = schema::categories::table
-        .filter(schema::categories::id.eq(&cat_id))
-        .load::<Categories>(&_connection)
-        .expect("E");
-    let _cat = _cats.into_iter().nth(0).unwrap();
+    let _cat = db(&pool, move |_connection| Categories::get(_connection, cat_id)).await?;
 
     if is_ajax == 0 {
-        get_first_load_page (
+        return get_first_load_page (
             &session,
             is_desctop,
             "Изменение категории ".to_string() + &_cat.name,
             "вебсервисы.рф: Изменение категории ".to_string() + &_cat.name,
-            "/edit_category/".to_string() + &_cat.id.to_string() + &"/".to_string(),
+            format!("/edit_category/{}/", _cat.id),
             _cat.get_image(),
             template_types,
-        ).await
+        ).await;
     }
-    else if is_signed_in(&session) {
-        let _request_user = get_request_user_data(&session);
-        if _request_user.perm == 60 {
-            let _cats = schema::categories::table
-                .load::<Categories>(&_connection)
-                .expect("Error");
-
-            if is_desctop {
-                #[derive(TemplateOnce)]
-                #[template(path = "desctop/pages/edit_category.stpl")]
-                struct Template {
-                    request_user:   User,
-                    cat:            Categories,
-                    cats:           Vec<Categories>,
-                    is_ajax:        i32,
-                    template_types: i16,
-                }
-                let body = Template {
-                    request_user:   _request_user,
-                    cat:            _cat,
-                    cats:           _cats,
-                    is_ajax:        is_ajax,
-                    template_types: template_types,
-                }
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-                Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
-            }
-            else {
-                #[derive(TemplateOnce)]
-                #[template(path = "mobile/pages/edit_category.stpl")]
-                struct Template {
-                    cat:            Categories,
-                    cats:           Vec<Categories>,
-                    is_ajax:        i32,
-                    template_types: i16,
-                }
-                let body = Template {
-                    cat:            _cat,
-                    cats:           _cats,
-                    is_ajax:        is_ajax,
-                    template_types: template_types,
-                }
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-                Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
-            }
+    if !is_signed_in(&session) {
+        return permission_denied();
+    }
+    let (_request_user, is_admin) = get_request_user_data(&pool, &session).await?;
+    if !is_admin {
+        return permission_denied();
+    }
+    let _cats = db(&pool, Categories::get_all).await?;
+
+    if is_desctop {
+        #[derive(TemplateOnce)]
+        #[template(path = "desctop/pages/edit_category.stpl")]
+        struct Template {
+            request_user:   User,
+            cat:            Categories,
+            cats:           Vec<Categories>,
+            is_ajax:        i32,
+            template_types: i16,
         }
-        else {
-            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied."))
+        let body = Template {
+            request_user:   _request_user,
+            cat:            _cat,
+            cats:           _cats,
+            is_ajax:        is_ajax,
+            template_types: template_types,
         }
+        .render_once()?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
     }
     else {
-        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied."))
+        #[derive(TemplateOnce)]
+        #[template(path = "mobile/pages/edit_category.stpl")]
+        struct Template {
+            cat:            Categories,
+            cats:           Vec<Categories>,
+            is_ajax:        i32,
+            template_types: i16,
+        }
+        let body = Template {
+            cat:            _cat,
+            cats:           _cats,
+            is_ajax:        is_ajax,
+            template_types: template_types,
+        }
+        .render_once()?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
     }
 }
 
-pub async fn create_item_page(session: Session, req: HttpRequest) -> actix_web::Result<HttpResponse> {
+pub async fn create_item_page(session: Session, req: HttpRequest, pool: Data<DbPool>) -> Result<HttpResponse, Error> {
     let (is_desctop, is_ajax) = get_device_and_ajax(&req);
     let template_types = get_template(&req);
     if is_ajax == 0 {
-        get_first_load_page (
+        return get_first_load_page (
             &session,
             is_desctop,
             "Создание объекта".to_string(),
@@ -1294,395 +1103,301 @@ pub async fn create_item_page(session: Session, req: HttpRequest) -> actix_web::
             "/create_item/".to_string(),
             "/static/images/dark/store.jpg".to_string(),
             template_types,
-        ).await
+        ).await;
     }
-    else if is_signed_in(&session) {
-        let _request_user = get_request_user_data(&session);
-        if _request_user.perm == 60 {
-            use schema::{
-                tags::dsl::tags,
-                tech_categories::dsl::tech_categories,
-            };
-            use crate::models::TechCategories;
-
-            let _connection = establish_connection();
-            let all_tags = tags
-                .load::<Tag>(&_connection)
-                .expect("Error.");
-
-            let _tech_categories = tech_categories
-                .load::<TechCategories>(&_connection)
-                .expect("E");
-
-            if is_desctop {
-                #[derive(TemplateOnce)]
-                #[template(path = "desctop/pages/create_item.stpl")]
-                struct Template {
-                    request_user:   User,
-                    all_tags:       Vec<Tag>,
-                    is_ajax:        i32,
-                    template_types: i16,
-                }
-                let body = Template {
-                    request_user:   _request_user,
-                    all_tags:       all_tags,
-                    is_ajax:        is_ajax,
-                    template_types: template_types,
-                }
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-                Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
-            }
-            else {
-                #[derive(TemplateOnce)]
-                #[template(path = "mobile/pages/create_item.stpl")]
-                struct Template {
-                    all_tags:       Vec<Tag>,
-                    is_ajax:        i32,
-                    template_types: i16,
-                }
-                let body = Template {
-                    all_tags:       all_tags,
-                    is_ajax:        is_ajax,
-                    template_types: template_types,
-                }
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-                Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
-            }
+    if !is_signed_in(&session) {
+        return permission_denied();
+    }
+    let (_request_user, is_admin) = get_request_user_data(&pool, &session).await?;
+    if !is_admin {
+        return permission_denied();
+    }
+    let all_tags = db(&pool, Tag::all).await?;
+
+    if is_desctop {
+        #[derive(TemplateOnce)]
+        #[template(path = "desctop/pages/create_item.stpl")]
+        struct Template {
+            request_user:   User,
+            all_tags:       Vec<Tag>,
+            is_ajax:        i32,
+            template_types: i16,
         }
-        else {
-            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied."))
+        let body = Template {
+            request_user:   _request_user,
+            all_tags:       all_tags,
+            is_ajax:        is_ajax,
+            template_types: template_types,
         }
+        .render_once()?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
     }
     else {
-        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied."))
+        #[derive(TemplateOnce)]
+        #[template(path = "mobile/pages/create_item.stpl")]
+        struct Template {
+            all_tags:       Vec<Tag>,
+            is_ajax:        i32,
+            template_types: i16,
+        }
+        let body = Template {
+            all_tags:       all_tags,
+            is_ajax:        is_ajax,
+            template_types: template_types,
+        }
+        .render_once()?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
     }
 }
-pub async fn edit_item_page(session: Session, req: HttpRequest, _id: web::Path<i32>) -> actix_web::Result<HttpResponse> {
-    use schema::items::dsl::items;
 
+pub async fn edit_item_page(session: Session, req: HttpRequest, pool: Data<DbPool>, _id: web::Path<i32>) -> Result<HttpResponse, Error> {
     let _item_id: i32 = *_id;
     let template_types = get_template(&req);
-    let _connection = establish_connection();
-    let _item = items
-        .filter(schema::items::id.eq(&_item_id))
-        .first::<Item>(&_connection)
-        .expect("E");
+    let _item = db(&pool, move |_connection| Item::get(_connection, _item_id)).await?;
 
     let (is_desctop, is_ajax) = get_device_and_ajax(&req);
     if is_ajax == 0 {
-        get_first_load_page (
+        return get_first_load_page (
             &session,
             is_desctop,
             "Изменение объекта ".to_string() + &_item.title,
             "вебсервисы.рф: Изменение объекта ".to_string() + &_item.title,
-            "/edit_item/".to_string() + &_item.id.to_string() + &"/".to_string(),
+            format!("/edit_item/{}/", _item.id),
             _item.get_image(),
             template_types,
-        ).await
+        ).await;
     }
-    else if is_signed_in(&session) {
-        let _request_user = get_request_user_data(&session);
-        if _request_user.perm == 60 || _item.user_id == _request_user.id {
-            use schema::{
-                tags::dsl::tags,
-                categories::dsl::categories,
-                tech_categories::dsl::tech_categories,
-            };
-            use crate::models:: TechCategories;
-
-            let item_cats = _item.get_categories_obj().expect("E");
-            let item_tags = _item.get_tags_obj().expect("E");
-
-            let _all_tags = tags
-                .load::<Tag>(&_connection)
-                .expect("Error.");
-
-            let _cats = categories
-                .filter(schema::categories::types.eq(_item.types))
-                .load::<Categories>(&_connection)
-                .expect("Error");
-
-            let mut level: i16 = 0;
-            let mut _tech_categories: Vec<TechCategories> = Vec::new();
-            let _serve = _item.get_serves();
-            if _serve.len() > 0 {
-                let tech_id = _serve[0].tech_cat_id;
-                let _tech_categories = tech_categories
-                    .filter(schema::tech_categories::id.eq(tech_id))
-                    .load::<TechCategories>(&_connection)
-                    .expect("E");
-
-                level = _tech_categories[0].level;
-                let _tech_categories = tech_categories
-                    .filter(schema::tech_categories::level.eq(level))
-                    .load::<TechCategories>(&_connection)
-                    .expect("E");
-            }
+    if !is_signed_in(&session) {
+        return permission_denied();
+    }
+    let (_request_user, is_admin) = get_request_user_data(&pool, &session).await?;
+    if !is_admin && _item.user_id != _request_user.id {
+        return permission_denied();
+    }
+    use crate::models::TechCategories;
 
-            if is_desctop {
-                #[derive(TemplateOnce)]
-                #[template(path = "desctop/pages/edit_item.stpl")]
-                struct Template {
-                    request_user:   User,
-                    object:         Item,
-                    cats:           Vec<Categories>,
-                    is_ajax:        i32,
-                    all_tags:       Vec<Tag>,
-                    item_tags:      Vec<Tag>,
-                    item_cats:      Vec<Categories>,
-                    tech_cats:      Vec<TechCategories>,
-                    level:          i16,
-                    template_types: i16,
-                }
-                let body = Template {
-                    request_user:   _request_user,
-                    object:         _item,
-                    cats:           _cats,
-                    is_ajax:        is_ajax,
-                    all_tags:       _all_tags,
-                    item_tags:      item_tags,
-                    item_cats:      item_cats,
-                    tech_cats:      _tech_categories,
-                    level:          level,
-                    template_types: template_types,
-                }
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-                Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
-            }
-            else {
-                #[derive(TemplateOnce)]
-                #[template(path = "mobile/pages/edit_item.stpl")]
-                struct Template {
-                    object:         Item,
-                    cats:           Vec<Categories>,
-                    is_ajax:        i32,
-                    all_tags:       Vec<Tag>,
-                    item_tags:      Vec<Tag>,
-                    item_cats:      Vec<Categories>,
-                    tech_cats:      Vec<TechCategories>,
-                    level:          i16,
-                    template_types: i16,
-                }
-                let body = Template {
-                    object:         _item,
-                    cats:           _cats,
-                    is_ajax:        is_ajax,
-                    all_tags:       _all_tags,
-                    item_tags:      item_tags,
-                    item_cats:      item_cats,
-                    tech_cats:      _tech_categories,
-                    level:          level,
-                    template_types: template_types,
-                }
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-                Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
-            }
+    let item = _item.clone();
+    let (item_cats, item_tags, _all_tags, _cats, level, _tech_categories) = db(&pool, move |_connection| -> Result<_, Error> {
+        let item_cats = item.get_categories_obj(_connection)?;
+        let item_tags = item.get_tags_obj(_connection)?;
+        let _all_tags = Tag::all(_connection)?;
+        let _cats = Categories::get_for_types(_connection, item.types)?;
+
+        // технические категории того же уровня, что у первой услуги объекта
+        let mut level: i16 = 0;
+        let mut _tech_categories: Vec<TechCategories> = Vec::new();
+        if let Some(_serve) = item.get_serves(_connection)?.first() {
+            level = TechCategories::get(_connection, _serve.tech_cat_id)?.level;
+            _tech_categories = TechCategories::get_for_level(_connection, level)?;
         }
-        else {
-            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied."))
+        Ok((item_cats, item_tags, _all_tags, _cats, level, _tech_categories))
+    }).await?;
+
+    if is_desctop {
+        #[derive(TemplateOnce)]
+        #[template(path = "desctop/pages/edit_item.stpl")]
+        struct Template {
+            request_user:   User,
+            object:         Item,
+            cats:           Vec<Categories>,
+            is_ajax:        i32,
+            all_tags:       Vec<Tag>,
+            item_tags:      Vec<Tag>,
+            item_cats:      Vec<Categories>,
+            tech_cats:      Vec<TechCategories>,
+            level:          i16,
+            template_types: i16,
         }
+        let body = Template {
+            request_user:   _request_user,
+            object:         _item,
+            cats:           _cats,
+            is_ajax:        is_ajax,
+            all_tags:       _all_tags,
+            item_tags:      item_tags,
+            item_cats:      item_cats,
+            tech_cats:      _tech_categories,
+            level:          level,
+            template_types: template_types,
+        }
+        .render_once()?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
     }
     else {
-        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied."))
+        #[derive(TemplateOnce)]
+        #[template(path = "mobile/pages/edit_item.stpl")]
+        struct Template {
+            object:         Item,
+            cats:           Vec<Categories>,
+            is_ajax:        i32,
+            all_tags:       Vec<Tag>,
+            item_tags:      Vec<Tag>,
+            item_cats:      Vec<Categories>,
+            tech_cats:      Vec<TechCategories>,
+            level:          i16,
+            template_types: i16,
+        }
+        let body = Template {
+            object:         _item,
+            cats:           _cats,
+            is_ajax:        is_ajax,
+            all_tags:       _all_tags,
+            item_tags:      item_tags,
+            item_cats:      item_cats,
+            tech_cats:      _tech_categories,
+            level:          level,
+            template_types: template_types,
+        }
+        .render_once()?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
     }
 }
 
-pub async fn edit_content_item_page(session: Session, req: HttpRequest, _id: web::Path<i32>) -> actix_web::Result<HttpResponse> {
-    use crate::schema::items::dsl::items;
-
+pub async fn edit_content_item_page(session: Session, req: HttpRequest, pool: Data<DbPool>, _id: web::Path<i32>) -> Result<HttpResponse, Error> {
     let _item_id: i32 = *_id;
     let template_types = get_template(&req);
-    let _connection = establish_connection();
-    let _item = items
-        .filter(schema::items::id.eq(&_item_id))
-        .first::<Item>(&_connection)
-        .expect("E");
+    let _item = db(&pool, move |_connection| Item::get(_connection, _item_id)).await?;
 
     let (is_desctop, is_ajax) = get_device_and_ajax(&req);
     if is_ajax == 0 {
-        get_first_load_page (
+        return get_first_load_page (
             &session,
             is_desctop,
             "Изменение текста объекта ".to_string() + &_item.title,
             "вебсервисы.рф: Изменение текста объекта ".to_string() + &_item.title,
-            "/edit_content_item/".to_string() + &_item.id.to_string() + &"/".to_string(),
+            format!("/edit_content_item/{}/", _item.id),
             _item.get_image(),
             template_types,
-        ).await
+        ).await;
     }
-    else if is_signed_in(&session) {
-        let _request_user = get_request_user_data(&session);
-        if _request_user.perm == 60 || _request_user.id == _item.user_id {
-            if is_desctop {
-                #[derive(TemplateOnce)]
-                #[template(path = "desctop/pages/edit_content_item.stpl")]
-                struct Template {
-                    request_user:   User,
-                    item:           Item,
-                    is_ajax:        i32,
-                    template_types: i16,
-                }
-                let body = Template {
-                    request_user:   _request_user,
-                    item:           _item,
-                    is_ajax:        is_ajax,
-                    template_types: template_types,
-                }
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-                Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
-            }
-            else {
-                #[derive(TemplateOnce)]
-                #[template(path = "mobile/pages/edit_content_item.stpl")]
-                struct Template {
-                    item:           Item,
-                    is_ajax:        i32,
-                    template_types: i16,
-                }
-                let body = Template {
-                    item:           _item,
-                    is_ajax:        is_ajax,
-                    template_types: template_types,
-                }
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-                Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
-            }
+    if !is_signed_in(&session) {
+        return permission_denied();
+    }
+    let (_request_user, is_admin) = get_request_user_data(&pool, &session).await?;
+    if !is_admin && _item.user_id != _request_user.id {
+        return permission_denied();
+    }
+
+    if is_desctop {
+        #[derive(TemplateOnce)]
+        #[template(path = "desctop/pages/edit_content_item.stpl")]
+        struct Template {
+            request_user:   User,
+            item:           Item,
+            is_ajax:        i32,
+            template_types: i16,
         }
-        else {
-            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied."))
+        let body = Template {
+            request_user:   _request_user,
+            item:           _item,
+            is_ajax:        is_ajax,
+            template_types: template_types,
         }
+        .render_once()?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
     }
     else {
-        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied."))
+        #[derive(TemplateOnce)]
+        #[template(path = "mobile/pages/edit_content_item.stpl")]
+        struct Template {
+            item:           Item,
+            is_ajax:        i32,
+            template_types: i16,
+        }
+        let body = Template {
+            item:           _item,
+            is_ajax:        is_ajax,
+            template_types: template_types,
+        }
+        .render_once()?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
     }
 }
 
-pub async fn edit_file_page(session: Session, req: HttpRequest, _id: web::Path<i32>) -> actix_web::Result<HttpResponse> {
-    use crate::schema::files::dsl::files;
-    use crate::models::File;
-
+pub async fn edit_file_page(session: Session, req: HttpRequest, pool: Data<DbPool>, _id: web::Path<i32>) -> Result<HttpResponse, Error> {
     let _file_id: i32 = *_id;
     let template_types = get_template(&req);
-    let _connection = establish_connection();
-    let _file = files
-        .filter(schema::files::id.eq(&_file_id))
-        .first::<File>(&_connection)
-        .expect("E");
+    let _file = db(&pool, move |_connection| find_file_by_id(_file_id, _connection))
+        .await?
+        .ok_or(Error::NotFound)?;
 
     let (is_desctop, is_ajax) = get_device_and_ajax(&req);
     if is_ajax == 0 {
-        get_first_load_page (
+        return get_first_load_page (
             &session,
             is_desctop,
             "Изменение файла".to_string(),
             "вебсервисы.рф: Изменение файла".to_string(),
-            "/edit_file/".to_string() + &_file.id.to_string() + &"/".to_string(),
+            format!("/edit_file/{}/", _file.id),
             "/static/images/dark/store.jpg".to_string(),
             template_types,
-        ).await
+        ).await;
     }
-    else if is_signed_in(&session) {
-        let _request_user = get_request_user_data(&session);
-        if _request_user.perm == 60 || _request_user.id == _file.user_id {
-            if is_desctop {
-                #[derive(TemplateOnce)]
-                #[template(path = "desctop/pages/edit_file.stpl")]
-                struct Template {
-                    request_user:   User,
-                    file:           File,
-                    is_ajax:        i32,
-                    template_types: i16,
-                }
-                let body = Template {
-                    request_user:   _request_user,
-                    file:           _file,
-                    is_ajax:        is_ajax,
-                    template_types: template_types,
-                }
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-                Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
-            }
-            else {
-                #[derive(TemplateOnce)]
-                #[template(path = "mobile/pages/edit_file.stpl")]
-                struct Template {
-                    file:           File,
-                    is_ajax:        i32,
-                    template_types: i16,
-                }
-                let body = Template {
-                    file:           _file,
-                    is_ajax:        is_ajax,
-                    template_types: template_types,
-                }
-                .render_once()
-                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-                Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
-            }
+    if !is_signed_in(&session) {
+        return permission_denied();
+    }
+    let (_request_user, is_admin) = get_request_user_data(&pool, &session).await?;
+    if !is_admin && _file.user_id != _request_user.id {
+        return permission_denied();
+    }
+
+    if is_desctop {
+        #[derive(TemplateOnce)]
+        #[template(path = "desctop/pages/edit_file.stpl")]
+        struct Template {
+            request_user:   User,
+            file:           File,
+            is_ajax:        i32,
+            template_types: i16,
         }
-        else {
-            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied."))
+        let body = Template {
+            request_user:   _request_user,
+            file:           _file,
+            is_ajax:        is_ajax,
+            template_types: template_types,
         }
+        .render_once()?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
     }
     else {
-        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied."))
+        #[derive(TemplateOnce)]
+        #[template(path = "mobile/pages/edit_file.stpl")]
+        struct Template {
+            file:           File,
+            is_ajax:        i32,
+            template_types: i16,
+        }
+        let body = Template {
+            file:           _file,
+            is_ajax:        is_ajax,
+            template_types: template_types,
+        }
+        .render_once()?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
     }
 }
 
-pub async fn image_page(req: HttpRequest, _id: web::Path<i32>) -> actix_web::Result<HttpResponse> {
-    use crate::schema::{
-        files::dsl::files,
-        items::dsl::items,
-    };
-    use crate::models::File;
-
-    let _connection = establish_connection();
+// Картинка из галереи объекта с переходами к соседним
+pub async fn image_page(req: HttpRequest, pool: Data<DbPool>, _id: web::Path<i32>) -> Result<HttpResponse, Error> {
     let template_types = get_template(&req);
     let _id: i32 = *_id;
-    let _file = files
-        .filter(schema::files::id.eq(_id))
-        .first::<File>(&_connection)
-        .expect("E.");
-
-    let _item = items
-        .filter(schema::items::id.eq(_file.item_id))
-        .filter(schema::items::types.eq(_file.item_types))
-        .first::<Item>(&_connection)
-        .expect("E.");
-
-    let _images = _item.get_images_ids();
-    let _images_len = _images.len();
-    let mut prev: Option<File> = None;
-    let mut next: Option<File> = None;
-
-    for (i, obj) in _images.iter().enumerate().rev() {
-        if obj == &_id {
-            if (i + 1) != _images_len {
-                let _next = Some(&_images[i + 1]);
-                next = Some(files
-                    .filter(schema::files::id.eq(_next.unwrap()))
-                    .filter(schema::files::types.eq(_item.types))
-                    .first::<File>(&_connection)
-                    .expect("E"));
-            };
+    let (_file, _item, prev, next) = db(&pool, move |_connection| -> Result<_, Error> {
+        let _file = find_file_by_id(_id, _connection)?.ok_or(Error::NotFound)?;
+        let _item = Item::get_by_image(_connection, _id)?;
+
+        let _images = _item.get_images_ids(_connection)?;
+        let mut prev: Option<File> = None;
+        let mut next: Option<File> = None;
+        if let Some(i) = _images.iter().position(|obj| *obj == _id) {
+            if let Some(next_id) = _images.get(i + 1) {
+                next = find_file_by_id(*next_id, _connection)?;
+            }
             if i != 0 {
-                let _prev = Some(&_images[i - 1]);
-                prev = Some(files
-                    .filter(schema::files::id.eq(_prev.unwrap()))
-                    .filter(schema::files::types.eq(_item.types))
-                    .first::<File>(&_connection)
-                    .expect("E"));
-            };
-            break;
+                prev = find_file_by_id(_images[i - 1], _connection)?;
+            }
         }
-    };
+        Ok((_file, _item, prev, next))
+    }).await?;
 
     #[derive(TemplateOnce)]
     #[template(path = "desctop/load/image.stpl")]
@@ -1700,7 +1415,78 @@ pub async fn image_page(req: HttpRequest, _id: web::Path<i32>) -> actix_web::Res
         next:           next,
         template_types: template_types,
     }
-    .render_once()
-    .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    .render_once()?;
     Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
 }
+
+// Статистика города, страница только для администраторов
+pub async fn city_statistics_page(
+    req: HttpRequest,
+    session: Session,
+    pool: Data<DbPool>,
+    _id: web::Path<i32>,
+    query: web::Query<StatisticsQuery>,
+) -> Result<HttpResponse, Error> {
+    let (is_desctop, is_ajax) = get_device_and_ajax(&req);
+    let template_types = get_template(&req);
+    let city_id: i32 = *_id;
+
+    if is_ajax == 0 {
+        return get_first_load_page (
+            &session,
+            is_desctop,
+            "Статистика города".to_string(),
+            "вебсервисы.рф: Статистика города".to_string(),
+            format!("/cities/{}/statistics/", city_id),
+            "/static/images/dark/store.jpg".to_string(),
+            template_types,
+        ).await;
+    }
+    if !is_signed_in(&session) {
+        return permission_denied();
+    }
+    let (_request_user, is_admin) = get_request_user_data(&pool, &session).await?;
+    if !is_admin {
+        return permission_denied();
+    }
+    let months = query.months.unwrap_or(DEFAULT_STATISTICS_MONTHS);
+    let now = chrono::Local::now().naive_local();
+    let _stats = db(&pool, move |_connection| get_city_statistics(_connection, city_id, months, now)).await?;
+
+    if is_desctop {
+        #[derive(TemplateOnce)]
+        #[template(path = "desctop/pages/city_statistics.stpl")]
+        struct Template {
+            request_user:   User,
+            is_ajax:        i32,
+            stats:          CityStatistics,
+            template_types: i16,
+        }
+        let body = Template {
+            request_user:   _request_user,
+            is_ajax:        is_ajax,
+            stats:          _stats,
+            template_types: template_types,
+        }
+        .render_once()?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+    }
+    else {
+        #[derive(TemplateOnce)]
+        #[template(path = "mobile/pages/city_statistics.stpl")]
+        struct Template {
+            request_user:   User,
+            is_ajax:        i32,
+            stats:          CityStatistics,
+            template_types: i16,
+        }
+        let body = Template {
+            request_user:   _request_user,
+            is_ajax:        is_ajax,
+            stats:          _stats,
+            template_types: template_types,
+        }
+        .render_once()?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+    }
+}
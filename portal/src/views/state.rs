@@ -0,0 +1,21 @@
+use actix_web::{web, Result};
+
+use crate::utils::{AppState, IndexResponse};
+
+
+// Служебная страница: номер воркера, его счетчик запросов и общий список сообщений
+pub fn state_routes(config: &mut web::ServiceConfig) {
+    config.route("/test/", web::get().to(test_page));
+}
+
+pub async fn test_page(state: web::Data<AppState>) -> Result<web::Json<IndexResponse>> {
+    let request_count = state.request_count.get() + 1;
+    state.request_count.set(request_count);
+    let ms = state.messages.lock().unwrap();
+
+    Ok(web::Json(IndexResponse {
+        server_id: state.server_id,
+        request_count,
+        messages: ms.clone(),
+    }))
+}
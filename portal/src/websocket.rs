@@ -0,0 +1,221 @@
+use actix::prelude::*;
+use actix_web::{web, HttpRequest, HttpResponse, Error};
+use actix_web_actors::ws;
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub fn websocket_routes(config: &mut web::ServiceConfig) {
+    config.route("/ws/", web::get().to(websocket_page));
+}
+
+// Сообщение, которое сервер рассылает клиентам (в виде json)
+#[derive(Message, Serialize, Clone)]
+#[rtype(result = "()")]
+pub struct MessageToClient {
+    pub msg_type: String, // Тип сообщения, например "page_view"
+    pub id:       i32,    // Идентификатор объекта, к которому относится сообщение
+    pub data:     Value,  // Полезная нагрузка
+}
+
+impl MessageToClient {
+    pub fn new(msg_type: &str, id: i32, data: Value) -> Self {
+        MessageToClient {
+            msg_type: msg_type.to_string(),
+            id:       id,
+            data:     data,
+        }
+    }
+}
+
+// Сообщение конкретному пользователю (если он сейчас на сайте)
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct MessageToUser {
+    pub user_id: i32,
+    pub msg:     MessageToClient,
+}
+
+#[derive(Message)]
+#[rtype(usize)]
+pub struct Connect {
+    pub addr:    Recipient<MessageToClient>,
+    pub user_id: Option<i32>,
+}
+
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct Disconnect {
+    pub id: usize,
+}
+
+// Актор, который хранит все открытые соединения
+pub struct Server {
+    sessions: HashMap<usize, (Recipient<MessageToClient>, Option<i32>)>,
+    next_id:  usize,
+}
+
+impl Server {
+    pub fn new() -> Server {
+        Server {
+            sessions: HashMap::new(),
+            next_id:  1,
+        }
+    }
+}
+
+impl Actor for Server {
+    type Context = Context<Self>;
+}
+
+impl Handler<Connect> for Server {
+    type Result = usize;
+
+    fn handle(&mut self, msg: Connect, _: &mut Context<Self>) -> Self::Result {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.sessions.insert(id, (msg.addr, msg.user_id));
+        id
+    }
+}
+
+impl Handler<Disconnect> for Server {
+    type Result = ();
+
+    fn handle(&mut self, msg: Disconnect, _: &mut Context<Self>) {
+        self.sessions.remove(&msg.id);
+    }
+}
+
+impl Handler<MessageToClient> for Server {
+    type Result = ();
+
+    fn handle(&mut self, msg: MessageToClient, _: &mut Context<Self>) {
+        for (addr, _) in self.sessions.values() {
+            addr.do_send(msg.clone());
+        }
+    }
+}
+
+impl Handler<MessageToUser> for Server {
+    type Result = ();
+
+    fn handle(&mut self, msg: MessageToUser, _: &mut Context<Self>) {
+        for (addr, user_id) in self.sessions.values() {
+            if *user_id == Some(msg.user_id) {
+                addr.do_send(msg.msg.clone());
+            }
+        }
+    }
+}
+
+// Актор одного соединения
+pub struct WsSession {
+    id:      usize,
+    user_id: Option<i32>,
+    hb:      Instant,
+    server:  Addr<Server>,
+}
+
+impl WsSession {
+    fn heartbeat(&self, ctx: &mut ws::WebsocketContext<Self>) {
+        ctx.run_interval(HEARTBEAT_INTERVAL, |act, ctx| {
+            if Instant::now().duration_since(act.hb) > CLIENT_TIMEOUT {
+                act.server.do_send(Disconnect { id: act.id });
+                ctx.stop();
+                return;
+            }
+            ctx.ping(b"");
+        });
+    }
+}
+
+impl Actor for WsSession {
+    type Context = ws::WebsocketContext<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        self.heartbeat(ctx);
+
+        let addr = ctx.address();
+        self.server
+            .send(Connect {
+                addr:    addr.recipient(),
+                user_id: self.user_id,
+            })
+            .into_actor(self)
+            .then(|res, act, ctx| {
+                match res {
+                    Ok(res) => act.id = res,
+                    _ => ctx.stop(),
+                }
+                fut::ready(())
+            })
+            .wait(ctx);
+    }
+
+    fn stopping(&mut self, _: &mut Self::Context) -> Running {
+        self.server.do_send(Disconnect { id: self.id });
+        Running::Stop
+    }
+}
+
+impl Handler<MessageToClient> for WsSession {
+    type Result = ();
+
+    fn handle(&mut self, msg: MessageToClient, ctx: &mut Self::Context) {
+        if let Ok(text) = serde_json::to_string(&msg) {
+            ctx.text(text);
+        }
+    }
+}
+
+impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for WsSession {
+    fn handle(&mut self, msg: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
+        let msg = match msg {
+            Ok(msg) => msg,
+            Err(_) => {
+                ctx.stop();
+                return;
+            }
+        };
+        match msg {
+            ws::Message::Ping(msg) => {
+                self.hb = Instant::now();
+                ctx.pong(&msg);
+            },
+            ws::Message::Pong(_) => {
+                self.hb = Instant::now();
+            },
+            ws::Message::Close(reason) => {
+                ctx.close(reason);
+                ctx.stop();
+            },
+            _ => (),
+        }
+    }
+}
+
+pub async fn websocket_page (
+    req: HttpRequest,
+    stream: web::Payload,
+    session: actix_session::Session,
+    srv: web::Data<Addr<Server>>) -> Result<HttpResponse, Error> {
+    let user_id = session.get::<i32>("user_id").unwrap_or(None);
+    ws::start (
+        WsSession {
+            id:      0,
+            user_id: user_id,
+            hb:      Instant::now(),
+            server:  srv.get_ref().clone(),
+        },
+        &req,
+        stream,
+    )
+}